@@ -0,0 +1,143 @@
+//! Regression benchmarks running the scenes of the `testbench` module.
+//!
+//! Run with `cargo bench -p rapier2d --features testbench` or
+//! `cargo bench -p rapier3d --features testbench`. An optional argument only runs the scenes
+//! with a name containing it, e.g., `cargo bench -p rapier3d --features testbench -- pyramid`.
+
+#![allow(unexpected_cfgs)] // This happens due to the dim2/dim3 cfg.
+
+#[cfg(feature = "dim2")]
+use rapier2d::{
+    geometry::{BroadPhaseProxyShape, NarrowPhase},
+    parry,
+    pipeline::PhysicsWorld,
+    testbench,
+};
+#[cfg(feature = "dim3")]
+use rapier3d::{
+    geometry::{BroadPhaseProxyShape, NarrowPhase},
+    parry,
+    pipeline::PhysicsWorld,
+    testbench,
+};
+use std::time::{Duration, Instant};
+
+const NUM_WARMUP_STEPS: usize = 10;
+const NUM_SAMPLES: usize = 5;
+const NUM_STEPS_PER_SAMPLE: usize = 100;
+#[cfg(feature = "dim2")]
+const PYRAMID_SIZE: usize = 40;
+#[cfg(feature = "dim3")]
+const PYRAMID_SIZE: usize = 12;
+const PILE_SIZE: usize = 500;
+const CHAIN_SIZE: usize = 100;
+const NUM_BALLS: usize = 20_000;
+const NUM_CHUNKS: usize = 10;
+const COLLIDERS_PER_CHUNK: usize = 10_000;
+const NUM_RODS: usize = 5_000;
+
+type SceneBuilder = fn() -> PhysicsWorld;
+
+fn main() {
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with("--"));
+
+    let scenes: [(&str, SceneBuilder); 8] = [
+        ("pyramid", || testbench::pyramid(PYRAMID_SIZE)),
+        ("box_pile", || testbench::box_pile(PILE_SIZE)),
+        ("joint_chain", || testbench::joint_chain(CHAIN_SIZE)),
+        ("heightfield_terrain", || testbench::heightfield_terrain(0)),
+        ("ball_container", || testbench::ball_container(NUM_BALLS)),
+        // The same scene without the ball contact fast path of the default dispatcher.
+        ("ball_container_generic", || {
+            let mut world = testbench::ball_container(NUM_BALLS);
+            world.narrow_phase =
+                NarrowPhase::with_query_dispatcher(parry::query::DefaultQueryDispatcher);
+            world
+        }),
+        ("spinning_rods", || {
+            testbench::spinning_rods(NUM_RODS, BroadPhaseProxyShape::Aabb)
+        }),
+        // The same scene with proxies that don’t change while the rods rotate. The larger
+        // proxies generate more contact pairs, but the same contacts.
+        ("spinning_rods_sphere", || {
+            testbench::spinning_rods(NUM_RODS, BroadPhaseProxyShape::BoundingSphere)
+        }),
+    ];
+
+    for (name, scene) in &scenes {
+        if filter
+            .as_ref()
+            .is_some_and(|filter| !name.contains(filter.as_str()))
+        {
+            continue;
+        }
+
+        let mut step_times = Vec::with_capacity(NUM_SAMPLES);
+        let mut checksum = None;
+        let mut num_bodies = 0;
+        let mut num_contact_pairs = 0;
+
+        for _ in 0..NUM_SAMPLES {
+            let mut world = scene();
+            let _ = testbench::run_steps(&mut world, NUM_WARMUP_STEPS);
+            let report = testbench::run_steps(&mut world, NUM_STEPS_PER_SAMPLE);
+            step_times.push(report.average_step_time());
+            num_bodies = world.bodies.len();
+            num_contact_pairs = report.counters.cd.ncontact_pairs;
+
+            assert_eq!(
+                *checksum.get_or_insert(report.position_checksum),
+                report.position_checksum,
+                "non-deterministic simulation of the {name} scene"
+            );
+        }
+
+        step_times.sort();
+        let mean = step_times.iter().sum::<Duration>() / NUM_SAMPLES as u32;
+        println!(
+            "{name:<22} mean: {mean:>10.3?}/step, min: {:>10.3?}/step, max: {:>10.3?}/step, \
+             bodies: {}, contact pairs: {}, checksum: {:016x}",
+            step_times[0],
+            step_times[NUM_SAMPLES - 1],
+            num_bodies,
+            num_contact_pairs,
+            checksum.unwrap_or_default()
+        );
+    }
+
+    if filter
+        .as_ref()
+        .is_none_or(|filter| "chunk_removal".contains(filter.as_str()))
+    {
+        chunk_removal();
+    }
+}
+
+/// Compares the time needed to remove all the colliders of one chunk out of `NUM_CHUNKS`
+/// static chunks (and step once) to the time needed to build the whole world and step it once.
+fn chunk_removal() {
+    let mut rebuild_times = Vec::with_capacity(NUM_SAMPLES);
+    let mut removal_times = Vec::with_capacity(NUM_SAMPLES);
+
+    for _ in 0..NUM_SAMPLES {
+        let start = Instant::now();
+        let mut world = testbench::static_chunks(NUM_CHUNKS, COLLIDERS_PER_CHUNK);
+        world.step();
+        rebuild_times.push(start.elapsed());
+
+        let (_, chunk) = world.bodies.iter().next().unwrap();
+        let chunk_colliders = chunk.colliders().to_vec();
+        let start = Instant::now();
+        let _ = world.remove_colliders(&chunk_colliders);
+        world.step();
+        removal_times.push(start.elapsed());
+    }
+
+    let rebuild = rebuild_times.iter().sum::<Duration>() / NUM_SAMPLES as u32;
+    let removal = removal_times.iter().sum::<Duration>() / NUM_SAMPLES as u32;
+    println!(
+        "{:<22} removal: {removal:>10.3?}, full rebuild: {rebuild:>10.3?}, colliders: {}",
+        "chunk_removal",
+        NUM_CHUNKS * COLLIDERS_PER_CHUNK,
+    );
+}
@@ -14,6 +14,7 @@ mod capsules3;
 mod ccd3;
 mod compound3;
 mod convex_polyhedron3;
+mod deformable_trimesh3;
 mod heightfield3;
 mod joint_ball3;
 mod joint_fixed3;
@@ -24,8 +25,10 @@ mod many_pyramids3;
 mod many_sleep3;
 mod many_static3;
 mod pyramid3;
+mod resting_crates3;
 mod stacks3;
 mod trimesh3;
+mod voxels3;
 
 enum Command {
     Run(String),
@@ -62,7 +65,26 @@ pub fn main() {
         ("Heightfield", heightfield3::init_world),
         ("Stacks", stacks3::init_world),
         ("Pyramid", pyramid3::init_world),
+        ("Resting crates", resting_crates3::init_world),
+        (
+            "Resting crates (4 contacts)",
+            resting_crates3::init_world_with_4_contacts,
+        ),
+        (
+            "Resting crates (2 contacts)",
+            resting_crates3::init_world_with_2_contacts,
+        ),
         ("Trimesh", trimesh3::init_world),
+        ("Deformable trimesh", deformable_trimesh3::init_world),
+        (
+            "Deformable trimesh (rebuild)",
+            deformable_trimesh3::init_world_with_rebuild,
+        ),
+        ("Voxels", voxels3::init_world),
+        (
+            "Voxels (one collider per cube)",
+            voxels3::init_world_with_cubes,
+        ),
         ("ImpulseJoint ball", joint_ball3::init_world),
         ("ImpulseJoint fixed", joint_fixed3::init_world),
         ("ImpulseJoint revolute", joint_revolute3::init_world),
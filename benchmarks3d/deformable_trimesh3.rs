@@ -0,0 +1,101 @@
+use rapier3d::na::ComplexField;
+use rapier3d::prelude::*;
+use rapier_testbed3d::Testbed;
+
+pub fn init_world(testbed: &mut Testbed) {
+    init_world_with_deformation(testbed, true)
+}
+
+/// Same scene as `init_world`, but the ground trimesh is rebuilt from scratch at each step instead
+/// of being refitted.
+pub fn init_world_with_rebuild(testbed: &mut Testbed) {
+    init_world_with_deformation(testbed, false)
+}
+
+fn wave(vertices: &[Point<f32>], time: f32) -> Vec<Point<f32>> {
+    // NOTE: make sure we use the sin/cos from simba to ensure
+    // cross-platform determinism of the example when the
+    // enhanced_determinism feature is enabled.
+    vertices
+        .iter()
+        .map(|pt| {
+            let height = <f32 as ComplexField>::sin(pt.x * 0.2 + time)
+                + <f32 as ComplexField>::cos(pt.z * 0.2 + time);
+            point![pt.x, height, pt.z]
+        })
+        .collect()
+}
+
+fn init_world_with_deformation(testbed: &mut Testbed, refit: bool) {
+    /*
+     * World
+     */
+    let mut bodies = RigidBodySet::new();
+    let mut colliders = ColliderSet::new();
+    let impulse_joints = ImpulseJointSet::new();
+    let multibody_joints = MultibodyJointSet::new();
+
+    /*
+     * Ground: a 71x71 grid, i.e., about 10k triangles.
+     */
+    let ground_size = vector![100.0, 1.0, 100.0];
+    let nsubdivs = 71;
+    let heightfield = HeightField::new(DMatrix::zeros(nsubdivs + 1, nsubdivs + 1), ground_size);
+    let (vertices, indices) = heightfield.to_trimesh();
+    let vertices = wave(&vertices, 0.0);
+
+    let handle = bodies.insert(RigidBodyBuilder::fixed());
+    let collider = if refit {
+        ColliderBuilder::deformable_trimesh(vertices.clone(), indices.clone())
+    } else {
+        ColliderBuilder::trimesh(vertices.clone(), indices.clone())
+    };
+    let ground_handle = colliders.insert_with_parent(collider, handle, &mut bodies);
+
+    /*
+     * Create the cubes
+     */
+    let num = 8;
+    let rad = 1.0;
+
+    let shift = rad * 2.0 + rad;
+    let centerx = shift * (num / 2) as f32;
+    let centery = shift / 2.0;
+    let centerz = shift * (num / 2) as f32;
+
+    for j in 0usize..10 {
+        for i in 0..num {
+            for k in 0usize..num {
+                let x = i as f32 * shift - centerx;
+                let y = j as f32 * shift + centery + 3.0;
+                let z = k as f32 * shift - centerz;
+
+                let rigid_body = RigidBodyBuilder::dynamic().translation(vector![x, y, z]);
+                let handle = bodies.insert(rigid_body);
+                let collider = ColliderBuilder::cuboid(rad, rad, rad);
+                colliders.insert_with_parent(collider, handle, &mut bodies);
+            }
+        }
+    }
+
+    /*
+     * Deform the ground at each step.
+     */
+    testbed.add_callback(move |_, physics, _, run_state| {
+        let time = run_state.timestep_id as f32 * physics.integration_parameters.dt;
+        let new_vertices = wave(&vertices, time);
+        let ground = &mut physics.colliders[ground_handle];
+
+        if refit {
+            ground.set_trimesh_vertices(&new_vertices);
+        } else {
+            ground.set_shape(SharedShape::trimesh(new_vertices, indices.clone()));
+        }
+    });
+
+    /*
+     * Set up the testbed.
+     */
+    testbed.set_world(bodies, colliders, impulse_joints, multibody_joints);
+    testbed.look_at(point![100.0, 100.0, 100.0], Point::origin());
+}
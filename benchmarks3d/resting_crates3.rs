@@ -0,0 +1,82 @@
+use rapier3d::prelude::*;
+use rapier_testbed3d::Testbed;
+use std::num::NonZeroUsize;
+
+pub fn init_world(testbed: &mut Testbed) {
+    init_world_with_max_contacts(testbed, usize::MAX)
+}
+
+/// Same scene as `init_world`, but the solver only sees 4 points per contact manifold.
+pub fn init_world_with_4_contacts(testbed: &mut Testbed) {
+    init_world_with_max_contacts(testbed, 4)
+}
+
+/// Same scene as `init_world`, but the solver only sees 2 points per contact manifold.
+pub fn init_world_with_2_contacts(testbed: &mut Testbed) {
+    init_world_with_max_contacts(testbed, 2)
+}
+
+fn init_world_with_max_contacts(testbed: &mut Testbed, max_contacts: usize) {
+    /*
+     * World
+     */
+    let mut bodies = RigidBodySet::new();
+    let mut colliders = ColliderSet::new();
+    let impulse_joints = ImpulseJointSet::new();
+    let multibody_joints = MultibodyJointSet::new();
+
+    /*
+     * Ground
+     */
+    let ground_size = 100.0;
+    let ground_height = 0.1;
+
+    let rigid_body = RigidBodyBuilder::fixed().translation(vector![0.0, -ground_height, 0.0]);
+    let handle = bodies.insert(rigid_body);
+    let collider = ColliderBuilder::cuboid(ground_size, ground_height, ground_size);
+    colliders.insert_with_parent(collider, handle, &mut bodies);
+
+    /*
+     * Create 500 crates, in stacks where every other crate is rotated by 45 degrees so each
+     * contact manifold has up to 8 points.
+     */
+    let num = 10;
+    let height = 5;
+    let rad = 0.5;
+    let shift = rad * 4.0;
+    let center = shift * (num as f32 - 1.0) / 2.0;
+
+    for i in 0..num {
+        for k in 0..num {
+            for j in 0..height {
+                let x = i as f32 * shift - center;
+                let y = rad + j as f32 * rad * 2.0;
+                let z = k as f32 * shift - center;
+                let angle = if j % 2 == 0 {
+                    0.0
+                } else {
+                    std::f32::consts::FRAC_PI_4
+                };
+
+                // Build the rigid body.
+                let rigid_body = RigidBodyBuilder::dynamic()
+                    .translation(vector![x, y, z])
+                    .rotation(vector![0.0, angle, 0.0]);
+                let handle = bodies.insert(rigid_body);
+                let collider = ColliderBuilder::cuboid(rad, rad, rad);
+                colliders.insert_with_parent(collider, handle, &mut bodies);
+            }
+        }
+    }
+
+    /*
+     * Set up the testbed.
+     */
+    testbed.set_world(bodies, colliders, impulse_joints, multibody_joints);
+    testbed
+        .harness_mut()
+        .physics
+        .integration_parameters
+        .max_contact_points_per_manifold = NonZeroUsize::new(max_contacts).unwrap();
+    testbed.look_at(point![30.0, 20.0, 30.0], Point::origin());
+}
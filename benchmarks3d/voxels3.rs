@@ -0,0 +1,90 @@
+use rapier3d::prelude::*;
+use rapier_testbed3d::Testbed;
+
+pub fn init_world(testbed: &mut Testbed) {
+    init_world_with_chunk(testbed, false)
+}
+
+/// Same scene as `init_world`, but with one collider per cube instead of a single voxels collider.
+pub fn init_world_with_cubes(testbed: &mut Testbed) {
+    init_world_with_chunk(testbed, true)
+}
+
+fn init_world_with_chunk(testbed: &mut Testbed, one_collider_per_cube: bool) {
+    /*
+     * World
+     */
+    let mut bodies = RigidBodySet::new();
+    let mut colliders = ColliderSet::new();
+    let impulse_joints = ImpulseJointSet::new();
+    let multibody_joints = MultibodyJointSet::new();
+
+    /*
+     * Ground: a 32x32x32 chunk of unit cubes, with its top face at y = 0.
+     */
+    let chunk_size = 32;
+    let cell_size = 1.0;
+    let mut cells = vec![];
+
+    for i in 0..chunk_size {
+        for j in 0..chunk_size {
+            for k in 0..chunk_size {
+                cells.push(point![
+                    i - chunk_size / 2,
+                    j - chunk_size,
+                    k - chunk_size / 2
+                ]);
+            }
+        }
+    }
+
+    let handle = bodies.insert(RigidBodyBuilder::fixed());
+
+    if one_collider_per_cube {
+        let half_cell = cell_size / 2.0;
+
+        for cell in &cells {
+            let center = cell.cast::<f32>() * cell_size + Vector::repeat(half_cell);
+            let collider =
+                ColliderBuilder::cuboid(half_cell, half_cell, half_cell).translation(center.coords);
+            colliders.insert_with_parent(collider, handle, &mut bodies);
+        }
+    } else {
+        let collider = ColliderBuilder::voxels(cell_size, &cells);
+        colliders.insert_with_parent(collider, handle, &mut bodies);
+    }
+
+    /*
+     * Create the dynamic bodies falling on the chunk.
+     */
+    let num = 10;
+    let rad = 0.5;
+    let shift = rad * 2.5;
+    let centerx = shift * num as f32 / 2.0;
+    let centerz = shift * num as f32 / 2.0;
+
+    for j in 0usize..10 {
+        for i in 0..num {
+            for k in 0..num {
+                let x = i as f32 * shift - centerx;
+                let y = j as f32 * shift + 3.0;
+                let z = k as f32 * shift - centerz;
+
+                let rigid_body = RigidBodyBuilder::dynamic().translation(vector![x, y, z]);
+                let handle = bodies.insert(rigid_body);
+                let collider = if (i + j + k) % 2 == 0 {
+                    ColliderBuilder::cuboid(rad, rad, rad)
+                } else {
+                    ColliderBuilder::ball(rad)
+                };
+                colliders.insert_with_parent(collider, handle, &mut bodies);
+            }
+        }
+    }
+
+    /*
+     * Set up the testbed.
+     */
+    testbed.set_world(bodies, colliders, impulse_joints, multibody_joints);
+    testbed.look_at(point![40.0, 30.0, 40.0], Point::origin());
+}
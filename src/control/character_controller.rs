@@ -1,5 +1,7 @@
 use crate::dynamics::RigidBodySet;
-use crate::geometry::{ColliderHandle, ColliderSet, ContactManifold, Shape, ShapeCastHit};
+use crate::geometry::{
+    ColliderHandle, ColliderSet, ContactManifold, Shape, ShapeCastHit, SupportMapFallbackDispatcher,
+};
 use crate::math::{Isometry, Point, Real, UnitVector, Vector};
 use crate::pipeline::{QueryFilter, QueryFilterFlags, QueryPipeline};
 use crate::utils;
@@ -7,7 +9,7 @@ use na::{RealField, Vector2};
 use parry::bounding_volume::BoundingVolume;
 use parry::math::Translation;
 use parry::query::details::ShapeCastOptions;
-use parry::query::{DefaultQueryDispatcher, PersistentQueryDispatcher};
+use parry::query::PersistentQueryDispatcher;
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -416,7 +418,7 @@ impl KinematicCharacterController {
         let prediction = self.predict_ground(dims.y);
 
         // TODO: allow custom dispatchers.
-        let dispatcher = DefaultQueryDispatcher;
+        let dispatcher = SupportMapFallbackDispatcher;
 
         let mut manifolds: Vec<ContactManifold> = vec![];
         let character_aabb = character_shape
@@ -804,7 +806,7 @@ impl KinematicCharacterController {
         let prediction = self.predict_ground(up_extent);
 
         // TODO: allow custom dispatchers.
-        let dispatcher = DefaultQueryDispatcher;
+        let dispatcher = SupportMapFallbackDispatcher;
 
         let mut manifolds: Vec<ContactManifold> = vec![];
         let character_aabb = character_shape
@@ -873,3 +875,227 @@ fn subtract_hit(translation: Vector<Real>, hit: &ShapeCastHit) -> Vector<Real> {
     let surface_correction = surface_correction * (1.0 + 1.0e-5);
     translation + *hit.normal1 * surface_correction
 }
+
+#[cfg(test)]
+mod test {
+    use crate::control::{CharacterAutostep, CharacterLength, KinematicCharacterController};
+    use crate::dynamics::RigidBodySet;
+    use crate::geometry::{ColliderBuilder, ColliderSet, SharedShape};
+    use crate::math::{Isometry, Real, Vector};
+    use crate::pipeline::{QueryFilter, QueryPipeline};
+
+    const CHARACTER_HALF_HEIGHT: Real = 0.3;
+    const CHARACTER_RADIUS: Real = 0.15;
+
+    fn cuboid(half_extents: Vector<Real>, position: Isometry<Real>) -> ColliderBuilder {
+        ColliderBuilder::new(SharedShape::cuboid(
+            half_extents.x,
+            half_extents.y,
+            #[cfg(feature = "dim3")]
+            half_extents.z,
+        ))
+        .position(position)
+    }
+
+    fn horizontal(x: Real, z: Real) -> Vector<Real> {
+        #[cfg(feature = "dim2")]
+        return {
+            let _ = z;
+            Vector::new(x, 0.0)
+        };
+        #[cfg(feature = "dim3")]
+        return Vector::new(x, 0.0, z);
+    }
+
+    fn rotation_z(angle: Real) -> Isometry<Real> {
+        #[cfg(feature = "dim2")]
+        return Isometry::rotation(angle);
+        #[cfg(feature = "dim3")]
+        return Isometry::rotation(Vector::z() * angle);
+    }
+
+    /// A large floor whose top surface lies on the plane `y = 0`.
+    fn floor() -> ColliderBuilder {
+        cuboid(
+            Vector::repeat(10.0),
+            Isometry::translation(
+                0.0,
+                -10.0,
+                #[cfg(feature = "dim3")]
+                0.0,
+            ),
+        )
+    }
+
+    /// A slope starting at `x = 1` on the floor, rising along `+x` with the given angle.
+    fn slope(angle: Real) -> ColliderBuilder {
+        let mut pos = rotation_z(angle);
+        let normal = pos * Vector::y();
+        pos.translation.vector = horizontal(1.0, 0.0) - normal * 0.5;
+        cuboid(horizontal(10.0, 10.0) + Vector::y() * 0.5, pos)
+    }
+
+    /// Moves a capsule-shaped character, standing on the floor at the origin, by
+    /// `step_translation` during `num_steps` steps. Returns its final position and
+    /// whether it was grounded after the last step.
+    fn simulate_character(
+        controller: &KinematicCharacterController,
+        colliders: &ColliderSet,
+        step_translation: Vector<Real>,
+        num_steps: usize,
+    ) -> (Vector<Real>, bool) {
+        let bodies = RigidBodySet::new();
+        let mut queries = QueryPipeline::new();
+        queries.update(&bodies, colliders);
+
+        let shape = SharedShape::capsule_y(CHARACTER_HALF_HEIGHT, CHARACTER_RADIUS);
+        let mut pos = Isometry::translation(
+            0.0,
+            CHARACTER_HALF_HEIGHT + CHARACTER_RADIUS + 0.02,
+            #[cfg(feature = "dim3")]
+            0.0,
+        );
+        let mut grounded = false;
+
+        for _ in 0..num_steps {
+            let movement = controller.move_shape(
+                1.0 / 60.0,
+                &bodies,
+                colliders,
+                &queries,
+                &*shape,
+                &pos,
+                step_translation,
+                QueryFilter::default(),
+                |_| {},
+            );
+            pos.translation.vector += movement.translation;
+            grounded = movement.grounded;
+        }
+
+        (pos.translation.vector, grounded)
+    }
+
+    #[test]
+    fn character_autosteps_on_stairs() {
+        let mut colliders = ColliderSet::new();
+        let step_height = 0.2;
+        colliders.insert(floor());
+        colliders.insert(cuboid(
+            horizontal(5.0, 10.0) + Vector::y() * (step_height / 2.0),
+            Isometry::translation(
+                6.0,
+                step_height / 2.0,
+                #[cfg(feature = "dim3")]
+                0.0,
+            ),
+        ));
+
+        let movement = horizontal(0.05, 0.0) - Vector::y() * 0.02;
+        let mut controller = KinematicCharacterController {
+            autostep: None,
+            ..Default::default()
+        };
+
+        // Without autostep, the stair blocks the character.
+        let (pos, _) = simulate_character(&controller, &colliders, movement, 60);
+        assert!(pos.x < 1.0, "the stair should block the character: {pos}");
+        assert!(
+            pos.y < step_height + CHARACTER_HALF_HEIGHT + CHARACTER_RADIUS - 0.1,
+            "{pos}"
+        );
+
+        // With autostep, the character climbs the stair and keeps moving forward.
+        controller.autostep = Some(CharacterAutostep {
+            max_height: CharacterLength::Absolute(0.25),
+            min_width: CharacterLength::Absolute(0.1),
+            include_dynamic_bodies: true,
+        });
+        let (pos, grounded) = simulate_character(&controller, &colliders, movement, 60);
+        assert!(pos.x > 2.0, "the character should climb the stair: {pos}");
+        assert!(
+            pos.y > step_height + CHARACTER_HALF_HEIGHT + CHARACTER_RADIUS - 0.05,
+            "the character should stand on the stair: {pos}"
+        );
+        assert!(grounded);
+
+        // Stairs taller than the autostep height still block the character.
+        controller.autostep = Some(CharacterAutostep {
+            max_height: CharacterLength::Absolute(0.1),
+            min_width: CharacterLength::Absolute(0.1),
+            include_dynamic_bodies: true,
+        });
+        let (pos, _) = simulate_character(&controller, &colliders, movement, 60);
+        assert!(pos.x < 1.0, "the stair is too high to be climbed: {pos}");
+    }
+
+    #[test]
+    fn character_cannot_climb_steep_slopes() {
+        let controller = KinematicCharacterController::default();
+        let movement = horizontal(0.05, 0.0) - Vector::y() * 0.1;
+
+        // A gentle slope is climbed.
+        let mut colliders = ColliderSet::new();
+        colliders.insert(floor());
+        colliders.insert(slope(20.0_f64.to_radians() as Real));
+        let (pos, grounded) = simulate_character(&controller, &colliders, movement, 180);
+        assert!(pos.x > 2.0, "the gentle slope should be climbed: {pos}");
+        assert!(
+            pos.y > CHARACTER_HALF_HEIGHT + CHARACTER_RADIUS + 0.3,
+            "{pos}"
+        );
+        assert!(grounded);
+
+        // A slope steeper than `max_slope_climb_angle` is not climbed.
+        let mut colliders = ColliderSet::new();
+        colliders.insert(floor());
+        colliders.insert(slope(60.0_f64.to_radians() as Real));
+        let (pos, _) = simulate_character(&controller, &colliders, movement, 180);
+        assert!(pos.x < 1.2, "the steep slope should not be climbed: {pos}");
+        assert!(
+            pos.y < CHARACTER_HALF_HEIGHT + CHARACTER_RADIUS + 0.2,
+            "{pos}"
+        );
+    }
+
+    #[test]
+    fn character_slides_along_corner() {
+        let mut colliders = ColliderSet::new();
+        colliders.insert(floor());
+        // A wall with its face on the plane `x = 1`.
+        colliders.insert(cuboid(
+            Vector::repeat(10.0),
+            Isometry::translation(
+                11.0,
+                0.0,
+                #[cfg(feature = "dim3")]
+                0.0,
+            ),
+        ));
+
+        // Push the character diagonally into the corner between the floor and the wall.
+        let controller = KinematicCharacterController::default();
+        let movement = horizontal(0.05, 0.05) - Vector::y() * 0.05;
+        let (pos, grounded) = simulate_character(&controller, &colliders, movement, 60);
+
+        // The character ends up stuck in the corner without penetrating the floor or the wall.
+        assert!(grounded);
+        assert!(pos.x <= 1.0 - CHARACTER_RADIUS + 1.0e-3, "{pos}");
+        assert!(pos.x > 1.0 - CHARACTER_RADIUS - 0.1, "{pos}");
+        assert!(
+            pos.y >= CHARACTER_HALF_HEIGHT + CHARACTER_RADIUS - 1.0e-3,
+            "{pos}"
+        );
+        assert!(
+            pos.y < CHARACTER_HALF_HEIGHT + CHARACTER_RADIUS + 0.1,
+            "{pos}"
+        );
+
+        // In 3D, the motion parallel to the corner is preserved: the character slides along it.
+        #[cfg(feature = "dim3")]
+        assert!(
+            pos.z > 2.5,
+            "the character should slide along the corner: {pos}"
+        );
+    }
+}
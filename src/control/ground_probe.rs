@@ -0,0 +1,290 @@
+use crate::dynamics::RigidBodySet;
+use crate::geometry::{ColliderHandle, ColliderSet, Ray, Shape};
+use crate::math::{Isometry, Point, Real, UnitVector, Vector, DEFAULT_EPSILON};
+use crate::pipeline::{QueryFilter, QueryPipeline};
+use parry::query::details::ShapeCastOptions;
+use parry::query::ShapeCastStatus;
+
+/// The ground detected by [`ground_cast`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GroundHit {
+    /// The handle of the collider that was hit.
+    pub collider: ColliderHandle,
+    /// The contact point on the hit collider, in world-space.
+    pub point: Point<Real>,
+    /// The outward normal of the hit collider at `point`, in world-space.
+    ///
+    /// If the probe started inside of the collider, this is the penetration normal if it could
+    /// be computed, and the opposite of the cast direction otherwise.
+    pub normal: UnitVector<Real>,
+    /// The distance traveled by the probe along the cast direction before hitting the ground.
+    pub distance: Real,
+    /// The velocity of the hit collider’s rigid-body at `point`, or zero if the collider has
+    /// no parent rigid-body.
+    ///
+    /// This is the velocity a character standing at `point` must inherit to follow a moving
+    /// platform.
+    pub surface_velocity: Vector<Real>,
+    /// Is the probe already penetrating the ground at its starting position?
+    ///
+    /// If `true`, `distance` is zero and `point` and `normal` are only approximate.
+    pub started_inside: bool,
+}
+
+/// Probes for the ground along the given direction, with a ray or a shape.
+///
+/// This is the building block of character and vehicle controllers for detecting the floor below
+/// a character or a wheel.
+///
+/// # Parameters
+/// * `query_pipeline` - The query pipeline, updated with `bodies` and `colliders`.
+/// * `from` - The starting position of the probe. Only its translation is used for ray probes.
+/// * `direction` - The direction the probe is cast along (usually down).
+/// * `max_dist` - The maximum distance the probe travels along `direction`.
+/// * `probe_shape` - The shape to cast (for example a ball or a capsule for a rounded tip), or
+///   `None` to cast a ray.
+/// * `filter` - The set of rules used to determine which collider is taken into account by this
+///   query. Sensors are always excluded since they can’t be stood on.
+#[allow(clippy::too_many_arguments)]
+pub fn ground_cast(
+    query_pipeline: &QueryPipeline,
+    bodies: &RigidBodySet,
+    colliders: &ColliderSet,
+    from: &Isometry<Real>,
+    direction: &UnitVector<Real>,
+    max_dist: Real,
+    probe_shape: Option<&dyn Shape>,
+    filter: QueryFilter,
+) -> Option<GroundHit> {
+    let filter = filter.exclude_sensors();
+
+    let (collider, point, normal, distance, started_inside) = if let Some(shape) = probe_shape {
+        let options = ShapeCastOptions {
+            max_time_of_impact: max_dist,
+            target_distance: 0.0,
+            stop_at_penetration: true,
+            compute_impact_geometry_on_penetration: true,
+        };
+        let (handle, hit) = query_pipeline.cast_shape(
+            bodies,
+            colliders,
+            from,
+            &direction.into_inner(),
+            shape,
+            options,
+            filter,
+        )?;
+        (
+            handle,
+            hit.witness1,
+            hit.normal1.into_inner(),
+            hit.time_of_impact,
+            hit.status == ShapeCastStatus::PenetratingOrWithinTargetDist,
+        )
+    } else {
+        let ray = Ray::new(Point::from(from.translation.vector), **direction);
+        let (handle, hit) = query_pipeline
+            .cast_ray_and_get_normal(bodies, colliders, &ray, max_dist, true, filter)?;
+        (
+            handle,
+            ray.point_at(hit.time_of_impact),
+            hit.normal,
+            hit.time_of_impact,
+            hit.time_of_impact == 0.0,
+        )
+    };
+
+    let normal = UnitVector::try_new(normal, DEFAULT_EPSILON).unwrap_or(-*direction);
+    let surface_velocity = colliders[collider]
+        .parent()
+        .and_then(|parent| bodies.get(parent))
+        .map(|body| body.velocity_at_point(&point))
+        .unwrap_or_else(Vector::zeros);
+
+    Some(GroundHit {
+        collider,
+        point,
+        normal,
+        distance,
+        surface_velocity,
+        started_inside,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::ground_cast;
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderSet, SharedShape};
+    use crate::math::{Isometry, Real, Vector};
+    use crate::pipeline::{QueryFilter, QueryPipeline};
+
+    fn point_above(x: Real, y: Real) -> Isometry<Real> {
+        Isometry::translation(
+            x,
+            y,
+            #[cfg(feature = "dim3")]
+            0.0,
+        )
+    }
+
+    fn cuboid(half_extents: Vector<Real>) -> ColliderBuilder {
+        ColliderBuilder::new(SharedShape::cuboid(
+            half_extents.x,
+            half_extents.y,
+            #[cfg(feature = "dim3")]
+            half_extents.z,
+        ))
+    }
+
+    #[test]
+    fn ground_cast_on_slope() {
+        let angle = 30.0_f64.to_radians() as Real;
+        #[cfg(feature = "dim2")]
+        let mut pos = Isometry::rotation(angle);
+        #[cfg(feature = "dim3")]
+        let mut pos = Isometry::rotation(Vector::z() * angle);
+        let slope_normal = pos * Vector::y();
+        // The slope surface passes through the origin.
+        pos.translation.vector = -slope_normal * 0.5;
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let handle =
+            colliders.insert(cuboid(Vector::repeat(10.0) - Vector::y() * 9.5).position(pos));
+        let mut queries = QueryPipeline::new();
+        queries.update(&bodies, &colliders);
+
+        let down = -Vector::y_axis();
+        let ray_hit = ground_cast(
+            &queries,
+            &bodies,
+            &colliders,
+            &point_above(0.0, 2.0),
+            &down,
+            10.0,
+            None,
+            QueryFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(ray_hit.collider, handle);
+        assert!((ray_hit.distance - 2.0).abs() < 1.0e-3, "{ray_hit:?}");
+        assert!(ray_hit.point.coords.norm() < 1.0e-3, "{ray_hit:?}");
+        assert!(
+            (*ray_hit.normal - slope_normal).norm() < 1.0e-3,
+            "{ray_hit:?}"
+        );
+        assert_eq!(ray_hit.surface_velocity, Vector::zeros());
+        assert!(!ray_hit.started_inside);
+
+        // A rounded tip touches the slope before the ray does.
+        let radius = 0.2;
+        let ball = SharedShape::ball(radius);
+        let ball_hit = ground_cast(
+            &queries,
+            &bodies,
+            &colliders,
+            &point_above(0.0, 2.0),
+            &down,
+            10.0,
+            Some(&*ball),
+            QueryFilter::default(),
+        )
+        .unwrap();
+        let expected_dist = 2.0 - radius / angle.cos();
+        assert!(
+            (ball_hit.distance - expected_dist).abs() < 1.0e-3,
+            "{ball_hit:?}"
+        );
+        assert!(
+            (*ball_hit.normal - slope_normal).norm() < 1.0e-3,
+            "{ball_hit:?}"
+        );
+        assert!(!ball_hit.started_inside);
+
+        // Nothing is hit beyond the maximum distance.
+        assert!(ground_cast(
+            &queries,
+            &bodies,
+            &colliders,
+            &point_above(0.0, 2.0),
+            &down,
+            1.0,
+            None,
+            QueryFilter::default(),
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn ground_cast_on_moving_platform_ignores_sensors() {
+        let platform_vel = Vector::x() * 2.0;
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let platform = bodies.insert(
+            RigidBodyBuilder::kinematic_velocity_based()
+                .translation(-Vector::y() * 0.5)
+                .linvel(platform_vel),
+        );
+        let platform_collider = colliders.insert_with_parent(
+            cuboid(Vector::repeat(2.0) - Vector::y() * 1.5),
+            platform,
+            &mut bodies,
+        );
+        // A sensor between the probe and the platform must not be reported as ground.
+        colliders.insert(
+            cuboid(Vector::repeat(0.5))
+                .translation(Vector::y())
+                .sensor(true),
+        );
+        let mut queries = QueryPipeline::new();
+        queries.update(&bodies, &colliders);
+
+        for probe in [None, Some(SharedShape::capsule_y(0.2, 0.1))] {
+            let hit = ground_cast(
+                &queries,
+                &bodies,
+                &colliders,
+                &point_above(0.0, 2.0),
+                &-Vector::y_axis(),
+                10.0,
+                probe.as_deref(),
+                QueryFilter::default(),
+            )
+            .unwrap();
+            assert_eq!(hit.collider, platform_collider);
+            assert!(
+                (hit.surface_velocity - platform_vel).norm() < 1.0e-5,
+                "{hit:?}"
+            );
+            assert!(!hit.started_inside);
+        }
+    }
+
+    #[test]
+    fn ground_cast_inside_wall_is_flagged() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let wall = colliders.insert(cuboid(Vector::repeat(1.0)));
+        let mut queries = QueryPipeline::new();
+        queries.update(&bodies, &colliders);
+
+        let ball = SharedShape::ball(0.2);
+        for probe in [None, Some(&*ball)] {
+            let hit = ground_cast(
+                &queries,
+                &bodies,
+                &colliders,
+                &point_above(0.0, 0.5),
+                &-Vector::y_axis(),
+                10.0,
+                probe,
+                QueryFilter::default(),
+            )
+            .unwrap();
+            assert_eq!(hit.collider, wall);
+            assert!(hit.started_inside, "{hit:?}");
+            assert_eq!(hit.distance, 0.0);
+        }
+    }
+}
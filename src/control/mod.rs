@@ -4,11 +4,17 @@ pub use self::character_controller::{
     CharacterAutostep, CharacterCollision, CharacterLength, EffectiveCharacterMovement,
     KinematicCharacterController,
 };
+pub use self::ground_probe::{ground_cast, GroundHit};
+#[cfg(feature = "projectiles")]
+pub use self::projectile::{Projectile, ProjectileHandle, ProjectileHit, ProjectileSet};
 
 #[cfg(feature = "dim3")]
 pub use self::ray_cast_vehicle_controller::{DynamicRayCastVehicleController, Wheel, WheelTuning};
 
 mod character_controller;
+mod ground_probe;
+#[cfg(feature = "projectiles")]
+mod projectile;
 
 #[cfg(feature = "dim3")]
 mod ray_cast_vehicle_controller;
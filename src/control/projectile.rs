@@ -0,0 +1,336 @@
+use crate::data::Arena;
+use crate::dynamics::RigidBodySet;
+use crate::geometry::{ColliderHandle, ColliderSet, Ray};
+use crate::math::{Point, Real, Vector, DEFAULT_EPSILON};
+use crate::pipeline::{QueryFilter, QueryPipeline};
+
+/// The unique identifier of a projectile added to a [`ProjectileSet`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[repr(transparent)]
+pub struct ProjectileHandle(pub crate::data::arena::Index);
+
+impl ProjectileHandle {
+    /// Converts this handle into its (index, generation) components.
+    pub fn into_raw_parts(self) -> (u32, u32) {
+        self.0.into_raw_parts()
+    }
+
+    /// Reconstructs an handle from its (index, generation) components.
+    pub fn from_raw_parts(id: u32, generation: u32) -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(id, generation))
+    }
+
+    /// An always-invalid projectile handle.
+    pub fn invalid() -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(
+            crate::INVALID_U32,
+            crate::INVALID_U32,
+        ))
+    }
+}
+
+impl std::fmt::Display for ProjectileHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ProjectileHandle({})", self.0)
+    }
+}
+
+/// A point-like projectile moved by segment-casts instead of being simulated as a rigid-body.
+///
+/// This is much cheaper than a rigid-body with CCD enabled for very small and very fast objects
+/// like bullets.
+#[derive(Copy, Clone)]
+pub struct Projectile {
+    /// The current position of the projectile.
+    pub position: Point<Real>,
+    /// The current velocity of the projectile.
+    pub velocity: Vector<Real>,
+    /// The multiplier applied to the gravity affecting this projectile (default: `1.0`).
+    pub gravity_scale: Real,
+    /// The magnitude of the impulse applied, along the projectile’s velocity, to the dynamic
+    /// rigid-body it hits (default: `0.0`).
+    pub impulse: Real,
+    /// User-defined data associated to this projectile.
+    pub user_data: u128,
+}
+
+impl Projectile {
+    /// Creates a new projectile with the given initial position and velocity.
+    pub fn new(position: Point<Real>, velocity: Vector<Real>) -> Self {
+        Self {
+            position,
+            velocity,
+            gravity_scale: 1.0,
+            impulse: 0.0,
+            user_data: 0,
+        }
+    }
+
+    /// Sets the multiplier applied to the gravity affecting this projectile.
+    #[must_use]
+    pub fn gravity_scale(mut self, gravity_scale: Real) -> Self {
+        self.gravity_scale = gravity_scale;
+        self
+    }
+
+    /// Sets the magnitude of the impulse applied to the dynamic rigid-body this projectile hits.
+    #[must_use]
+    pub fn impulse(mut self, impulse: Real) -> Self {
+        self.impulse = impulse;
+        self
+    }
+
+    /// Sets the user-defined data associated to this projectile.
+    #[must_use]
+    pub fn user_data(mut self, user_data: u128) -> Self {
+        self.user_data = user_data;
+        self
+    }
+}
+
+/// A hit between a projectile and a collider, reported by [`ProjectileSet::update`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ProjectileHit {
+    /// The projectile that hit the collider. It is removed from its set after this hit.
+    pub projectile: ProjectileHandle,
+    /// The user-data of the projectile.
+    pub user_data: u128,
+    /// The collider that was hit.
+    pub collider: ColliderHandle,
+    /// The hit point, in world-space.
+    pub point: Point<Real>,
+    /// The normal of the collider at the hit point, in world-space.
+    ///
+    /// This is zero if the projectile started its motion inside of the collider.
+    pub normal: Vector<Real>,
+    /// The velocity of the projectile at the time of the hit.
+    pub velocity: Vector<Real>,
+    /// The impulse applied to the rigid-body the collider is attached to, or zero if it isn’t
+    /// attached to a dynamic rigid-body.
+    pub impulse: Vector<Real>,
+}
+
+/// A set of projectiles moved by segment-casts against the colliders of a scene.
+#[derive(Clone, Default)]
+pub struct ProjectileSet {
+    projectiles: Arena<Projectile>,
+}
+
+impl ProjectileSet {
+    /// Creates a new empty set of projectiles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of projectiles in this set.
+    pub fn len(&self) -> usize {
+        self.projectiles.len()
+    }
+
+    /// `true` if there are no projectiles in this set.
+    pub fn is_empty(&self) -> bool {
+        self.projectiles.is_empty()
+    }
+
+    /// Adds a projectile to this set.
+    pub fn insert(&mut self, projectile: Projectile) -> ProjectileHandle {
+        ProjectileHandle(self.projectiles.insert(projectile))
+    }
+
+    /// Removes a projectile from this set.
+    pub fn remove(&mut self, handle: ProjectileHandle) -> Option<Projectile> {
+        self.projectiles.remove(handle.0)
+    }
+
+    /// Removes every projectile from this set.
+    pub fn clear(&mut self) {
+        self.projectiles.clear();
+    }
+
+    /// Gets the projectile with the given handle.
+    pub fn get(&self, handle: ProjectileHandle) -> Option<&Projectile> {
+        self.projectiles.get(handle.0)
+    }
+
+    /// Gets a mutable reference to the projectile with the given handle.
+    pub fn get_mut(&mut self, handle: ProjectileHandle) -> Option<&mut Projectile> {
+        self.projectiles.get_mut(handle.0)
+    }
+
+    /// Iterates through all the projectiles of this set.
+    pub fn iter(&self) -> impl Iterator<Item = (ProjectileHandle, &Projectile)> {
+        self.projectiles
+            .iter()
+            .map(|(h, p)| (ProjectileHandle(h), p))
+    }
+
+    /// Advances every projectile by the timestep `dt`.
+    ///
+    /// The velocity of each projectile is first updated with the `gravity` multiplied by its
+    /// gravity scale. Then, the segment it travels during `dt` is cast against the colliders of
+    /// `query_pipeline` that pass the `filter`. On the first hit, the projectile’s impulse is
+    /// applied to the dynamic rigid-body attached to the hit collider (waking it up), `on_hit`
+    /// is called, and the projectile is removed from this set.
+    ///
+    /// The `query_pipeline` must have been updated with `bodies` and `colliders`. Projectiles
+    /// that must hit different colliders can be stored in different sets.
+    pub fn update(
+        &mut self,
+        dt: Real,
+        gravity: &Vector<Real>,
+        query_pipeline: &QueryPipeline,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+        filter: QueryFilter,
+        mut on_hit: impl FnMut(&ProjectileHit),
+    ) {
+        self.projectiles.retain(|handle, projectile| {
+            projectile.velocity += gravity * (projectile.gravity_scale * dt);
+
+            let ray = Ray::new(projectile.position, projectile.velocity * dt);
+            let Some((collider, hit)) =
+                query_pipeline.cast_ray_and_get_normal(bodies, colliders, &ray, 1.0, true, filter)
+            else {
+                projectile.position += ray.dir;
+                return true;
+            };
+
+            let point = ray.point_at(hit.time_of_impact);
+            let mut impulse = Vector::zeros();
+
+            if let Some(body) = colliders[collider]
+                .parent()
+                .and_then(|parent| bodies.get_mut(parent))
+            {
+                if body.is_dynamic() {
+                    impulse = projectile
+                        .velocity
+                        .try_normalize(DEFAULT_EPSILON)
+                        .unwrap_or_else(Vector::zeros)
+                        * projectile.impulse;
+                    body.apply_impulse_at_point(impulse, point, true);
+                }
+            }
+
+            on_hit(&ProjectileHit {
+                projectile: ProjectileHandle(handle),
+                user_data: projectile.user_data,
+                collider,
+                point,
+                normal: hit.normal,
+                velocity: projectile.velocity,
+                impulse,
+            });
+
+            false
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Projectile, ProjectileSet};
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderSet, Group, InteractionGroups};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::{QueryFilter, QueryPipeline};
+    use std::collections::HashMap;
+
+    #[test]
+    fn projectiles_apply_their_impulse_to_a_wall_of_boxes() {
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+
+        // A wall of unit boxes with its front face on the plane `x = 9.5`.
+        let depth = if cfg!(feature = "dim3") { 5 } else { 1 };
+        let mut boxes = vec![];
+        for i in 0..5 {
+            for j in 0..depth {
+                #[cfg(feature = "dim2")]
+                let (translation, collider) = {
+                    let _ = j;
+                    (
+                        Vector::new(10.0, i as Real),
+                        ColliderBuilder::cuboid(0.5, 0.5),
+                    )
+                };
+                #[cfg(feature = "dim3")]
+                let (translation, collider) = (
+                    Vector::new(10.0, i as Real, j as Real),
+                    ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+                );
+                let body = bodies.insert(RigidBodyBuilder::dynamic().translation(translation));
+                colliders.insert_with_parent(collider, body, &mut bodies);
+                boxes.push(body);
+            }
+        }
+
+        // A shield in front of the wall, excluded by the projectiles’ filter.
+        #[cfg(feature = "dim2")]
+        let shield = ColliderBuilder::cuboid(0.1, 10.0);
+        #[cfg(feature = "dim3")]
+        let shield = ColliderBuilder::cuboid(0.1, 10.0, 10.0);
+        colliders.insert(
+            shield
+                .translation(Vector::x() * 5.0)
+                .collision_groups(InteractionGroups::new(Group::GROUP_2, Group::ALL)),
+        );
+
+        let mut queries = QueryPipeline::new();
+        queries.update(&bodies, &colliders);
+
+        let impulse = 0.5;
+        let filter =
+            QueryFilter::default().groups(InteractionGroups::new(Group::GROUP_1, Group::GROUP_1));
+        let mut projectiles = ProjectileSet::new();
+        for k in 0..1000 {
+            #[cfg(feature = "dim2")]
+            let start = Point::new(0.0, k as Real * 0.004 + 0.001);
+            #[cfg(feature = "dim3")]
+            let start = Point::new(0.0, k as Real * 0.004 + 0.001, (k % 5) as Real);
+            projectiles.insert(
+                Projectile::new(start, Vector::x() * 300.0)
+                    .gravity_scale(0.0)
+                    .impulse(impulse)
+                    .user_data(k),
+            );
+        }
+
+        let mut total_impulses = HashMap::new();
+        let mut num_hits = 0;
+        for _ in 0..10 {
+            projectiles.update(
+                1.0 / 60.0,
+                &(-Vector::y() * 9.81),
+                &queries,
+                &mut bodies,
+                &colliders,
+                filter,
+                |hit| {
+                    num_hits += 1;
+                    assert_eq!(hit.impulse, Vector::x() * impulse);
+                    assert!((hit.point.x - 9.5).abs() < 1.0e-3, "{:?}", hit.point);
+                    assert!(hit.normal.x < -0.99, "{:?}", hit.normal);
+                    let body = colliders[hit.collider].parent().unwrap();
+                    *total_impulses.entry(body).or_insert(Vector::zeros()) += hit.impulse;
+                },
+            );
+        }
+
+        assert_eq!(num_hits, 1000);
+        assert!(projectiles.is_empty());
+
+        for body in boxes {
+            let expected = total_impulses
+                .get(&body)
+                .copied()
+                .unwrap_or_else(Vector::zeros);
+            let momentum = bodies[body].linvel() * bodies[body].mass();
+            assert!(
+                (momentum - expected).norm() < 1.0e-3,
+                "{momentum} != {expected}"
+            );
+        }
+    }
+}
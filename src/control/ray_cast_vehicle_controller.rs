@@ -824,3 +824,109 @@ fn resolve_single_unilateral(body1: &RigidBody, pt1: &Point<Real>, normal: &Vect
     let contact_damping = 0.2;
     -contact_damping * rel_vel * jac_diag_ab_inv
 }
+
+#[cfg(test)]
+mod test {
+    use super::{DynamicRayCastVehicleController, WheelTuning};
+    use crate::prelude::*;
+
+    #[test]
+    fn vehicle_climbs_ramp_without_flipping() {
+        let mut pipeline = PhysicsPipeline::new();
+        let gravity = Vector::y() * -9.81;
+        let params = IntegrationParameters::default();
+        let mut islands = IslandManager::new();
+        let mut bf = BroadPhaseMultiSap::new();
+        let mut nf = NarrowPhase::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut ccd = CCDSolver::new();
+        let mut queries = QueryPipeline::new();
+
+        // Flat ground with its top surface on `y = 0`, followed by a 20° ramp starting at `x = 4`.
+        let ramp_angle = 20.0f64.to_radians() as Real;
+        let ramp_half_length = 5.0;
+        colliders
+            .insert(ColliderBuilder::cuboid(4.0, 0.5, 10.0).translation(vector![0.0, -0.5, 0.0]));
+        let ramp_dir = vector![ramp_angle.cos(), ramp_angle.sin(), 0.0];
+        let ramp_normal = vector![-ramp_angle.sin(), ramp_angle.cos(), 0.0];
+        colliders.insert(
+            ColliderBuilder::cuboid(ramp_half_length, 0.5, 10.0)
+                .translation(
+                    vector![4.0, 0.0, 0.0] + ramp_dir * ramp_half_length - ramp_normal * 0.5,
+                )
+                .rotation(Vector::z() * ramp_angle),
+        );
+
+        let hw = 0.3;
+        let hh = 0.15;
+        let chassis =
+            bodies.insert(RigidBodyBuilder::dynamic().translation(vector![0.0, 0.5, 0.0]));
+        colliders.insert_with_parent(
+            ColliderBuilder::cuboid(hw * 2.0, hh, hw),
+            chassis,
+            &mut bodies,
+        );
+
+        let mut vehicle = DynamicRayCastVehicleController::new(chassis);
+        let tuning = WheelTuning::default();
+        for pos in [
+            point![hw * 1.5, -hh, hw],
+            point![hw * 1.5, -hh, -hw],
+            point![-hw * 1.5, -hh, hw],
+            point![-hw * 1.5, -hh, -hw],
+        ] {
+            vehicle.add_wheel(pos, -Vector::y(), Vector::z(), hh, hh / 4.0, &tuning);
+        }
+
+        for wheel in vehicle.wheels_mut() {
+            wheel.engine_force = 5.0;
+        }
+
+        let ramp_top = vector![4.0, 0.0, 0.0] + ramp_dir * ramp_half_length * 2.0;
+        let mut reached_ramp_top = false;
+
+        for _ in 0..600 {
+            vehicle.update_vehicle(
+                params.dt,
+                &mut bodies,
+                &colliders,
+                &queries,
+                QueryFilter::exclude_dynamic().exclude_rigid_body(chassis),
+            );
+            pipeline.step(
+                &gravity,
+                &params,
+                &mut islands,
+                &mut bf,
+                &mut nf,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut ccd,
+                Some(&mut queries),
+                &(),
+                &(),
+            );
+
+            // The chassis must never flip over.
+            let up = bodies[chassis].position() * Vector::y();
+            assert!(up.y > 0.5, "the vehicle flipped: {up}");
+
+            if bodies[chassis].translation().x > ramp_top.x - 1.0 {
+                reached_ramp_top = true;
+                break;
+            }
+        }
+
+        assert!(
+            reached_ramp_top,
+            "the vehicle didn’t climb the ramp: {}",
+            bodies[chassis].translation()
+        );
+        assert!(bodies[chassis].translation().y > ramp_top.y - 1.0);
+    }
+}
@@ -6,6 +6,8 @@ use std::fmt::{Display, Formatter, Result};
 pub struct CollisionDetectionCounters {
     /// Number of contact pairs detected.
     pub ncontact_pairs: usize,
+    /// Number of contact pairs skipped by the narrow-phase because their shapes are too far apart.
+    pub nculled_pairs: usize,
     /// Time spent for the broad-phase of the collision detection.
     pub broad_phase_time: Timer,
     /// Time spent for the narrow-phase of the collision detection.
@@ -17,6 +19,7 @@ impl CollisionDetectionCounters {
     pub fn new() -> Self {
         CollisionDetectionCounters {
             ncontact_pairs: 0,
+            nculled_pairs: 0,
             broad_phase_time: Timer::new(),
             narrow_phase_time: Timer::new(),
         }
@@ -25,6 +28,7 @@ impl CollisionDetectionCounters {
     /// Resets all the coounters and timers.
     pub fn reset(&mut self) {
         self.ncontact_pairs = 0;
+        self.nculled_pairs = 0;
         self.broad_phase_time.reset();
         self.narrow_phase_time.reset();
     }
@@ -33,6 +37,7 @@ impl CollisionDetectionCounters {
 impl Display for CollisionDetectionCounters {
     fn fmt(&self, f: &mut Formatter) -> Result {
         writeln!(f, "Number of contact pairs: {}", self.ncontact_pairs)?;
+        writeln!(f, "Number of culled pairs: {}", self.nculled_pairs)?;
         writeln!(f, "Broad-phase time: {}", self.broad_phase_time)?;
         writeln!(f, "Narrow-phase time: {}", self.narrow_phase_time)
     }
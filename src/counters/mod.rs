@@ -4,7 +4,7 @@ use std::fmt::{Display, Formatter, Result};
 
 pub use self::ccd_counters::CCDCounters;
 pub use self::collision_detection_counters::CollisionDetectionCounters;
-pub use self::solver_counters::SolverCounters;
+pub use self::solver_counters::{IslandSolverCounters, SolverCounters};
 pub use self::stages_counters::StagesCounters;
 pub use self::timer::Timer;
 
@@ -15,7 +15,7 @@ mod stages_counters;
 mod timer;
 
 /// Aggregation of all the performances counters tracked by rapier.
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Counters {
     /// Whether this counter is enabled or not.
     pub enabled: bool,
@@ -115,6 +115,11 @@ impl Counters {
         self.cd.ncontact_pairs = n;
     }
 
+    /// Set the number of contact pairs culled by the narrow-phase.
+    pub fn set_nculled_pairs(&mut self, n: usize) {
+        self.cd.nculled_pairs = n;
+    }
+
     /// Resets all the counters and timers.
     pub fn reset(&mut self) {
         if self.enabled {
@@ -1,13 +1,42 @@
 use crate::counters::Timer;
 use std::fmt::{Display, Formatter, Result};
 
+/// Statistics about the constraints resolution of a single active island.
+///
+/// Note that islands with less than
+/// [`IntegrationParameters::min_island_size`](crate::dynamics::IntegrationParameters::min_island_size)
+/// dynamic bodies are merged together before being solved.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IslandSolverCounters {
+    /// Number of active dynamic rigid-bodies in this island.
+    pub nbodies: usize,
+    /// Number of contact constraints in this island.
+    pub ncontact_constraints: usize,
+    /// Number of impulse joint constraints in this island.
+    pub njoint_constraints: usize,
+    /// Number of solver iterations run on this island.
+    ///
+    /// This is [`IntegrationParameters::num_solver_iterations`](crate::dynamics::IntegrationParameters::num_solver_iterations)
+    /// plus the largest [`RigidBody::additional_solver_iterations`](crate::dynamics::RigidBody::additional_solver_iterations)
+    /// among the bodies of this island.
+    pub niterations: usize,
+}
+
 /// Performance counters related to constraints resolution.
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone)]
 pub struct SolverCounters {
     /// Number of constraints generated.
     pub nconstraints: usize,
     /// Number of contacts found.
     pub ncontacts: usize,
+    /// Statistics of each active island, indexed by active island id. Only filled while the
+    /// counters are enabled.
+    pub islands: Vec<IslandSolverCounters>,
+    /// Number of groups of awake dynamic rigid-bodies connected by contacts or joints, before
+    /// the small ones are merged into the islands of [`Self::islands`].
+    ///
+    /// See [`IslandManager::num_connected_islands`](crate::dynamics::IslandManager::num_connected_islands).
+    pub nconnected_islands: usize,
     /// Time spent for the resolution of the constraints (force computation).
     pub velocity_resolution_time: Timer,
     /// Time spent for the assembly of all the velocity constraints.
@@ -24,6 +53,8 @@ impl SolverCounters {
         SolverCounters {
             nconstraints: 0,
             ncontacts: 0,
+            islands: Vec::new(),
+            nconnected_islands: 0,
             velocity_assembly_time: Timer::new(),
             velocity_resolution_time: Timer::new(),
             velocity_update_time: Timer::new(),
@@ -35,6 +66,8 @@ impl SolverCounters {
     pub fn reset(&mut self) {
         self.nconstraints = 0;
         self.ncontacts = 0;
+        self.islands.clear();
+        self.nconnected_islands = 0;
         self.velocity_resolution_time.reset();
         self.velocity_assembly_time.reset();
         self.velocity_update_time.reset();
@@ -46,6 +79,12 @@ impl Display for SolverCounters {
     fn fmt(&self, f: &mut Formatter) -> Result {
         writeln!(f, "Number of contacts: {}", self.ncontacts)?;
         writeln!(f, "Number of constraints: {}", self.nconstraints)?;
+        writeln!(f, "Number of solved islands: {}", self.islands.len())?;
+        writeln!(
+            f,
+            "Number of connected islands: {}",
+            self.nconnected_islands
+        )?;
         writeln!(f, "Velocity assembly time: {}", self.velocity_assembly_time)?;
         writeln!(
             f,
@@ -92,6 +92,12 @@ impl Index {
     }
 }
 
+impl std::fmt::Display for Index {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
 const DEFAULT_CAPACITY: usize = 4;
 
 impl<T> Default for Arena<T> {
@@ -176,6 +182,8 @@ impl<T> Arena<T> {
         }));
         self.free_list_head = Some(0);
         self.len = 0;
+        // Make sure the indices of the cleared elements don’t match the future ones.
+        self.generation += 1;
     }
 
     /// Attempts to insert `value` into the arena using existing capacity.
@@ -13,6 +13,11 @@ impl<T> Coarena<T> {
         Self { data: Vec::new() }
     }
 
+    /// Removes all the elements of this coarena, but keeps its allocation.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
     /// Iterates through all the elements of this coarena.
     pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> {
         self.data
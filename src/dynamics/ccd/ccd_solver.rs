@@ -1,6 +1,6 @@
 use super::TOIEntry;
-use crate::dynamics::{IslandManager, RigidBodyHandle, RigidBodySet};
-use crate::geometry::{ColliderParent, ColliderSet, CollisionEvent, NarrowPhase};
+use crate::dynamics::{IslandManager, RigidBody, RigidBodyHandle, RigidBodySet, RigidBodyVelocity};
+use crate::geometry::{Aabb, Collider, ColliderParent, ColliderSet, CollisionEvent, NarrowPhase};
 use crate::math::Real;
 use crate::parry::utils::SortedPair;
 use crate::pipeline::{EventHandler, QueryPipeline, QueryPipelineMode};
@@ -9,6 +9,35 @@ use parry::query::{DefaultQueryDispatcher, QueryDispatcher};
 use parry::utils::hashmap::HashMap;
 use std::collections::BinaryHeap;
 
+/// The AABB swept by the collider `co`, attached to `rb`, if `rb` moves with the velocities
+/// `vels` and its forces during `dt`.
+///
+/// This includes the arcs described by the collider’s points if `rb` rotates.
+pub(crate) fn predicted_swept_aabb(
+    co: &Collider,
+    rb: &RigidBody,
+    vels: &RigidBodyVelocity,
+    dt: Real,
+) -> Aabb {
+    let co_parent = co
+        .parent
+        .as_ref()
+        .expect("Could not find the ColliderParent component.");
+    let local_com = &rb.mprops.local_mprops.local_com;
+    let new_vels = rb.forces.integrate(dt, vels, &rb.mprops);
+    let next_position = new_vels.integrate(dt, &rb.pos.position, local_com);
+    #[cfg(feature = "dim2")]
+    let angle = new_vels.angvel.abs() * dt;
+    #[cfg(feature = "dim3")]
+    let angle = new_vels.angvel.norm() * dt;
+
+    co.compute_rotating_swept_aabb(
+        &(next_position * co_parent.pos_wrt_parent),
+        &(rb.pos.position * local_com),
+        angle,
+    )
+}
+
 pub enum PredictedImpacts {
     Impacts(HashMap<RigidBodyHandle, Real>),
     ImpactsAfterEndTime(Real),
@@ -51,14 +80,30 @@ impl CCDSolver {
     /// Apply motion-clamping to the bodies affected by the given `impacts`.
     ///
     /// The `impacts` should be the result of a previous call to `self.predict_next_impacts`.
+    /// This uses the default [`IntegrationParameters::ccd_min_advancement`](crate::dynamics::IntegrationParameters::ccd_min_advancement).
     pub fn clamp_motions(&self, dt: Real, bodies: &mut RigidBodySet, impacts: &PredictedImpacts) {
+        self.clamp_motions_with_min_advancement(dt, 0.15, bodies, impacts)
+    }
+
+    /// Apply motion-clamping to the bodies affected by the given `impacts`.
+    ///
+    /// Each clamped body still advances by at least `min_advancement` times its CCD thickness,
+    /// so it can’t get stuck at a time-of-impact of zero. See
+    /// [`IntegrationParameters::ccd_min_advancement`](crate::dynamics::IntegrationParameters::ccd_min_advancement).
+    pub fn clamp_motions_with_min_advancement(
+        &self,
+        dt: Real,
+        min_advancement: Real,
+        bodies: &mut RigidBodySet,
+        impacts: &PredictedImpacts,
+    ) {
         if let PredictedImpacts::Impacts(tois) = impacts {
             for (handle, toi) in tois {
                 let rb = bodies.index_mut_internal(*handle);
                 let local_com = &rb.mprops.local_mprops.local_com;
 
                 let min_toi = (rb.ccd.ccd_thickness
-                    * 0.15
+                    * min_advancement
                     * crate::utils::inv(rb.ccd.max_point_velocity(&rb.integrated_vels)))
                 .min(dt);
                 // println!(
@@ -130,28 +175,13 @@ impl CCDSolver {
             let rb1 = &bodies[*handle];
 
             if rb1.ccd.ccd_active {
-                let predicted_body_pos1 = rb1.pos.integrate_forces_and_velocities(
-                    dt,
-                    &rb1.forces,
-                    &rb1.integrated_vels,
-                    &rb1.mprops,
-                );
-
                 for ch1 in &rb1.colliders.0 {
                     let co1 = &colliders[*ch1];
-                    let co1_parent = co1
-                        .parent
-                        .as_ref()
-                        .expect("Could not find the ColliderParent component.");
-
                     if co1.is_sensor() {
                         continue; // Ignore sensors.
                     }
 
-                    let predicted_collider_pos1 = predicted_body_pos1 * co1_parent.pos_wrt_parent;
-                    let aabb1 = co1
-                        .shape
-                        .compute_swept_aabb(&co1.pos, &predicted_collider_pos1);
+                    let aabb1 = predicted_swept_aabb(co1, rb1, &rb1.integrated_vels, dt);
 
                     self.query_pipeline
                         .colliders_with_aabb_intersecting_aabb(&aabb1, |ch2| {
@@ -231,6 +261,7 @@ impl CCDSolver {
         colliders: &ColliderSet,
         narrow_phase: &NarrowPhase,
         events: &dyn EventHandler,
+        step_id: u64,
     ) -> PredictedImpacts {
         let mut frozen = HashMap::<_, Real>::default();
         let mut all_toi = BinaryHeap::new();
@@ -254,24 +285,9 @@ impl CCDSolver {
             let rb1 = &bodies[*handle];
 
             if rb1.ccd.ccd_active {
-                let predicted_body_pos1 = rb1.pos.integrate_forces_and_velocities(
-                    dt,
-                    &rb1.forces,
-                    &rb1.integrated_vels,
-                    &rb1.mprops,
-                );
-
                 for ch1 in &rb1.colliders.0 {
                     let co1 = &colliders[*ch1];
-                    let co_parent1 = co1
-                        .parent
-                        .as_ref()
-                        .expect("Could not find the ColliderParent component.");
-
-                    let predicted_collider_pos1 = predicted_body_pos1 * co_parent1.pos_wrt_parent;
-                    let aabb1 = co1
-                        .shape
-                        .compute_swept_aabb(&co1.pos, &predicted_collider_pos1);
+                    let aabb1 = predicted_swept_aabb(co1, rb1, &rb1.integrated_vels, dt);
 
                     self.query_pipeline
                         .colliders_with_aabb_intersecting_aabb(&aabb1, |ch2| {
@@ -537,12 +553,14 @@ impl CCDSolver {
             {
                 // Emit one intersection-started and one intersection-stopped event.
                 events.handle_collision_event(
+                    step_id,
                     bodies,
                     colliders,
                     CollisionEvent::Started(toi.c1, toi.c2, CollisionEventFlags::SENSOR),
                     None,
                 );
                 events.handle_collision_event(
+                    step_id,
                     bodies,
                     colliders,
                     CollisionEvent::Stopped(toi.c1, toi.c2, CollisionEventFlags::SENSOR),
@@ -554,3 +572,47 @@ impl CCDSolver {
         PredictedImpacts::Impacts(frozen)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{CCDSolver, PredictedImpacts};
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderSet};
+    use crate::math::{Real, Vector};
+    use parry::utils::hashmap::HashMap;
+
+    #[test]
+    fn wedged_body_keeps_advancing() {
+        let dt = 1.0 / 60.0;
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let ball = bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .linvel(Vector::x() * 60.0)
+                .ccd_enabled(true),
+        );
+        colliders.insert_with_parent(ColliderBuilder::ball(0.5), ball, &mut bodies);
+        let ccd_thickness = bodies[ball].ccd.ccd_thickness;
+        bodies[ball].integrated_vels = bodies[ball].vels;
+
+        // A body wedged into a corner reports an impact at the very start of each substep.
+        let mut tois = HashMap::default();
+        let _ = tois.insert(ball, 0.0);
+        let impacts = PredictedImpacts::Impacts(tois);
+        let ccd_solver = CCDSolver::new();
+        let advancement = |bodies: &mut RigidBodySet, min_advancement: Real| {
+            ccd_solver.clamp_motions_with_min_advancement(dt, min_advancement, bodies, &impacts);
+            let rb = &bodies[ball];
+            (rb.pos.next_position.translation.vector - rb.pos.position.translation.vector).x
+        };
+
+        // Without any minimum advancement, the body freezes.
+        assert_eq!(advancement(&mut bodies, 0.0), 0.0);
+
+        // Otherwise, it keeps moving forward, but less than its thickness to avoid tunneling.
+        let distance = advancement(&mut bodies, 0.15);
+        approx::assert_relative_eq!(distance, ccd_thickness * 0.15, epsilon = 1.0e-5);
+        assert!(distance > 0.0 && distance < ccd_thickness);
+        approx::assert_relative_eq!(advancement(&mut bodies, 1.0e6), 60.0 * dt);
+    }
+}
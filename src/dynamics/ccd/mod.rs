@@ -4,5 +4,7 @@
 pub use self::ccd_solver::{CCDSolver, PredictedImpacts};
 pub use self::toi_entry::TOIEntry;
 
+pub(crate) use self::ccd_solver::predicted_swept_aabb;
+
 mod ccd_solver;
 mod toi_entry;
@@ -65,14 +65,16 @@ impl TOIEntry {
         let angvel2 = frozen2.is_none() as u32 as Real
             * rb2.map(|b| b.integrated_vels.angvel).unwrap_or(na::zero());
 
+        // The points of each collider move at most at their body’s linear velocity plus
+        // the angular velocity times their distance to the body’s center of mass.
+        let max_dist1 = rb1.map(|b| Self::max_dist_from_com(co1, b)).unwrap_or(0.0);
+        let max_dist2 = rb2.map(|b| Self::max_dist_from_com(co2, b)).unwrap_or(0.0);
         #[cfg(feature = "dim2")]
-        let vel12 = (linvel2 - linvel1).norm()
-            + angvel1.abs() * rb1.map(|b| b.ccd.ccd_max_dist).unwrap_or(0.0)
-            + angvel2.abs() * rb2.map(|b| b.ccd.ccd_max_dist).unwrap_or(0.0);
+        let vel12 =
+            (linvel2 - linvel1).norm() + angvel1.abs() * max_dist1 + angvel2.abs() * max_dist2;
         #[cfg(feature = "dim3")]
-        let vel12 = (linvel2 - linvel1).norm()
-            + angvel1.norm() * rb1.map(|b| b.ccd.ccd_max_dist).unwrap_or(0.0)
-            + angvel2.norm() * rb2.map(|b| b.ccd.ccd_max_dist).unwrap_or(0.0);
+        let vel12 =
+            (linvel2 - linvel1).norm() + angvel1.norm() * max_dist1 + angvel2.norm() * max_dist2;
 
         // We may be slightly over-conservative by taking the `max(0.0)` here.
         // But removing the `max` doesn't really affect performances so let's
@@ -153,6 +155,14 @@ impl TOIEntry {
         ))
     }
 
+    fn max_dist_from_com(co: &Collider, rb: &RigidBody) -> Real {
+        let bsphere = co.shape.compute_bounding_sphere(&co.pos);
+        na::distance(
+            &bsphere.center,
+            &(rb.pos.position * rb.mprops.local_mprops.local_com),
+        ) + bsphere.radius
+    }
+
     fn body_motion(rb: &RigidBody) -> NonlinearRigidMotion {
         if rb.ccd.ccd_active {
             NonlinearRigidMotion::new(
@@ -0,0 +1,626 @@
+use crate::data::Arena;
+use crate::dynamics::{IntegrationParameters, IslandManager, RigidBodyHandle, RigidBodySet};
+use crate::geometry::NarrowPhase;
+use crate::math::{Point, Real, Vector, DEFAULT_EPSILON};
+
+/// The unique identifier of a gravity well added to a [`GravityWellSet`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[repr(transparent)]
+pub struct GravityWellHandle(pub crate::data::arena::Index);
+
+impl GravityWellHandle {
+    /// Converts this handle into its (index, generation) components.
+    pub fn into_raw_parts(self) -> (u32, u32) {
+        self.0.into_raw_parts()
+    }
+
+    /// Reconstructs an handle from its (index, generation) components.
+    pub fn from_raw_parts(id: u32, generation: u32) -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(id, generation))
+    }
+
+    /// An always-invalid gravity well handle.
+    pub fn invalid() -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(
+            crate::INVALID_U32,
+            crate::INVALID_U32,
+        ))
+    }
+}
+
+impl std::fmt::Display for GravityWellHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GravityWellHandle({})", self.0)
+    }
+}
+
+/// A point mass attracting the dynamic rigid-bodies within its sphere of influence, like a star
+/// or a planet.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct GravityWell {
+    /// The center of the well, in world-space, or in the local-space of [`Self::body`] if it is
+    /// attached to a rigid-body.
+    pub center: Point<Real>,
+    /// The rigid-body this well is attached to and follows, if any.
+    ///
+    /// This rigid-body isn’t attracted by its own well. The well is ignored if this rigid-body
+    /// is removed.
+    pub body: Option<RigidBodyHandle>,
+    /// The standard gravitational parameter `GM` of the well: the acceleration at a distance
+    /// `d` from its center is `strength / d²`.
+    pub strength: Real,
+    /// The radius of the sphere of influence of the well: only the bodies with their
+    /// center-of-mass closer than this distance from its center are attracted by it.
+    pub influence_radius: Real,
+    /// The distance below which the acceleration stops growing, e.g., the radius of a planet
+    /// (default: `0.0`).
+    pub min_distance: Real,
+    /// User-defined data associated to this gravity well.
+    pub user_data: u128,
+}
+
+impl GravityWell {
+    /// Creates a gravity well with a fixed world-space center.
+    pub fn new(center: Point<Real>, strength: Real, influence_radius: Real) -> Self {
+        Self {
+            center,
+            body: None,
+            strength,
+            influence_radius,
+            min_distance: 0.0,
+            user_data: 0,
+        }
+    }
+
+    /// Creates a gravity well centered on the origin of the given rigid-body, and following it.
+    pub fn attached_to(body: RigidBodyHandle, strength: Real, influence_radius: Real) -> Self {
+        Self {
+            body: Some(body),
+            ..Self::new(Point::origin(), strength, influence_radius)
+        }
+    }
+
+    /// Sets the center of the well, in the local-space of its rigid-body if it is attached to one.
+    #[must_use]
+    pub fn center(mut self, center: Point<Real>) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// Sets the distance below which the acceleration stops growing.
+    #[must_use]
+    pub fn min_distance(mut self, min_distance: Real) -> Self {
+        self.min_distance = min_distance;
+        self
+    }
+
+    /// Sets the user-defined data associated to this gravity well.
+    #[must_use]
+    pub fn user_data(mut self, user_data: u128) -> Self {
+        self.user_data = user_data;
+        self
+    }
+
+    /// The acceleration this well, centered at the world-space point `world_center`, applies at
+    /// the world-space `point`, ignoring its sphere of influence.
+    pub fn acceleration_at(&self, world_center: &Point<Real>, point: &Point<Real>) -> Vector<Real> {
+        let to_center = world_center - point;
+        let distance = to_center.norm();
+
+        if distance > DEFAULT_EPSILON {
+            let clamped_distance = distance.max(self.min_distance);
+            to_center * (self.strength / (clamped_distance * clamped_distance * distance))
+        } else {
+            Vector::zeros()
+        }
+    }
+}
+
+/// The rule selecting the well attracting a body inside the spheres of influence of several
+/// gravity wells.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum GravityWellSelection {
+    /// The well with the smallest sphere of influence, e.g., the moon rather than its planet,
+    /// and the planet rather than its star.
+    #[default]
+    Innermost,
+    /// The well applying the largest acceleration to the body.
+    Strongest,
+}
+
+/// A set of gravity wells attracting each dynamic rigid-body toward the dominant well it is in.
+///
+/// Each dynamic rigid-body is attracted by a single well, selected with [`Self::selection`]
+/// among the ones containing its center-of-mass in their sphere of influence. This models
+/// hierarchical systems, e.g., ships orbiting planets orbiting a star, with a clean hand-off
+/// when a body crosses the boundary of a sphere of influence. The acceleration of the well
+/// is added to the gravity of the body, and multiplied by its gravity scale.
+///
+/// Call [`Self::update`] before each step of the physics pipeline. This is done automatically
+/// by [`PhysicsWorld`](crate::pipeline::PhysicsWorld).
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct GravityWellSet {
+    wells: Arena<GravityWell>,
+    /// The rule selecting the well attracting a body inside several spheres of influence.
+    pub selection: GravityWellSelection,
+    /// Buffers reused by [`Self::update`].
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    accelerations: Vec<(RigidBodyHandle, Vector<Real>)>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    to_wake_up: Vec<RigidBodyHandle>,
+}
+
+impl GravityWellSet {
+    /// Creates a new empty set of gravity wells.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of gravity wells in this set.
+    pub fn len(&self) -> usize {
+        self.wells.len()
+    }
+
+    /// `true` if there are no gravity wells in this set.
+    pub fn is_empty(&self) -> bool {
+        self.wells.is_empty()
+    }
+
+    /// Adds a gravity well to this set.
+    pub fn insert(&mut self, well: GravityWell) -> GravityWellHandle {
+        GravityWellHandle(self.wells.insert(well))
+    }
+
+    /// Removes a gravity well from this set.
+    pub fn remove(&mut self, handle: GravityWellHandle) -> Option<GravityWell> {
+        self.wells.remove(handle.0)
+    }
+
+    /// Removes every gravity well from this set.
+    ///
+    /// The bodies they were attracting stop being attracted at the next step.
+    pub fn clear(&mut self) {
+        self.wells.clear();
+    }
+
+    /// Gets the gravity well with the given handle.
+    pub fn get(&self, handle: GravityWellHandle) -> Option<&GravityWell> {
+        self.wells.get(handle.0)
+    }
+
+    /// Gets a mutable reference to the gravity well with the given handle.
+    pub fn get_mut(&mut self, handle: GravityWellHandle) -> Option<&mut GravityWell> {
+        self.wells.get_mut(handle.0)
+    }
+
+    /// Iterates through all the gravity wells of this set.
+    pub fn iter(&self) -> impl Iterator<Item = (GravityWellHandle, &GravityWell)> {
+        self.wells.iter().map(|(h, w)| (GravityWellHandle(h), w))
+    }
+
+    /// The world-space center of a well, or `None` if the rigid-body it is attached to doesn’t
+    /// exist.
+    fn world_center(well: &GravityWell, bodies: &RigidBodySet) -> Option<Point<Real>> {
+        match well.body {
+            Some(body) => bodies.get(body).map(|rb| rb.position() * well.center),
+            None => Some(well.center),
+        }
+    }
+
+    /// The dominant well at the given world-space point, and the acceleration it applies there.
+    ///
+    /// The wells attached to the rigid-body `exclude_body` are ignored.
+    pub fn dominant_well(
+        &self,
+        bodies: &RigidBodySet,
+        point: &Point<Real>,
+        exclude_body: Option<RigidBodyHandle>,
+    ) -> Option<(GravityWellHandle, Vector<Real>)> {
+        let mut best: Option<(GravityWellHandle, Vector<Real>, Real)> = None;
+
+        for (handle, well) in self.iter() {
+            if well.body.is_some() && well.body == exclude_body {
+                continue;
+            }
+
+            let Some(center) = Self::world_center(well, bodies) else {
+                continue;
+            };
+
+            if na::distance(&center, point) >= well.influence_radius {
+                continue;
+            }
+
+            let acceleration = well.acceleration_at(&center, point);
+            let score = match self.selection {
+                GravityWellSelection::Innermost => -well.influence_radius,
+                GravityWellSelection::Strongest => acceleration.norm(),
+            };
+
+            let is_better = match best {
+                Some((_, _, best_score)) => score > best_score,
+                None => true,
+            };
+
+            if is_better {
+                best = Some((handle, acceleration, score));
+            }
+        }
+
+        best.map(|(handle, acceleration, _)| (handle, acceleration))
+    }
+
+    /// Computes the acceleration of every dynamic rigid-body inside a well, to be applied by the
+    /// next step of the physics pipeline.
+    ///
+    /// The wells attached to rigid-bodies are centered on their current position. This also
+    /// wakes up the bodies inside a well unless they are at rest relative to it: touching
+    /// something, and with a velocity relative to the well’s rigid-body below their sleep
+    /// threshold. This prevents, e.g., a body from falling asleep at the apex of its trajectory.
+    /// Sleeping bodies touching something are left asleep, but their acceleration is still
+    /// computed so they are attracted by their well as soon as they are woken up, e.g., by a
+    /// contact during the next step.
+    pub fn update(
+        &mut self,
+        params: &IntegrationParameters,
+        islands: &mut IslandManager,
+        bodies: &mut RigidBodySet,
+        narrow_phase: &NarrowPhase,
+    ) {
+        if self.wells.is_empty() {
+            return;
+        }
+
+        self.accelerations.clear();
+        self.to_wake_up.clear();
+
+        for (handle, rb) in bodies.iter() {
+            if !rb.is_dynamic() || !rb.is_enabled() {
+                continue;
+            }
+
+            let is_supported = || {
+                rb.colliders().iter().any(|collider| {
+                    narrow_phase
+                        .contact_pairs_with(*collider)
+                        .any(|pair| pair.has_any_active_contact)
+                })
+            };
+
+            let Some((well_handle, acceleration)) =
+                self.dominant_well(bodies, rb.center_of_mass(), Some(handle))
+            else {
+                continue;
+            };
+
+            self.accelerations.push((handle, acceleration));
+
+            if rb.is_sleeping() && is_supported() {
+                continue;
+            }
+
+            // The body is at rest relative to the well if it is supported by something and
+            // moves along with the well’s rigid-body.
+            let well_velocity = self.wells[well_handle.0]
+                .body
+                .and_then(|body| bodies.get(body))
+                .map(|well_rb| well_rb.velocity_at_point(rb.center_of_mass()))
+                .unwrap_or_else(Vector::zeros);
+            let linear_threshold = rb
+                .activation()
+                .effective_normalized_linear_threshold(params)
+                * params.length_unit;
+
+            if (rb.linvel() - well_velocity).norm() > linear_threshold || !is_supported() {
+                self.to_wake_up.push(handle);
+            }
+        }
+
+        for (handle, acceleration) in &self.accelerations {
+            bodies
+                .index_mut_internal(*handle)
+                .forces
+                .gravity_well_acceleration = *acceleration;
+        }
+
+        for handle in &self.to_wake_up {
+            islands.wake_up(bodies, *handle, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GravityWell;
+    use crate::dynamics::{RigidBody, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::PhysicsWorld;
+    use crate::utils::SimdCross;
+
+    /// The angular momentum, per unit mass, of a rigid-body orbiting the origin in the xy plane.
+    fn angular_momentum(rb: &RigidBody) -> Real {
+        let momentum = rb.translation().gcross(*rb.linvel());
+        #[cfg(feature = "dim2")]
+        return momentum;
+        #[cfg(feature = "dim3")]
+        return momentum.z;
+    }
+
+    #[test]
+    fn ship_is_handed_off_from_planet_to_star() {
+        let (star_strength, planet_strength) = (1000.0, 10.0);
+        let (star_influence, planet_influence) = (1000.0, 15.0);
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        let dt = world.integration_parameters.dt;
+        world.gravity_wells.insert(GravityWell::new(
+            Point::origin(),
+            star_strength,
+            star_influence,
+        ));
+
+        // A planet on a circular orbit around the star.
+        let orbit_radius = 100.0;
+        let planet_speed = (star_strength / orbit_radius).sqrt();
+        let planet = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * orbit_radius)
+                .linvel(Vector::y() * planet_speed),
+        );
+        world.insert_collider_with_parent(ColliderBuilder::ball(1.0), planet);
+        world.gravity_wells.insert(
+            GravityWell::attached_to(planet, planet_strength, planet_influence).min_distance(1.0),
+        );
+
+        // A ship escaping the planet, away from the star.
+        let ship = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * (orbit_radius + 3.0))
+                .linvel(Vector::y() * planet_speed + Vector::x() * 3.0),
+        );
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.1), ship);
+
+        let mut handoff_step = None;
+        let mut prev_dvel = Vector::zeros();
+        let mut star_angular_momentum = 0.0;
+
+        for i in 0..3600 {
+            let ship_pos = *world.bodies[ship].center_of_mass();
+            let planet_pos = *world.bodies[planet].center_of_mass();
+            let prev_vel = *world.bodies[ship].linvel();
+            world.step();
+
+            // The ship is attracted by the innermost well it was in at the beginning of the step.
+            let to_planet = planet_pos - ship_pos;
+            let in_planet_well = to_planet.norm() < planet_influence;
+            let expected_gravity = if in_planet_well {
+                to_planet * planet_strength / to_planet.norm().powi(3)
+            } else {
+                -ship_pos.coords * star_strength / ship_pos.coords.norm().powi(3)
+            };
+            let rb = &world.bodies[ship];
+            assert!(
+                (rb.effective_gravity() - expected_gravity).norm()
+                    < expected_gravity.norm() * 1.0e-3,
+                "{} != {expected_gravity}",
+                rb.effective_gravity()
+            );
+
+            // No impulse is applied to the ship besides the gravity of its well.
+            let dvel = rb.linvel() - prev_vel;
+            assert!(
+                (dvel - rb.effective_gravity() * dt).norm() < 1.0e-5,
+                "{dvel} != {}",
+                rb.effective_gravity() * dt
+            );
+
+            match handoff_step {
+                None if !in_planet_well => {
+                    // The jump of velocity change at the hand-off is bounded by the
+                    // accelerations of both wells at the boundary.
+                    let max_jump = (planet_strength / (planet_influence * planet_influence)
+                        + star_strength / (orbit_radius * orbit_radius))
+                        * dt;
+                    assert!(
+                        (dvel - prev_dvel).norm() <= max_jump,
+                        "{} > {max_jump}",
+                        (dvel - prev_dvel).norm()
+                    );
+                    handoff_step = Some(i);
+                    star_angular_momentum = angular_momentum(rb);
+                }
+                Some(_) => {
+                    // The ship keeps orbiting the star on its own: its angular momentum
+                    // around the star is conserved.
+                    assert!(!in_planet_well);
+                    assert!(
+                        (angular_momentum(rb) - star_angular_momentum).abs()
+                            < star_angular_momentum.abs() * 2.0e-3,
+                        "{} != {star_angular_momentum}",
+                        angular_momentum(rb)
+                    );
+                    assert!(rb.translation().norm() < star_influence);
+                }
+                None => {}
+            }
+
+            prev_dvel = dvel;
+        }
+
+        let handoff_step = handoff_step.expect("the ship never left the planet’s well");
+        assert!(handoff_step > 0 && handoff_step < 1200, "{handoff_step}");
+        // The planet stayed on its circular orbit.
+        let planet_distance = world.bodies[planet].translation().norm();
+        assert!(
+            (planet_distance - orbit_radius).abs() < 1.0,
+            "{planet_distance}"
+        );
+    }
+
+    #[test]
+    fn gravity_well_acceleration_only_lasts_one_step() {
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        world
+            .gravity_wells
+            .insert(GravityWell::new(Point::origin(), 100.0, 100.0));
+        let body = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::x() * 10.0));
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5), body);
+
+        world.step();
+        assert!(world.bodies[body].effective_gravity().x < 0.0);
+
+        // Stepping the pipeline directly, without updating the wells, doesn’t apply them.
+        world.physics_pipeline.step(
+            &world.gravity,
+            &world.integration_parameters,
+            &mut world.islands,
+            &mut world.broad_phase,
+            &mut world.narrow_phase,
+            &mut world.bodies,
+            &mut world.colliders,
+            &mut world.impulse_joints,
+            &mut world.multibody_joints,
+            &mut world.ccd_solver,
+            None,
+            &(),
+            &(),
+        );
+        assert_eq!(world.bodies[body].effective_gravity(), Vector::zeros());
+    }
+
+    #[test]
+    fn bodies_in_gravity_wells_only_sleep_at_rest() {
+        let mut world = PhysicsWorld::new(Vector::zeros());
+
+        // A weak well in which a body starts at rest, far from anything.
+        let weak_center = Point::from(Vector::x() * 1000.0);
+        world
+            .gravity_wells
+            .insert(GravityWell::new(weak_center, 1.0, 20.0));
+        let floating = world.insert_body(
+            RigidBodyBuilder::dynamic().translation(weak_center.coords + Vector::x() * 10.0),
+        );
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5), floating);
+
+        // A planet on the surface of which a box rests.
+        let planet_radius = 50.0;
+        world.gravity_wells.insert(
+            GravityWell::new(
+                Point::from(-Vector::y() * planet_radius),
+                9.81 * planet_radius * planet_radius,
+                200.0,
+            )
+            .min_distance(planet_radius),
+        );
+        #[cfg(feature = "dim2")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        world.insert_collider(ground.translation(-Vector::y() * 0.5));
+        let resting = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        world.insert_collider_with_parent(cuboid, resting);
+
+        for _ in 0..600 {
+            world.step();
+        }
+
+        // The floating body moves too slowly to stay awake by itself, but keeps falling.
+        let floating = &world.bodies[floating];
+        assert!(!floating.is_sleeping());
+        assert!(
+            floating.translation().x < weak_center.x + 9.7,
+            "{}",
+            floating.translation()
+        );
+        // The box at rest on the planet fell asleep, its center being half a meter above the
+        // planet’s surface.
+        assert!(world.bodies[resting].is_sleeping());
+        let expected_gravity =
+            -Vector::y() * 9.81 * (planet_radius / (planet_radius + 0.5)).powi(2);
+        assert!(
+            (world.bodies[resting].effective_gravity() - expected_gravity).norm() < 1.0e-3,
+            "{}",
+            world.bodies[resting].effective_gravity()
+        );
+    }
+
+    #[test]
+    fn sleeping_body_woken_by_a_contact_is_attracted_by_its_well() {
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        let planet_radius = 50.0;
+        let planet = world.gravity_wells.insert(
+            GravityWell::new(
+                Point::from(-Vector::y() * planet_radius),
+                9.81 * planet_radius * planet_radius,
+                200.0,
+            )
+            .min_distance(planet_radius),
+        );
+        #[cfg(feature = "dim2")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        world.insert_collider(ground.translation(-Vector::y() * 0.5));
+        let resting = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        world.insert_collider_with_parent(cuboid.clone(), resting);
+
+        for _ in 0..600 {
+            world.step();
+        }
+        assert!(world.bodies[resting].is_sleeping());
+
+        // The well gets stronger while the box sleeps: its acceleration is still refreshed.
+        world.gravity_wells.get_mut(planet).unwrap().strength *= 2.0;
+        world.step();
+        let expected_gravity =
+            -Vector::y() * 2.0 * 9.81 * (planet_radius / (planet_radius + 0.5)).powi(2);
+        let rb = &world.bodies[resting];
+        assert!(rb.is_sleeping());
+        assert!(
+            (rb.forces.gravity_well_acceleration - expected_gravity).norm() < 1.0e-3,
+            "{}",
+            rb.forces.gravity_well_acceleration
+        );
+
+        // Drop a box on the resting one: it is woken up by the new contact during a step, and
+        // then integrated with the pull of the well.
+        let falling = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 2.0)
+                .linvel(-Vector::y() * 5.0),
+        );
+        world.insert_collider_with_parent(cuboid, falling);
+
+        let mut num_awake_steps = 0;
+        for _ in 0..30 {
+            let was_sleeping = world.bodies[resting].is_sleeping();
+            world.step();
+            let rb = &world.bodies[resting];
+            if !was_sleeping {
+                num_awake_steps += 1;
+                assert!(
+                    (rb.effective_gravity() - expected_gravity).norm() < 1.0e-2,
+                    "{}",
+                    rb.effective_gravity()
+                );
+            }
+        }
+        assert!(num_awake_steps > 0);
+    }
+}
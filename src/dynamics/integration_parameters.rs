@@ -1,3 +1,4 @@
+use crate::dynamics::RigidBodyActivation;
 use crate::math::Real;
 use na::RealField;
 use std::num::NonZeroUsize;
@@ -60,13 +61,37 @@ pub struct IntegrationParameters {
     ///
     /// (default `1.0`).
     pub warmstart_coefficient: Real,
+    /// The number of consecutive contact updates a contact point must be kept through before
+    /// being warmstarted (default: `1`).
+    ///
+    /// See [`ContactData::age`](crate::geometry::ContactData::age). Younger contact points start
+    /// from a zero impulse instead, which avoids applying stale impulses to the contacts of
+    /// colliders that were just teleported or reshaped. Larger values make warmstarting more
+    /// conservative.
+    pub warmstart_min_contact_age: u32,
+    /// Relative displacement of two colliders at a contact point, caused by teleporting one of
+    /// them, beyond which the contact point is no longer warmstarted (default: `0.002m`).
+    ///
+    /// Contacts between colliders teleported together aren’t affected. This value is implicitly
+    /// scaled by [`IntegrationParameters::length_unit`].
+    pub normalized_warmstart_max_teleport_displacement: Real,
+    /// Distance within which a new contact point inherits the impulses of a previous contact
+    /// point of the same pair when their features differ (default: `0.01m`).
+    ///
+    /// Contact points are first matched by the features (faces, edges, vertices) they involve.
+    /// Points whose features changed, e.g., when a box slides across the edge of a triangle or
+    /// goes from a face-face to an edge-face contact, are then matched with the closest unmatched
+    /// previous point lying within this distance of them on either collider, so they are still
+    /// warmstarted. Set this to `0.0` to only match contact points by features. This value is
+    /// implicitly scaled by [`IntegrationParameters::length_unit`].
+    pub normalized_contact_matching_tolerance: Real,
 
     /// The approximate size of most dynamic objects in the scene.
     ///
     /// This value is used internally to estimate some length-based tolerance. In particular, the
     /// values [`IntegrationParameters::allowed_linear_error`],
     /// [`IntegrationParameters::max_corrective_velocity`],
-    /// [`IntegrationParameters::prediction_distance`], [`RigidBodyActivation::linear_threshold`]
+    /// [`IntegrationParameters::prediction_distance`], [`RigidBodyActivation::normalized_linear_threshold`]
     /// are scaled by this value implicitly.
     ///
     /// This value can be understood as the number of units-per-meter in your physical world compared
@@ -80,7 +105,12 @@ pub struct IntegrationParameters {
     ///
     /// This value is implicitly scaled by [`IntegrationParameters::length_unit`].
     pub normalized_allowed_linear_error: Real,
-    /// Maximum amount of penetration the solver will attempt to resolve in one timestep (default: `10.0`).
+    /// Maximum velocity the solver will use to push penetrating objects apart (default: `10.0`).
+    ///
+    /// Penetrations are corrected at a velocity proportional to their depth, up to this value. This
+    /// avoids violently ejecting objects spawned deeply inside each other: they are pushed apart
+    /// smoothly over several steps instead. This correction isn’t kept in the velocity of the
+    /// rigid-bodies at the end of the step. Set it to `Real::MAX` to disable the limit.
     ///
     /// This value is implicitly scaled by [`IntegrationParameters::length_unit`].
     pub normalized_max_corrective_velocity: Real,
@@ -88,6 +118,13 @@ pub struct IntegrationParameters {
     ///
     /// This value is implicitly scaled by [`IntegrationParameters::length_unit`].
     pub normalized_prediction_distance: Real,
+    /// The relative normal velocity below which contacts don’t bounce (default: `1.0m/s`).
+    ///
+    /// Contacts approaching slower than this threshold are solved with zero restitution so that
+    /// they come to rest exactly. It is overridden by the threshold of colliders with a
+    /// [`ColliderMaterial::restitution_velocity_threshold`](crate::geometry::ColliderMaterial::restitution_velocity_threshold)
+    /// set. This value is implicitly scaled by [`IntegrationParameters::length_unit`].
+    pub normalized_restitution_velocity_threshold: Real,
     /// The number of solver iterations run by the constraints solver for calculating forces (default: `4`).
     pub num_solver_iterations: NonZeroUsize,
     /// Number of addition friction resolution iteration run during the last solver sub-step (default: `0`).
@@ -96,10 +133,131 @@ pub struct IntegrationParameters {
     pub num_internal_pgs_iterations: usize,
     /// The number of stabilization iterations run at each solver iterations (default: `2`).
     pub num_internal_stabilization_iterations: usize,
+    /// The number of nonlinear position iterations run at the end of each timestep for the impulse
+    /// joints with [`GenericJoint::position_solver_enabled`](crate::dynamics::GenericJoint::position_solver_enabled)
+    /// set (default: `4`).
+    ///
+    /// Each iteration directly moves the bodies attached to these joints to reduce the drift of
+    /// their locked axes, instead of only correcting it through velocities. The velocities of the
+    /// bodies aren’t modified by this correction.
+    pub num_joint_position_iterations: usize,
     /// Minimum number of dynamic bodies in each active island (default: `128`).
     pub min_island_size: usize,
     /// Maximum number of substeps performed by the  solver (default: `1`).
     pub max_ccd_substeps: usize,
+    /// Minimum distance a body clamped by CCD still advances, as a fraction of its CCD thickness
+    /// (default: `0.15`).
+    ///
+    /// This lets bodies already penetrating (or resting against) an obstacle keep moving instead of
+    /// getting stuck at a time-of-impact of zero. Values closer to `1.0` reduce stuttering but
+    /// increase the risk of tunneling through thin obstacles. Setting it to `0.0` clamps the
+    /// motion exactly at the time-of-impact.
+    pub ccd_min_advancement: Real,
+    /// The maximum number of contact points of a contact manifold seen by the constraints solver
+    /// (default: `usize::MAX`).
+    ///
+    /// Contact manifolds with more points are reduced to the deepest point and the points
+    /// maximizing the contact area. Lower values make the solver faster on large flat contacts
+    /// but can make curved or uneven contacts less stable. The default value keeps every point.
+    pub max_contact_points_per_manifold: NonZeroUsize,
+
+    /// The maximum linear velocity any dynamic rigid-body can reach (default: `Real::MAX`).
+    ///
+    /// Linear velocities with a greater magnitude are clamped after integration. This acts as a
+    /// safety net preventing a solver explosion from propagating to the whole simulation. It
+    /// doesn’t apply to multibody links, whose velocities are expressed in joint space.
+    pub max_linear_velocity: Real,
+    /// The maximum angular velocity any dynamic rigid-body can reach (default: `Real::MAX`).
+    ///
+    /// Angular velocities with a greater magnitude are clamped after integration. Like
+    /// [`Self::max_linear_velocity`], it doesn’t apply to multibody links.
+    pub max_angular_velocity: Real,
+    /// The linear damping applied to rigid-bodies that don’t set their own (default: `0.0`).
+    ///
+    /// Damping is applied once per timestep by dividing the velocities by `1 + dt * damping`,
+    /// so the same value decays velocities slightly differently for different timestep lengths.
+    /// Use [`Self::damping_for_dt`] to convert it. See [`RigidBody::inherits_linear_damping`](crate::dynamics::RigidBody::inherits_linear_damping).
+    ///
+    /// Like the damping set on each rigid-body, it doesn’t apply to multibody links: they are
+    /// damped in joint space by [`Multibody::damping_mut`](crate::dynamics::Multibody::damping_mut).
+    pub linear_damping: Real,
+    /// The angular damping applied to rigid-bodies that don’t set their own (default: `0.0`).
+    ///
+    /// See [`RigidBody::inherits_angular_damping`](crate::dynamics::RigidBody::inherits_angular_damping).
+    /// Like [`Self::linear_damping`], it doesn’t apply to multibody links.
+    pub angular_damping: Real,
+    /// Enables the integration of gyroscopic forces (default: `false`).
+    ///
+    /// When enabled, the `ω × (Iω)` term of Euler’s rotation equations is integrated implicitly
+    /// at each timestep. This is what makes bodies with asymmetric inertia tumble realistically
+    /// (e.g. the Dzhanibekov effect) and prevents fast-spinning elongated objects from gaining
+    /// energy. This has no effect in 2D.
+    pub gyroscopic_forces_enabled: bool,
+    /// Enables the culling of the contact pairs too far apart to generate contacts (default: `true`).
+    ///
+    /// When enabled, the narrow-phase compares the bounding spheres of the shapes of each pair
+    /// (and the bounding box of one shape in the local frame of the other) before running the
+    /// exact contact generation. Pairs further apart than their prediction distance, including
+    /// their relative motion with soft-CCD, are skipped. This never skips a pair that would have
+    /// generated contacts, but saves exact contact computations for pairs with overlapping
+    /// AABBs, like long thin objects or fast-moving ones.
+    pub narrow_phase_culling_enabled: bool,
+    /// Makes the simulation independent of the order rigid-bodies, colliders, and joints were
+    /// inserted in (default: `false`).
+    ///
+    /// When enabled, the rigid-bodies of each active island, the contact manifolds, and the
+    /// impulse joints are sorted by the `user_data` of their bodies and colliders before being
+    /// solved. Two worlds built with the same bodies, colliders, and joints then produce
+    /// bitwise-identical trajectories regardless of the insertion order, as long as every
+    /// `user_data` is unique (ties fall back to handles, which depend on the insertion order).
+    /// This costs a sort of each active island every step. Multibody joints aren’t reordered.
+    pub order_by_user_data: bool,
+    /// The linear velocity below which a rigid-body can fall asleep (default: `0.4`).
+    ///
+    /// This value is implicitly scaled by [`IntegrationParameters::length_unit`]. It is
+    /// overridden by the threshold of rigid-bodies with a
+    /// [`RigidBodyActivation::normalized_linear_threshold`](crate::dynamics::RigidBodyActivation::normalized_linear_threshold)
+    /// set. If negative, rigid-bodies never sleep.
+    pub normalized_sleep_linear_threshold: Real,
+    /// The angular velocity below which a rigid-body can fall asleep (default: `0.5`).
+    ///
+    /// It is overridden by the threshold of rigid-bodies with a
+    /// [`RigidBodyActivation::angular_threshold`](crate::dynamics::RigidBodyActivation::angular_threshold)
+    /// set. If negative, rigid-bodies never sleep.
+    pub sleep_angular_threshold: Real,
+    /// The amount of time, in seconds, a rigid-body must remain below the sleep thresholds before
+    /// falling asleep (default: `2.0`).
+    ///
+    /// This is accumulated with the timestep length [`Self::dt`], so it doesn’t depend on the
+    /// simulation frequency. It is overridden by the value of rigid-bodies with a
+    /// [`RigidBodyActivation::time_until_sleep`](crate::dynamics::RigidBodyActivation::time_until_sleep)
+    /// set.
+    pub time_until_sleep: Real,
+    /// The time constant, in seconds, of the exponential moving average smoothing the kinetic
+    /// energy of rigid-bodies before comparing it to the sleep thresholds (default: `0.1`).
+    ///
+    /// The squared linear and angular velocities of a rigid-body are normalized by its sleep
+    /// thresholds (the linear one being scaled by [`Self::length_unit`], the typical body size),
+    /// then averaged over roughly this duration into
+    /// [`RigidBodyActivation::smoothed_energy`]. This keeps bodies jittering around the thresholds
+    /// from alternately counting down to sleep and being reset. Set it to `0.0` to compare the
+    /// instantaneous energy instead.
+    pub sleep_energy_smoothing_time: Real,
+    /// The normalized smoothed energy below which a rigid-body counts down to sleep
+    /// (default: `1.0`).
+    ///
+    /// A body moving exactly at its linear or angular sleep threshold has a normalized energy of
+    /// `1.0`. See [`Self::sleep_energy_smoothing_time`].
+    pub sleep_enter_energy_threshold: Real,
+    /// The normalized smoothed energy above which a rigid-body counting down to sleep starts over
+    /// (default: `2.0`).
+    ///
+    /// Keeping this larger than [`Self::sleep_enter_energy_threshold`] adds hysteresis to the
+    /// sleep criterion: the countdown of a rigid-body whose energy lies between both thresholds
+    /// is paused instead of reset, so small disturbances no longer make it start over. The island
+    /// containing the body only falls asleep once each of its bodies spent a total of
+    /// [`Self::time_until_sleep`] below the enter threshold.
+    pub sleep_exit_energy_threshold: Real,
 }
 
 impl IntegrationParameters {
@@ -140,10 +298,49 @@ impl IntegrationParameters {
         self.contact_natural_frequency * Real::two_pi()
     }
 
+    /// The [`Self::erp_from_natural_frequency`] coefficient, multiplied by the inverse timestep
+    /// length.
+    fn erp_inv_dt_from_natural_frequency(
+        &self,
+        natural_frequency: Real,
+        damping_ratio: Real,
+    ) -> Real {
+        let ang_freq = natural_frequency * Real::two_pi();
+        ang_freq / (self.dt * ang_freq + 2.0 * damping_ratio)
+    }
+
+    /// The Error Reduction Parameter of a spring with the given natural frequency (in Hz) and
+    /// damping ratio, for a timestep of length [`Self::dt`].
+    ///
+    /// Springs expressed with a natural frequency and a damping ratio are independent of the
+    /// timestep length: this is how contacts and joints are regularized, and this returns the
+    /// per-step coefficient they result in.
+    pub fn erp_from_natural_frequency(&self, natural_frequency: Real, damping_ratio: Real) -> Real {
+        self.dt * self.erp_inv_dt_from_natural_frequency(natural_frequency, damping_ratio)
+    }
+
+    /// The CFM (constraints force mixing) coefficient of a spring with the given natural
+    /// frequency (in Hz) and damping ratio, for a timestep of length [`Self::dt`].
+    ///
+    /// The returned value must be divided by the projected mass of the constraint.
+    pub fn cfm_coeff_from_natural_frequency(
+        &self,
+        natural_frequency: Real,
+        damping_ratio: Real,
+    ) -> Real {
+        // Compute CFM assuming a critically damped spring multiplied by the damping ratio.
+        let inv_erp_minus_one =
+            1.0 / self.erp_from_natural_frequency(natural_frequency, damping_ratio) - 1.0;
+        inv_erp_minus_one * inv_erp_minus_one
+            / ((1.0 + inv_erp_minus_one) * 4.0 * damping_ratio * damping_ratio)
+    }
+
     /// The [`Self::contact_erp`] coefficient, multiplied by the inverse timestep length.
     pub fn contact_erp_inv_dt(&self) -> Real {
-        let ang_freq = self.contact_angular_frequency();
-        ang_freq / (self.dt * ang_freq + 2.0 * self.contact_damping_ratio)
+        self.erp_inv_dt_from_natural_frequency(
+            self.contact_natural_frequency,
+            self.contact_damping_ratio,
+        )
     }
 
     /// The effective Error Reduction Parameter applied for calculating regularization forces
@@ -162,8 +359,10 @@ impl IntegrationParameters {
 
     /// The [`Self::joint_erp`] coefficient, multiplied by the inverse timestep length.
     pub fn joint_erp_inv_dt(&self) -> Real {
-        let ang_freq = self.joint_angular_frequency();
-        ang_freq / (self.dt * ang_freq + 2.0 * self.joint_damping_ratio)
+        self.erp_inv_dt_from_natural_frequency(
+            self.joint_natural_frequency,
+            self.joint_damping_ratio,
+        )
     }
 
     /// The effective Error Reduction Parameter applied for calculating regularization forces
@@ -181,19 +380,17 @@ impl IntegrationParameters {
     /// [`Self::contact_damping_ratio`] and the substep length.
     pub fn contact_cfm_factor(&self) -> Real {
         // Compute CFM assuming a critically damped spring multiplied by the damping ratio.
-        let inv_erp_minus_one = 1.0 / self.contact_erp() - 1.0;
-
+        // With `inv_erp_minus_one = 1.0 / self.contact_erp() - 1.0`:
         // let stiffness = 4.0 * damping_ratio * damping_ratio * projected_mass
         //     / (dt * dt * inv_erp_minus_one * inv_erp_minus_one);
         // let damping = 4.0 * damping_ratio * damping_ratio * projected_mass
         //     / (dt * inv_erp_minus_one);
         // let cfm = 1.0 / (dt * dt * stiffness + dt * damping);
         // NOTE: This simplifies to cfm = cfm_coeff / projected_mass:
-        let cfm_coeff = inv_erp_minus_one * inv_erp_minus_one
-            / ((1.0 + inv_erp_minus_one)
-                * 4.0
-                * self.contact_damping_ratio
-                * self.contact_damping_ratio);
+        let cfm_coeff = self.cfm_coeff_from_natural_frequency(
+            self.contact_natural_frequency,
+            self.contact_damping_ratio,
+        );
 
         // Furthermore, we use this coefficient inside of the impulse resolution.
         // Surprisingly, several simplifications happen there.
@@ -219,14 +416,50 @@ impl IntegrationParameters {
     /// This parameter is computed automatically from [`Self::joint_natural_frequency`],
     /// [`Self::joint_damping_ratio`] and the substep length.
     pub fn joint_cfm_coeff(&self) -> Real {
-        // Compute CFM assuming a critically damped spring multiplied by the damping ratio.
-        // The logic is similar to `Self::cfm_factor`.
-        let inv_erp_minus_one = 1.0 / self.joint_erp() - 1.0;
-        inv_erp_minus_one * inv_erp_minus_one
-            / ((1.0 + inv_erp_minus_one)
-                * 4.0
-                * self.joint_damping_ratio
-                * self.joint_damping_ratio)
+        self.cfm_coeff_from_natural_frequency(
+            self.joint_natural_frequency,
+            self.joint_damping_ratio,
+        )
+    }
+
+    /// Converts a linear or angular damping coefficient tuned for timesteps of length
+    /// [`Self::dt`] into the coefficient decaying velocities by the same amount per second with
+    /// timesteps of length `new_dt`.
+    pub fn damping_for_dt(&self, damping: Real, new_dt: Real) -> Real {
+        if self.dt == 0.0 || new_dt == 0.0 {
+            return damping;
+        }
+
+        ((1.0 + self.dt * damping).powf(new_dt / self.dt) - 1.0) / new_dt
+    }
+
+    /// Derives integration parameters for timesteps of length `new_dt` that simulate as closely
+    /// as possible to these parameters.
+    ///
+    /// Contact and joint regularization, motors, thresholds, and velocities are expressed in
+    /// timestep-independent units and are kept as-is. The number of solver iterations is scaled
+    /// so that the solver substeps keep the same length, the global damping coefficients are
+    /// converted with [`Self::damping_for_dt`], and [`Self::min_ccd_dt`] is scaled with the
+    /// timestep. Per-body damping coefficients and additional solver iterations aren’t part of
+    /// the integration parameters and must be converted separately.
+    pub fn scaled_for_dt(&self, new_dt: Real) -> Self {
+        let ratio = if self.dt == 0.0 {
+            1.0
+        } else {
+            new_dt / self.dt
+        };
+        let num_solver_iterations =
+            (self.num_solver_iterations.get() as Real * ratio).round() as usize;
+
+        Self {
+            dt: new_dt,
+            min_ccd_dt: self.min_ccd_dt * ratio,
+            num_solver_iterations: NonZeroUsize::new(num_solver_iterations)
+                .unwrap_or(NonZeroUsize::MIN),
+            linear_damping: self.damping_for_dt(self.linear_damping, new_dt),
+            angular_damping: self.damping_for_dt(self.angular_damping, new_dt),
+            ..*self
+        }
     }
 
     /// Amount of penetration the engine won’t attempt to correct (default: `0.001` multiplied by
@@ -235,7 +468,7 @@ impl IntegrationParameters {
         self.normalized_allowed_linear_error * self.length_unit
     }
 
-    /// Maximum amount of penetration the solver will attempt to resolve in one timestep.
+    /// Maximum velocity the solver will use to push penetrating objects apart.
     ///
     /// This is equal to [`Self::normalized_max_corrective_velocity`] multiplied by
     /// [`Self::length_unit`].
@@ -253,6 +486,25 @@ impl IntegrationParameters {
         self.normalized_prediction_distance * self.length_unit
     }
 
+    /// Relative displacement of two colliders at a contact point, caused by teleporting one of
+    /// them, beyond which the contact point is no longer warmstarted (default: `0.002m`
+    /// multiplied by [`Self::length_unit`]).
+    pub fn warmstart_max_teleport_displacement(&self) -> Real {
+        self.normalized_warmstart_max_teleport_displacement * self.length_unit
+    }
+
+    /// Distance within which a new contact point inherits the impulses of a previous contact
+    /// point with different features (default: `0.01m` multiplied by [`Self::length_unit`]).
+    pub fn contact_matching_tolerance(&self) -> Real {
+        self.normalized_contact_matching_tolerance * self.length_unit
+    }
+
+    /// The relative normal velocity below which contacts don’t bounce (default: `1.0m/s`
+    /// multiplied by [`Self::length_unit`]).
+    pub fn restitution_velocity_threshold(&self) -> Real {
+        self.normalized_restitution_velocity_threshold * self.length_unit
+    }
+
     /// Initialize the simulation parameters with settings matching the TGS-soft solver
     /// with warmstarting.
     ///
@@ -266,8 +518,12 @@ impl IntegrationParameters {
             joint_natural_frequency: 1.0e6,
             joint_damping_ratio: 1.0,
             warmstart_coefficient: 1.0,
+            warmstart_min_contact_age: 1,
+            normalized_warmstart_max_teleport_displacement: 0.002,
+            normalized_contact_matching_tolerance: 0.01,
             num_internal_pgs_iterations: 1,
             num_internal_stabilization_iterations: 2,
+            num_joint_position_iterations: 4,
             num_additional_friction_iterations: 0,
             num_solver_iterations: NonZeroUsize::new(4).unwrap(),
             // TODO: what is the optimal value for min_island_size?
@@ -279,8 +535,25 @@ impl IntegrationParameters {
             normalized_allowed_linear_error: 0.001,
             normalized_max_corrective_velocity: 10.0,
             normalized_prediction_distance: 0.002,
+            normalized_restitution_velocity_threshold: 1.0,
             max_ccd_substeps: 1,
+            ccd_min_advancement: 0.15,
+            max_contact_points_per_manifold: NonZeroUsize::MAX,
             length_unit: 1.0,
+            max_linear_velocity: Real::MAX,
+            max_angular_velocity: Real::MAX,
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            gyroscopic_forces_enabled: false,
+            narrow_phase_culling_enabled: true,
+            order_by_user_data: false,
+            normalized_sleep_linear_threshold:
+                RigidBodyActivation::default_normalized_linear_threshold(),
+            sleep_angular_threshold: RigidBodyActivation::default_angular_threshold(),
+            time_until_sleep: RigidBodyActivation::default_time_until_sleep(),
+            sleep_energy_smoothing_time: 0.1,
+            sleep_enter_energy_threshold: 1.0,
+            sleep_exit_energy_threshold: 2.0,
         }
     }
 
@@ -1,10 +1,12 @@
 use crate::dynamics::{
-    ImpulseJointSet, MultibodyJointSet, RigidBodyActivation, RigidBodyChanges, RigidBodyColliders,
-    RigidBodyHandle, RigidBodyIds, RigidBodySet, RigidBodyType, RigidBodyVelocity,
+    ImpulseJointSet, IntegrationParameters, MultibodyJointSet, RigidBodyActivation,
+    RigidBodyChanges, RigidBodyColliders, RigidBodyHandle, RigidBodyIds, RigidBodySet,
+    RigidBodyType, RigidBodyVelocity,
 };
-use crate::geometry::{ColliderSet, NarrowPhase};
-use crate::math::Real;
+use crate::geometry::{ColliderHandle, ColliderSet, NarrowPhase};
+use crate::math::{Real, Vector};
 use crate::utils::SimdDot;
+use parry::utils::hashmap::HashMap;
 
 /// Structure responsible for maintaining the set of active rigid-bodies, and
 /// putting non-moving rigid-bodies to sleep to save computation times.
@@ -15,6 +17,7 @@ pub struct IslandManager {
     pub(crate) active_kinematic_set: Vec<RigidBodyHandle>,
     pub(crate) active_islands: Vec<usize>,
     pub(crate) active_islands_additional_solver_iterations: Vec<usize>,
+    num_connected_islands: usize,
     active_set_timestamp: u32,
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
     can_sleep: Vec<RigidBodyHandle>, // Workspace.
@@ -30,16 +33,43 @@ impl IslandManager {
             active_kinematic_set: vec![],
             active_islands: vec![],
             active_islands_additional_solver_iterations: vec![],
+            num_connected_islands: 0,
             active_set_timestamp: 0,
             can_sleep: vec![],
             stack: vec![],
         }
     }
 
+    /// Removes every rigid-body from the active sets and islands, but keeps the allocated memory.
+    ///
+    /// This is meant for emptying a whole world in-place, together with [`RigidBodySet::clear`].
+    pub fn clear(&mut self) {
+        self.active_dynamic_set.clear();
+        self.active_kinematic_set.clear();
+        self.active_islands.clear();
+        self.active_islands_additional_solver_iterations.clear();
+        self.num_connected_islands = 0;
+        self.can_sleep.clear();
+        self.stack.clear();
+    }
+
     pub(crate) fn num_islands(&self) -> usize {
         self.active_islands.len() - 1
     }
 
+    /// The number of groups of awake dynamic rigid-bodies connected by contacts or joints, as
+    /// computed at the beginning of the last timestep.
+    ///
+    /// The connectivity is recomputed from the current contacts and joints at each timestep, so
+    /// a structure splitting into disconnected parts (e.g. after its joints were removed or
+    /// disabled) is counted as several islands at the next timestep, and each part can fall
+    /// asleep independently. Unlike the islands solved by the constraints solver, these aren’t
+    /// merged together when they have less than
+    /// [`IntegrationParameters::min_island_size`] rigid-bodies.
+    pub fn num_connected_islands(&self) -> usize {
+        self.num_connected_islands
+    }
+
     /// Update this data-structure after one or multiple rigid-bodies have been removed for `bodies`.
     pub fn cleanup_removed_rigid_bodies(&mut self, bodies: &mut RigidBodySet) {
         let mut active_sets = [&mut self.active_kinematic_set, &mut self.active_dynamic_set];
@@ -114,6 +144,196 @@ impl IslandManager {
         }
     }
 
+    /// Forces the island containing the given dynamic rigid-body to sleep.
+    ///
+    /// Islands are recomputed at each timestep so they are identified by any of their rigid-bodies.
+    /// Here, the island contains every dynamic rigid-body transitively attached to `handle` by
+    /// a joint or by a contact pair with at least one active contact.
+    ///
+    /// Unlike calling [`RigidBody::sleep`](crate::dynamics::RigidBody::sleep) on each of them,
+    /// this first runs one position correction pass over the island’s contacts to remove any
+    /// penetration deeper than [`IntegrationParameters::allowed_linear_error`], so the island
+    /// is not left in a violated state that would be resolved abruptly once it wakes up. The
+    /// island then wakes up as usual, e.g., when another body starts touching it.
+    ///
+    /// Returns the handles of the rigid-bodies put to sleep. This is empty if `handle` doesn’t
+    /// identify a dynamic rigid-body.
+    pub fn force_sleep(
+        &mut self,
+        params: &IntegrationParameters,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+        narrow_phase: &NarrowPhase,
+        impulse_joints: &ImpulseJointSet,
+        multibody_joints: &MultibodyJointSet,
+        handle: RigidBodyHandle,
+    ) -> Vec<RigidBodyHandle> {
+        let mut island = vec![];
+
+        if bodies.get(handle).map(|rb| rb.is_dynamic()) != Some(true) {
+            return island;
+        }
+
+        // Collect the island with a breadth-first traversal that doesn’t go through
+        // non-dynamic bodies.
+        let mut island_ids = HashMap::default();
+        island_ids.insert(handle, 0);
+        island.push(handle);
+        let mut next = 0;
+
+        while let Some(curr) = island.get(next).copied() {
+            next += 1;
+
+            let neighbors = narrow_phase
+                .bodies_interacting_with(curr, bodies, colliders, true)
+                .chain(
+                    impulse_joints
+                        .attached_enabled_joints(curr)
+                        .map(|inter| crate::utils::select_other((inter.0, inter.1), curr)),
+                )
+                .chain(multibody_joints.bodies_attached_with_enabled_joint(curr));
+
+            for other in neighbors {
+                if bodies[other].is_dynamic() && !island_ids.contains_key(&other) {
+                    island_ids.insert(other, island.len());
+                    island.push(other);
+                }
+            }
+        }
+
+        // One position solver pass over the deepest point of each contact manifold, projecting
+        // out any penetration beyond the allowed error. The penetration depths are linearized
+        // along the contact normals: only translations are applied.
+        struct PenetrationConstraint {
+            ids: [Option<usize>; 2],
+            normal: Vector<Real>,
+            dist: Real,
+        }
+
+        let island_id = |collider: ColliderHandle| {
+            colliders[collider]
+                .parent()
+                .and_then(|parent| island_ids.get(&parent).copied())
+        };
+        let mut constraints = vec![];
+        let mut body_constraints = vec![vec![]; island.len()];
+
+        for body in &island {
+            for collider in bodies[*body].colliders() {
+                for pair in narrow_phase.contact_pairs_with(*collider) {
+                    let ids = [island_id(pair.collider1), island_id(pair.collider2)];
+
+                    // Pairs between two bodies of the island are seen twice.
+                    if ids[0].is_some() && ids[1].is_some() && *collider != pair.collider1 {
+                        continue;
+                    }
+
+                    for manifold in &pair.manifolds {
+                        let deepest = manifold
+                            .data
+                            .solver_contacts
+                            .iter()
+                            .map(|contact| contact.dist)
+                            .min_by(|a, b| a.total_cmp(b));
+
+                        if let Some(dist) = deepest {
+                            for id in ids.iter().flatten() {
+                                body_constraints[*id].push(constraints.len());
+                            }
+
+                            constraints.push(PenetrationConstraint {
+                                ids,
+                                normal: manifold.data.normal,
+                                dist,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        // Shock propagation: a body with fewer contacts between it and a non-dynamic body is
+        // treated as immovable when pushing away the bodies further from it. A single pass,
+        // ordered by that distance, then settles a pile from the bottom up.
+        let mut depths = vec![usize::MAX; island.len()];
+        let mut queue = vec![];
+
+        for constraint in &constraints {
+            if let [Some(id), None] | [None, Some(id)] = constraint.ids {
+                if depths[id] != 0 {
+                    depths[id] = 0;
+                    queue.push(id);
+                }
+            }
+        }
+
+        let mut next = 0;
+        while let Some(curr) = queue.get(next).copied() {
+            next += 1;
+
+            for constraint_id in &body_constraints[curr] {
+                for id in constraints[*constraint_id].ids.iter().flatten() {
+                    if depths[*id] == usize::MAX {
+                        depths[*id] = depths[curr] + 1;
+                        queue.push(*id);
+                    }
+                }
+            }
+        }
+
+        let depth = |id: Option<usize>| id.map(|id| depths[id]);
+        constraints.sort_by_key(|constraint| {
+            let [depth1, depth2] = constraint.ids.map(depth);
+            depth1.min(depth2)
+        });
+
+        let allowed_error = params.allowed_linear_error();
+        let mut shifts = vec![Vector::zeros(); island.len()];
+
+        for constraint in &constraints {
+            let [id1, id2] = constraint.ids;
+            let movable = |id: Option<usize>, other: Option<usize>| {
+                id.filter(|id| depth(other).is_none_or(|d| depths[*id] >= d))
+            };
+            let [mov1, mov2] = [movable(id1, id2), movable(id2, id1)];
+            let inv_mass = |id: Option<usize>| {
+                id.map(|id| bodies[island[id]].mass_properties().effective_inv_mass)
+                    .unwrap_or_else(Vector::zeros)
+            };
+            let shift = |id: Option<usize>| id.map(|id| shifts[id]).unwrap_or_else(Vector::zeros);
+
+            let normal = constraint.normal;
+            let dist = constraint.dist + normal.dot(&(shift(id2) - shift(id1)));
+            let dir1 = inv_mass(mov1).component_mul(&normal);
+            let dir2 = inv_mass(mov2).component_mul(&normal);
+            let lhs = normal.dot(&(dir1 + dir2));
+
+            if dist < -allowed_error && lhs > 0.0 {
+                let correction = (-allowed_error - dist) / lhs;
+
+                if let Some(id1) = mov1 {
+                    shifts[id1] -= dir1 * correction;
+                }
+                if let Some(id2) = mov2 {
+                    shifts[id2] += dir2 * correction;
+                }
+            }
+        }
+
+        for (body, shift) in island.iter().zip(shifts.iter()) {
+            if let Some(rb) = bodies.get_mut_internal_with_modification_tracking(*body) {
+                if *shift != Vector::zeros() {
+                    let translation = rb.translation() + shift;
+                    rb.set_translation(translation, false);
+                }
+
+                rb.sleep();
+            }
+        }
+
+        island
+    }
+
     /// Iter through all the active kinematic rigid-bodies on this set.
     pub fn active_kinematic_bodies(&self) -> &[RigidBodyHandle] {
         &self.active_kinematic_set[..]
@@ -129,6 +349,25 @@ impl IslandManager {
         &self.active_dynamic_set[island_range]
     }
 
+    /// Sorts the rigid-bodies of each active island by `user_data`, then by handle.
+    ///
+    /// See [`IntegrationParameters::order_by_user_data`].
+    pub(crate) fn sort_active_islands_by_user_data(&mut self, bodies: &mut RigidBodySet) {
+        for island_id in 0..self.num_islands() {
+            let start = self.active_islands[island_id];
+            let island = &mut self.active_dynamic_set[start..self.active_islands[island_id + 1]];
+            island.sort_unstable_by_key(|handle| {
+                (bodies[*handle].user_data, handle.into_raw_parts())
+            });
+
+            for (offset, handle) in island.iter().enumerate() {
+                let rb = bodies.index_mut_internal(*handle);
+                rb.ids.active_set_id = start + offset;
+                rb.ids.active_set_offset = offset;
+            }
+        }
+    }
+
     pub(crate) fn active_island_additional_solver_iterations(&self, island_id: usize) -> usize {
         self.active_islands_additional_solver_iterations[island_id]
     }
@@ -149,15 +388,14 @@ impl IslandManager {
 
     pub(crate) fn update_active_set_with_contacts(
         &mut self,
-        dt: Real,
-        length_unit: Real,
+        params: &IntegrationParameters,
         bodies: &mut RigidBodySet,
         colliders: &ColliderSet,
         narrow_phase: &NarrowPhase,
         impulse_joints: &ImpulseJointSet,
         multibody_joints: &MultibodyJointSet,
-        min_island_size: usize,
     ) {
+        let min_island_size = params.min_island_size;
         assert!(
             min_island_size > 0,
             "The minimum island size must be at least 1."
@@ -182,9 +420,11 @@ impl IslandManager {
             let sq_linvel = rb.vels.linvel.norm_squared();
             let sq_angvel = rb.vels.angvel.gdot(rb.vels.angvel);
 
-            update_energy(length_unit, &mut rb.activation, sq_linvel, sq_angvel, dt);
+            update_energy(params, &mut rb.activation, sq_linvel, sq_angvel);
 
-            if rb.activation.time_since_can_sleep >= rb.activation.time_until_sleep {
+            if rb.activation.time_since_can_sleep
+                >= rb.activation.effective_time_until_sleep(params)
+            {
                 // Mark them as sleeping for now. This will
                 // be set to false during the graph traversal
                 // if it should not be put to sleep.
@@ -221,14 +461,57 @@ impl IslandManager {
             }
         }
 
+        // Read all the contacts and push objects penetrating this rigid-body deeper than
+        // the allowed error.
+        #[inline(always)]
+        fn push_penetrating_bodies(
+            rb_colliders: &RigidBodyColliders,
+            colliders: &ColliderSet,
+            narrow_phase: &NarrowPhase,
+            allowed_error: Real,
+            stack: &mut Vec<RigidBodyHandle>,
+        ) {
+            for collider_handle in &rb_colliders.0 {
+                for inter in narrow_phase.contact_pairs_with(*collider_handle) {
+                    let penetrates = inter.manifolds.iter().any(|manifold| {
+                        manifold
+                            .data
+                            .solver_contacts
+                            .iter()
+                            .any(|contact| contact.dist < -allowed_error)
+                    });
+
+                    if penetrates {
+                        let other = crate::utils::select_other(
+                            (inter.collider1, inter.collider2),
+                            *collider_handle,
+                        );
+                        if let Some(other_body) = colliders[other].parent {
+                            stack.push(other_body.handle);
+                        }
+                    }
+                }
+            }
+        }
+
         // Now iterate on all active kinematic bodies and push all the bodies
         // touching them to the stack so they can be woken up.
         for h in self.active_kinematic_set.iter() {
             let rb = &bodies[*h];
 
             if rb.vels.is_zero() {
-                // If the kinematic body does not move, it does not have
-                // to wake up any dynamic body.
+                // If the kinematic body does not move, it does not have to wake up
+                // the dynamic bodies merely touching it. It may still have been
+                // teleported into them though, so wake up the ones it penetrates
+                // beyond the allowed error instead of letting it sink into a
+                // sleeping island.
+                push_penetrating_bodies(
+                    &rb.colliders,
+                    colliders,
+                    narrow_phase,
+                    params.allowed_linear_error(),
+                    &mut self.stack,
+                );
                 continue;
             }
 
@@ -254,6 +537,7 @@ impl IslandManager {
         //       iterations on top of the stack (and other bodies on the back). Not sure it’s
         //       worth it though.
         let mut additional_solver_iterations = 0;
+        self.num_connected_islands = 0;
 
         while let Some(handle) = self.stack.pop() {
             let rb = bodies.index_mut_internal(handle);
@@ -264,6 +548,11 @@ impl IslandManager {
                 continue;
             }
 
+            if self.active_dynamic_set.is_empty() || self.stack.len() < island_marker {
+                // We are starting the traversal of a new connected component.
+                self.num_connected_islands += 1;
+            }
+
             if self.stack.len() < island_marker {
                 if additional_solver_iterations != rb.additional_solver_iterations
                     || self.active_dynamic_set.len() - *self.active_islands.last().unwrap()
@@ -319,6 +608,7 @@ impl IslandManager {
             let rb = bodies.index_mut_internal(*handle);
             if rb.activation.sleeping {
                 rb.vels = RigidBodyVelocity::zero();
+                rb.vels_before_solve = RigidBodyVelocity::zero();
                 rb.activation.sleep();
             }
         }
@@ -326,18 +616,386 @@ impl IslandManager {
 }
 
 fn update_energy(
-    length_unit: Real,
+    params: &IntegrationParameters,
     activation: &mut RigidBodyActivation,
     sq_linvel: Real,
     sq_angvel: Real,
-    dt: Real,
 ) {
-    let linear_threshold = activation.normalized_linear_threshold * length_unit;
-    if sq_linvel < linear_threshold * linear_threshold.abs()
-        && sq_angvel < activation.angular_threshold * activation.angular_threshold.abs()
-    {
-        activation.time_since_can_sleep += dt;
+    let linear_threshold =
+        activation.effective_normalized_linear_threshold(params) * params.length_unit;
+    let angular_threshold = activation.effective_angular_threshold(params);
+
+    // Non-positive thresholds prevent the body from sleeping.
+    if linear_threshold <= 0.0 || angular_threshold <= 0.0 {
+        activation.time_since_can_sleep = 0.0;
+        return;
+    }
+
+    let energy = sq_linvel / (linear_threshold * linear_threshold)
+        + sq_angvel / (angular_threshold * angular_threshold);
+    let smoothing = if params.sleep_energy_smoothing_time > 0.0 {
+        1.0 - (-params.dt / params.sleep_energy_smoothing_time).exp()
     } else {
+        1.0
+    };
+    activation.smoothed_energy += (energy - activation.smoothed_energy) * smoothing;
+
+    // Hysteresis: the countdown to sleep only progresses below the enter threshold, but only
+    // starts over above the exit threshold.
+    if activation.smoothed_energy < params.sleep_enter_energy_threshold {
+        activation.time_since_can_sleep += params.dt;
+    } else if activation.smoothed_energy >= params.sleep_exit_energy_threshold {
         activation.time_since_can_sleep = 0.0;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RigidBodyBuilder, RigidBodyHandle};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::PhysicsWorld;
+
+    /// A stack of cuboids spawned slightly overlapping, and simulated for a single step so it
+    /// is still far from resting.
+    fn overlapping_box_stack(len: usize) -> (PhysicsWorld, Vec<RigidBodyHandle>) {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        let ground_body =
+            world.insert_body(RigidBodyBuilder::fixed().translation(Vector::y() * -0.5));
+        world.insert_collider_with_parent(ground, ground_body);
+
+        let stack: Vec<_> = (0..len)
+            .map(|i| {
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic().translation(Vector::y() * (0.45 + i as Real * 0.9)),
+                );
+                world.insert_collider_with_parent(cuboid.clone(), body);
+                body
+            })
+            .collect();
+
+        world.step();
+        (world, stack)
+    }
+
+    /// Drops a ball on top of the stack and returns the largest upward velocity reached by the
+    /// bodies of the stack.
+    fn drop_ball_on_stack(world: &mut PhysicsWorld, stack: &[RigidBodyHandle]) -> Real {
+        let top = world.bodies()[*stack.last().unwrap()].translation().y;
+        let ball =
+            world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * (top + 1.5)));
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.25), ball);
+
+        let mut max_upward_vel: Real = 0.0;
+        for _ in 0..60 {
+            world.step();
+            for body in stack {
+                max_upward_vel = max_upward_vel.max(world.bodies()[*body].linvel().y);
+            }
+        }
+        max_upward_vel
+    }
+
+    #[test]
+    fn force_slept_stack_does_not_pop_on_wake() {
+        let (mut naive, stack) = overlapping_box_stack(5);
+        for body in &stack {
+            naive.bodies[*body].sleep();
+        }
+        let naive_upward_vel = drop_ball_on_stack(&mut naive, &stack);
+        // Just zeroing the velocities keeps the penetrations, resolved violently on wake.
+        assert!(naive_upward_vel > 1.0, "{naive_upward_vel}");
+
+        let (mut world, stack) = overlapping_box_stack(5);
+        let slept = world.force_sleep(stack[2]);
+        assert_eq!(slept.len(), stack.len());
+        assert!(stack.iter().all(|body| slept.contains(body)));
+
+        // The island stays asleep, at its settled position.
+        let positions: Vec<_> = stack
+            .iter()
+            .map(|body| *world.bodies()[*body].translation())
+            .collect();
+        for _ in 0..10 {
+            world.step();
+        }
+        for (body, pos) in stack.iter().zip(positions.iter()) {
+            assert!(world.bodies()[*body].is_sleeping());
+            assert_eq!(world.bodies()[*body].translation(), pos);
+        }
+
+        let upward_vel = drop_ball_on_stack(&mut world, &stack);
+        assert!(stack
+            .iter()
+            .all(|body| !world.bodies()[*body].is_sleeping()));
+        assert!(upward_vel < 0.5, "{upward_vel}");
+    }
+
+    #[test]
+    #[cfg(feature = "dim2")]
+    fn convex_polygon_stack_falls_asleep() {
+        let mut world = PhysicsWorld::default();
+        world.insert_collider(ColliderBuilder::cuboid(10.0, 0.5).translation(Vector::y() * -0.5));
+
+        // A pentagon with flat top and bottom, given counter-clockwise.
+        let pentagon = vec![
+            Point::new(-1.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(1.0, 0.6),
+            Point::new(0.4, 1.0),
+            Point::new(-1.0, 1.0),
+        ];
+        // A triangle given in clockwise order with an extra interior point, both fixed by
+        // the convex-hull computation, and with a rounded border.
+        let triangle = [
+            Point::new(-0.5, 0.0),
+            Point::new(0.0, 0.7),
+            Point::new(0.0, 0.2),
+            Point::new(0.5, 0.0),
+        ];
+
+        let pentagon_body = world.insert_body(RigidBodyBuilder::dynamic());
+        world.insert_collider_with_parent(
+            ColliderBuilder::convex_polyline(pentagon).unwrap(),
+            pentagon_body,
+        );
+        let triangle_body =
+            world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::new(-0.3, 1.05)));
+        world.insert_collider_with_parent(
+            ColliderBuilder::round_convex_hull(&triangle, 0.02).unwrap(),
+            triangle_body,
+        );
+
+        let asleep = (0..300).find(|_| {
+            world.step();
+            world.bodies()[pentagon_body].is_sleeping()
+                && world.bodies()[triangle_body].is_sleeping()
+        });
+        assert!(asleep.is_some());
+
+        // The triangle is still resting on the pentagon.
+        let triangle_pos = world.bodies()[triangle_body].translation();
+        approx::assert_relative_eq!(triangle_pos.y, 1.02, epsilon = 0.01);
+        assert!(triangle_pos.x.abs() < 0.5);
+    }
+
+    #[test]
+    fn removing_a_joint_wakes_up_the_whole_sleeping_chain() {
+        #[cfg(feature = "dim2")]
+        use crate::dynamics::RevoluteJointBuilder;
+        #[cfg(feature = "dim3")]
+        use crate::dynamics::SphericalJointBuilder;
+
+        for multibody in [false, true] {
+            let mut world = PhysicsWorld::default();
+            let anchor =
+                world.insert_body(RigidBodyBuilder::fixed().translation(Vector::y() * 10.0));
+            let mut links = vec![];
+            let mut top_joint = None;
+            let mut parent = anchor;
+
+            for i in 1..=5 {
+                let link = world.insert_body(
+                    RigidBodyBuilder::dynamic().translation(Vector::y() * (10.0 - i as Real)),
+                );
+                let _ = world.insert_collider_with_parent(ColliderBuilder::ball(0.2), link);
+                #[cfg(feature = "dim2")]
+                let joint = RevoluteJointBuilder::new();
+                #[cfg(feature = "dim3")]
+                let joint = SphericalJointBuilder::new();
+                let joint = joint.local_anchor2(Point::from(Vector::y()));
+
+                if multibody {
+                    let handle = world.insert_multibody_joint(parent, link, joint).unwrap();
+                    top_joint = top_joint.or(Some(Err(handle)));
+                } else {
+                    let handle = world.insert_impulse_joint(parent, link, joint);
+                    top_joint = top_joint.or(Some(Ok(handle)));
+                }
+
+                links.push(link);
+                parent = link;
+            }
+
+            for _ in 0..10 {
+                world.step();
+            }
+            let _ = world.force_sleep(links[0]);
+            world.step();
+            assert!(links.iter().all(|link| world.bodies[*link].is_sleeping()));
+
+            // Removing the joint the chain hangs from makes every link fall at the next step.
+            match top_joint.unwrap() {
+                Ok(handle) => {
+                    let _ = world.remove_impulse_joint(handle);
+                }
+                Err(handle) => {
+                    let _ = world.remove_multibody_joint(handle);
+                }
+            }
+            world.step();
+
+            for link in &links {
+                let rb = &world.bodies[*link];
+                assert!(!rb.is_sleeping(), "multibody: {multibody}");
+                assert!(rb.linvel().y < -0.1, "multibody: {multibody}");
+            }
+        }
+    }
+
+    #[test]
+    fn teleported_press_wakes_sleeping_stack_before_sinking_into_it() {
+        #[cfg(feature = "dim2")]
+        let (ground, block, press) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+            ColliderBuilder::cuboid(1.0, 0.25),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, block, press) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+            ColliderBuilder::cuboid(1.0, 0.25, 1.0),
+        );
+
+        let mut world = PhysicsWorld::default();
+        world.insert_collider(ground.translation(Vector::y() * -0.5));
+        let stack: Vec<_> = (0..3)
+            .map(|i| {
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic().translation(Vector::y() * (i as Real + 0.5)),
+                );
+                world.insert_collider_with_parent(block.clone(), body);
+                body
+            })
+            .collect();
+
+        let asleep = (0..600).any(|_| {
+            world.step();
+            stack.iter().all(|body| world.bodies()[*body].is_sleeping())
+        });
+        assert!(asleep);
+
+        // Lower the press slowly by teleporting it, so its velocity stays zero and it
+        // never wakes up the stack by merely touching it.
+        let top = stack[2];
+        let press_height = 3.0 + 0.25 + 0.01;
+        let press_body = world.insert_body(
+            RigidBodyBuilder::kinematic_position_based().translation(Vector::y() * press_height),
+        );
+        world.insert_collider_with_parent(press, press_body);
+
+        let penetration = |world: &PhysicsWorld| {
+            world.bodies()[top].translation().y + 0.5
+                - (world.bodies()[press_body].translation().y - 0.25)
+        };
+        let lowering_step = 0.0002;
+        let woke_up = (1..200).find(|i| {
+            let y = press_height - *i as Real * lowering_step;
+            world.bodies[press_body].set_translation(Vector::y() * y, true);
+            world.step();
+            stack
+                .iter()
+                .all(|body| !world.bodies()[*body].is_sleeping())
+        });
+
+        assert!(woke_up.is_some());
+        let allowed_error = world.integration_parameters.allowed_linear_error();
+        let penetration_at_wake = penetration(&world);
+        assert!(
+            penetration_at_wake < allowed_error + 2.0 * lowering_step,
+            "{penetration_at_wake}"
+        );
+
+        // Once awake, the stack is compressed by the press without being launched.
+        for _ in 0..60 {
+            world.step();
+            assert!(penetration(&world) < 0.01, "{}", penetration(&world));
+            assert!(world.bodies()[top].linvel().norm() < 0.5);
+        }
+    }
+
+    #[test]
+    fn broken_bridge_splits_into_islands_sleeping_independently() {
+        use crate::dynamics::RevoluteJointBuilder;
+
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let (ground, plank) = (
+            ColliderBuilder::cuboid(20.0, 0.5),
+            ColliderBuilder::cuboid(0.45, 0.1),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, plank) = (
+            ColliderBuilder::cuboid(20.0, 0.5, 20.0),
+            ColliderBuilder::cuboid(0.45, 0.1, 0.5),
+        );
+        world.insert_collider(ground.translation(-Vector::y() * 0.5));
+
+        // A bridge of 20 planks lying on the ground, each jointed to the next one. The planks
+        // don’t touch each other so they are only connected through the joints.
+        let planks: Vec<_> = (0..20)
+            .map(|i| {
+                let position = Vector::x() * (i as Real - 10.0) + Vector::y() * 0.1;
+                let body = world.insert_body(RigidBodyBuilder::dynamic().translation(position));
+                world.insert_collider_with_parent(plank.clone(), body);
+                body
+            })
+            .collect();
+        let joints: Vec<_> = planks
+            .windows(2)
+            .map(|pair| {
+                #[cfg(feature = "dim2")]
+                let joint = RevoluteJointBuilder::new();
+                #[cfg(feature = "dim3")]
+                let joint = RevoluteJointBuilder::new(Vector::z_axis());
+                let joint = joint
+                    .local_anchor1(Point::from(Vector::x() * 0.5))
+                    .local_anchor2(Point::from(-Vector::x() * 0.5));
+                world.insert_impulse_joint(pair[0], pair[1], joint)
+            })
+            .collect();
+
+        // Keep disturbing the first plank.
+        let disturb = |world: &mut PhysicsWorld, i: usize| {
+            if i.is_multiple_of(30) {
+                world.bodies[planks[0]].apply_impulse(Vector::y() * 0.2, true);
+            }
+        };
+
+        for i in 0..30 {
+            disturb(&mut world, i);
+            world.step();
+            assert_eq!(world.physics_pipeline.counters.solver.nconnected_islands, 1);
+        }
+
+        // Snap the bridge in half.
+        let _ = world.remove_impulse_joint(joints[9]);
+        disturb(&mut world, 30);
+        world.step();
+        assert_eq!(world.islands.num_connected_islands(), 2);
+        assert_eq!(world.physics_pipeline.counters.solver.nconnected_islands, 2);
+
+        for i in 31..600 {
+            disturb(&mut world, i);
+            world.step();
+        }
+
+        // The far half fell asleep while the near half is still being disturbed.
+        assert!(planks[10..]
+            .iter()
+            .all(|plank| world.bodies[*plank].is_sleeping()));
+        assert!(!world.bodies[planks[0]].is_sleeping());
+        assert_eq!(world.physics_pipeline.counters.solver.nconnected_islands, 1);
+    }
+}
@@ -35,6 +35,21 @@ impl FixedJoint {
         self
     }
 
+    /// Is the drift of this joint also corrected at the position level?
+    ///
+    /// See [`GenericJoint::position_solver_enabled`].
+    pub fn position_solver_enabled(&self) -> bool {
+        self.data.position_solver_enabled
+    }
+
+    /// Sets whether the drift of this joint is also corrected at the position level.
+    ///
+    /// See [`GenericJoint::position_solver_enabled`].
+    pub fn set_position_solver_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.data.set_position_solver_enabled(enabled);
+        self
+    }
+
     /// The joint’s frame, expressed in the first rigid-body’s local-space.
     #[must_use]
     pub fn local_frame1(&self) -> &Isometry<Real> {
@@ -108,6 +123,15 @@ impl FixedJointBuilder {
         self
     }
 
+    /// Sets whether the drift of this joint is also corrected at the position level.
+    ///
+    /// See [`GenericJoint::position_solver_enabled`].
+    #[must_use]
+    pub fn position_solver_enabled(mut self, enabled: bool) -> Self {
+        self.0.set_position_solver_enabled(enabled);
+        self
+    }
+
     /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
     #[must_use]
     pub fn local_frame1(mut self, local_frame: Isometry<Real>) -> Self {
@@ -1,8 +1,11 @@
 #![allow(clippy::bad_bit_mask)] // Clippy will complain about the bitmasks due to JointAxesMask::FREE_FIXED_AXES being 0.
 
 use crate::dynamics::solver::MotorParameters;
-use crate::dynamics::{FixedJoint, MotorModel, PrismaticJoint, RevoluteJoint, RopeJoint};
-use crate::math::{Isometry, Point, Real, Rotation, UnitVector, Vector, SPATIAL_DIM};
+use crate::dynamics::{
+    FixedJoint, IntegrationParameters, MotorModel, OrientationLockJoint, PrismaticJoint,
+    RevoluteJoint, RopeJoint,
+};
+use crate::math::{Isometry, Point, Real, Rotation, UnitVector, Vector, DIM, SPATIAL_DIM};
 use crate::utils::{SimdBasis, SimdRealCopy};
 
 #[cfg(feature = "dim3")]
@@ -121,6 +124,40 @@ pub struct JointLimits<N> {
     pub max: N,
     /// The impulse applied to enforce the joint’s limit.
     pub impulse: N,
+    /// The restitution coefficient of the limit stops.
+    ///
+    /// When the joint hits one of its limits faster than the restitution velocity threshold,
+    /// it bounces back with its approach velocity multiplied by this coefficient, like a
+    /// contact with the same restitution. Zero by default: the joint stops dead.
+    ///
+    /// This only affects uncoupled limits (e.g. the limits of revolute and prismatic joints)
+    /// of impulse joints.
+    pub restitution: N,
+    /// The approach velocity below which the limit stops don’t bounce, overriding the default.
+    ///
+    /// If `None`, this is [`IntegrationParameters::restitution_velocity_threshold`] for linear
+    /// limits and [`IntegrationParameters::normalized_restitution_velocity_threshold`] radians
+    /// per second for angular limits.
+    pub restitution_velocity_threshold: Option<N>,
+    /// The Error Reduction Parameter of the limit stops, overriding [`GenericJoint::erp`].
+    ///
+    /// Lower values push the joint back inside of its limits more slowly. This only affects
+    /// impulse joints.
+    pub erp: Option<N>,
+    /// The Constraint Force Mixing coefficient of the limit stops, overriding
+    /// [`GenericJoint::softness`].
+    ///
+    /// Larger values make the stops springy: the joint can overshoot its limits before being
+    /// pushed back. This only affects impulse joints.
+    ///
+    /// The usable range is about `[0, 8]`. A load pressing the joint against its stop with an
+    /// acceleration `a` leaves it moving past the limit with a residual velocity of about
+    /// `softness * a * dt / num_solver_iterations`, which grows with the softness: with larger
+    /// values, e.g., `50` under gravity with the default [`IntegrationParameters`], the stop
+    /// sinks tens of centimeters past its limit and the body never comes to rest.
+    ///
+    /// [`IntegrationParameters`]: crate::dynamics::IntegrationParameters
+    pub softness: Option<N>,
 }
 
 impl<N: SimdRealCopy> Default for JointLimits<N> {
@@ -129,6 +166,10 @@ impl<N: SimdRealCopy> Default for JointLimits<N> {
             min: -N::splat(Real::MAX),
             max: N::splat(Real::MAX),
             impulse: N::splat(0.0),
+            restitution: N::splat(0.0),
+            restitution_velocity_threshold: None,
+            erp: None,
+            softness: None,
         }
     }
 }
@@ -138,7 +179,7 @@ impl<N: SimdRealCopy> From<[N; 2]> for JointLimits<N> {
         Self {
             min: value[0],
             max: value[1],
-            impulse: N::splat(0.0),
+            ..Self::default()
         }
     }
 }
@@ -156,11 +197,26 @@ pub struct JointMotor {
     /// The damping coefficient of the motor’s spring-like equation.
     pub damping: Real,
     /// The maximum force this motor can deliver.
+    ///
+    /// This is a force (or a torque for angular axes) regardless of the motor’s [`MotorModel`],
+    /// so heavier bodies are accelerated slower by a saturated motor.
     pub max_force: Real,
     /// The impulse applied by this motor.
     pub impulse: Real,
     /// The spring-like model used for simulating this motor.
     pub model: MotorModel,
+    /// The maximum rate at which the effective target velocity of this motor moves toward
+    /// [`Self::target_vel`] (default: `Real::MAX`, i.e., no ramping).
+    ///
+    /// See [`GenericJoint::set_motor_target_with_ramp`].
+    pub max_target_acceleration: Real,
+    /// The maximum rate at which the effective target position of this motor moves toward
+    /// [`Self::target_pos`] (default: `Real::MAX`, i.e., no ramping).
+    ///
+    /// See [`GenericJoint::set_motor_position_with_ramp`].
+    pub max_target_velocity: Real,
+    pub(crate) effective_target_vel: Real,
+    pub(crate) effective_target_pos: Real,
 }
 
 impl Default for JointMotor {
@@ -173,11 +229,57 @@ impl Default for JointMotor {
             max_force: Real::MAX,
             impulse: 0.0,
             model: MotorModel::AccelerationBased,
+            max_target_acceleration: Real::MAX,
+            max_target_velocity: Real::MAX,
+            effective_target_vel: 0.0,
+            effective_target_pos: 0.0,
         }
     }
 }
 
 impl JointMotor {
+    /// The target velocity the motor is currently driving toward.
+    ///
+    /// This differs from [`Self::target_vel`] while it is being ramped toward it, at a rate of
+    /// [`Self::max_target_acceleration`].
+    pub fn effective_target_vel(&self) -> Real {
+        self.effective_target_vel
+    }
+
+    /// The target position the motor is currently driving toward.
+    ///
+    /// This differs from [`Self::target_pos`] while it is being ramped toward it, at a rate of
+    /// [`Self::max_target_velocity`].
+    pub fn effective_target_pos(&self) -> Real {
+        self.effective_target_pos
+    }
+
+    /// Moves the effective targets of this motor toward the requested ones, by at most the
+    /// ramping rates over a timestep of length `dt`.
+    pub(crate) fn advance_ramps(&mut self, dt: Real) {
+        fn move_toward(current: Real, target: Real, max_rate: Real, dt: Real) -> Real {
+            if max_rate == Real::MAX {
+                target
+            } else {
+                let max_delta = max_rate * dt;
+                current + (target - current).clamp(-max_delta, max_delta)
+            }
+        }
+
+        self.effective_target_vel = move_toward(
+            self.effective_target_vel,
+            self.target_vel,
+            self.max_target_acceleration,
+            dt,
+        );
+        self.effective_target_pos = move_toward(
+            self.effective_target_pos,
+            self.target_pos,
+            self.max_target_velocity,
+            dt,
+        );
+    }
+
     pub(crate) fn motor_params(&self, dt: Real) -> MotorParameters<Real> {
         let (erp_inv_dt, cfm_coeff, cfm_gain) =
             self.model
@@ -187,8 +289,8 @@ impl JointMotor {
             cfm_coeff,
             cfm_gain,
             // keep_lhs,
-            target_pos: self.target_pos,
-            target_vel: self.target_vel,
+            target_pos: self.effective_target_pos,
+            target_vel: self.effective_target_vel,
             max_impulse: self.max_force * dt,
         }
     }
@@ -242,6 +344,26 @@ pub struct GenericJoint {
     pub motors: [JointMotor; SPATIAL_DIM],
     /// Are contacts between the attached rigid-bodies enabled?
     pub contacts_enabled: bool,
+    /// The Error Reduction Parameter of this joint, overriding [`IntegrationParameters::joint_erp`].
+    ///
+    /// This is the fraction, in `[0, 1]`, of the positional drift of the joint corrected at each
+    /// substep. Lower values give a softer correction. If `None`, the value computed from the
+    /// [`IntegrationParameters`] is used.
+    pub erp: Option<Real>,
+    /// The Constraint Force Mixing coefficient of this joint, overriding
+    /// [`IntegrationParameters::joint_cfm_coeff`].
+    ///
+    /// A value of `0.0` makes the joint perfectly rigid, and larger values let it stretch more
+    /// under load. If `None`, the value computed from the [`IntegrationParameters`] is used.
+    pub softness: Option<Real>,
+    /// Is the drift of this joint’s locked axes also corrected at the position level?
+    ///
+    /// If `true`, the bodies attached to this joint are moved at the end of each timestep to
+    /// reduce the drift of its locked axes, with [`IntegrationParameters::num_joint_position_iterations`]
+    /// nonlinear position iterations. This removes the separation that builds up on stiff joints,
+    /// like welds, under heavy loads. Limits and motors aren’t affected. This is ignored for
+    /// joints attached to multibodies.
+    pub position_solver_enabled: bool,
     /// Whether or not the joint is enabled.
     pub enabled: JointEnabled,
     /// User-defined data associated to this joint.
@@ -260,6 +382,9 @@ impl Default for GenericJoint {
             limits: [JointLimits::default(); SPATIAL_DIM],
             motors: [JointMotor::default(); SPATIAL_DIM],
             contacts_enabled: true,
+            erp: None,
+            softness: None,
+            position_solver_enabled: false,
             enabled: JointEnabled::Enabled,
             user_data: 0,
         }
@@ -276,7 +401,22 @@ impl GenericJoint {
     #[cfg(feature = "simd-is-enabled")]
     /// Can this joint use SIMD-accelerated constraint formulations?
     pub(crate) fn supports_simd_constraints(&self) -> bool {
-        self.limit_axes.is_empty() && self.motor_axes.is_empty()
+        self.limit_axes.is_empty()
+            && self.motor_axes.is_empty()
+            && self.erp.is_none()
+            && self.softness.is_none()
+    }
+
+    /// The [`Self::erp`] of this joint, or the default one, multiplied by the inverse timestep length.
+    pub(crate) fn erp_inv_dt(&self, params: &IntegrationParameters) -> Real {
+        self.erp
+            .map(|erp| erp * params.inv_dt())
+            .unwrap_or_else(|| params.joint_erp_inv_dt())
+    }
+
+    /// The [`Self::softness`] of this joint, or the default CFM coefficient.
+    pub(crate) fn cfm_coeff(&self, params: &IntegrationParameters) -> Real {
+        self.softness.unwrap_or_else(|| params.joint_cfm_coeff())
     }
 
     #[doc(hidden)]
@@ -398,6 +538,51 @@ impl GenericJoint {
         self
     }
 
+    /// The Error Reduction Parameter override of this joint, if any.
+    ///
+    /// See [`Self::erp`].
+    pub fn erp(&self) -> Option<Real> {
+        self.erp
+    }
+
+    /// Sets the Error Reduction Parameter override of this joint.
+    ///
+    /// Set to `None` to use the value from the [`IntegrationParameters`]. See [`Self::erp`].
+    pub fn set_erp(&mut self, erp: Option<Real>) -> &mut Self {
+        self.erp = erp;
+        self
+    }
+
+    /// The Constraint Force Mixing override of this joint, if any.
+    ///
+    /// See [`Self::softness`].
+    pub fn softness(&self) -> Option<Real> {
+        self.softness
+    }
+
+    /// Sets the Constraint Force Mixing override of this joint.
+    ///
+    /// Set to `None` to use the value from the [`IntegrationParameters`]. See [`Self::softness`].
+    pub fn set_softness(&mut self, softness: Option<Real>) -> &mut Self {
+        self.softness = softness;
+        self
+    }
+
+    /// Is the drift of this joint also corrected at the position level?
+    ///
+    /// See [`Self::position_solver_enabled`].
+    pub fn position_solver_enabled(&self) -> bool {
+        self.position_solver_enabled
+    }
+
+    /// Sets whether the drift of this joint is also corrected at the position level.
+    ///
+    /// See [`Self::position_solver_enabled`].
+    pub fn set_position_solver_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.position_solver_enabled = enabled;
+        self
+    }
+
     /// The joint limits along the specified axis.
     #[must_use]
     pub fn limits(&self, axis: JointAxis) -> Option<&JointLimits<Real>> {
@@ -418,6 +603,68 @@ impl GenericJoint {
         self
     }
 
+    /// Sets the restitution of the limit stops along the specified axis.
+    ///
+    /// See [`JointLimits::restitution`] and [`JointLimits::restitution_velocity_threshold`].
+    pub fn set_limit_restitution(
+        &mut self,
+        axis: JointAxis,
+        restitution: Real,
+        velocity_threshold: Option<Real>,
+    ) -> &mut Self {
+        let limits = &mut self.limits[axis as usize];
+        limits.restitution = restitution;
+        limits.restitution_velocity_threshold = velocity_threshold;
+        self
+    }
+
+    /// Sets the Error Reduction Parameter and Constraint Force Mixing coefficient of the limit
+    /// stops along the specified axis, overriding the ones of the joint.
+    ///
+    /// See [`JointLimits::erp`] and [`JointLimits::softness`].
+    pub fn set_limit_softness(
+        &mut self,
+        axis: JointAxis,
+        erp: Option<Real>,
+        softness: Option<Real>,
+    ) -> &mut Self {
+        let limits = &mut self.limits[axis as usize];
+        limits.erp = erp;
+        limits.softness = softness;
+        self
+    }
+
+    /// The [`JointLimits::erp`] of the limit along the `i`-th axis, or the joint’s one,
+    /// multiplied by the inverse timestep length.
+    pub(crate) fn limit_erp_inv_dt(&self, i: usize, params: &IntegrationParameters) -> Real {
+        self.limits[i]
+            .erp
+            .map(|erp| erp * params.inv_dt())
+            .unwrap_or_else(|| self.erp_inv_dt(params))
+    }
+
+    /// The [`JointLimits::softness`] of the limit along the `i`-th axis, or the joint’s one.
+    pub(crate) fn limit_cfm_coeff(&self, i: usize, params: &IntegrationParameters) -> Real {
+        self.limits[i]
+            .softness
+            .unwrap_or_else(|| self.cfm_coeff(params))
+    }
+
+    /// The approach velocity below which the limit along the `i`-th axis doesn’t bounce.
+    pub(crate) fn limit_restitution_velocity_threshold(
+        &self,
+        i: usize,
+        params: &IntegrationParameters,
+    ) -> Real {
+        self.limits[i]
+            .restitution_velocity_threshold
+            .unwrap_or(if i < DIM {
+                params.restitution_velocity_threshold()
+            } else {
+                params.normalized_restitution_velocity_threshold
+            })
+    }
+
     /// The spring-like motor model along the specified axis of this joint.
     #[must_use]
     pub fn motor_model(&self, axis: JointAxis) -> Option<MotorModel> {
@@ -462,6 +709,57 @@ impl GenericJoint {
         self.set_motor(axis, target_pos, 0.0, stiffness, damping)
     }
 
+    /// Sets the target velocity this motor needs to reach, ramping toward it at a bounded rate.
+    ///
+    /// Instead of switching to `target_vel` instantly, the effective target velocity of the motor
+    /// changes by at most `max_acceleration` per second, starting from its current value. This
+    /// avoids the impulse spikes caused by large changes of target velocity. The stiffness and
+    /// damping of the motor are kept: configure them first, e.g., with
+    /// [`Self::set_motor_velocity`]. The current effective target is given by
+    /// [`JointMotor::effective_target_vel`].
+    ///
+    /// Setting the motor with any other method than the `*_with_ramp` ones cancels the ramps
+    /// and applies the new targets instantly.
+    pub fn set_motor_target_with_ramp(
+        &mut self,
+        axis: JointAxis,
+        target_vel: Real,
+        max_acceleration: Real,
+    ) -> &mut Self {
+        self.motor_axes |= axis.into();
+        let motor = &mut self.motors[axis as usize];
+        motor.target_vel = target_vel;
+        motor.max_target_acceleration = max_acceleration;
+        self
+    }
+
+    /// Sets the target angle this motor needs to reach, ramping toward it at a bounded rate.
+    ///
+    /// Instead of switching to `target_pos` instantly, the effective target position of the motor
+    /// changes by at most `max_velocity` per second, starting from its current value. The
+    /// stiffness and damping of the motor are kept: configure them first, e.g., with
+    /// [`Self::set_motor_position`]. The current effective target is given by
+    /// [`JointMotor::effective_target_pos`].
+    pub fn set_motor_position_with_ramp(
+        &mut self,
+        axis: JointAxis,
+        target_pos: Real,
+        max_velocity: Real,
+    ) -> &mut Self {
+        self.motor_axes |= axis.into();
+        let motor = &mut self.motors[axis as usize];
+        motor.target_pos = target_pos;
+        motor.max_target_velocity = max_velocity;
+        self
+    }
+
+    /// Moves the effective targets of all the motors of this joint toward the requested ones.
+    pub(crate) fn advance_motor_ramps(&mut self, dt: Real) {
+        for motor in &mut self.motors {
+            motor.advance_ramps(dt);
+        }
+    }
+
     /// Sets the maximum force the motor can deliver along the specified axis.
     pub fn set_motor_max_force(&mut self, axis: JointAxis, max_force: Real) -> &mut Self {
         self.motors[axis as usize].max_force = max_force;
@@ -494,6 +792,10 @@ impl GenericJoint {
         self.motors[i].target_pos = target_pos;
         self.motors[i].stiffness = stiffness;
         self.motors[i].damping = damping;
+        self.motors[i].max_target_acceleration = Real::MAX;
+        self.motors[i].max_target_velocity = Real::MAX;
+        self.motors[i].effective_target_vel = target_vel;
+        self.motors[i].effective_target_pos = target_pos;
         self
     }
 
@@ -512,6 +814,8 @@ impl GenericJoint {
 
             self.motors[dim].target_vel = -self.motors[dim].target_vel;
             self.motors[dim].target_pos = -self.motors[dim].target_pos;
+            self.motors[dim].effective_target_vel = -self.motors[dim].effective_target_vel;
+            self.motors[dim].effective_target_pos = -self.motors[dim].effective_target_pos;
         }
     }
 }
@@ -524,7 +828,7 @@ macro_rules! joint_conversion_methods(
             if self.locked_axes == $axes {
                 // SAFETY: this is OK because the target joint type is
                 //         a `repr(transparent)` newtype of `Joint`.
-                Some(unsafe { std::mem::transmute(self) })
+                Some(unsafe { std::mem::transmute::<&GenericJoint, &$Joint>(self) })
             } else {
                 None
             }
@@ -536,7 +840,7 @@ macro_rules! joint_conversion_methods(
             if self.locked_axes == $axes {
                 // SAFETY: this is OK because the target joint type is
                 //         a `repr(transparent)` newtype of `Joint`.
-                Some(unsafe { std::mem::transmute(self) })
+                Some(unsafe { std::mem::transmute::<&mut GenericJoint, &mut $Joint>(self) })
             } else {
                 None
             }
@@ -563,6 +867,12 @@ impl GenericJoint {
         PrismaticJoint,
         JointAxesMask::LOCKED_PRISMATIC_AXES
     );
+    joint_conversion_methods!(
+        as_orientation_lock,
+        as_orientation_lock_mut,
+        OrientationLockJoint,
+        JointAxesMask::ANG_AXES
+    );
     joint_conversion_methods!(
         as_rope,
         as_rope_mut,
@@ -605,6 +915,29 @@ impl GenericJointBuilder {
         self
     }
 
+    /// Sets the Error Reduction Parameter of this joint, overriding the one from the
+    /// [`IntegrationParameters`].
+    #[must_use]
+    pub fn erp(mut self, erp: Real) -> Self {
+        self.0.erp = Some(erp);
+        self
+    }
+
+    /// Sets the Constraint Force Mixing coefficient of this joint, overriding the one from the
+    /// [`IntegrationParameters`].
+    #[must_use]
+    pub fn softness(mut self, softness: Real) -> Self {
+        self.0.softness = Some(softness);
+        self
+    }
+
+    /// Sets whether the drift of this joint is also corrected at the position level.
+    #[must_use]
+    pub fn position_solver_enabled(mut self, enabled: bool) -> Self {
+        self.0.position_solver_enabled = enabled;
+        self
+    }
+
     /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
     #[must_use]
     pub fn local_frame1(mut self, local_frame: Isometry<Real>) -> Self {
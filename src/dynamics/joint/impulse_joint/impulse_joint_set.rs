@@ -4,6 +4,7 @@ use crate::geometry::{InteractionGraph, RigidBodyGraphIndex, TemporaryInteractio
 use crate::data::arena::Arena;
 use crate::data::Coarena;
 use crate::dynamics::{GenericJoint, IslandManager, RigidBodyHandle, RigidBodySet};
+use crate::pipeline::InvalidHandle;
 
 /// The unique identifier of a joint added to the joint set.
 /// The unique identifier of a collider added to a collider set.
@@ -32,6 +33,12 @@ impl ImpulseJointHandle {
     }
 }
 
+impl std::fmt::Display for ImpulseJointHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ImpulseJointHandle({})", self.0)
+    }
+}
+
 pub(crate) type JointIndex = usize;
 pub(crate) type JointGraphEdge = crate::data::graph::Edge<ImpulseJoint>;
 
@@ -276,6 +283,28 @@ impl ImpulseJointSet {
         ImpulseJointHandle(handle)
     }
 
+    /// Inserts a new joint into this set after checking that both attached rigid-bodies exist.
+    ///
+    /// Returns an error identifying the first missing rigid-body instead of inserting a joint
+    /// that would make [`PhysicsPipeline::step`](crate::pipeline::PhysicsPipeline::step) panic.
+    /// See [`Self::insert`] for details.
+    pub fn try_insert(
+        &mut self,
+        bodies: &RigidBodySet,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        data: impl Into<GenericJoint>,
+        wake_up: bool,
+    ) -> Result<ImpulseJointHandle, InvalidHandle> {
+        for body in [body1, body2] {
+            if !bodies.contains(body) {
+                return Err(InvalidHandle::RigidBody(body));
+            }
+        }
+
+        Ok(self.insert(body1, body2, data, wake_up))
+    }
+
     /// Retrieve all the enabled impulse joints happening between two active bodies.
     // NOTE: this is very similar to the code from NarrowPhase::select_active_interactions.
     pub(crate) fn select_active_interactions(
@@ -313,7 +342,8 @@ impl ImpulseJointSet {
     /// Removes a joint from this set.
     ///
     /// If `wake_up` is set to `true`, then the bodies attached to this joint will be
-    /// automatically woken up.
+    /// automatically woken up. The rest of their island is woken up as well at the next
+    /// timestep, through its contacts and joints with these bodies.
     pub fn remove(&mut self, handle: ImpulseJointHandle, wake_up: bool) -> Option<ImpulseJoint> {
         let id = self.joint_ids.remove(handle.0)?;
         let endpoints = self.joint_graph.graph.edge_endpoints(id)?;
@@ -336,6 +366,16 @@ impl ImpulseJointSet {
         removed_joint
     }
 
+    /// Removes all the impulse joints from this set, but keeps its allocated memory.
+    ///
+    /// The attached rigid-bodies are not woken up.
+    pub fn clear(&mut self) {
+        self.rb_graph_ids.clear();
+        self.joint_ids.clear();
+        self.joint_graph.clear();
+        self.to_wake_up.clear();
+    }
+
     /// Deletes all the impulse_joints attached to the given rigid-body.
     ///
     /// The provided rigid-body handle is not required to identify a rigid-body that
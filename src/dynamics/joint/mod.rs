@@ -3,6 +3,7 @@ pub use self::generic_joint::*;
 pub use self::impulse_joint::*;
 pub use self::motor_model::MotorModel;
 pub use self::multibody_joint::*;
+pub use self::orientation_lock_joint::*;
 pub use self::prismatic_joint::*;
 pub use self::revolute_joint::*;
 pub use self::rope_joint::*;
@@ -16,6 +17,7 @@ mod generic_joint;
 mod impulse_joint;
 mod motor_model;
 mod multibody_joint;
+mod orientation_lock_joint;
 mod prismatic_joint;
 mod revolute_joint;
 mod rope_joint;
@@ -1,15 +1,23 @@
 use crate::math::Real;
 
 /// The spring-like model used for constraints resolution.
+///
+/// Motor stiffnesses and dampings are physical coefficients: the same values result in the same
+/// motion regardless of the timestep length.
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub enum MotorModel {
     /// The solved spring-like equation is:
     /// `acceleration = stiffness * (pos - target_pos) + damping * (vel - target_vel)`
+    ///
+    /// The stiffness and damping are implicitly scaled by the effective mass of the constraint,
+    /// so bodies of different masses move identically.
     #[default]
     AccelerationBased,
     /// The solved spring-like equation is:
     /// `force = stiffness * (pos - target_pos) + damping * (vel - target_vel)`
+    ///
+    /// Heavier bodies react slower to the same stiffness and damping.
     ForceBased,
 }
 
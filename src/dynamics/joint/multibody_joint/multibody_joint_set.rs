@@ -1,8 +1,9 @@
 use crate::data::{Arena, Coarena, Index};
 use crate::dynamics::joint::MultibodyLink;
-use crate::dynamics::{GenericJoint, Multibody, MultibodyJoint, RigidBodyHandle};
+use crate::dynamics::{GenericJoint, Multibody, MultibodyJoint, RigidBodyHandle, RigidBodySet};
 use crate::geometry::{InteractionGraph, RigidBodyGraphIndex};
 use crate::parry::partitioning::IndexedData;
+use crate::pipeline::InvalidHandle;
 
 /// The unique handle of an multibody_joint added to a `MultibodyJointSet`.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -36,6 +37,12 @@ impl MultibodyJointHandle {
     }
 }
 
+impl std::fmt::Display for MultibodyJointHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MultibodyJointHandle({})", self.0)
+    }
+}
+
 impl Default for MultibodyJointHandle {
     fn default() -> Self {
         Self::invalid()
@@ -187,47 +194,98 @@ impl MultibodyJointSet {
         Some(MultibodyJointHandle(body2.0))
     }
 
+    /// Inserts a new multibody_joint into this set after checking that both attached
+    /// rigid-bodies exist.
+    ///
+    /// Returns an error identifying the first missing rigid-body instead of inserting a joint
+    /// that would make [`PhysicsPipeline::step`](crate::pipeline::PhysicsPipeline::step) panic.
+    /// Otherwise, returns the result of [`Self::insert`].
+    pub fn try_insert(
+        &mut self,
+        bodies: &RigidBodySet,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        data: impl Into<GenericJoint>,
+        wake_up: bool,
+    ) -> Result<Option<MultibodyJointHandle>, InvalidHandle> {
+        for body in [body1, body2] {
+            if !bodies.contains(body) {
+                return Err(InvalidHandle::RigidBody(body));
+            }
+        }
+
+        Ok(self.insert(body1, body2, data, wake_up))
+    }
+
     /// Removes an multibody_joint from this set.
-    pub fn remove(&mut self, handle: MultibodyJointHandle, wake_up: bool) {
-        if let Some(removed) = self.rb2mb.get(handle.0).copied() {
-            let multibody = self.multibodies.remove(removed.multibody.0).unwrap();
+    ///
+    /// If `wake_up` is `true`, every rigid-body of the multibody the joint was part of is woken
+    /// up, since they may have been supported through that joint.
+    ///
+    /// Returns the description of the removed joint, or `None` if `handle` doesn’t identify
+    /// any multibody joint.
+    pub fn remove(&mut self, handle: MultibodyJointHandle, wake_up: bool) -> Option<GenericJoint> {
+        let removed = self.rb2mb.get(handle.0).copied()?;
+        let link = self
+            .multibodies
+            .get(removed.multibody.0)?
+            .link(removed.id)?;
+
+        // The root of a multibody isn’t attached by any joint.
+        let parent_link = link.parent_id()?;
+        let joint = link.joint.data;
+        let multibody = self.multibodies.remove(removed.multibody.0).unwrap();
+
+        // Remove the edge from the connectivity graph.
+        let parent_rb = multibody.link(parent_link).unwrap().rigid_body;
+        self.connectivity_graph.remove_edge(
+            self.rb2mb.get(parent_rb.0).unwrap().graph_id,
+            removed.graph_id,
+        );
+
+        // TODO: remove the node if it no longer has any attached edges?
+
+        // Extract the individual sub-trees generated by this removal.
+        let multibodies = multibody.remove_link(removed.id, true);
 
-            // Remove the edge from the connectivity graph.
-            if let Some(parent_link) = multibody.link(removed.id).unwrap().parent_id() {
-                let parent_rb = multibody.link(parent_link).unwrap().rigid_body;
-                self.connectivity_graph.remove_edge(
-                    self.rb2mb.get(parent_rb.0).unwrap().graph_id,
-                    removed.graph_id,
-                );
+        if wake_up {
+            // Wake up every body of the sub-trees, not only the ones attached to the removed
+            // joint: the whole sub-tree may have been supported through that joint.
+            for multibody in &multibodies {
+                self.to_wake_up
+                    .extend(multibody.links().map(|link| link.rigid_body));
+            }
+            self.to_wake_up.push(parent_rb);
+        }
 
-                if wake_up {
-                    self.to_wake_up.push(RigidBodyHandle(handle.0));
-                    self.to_wake_up.push(parent_rb);
+        // Update the rb2mb mapping.
+        for multibody in multibodies {
+            if multibody.num_links() == 1 {
+                // We don’t have any multibody_joint attached to this body, remove it.
+                if let Some(other) = self.connectivity_graph.remove_node(removed.graph_id) {
+                    self.rb2mb.get_mut(other.0).unwrap().graph_id = removed.graph_id;
                 }
-
-                // TODO: remove the node if it no longer has any attached edges?
-
-                // Extract the individual sub-trees generated by this removal.
-                let multibodies = multibody.remove_link(removed.id, true);
-
-                // Update the rb2mb mapping.
-                for multibody in multibodies {
-                    if multibody.num_links() == 1 {
-                        // We don’t have any multibody_joint attached to this body, remove it.
-                        if let Some(other) = self.connectivity_graph.remove_node(removed.graph_id) {
-                            self.rb2mb.get_mut(other.0).unwrap().graph_id = removed.graph_id;
-                        }
-                    } else {
-                        let mb_id = self.multibodies.insert(multibody);
-                        for link in self.multibodies[mb_id].links() {
-                            let ids = self.rb2mb.get_mut(link.rigid_body.0).unwrap();
-                            ids.multibody = MultibodyIndex(mb_id);
-                            ids.id = link.internal_id;
-                        }
-                    }
+            } else {
+                let mb_id = self.multibodies.insert(multibody);
+                for link in self.multibodies[mb_id].links() {
+                    let ids = self.rb2mb.get_mut(link.rigid_body.0).unwrap();
+                    ids.multibody = MultibodyIndex(mb_id);
+                    ids.id = link.internal_id;
                 }
             }
         }
+
+        Some(joint)
+    }
+
+    /// Removes all the multibody joints from this set, but keeps its allocated memory.
+    ///
+    /// The attached rigid-bodies are not woken up.
+    pub fn clear(&mut self) {
+        self.multibodies.clear();
+        self.rb2mb.clear();
+        self.connectivity_graph.clear();
+        self.to_wake_up.clear();
     }
 
     /// Removes all the multibody_joints from the multibody the given rigid-body is part of.
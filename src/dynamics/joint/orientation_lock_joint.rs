@@ -0,0 +1,168 @@
+use crate::dynamics::{GenericJoint, GenericJointBuilder, JointAxesMask};
+use crate::math::{Isometry, Real, Translation};
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(transparent)]
+/// An orientation lock joint, locks all relative rotations between two bodies while leaving
+/// their relative translations free.
+///
+/// This is the angular counterpart of the spherical joint (or of the revolute joint in 2D) which
+/// locks all relative translations while leaving relative rotations free.
+pub struct OrientationLockJoint {
+    /// The underlying joint data.
+    pub data: GenericJoint,
+}
+
+impl Default for OrientationLockJoint {
+    fn default() -> Self {
+        OrientationLockJoint::new()
+    }
+}
+
+impl OrientationLockJoint {
+    /// Creates a new orientation lock joint.
+    ///
+    /// The bodies are rotated so that the orientations of both joint frames match. Use
+    /// [`Self::from_poses`] to lock the relative orientation the bodies currently have instead.
+    #[must_use]
+    pub fn new() -> Self {
+        let data = GenericJointBuilder::new(JointAxesMask::ANG_AXES).build();
+        Self { data }
+    }
+
+    /// Creates a new orientation lock joint keeping the relative orientation two bodies
+    /// currently have.
+    ///
+    /// `pos1` and `pos2` are the current world-space poses of the first and second rigid-bodies.
+    #[must_use]
+    pub fn from_poses(pos1: &Isometry<Real>, pos2: &Isometry<Real>) -> Self {
+        let mut joint = Self::new();
+        joint.set_local_frame2(Isometry::from_parts(
+            Translation::identity(),
+            pos2.rotation.inverse() * pos1.rotation,
+        ));
+        joint
+    }
+
+    /// Are contacts between the attached rigid-bodies enabled?
+    pub fn contacts_enabled(&self) -> bool {
+        self.data.contacts_enabled
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    pub fn set_contacts_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.data.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// The Error Reduction Parameter of this joint, if it overrides the one from the
+    /// integration parameters.
+    ///
+    /// See [`GenericJoint::erp`].
+    pub fn erp(&self) -> Option<Real> {
+        self.data.erp
+    }
+
+    /// Sets the Error Reduction Parameter of this joint.
+    ///
+    /// Set to `None` to use the value from the integration parameters.
+    pub fn set_erp(&mut self, erp: Option<Real>) -> &mut Self {
+        self.data.set_erp(erp);
+        self
+    }
+
+    /// The joint’s frame, expressed in the first rigid-body’s local-space.
+    ///
+    /// Only the orientation of this frame affects the joint.
+    #[must_use]
+    pub fn local_frame1(&self) -> &Isometry<Real> {
+        &self.data.local_frame1
+    }
+
+    /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
+    pub fn set_local_frame1(&mut self, local_frame: Isometry<Real>) -> &mut Self {
+        self.data.set_local_frame1(local_frame);
+        self
+    }
+
+    /// The joint’s frame, expressed in the second rigid-body’s local-space.
+    ///
+    /// Only the orientation of this frame affects the joint.
+    #[must_use]
+    pub fn local_frame2(&self) -> &Isometry<Real> {
+        &self.data.local_frame2
+    }
+
+    /// Sets joint’s frame, expressed in the second rigid-body’s local-space.
+    pub fn set_local_frame2(&mut self, local_frame: Isometry<Real>) -> &mut Self {
+        self.data.set_local_frame2(local_frame);
+        self
+    }
+}
+
+impl From<OrientationLockJoint> for GenericJoint {
+    fn from(val: OrientationLockJoint) -> GenericJoint {
+        val.data
+    }
+}
+
+/// Create orientation lock joints using the builder pattern.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct OrientationLockJointBuilder(pub OrientationLockJoint);
+
+impl OrientationLockJointBuilder {
+    /// Creates a new builder for orientation lock joints.
+    pub fn new() -> Self {
+        Self(OrientationLockJoint::new())
+    }
+
+    /// Creates a new builder for orientation lock joints keeping the relative orientation two
+    /// bodies currently have.
+    ///
+    /// See [`OrientationLockJoint::from_poses`].
+    pub fn from_poses(pos1: &Isometry<Real>, pos2: &Isometry<Real>) -> Self {
+        Self(OrientationLockJoint::from_poses(pos1, pos2))
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    #[must_use]
+    pub fn contacts_enabled(mut self, enabled: bool) -> Self {
+        self.0.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// Sets the Error Reduction Parameter of this joint.
+    #[must_use]
+    pub fn erp(mut self, erp: Real) -> Self {
+        self.0.set_erp(Some(erp));
+        self
+    }
+
+    /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
+    #[must_use]
+    pub fn local_frame1(mut self, local_frame: Isometry<Real>) -> Self {
+        self.0.set_local_frame1(local_frame);
+        self
+    }
+
+    /// Sets joint’s frame, expressed in the second rigid-body’s local-space.
+    #[must_use]
+    pub fn local_frame2(mut self, local_frame: Isometry<Real>) -> Self {
+        self.0.set_local_frame2(local_frame);
+        self
+    }
+
+    /// Build the orientation lock joint.
+    #[must_use]
+    pub fn build(self) -> OrientationLockJoint {
+        self.0
+    }
+}
+
+impl From<OrientationLockJointBuilder> for GenericJoint {
+    fn from(val: OrientationLockJointBuilder) -> GenericJoint {
+        val.0.into()
+    }
+}
@@ -41,6 +41,21 @@ impl PrismaticJoint {
         self
     }
 
+    /// Is the drift of this joint also corrected at the position level?
+    ///
+    /// See [`GenericJoint::position_solver_enabled`].
+    pub fn position_solver_enabled(&self) -> bool {
+        self.data.position_solver_enabled
+    }
+
+    /// Sets whether the drift of this joint is also corrected at the position level.
+    ///
+    /// See [`GenericJoint::position_solver_enabled`].
+    pub fn set_position_solver_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.data.set_position_solver_enabled(enabled);
+        self
+    }
+
     /// The joint’s anchor, expressed in the local-space of the first rigid-body.
     #[must_use]
     pub fn local_anchor1(&self) -> Point<Real> {
@@ -120,6 +135,34 @@ impl PrismaticJoint {
         self
     }
 
+    /// Sets the target velocity this motor needs to reach, changing the effective target by at
+    /// most `max_acceleration` per second.
+    ///
+    /// See [`GenericJoint::set_motor_target_with_ramp`] for details.
+    pub fn set_motor_target_with_ramp(
+        &mut self,
+        target_vel: Real,
+        max_acceleration: Real,
+    ) -> &mut Self {
+        self.data
+            .set_motor_target_with_ramp(JointAxis::X, target_vel, max_acceleration);
+        self
+    }
+
+    /// Sets the target position this motor needs to reach, changing the effective target by at
+    /// most `max_velocity` per second.
+    ///
+    /// See [`GenericJoint::set_motor_position_with_ramp`] for details.
+    pub fn set_motor_position_with_ramp(
+        &mut self,
+        target_pos: Real,
+        max_velocity: Real,
+    ) -> &mut Self {
+        self.data
+            .set_motor_position_with_ramp(JointAxis::X, target_pos, max_velocity);
+        self
+    }
+
     /// Configure both the target angle and target velocity of the motor.
     pub fn set_motor(
         &mut self,
@@ -150,6 +193,28 @@ impl PrismaticJoint {
         self.data.set_limits(JointAxis::X, limits);
         self
     }
+
+    /// Sets the restitution of the limit stops of this joint.
+    ///
+    /// See [`JointLimits::restitution`] and [`JointLimits::restitution_velocity_threshold`].
+    pub fn set_limit_restitution(
+        &mut self,
+        restitution: Real,
+        velocity_threshold: Option<Real>,
+    ) -> &mut Self {
+        self.data
+            .set_limit_restitution(JointAxis::X, restitution, velocity_threshold);
+        self
+    }
+
+    /// Sets the Error Reduction Parameter and Constraint Force Mixing coefficient of the limit
+    /// stops of this joint.
+    ///
+    /// See [`JointLimits::erp`] and [`JointLimits::softness`].
+    pub fn set_limit_softness(&mut self, erp: Option<Real>, softness: Option<Real>) -> &mut Self {
+        self.data.set_limit_softness(JointAxis::X, erp, softness);
+        self
+    }
 }
 
 impl From<PrismaticJoint> for GenericJoint {
@@ -180,6 +245,15 @@ impl PrismaticJointBuilder {
         self
     }
 
+    /// Sets whether the drift of this joint is also corrected at the position level.
+    ///
+    /// See [`GenericJoint::position_solver_enabled`].
+    #[must_use]
+    pub fn position_solver_enabled(mut self, enabled: bool) -> Self {
+        self.0.set_position_solver_enabled(enabled);
+        self
+    }
+
     /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
     #[must_use]
     pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
@@ -256,6 +330,26 @@ impl PrismaticJointBuilder {
         self
     }
 
+    /// Sets the restitution of the limit stops of the joint, bouncing at approach velocities
+    /// above the default threshold.
+    ///
+    /// See [`JointLimits::restitution`].
+    #[must_use]
+    pub fn limit_restitution(mut self, restitution: Real) -> Self {
+        self.0.set_limit_restitution(restitution, None);
+        self
+    }
+
+    /// Sets the Constraint Force Mixing coefficient of the limit stops of the joint, making
+    /// them springy.
+    ///
+    /// See [`JointLimits::softness`].
+    #[must_use]
+    pub fn limit_softness(mut self, softness: Real) -> Self {
+        self.0.data.limits[JointAxis::X as usize].softness = Some(softness);
+        self
+    }
+
     /// Builds the prismatic joint.
     #[must_use]
     pub fn build(self) -> PrismaticJoint {
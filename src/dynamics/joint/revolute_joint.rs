@@ -23,6 +23,25 @@ impl RevoluteJoint {
         Self { data: data.build() }
     }
 
+    /// Creates a new revolute joint attaching two bodies at a world-space anchor point, given
+    /// the current world-space poses `pos1` and `pos2` of the first and second rigid-bodies.
+    ///
+    /// The relative translation of the bodies is locked, keeping the anchor at the same place
+    /// on both bodies, while they remain free to rotate relative to each other. See
+    /// [`OrientationLockJoint`](crate::dynamics::OrientationLockJoint) for the opposite case.
+    #[cfg(feature = "dim2")]
+    pub fn from_poses(
+        pos1: &crate::math::Isometry<Real>,
+        pos2: &crate::math::Isometry<Real>,
+        anchor: Point<Real>,
+    ) -> Self {
+        let mut joint = Self::new();
+        joint
+            .set_local_anchor1(pos1.inverse_transform_point(&anchor))
+            .set_local_anchor2(pos2.inverse_transform_point(&anchor));
+        joint
+    }
+
     /// Creates a new revolute joint allowing only relative rotations along the specified axis.
     ///
     /// This axis is expressed in the local-space of both rigid-bodies.
@@ -51,6 +70,21 @@ impl RevoluteJoint {
         self
     }
 
+    /// Is the drift of this joint also corrected at the position level?
+    ///
+    /// See [`GenericJoint::position_solver_enabled`].
+    pub fn position_solver_enabled(&self) -> bool {
+        self.data.position_solver_enabled
+    }
+
+    /// Sets whether the drift of this joint is also corrected at the position level.
+    ///
+    /// See [`GenericJoint::position_solver_enabled`].
+    pub fn set_position_solver_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.data.set_position_solver_enabled(enabled);
+        self
+    }
+
     /// The joint’s anchor, expressed in the local-space of the first rigid-body.
     #[must_use]
     pub fn local_anchor1(&self) -> Point<Real> {
@@ -106,6 +140,34 @@ impl RevoluteJoint {
         self
     }
 
+    /// Sets the target velocity this motor needs to reach, changing the effective target by at
+    /// most `max_acceleration` per second.
+    ///
+    /// See [`GenericJoint::set_motor_target_with_ramp`] for details.
+    pub fn set_motor_target_with_ramp(
+        &mut self,
+        target_vel: Real,
+        max_acceleration: Real,
+    ) -> &mut Self {
+        self.data
+            .set_motor_target_with_ramp(JointAxis::AngX, target_vel, max_acceleration);
+        self
+    }
+
+    /// Sets the target angle this motor needs to reach, changing the effective target by at
+    /// most `max_velocity` per second.
+    ///
+    /// See [`GenericJoint::set_motor_position_with_ramp`] for details.
+    pub fn set_motor_position_with_ramp(
+        &mut self,
+        target_pos: Real,
+        max_velocity: Real,
+    ) -> &mut Self {
+        self.data
+            .set_motor_position_with_ramp(JointAxis::AngX, target_pos, max_velocity);
+        self
+    }
+
     /// Configure both the target angle and target velocity of the motor.
     pub fn set_motor(
         &mut self,
@@ -136,6 +198,28 @@ impl RevoluteJoint {
         self.data.set_limits(JointAxis::AngX, limits);
         self
     }
+
+    /// Sets the restitution of the limit stops of this joint.
+    ///
+    /// See [`JointLimits::restitution`] and [`JointLimits::restitution_velocity_threshold`].
+    pub fn set_limit_restitution(
+        &mut self,
+        restitution: Real,
+        velocity_threshold: Option<Real>,
+    ) -> &mut Self {
+        self.data
+            .set_limit_restitution(JointAxis::AngX, restitution, velocity_threshold);
+        self
+    }
+
+    /// Sets the Error Reduction Parameter and Constraint Force Mixing coefficient of the limit
+    /// stops of this joint.
+    ///
+    /// See [`JointLimits::erp`] and [`JointLimits::softness`].
+    pub fn set_limit_softness(&mut self, erp: Option<Real>, softness: Option<Real>) -> &mut Self {
+        self.data.set_limit_softness(JointAxis::AngX, erp, softness);
+        self
+    }
 }
 
 impl From<RevoluteJoint> for GenericJoint {
@@ -159,6 +243,18 @@ impl RevoluteJointBuilder {
         Self(RevoluteJoint::new())
     }
 
+    /// Creates a new revolute joint builder attaching two bodies at a world-space anchor point.
+    ///
+    /// See [`RevoluteJoint::from_poses`].
+    #[cfg(feature = "dim2")]
+    pub fn from_poses(
+        pos1: &crate::math::Isometry<Real>,
+        pos2: &crate::math::Isometry<Real>,
+        anchor: Point<Real>,
+    ) -> Self {
+        Self(RevoluteJoint::from_poses(pos1, pos2, anchor))
+    }
+
     /// Creates a new revolute joint builder, allowing only relative rotations along the specified axis.
     ///
     /// This axis is expressed in the local-space of both rigid-bodies.
@@ -174,6 +270,15 @@ impl RevoluteJointBuilder {
         self
     }
 
+    /// Sets whether the drift of this joint is also corrected at the position level.
+    ///
+    /// See [`GenericJoint::position_solver_enabled`].
+    #[must_use]
+    pub fn position_solver_enabled(mut self, enabled: bool) -> Self {
+        self.0.set_position_solver_enabled(enabled);
+        self
+    }
+
     /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
     #[must_use]
     pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
@@ -236,6 +341,26 @@ impl RevoluteJointBuilder {
         self
     }
 
+    /// Sets the restitution of the limit stops of the joint, bouncing at approach velocities
+    /// above the default threshold.
+    ///
+    /// See [`JointLimits::restitution`].
+    #[must_use]
+    pub fn limit_restitution(mut self, restitution: Real) -> Self {
+        self.0.set_limit_restitution(restitution, None);
+        self
+    }
+
+    /// Sets the Constraint Force Mixing coefficient of the limit stops of the joint, making
+    /// them springy.
+    ///
+    /// See [`JointLimits::softness`].
+    #[must_use]
+    pub fn limit_softness(mut self, softness: Real) -> Self {
+        self.0.data.limits[JointAxis::AngX as usize].softness = Some(softness);
+        self
+    }
+
     /// Builds the revolute joint.
     #[must_use]
     pub fn build(self) -> RevoluteJoint {
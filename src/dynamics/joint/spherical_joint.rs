@@ -26,6 +26,20 @@ impl SphericalJoint {
         Self { data }
     }
 
+    /// Creates a new spherical joint attaching two bodies at a world-space anchor point, given
+    /// the current world-space poses `pos1` and `pos2` of the first and second rigid-bodies.
+    ///
+    /// The relative translation of the bodies is locked, keeping the anchor at the same place
+    /// on both bodies, while they remain free to rotate relative to each other. See
+    /// [`OrientationLockJoint`](crate::dynamics::OrientationLockJoint) for the opposite case.
+    pub fn from_poses(pos1: &Isometry<Real>, pos2: &Isometry<Real>, anchor: Point<Real>) -> Self {
+        let mut joint = Self::new();
+        joint
+            .set_local_anchor1(pos1.inverse_transform_point(&anchor))
+            .set_local_anchor2(pos2.inverse_transform_point(&anchor));
+        joint
+    }
+
     /// The underlying generic joint.
     pub fn data(&self) -> &GenericJoint {
         &self.data
@@ -187,6 +201,14 @@ impl SphericalJointBuilder {
         Self(SphericalJoint::new())
     }
 
+    /// Creates a new builder for spherical joints attaching two bodies at a world-space anchor
+    /// point.
+    ///
+    /// See [`SphericalJoint::from_poses`].
+    pub fn from_poses(pos1: &Isometry<Real>, pos2: &Isometry<Real>, anchor: Point<Real>) -> Self {
+        Self(SphericalJoint::from_poses(pos1, pos2, anchor))
+    }
+
     /// Sets whether contacts between the attached rigid-bodies are enabled.
     #[must_use]
     pub fn contacts_enabled(mut self, enabled: bool) -> Self {
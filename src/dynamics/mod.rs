@@ -1,7 +1,11 @@
 //! Structures related to dynamics: bodies, impulse_joints, etc.
 
+pub(crate) use self::ccd::predicted_swept_aabb;
 pub use self::ccd::CCDSolver;
 pub use self::coefficient_combine_rule::CoefficientCombineRule;
+pub use self::gravity_well::{
+    GravityWell, GravityWellHandle, GravityWellSelection, GravityWellSet,
+};
 pub use self::integration_parameters::IntegrationParameters;
 pub use self::island_manager::IslandManager;
 pub(crate) use self::joint::JointGraphEdge;
@@ -12,13 +16,28 @@ pub use self::rigid_body_components::*;
 pub(crate) use self::solver::IslandSolver;
 // #[cfg(feature = "parallel")]
 // pub(crate) use self::solver::ParallelIslandSolver;
+/// The mass, center-of-mass, and angular inertia of a rigid-body or a collider.
+///
+/// They can be built from scratch with [`MassProperties::new`], where the center-of-mass is
+/// expressed in the local-space of the rigid-body and the angular inertia is given relative to
+/// the center-of-mass. In 3D, this is the principal angular inertia along the local coordinate axes;
+/// use [`MassProperties::with_principal_inertia_frame`] or [`MassProperties::with_inertia_matrix`]
+/// for other principal axes. They can also be computed for a shape at a given density, either
+/// with constructors like [`MassProperties::from_ball`], [`MassProperties::from_cuboid`] or
+/// [`MassProperties::from_compound`], or with
+/// [`Shape::mass_properties`](crate::geometry::Shape::mass_properties).
+///
+/// Mass properties can be added and subtracted, taking their centers-of-mass into account for the
+/// resulting angular inertia. Subtracting the mass properties of a shape from those of a larger one
+/// gives the mass properties of a hollow object.
 pub use parry::mass_properties::MassProperties;
 
-pub use self::rigid_body::{RigidBody, RigidBodyBuilder};
+pub use self::rigid_body::{constraint_effective_mass, RigidBody, RigidBodyBuilder};
 pub use self::rigid_body_set::{BodyPair, RigidBodySet};
 
 mod ccd;
 mod coefficient_combine_rule;
+mod gravity_well;
 mod integration_parameters;
 mod island_manager;
 mod joint;
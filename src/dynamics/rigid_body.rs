@@ -1,13 +1,16 @@
 use crate::dynamics::{
-    LockedAxes, MassProperties, RigidBodyActivation, RigidBodyAdditionalMassProps, RigidBodyCcd,
-    RigidBodyChanges, RigidBodyColliders, RigidBodyDamping, RigidBodyDominance, RigidBodyForces,
-    RigidBodyIds, RigidBodyMassProps, RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
+    IntegrationParameters, LockedAxes, MassProperties, RigidBodyActivation,
+    RigidBodyAdditionalMassProps, RigidBodyCcd, RigidBodyChanges, RigidBodyColliders,
+    RigidBodyDamping, RigidBodyDominance, RigidBodyForces, RigidBodyIds, RigidBodyMassProps,
+    RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
 };
 use crate::geometry::{
     ColliderHandle, ColliderMassProps, ColliderParent, ColliderPosition, ColliderSet, ColliderShape,
 };
-use crate::math::{AngVector, Isometry, Point, Real, Rotation, Vector};
-use crate::utils::SimdCross;
+use crate::math::{AngVector, AngularInertia, Isometry, Point, Real, Rotation, Vector};
+#[cfg(feature = "glam")]
+use crate::math::{GlamRotation, GlamVector};
+use crate::utils::{SimdAngularInertia, SimdCross, SimdDot};
 use num::Zero;
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -23,7 +26,13 @@ pub struct RigidBody {
     //       should remove this field.
     pub(crate) integrated_vels: RigidBodyVelocity,
     pub(crate) vels: RigidBodyVelocity,
+    /// The velocities after integrating the forces of the last step, before solving constraints.
+    pub(crate) vels_before_solve: RigidBodyVelocity,
     pub(crate) damping: RigidBodyDamping,
+    /// Does this rigid-body use the global linear damping instead of `damping.linear_damping`?
+    pub(crate) inherit_linear_damping: bool,
+    /// Does this rigid-body use the global angular damping instead of `damping.angular_damping`?
+    pub(crate) inherit_angular_damping: bool,
     pub(crate) forces: RigidBodyForces,
     pub(crate) ccd: RigidBodyCcd,
     pub(crate) ids: RigidBodyIds,
@@ -37,6 +46,10 @@ pub struct RigidBody {
     pub(crate) dominance: RigidBodyDominance,
     pub(crate) enabled: bool,
     pub(crate) additional_solver_iterations: usize,
+    /// The step group of this rigid-body.
+    pub(crate) step_group: u8,
+    /// Is this body temporarily treated as kinematic because its step group isn’t stepped?
+    pub(crate) step_frozen: bool,
     /// User-defined data associated to this rigid-body.
     pub user_data: u128,
 }
@@ -54,7 +67,10 @@ impl RigidBody {
             mprops: RigidBodyMassProps::default(),
             integrated_vels: RigidBodyVelocity::default(),
             vels: RigidBodyVelocity::default(),
+            vels_before_solve: RigidBodyVelocity::default(),
             damping: RigidBodyDamping::default(),
+            inherit_linear_damping: true,
+            inherit_angular_damping: true,
             forces: RigidBodyForces::default(),
             ccd: RigidBodyCcd::default(),
             ids: RigidBodyIds::default(),
@@ -66,6 +82,8 @@ impl RigidBody {
             enabled: true,
             user_data: 0,
             additional_solver_iterations: 0,
+            step_group: 0,
+            step_frozen: false,
         }
     }
 
@@ -98,7 +116,10 @@ impl RigidBody {
             mprops,
             integrated_vels,
             vels,
+            vels_before_solve,
             damping,
+            inherit_linear_damping,
+            inherit_angular_damping,
             forces,
             ccd,
             ids: _ids,             // Internal ids must not be overwritten.
@@ -109,6 +130,8 @@ impl RigidBody {
             dominance,
             enabled,
             additional_solver_iterations,
+            step_group,
+            step_frozen: _step_frozen, // Only set while a timestep is running.
             user_data,
         } = other;
 
@@ -116,7 +139,10 @@ impl RigidBody {
         self.mprops = mprops.clone();
         self.integrated_vels = *integrated_vels;
         self.vels = *vels;
+        self.vels_before_solve = *vels_before_solve;
         self.damping = *damping;
+        self.inherit_linear_damping = *inherit_linear_damping;
+        self.inherit_angular_damping = *inherit_angular_damping;
         self.forces = *forces;
         self.ccd = *ccd;
         self.activation = *activation;
@@ -124,6 +150,7 @@ impl RigidBody {
         self.dominance = *dominance;
         self.enabled = *enabled;
         self.additional_solver_iterations = *additional_solver_iterations;
+        self.step_group = *step_group;
         self.user_data = *user_data;
 
         self.changes = RigidBodyChanges::all();
@@ -150,6 +177,32 @@ impl RigidBody {
         self.additional_solver_iterations = additional_iterations;
     }
 
+    /// The step group of this rigid-body.
+    ///
+    /// See [`Self::set_step_group`] for additional information.
+    pub fn step_group(&self) -> u8 {
+        self.step_group
+    }
+
+    /// Sets the step group of this rigid-body.
+    ///
+    /// A dynamic rigid-body is only simulated by
+    /// [`PhysicsPipeline::step_with_groups`](crate::pipeline::PhysicsPipeline::step_with_groups)
+    /// if the bit `1 << step_group` is set in the given mask. Otherwise, it is treated as a
+    /// kinematic body at rest for the duration of that timestep: it isn’t integrated, doesn’t
+    /// take part in islands, and acts as a static obstacle for the bodies being stepped.
+    ///
+    /// The default step group is 0. Step groups are ignored for non-dynamic bodies and
+    /// multibody links.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step_group` is not smaller than 32.
+    pub fn set_step_group(&mut self, step_group: u8) {
+        assert!(step_group < 32, "The step group must be smaller than 32.");
+        self.step_group = step_group;
+    }
+
     /// The activation status of this rigid-body.
     pub fn activation(&self) -> &RigidBodyActivation {
         &self.activation
@@ -183,28 +236,101 @@ impl RigidBody {
         }
     }
 
-    /// The linear damping coefficient of this rigid-body.
+    /// The linear damping coefficient set on this rigid-body.
+    ///
+    /// It is ignored while [`Self::inherits_linear_damping`] is `true`. Use
+    /// [`Self::effective_linear_damping`] to get the coefficient actually applied.
     #[inline]
     pub fn linear_damping(&self) -> Real {
         self.damping.linear_damping
     }
 
     /// Sets the linear damping coefficient of this rigid-body.
+    ///
+    /// This overrides the global [`IntegrationParameters::linear_damping`].
     #[inline]
     pub fn set_linear_damping(&mut self, damping: Real) {
         self.damping.linear_damping = damping;
+        self.inherit_linear_damping = false;
+    }
+
+    /// Does this rigid-body use the global [`IntegrationParameters::linear_damping`]
+    /// instead of its own [`Self::linear_damping`]?
+    ///
+    /// This is `true` until a linear damping is set on the rigid-body.
+    #[inline]
+    pub fn inherits_linear_damping(&self) -> bool {
+        self.inherit_linear_damping
+    }
+
+    /// Sets whether this rigid-body uses the global linear damping instead of its own.
+    #[inline]
+    pub fn set_inherits_linear_damping(&mut self, inherit: bool) {
+        self.inherit_linear_damping = inherit;
     }
 
-    /// The angular damping coefficient of this rigid-body.
+    /// The linear damping coefficient actually applied to this rigid-body by the given
+    /// integration parameters.
+    #[inline]
+    pub fn effective_linear_damping(&self, params: &IntegrationParameters) -> Real {
+        if self.inherit_linear_damping {
+            params.linear_damping
+        } else {
+            self.damping.linear_damping
+        }
+    }
+
+    /// The angular damping coefficient set on this rigid-body.
+    ///
+    /// It is ignored while [`Self::inherits_angular_damping`] is `true`. Use
+    /// [`Self::effective_angular_damping`] to get the coefficient actually applied.
     #[inline]
     pub fn angular_damping(&self) -> Real {
         self.damping.angular_damping
     }
 
     /// Sets the angular damping coefficient of this rigid-body.
+    ///
+    /// This overrides the global [`IntegrationParameters::angular_damping`].
     #[inline]
     pub fn set_angular_damping(&mut self, damping: Real) {
-        self.damping.angular_damping = damping
+        self.damping.angular_damping = damping;
+        self.inherit_angular_damping = false;
+    }
+
+    /// Does this rigid-body use the global [`IntegrationParameters::angular_damping`]
+    /// instead of its own [`Self::angular_damping`]?
+    ///
+    /// This is `true` until an angular damping is set on the rigid-body.
+    #[inline]
+    pub fn inherits_angular_damping(&self) -> bool {
+        self.inherit_angular_damping
+    }
+
+    /// Sets whether this rigid-body uses the global angular damping instead of its own.
+    #[inline]
+    pub fn set_inherits_angular_damping(&mut self, inherit: bool) {
+        self.inherit_angular_damping = inherit;
+    }
+
+    /// The angular damping coefficient actually applied to this rigid-body by the given
+    /// integration parameters.
+    #[inline]
+    pub fn effective_angular_damping(&self, params: &IntegrationParameters) -> Real {
+        if self.inherit_angular_damping {
+            params.angular_damping
+        } else {
+            self.damping.angular_damping
+        }
+    }
+
+    /// The damping coefficients actually applied to this rigid-body by the given integration
+    /// parameters.
+    pub(crate) fn effective_damping(&self, params: &IntegrationParameters) -> RigidBodyDamping {
+        RigidBodyDamping {
+            linear_damping: self.effective_linear_damping(params),
+            angular_damping: self.effective_angular_damping(params),
+        }
     }
 
     /// The type of this rigid-body.
@@ -212,6 +338,16 @@ impl RigidBody {
         self.body_type
     }
 
+    /// The type of this rigid-body, ignoring the kinematic override applied to bodies
+    /// whose step group isn’t being stepped.
+    pub(crate) fn unfrozen_body_type(&self) -> RigidBodyType {
+        if self.step_frozen {
+            RigidBodyType::Dynamic
+        } else {
+            self.body_type
+        }
+    }
+
     /// Sets the type of this rigid-body.
     pub fn set_body_type(&mut self, status: RigidBodyType, wake_up: bool) {
         if status != self.body_type {
@@ -458,6 +594,15 @@ impl RigidBody {
     /// It is a generally cheaper variant of regular CCD (that can be enabled with
     /// [`RigidBody::enable_ccd`] since it relies on predictive constraints instead of
     /// shape-cast and substeps.
+    ///
+    /// The prediction distance of every contact pair involving this rigid-body is extended by
+    /// the distance it travels during the timestep (its linear velocity times `dt`), capped
+    /// to `prediction_distance`. Speculative contacts are then generated early enough for the
+    /// constraints solver to stop the body before it tunnels.
+    ///
+    /// Both kinds of CCD can be combined: soft-CCD is applied first by the constraints solver.
+    /// Regular CCD only clamps the motion of the bodies that still move fast enough to
+    /// tunnel after that.
     pub fn set_soft_ccd_prediction(&mut self, prediction_distance: Real) {
         self.ccd.soft_ccd_prediction = prediction_distance;
     }
@@ -666,14 +811,33 @@ impl RigidBody {
         }
     }
 
+    /// Removes from this rigid-body all the colliders for which `f` returns `false`.
+    pub(crate) fn retain_colliders_internal(&mut self, mut f: impl FnMut(ColliderHandle) -> bool) {
+        let num_colliders = self.colliders.0.len();
+        self.colliders.0.retain(|handle| f(*handle));
+
+        if self.colliders.0.len() != num_colliders {
+            self.changes.set(RigidBodyChanges::COLLIDERS, true);
+        }
+    }
+
     /// Put this rigid body to sleep.
     ///
     /// A sleeping body no longer moves and is no longer simulated by the physics engine unless
     /// it is waken up. It can be woken manually with `self.wake_up` or automatically due to
     /// external forces like contacts.
+    ///
+    /// This zeroes the velocities of the rigid-body as-is, so its contacts keep any residual
+    /// penetration. See [`IslandManager::force_sleep`](crate::dynamics::IslandManager::force_sleep)
+    /// to put a whole island to sleep after settling its contacts.
     pub fn sleep(&mut self) {
+        if !self.activation.sleeping {
+            self.changes.insert(RigidBodyChanges::SLEEP);
+        }
+
         self.activation.sleep();
         self.vels = RigidBodyVelocity::zero();
+        self.vels_before_solve = RigidBodyVelocity::zero();
     }
 
     /// Wakes up this rigid body if it is sleeping.
@@ -702,6 +866,17 @@ impl RigidBody {
         !self.vels.linvel.is_zero() || !self.vels.angvel.is_zero()
     }
 
+    /// The velocities of this rigid-body during the last step, after the forces (including
+    /// gravity) were integrated but before the constraints (contacts and joints) were solved.
+    ///
+    /// Comparing it with the velocities after the step, e.g., when a collision event is
+    /// received, gives the change of velocity caused by impacts. This is updated at each step
+    /// simulating this dynamic rigid-body. It keeps its last value while the rigid-body isn’t
+    /// simulated, and is zeroed when it is put to sleep.
+    pub fn velocity_before_solve(&self) -> &RigidBodyVelocity {
+        &self.vels_before_solve
+    }
+
     /// The linear velocity of this rigid-body.
     pub fn linvel(&self) -> &Vector<Real> {
         &self.vels.linvel
@@ -797,6 +972,8 @@ impl RigidBody {
     }
 
     /// Sets the translational part of this rigid-body's position.
+    ///
+    /// See [`Self::set_position`] for details on how this affects existing contacts.
     #[inline]
     pub fn set_translation(&mut self, translation: Vector<Real>, wake_up: bool) {
         if self.pos.position.translation.vector != translation
@@ -849,6 +1026,10 @@ impl RigidBody {
     ///
     /// If `wake_up` is `true` then the rigid-body will be woken up if it was
     /// put to sleep because it did not move for a while.
+    ///
+    /// Contact points are cached in the local-space of each collider, so the contacts
+    /// between rigid-bodies teleported together remain valid and keep warmstarting the
+    /// constraints solver. Contacts with rigid-bodies that didn’t move are recomputed.
     pub fn set_position(&mut self, pos: Isometry<Real>, wake_up: bool) {
         if self.pos.position != pos || self.pos.next_position != pos {
             self.changes.insert(RigidBodyChanges::POSITION);
@@ -1068,6 +1249,26 @@ impl RigidBody {
         self.apply_torque_impulse(torque_impulse, wake_up);
     }
 
+    /// The gravitational acceleration applied to this rigid-body during the last timestep.
+    ///
+    /// This is the gravity given to the physics pipeline multiplied by the gravity scale of this
+    /// rigid-body, plus the acceleration of the gravity well attracting it (see
+    /// [`GravityWellSet`](crate::dynamics::GravityWellSet)) and the mass-independent accelerations
+    /// of the force fields it is in (see
+    /// [`ForceField::gravity_on_body`](crate::geometry::ForceField::gravity_on_body)). This can be
+    /// used, e.g., to orient a character relative to the gravity it actually experiences.
+    ///
+    /// It is recomputed at each timestep for the awake rigid-bodies, so a sleeping rigid-body keeps
+    /// the effective gravity of the last timestep it was awake. Returns zero if the rigid-body
+    /// isn’t dynamic.
+    pub fn effective_gravity(&self) -> Vector<Real> {
+        if self.body_type == RigidBodyType::Dynamic {
+            self.forces.effective_gravity
+        } else {
+            Vector::zeros()
+        }
+    }
+
     /// Retrieves the constant force(s) that the user has added to the body.
     ///
     /// Returns zero if the rigid-body isn’t dynamic.
@@ -1097,6 +1298,43 @@ impl RigidBody {
         self.vels.velocity_at_point(point, &self.mprops.world_com)
     }
 
+    /// The inverse mass of this rigid-body along each world-space axis, as used by the
+    /// constraints solver.
+    ///
+    /// This is zero along the axes with locked translations, and along every axis for
+    /// non-dynamic rigid-bodies, which the solver treats as having an infinite mass. Dominance
+    /// depends on the other body of a constraint and isn’t taken into account, see
+    /// [`constraint_effective_mass`].
+    pub fn effective_inv_mass(&self) -> Vector<Real> {
+        if self.is_dynamic() {
+            self.mprops.effective_inv_mass
+        } else {
+            Vector::zeros()
+        }
+    }
+
+    /// The square root of the world-space inverse angular inertia tensor of this rigid-body, as
+    /// used by the constraints solver.
+    ///
+    /// This takes locked rotations into account, and is zero for non-dynamic rigid-bodies. For a
+    /// constraint applying an angular impulse `torque_dir`, the contribution of this body to the
+    /// inverse effective mass is `|sqrt_ii * torque_dir|²` (`(sqrt_ii * torque_dir)²` in 2D).
+    pub fn effective_world_inv_inertia_sqrt(&self) -> AngularInertia<Real> {
+        if self.is_dynamic() {
+            self.mprops.effective_world_inv_inertia_sqrt
+        } else {
+            AngularInertia::zero()
+        }
+    }
+
+    /// The world-space inverse angular inertia tensor of this rigid-body, as used by the
+    /// constraints solver.
+    ///
+    /// This is the square of [`Self::effective_world_inv_inertia_sqrt`].
+    pub fn effective_world_inv_inertia(&self) -> AngularInertia<Real> {
+        self.effective_world_inv_inertia_sqrt().squared()
+    }
+
     /// The kinetic energy of this body.
     pub fn kinetic_energy(&self) -> Real {
         self.vels.kinetic_energy(&self.mprops)
@@ -1118,6 +1356,77 @@ impl RigidBody {
     }
 }
 
+/// The effective mass of a constraint between `body1` and `body2`, applying opposite impulses
+/// along `dir` at the world-space points `point1` and `point2`.
+///
+/// This is the mass the constraints solver uses for such a constraint, e.g., the normal part of
+/// a contact: applying an impulse `effective_mass * dvel` along `dir` cancels the relative
+/// velocity `dvel` of the two points along `dir` (assuming `dir` is a unit vector). It is computed
+/// from [`RigidBody::effective_inv_mass`] and [`RigidBody::effective_world_inv_inertia_sqrt`],
+/// and takes the dominance of the bodies into account: a body with a larger
+/// [`RigidBody::effective_dominance_group`] than the other acts as if it had an infinite mass.
+///
+/// Returns zero if neither body can be moved by the constraint.
+pub fn constraint_effective_mass(
+    body1: &RigidBody,
+    point1: &Point<Real>,
+    body2: &RigidBody,
+    point2: &Point<Real>,
+    dir: &Vector<Real>,
+) -> Real {
+    let inv_mass_part = |body: &RigidBody, point: &Point<Real>| {
+        let gcross = body
+            .effective_world_inv_inertia_sqrt()
+            .transform_vector((point - body.center_of_mass()).gcross(*dir));
+        dir.dot(&body.effective_inv_mass().component_mul(dir)) + gcross.gdot(gcross)
+    };
+
+    let dominance1 = body1.effective_dominance_group();
+    let dominance2 = body2.effective_dominance_group();
+    let mut inv_mass = 0.0;
+
+    if dominance1 <= dominance2 {
+        inv_mass += inv_mass_part(body1, point1);
+    }
+    if dominance2 <= dominance1 {
+        inv_mass += inv_mass_part(body2, point2);
+    }
+
+    crate::utils::inv(inv_mass)
+}
+
+#[cfg(feature = "glam")]
+impl RigidBody {
+    /// The world-space position of this rigid-body, as a `glam` translation and rotation.
+    pub fn position_glam(&self) -> (GlamVector, GlamRotation) {
+        self.pos.position.into()
+    }
+
+    /// The translational part of this rigid-body's position, as a `glam` vector.
+    pub fn translation_glam(&self) -> GlamVector {
+        self.pos.position.translation.vector.into()
+    }
+
+    /// Sets the translational part of this rigid-body's position from a `glam` vector.
+    ///
+    /// See [`Self::set_translation`].
+    pub fn set_translation_glam(&mut self, translation: GlamVector, wake_up: bool) {
+        self.set_translation(translation.into(), wake_up)
+    }
+
+    /// The linear velocity of this rigid-body, as a `glam` vector.
+    pub fn linvel_glam(&self) -> GlamVector {
+        self.vels.linvel.into()
+    }
+
+    /// Sets the linear velocity of this rigid-body from a `glam` vector.
+    ///
+    /// See [`Self::set_linvel`].
+    pub fn set_linvel_glam(&mut self, linvel: GlamVector, wake_up: bool) {
+        self.set_linvel(linvel.into(), wake_up)
+    }
+}
+
 /// A builder for rigid-bodies.
 #[derive(Clone, Debug, PartialEq)]
 #[must_use = "Builder functions return the updated builder"]
@@ -1134,6 +1443,12 @@ pub struct RigidBodyBuilder {
     pub linear_damping: Real,
     /// Damping factor for gradually slowing down the angular motion of the rigid-body, `0.0` by default.
     pub angular_damping: Real,
+    /// Does the rigid-body to be built use the global linear damping from the integration
+    /// parameters instead of [`Self::linear_damping`]? `true` unless a linear damping was set.
+    pub inherit_linear_damping: bool,
+    /// Does the rigid-body to be built use the global angular damping from the integration
+    /// parameters instead of [`Self::angular_damping`]? `true` unless an angular damping was set.
+    pub inherit_angular_damping: bool,
     body_type: RigidBodyType,
     mprops_flags: LockedAxes,
     /// The additional mass-properties of the rigid-body being built. See [`RigidBodyBuilder::additional_mass_properties`] for more information.
@@ -1156,6 +1471,15 @@ pub struct RigidBodyBuilder {
     /// It is a generally cheaper variant of regular CCD (that can be enabled with
     /// [`RigidBodyBuilder::ccd_enabled`] since it relies on predictive constraints instead of
     /// shape-cast and substeps.
+    ///
+    /// The prediction distance of every contact pair involving this rigid-body is extended by
+    /// the distance it travels during the timestep (its linear velocity times `dt`), capped
+    /// to this distance. Speculative contacts are then generated early enough for the
+    /// constraints solver to stop the body before it tunnels.
+    ///
+    /// Both kinds of CCD can be combined: soft-CCD is applied first by the constraints solver.
+    /// Regular CCD only clamps the motion of the bodies that still move fast enough to
+    /// tunnel after that.
     pub soft_ccd_prediction: Real,
     /// The dominance group of the rigid-body to be built.
     pub dominance_group: i8,
@@ -1168,6 +1492,10 @@ pub struct RigidBodyBuilder {
     ///
     /// See [`RigidBody::set_additional_solver_iterations`] for additional information.
     pub additional_solver_iterations: usize,
+    /// The step group of the rigid-body to be built.
+    ///
+    /// See [`RigidBody::set_step_group`] for additional information.
+    pub step_group: u8,
 }
 
 impl RigidBodyBuilder {
@@ -1180,6 +1508,8 @@ impl RigidBodyBuilder {
             gravity_scale: 1.0,
             linear_damping: 0.0,
             angular_damping: 0.0,
+            inherit_linear_damping: true,
+            inherit_angular_damping: true,
             body_type,
             mprops_flags: LockedAxes::empty(),
             additional_mass_properties: RigidBodyAdditionalMassProps::default(),
@@ -1191,6 +1521,7 @@ impl RigidBodyBuilder {
             enabled: true,
             user_data: 0,
             additional_solver_iterations: 0,
+            step_group: 0,
         }
     }
 
@@ -1235,42 +1566,110 @@ impl RigidBodyBuilder {
     ///
     /// See [`RigidBody::set_additional_solver_iterations`] for additional information.
     pub fn additional_solver_iterations(mut self, additional_iterations: usize) -> Self {
+        self.set_additional_solver_iterations(additional_iterations);
+        self
+    }
+
+    /// Borrowing variant of [`Self::additional_solver_iterations`].
+    pub fn set_additional_solver_iterations(&mut self, additional_iterations: usize) -> &mut Self {
         self.additional_solver_iterations = additional_iterations;
         self
     }
 
     /// Sets the scale applied to the gravity force affecting the rigid-body to be created.
     pub fn gravity_scale(mut self, scale_factor: Real) -> Self {
+        self.set_gravity_scale(scale_factor);
+        self
+    }
+
+    /// Borrowing variant of [`Self::gravity_scale`].
+    pub fn set_gravity_scale(&mut self, scale_factor: Real) -> &mut Self {
         self.gravity_scale = scale_factor;
         self
     }
 
     /// Sets the dominance group of this rigid-body.
     pub fn dominance_group(mut self, group: i8) -> Self {
+        self.set_dominance_group(group);
+        self
+    }
+
+    /// Borrowing variant of [`Self::dominance_group`].
+    pub fn set_dominance_group(&mut self, group: i8) -> &mut Self {
         self.dominance_group = group;
         self
     }
 
+    /// Sets the step group of this rigid-body.
+    ///
+    /// See [`RigidBody::set_step_group`] for additional information.
+    pub fn step_group(mut self, step_group: u8) -> Self {
+        self.set_step_group(step_group);
+        self
+    }
+
+    /// Borrowing variant of [`Self::step_group`].
+    pub fn set_step_group(&mut self, step_group: u8) -> &mut Self {
+        self.step_group = step_group;
+        self
+    }
+
     /// Sets the initial translation of the rigid-body to be created.
     pub fn translation(mut self, translation: Vector<Real>) -> Self {
+        self.set_translation(translation);
+        self
+    }
+
+    /// Borrowing variant of [`Self::translation`].
+    pub fn set_translation(&mut self, translation: Vector<Real>) -> &mut Self {
         self.position.translation.vector = translation;
         self
     }
 
     /// Sets the initial orientation of the rigid-body to be created.
+    ///
+    /// In 2D, `angle` is a plain angle in radians.
+    #[cfg_attr(
+        all(feature = "dim2", feature = "f32"),
+        doc = r#"
+```
+# use rapier2d::prelude::*;
+let body = RigidBodyBuilder::dynamic().rotation(0.5).build();
+assert!((body.rotation().angle() - 0.5).abs() < 1.0e-6);
+```
+"#
+    )]
     pub fn rotation(mut self, angle: AngVector<Real>) -> Self {
+        self.set_rotation(angle);
+        self
+    }
+
+    /// Borrowing variant of [`Self::rotation`].
+    pub fn set_rotation(&mut self, angle: AngVector<Real>) -> &mut Self {
         self.position.rotation = Rotation::new(angle);
         self
     }
 
     /// Sets the initial position (translation and orientation) of the rigid-body to be created.
     pub fn position(mut self, pos: Isometry<Real>) -> Self {
+        self.set_position(pos);
+        self
+    }
+
+    /// Borrowing variant of [`Self::position`].
+    pub fn set_position(&mut self, pos: Isometry<Real>) -> &mut Self {
         self.position = pos;
         self
     }
 
     /// An arbitrary user-defined 128-bit integer associated to the rigid-bodies built by this builder.
     pub fn user_data(mut self, data: u128) -> Self {
+        self.set_user_data(data);
+        self
+    }
+
+    /// Borrowing variant of [`Self::user_data`].
+    pub fn set_user_data(&mut self, data: u128) -> &mut Self {
         self.user_data = data;
         self
     }
@@ -1289,6 +1688,12 @@ impl RigidBodyBuilder {
     /// mass-properties of your rigid-body, don't attach colliders to it, or
     /// only attach colliders with densities equal to zero.
     pub fn additional_mass_properties(mut self, mprops: MassProperties) -> Self {
+        self.set_additional_mass_properties(mprops);
+        self
+    }
+
+    /// Borrowing variant of [`Self::additional_mass_properties`].
+    pub fn set_additional_mass_properties(&mut self, mprops: MassProperties) -> &mut Self {
         self.additional_mass_properties = RigidBodyAdditionalMassProps::MassProps(mprops);
         self
     }
@@ -1309,18 +1714,36 @@ impl RigidBodyBuilder {
     /// # Parameters
     /// * `mass`- The mass that will be added to the created rigid-body.
     pub fn additional_mass(mut self, mass: Real) -> Self {
+        self.set_additional_mass(mass);
+        self
+    }
+
+    /// Borrowing variant of [`Self::additional_mass`].
+    pub fn set_additional_mass(&mut self, mass: Real) -> &mut Self {
         self.additional_mass_properties = RigidBodyAdditionalMassProps::Mass(mass);
         self
     }
 
     /// Sets the axes along which this rigid-body cannot translate or rotate.
     pub fn locked_axes(mut self, locked_axes: LockedAxes) -> Self {
+        self.set_locked_axes(locked_axes);
+        self
+    }
+
+    /// Borrowing variant of [`Self::locked_axes`].
+    pub fn set_locked_axes(&mut self, locked_axes: LockedAxes) -> &mut Self {
         self.mprops_flags = locked_axes;
         self
     }
 
     /// Prevents this rigid-body from translating because of forces.
     pub fn lock_translations(mut self) -> Self {
+        self.set_lock_translations();
+        self
+    }
+
+    /// Borrowing variant of [`Self::lock_translations`].
+    pub fn set_lock_translations(&mut self) -> &mut Self {
         self.mprops_flags.set(LockedAxes::TRANSLATION_LOCKED, true);
         self
     }
@@ -1332,6 +1755,22 @@ impl RigidBodyBuilder {
         allow_translations_y: bool,
         #[cfg(feature = "dim3")] allow_translations_z: bool,
     ) -> Self {
+        self.set_enabled_translations(
+            allow_translations_x,
+            allow_translations_y,
+            #[cfg(feature = "dim3")]
+            allow_translations_z,
+        );
+        self
+    }
+
+    /// Borrowing variant of [`Self::enabled_translations`].
+    pub fn set_enabled_translations(
+        &mut self,
+        allow_translations_x: bool,
+        allow_translations_y: bool,
+        #[cfg(feature = "dim3")] allow_translations_z: bool,
+    ) -> &mut Self {
         self.mprops_flags
             .set(LockedAxes::TRANSLATION_LOCKED_X, !allow_translations_x);
         self.mprops_flags
@@ -1359,7 +1798,28 @@ impl RigidBodyBuilder {
     }
 
     /// Prevents this rigid-body from rotating because of forces.
+    ///
+    /// This is typically used for characters, e.g., the fixed-rotation capsule of a 2D platformer.
+    #[cfg_attr(
+        all(feature = "dim2", feature = "f32"),
+        doc = r#"
+```
+# use rapier2d::prelude::*;
+# let mut world = PhysicsWorld::default();
+let character = RigidBodyBuilder::dynamic().translation(vector![0.0, 1.0]).lock_rotations();
+let character = world.insert_body(character);
+world.insert_collider_with_parent(ColliderBuilder::capsule_y(0.5, 0.25), character);
+```
+"#
+    )]
+    #[doc(alias = "fixed_rotation")]
     pub fn lock_rotations(mut self) -> Self {
+        self.set_lock_rotations();
+        self
+    }
+
+    /// Borrowing variant of [`Self::lock_rotations`].
+    pub fn set_lock_rotations(&mut self) -> &mut Self {
         self.mprops_flags.set(LockedAxes::ROTATION_LOCKED_X, true);
         self.mprops_flags.set(LockedAxes::ROTATION_LOCKED_Y, true);
         self.mprops_flags.set(LockedAxes::ROTATION_LOCKED_Z, true);
@@ -1374,6 +1834,18 @@ impl RigidBodyBuilder {
         allow_rotations_y: bool,
         allow_rotations_z: bool,
     ) -> Self {
+        self.set_enabled_rotations(allow_rotations_x, allow_rotations_y, allow_rotations_z);
+        self
+    }
+
+    /// Borrowing variant of [`Self::enabled_rotations`].
+    #[cfg(feature = "dim3")]
+    pub fn set_enabled_rotations(
+        &mut self,
+        allow_rotations_x: bool,
+        allow_rotations_y: bool,
+        allow_rotations_z: bool,
+    ) -> &mut Self {
         self.mprops_flags
             .set(LockedAxes::ROTATION_LOCKED_X, !allow_rotations_x);
         self.mprops_flags
@@ -1400,7 +1872,14 @@ impl RigidBodyBuilder {
     /// The higher the linear damping factor is, the more quickly the rigid-body
     /// will slow-down its translational movement.
     pub fn linear_damping(mut self, factor: Real) -> Self {
+        self.set_linear_damping(factor);
+        self
+    }
+
+    /// Borrowing variant of [`Self::linear_damping`].
+    pub fn set_linear_damping(&mut self, factor: Real) -> &mut Self {
         self.linear_damping = factor;
+        self.inherit_linear_damping = false;
         self
     }
 
@@ -1409,24 +1888,49 @@ impl RigidBodyBuilder {
     /// The higher the angular damping factor is, the more quickly the rigid-body
     /// will slow-down its rotational movement.
     pub fn angular_damping(mut self, factor: Real) -> Self {
+        self.set_angular_damping(factor);
+        self
+    }
+
+    /// Borrowing variant of [`Self::angular_damping`].
+    pub fn set_angular_damping(&mut self, factor: Real) -> &mut Self {
         self.angular_damping = factor;
+        self.inherit_angular_damping = false;
         self
     }
 
     /// Sets the initial linear velocity of the rigid-body to be created.
     pub fn linvel(mut self, linvel: Vector<Real>) -> Self {
+        self.set_linvel(linvel);
+        self
+    }
+
+    /// Borrowing variant of [`Self::linvel`].
+    pub fn set_linvel(&mut self, linvel: Vector<Real>) -> &mut Self {
         self.linvel = linvel;
         self
     }
 
     /// Sets the initial angular velocity of the rigid-body to be created.
     pub fn angvel(mut self, angvel: AngVector<Real>) -> Self {
+        self.set_angvel(angvel);
+        self
+    }
+
+    /// Borrowing variant of [`Self::angvel`].
+    pub fn set_angvel(&mut self, angvel: AngVector<Real>) -> &mut Self {
         self.angvel = angvel;
         self
     }
 
     /// Sets whether the rigid-body to be created can sleep if it reaches a dynamic equilibrium.
     pub fn can_sleep(mut self, can_sleep: bool) -> Self {
+        self.set_can_sleep(can_sleep);
+        self
+    }
+
+    /// Borrowing variant of [`Self::can_sleep`].
+    pub fn set_can_sleep(&mut self, can_sleep: bool) -> &mut Self {
         self.can_sleep = can_sleep;
         self
     }
@@ -1435,6 +1939,12 @@ impl RigidBodyBuilder {
     ///
     /// CCD prevents tunneling, but may still allow limited interpenetration of colliders.
     pub fn ccd_enabled(mut self, enabled: bool) -> Self {
+        self.set_ccd_enabled(enabled);
+        self
+    }
+
+    /// Borrowing variant of [`Self::ccd_enabled`].
+    pub fn set_ccd_enabled(&mut self, enabled: bool) -> &mut Self {
         self.ccd_enabled = enabled;
         self
     }
@@ -1449,19 +1959,46 @@ impl RigidBodyBuilder {
     /// It is a generally cheaper variant of regular CCD (that can be enabled with
     /// [`RigidBodyBuilder::ccd_enabled`] since it relies on predictive constraints instead of
     /// shape-cast and substeps.
+    ///
+    /// The prediction distance of every contact pair involving this rigid-body is extended by
+    /// the distance it travels during the timestep (its linear velocity times `dt`), capped
+    /// to `prediction_distance`. Speculative contacts are then generated early enough for the
+    /// constraints solver to stop the body before it tunnels.
+    ///
+    /// Both kinds of CCD can be combined: soft-CCD is applied first by the constraints solver.
+    /// Regular CCD only clamps the motion of the bodies that still move fast enough to
+    /// tunnel after that.
     pub fn soft_ccd_prediction(mut self, prediction_distance: Real) -> Self {
+        self.set_soft_ccd_prediction(prediction_distance);
+        self
+    }
+
+    /// Borrowing variant of [`Self::soft_ccd_prediction`].
+    pub fn set_soft_ccd_prediction(&mut self, prediction_distance: Real) -> &mut Self {
         self.soft_ccd_prediction = prediction_distance;
         self
     }
 
     /// Sets whether the rigid-body is to be created asleep.
     pub fn sleeping(mut self, sleeping: bool) -> Self {
+        self.set_sleeping(sleeping);
+        self
+    }
+
+    /// Borrowing variant of [`Self::sleeping`].
+    pub fn set_sleeping(&mut self, sleeping: bool) -> &mut Self {
         self.sleeping = sleeping;
         self
     }
 
     /// Enable or disable the rigid-body after its creation.
     pub fn enabled(mut self, enabled: bool) -> Self {
+        self.set_enabled(enabled);
+        self
+    }
+
+    /// Borrowing variant of [`Self::enabled`].
+    pub fn set_enabled(&mut self, enabled: bool) -> &mut Self {
         self.enabled = enabled;
         self
     }
@@ -1476,6 +2013,7 @@ impl RigidBodyBuilder {
         rb.body_type = self.body_type;
         rb.user_data = self.user_data;
         rb.additional_solver_iterations = self.additional_solver_iterations;
+        rb.set_step_group(self.step_group);
 
         if self.additional_mass_properties
             != RigidBodyAdditionalMassProps::MassProps(MassProperties::zero())
@@ -1487,6 +2025,8 @@ impl RigidBodyBuilder {
         rb.mprops.flags = self.mprops_flags;
         rb.damping.linear_damping = self.linear_damping;
         rb.damping.angular_damping = self.angular_damping;
+        rb.inherit_linear_damping = self.inherit_linear_damping;
+        rb.inherit_angular_damping = self.inherit_angular_damping;
         rb.forces.gravity_scale = self.gravity_scale;
         rb.dominance = RigidBodyDominance(self.dominance_group);
         rb.enabled = self.enabled;
@@ -1498,16 +2038,403 @@ impl RigidBodyBuilder {
         }
 
         if !self.can_sleep {
-            rb.activation.normalized_linear_threshold = -1.0;
-            rb.activation.angular_threshold = -1.0;
+            rb.activation.normalized_linear_threshold = Some(-1.0);
+            rb.activation.angular_threshold = Some(-1.0);
         }
 
         rb
     }
 }
 
+#[cfg(feature = "glam")]
+impl RigidBodyBuilder {
+    /// Sets the initial translation of the rigid-body to be created from a `glam` vector.
+    pub fn translation_glam(self, translation: GlamVector) -> Self {
+        self.translation(translation.into())
+    }
+
+    /// Sets the initial position of the rigid-body to be created from a `glam` translation and rotation.
+    pub fn position_glam(self, translation: GlamVector, rotation: GlamRotation) -> Self {
+        self.position((translation, rotation).into())
+    }
+
+    /// Sets the initial linear velocity of the rigid-body to be created from a `glam` vector.
+    pub fn linvel_glam(self, linvel: GlamVector) -> Self {
+        self.linvel(linvel.into())
+    }
+}
+
 impl From<RigidBodyBuilder> for RigidBody {
     fn from(val: RigidBodyBuilder) -> RigidBody {
         val.build()
     }
 }
+
+impl From<&RigidBody> for RigidBodyBuilder {
+    /// Initializes a builder with the configuration of an existing rigid-body.
+    ///
+    /// This can be used to spawn variants of an existing rigid-body. Its attached colliders,
+    /// joints, and accumulated forces are not part of the configuration.
+    fn from(rb: &RigidBody) -> RigidBodyBuilder {
+        Self {
+            position: rb.pos.position,
+            linvel: rb.vels.linvel,
+            angvel: rb.vels.angvel,
+            gravity_scale: rb.forces.gravity_scale,
+            linear_damping: rb.damping.linear_damping,
+            angular_damping: rb.damping.angular_damping,
+            inherit_linear_damping: rb.inherit_linear_damping,
+            inherit_angular_damping: rb.inherit_angular_damping,
+            body_type: rb.body_type,
+            mprops_flags: rb.mprops.flags,
+            additional_mass_properties: rb
+                .mprops
+                .additional_local_mprops
+                .as_deref()
+                .copied()
+                .unwrap_or_default(),
+            can_sleep: rb
+                .activation
+                .normalized_linear_threshold
+                .is_none_or(|t| t >= 0.0)
+                || rb.activation.angular_threshold.is_none_or(|t| t >= 0.0),
+            sleeping: rb.activation.sleeping,
+            ccd_enabled: rb.ccd.ccd_enabled,
+            soft_ccd_prediction: rb.ccd.soft_ccd_prediction,
+            dominance_group: rb.dominance.0,
+            enabled: rb.enabled,
+            user_data: rb.user_data,
+            additional_solver_iterations: rb.additional_solver_iterations,
+            step_group: rb.step_group,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::SimdDot;
+    use approx::assert_relative_eq;
+    use na::RealField;
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn glam_round_trip() {
+        use super::RigidBodyBuilder;
+        use crate::math::{GlamRotation, GlamVector, Isometry, Real, Rotation, Vector};
+
+        let translation = Vector::from_fn(|i, _| (i + 1) as Real * 1.5);
+        #[cfg(feature = "dim2")]
+        let rotation = Rotation::new(0.7);
+        #[cfg(feature = "dim3")]
+        let rotation = Rotation::from_euler_angles(0.1, 0.7, -1.3);
+        let pos = Isometry::from_parts(translation.into(), rotation);
+
+        // Vectors convert losslessly both ways.
+        let glam_translation: GlamVector = translation.into();
+        assert_eq!(glam_translation.to_array(), translation.as_slice());
+        assert_eq!(Vector::from(glam_translation), translation);
+
+        // Rotations keep their components order: glam stores quaternions as
+        // `[x, y, z, w]` and nalgebra as `[i, j, k, w]`.
+        let (glam_translation, glam_rotation): (GlamVector, GlamRotation) = pos.into();
+        assert_eq!(Vector::from(glam_translation), translation);
+        // NOTE: 2D rotations go through their angle, which is only exact up to rounding errors.
+        #[cfg(feature = "dim2")]
+        approx::assert_relative_eq!(glam_rotation, rotation.angle(), epsilon = 1.0e-6);
+        #[cfg(feature = "dim3")]
+        {
+            assert_eq!(glam_rotation.to_array(), rotation.coords.as_slice());
+            // NOTE: nalgebra re-normalizes quaternions coming from glam.
+            approx::assert_relative_eq!(Rotation::from(glam_rotation), rotation, epsilon = 1.0e-6);
+
+            // Both libraries must agree on the rotated vector.
+            let v = Vector::new(0.3, -2.0, 5.0);
+            let rotated = glam_rotation * GlamVector::from(v);
+            approx::assert_relative_eq!(Vector::from(rotated), rotation * v, epsilon = 1.0e-5);
+        }
+
+        // Convenience accessors on rigid-bodies.
+        let mut rb = RigidBodyBuilder::dynamic()
+            .position_glam(glam_translation, glam_rotation)
+            .linvel_glam(glam_translation * 2.0)
+            .build();
+        approx::assert_relative_eq!(*rb.position(), pos, epsilon = 1.0e-6);
+        assert_eq!(rb.position_glam().0, glam_translation);
+        approx::assert_relative_eq!(Isometry::from(rb.position_glam()), pos, epsilon = 1.0e-6);
+        assert_eq!(rb.linvel_glam(), glam_translation * 2.0);
+
+        rb.set_translation_glam(GlamVector::ONE, true);
+        assert_eq!(*rb.translation(), Vector::repeat(1.0));
+        assert_eq!(rb.translation_glam(), GlamVector::ONE);
+
+        rb.set_linvel_glam(GlamVector::ZERO, true);
+        assert_eq!(*rb.linvel(), Vector::zeros());
+
+        let rb = RigidBodyBuilder::fixed()
+            .translation_glam(glam_translation)
+            .build();
+        assert_eq!(*rb.translation(), translation);
+    }
+
+    #[cfg(feature = "mint")]
+    #[test]
+    fn mint_round_trip() {
+        use crate::math::{Point, Real, Vector};
+
+        let v = Vector::from_fn(|i, _| (i + 1) as Real * 1.5);
+        #[cfg(feature = "dim2")]
+        let mint_v: mint::Vector2<Real> = v.into();
+        #[cfg(feature = "dim3")]
+        let mint_v: mint::Vector3<Real> = v.into();
+        assert_eq!(Vector::from(mint_v), v);
+
+        let p = Point::from(v);
+        #[cfg(feature = "dim2")]
+        let mint_p: mint::Point2<Real> = p.into();
+        #[cfg(feature = "dim3")]
+        let mint_p: mint::Point3<Real> = p.into();
+        assert_eq!(Point::from(mint_p), p);
+
+        #[cfg(feature = "dim3")]
+        {
+            use crate::math::Rotation;
+
+            // mint stores the vector part in `v` and the scalar part in `s`.
+            let rotation = Rotation::from_euler_angles(0.1, 0.7, -1.3);
+            let mint_q: mint::Quaternion<Real> = rotation.into();
+            assert_eq!(mint_q.v.x, rotation.i);
+            assert_eq!(mint_q.v.y, rotation.j);
+            assert_eq!(mint_q.v.z, rotation.k);
+            assert_eq!(mint_q.s, rotation.w);
+            assert_eq!(Rotation::new_unchecked(mint_q.into()), rotation);
+        }
+    }
+
+    #[test]
+    fn compound_mass_properties_include_collider_offsets() {
+        use crate::prelude::*;
+
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        let single = world.insert_body(RigidBodyBuilder::dynamic());
+        let compound =
+            world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 5.0));
+
+        // A long cuboid, and the same cuboid made of two halves. The halves are built rotated by
+        // 90 degrees then rotated back, so their inertia tensors must be rotated too.
+        #[cfg(feature = "dim2")]
+        let (long_cuboid, half_cuboid, rotation) = (
+            ColliderBuilder::cuboid(2.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 1.0),
+            Real::frac_pi_2(),
+        );
+        #[cfg(feature = "dim3")]
+        let (long_cuboid, half_cuboid, rotation) = (
+            ColliderBuilder::cuboid(2.0, 0.5, 0.25),
+            ColliderBuilder::cuboid(0.25, 0.5, 1.0),
+            Vector::y() * Real::frac_pi_2(),
+        );
+        world.insert_collider_with_parent(long_cuboid, single);
+        let halves = [-1.0, 1.0].map(|x| {
+            let half = half_cuboid
+                .clone()
+                .translation(Vector::x() * x)
+                .rotation(rotation);
+            world.insert_collider_with_parent(half, compound)
+        });
+
+        // Applies the same torque impulse to both bodies and returns their angular velocities.
+        let angular_responses = |world: &mut PhysicsWorld| {
+            world.step();
+            #[cfg(feature = "dim2")]
+            let torque_impulse = 1.0;
+            #[cfg(feature = "dim3")]
+            let torque_impulse = Vector::new(0.3, 0.5, 1.0);
+
+            let responses = [single, compound].map(|handle| {
+                let rb = &mut world.bodies[handle];
+                rb.set_angvel(na::zero(), false);
+                rb.apply_torque_impulse(torque_impulse, false);
+                rb.vels.angvel
+            });
+            (responses[0], responses[1])
+        };
+
+        let (single_angvel, compound_angvel) = angular_responses(&mut world);
+        assert_relative_eq!(
+            world.bodies[single].mass(),
+            world.bodies[compound].mass(),
+            max_relative = 1.0e-5
+        );
+        assert_relative_eq!(single_angvel, compound_angvel, max_relative = 0.01);
+
+        // Moving the halves to the center of the body reduces its angular inertia.
+        for half in halves {
+            world.colliders[half].set_translation_wrt_parent(Vector::zeros());
+        }
+        let (single_angvel, compound_angvel) = angular_responses(&mut world);
+        assert!(compound_angvel.gdot(compound_angvel) > 1.5 * single_angvel.gdot(single_angvel));
+
+        // Removing one half divides the mass by two.
+        world.remove_collider(halves[0]);
+        world.step();
+        assert_relative_eq!(
+            world.bodies[compound].mass(),
+            world.bodies[single].mass() / 2.0,
+            max_relative = 1.0e-5
+        );
+    }
+
+    #[test]
+    fn builders_from_templates() {
+        use crate::prelude::*;
+
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        let original = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .linvel(Vector::x() * 2.0)
+                .gravity_scale(0.5)
+                .linear_damping(0.3)
+                .lock_rotations()
+                .can_sleep(false)
+                .ccd_enabled(true)
+                .additional_mass(3.0)
+                .user_data(42),
+        );
+        let original_collider = world.insert_collider_with_parent(
+            ColliderBuilder::ball(0.5)
+                .friction(0.1)
+                .restitution(0.7)
+                .density(2.0)
+                .translation(Vector::y())
+                .active_events(ActiveEvents::COLLISION_EVENTS),
+            original,
+        );
+
+        // Clone the configuration of the existing objects, and tweak each instance.
+        let body_template = RigidBodyBuilder::from(&world.bodies[original]);
+        let collider_template = ColliderBuilder::from(&world.colliders[original_collider]);
+        let variants: Vec<_> = (0..100)
+            .map(|i| {
+                let mut body = body_template.clone();
+                body.set_translation(Vector::x() * (i as Real * 2.0 + 2.0));
+                if i % 2 == 0 {
+                    body.set_user_data(i as u128).set_gravity_scale(1.0);
+                }
+                let body = world.insert_body(body);
+
+                let mut collider = collider_template.clone();
+                if i % 3 == 0 {
+                    collider.set_friction(i as Real / 100.0);
+                }
+                world.insert_collider_with_parent(collider, body);
+                (i, body)
+            })
+            .collect();
+
+        let rb0 = &world.bodies[original];
+        let co0 = &world.colliders[original_collider];
+        for (i, handle) in variants {
+            let rb = &world.bodies[handle];
+            let co = &world.colliders[rb.colliders()[0]];
+            assert_eq!(rb.translation().x, i as Real * 2.0 + 2.0);
+            assert_eq!(rb.linvel(), rb0.linvel());
+            assert_eq!(rb.linear_damping(), rb0.linear_damping());
+            assert_eq!(rb.locked_axes(), rb0.locked_axes());
+            assert_eq!(rb.is_ccd_enabled(), rb0.is_ccd_enabled());
+            assert_eq!(rb.activation(), rb0.activation());
+            assert_relative_eq!(rb.mass(), rb0.mass());
+            assert_eq!(co.position_wrt_parent(), co0.position_wrt_parent());
+            assert_eq!(co.restitution(), co0.restitution());
+            assert_eq!(co.active_events(), co0.active_events());
+
+            if i % 2 == 0 {
+                assert_eq!(rb.user_data, i as u128);
+                assert_eq!(rb.gravity_scale(), 1.0);
+            } else {
+                assert_eq!(rb.user_data, 42);
+                assert_eq!(rb.gravity_scale(), 0.5);
+            }
+
+            if i % 3 == 0 {
+                assert_eq!(co.friction(), i as Real / 100.0);
+            } else {
+                assert_eq!(co.friction(), 0.1);
+            }
+        }
+    }
+
+    #[test]
+    fn hollow_ball_mass_properties() {
+        use crate::dynamics::MassProperties;
+        use crate::geometry::{Ball, Shape};
+        use crate::math::{Point, Real};
+
+        let (density, outer, inner): (Real, Real, Real) = (2.0, 1.0, 0.8);
+        let solid = MassProperties::from_ball(density, outer);
+        let core = MassProperties::from_ball(density, inner);
+        assert_eq!(Ball::new(outer).mass_properties(density), solid);
+
+        // Subtracting the core gives a hollow ball.
+        let shell = solid - core;
+        #[cfg(feature = "dim2")]
+        let (mass, inertia) = {
+            let mass = density * Real::pi() * (outer.powi(2) - inner.powi(2));
+            (mass, mass * (outer.powi(2) + inner.powi(2)) / 2.0)
+        };
+        #[cfg(feature = "dim3")]
+        let (mass, inertia) = {
+            let mass = density * Real::pi() * 4.0 / 3.0 * (outer.powi(3) - inner.powi(3));
+            let inertia = mass * 2.0 / 5.0 * (outer.powi(5) - inner.powi(5))
+                / (outer.powi(3) - inner.powi(3));
+            (mass, inertia)
+        };
+        assert_relative_eq!(shell.mass(), mass, max_relative = 1.0e-4);
+        assert_relative_eq!(shell.local_com, Point::origin(), epsilon = 1.0e-5);
+        #[cfg(feature = "dim2")]
+        assert_relative_eq!(shell.principal_inertia(), inertia, max_relative = 1.0e-3);
+        #[cfg(feature = "dim3")]
+        for i in 0..3 {
+            assert_relative_eq!(shell.principal_inertia()[i], inertia, max_relative = 1.0e-3);
+        }
+
+        // Adding the core back gives the solid ball.
+        assert_relative_eq!(shell + core, solid, epsilon = 1.0e-3);
+    }
+
+    #[test]
+    fn compound_mass_properties_use_the_parallel_axis_theorem() {
+        use crate::dynamics::MassProperties;
+        use crate::geometry::SharedShape;
+        use crate::math::{Isometry, Point};
+
+        let density = 3.0;
+        let ball = MassProperties::from_ball(density, 0.5);
+        #[cfg(feature = "dim2")]
+        let shift = Isometry::translation(1.0, 0.0);
+        #[cfg(feature = "dim3")]
+        let shift = Isometry::translation(1.0, 0.0, 0.0);
+        let shapes = [
+            (shift, SharedShape::ball(0.5)),
+            (shift.inverse(), SharedShape::ball(0.5)),
+        ];
+        let compound = MassProperties::from_compound(density, &shapes);
+        let sum = ball.transform_by(&shift) + ball.transform_by(&shift.inverse());
+        assert_relative_eq!(compound, sum, epsilon = 1.0e-4);
+        assert_relative_eq!(sum.mass(), 2.0 * ball.mass(), max_relative = 1.0e-5);
+        assert_relative_eq!(sum.local_com, Point::origin(), epsilon = 1.0e-5);
+
+        // Each ball adds `m * d²` to the angular inertia around the z axis.
+        #[cfg(feature = "dim2")]
+        let (inertia, ball_inertia) = (sum.principal_inertia(), ball.principal_inertia());
+        #[cfg(feature = "dim3")]
+        let (inertia, ball_inertia) = (
+            sum.reconstruct_inertia_matrix()[(2, 2)],
+            ball.principal_inertia().z,
+        );
+        assert_relative_eq!(
+            inertia,
+            2.0 * (ball_inertia + ball.mass()),
+            max_relative = 1.0e-4
+        );
+    }
+}
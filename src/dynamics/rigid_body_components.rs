@@ -1,4 +1,4 @@
-use crate::dynamics::MassProperties;
+use crate::dynamics::{IntegrationParameters, MassProperties};
 use crate::geometry::{
     ColliderChanges, ColliderHandle, ColliderMassProps, ColliderParent, ColliderPosition,
     ColliderSet, ColliderShape,
@@ -10,6 +10,44 @@ use crate::parry::partitioning::IndexedData;
 use crate::utils::{SimdAngularInertia, SimdCross, SimdDot};
 use num::Zero;
 
+/// Combines two mass properties expressed in the same local-space.
+///
+/// The inertia tensors are shifted to the combined center of mass with the parallel axis theorem.
+#[cfg(feature = "dim2")]
+fn add_mass_properties(mprops1: MassProperties, mprops2: MassProperties) -> MassProperties {
+    mprops1 + mprops2
+}
+
+/// Combines two mass properties expressed in the same local-space.
+///
+/// The inertia tensors are shifted to the combined center of mass with the parallel axis theorem.
+// NOTE: we don’t rely on the `Add` implementation of `MassProperties` since it adds the
+//       `m * d * dᵀ` term of the parallel axis theorem instead of subtracting it in 3D.
+#[cfg(feature = "dim3")]
+fn add_mass_properties(mprops1: MassProperties, mprops2: MassProperties) -> MassProperties {
+    if mprops1.is_zero() {
+        return mprops2;
+    } else if mprops2.is_zero() {
+        return mprops1;
+    }
+
+    let mass1 = mprops1.mass();
+    let mass2 = mprops2.mass();
+    let mass = mass1 + mass2;
+    let local_com =
+        (mprops1.local_com * mass1 + mprops2.local_com.coords * mass2) * crate::utils::inv(mass);
+
+    let shifted_inertia = |mprops: &MassProperties, mass: Real| {
+        let shift = local_com - mprops.local_com;
+        mprops.reconstruct_inertia_matrix()
+            + (na::Matrix3::from_diagonal_element(shift.norm_squared()) - shift * shift.transpose())
+                * mass
+    };
+    let inertia = shifted_inertia(&mprops1, mass1) + shifted_inertia(&mprops2, mass2);
+
+    MassProperties::with_inertia_matrix(local_com, mass, inertia)
+}
+
 /// The unique handle of a rigid body added to a `RigidBodySet`.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -36,6 +74,12 @@ impl RigidBodyHandle {
     }
 }
 
+impl std::fmt::Display for RigidBodyHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RigidBodyHandle({})", self.0)
+    }
+}
+
 impl IndexedData for RigidBodyHandle {
     fn default() -> Self {
         Self(IndexedData::default())
@@ -64,6 +108,11 @@ pub enum RigidBodyType {
     /// One-way interaction means that a kinematic body can push a dynamic body, but a kinematic body
     /// cannot be pushed by anything. In other words, the trajectory of a kinematic body can only be
     /// modified by the user and is independent from any contact or joint it is involved in.
+    ///
+    /// At each timestep, its velocity is deduced from the difference between its current position
+    /// and the one set with [`RigidBody::set_next_kinematic_position`](crate::dynamics::RigidBody::set_next_kinematic_position).
+    /// Contacts and joints use that velocity, so friction carries the dynamic bodies resting on
+    /// a moving kinematic platform and contacts don’t resist its motion.
     KinematicPositionBased = 2,
     /// A `RigidBodyType::KinematicVelocityBased` body cannot be affected by any external forces but can be controlled
     /// by the user at the velocity level while keeping realistic one-way interaction with dynamic bodies.
@@ -378,7 +427,7 @@ impl RigidBodyMassProps {
                             .mprops
                             .mass_properties(&*co.shape)
                             .transform_by(&co_parent.pos_wrt_parent);
-                        self.local_mprops += to_add;
+                        self.local_mprops = add_mass_properties(self.local_mprops, to_add);
                     }
                 }
             }
@@ -386,7 +435,7 @@ impl RigidBodyMassProps {
 
         match added_mprops {
             RigidBodyAdditionalMassProps::MassProps(mprops) => {
-                self.local_mprops += mprops;
+                self.local_mprops = add_mass_properties(self.local_mprops, mprops);
             }
             RigidBodyAdditionalMassProps::Mass(mass) => {
                 let new_mass = self.local_mprops.mass() + mass;
@@ -587,6 +636,71 @@ impl RigidBodyVelocity {
         }
     }
 
+    /// Returns the velocities with their magnitudes clamped to `max_linvel` and `max_angvel`.
+    #[must_use]
+    pub fn clamp_magnitudes(&self, max_linvel: Real, max_angvel: Real) -> Self {
+        let mut result = *self;
+        let linvel_norm = self.linvel.norm();
+
+        if linvel_norm > max_linvel {
+            result.linvel *= max_linvel / linvel_norm;
+        }
+
+        #[cfg(feature = "dim2")]
+        {
+            result.angvel = self.angvel.clamp(-max_angvel, max_angvel);
+        }
+
+        #[cfg(feature = "dim3")]
+        {
+            let angvel_norm = self.angvel.norm();
+            if angvel_norm > max_angvel {
+                result.angvel *= max_angvel / angvel_norm;
+            }
+        }
+
+        result
+    }
+
+    /// Returns the velocities updated by the gyroscopic forces applied during a timestep of
+    /// length `dt`.
+    ///
+    /// The gyroscopic term is integrated implicitly (with one Newton iteration) in the principal
+    /// inertia frame of the rigid-body, so the rotational kinetic energy doesn’t grow for
+    /// torque-free bodies.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn apply_gyroscopic_forces(
+        &self,
+        dt: Real,
+        rb_mprops: &RigidBodyMassProps,
+        rotation: &Rotation<Real>,
+    ) -> Self {
+        let inertia = rb_mprops.local_mprops.principal_inertia();
+
+        if inertia.is_zero() {
+            return *self;
+        }
+
+        let inertia_frame = rotation * rb_mprops.local_mprops.principal_inertia_local_frame;
+        let inertia_mat = na::Matrix3::from_diagonal(&inertia);
+        let local_angvel = inertia_frame.inverse_transform_vector(&self.angvel);
+        let local_ang_momentum = inertia.component_mul(&local_angvel);
+
+        // Solve `I (ω2 - ω1) + dt ω2 × (I ω2) = 0` for `ω2` with one Newton step starting at `ω1`.
+        let residual = local_angvel.cross(&local_ang_momentum) * dt;
+        let jacobian = inertia_mat
+            + (local_angvel.cross_matrix() * inertia_mat - local_ang_momentum.cross_matrix()) * dt;
+
+        match jacobian.try_inverse() {
+            Some(inv_jacobian) => RigidBodyVelocity {
+                linvel: self.linvel,
+                angvel: inertia_frame * (local_angvel - inv_jacobian * residual),
+            },
+            None => *self,
+        }
+    }
+
     /// The velocity of the given world-space point on this rigid-body.
     #[must_use]
     pub fn velocity_at_point(&self, point: &Point<Real>, world_com: &Point<Real>) -> Vector<Real> {
@@ -748,6 +862,13 @@ pub struct RigidBodyForces {
     pub user_force: Vector<Real>,
     /// Torque applied by the user.
     pub user_torque: AngVector<Real>,
+    /// The gravitational acceleration applied to this rigid-body during the last timestep it was
+    /// simulated at, including its gravity scale and force fields (only for dynamic bodies).
+    pub effective_gravity: Vector<Real>,
+    /// The acceleration of the gravity well attracting this rigid-body, added to the gravity
+    /// before applying the gravity scale. Set by [`GravityWellSet::update`](crate::dynamics::GravityWellSet::update),
+    /// and cleared by the physics pipeline once applied.
+    pub(crate) gravity_well_acceleration: Vector<Real>,
 }
 
 impl Default for RigidBodyForces {
@@ -758,6 +879,8 @@ impl Default for RigidBodyForces {
             gravity_scale: 1.0,
             user_force: na::zero(),
             user_torque: na::zero(),
+            effective_gravity: na::zero(),
+            gravity_well_acceleration: na::zero(),
         }
     }
 }
@@ -788,7 +911,8 @@ impl RigidBodyForces {
         gravity: &Vector<Real>,
         mass: &Vector<Real>,
     ) {
-        self.force = self.user_force + gravity.component_mul(mass) * self.gravity_scale;
+        self.effective_gravity = (gravity + self.gravity_well_acceleration) * self.gravity_scale;
+        self.force = self.user_force + self.effective_gravity.component_mul(mass);
         self.torque = self.user_torque;
     }
 
@@ -944,7 +1068,7 @@ impl RigidBodyColliders {
             .mass_properties(&**co_shape)
             .transform_by(&co_parent.pos_wrt_parent);
         self.0.push(co_handle);
-        rb_mprops.local_mprops += mass_properties;
+        rb_mprops.local_mprops = add_mass_properties(rb_mprops.local_mprops, mass_properties);
         rb_mprops.update_world_mass_properties(&rb_pos.position);
     }
 
@@ -992,20 +1116,36 @@ impl RigidBodyDominance {
 ///
 /// This controls whether a body is sleeping or not.
 /// If the threshold is negative, the body never sleeps.
+///
+/// The sleep thresholds left to `None` are inherited from the global thresholds of
+/// [`IntegrationParameters`]. The ones set explicitly on this body override the global ones.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct RigidBodyActivation {
     /// The threshold linear velocity bellow which the body can fall asleep.
     ///
     /// The value is "normalized", i.e., the actual threshold applied by the physics engine
-    /// is equal to this value multiplied by [`IntegrationParameters::length_unit`].
-    pub normalized_linear_threshold: Real,
+    /// is equal to this value multiplied by [`IntegrationParameters::length_unit`]. If `None`,
+    /// [`IntegrationParameters::normalized_sleep_linear_threshold`] is used instead.
+    pub normalized_linear_threshold: Option<Real>,
     /// The angular linear velocity bellow which the body can fall asleep.
-    pub angular_threshold: Real,
+    ///
+    /// If `None`, [`IntegrationParameters::sleep_angular_threshold`] is used instead.
+    pub angular_threshold: Option<Real>,
     /// The amount of time the rigid-body must remain below the thresholds to be put to sleep.
-    pub time_until_sleep: Real,
+    ///
+    /// If `None`, [`IntegrationParameters::time_until_sleep`] is used instead.
+    pub time_until_sleep: Option<Real>,
     /// Since how much time can this body sleep?
     pub time_since_can_sleep: Real,
+    /// The exponential moving average of the kinetic energy of this body, normalized by its sleep
+    /// thresholds.
+    ///
+    /// A body moving exactly at its linear (or angular) sleep threshold has a normalized energy
+    /// of `1.0`. This is the value compared to [`IntegrationParameters::sleep_enter_energy_threshold`]
+    /// and [`IntegrationParameters::sleep_exit_energy_threshold`] to decide whether the body can
+    /// sleep. See [`IntegrationParameters::sleep_energy_smoothing_time`].
+    pub smoothed_energy: Real,
     /// Is this body sleeping?
     pub sleeping: bool,
 }
@@ -1033,24 +1173,26 @@ impl RigidBodyActivation {
         2.0
     }
 
-    /// Create a new rb_activation status initialised with the default rb_activation threshold and is active.
+    /// Create a new rb_activation status inheriting the global sleep thresholds and is active.
     pub fn active() -> Self {
         RigidBodyActivation {
-            normalized_linear_threshold: Self::default_normalized_linear_threshold(),
-            angular_threshold: Self::default_angular_threshold(),
-            time_until_sleep: Self::default_time_until_sleep(),
+            normalized_linear_threshold: None,
+            angular_threshold: None,
+            time_until_sleep: None,
             time_since_can_sleep: 0.0,
+            smoothed_energy: 0.0,
             sleeping: false,
         }
     }
 
-    /// Create a new rb_activation status initialised with the default rb_activation threshold and is inactive.
+    /// Create a new rb_activation status inheriting the global sleep thresholds and is inactive.
     pub fn inactive() -> Self {
         RigidBodyActivation {
-            normalized_linear_threshold: Self::default_normalized_linear_threshold(),
-            angular_threshold: Self::default_angular_threshold(),
-            time_until_sleep: Self::default_time_until_sleep(),
-            time_since_can_sleep: Self::default_time_until_sleep(),
+            normalized_linear_threshold: None,
+            angular_threshold: None,
+            time_until_sleep: None,
+            time_since_can_sleep: Real::MAX,
+            smoothed_energy: 0.0,
             sleeping: true,
         }
     }
@@ -1058,12 +1200,32 @@ impl RigidBodyActivation {
     /// Create a new activation status that prevents the rigid-body from sleeping.
     pub fn cannot_sleep() -> Self {
         RigidBodyActivation {
-            normalized_linear_threshold: -1.0,
-            angular_threshold: -1.0,
+            normalized_linear_threshold: Some(-1.0),
+            angular_threshold: Some(-1.0),
             ..Self::active()
         }
     }
 
+    /// The normalized linear sleep threshold actually used by the physics engine: the one of this
+    /// body if set, or the global one otherwise.
+    pub fn effective_normalized_linear_threshold(&self, params: &IntegrationParameters) -> Real {
+        self.normalized_linear_threshold
+            .unwrap_or(params.normalized_sleep_linear_threshold)
+    }
+
+    /// The angular sleep threshold actually used by the physics engine: the one of this body if
+    /// set, or the global one otherwise.
+    pub fn effective_angular_threshold(&self, params: &IntegrationParameters) -> Real {
+        self.angular_threshold
+            .unwrap_or(params.sleep_angular_threshold)
+    }
+
+    /// The time until sleep actually used by the physics engine: the one of this body if set, or
+    /// the global one otherwise.
+    pub fn effective_time_until_sleep(&self, params: &IntegrationParameters) -> Real {
+        self.time_until_sleep.unwrap_or(params.time_until_sleep)
+    }
+
     /// Returns `true` if the body is not asleep.
     #[inline]
     pub fn is_active(&self) -> bool {
@@ -1080,9 +1242,14 @@ impl RigidBodyActivation {
     }
 
     /// Put this rigid-body to sleep.
+    ///
+    /// The body is considered to have been able to sleep for an unbounded amount of time, so it
+    /// falls asleep again as soon as it is woken up without being reset by a strong wake-up,
+    /// whatever its effective [`Self::time_until_sleep`].
     #[inline]
     pub fn sleep(&mut self) {
         self.sleeping = true;
-        self.time_since_can_sleep = self.time_until_sleep;
+        self.time_since_can_sleep = Real::MAX;
+        self.smoothed_energy = 0.0;
     }
 }
@@ -3,6 +3,7 @@ use crate::dynamics::{
     ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBody, RigidBodyChanges, RigidBodyHandle,
 };
 use crate::geometry::ColliderSet;
+use crate::pipeline::InvalidHandle;
 use std::ops::{Index, IndexMut};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -76,6 +77,9 @@ impl RigidBodySet {
     }
 
     /// Removes a rigid-body, and all its attached colliders and impulse_joints, from these sets.
+    ///
+    /// Every other rigid-body touching one of the removed colliders is woken up at the beginning
+    /// of the next simulation step (see [`ColliderSet::remove`]).
     pub fn remove(
         &mut self,
         handle: RigidBodyHandle,
@@ -115,6 +119,48 @@ impl RigidBodySet {
         Some(rb)
     }
 
+    /// Removes several rigid-bodies, and all their attached colliders and joints, from these sets.
+    ///
+    /// This is equivalent to calling [`Self::remove`] on each handle. Handles of rigid-bodies that
+    /// don’t exist are ignored. Like with [`ColliderSet::remove_batch`], the broad-phase and the
+    /// narrow-phase are cleaned-up at the beginning of the next simulation step, all at once.
+    ///
+    /// Returns the removed rigid-bodies with their handles, in the order of `handles`.
+    pub fn remove_batch(
+        &mut self,
+        handles: &[RigidBodyHandle],
+        islands: &mut IslandManager,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        remove_attached_colliders: bool,
+    ) -> Vec<(RigidBodyHandle, RigidBody)> {
+        handles
+            .iter()
+            .filter_map(|handle| {
+                let rb = self.remove(
+                    *handle,
+                    islands,
+                    colliders,
+                    impulse_joints,
+                    multibody_joints,
+                    remove_attached_colliders,
+                )?;
+                Some((*handle, rb))
+            })
+            .collect()
+    }
+
+    /// Removes all the rigid-bodies from this set, but keeps its allocated memory.
+    ///
+    /// Unlike [`Self::remove`], this doesn’t update the other sets. This is meant for emptying
+    /// a whole world in-place, along with [`ColliderSet::clear`], [`IslandManager::clear`],
+    /// etc. See [`PhysicsWorld::clear`](crate::pipeline::PhysicsWorld::clear) for an example.
+    pub fn clear(&mut self) {
+        self.bodies.clear();
+        self.modified_bodies.clear();
+    }
+
     /// Gets the rigid-body with the given handle without a known generation.
     ///
     /// This is useful when you know you want the rigid-body at position `i` but
@@ -206,6 +252,13 @@ impl RigidBodySet {
         })
     }
 
+    /// Iterates mutably through all the rigid-bodies on this set, without flagging them as modified.
+    pub(crate) fn iter_mut_internal(
+        &mut self,
+    ) -> impl Iterator<Item = (RigidBodyHandle, &mut RigidBody)> {
+        self.bodies.iter_mut().map(|(h, b)| (RigidBodyHandle(h), b))
+    }
+
     /// Update colliders positions after rigid-bodies moved.
     ///
     /// When a rigid-body moves, the positions of the colliders attached to it need to be updated.
@@ -231,7 +284,8 @@ impl Index<RigidBodyHandle> for RigidBodySet {
     type Output = RigidBody;
 
     fn index(&self, index: RigidBodyHandle) -> &RigidBody {
-        &self.bodies[index.0]
+        self.get(index)
+            .unwrap_or_else(|| panic!("{}.", InvalidHandle::RigidBody(index)))
     }
 }
 
@@ -246,7 +300,10 @@ impl Index<crate::data::Index> for RigidBodySet {
 #[cfg(not(feature = "dev-remove-slow-accessors"))]
 impl IndexMut<RigidBodyHandle> for RigidBodySet {
     fn index_mut(&mut self, handle: RigidBodyHandle) -> &mut RigidBody {
-        let rb = &mut self.bodies[handle.0];
+        let rb = self
+            .bodies
+            .get_mut(handle.0)
+            .unwrap_or_else(|| panic!("{}.", InvalidHandle::RigidBody(handle)));
         Self::mark_as_modified(handle, rb, &mut self.modified_bodies);
         rb
     }
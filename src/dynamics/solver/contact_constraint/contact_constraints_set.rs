@@ -503,7 +503,7 @@ impl ContactConstraintsSet {
     ) {
         macro_rules! update_contacts(
             ($builders: ident, $constraints: ident) => {
-                for (builder, constraint) in self.$builders.iter().zip(self.$constraints.iter_mut()) {
+                for (builder, constraint) in self.$builders.iter_mut().zip(self.$constraints.iter_mut()) {
                     builder.update(
                         &params,
                         small_step_id as Real * params.dt,
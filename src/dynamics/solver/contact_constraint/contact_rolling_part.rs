@@ -0,0 +1,108 @@
+use crate::math::{AngVector, AngularInertia, Real, MAX_MANIFOLD_POINTS};
+use crate::utils::{self, SimdAngularInertia, SimdDot};
+
+#[cfg(feature = "dim2")]
+use crate::num::Zero;
+
+/// The rolling friction of a contact constraint.
+///
+/// This is an angular constraint opposing the relative angular velocity of the two bodies. It
+/// is shared by all the contact points of the constraint since they all have the same angular
+/// jacobian. Its impulse is bounded by the sum of the rolling friction coefficient of each
+/// contact point multiplied by its normal impulse.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ContactRollingPart {
+    pub enabled: bool,
+    pub ii1: AngularInertia<Real>,
+    pub ii2: AngularInertia<Real>,
+    /// The angular velocity of the first body when it isn’t part of the solver velocities.
+    pub rhs: AngVector<Real>,
+    pub coefficients: [Real; MAX_MANIFOLD_POINTS],
+    pub impulse: AngVector<Real>,
+    pub impulse_accumulator: AngVector<Real>,
+}
+
+impl ContactRollingPart {
+    pub fn zero() -> Self {
+        Self {
+            enabled: false,
+            ii1: AngularInertia::zero(),
+            ii2: AngularInertia::zero(),
+            rhs: na::zero(),
+            coefficients: [0.0; MAX_MANIFOLD_POINTS],
+            impulse: na::zero(),
+            impulse_accumulator: na::zero(),
+        }
+    }
+
+    pub fn new(
+        ii1: AngularInertia<Real>,
+        ii2: AngularInertia<Real>,
+        rhs: AngVector<Real>,
+        coefficients: impl Iterator<Item = Real>,
+    ) -> Self {
+        let mut result = Self::zero();
+
+        for (k, coefficient) in coefficients.enumerate() {
+            result.coefficients[k] = coefficient;
+            result.enabled |= coefficient != 0.0;
+        }
+
+        if result.enabled {
+            result.ii1 = ii1;
+            result.ii2 = ii2;
+            result.rhs = rhs;
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn update(&mut self, warmstart_coefficient: Real) {
+        self.impulse_accumulator += self.impulse;
+        self.impulse *= warmstart_coefficient;
+    }
+
+    #[inline]
+    pub fn warmstart(&self, angvel1: &mut AngVector<Real>, angvel2: &mut AngVector<Real>) {
+        *angvel1 += self.ii1.transform_vector(self.impulse);
+        *angvel2 -= self.ii2.transform_vector(self.impulse);
+    }
+
+    #[inline]
+    pub fn solve(
+        &mut self,
+        normal_impulses: impl Iterator<Item = Real>,
+        angvel1: &mut AngVector<Real>,
+        angvel2: &mut AngVector<Real>,
+    ) {
+        let limit: Real = self
+            .coefficients
+            .iter()
+            .zip(normal_impulses)
+            .map(|(coefficient, impulse)| coefficient * impulse)
+            .sum();
+
+        let dangvel =
+            self.rhs + self.ii1.transform_vector(*angvel1) - self.ii2.transform_vector(*angvel2);
+
+        // Like the 3D friction, project the impulse along the relative angular velocity to
+        // avoid inverting the (possibly singular) angular inertia of the bodies.
+        let ii1_dangvel = self.ii1.transform_vector(dangvel);
+        let ii2_dangvel = self.ii2.transform_vector(dangvel);
+        let inv_lhs = dangvel.gdot(dangvel)
+            * utils::inv(ii1_dangvel.gdot(ii1_dangvel) + ii2_dangvel.gdot(ii2_dangvel));
+        let new_impulse = self.impulse - dangvel * inv_lhs;
+
+        #[cfg(feature = "dim2")]
+        let new_impulse = new_impulse.clamp(-limit, limit);
+        #[cfg(feature = "dim3")]
+        let new_impulse = new_impulse.cap_magnitude(limit);
+
+        let dlambda = new_impulse - self.impulse;
+        self.impulse = new_impulse;
+
+        *angvel1 += self.ii1.transform_vector(dlambda);
+        *angvel2 -= self.ii2.transform_vector(dlambda);
+    }
+}
@@ -122,6 +122,8 @@ impl GenericOneBodyConstraintBuilder {
 
                 // Normal part.
                 let normal_rhs_wo_bias;
+                let normal_restitution_rhs;
+                let normal_approach_vel;
                 {
                     let torque_dir2 = dp2.gcross(-force_dir1);
                     let inv_r2 = mb2
@@ -139,14 +141,14 @@ impl GenericOneBodyConstraintBuilder {
 
                     let r = crate::utils::inv(inv_r2);
 
-                    let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
-
                     let proj_vel1 = vel1.dot(&force_dir1);
                     let proj_vel2 = vel2.dot(&force_dir1);
                     let dvel = proj_vel1 - proj_vel2;
+                    let is_bouncy = manifold_point.is_bouncy(-dvel) as u32 as Real;
                     // NOTE: we add proj_vel1 since it’s not accessible through solver_vel.
-                    normal_rhs_wo_bias =
-                        proj_vel1 + (is_bouncy * manifold_point.restitution) * dvel;
+                    normal_rhs_wo_bias = proj_vel1;
+                    normal_restitution_rhs = (is_bouncy * manifold_point.restitution) * dvel;
+                    normal_approach_vel = -is_bouncy * dvel;
 
                     constraint.inner.elements[k].normal_part = OneBodyConstraintNormalPart {
                         gcross2: na::zero(), // Unused for generic constraints.
@@ -204,6 +206,8 @@ impl GenericOneBodyConstraintBuilder {
                     tangent_vel: manifold_point.tangent_velocity,
                     dist: manifold_point.dist,
                     normal_rhs_wo_bias,
+                    normal_restitution_rhs,
+                    normal_approach_vel,
                 };
 
                 builder.link2 = link2;
@@ -218,7 +222,7 @@ impl GenericOneBodyConstraintBuilder {
     }
 
     pub fn update(
-        &self,
+        &mut self,
         params: &IntegrationParameters,
         solved_dt: Real,
         _solver_bodies: &[SolverBody],
@@ -132,6 +132,8 @@ impl GenericTwoBodyConstraintBuilder {
 
                 // Normal part.
                 let normal_rhs_wo_bias;
+                let normal_restitution_rhs;
+                let normal_approach_vel;
                 {
                     let torque_dir1 = dp1.gcross(force_dir1);
                     let torque_dir2 = dp2.gcross(-force_dir1);
@@ -191,10 +193,13 @@ impl GenericTwoBodyConstraintBuilder {
 
                     let r = crate::utils::inv(inv_r1 + inv_r2);
 
-                    let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
+                    let projected_velocity = (vel1 - vel2).dot(&force_dir1);
+                    let is_bouncy = manifold_point.is_bouncy(-projected_velocity) as u32 as Real;
 
-                    normal_rhs_wo_bias =
-                        (is_bouncy * manifold_point.restitution) * (vel1 - vel2).dot(&force_dir1);
+                    normal_rhs_wo_bias = 0.0;
+                    normal_restitution_rhs =
+                        (is_bouncy * manifold_point.restitution) * projected_velocity;
+                    normal_approach_vel = -is_bouncy * projected_velocity;
 
                     constraint.inner.elements[k].normal_part = TwoBodyConstraintNormalPart {
                         gcross1,
@@ -298,6 +303,8 @@ impl GenericTwoBodyConstraintBuilder {
                     tangent_vel: manifold_point.tangent_velocity,
                     dist: manifold_point.dist,
                     normal_rhs_wo_bias,
+                    normal_restitution_rhs,
+                    normal_approach_vel,
                 };
 
                 builder.handle1 = handle1;
@@ -325,7 +332,7 @@ impl GenericTwoBodyConstraintBuilder {
     }
 
     pub fn update(
-        &self,
+        &mut self,
         params: &IntegrationParameters,
         solved_dt: Real,
         bodies: &[SolverBody],
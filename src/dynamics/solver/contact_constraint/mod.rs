@@ -3,6 +3,7 @@ pub(crate) use generic_one_body_constraint::*;
 pub(crate) use contact_constraints_set::{
     ConstraintsCounts, ContactConstraintTypes, ContactConstraintsSet,
 };
+pub(crate) use contact_rolling_part::ContactRollingPart;
 pub(crate) use generic_two_body_constraint::*;
 pub(crate) use generic_two_body_constraint_element::*;
 pub(crate) use one_body_constraint::*;
@@ -15,6 +16,7 @@ pub(crate) use two_body_constraint_element::*;
 pub(crate) use two_body_constraint_simd::*;
 
 mod contact_constraints_set;
+mod contact_rolling_part;
 mod generic_one_body_constraint;
 mod generic_one_body_constraint_element;
 mod generic_two_body_constraint;
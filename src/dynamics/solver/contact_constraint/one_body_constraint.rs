@@ -1,11 +1,14 @@
-use super::{OneBodyConstraintElement, OneBodyConstraintNormalPart};
-use crate::math::{Point, Real, Vector, DIM, MAX_MANIFOLD_POINTS};
+use super::{ContactRollingPart, OneBodyConstraintElement, OneBodyConstraintNormalPart};
+use crate::math::{AngularInertia, Point, Real, Vector, DIM, MAX_MANIFOLD_POINTS};
 #[cfg(feature = "dim2")]
 use crate::utils::SimdBasis;
 use crate::utils::{self, SimdAngularInertia, SimdCross, SimdDot, SimdRealCopy};
 use na::Matrix2;
 use parry::math::Isometry;
 
+#[cfg(feature = "dim2")]
+use crate::num::Zero;
+
 use crate::dynamics::integration_parameters::BLOCK_SOLVER_ENABLED;
 use crate::dynamics::solver::solver_body::SolverBody;
 use crate::dynamics::solver::SolverVel;
@@ -20,6 +23,14 @@ pub struct ContactPointInfos<N: SimdRealCopy> {
     pub local_p2: Point<N>,
     pub dist: N,
     pub normal_rhs_wo_bias: N,
+    /// The part of the normal velocity target due to restitution.
+    ///
+    /// It is computed once from the relative velocity before the solver runs. It only applies
+    /// to the substep where the gap between the bodies closes, and is reset once a substep
+    /// pushed them apart so that later substeps don’t bounce again.
+    pub normal_restitution_rhs: N,
+    /// The normal approach velocity before the solver runs, or zero if the contact isn’t bouncy.
+    pub normal_approach_vel: N,
 }
 
 impl<N: SimdRealCopy> Default for ContactPointInfos<N> {
@@ -30,6 +41,8 @@ impl<N: SimdRealCopy> Default for ContactPointInfos<N> {
             local_p2: Point::origin(),
             dist: N::zero(),
             normal_rhs_wo_bias: N::zero(),
+            normal_restitution_rhs: N::zero(),
+            normal_approach_vel: N::zero(),
         }
     }
 }
@@ -113,6 +126,14 @@ impl OneBodyConstraintBuilder {
             {
                 constraint.tangent1 = tangents1[0];
             }
+            // NOTE: the first body isn’t part of the solver velocities, so its angular velocity
+            //       goes to the rhs.
+            constraint.rolling_part = ContactRollingPart::new(
+                AngularInertia::zero(),
+                mprops2.effective_world_inv_inertia_sqrt,
+                vels1.angvel,
+                manifold_points.iter().map(|pt| pt.rolling_friction),
+            );
 
             for k in 0..manifold_points.len() {
                 let manifold_point = &manifold_points[k];
@@ -127,6 +148,8 @@ impl OneBodyConstraintBuilder {
 
                 // Normal part.
                 let normal_rhs_wo_bias;
+                let normal_restitution_rhs;
+                let normal_approach_vel;
                 {
                     let gcross2 = mprops2
                         .effective_world_inv_inertia_sqrt
@@ -138,14 +161,14 @@ impl OneBodyConstraintBuilder {
 
                     let projected_mass = utils::inv(projected_lin_mass + projected_ang_mass);
 
-                    let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
-
                     let proj_vel1 = vel1.dot(&force_dir1);
                     let proj_vel2 = vel2.dot(&force_dir1);
                     let dvel = proj_vel1 - proj_vel2;
+                    let is_bouncy = manifold_point.is_bouncy(-dvel) as u32 as Real;
                     // NOTE: we add proj_vel1 since it’s not accessible through solver_vel.
-                    normal_rhs_wo_bias =
-                        proj_vel1 + (is_bouncy * manifold_point.restitution) * dvel;
+                    normal_rhs_wo_bias = proj_vel1;
+                    normal_restitution_rhs = (is_bouncy * manifold_point.restitution) * dvel;
+                    normal_approach_vel = -is_bouncy * dvel;
 
                     constraint.elements[k].normal_part = OneBodyConstraintNormalPart {
                         gcross2,
@@ -205,6 +228,8 @@ impl OneBodyConstraintBuilder {
                         tangent_vel: flipped_multiplier * manifold_point.tangent_velocity,
                         dist: manifold_point.dist,
                         normal_rhs_wo_bias,
+                        normal_restitution_rhs,
+                        normal_approach_vel,
                     };
 
                     builder.infos[k] = infos;
@@ -252,7 +277,7 @@ impl OneBodyConstraintBuilder {
     }
 
     pub fn update(
-        &self,
+        &mut self,
         params: &IntegrationParameters,
         solved_dt: Real,
         bodies: &[SolverBody],
@@ -266,7 +291,7 @@ impl OneBodyConstraintBuilder {
     // TODO: this code is SOOOO similar to TwoBodyConstraint::update.
     //       In fact the only differences are types and the `rb1` and ignoring its ccd thickness.
     pub fn update_with_positions(
-        &self,
+        &mut self,
         params: &IntegrationParameters,
         solved_dt: Real,
         rb2_pos: &Isometry<Real>,
@@ -276,7 +301,7 @@ impl OneBodyConstraintBuilder {
         let inv_dt = params.inv_dt();
         let erp_inv_dt = params.contact_erp_inv_dt();
 
-        let all_infos = &self.infos[..constraint.num_contacts as usize];
+        let all_infos = &mut self.infos[..constraint.num_contacts as usize];
         let all_elements = &mut constraint.elements[..constraint.num_contacts as usize];
         let rb1 = &self.rb1;
         // Integrate the velocity of the static rigid-body, if it’s kinematic.
@@ -292,7 +317,7 @@ impl OneBodyConstraintBuilder {
             constraint.dir1.cross(&constraint.tangent1),
         ];
 
-        for (info, element) in all_infos.iter().zip(all_elements.iter_mut()) {
+        for (info, element) in all_infos.iter_mut().zip(all_elements.iter_mut()) {
             // NOTE: the tangent velocity is equivalent to an additional movement of the first body’s surface.
             let p1 = new_pos1 * info.local_p1 + info.tangent_vel * solved_dt;
             let p2 = rb2_pos * info.local_p2;
@@ -300,15 +325,26 @@ impl OneBodyConstraintBuilder {
 
             // Normal part.
             {
-                let rhs_wo_bias = info.normal_rhs_wo_bias + dist.max(0.0) * inv_dt;
+                // See `ContactPointInfos::normal_restitution_rhs`.
+                if solved_dt > 0.0 && element.normal_part.impulse > 0.0 {
+                    info.normal_restitution_rhs = 0.0;
+                    info.normal_approach_vel = 0.0;
+                }
+                element.normal_part.impulse_accumulator += element.normal_part.impulse;
+                element.normal_part.impulse *= params.warmstart_coefficient;
+
+                let speculative_rhs = dist.max(0.0) * inv_dt;
+                let rhs_wo_bias = if speculative_rhs < info.normal_approach_vel {
+                    info.normal_rhs_wo_bias + info.normal_restitution_rhs
+                } else {
+                    info.normal_rhs_wo_bias + speculative_rhs
+                };
                 let rhs_bias = (erp_inv_dt * (dist + params.allowed_linear_error()))
                     .clamp(-params.max_corrective_velocity(), 0.0);
                 let new_rhs = rhs_wo_bias + rhs_bias;
 
                 element.normal_part.rhs_wo_bias = rhs_wo_bias;
                 element.normal_part.rhs = new_rhs;
-                element.normal_part.impulse_accumulator += element.normal_part.impulse;
-                element.normal_part.impulse *= params.warmstart_coefficient;
             }
 
             // Tangent part.
@@ -323,6 +359,7 @@ impl OneBodyConstraintBuilder {
             }
         }
 
+        constraint.rolling_part.update(params.warmstart_coefficient);
         constraint.cfm_factor = cfm_factor;
     }
 }
@@ -337,6 +374,7 @@ pub(crate) struct OneBodyConstraint {
     pub cfm_factor: Real,
     pub limit: Real,
     pub elements: [OneBodyConstraintElement<Real>; MAX_MANIFOLD_POINTS],
+    pub rolling_part: ContactRollingPart,
 
     pub manifold_id: ContactManifoldIndex,
     pub manifold_contact_id: [u8; MAX_MANIFOLD_POINTS],
@@ -354,6 +392,7 @@ impl OneBodyConstraint {
             cfm_factor: 0.0,
             limit: 0.0,
             elements: [OneBodyConstraintElement::zero(); MAX_MANIFOLD_POINTS],
+            rolling_part: ContactRollingPart::zero(),
             manifold_id: ContactManifoldIndex::MAX,
             manifold_contact_id: [u8::MAX; MAX_MANIFOLD_POINTS],
             num_contacts: u8::MAX,
@@ -372,6 +411,11 @@ impl OneBodyConstraint {
             &mut solver_vel2,
         );
 
+        if self.rolling_part.enabled {
+            self.rolling_part
+                .warmstart(&mut na::zero(), &mut solver_vel2.angular);
+        }
+
         solver_vels[self.solver_vel2] = solver_vel2;
     }
 
@@ -396,6 +440,15 @@ impl OneBodyConstraint {
             solve_friction,
         );
 
+        if solve_friction && self.rolling_part.enabled {
+            let elements = &self.elements[..self.num_contacts as usize];
+            self.rolling_part.solve(
+                elements.iter().map(|elt| elt.normal_part.impulse),
+                &mut na::zero(),
+                &mut solver_vel2.angular,
+            );
+        }
+
         solver_vels[self.solver_vel2] = solver_vel2;
     }
 
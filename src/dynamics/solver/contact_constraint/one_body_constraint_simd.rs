@@ -116,9 +116,9 @@ impl SimdOneBodyConstraintBuilder {
             for k in 0..num_points {
                 let friction = SimdReal::from(gather![|ii| manifold_points[ii][k].friction]);
                 let restitution = SimdReal::from(gather![|ii| manifold_points[ii][k].restitution]);
-                let is_bouncy = SimdReal::from(gather![
-                    |ii| manifold_points[ii][k].is_bouncy() as u32 as Real
-                ]);
+                let restitution_velocity_threshold = SimdReal::from(gather![|ii| manifold_points
+                    [ii][k]
+                    .restitution_velocity_threshold]);
                 let warmstart_impulse =
                     SimdReal::from(gather![|ii| manifold_points[ii][k].warmstart_impulse]);
                 let warmstart_tangent_impulse = TangentImpulse::from(gather![|ii| manifold_points
@@ -142,6 +142,8 @@ impl SimdOneBodyConstraintBuilder {
 
                 // Normal part.
                 let normal_rhs_wo_bias;
+                let normal_restitution_rhs;
+                let normal_approach_vel;
                 {
                     let gcross2 = ii2.transform_vector(dp2.gcross(-force_dir1));
 
@@ -152,8 +154,12 @@ impl SimdOneBodyConstraintBuilder {
                     let projected_vel1 = vel1.dot(&force_dir1);
                     let projected_vel2 = vel2.dot(&force_dir1);
                     let projected_velocity = projected_vel1 - projected_vel2;
-                    normal_rhs_wo_bias =
-                        (is_bouncy * restitution) * projected_velocity + projected_vel1; // Add projected_vel1 since it’s not accessible through solver_vel.
+                    // See `SolverContact::is_bouncy`.
+                    let is_bouncy = (-projected_velocity).simd_gt(restitution_velocity_threshold);
+                    normal_restitution_rhs =
+                        (restitution * projected_velocity).select(is_bouncy, SimdReal::zero());
+                    normal_approach_vel = (-projected_velocity).select(is_bouncy, SimdReal::zero());
+                    normal_rhs_wo_bias = projected_vel1; // Add projected_vel1 since it’s not accessible through solver_vel.
 
                     constraint.elements[k].normal_part = OneBodyConstraintNormalPart {
                         gcross2,
@@ -202,6 +208,8 @@ impl SimdOneBodyConstraintBuilder {
                         tangent_vel: tangent_velocity * flipped_sign,
                         dist,
                         normal_rhs_wo_bias,
+                        normal_restitution_rhs,
+                        normal_approach_vel,
                     };
 
                     builder.infos[k] = infos;
@@ -254,7 +262,7 @@ impl SimdOneBodyConstraintBuilder {
     // TODO: this code is SOOOO similar to TwoBodyConstraintSimd::update.
     //       In fact the only differences are types and the `rb1` and ignoring its ccd thickness.
     pub fn update(
-        &self,
+        &mut self,
         params: &IntegrationParameters,
         solved_dt: Real,
         bodies: &[SolverBody],
@@ -271,7 +279,7 @@ impl SimdOneBodyConstraintBuilder {
         let rb2 = gather![|ii| &bodies[constraint.solver_vel2[ii]]];
         let poss2 = Isometry::from(gather![|ii| rb2[ii].position]);
 
-        let all_infos = &self.infos[..constraint.num_contacts as usize];
+        let all_infos = &mut self.infos[..constraint.num_contacts as usize];
         let all_elements = &mut constraint.elements[..constraint.num_contacts as usize];
 
         // Integrate the velocity of the static rigid-body, if it’s kinematic.
@@ -289,9 +297,10 @@ impl SimdOneBodyConstraintBuilder {
             constraint.dir1.cross(&constraint.tangent1),
         ];
 
+        let is_first_substep = solved_dt == 0.0;
         let solved_dt = SimdReal::splat(solved_dt);
 
-        for (info, element) in all_infos.iter().zip(all_elements.iter_mut()) {
+        for (info, element) in all_infos.iter_mut().zip(all_elements.iter_mut()) {
             // NOTE: the tangent velocity is equivalent to an additional movement of the first body’s surface.
             let p1 = new_pos1 * info.local_p1 + info.tangent_vel * solved_dt;
             let p2 = poss2 * info.local_p2;
@@ -299,16 +308,27 @@ impl SimdOneBodyConstraintBuilder {
 
             // Normal part.
             {
-                let rhs_wo_bias =
-                    info.normal_rhs_wo_bias + dist.simd_max(SimdReal::zero()) * inv_dt;
+                // See `ContactPointInfos::normal_restitution_rhs`.
+                if !is_first_substep {
+                    let has_bounced = element.normal_part.impulse.simd_gt(SimdReal::zero());
+                    info.normal_restitution_rhs =
+                        SimdReal::zero().select(has_bounced, info.normal_restitution_rhs);
+                    info.normal_approach_vel =
+                        SimdReal::zero().select(has_bounced, info.normal_approach_vel);
+                }
+                element.normal_part.impulse_accumulator += element.normal_part.impulse;
+                element.normal_part.impulse *= warmstart_coeff;
+
+                let speculative_rhs = dist.simd_max(SimdReal::zero()) * inv_dt;
+                let bounces = speculative_rhs.simd_lt(info.normal_approach_vel);
+                let rhs_wo_bias = info.normal_rhs_wo_bias
+                    + info.normal_restitution_rhs.select(bounces, speculative_rhs);
                 let rhs_bias = ((dist + allowed_lin_err) * erp_inv_dt)
                     .simd_clamp(-max_corrective_velocity, SimdReal::zero());
                 let new_rhs = rhs_wo_bias + rhs_bias;
 
                 element.normal_part.rhs_wo_bias = rhs_wo_bias;
                 element.normal_part.rhs = new_rhs;
-                element.normal_part.impulse_accumulator += element.normal_part.impulse;
-                element.normal_part.impulse *= warmstart_coeff;
             }
 
             // tangent parts.
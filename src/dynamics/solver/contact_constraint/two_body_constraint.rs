@@ -1,4 +1,4 @@
-use super::{ContactConstraintTypes, ContactPointInfos};
+use super::{ContactConstraintTypes, ContactPointInfos, ContactRollingPart};
 use crate::dynamics::solver::SolverVel;
 use crate::dynamics::solver::{AnyConstraintMut, SolverBody};
 
@@ -121,6 +121,7 @@ pub(crate) struct TwoBodyConstraint {
     pub manifold_contact_id: [u8; MAX_MANIFOLD_POINTS],
     pub num_contacts: u8,
     pub elements: [TwoBodyConstraintElement<Real>; MAX_MANIFOLD_POINTS],
+    pub rolling_part: ContactRollingPart,
 }
 
 impl TwoBodyConstraint {
@@ -139,6 +140,7 @@ impl TwoBodyConstraint {
             manifold_contact_id: [u8::MAX; MAX_MANIFOLD_POINTS],
             num_contacts: u8::MAX,
             elements: [TwoBodyConstraintElement::zero(); MAX_MANIFOLD_POINTS],
+            rolling_part: ContactRollingPart::zero(),
         }
     }
 }
@@ -201,6 +203,12 @@ impl TwoBodyConstraintBuilder {
             {
                 constraint.tangent1 = tangents1[0];
             }
+            constraint.rolling_part = ContactRollingPart::new(
+                mprops1.effective_world_inv_inertia_sqrt,
+                mprops2.effective_world_inv_inertia_sqrt,
+                na::zero(),
+                manifold_points.iter().map(|pt| pt.rolling_friction),
+            );
 
             for k in 0..manifold_points.len() {
                 let manifold_point = &manifold_points[k];
@@ -217,6 +225,8 @@ impl TwoBodyConstraintBuilder {
 
                 // Normal part.
                 let normal_rhs_wo_bias;
+                let normal_restitution_rhs;
+                let normal_approach_vel;
                 {
                     let gcross1 = mprops1
                         .effective_world_inv_inertia_sqrt
@@ -232,10 +242,13 @@ impl TwoBodyConstraintBuilder {
                             + gcross2.gdot(gcross2),
                     );
 
-                    let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
+                    let projected_velocity = (vel1 - vel2).dot(&force_dir1);
+                    let is_bouncy = manifold_point.is_bouncy(-projected_velocity) as u32 as Real;
 
-                    normal_rhs_wo_bias =
-                        (is_bouncy * manifold_point.restitution) * (vel1 - vel2).dot(&force_dir1);
+                    normal_rhs_wo_bias = 0.0;
+                    normal_restitution_rhs =
+                        (is_bouncy * manifold_point.restitution) * projected_velocity;
+                    normal_approach_vel = -is_bouncy * projected_velocity;
 
                     constraint.elements[k].normal_part = TwoBodyConstraintNormalPart {
                         gcross1,
@@ -301,6 +314,8 @@ impl TwoBodyConstraintBuilder {
                     tangent_vel: manifold_point.tangent_velocity,
                     dist: manifold_point.dist,
                     normal_rhs_wo_bias,
+                    normal_restitution_rhs,
+                    normal_approach_vel,
                 };
 
                 builder.infos[k] = infos;
@@ -352,7 +367,7 @@ impl TwoBodyConstraintBuilder {
     }
 
     pub fn update(
-        &self,
+        &mut self,
         params: &IntegrationParameters,
         solved_dt: Real,
         bodies: &[SolverBody],
@@ -366,7 +381,7 @@ impl TwoBodyConstraintBuilder {
 
     // Used by both generic and non-generic builders..
     pub fn update_with_positions(
-        &self,
+        &mut self,
         params: &IntegrationParameters,
         solved_dt: Real,
         rb1_pos: &Isometry<Real>,
@@ -377,7 +392,7 @@ impl TwoBodyConstraintBuilder {
         let inv_dt = params.inv_dt();
         let erp_inv_dt = params.contact_erp_inv_dt();
 
-        let all_infos = &self.infos[..constraint.num_contacts as usize];
+        let all_infos = &mut self.infos[..constraint.num_contacts as usize];
         let all_elements = &mut constraint.elements[..constraint.num_contacts as usize];
 
         #[cfg(feature = "dim2")]
@@ -388,7 +403,7 @@ impl TwoBodyConstraintBuilder {
             constraint.dir1.cross(&constraint.tangent1),
         ];
 
-        for (info, element) in all_infos.iter().zip(all_elements.iter_mut()) {
+        for (info, element) in all_infos.iter_mut().zip(all_elements.iter_mut()) {
             // Tangent velocity is equivalent to the first body’s surface moving artificially.
             let p1 = rb1_pos * info.local_p1 + info.tangent_vel * solved_dt;
             let p2 = rb2_pos * info.local_p2;
@@ -396,15 +411,26 @@ impl TwoBodyConstraintBuilder {
 
             // Normal part.
             {
-                let rhs_wo_bias = info.normal_rhs_wo_bias + dist.max(0.0) * inv_dt;
+                // See `ContactPointInfos::normal_restitution_rhs`.
+                if solved_dt > 0.0 && element.normal_part.impulse > 0.0 {
+                    info.normal_restitution_rhs = 0.0;
+                    info.normal_approach_vel = 0.0;
+                }
+                element.normal_part.impulse_accumulator += element.normal_part.impulse;
+                element.normal_part.impulse *= params.warmstart_coefficient;
+
+                let speculative_rhs = dist.max(0.0) * inv_dt;
+                let rhs_wo_bias = if speculative_rhs < info.normal_approach_vel {
+                    info.normal_rhs_wo_bias + info.normal_restitution_rhs
+                } else {
+                    info.normal_rhs_wo_bias + speculative_rhs
+                };
                 let rhs_bias = (erp_inv_dt * (dist + params.allowed_linear_error()))
                     .clamp(-params.max_corrective_velocity(), 0.0);
                 let new_rhs = rhs_wo_bias + rhs_bias;
 
                 element.normal_part.rhs_wo_bias = rhs_wo_bias;
                 element.normal_part.rhs = new_rhs;
-                element.normal_part.impulse_accumulator += element.normal_part.impulse;
-                element.normal_part.impulse *= params.warmstart_coefficient;
             }
 
             // Tangent part.
@@ -419,6 +445,7 @@ impl TwoBodyConstraintBuilder {
             }
         }
 
+        constraint.rolling_part.update(params.warmstart_coefficient);
         constraint.cfm_factor = cfm_factor;
     }
 }
@@ -439,6 +466,11 @@ impl TwoBodyConstraint {
             &mut solver_vel2,
         );
 
+        if self.rolling_part.enabled {
+            self.rolling_part
+                .warmstart(&mut solver_vel1.angular, &mut solver_vel2.angular);
+        }
+
         solver_vels[self.solver_vel1] = solver_vel1;
         solver_vels[self.solver_vel2] = solver_vel2;
     }
@@ -467,6 +499,15 @@ impl TwoBodyConstraint {
             solve_friction,
         );
 
+        if solve_friction && self.rolling_part.enabled {
+            let elements = &self.elements[..self.num_contacts as usize];
+            self.rolling_part.solve(
+                elements.iter().map(|elt| elt.normal_part.impulse),
+                &mut solver_vel1.angular,
+                &mut solver_vel2.angular,
+            );
+        }
+
         solver_vels[self.solver_vel1] = solver_vel1;
         solver_vels[self.solver_vel2] = solver_vel2;
     }
@@ -99,9 +99,9 @@ impl TwoBodyConstraintBuilderSimd {
             for k in 0..num_points {
                 let friction = SimdReal::from(gather![|ii| manifold_points[ii][k].friction]);
                 let restitution = SimdReal::from(gather![|ii| manifold_points[ii][k].restitution]);
-                let is_bouncy = SimdReal::from(gather![
-                    |ii| manifold_points[ii][k].is_bouncy() as u32 as Real
-                ]);
+                let restitution_velocity_threshold = SimdReal::from(gather![|ii| manifold_points
+                    [ii][k]
+                    .restitution_velocity_threshold]);
                 let warmstart_impulse =
                     SimdReal::from(gather![|ii| manifold_points[ii][k].warmstart_impulse]);
                 let warmstart_tangent_impulse = TangentImpulse::from(gather![|ii| manifold_points
@@ -124,6 +124,8 @@ impl TwoBodyConstraintBuilderSimd {
 
                 // Normal part.
                 let normal_rhs_wo_bias;
+                let normal_restitution_rhs;
+                let normal_approach_vel;
                 {
                     let gcross1 = ii1.transform_vector(dp1.gcross(force_dir1));
                     let gcross2 = ii2.transform_vector(dp2.gcross(-force_dir1));
@@ -136,7 +138,12 @@ impl TwoBodyConstraintBuilderSimd {
                     );
 
                     let projected_velocity = (vel1 - vel2).dot(&force_dir1);
-                    normal_rhs_wo_bias = is_bouncy * restitution * projected_velocity;
+                    // See `SolverContact::is_bouncy`.
+                    let is_bouncy = (-projected_velocity).simd_gt(restitution_velocity_threshold);
+                    normal_rhs_wo_bias = SimdReal::zero();
+                    normal_restitution_rhs =
+                        (restitution * projected_velocity).select(is_bouncy, SimdReal::zero());
+                    normal_approach_vel = (-projected_velocity).select(is_bouncy, SimdReal::zero());
 
                     constraint.elements[k].normal_part = TwoBodyConstraintNormalPart {
                         gcross1,
@@ -189,6 +196,8 @@ impl TwoBodyConstraintBuilderSimd {
                     tangent_vel: tangent_velocity,
                     dist,
                     normal_rhs_wo_bias,
+                    normal_restitution_rhs,
+                    normal_approach_vel,
                 };
 
                 builder.infos[k] = infos;
@@ -243,7 +252,7 @@ impl TwoBodyConstraintBuilderSimd {
     }
 
     pub fn update(
-        &self,
+        &mut self,
         params: &IntegrationParameters,
         solved_dt: Real,
         bodies: &[SolverBody],
@@ -263,7 +272,7 @@ impl TwoBodyConstraintBuilderSimd {
         let poss1 = Isometry::from(gather![|ii| rb1[ii].position]);
         let poss2 = Isometry::from(gather![|ii| rb2[ii].position]);
 
-        let all_infos = &self.infos[..constraint.num_contacts as usize];
+        let all_infos = &mut self.infos[..constraint.num_contacts as usize];
         let all_elements = &mut constraint.elements[..constraint.num_contacts as usize];
 
         #[cfg(feature = "dim2")]
@@ -274,9 +283,10 @@ impl TwoBodyConstraintBuilderSimd {
             constraint.dir1.cross(&constraint.tangent1),
         ];
 
+        let is_first_substep = solved_dt == 0.0;
         let solved_dt = SimdReal::splat(solved_dt);
 
-        for (info, element) in all_infos.iter().zip(all_elements.iter_mut()) {
+        for (info, element) in all_infos.iter_mut().zip(all_elements.iter_mut()) {
             // NOTE: the tangent velocity is equivalent to an additional movement of the first body’s surface.
             let p1 = poss1 * info.local_p1 + info.tangent_vel * solved_dt;
             let p2 = poss2 * info.local_p2;
@@ -284,16 +294,27 @@ impl TwoBodyConstraintBuilderSimd {
 
             // Normal part.
             {
-                let rhs_wo_bias =
-                    info.normal_rhs_wo_bias + dist.simd_max(SimdReal::zero()) * inv_dt;
+                // See `ContactPointInfos::normal_restitution_rhs`.
+                if !is_first_substep {
+                    let has_bounced = element.normal_part.impulse.simd_gt(SimdReal::zero());
+                    info.normal_restitution_rhs =
+                        SimdReal::zero().select(has_bounced, info.normal_restitution_rhs);
+                    info.normal_approach_vel =
+                        SimdReal::zero().select(has_bounced, info.normal_approach_vel);
+                }
+                element.normal_part.impulse_accumulator += element.normal_part.impulse;
+                element.normal_part.impulse *= warmstart_coeff;
+
+                let speculative_rhs = dist.simd_max(SimdReal::zero()) * inv_dt;
+                let bounces = speculative_rhs.simd_lt(info.normal_approach_vel);
+                let rhs_wo_bias = info.normal_rhs_wo_bias
+                    + info.normal_restitution_rhs.select(bounces, speculative_rhs);
                 let rhs_bias = ((dist + allowed_lin_err) * erp_inv_dt)
                     .simd_clamp(-max_corrective_velocity, SimdReal::zero());
                 let new_rhs = rhs_wo_bias + rhs_bias;
 
                 element.normal_part.rhs_wo_bias = rhs_wo_bias;
                 element.normal_part.rhs = new_rhs;
-                element.normal_part.impulse_accumulator += element.normal_part.impulse;
-                element.normal_part.impulse *= warmstart_coeff;
             }
 
             // tangent parts.
@@ -412,6 +412,17 @@ impl InteractionGroups {
                     continue;
                 }
 
+                // The SIMD contact constraints don’t support rolling friction.
+                if interaction
+                    .data
+                    .solver_contacts
+                    .iter()
+                    .any(|contact| contact.rolling_friction != 0.0)
+                {
+                    self.nongrouped_interactions.push(*interaction_i);
+                    continue;
+                }
+
                 let (status1, active_set_offset1) = if let Some(rb1) = interaction.data.rigid_body1
                 {
                     let rb1 = &bodies[rb1];
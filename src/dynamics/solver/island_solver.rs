@@ -1,5 +1,5 @@
-use super::{JointConstraintsSet, VelocitySolver};
-use crate::counters::Counters;
+use super::{solve_joint_positions, ConstraintsCounts, JointConstraintsSet, VelocitySolver};
+use crate::counters::{Counters, IslandSolverCounters};
 use crate::dynamics::solver::contact_constraint::ContactConstraintsSet;
 use crate::dynamics::IslandManager;
 use crate::dynamics::{IntegrationParameters, JointGraphEdge, JointIndex, RigidBodySet};
@@ -28,6 +28,31 @@ impl IslandSolver {
         }
     }
 
+    /// Statistics about the constraints that will be solved for the given island.
+    pub fn island_counters(
+        island_id: usize,
+        base_params: &IntegrationParameters,
+        islands: &IslandManager,
+        manifolds: &[&mut ContactManifold],
+        manifold_indices: &[ContactManifoldIndex],
+        impulse_joints: &[JointGraphEdge],
+        joint_indices: &[JointIndex],
+    ) -> IslandSolverCounters {
+        IslandSolverCounters {
+            nbodies: islands.active_island(island_id).len(),
+            ncontact_constraints: manifold_indices
+                .iter()
+                .map(|i| ConstraintsCounts::from_contacts(manifolds[*i]).num_constraints)
+                .sum(),
+            njoint_constraints: joint_indices
+                .iter()
+                .map(|i| ConstraintsCounts::from_joint(&impulse_joints[*i].weight).num_constraints)
+                .sum(),
+            niterations: base_params.num_solver_iterations.get()
+                + islands.active_island_additional_solver_iterations(island_id),
+        }
+    }
+
     pub fn init_and_solve(
         &mut self,
         island_id: usize,
@@ -86,6 +111,14 @@ impl IslandSolver {
             &mut self.contact_constraints,
             &mut self.joint_constraints,
         );
+        solve_joint_positions(
+            base_params,
+            bodies,
+            multibodies,
+            &mut self.velocity_solver.solver_bodies,
+            impulse_joints,
+            joint_indices,
+        );
         counters.solver.velocity_resolution_time.pause();
 
         // WRITEBACK
@@ -351,6 +351,8 @@ pub struct JointTwoBodyConstraintHelper<N: SimdRealCopy> {
     pub ang_basis: SMatrix<N, ANG_DIM, ANG_DIM>,
     pub lin_err: Vector<N>,
     pub ang_err: Rotation<N>,
+    pub erp_inv_dt: N,
+    pub cfm_coeff: N,
 }
 
 impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
@@ -360,6 +362,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         world_com1: &Point<N>,
         world_com2: &Point<N>,
         locked_lin_axes: u8,
+        erp_inv_dt: N,
+        cfm_coeff: N,
     ) -> Self {
         let mut frame1 = *frame1;
         let basis = frame1.rotation.to_rotation_matrix().into_inner();
@@ -407,12 +411,23 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             ang_basis,
             lin_err,
             ang_err,
+            erp_inv_dt,
+            cfm_coeff,
+        }
+    }
+
+    /// A copy of this helper with different regularization coefficients, e.g., to build the
+    /// constraints of joint limits with their own softness.
+    pub fn with_regularization(&self, erp_inv_dt: N, cfm_coeff: N) -> Self {
+        Self {
+            erp_inv_dt,
+            cfm_coeff,
+            ..*self
         }
     }
 
     pub fn limit_linear<const LANES: usize>(
         &self,
-        params: &IntegrationParameters,
         joint_id: [JointIndex; LANES],
         body1: &JointSolverBody<N, LANES>,
         body2: &JointSolverBody<N, LANES>,
@@ -421,15 +436,14 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         writeback_id: WritebackId,
     ) -> JointTwoBodyConstraint<N, LANES> {
         let zero = N::zero();
-        let mut constraint =
-            self.lock_linear(params, joint_id, body1, body2, limited_axis, writeback_id);
+        let mut constraint = self.lock_linear(joint_id, body1, body2, limited_axis, writeback_id);
 
         let dist = self.lin_err.dot(&constraint.lin_jac);
         let min_enabled = dist.simd_le(limits[0]);
         let max_enabled = limits[1].simd_le(dist);
 
-        let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
-        let cfm_coeff = N::splat(params.joint_cfm_coeff());
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         let rhs_bias =
             ((dist - limits[1]).simd_max(zero) - (limits[0] - dist).simd_max(zero)) * erp_inv_dt;
         constraint.rhs = constraint.rhs_wo_bias + rhs_bias;
@@ -487,8 +501,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         ang_jac1 = body1.sqrt_ii * ang_jac1;
         ang_jac2 = body2.sqrt_ii * ang_jac2;
 
-        let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
-        let cfm_coeff = N::splat(params.joint_cfm_coeff());
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         let rhs_bias = (dist - limits[1]).simd_max(zero) * erp_inv_dt;
         let rhs = rhs_wo_bias + rhs_bias;
         let impulse_bounds = [N::zero(), N::splat(Real::INFINITY)];
@@ -509,6 +523,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: N::zero(),
             rhs,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
@@ -525,8 +541,7 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         writeback_id: WritebackId,
     ) -> JointTwoBodyConstraint<N, LANES> {
         let inv_dt = N::splat(params.inv_dt());
-        let mut constraint =
-            self.lock_linear(params, joint_id, body1, body2, motor_axis, writeback_id);
+        let mut constraint = self.lock_linear(joint_id, body1, body2, motor_axis, writeback_id);
 
         let mut rhs_wo_bias = N::zero();
         if motor_params.erp_inv_dt != N::zero() {
@@ -623,13 +638,14 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: motor_params.cfm_gain,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
 
     pub fn lock_linear<const LANES: usize>(
         &self,
-        params: &IntegrationParameters,
         joint_id: [JointIndex; LANES],
         body1: &JointSolverBody<N, LANES>,
         body2: &JointSolverBody<N, LANES>,
@@ -647,8 +663,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         let mut ang_jac2 = self.cmat2_basis.column(locked_axis).into_owned();
 
         let rhs_wo_bias = N::zero();
-        let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
-        let cfm_coeff = N::splat(params.joint_cfm_coeff());
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         let rhs_bias = lin_jac.dot(&self.lin_err) * erp_inv_dt;
 
         ang_jac1 = body1.sqrt_ii * ang_jac1;
@@ -670,13 +686,14 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
 
     pub fn limit_angular<const LANES: usize>(
         &self,
-        params: &IntegrationParameters,
         joint_id: [JointIndex; LANES],
         body1: &JointSolverBody<N, LANES>,
         body2: &JointSolverBody<N, LANES>,
@@ -704,8 +721,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         #[cfg(feature = "dim3")]
         let ang_jac = self.ang_basis.column(_limited_axis).into_owned();
         let rhs_wo_bias = N::zero();
-        let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
-        let cfm_coeff = N::splat(params.joint_cfm_coeff());
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         let rhs_bias = ((s_ang - s_limits[1]).simd_max(zero)
             - (s_limits[0] - s_ang).simd_max(zero))
             * erp_inv_dt;
@@ -729,6 +746,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
@@ -779,13 +798,14 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: motor_params.cfm_gain,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
 
     pub fn lock_angular<const LANES: usize>(
         &self,
-        params: &IntegrationParameters,
         joint_id: [JointIndex; LANES],
         body1: &JointSolverBody<N, LANES>,
         body2: &JointSolverBody<N, LANES>,
@@ -798,8 +818,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         let ang_jac = self.ang_basis.column(_locked_axis).into_owned();
 
         let rhs_wo_bias = N::zero();
-        let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
-        let cfm_coeff = N::splat(params.joint_cfm_coeff());
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         #[cfg(feature = "dim2")]
         let rhs_bias = self.ang_err.im * erp_inv_dt;
         #[cfg(feature = "dim3")]
@@ -824,6 +844,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
@@ -877,7 +899,6 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
 
     pub fn limit_linear_one_body<const LANES: usize>(
         &self,
-        params: &IntegrationParameters,
         joint_id: [JointIndex; LANES],
         body1: &JointFixedSolverBody<N>,
         body2: &JointSolverBody<N, LANES>,
@@ -904,8 +925,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         let mut ang_jac2 = self.cmat2_basis.column(limited_axis).into_owned();
 
         let rhs_wo_bias = -lin_jac.dot(&body1.linvel) - ang_jac1.gdot(body1.angvel);
-        let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
-        let cfm_coeff = N::splat(params.joint_cfm_coeff());
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         let rhs_bias =
             ((dist - limits[1]).simd_max(zero) - (limits[0] - dist).simd_max(zero)) * erp_inv_dt;
 
@@ -924,6 +945,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
@@ -972,8 +995,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
 
         ang_jac2 = body2.sqrt_ii * ang_jac2;
 
-        let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
-        let cfm_coeff = N::splat(params.joint_cfm_coeff());
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         let rhs_bias = (dist - limits[1]).simd_max(zero) * erp_inv_dt;
         let rhs = rhs_wo_bias + rhs_bias;
         let impulse_bounds = [N::zero(), N::splat(Real::INFINITY)];
@@ -991,6 +1014,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: N::zero(),
             rhs,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
@@ -1046,6 +1071,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: motor_params.cfm_gain,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
@@ -1119,13 +1146,14 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: motor_params.cfm_gain,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
 
     pub fn lock_linear_one_body<const LANES: usize>(
         &self,
-        params: &IntegrationParameters,
         joint_id: [JointIndex; LANES],
         body1: &JointFixedSolverBody<N>,
         body2: &JointSolverBody<N, LANES>,
@@ -1141,8 +1169,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
 
         let rhs_wo_bias = -lin_jac.dot(&body1.linvel) - ang_jac1.gdot(body1.angvel);
 
-        let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
-        let cfm_coeff = N::splat(params.joint_cfm_coeff());
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         let rhs_bias = lin_jac.dot(&self.lin_err) * erp_inv_dt;
 
         ang_jac2 = body2.sqrt_ii * ang_jac2;
@@ -1160,6 +1188,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
@@ -1207,13 +1237,14 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: motor_params.cfm_gain,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
 
     pub fn limit_angular_one_body<const LANES: usize>(
         &self,
-        params: &IntegrationParameters,
         joint_id: [JointIndex; LANES],
         body1: &JointFixedSolverBody<N>,
         body2: &JointSolverBody<N, LANES>,
@@ -1242,8 +1273,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         let ang_jac = self.ang_basis.column(_limited_axis).into_owned();
         let rhs_wo_bias = -ang_jac.gdot(body1.angvel);
 
-        let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
-        let cfm_coeff = N::splat(params.joint_cfm_coeff());
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         let rhs_bias = ((s_ang - s_limits[1]).simd_max(zero)
             - (s_limits[0] - s_ang).simd_max(zero))
             * erp_inv_dt;
@@ -1263,13 +1294,14 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
 
     pub fn lock_angular_one_body<const LANES: usize>(
         &self,
-        params: &IntegrationParameters,
         joint_id: [JointIndex; LANES],
         body1: &JointFixedSolverBody<N>,
         body2: &JointSolverBody<N, LANES>,
@@ -1283,8 +1315,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
 
         let rhs_wo_bias = -ang_jac.gdot(body1.angvel);
 
-        let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
-        let cfm_coeff = N::splat(params.joint_cfm_coeff());
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         #[cfg(feature = "dim2")]
         let rhs_bias = self.ang_err.im * erp_inv_dt;
         #[cfg(feature = "dim3")]
@@ -1305,6 +1337,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             cfm_gain: N::zero(),
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
+            restitution: N::zero(),
+            restitution_velocity_threshold: N::zero(),
             writeback_id,
         }
     }
@@ -1360,7 +1394,6 @@ impl JointTwoBodyConstraintHelper<Real> {
     #[cfg(feature = "dim3")]
     pub fn limit_angular_coupled(
         &self,
-        params: &IntegrationParameters,
         joint_id: [JointIndex; 1],
         body1: &JointSolverBody<Real, 1>,
         body2: &JointSolverBody<Real, 1>,
@@ -1391,8 +1424,8 @@ impl JointTwoBodyConstraintHelper<Real> {
 
         let rhs_wo_bias = 0.0;
 
-        let erp_inv_dt = params.joint_erp_inv_dt();
-        let cfm_coeff = params.joint_cfm_coeff();
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         let rhs_bias = ((angle - limits[1]).max(0.0) - (limits[0] - angle).max(0.0)) * erp_inv_dt;
 
         let ang_jac1 = body1.sqrt_ii * ang_jac;
@@ -1414,6 +1447,8 @@ impl JointTwoBodyConstraintHelper<Real> {
             cfm_gain: 0.0,
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
+            restitution: 0.0,
+            restitution_velocity_threshold: 0.0,
             writeback_id,
         }
     }
@@ -1421,7 +1456,6 @@ impl JointTwoBodyConstraintHelper<Real> {
     #[cfg(feature = "dim3")]
     pub fn limit_angular_coupled_one_body(
         &self,
-        params: &IntegrationParameters,
         joint_id: [JointIndex; 1],
         body1: &JointFixedSolverBody<Real>,
         body2: &JointSolverBody<Real, 1>,
@@ -1452,8 +1486,8 @@ impl JointTwoBodyConstraintHelper<Real> {
 
         let rhs_wo_bias = -ang_jac.gdot(body1.angvel);
 
-        let erp_inv_dt = params.joint_erp_inv_dt();
-        let cfm_coeff = params.joint_cfm_coeff();
+        let erp_inv_dt = self.erp_inv_dt;
+        let cfm_coeff = self.cfm_coeff;
         let rhs_bias = ((angle - limits[1]).max(0.0) - (limits[0] - angle).max(0.0)) * erp_inv_dt;
 
         let ang_jac2 = body2.sqrt_ii * ang_jac;
@@ -1471,6 +1505,8 @@ impl JointTwoBodyConstraintHelper<Real> {
             cfm_gain: 0.0,
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
+            restitution: 0.0,
+            restitution_velocity_threshold: 0.0,
             writeback_id,
         }
     }
@@ -87,6 +87,8 @@ impl JointGenericTwoBodyConstraint {
             &body1.world_com,
             &body2.world_com,
             locked_axes,
+            joint.erp_inv_dt(params),
+            joint.cfm_coeff(params),
         );
 
         let start = len;
@@ -131,7 +133,6 @@ impl JointGenericTwoBodyConstraint {
         for i in DIM..SPATIAL_DIM {
             if locked_axes & (1 << i) != 0 {
                 out[len] = builder.lock_angular_generic(
-                    params,
                     jacobians,
                     j_id,
                     joint_id,
@@ -148,7 +149,6 @@ impl JointGenericTwoBodyConstraint {
         for i in 0..DIM {
             if locked_axes & (1 << i) != 0 {
                 out[len] = builder.lock_linear_generic(
-                    params,
                     jacobians,
                     j_id,
                     joint_id,
@@ -166,7 +166,6 @@ impl JointGenericTwoBodyConstraint {
         for i in DIM..SPATIAL_DIM {
             if limit_axes & (1 << i) != 0 {
                 out[len] = builder.limit_angular_generic(
-                    params,
                     jacobians,
                     j_id,
                     joint_id,
@@ -184,7 +183,6 @@ impl JointGenericTwoBodyConstraint {
         for i in 0..DIM {
             if limit_axes & (1 << i) != 0 {
                 out[len] = builder.limit_linear_generic(
-                    params,
                     jacobians,
                     j_id,
                     joint_id,
@@ -375,6 +373,8 @@ impl JointGenericOneBodyConstraint {
             &body1.world_com,
             &body2.world_com,
             locked_axes,
+            joint.erp_inv_dt(params),
+            joint.cfm_coeff(params),
         );
 
         let start = len;
@@ -420,7 +420,6 @@ impl JointGenericOneBodyConstraint {
         for i in DIM..SPATIAL_DIM {
             if locked_axes & (1 << i) != 0 {
                 out[len] = builder.lock_angular_generic_one_body(
-                    params,
                     jacobians,
                     j_id,
                     joint_id,
@@ -435,7 +434,6 @@ impl JointGenericOneBodyConstraint {
         for i in 0..DIM {
             if locked_axes & (1 << i) != 0 {
                 out[len] = builder.lock_linear_generic_one_body(
-                    params,
                     jacobians,
                     j_id,
                     joint_id,
@@ -451,7 +449,6 @@ impl JointGenericOneBodyConstraint {
         for i in DIM..SPATIAL_DIM {
             if limit_axes & (1 << i) != 0 {
                 out[len] = builder.limit_angular_generic_one_body(
-                    params,
                     jacobians,
                     j_id,
                     joint_id,
@@ -467,7 +464,6 @@ impl JointGenericOneBodyConstraint {
         for i in 0..DIM {
             if limit_axes & (1 << i) != 0 {
                 out[len] = builder.limit_linear_generic_one_body(
-                    params,
                     jacobians,
                     j_id,
                     joint_id,
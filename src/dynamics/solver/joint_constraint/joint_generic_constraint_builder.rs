@@ -563,7 +563,6 @@ impl JointTwoBodyConstraintHelper<Real> {
 
     pub fn lock_linear_generic(
         &self,
-        params: &IntegrationParameters,
         jacobians: &mut DVector<Real>,
         j_id: &mut usize,
         joint_id: JointIndex,
@@ -592,7 +591,7 @@ impl JointTwoBodyConstraintHelper<Real> {
             ang_jac2,
         );
 
-        let erp_inv_dt = params.joint_erp_inv_dt();
+        let erp_inv_dt = self.erp_inv_dt;
         let rhs_bias = lin_jac.dot(&self.lin_err) * erp_inv_dt;
         c.rhs += rhs_bias;
         c
@@ -600,7 +599,6 @@ impl JointTwoBodyConstraintHelper<Real> {
 
     pub fn limit_linear_generic(
         &self,
-        params: &IntegrationParameters,
         jacobians: &mut DVector<Real>,
         j_id: &mut usize,
         joint_id: JointIndex,
@@ -634,7 +632,7 @@ impl JointTwoBodyConstraintHelper<Real> {
         let min_enabled = dist <= limits[0];
         let max_enabled = limits[1] <= dist;
 
-        let erp_inv_dt = params.joint_erp_inv_dt();
+        let erp_inv_dt = self.erp_inv_dt;
         let rhs_bias = ((dist - limits[1]).max(0.0) - (limits[0] - dist).max(0.0)) * erp_inv_dt;
         constraint.rhs += rhs_bias;
         constraint.impulse_bounds = [
@@ -702,7 +700,6 @@ impl JointTwoBodyConstraintHelper<Real> {
 
     pub fn lock_angular_generic(
         &self,
-        params: &IntegrationParameters,
         jacobians: &mut DVector<Real>,
         j_id: &mut usize,
         joint_id: JointIndex,
@@ -732,7 +729,7 @@ impl JointTwoBodyConstraintHelper<Real> {
             ang_jac,
         );
 
-        let erp_inv_dt = params.joint_erp_inv_dt();
+        let erp_inv_dt = self.erp_inv_dt;
         #[cfg(feature = "dim2")]
         let rhs_bias = self.ang_err.im * erp_inv_dt;
         #[cfg(feature = "dim3")]
@@ -743,7 +740,6 @@ impl JointTwoBodyConstraintHelper<Real> {
 
     pub fn limit_angular_generic(
         &self,
-        params: &IntegrationParameters,
         jacobians: &mut DVector<Real>,
         j_id: &mut usize,
         joint_id: JointIndex,
@@ -786,7 +782,7 @@ impl JointTwoBodyConstraintHelper<Real> {
             max_enabled as u32 as Real * Real::MAX,
         ];
 
-        let erp_inv_dt = params.joint_erp_inv_dt();
+        let erp_inv_dt = self.erp_inv_dt;
         let rhs_bias =
             ((s_ang - s_limits[1]).max(0.0) - (s_limits[0] - s_ang).max(0.0)) * erp_inv_dt;
 
@@ -954,7 +950,6 @@ impl JointTwoBodyConstraintHelper<Real> {
 
     pub fn lock_linear_generic_one_body(
         &self,
-        params: &IntegrationParameters,
         jacobians: &mut DVector<Real>,
         j_id: &mut usize,
         joint_id: JointIndex,
@@ -979,7 +974,7 @@ impl JointTwoBodyConstraintHelper<Real> {
             ang_jac2,
         );
 
-        let erp_inv_dt = params.joint_erp_inv_dt();
+        let erp_inv_dt = self.erp_inv_dt;
         let rhs_bias = lin_jac.dot(&self.lin_err) * erp_inv_dt;
         c.rhs += rhs_bias;
         c
@@ -987,7 +982,6 @@ impl JointTwoBodyConstraintHelper<Real> {
 
     pub fn limit_linear_generic_one_body(
         &self,
-        params: &IntegrationParameters,
         jacobians: &mut DVector<Real>,
         j_id: &mut usize,
         joint_id: JointIndex,
@@ -1017,7 +1011,7 @@ impl JointTwoBodyConstraintHelper<Real> {
         let min_enabled = dist <= limits[0];
         let max_enabled = limits[1] <= dist;
 
-        let erp_inv_dt = params.joint_erp_inv_dt();
+        let erp_inv_dt = self.erp_inv_dt;
         let rhs_bias = ((dist - limits[1]).max(0.0) - (limits[0] - dist).max(0.0)) * erp_inv_dt;
         constraint.rhs += rhs_bias;
         constraint.impulse_bounds = [
@@ -1082,7 +1076,6 @@ impl JointTwoBodyConstraintHelper<Real> {
 
     pub fn lock_angular_generic_one_body(
         &self,
-        params: &IntegrationParameters,
         jacobians: &mut DVector<Real>,
         j_id: &mut usize,
         joint_id: JointIndex,
@@ -1108,7 +1101,7 @@ impl JointTwoBodyConstraintHelper<Real> {
             ang_jac,
         );
 
-        let erp_inv_dt = params.joint_erp_inv_dt();
+        let erp_inv_dt = self.erp_inv_dt;
         #[cfg(feature = "dim2")]
         let rhs_bias = self.ang_err.im * erp_inv_dt;
         #[cfg(feature = "dim3")]
@@ -1119,7 +1112,6 @@ impl JointTwoBodyConstraintHelper<Real> {
 
     pub fn limit_angular_generic_one_body(
         &self,
-        params: &IntegrationParameters,
         jacobians: &mut DVector<Real>,
         j_id: &mut usize,
         joint_id: JointIndex,
@@ -1158,7 +1150,7 @@ impl JointTwoBodyConstraintHelper<Real> {
             max_enabled as u32 as Real * Real::MAX,
         ];
 
-        let erp_inv_dt = params.joint_erp_inv_dt();
+        let erp_inv_dt = self.erp_inv_dt;
         let rhs_bias =
             ((s_ang - s_limits[1]).max(0.0) - (s_limits[0] - s_ang).max(0.0)) * erp_inv_dt;
 
@@ -36,6 +36,32 @@ impl<N: SimdRealCopy> Default for MotorParameters<N> {
     }
 }
 
+/// The velocity to add to the right-hand-side of a limit constraint so it bounces back.
+///
+/// This mirrors [`SolverContact::is_bouncy`](crate::geometry::SolverContact::is_bouncy): the
+/// limit bounces only if it is approached faster than `restitution_velocity_threshold`, i.e., if
+/// the relative velocity `dvel` moves toward an enabled bound of the limit.
+fn limit_restitution_rhs(
+    restitution: Real,
+    restitution_velocity_threshold: Real,
+    impulse_bounds: [Real; 2],
+    dvel: Real,
+) -> Real {
+    let approach_vel = if impulse_bounds[1] > 0.0 {
+        dvel
+    } else if impulse_bounds[0] < 0.0 {
+        -dvel
+    } else {
+        0.0
+    };
+
+    if approach_vel > restitution_velocity_threshold {
+        restitution * dvel
+    } else {
+        0.0
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum WritebackId {
     Dof(usize),
@@ -102,6 +128,9 @@ pub struct JointTwoBodyConstraint<N: SimdRealCopy, const LANES: usize> {
     pub rhs_wo_bias: N,
     pub cfm_gain: N,
     pub cfm_coeff: N,
+    // Only set for limits, and reset to zero once the bounce has been applied.
+    pub restitution: N,
+    pub restitution_velocity_threshold: N,
 
     pub im1: Vector<N>,
     pub im2: Vector<N>,
@@ -174,6 +203,8 @@ impl JointTwoBodyConstraint<Real, 1> {
             &body1.world_com,
             &body2.world_com,
             locked_axes,
+            joint.erp_inv_dt(params),
+            joint.cfm_coeff(params),
         );
 
         let start = len;
@@ -244,84 +275,100 @@ impl JointTwoBodyConstraint<Real, 1> {
         let start = len;
         for i in DIM..SPATIAL_DIM {
             if locked_axes & (1 << i) != 0 {
-                out[len] = builder.lock_angular(
-                    params,
-                    [joint_id],
-                    body1,
-                    body2,
-                    i - DIM,
-                    WritebackId::Dof(i),
-                );
+                out[len] =
+                    builder.lock_angular([joint_id], body1, body2, i - DIM, WritebackId::Dof(i));
                 len += 1;
             }
         }
         for i in 0..DIM {
             if locked_axes & (1 << i) != 0 {
-                out[len] =
-                    builder.lock_linear(params, [joint_id], body1, body2, i, WritebackId::Dof(i));
+                out[len] = builder.lock_linear([joint_id], body1, body2, i, WritebackId::Dof(i));
                 len += 1;
             }
         }
 
         for i in DIM..SPATIAL_DIM {
             if (limit_axes & !coupled_axes) & (1 << i) != 0 {
-                out[len] = builder.limit_angular(
-                    params,
-                    [joint_id],
-                    body1,
-                    body2,
-                    i - DIM,
-                    [joint.limits[i].min, joint.limits[i].max],
-                    WritebackId::Limit(i),
-                );
+                out[len] = builder
+                    .with_regularization(
+                        joint.limit_erp_inv_dt(i, params),
+                        joint.limit_cfm_coeff(i, params),
+                    )
+                    .limit_angular(
+                        [joint_id],
+                        body1,
+                        body2,
+                        i - DIM,
+                        [joint.limits[i].min, joint.limits[i].max],
+                        WritebackId::Limit(i),
+                    );
+                out[len].restitution = joint.limits[i].restitution;
+                out[len].restitution_velocity_threshold =
+                    joint.limit_restitution_velocity_threshold(i, params);
                 len += 1;
             }
         }
         for i in 0..DIM {
             if (limit_axes & !coupled_axes) & (1 << i) != 0 {
-                out[len] = builder.limit_linear(
-                    params,
-                    [joint_id],
-                    body1,
-                    body2,
-                    i,
-                    [joint.limits[i].min, joint.limits[i].max],
-                    WritebackId::Limit(i),
-                );
+                out[len] = builder
+                    .with_regularization(
+                        joint.limit_erp_inv_dt(i, params),
+                        joint.limit_cfm_coeff(i, params),
+                    )
+                    .limit_linear(
+                        [joint_id],
+                        body1,
+                        body2,
+                        i,
+                        [joint.limits[i].min, joint.limits[i].max],
+                        WritebackId::Limit(i),
+                    );
+                out[len].restitution = joint.limits[i].restitution;
+                out[len].restitution_velocity_threshold =
+                    joint.limit_restitution_velocity_threshold(i, params);
                 len += 1;
             }
         }
 
         #[cfg(feature = "dim3")]
         if has_ang_coupling && (limit_axes & (1 << first_coupled_ang_axis_id)) != 0 {
-            out[len] = builder.limit_angular_coupled(
-                params,
-                [joint_id],
-                body1,
-                body2,
-                coupled_axes,
-                [
-                    joint.limits[first_coupled_ang_axis_id].min,
-                    joint.limits[first_coupled_ang_axis_id].max,
-                ],
-                WritebackId::Limit(first_coupled_ang_axis_id),
-            );
+            out[len] = builder
+                .with_regularization(
+                    joint.limit_erp_inv_dt(first_coupled_ang_axis_id, params),
+                    joint.limit_cfm_coeff(first_coupled_ang_axis_id, params),
+                )
+                .limit_angular_coupled(
+                    [joint_id],
+                    body1,
+                    body2,
+                    coupled_axes,
+                    [
+                        joint.limits[first_coupled_ang_axis_id].min,
+                        joint.limits[first_coupled_ang_axis_id].max,
+                    ],
+                    WritebackId::Limit(first_coupled_ang_axis_id),
+                );
             len += 1;
         }
 
         if has_lin_coupling && (limit_axes & (1 << first_coupled_lin_axis_id)) != 0 {
-            out[len] = builder.limit_linear_coupled(
-                params,
-                [joint_id],
-                body1,
-                body2,
-                coupled_axes,
-                [
-                    joint.limits[first_coupled_lin_axis_id].min,
-                    joint.limits[first_coupled_lin_axis_id].max,
-                ],
-                WritebackId::Limit(first_coupled_lin_axis_id),
-            );
+            out[len] = builder
+                .with_regularization(
+                    joint.limit_erp_inv_dt(first_coupled_lin_axis_id, params),
+                    joint.limit_cfm_coeff(first_coupled_lin_axis_id, params),
+                )
+                .limit_linear_coupled(
+                    params,
+                    [joint_id],
+                    body1,
+                    body2,
+                    coupled_axes,
+                    [
+                        joint.limits[first_coupled_lin_axis_id].min,
+                        joint.limits[first_coupled_lin_axis_id].max,
+                    ],
+                    WritebackId::Limit(first_coupled_lin_axis_id),
+                );
             len += 1;
         }
         JointTwoBodyConstraintHelper::finalize_constraints(&mut out[start..len]);
@@ -333,6 +380,24 @@ impl JointTwoBodyConstraint<Real, 1> {
         let mut solver_vel1 = solver_vels[self.solver_vel1[0]];
         let mut solver_vel2 = solver_vels[self.solver_vel2[0]];
 
+        if self.restitution != 0.0 {
+            // First solve of a bouncy limit: the velocities haven’t been modified by this
+            // constraint yet so they give the approach velocity.
+            let dvel = self.lin_jac.dot(&(solver_vel2.linear - solver_vel1.linear))
+                + self.ang_jac2.gdot(solver_vel2.angular)
+                - self.ang_jac1.gdot(solver_vel1.angular)
+                + self.rhs_wo_bias;
+            let bounce = limit_restitution_rhs(
+                self.restitution,
+                self.restitution_velocity_threshold,
+                self.impulse_bounds,
+                dvel,
+            );
+            self.rhs += bounce;
+            self.rhs_wo_bias += bounce;
+            self.restitution = 0.0;
+        }
+
         self.solve_generic(&mut solver_vel1, &mut solver_vel2);
 
         solver_vels[self.solver_vel1[0]] = solver_vel1;
@@ -367,27 +432,22 @@ impl JointTwoBodyConstraint<SimdReal, SIMD_WIDTH> {
             &body1.world_com,
             &body2.world_com,
             locked_axes,
+            SimdReal::splat(params.joint_erp_inv_dt()),
+            SimdReal::splat(params.joint_cfm_coeff()),
         );
 
         let mut len = 0;
         for i in 0..DIM {
             if locked_axes & (1 << i) != 0 {
-                out[len] =
-                    builder.lock_linear(params, joint_id, body1, body2, i, WritebackId::Dof(i));
+                out[len] = builder.lock_linear(joint_id, body1, body2, i, WritebackId::Dof(i));
                 len += 1;
             }
         }
 
         for i in DIM..SPATIAL_DIM {
             if locked_axes & (1 << i) != 0 {
-                out[len] = builder.lock_angular(
-                    params,
-                    joint_id,
-                    body1,
-                    body2,
-                    i - DIM,
-                    WritebackId::Dof(i),
-                );
+                out[len] =
+                    builder.lock_angular(joint_id, body1, body2, i - DIM, WritebackId::Dof(i));
                 len += 1;
             }
         }
@@ -447,6 +507,9 @@ pub struct JointOneBodyConstraint<N: SimdRealCopy, const LANES: usize> {
     pub cfm_gain: N,
     pub rhs: N,
     pub rhs_wo_bias: N,
+    // Only set for limits, and reset to zero once the bounce has been applied.
+    pub restitution: N,
+    pub restitution_velocity_threshold: N,
 
     pub im2: Vector<N>,
 
@@ -510,6 +573,8 @@ impl JointOneBodyConstraint<Real, 1> {
             &body1.world_com,
             &body2.world_com,
             locked_axes,
+            joint.erp_inv_dt(params),
+            joint.cfm_coeff(params),
         );
 
         let start = len;
@@ -582,7 +647,6 @@ impl JointOneBodyConstraint<Real, 1> {
         for i in DIM..SPATIAL_DIM {
             if locked_axes & (1 << i) != 0 {
                 out[len] = builder.lock_angular_one_body(
-                    params,
                     [joint_id],
                     body1,
                     body2,
@@ -594,77 +658,94 @@ impl JointOneBodyConstraint<Real, 1> {
         }
         for i in 0..DIM {
             if locked_axes & (1 << i) != 0 {
-                out[len] = builder.lock_linear_one_body(
-                    params,
-                    [joint_id],
-                    body1,
-                    body2,
-                    i,
-                    WritebackId::Dof(i),
-                );
+                out[len] =
+                    builder.lock_linear_one_body([joint_id], body1, body2, i, WritebackId::Dof(i));
                 len += 1;
             }
         }
 
         for i in DIM..SPATIAL_DIM {
             if (limit_axes & !coupled_axes) & (1 << i) != 0 {
-                out[len] = builder.limit_angular_one_body(
-                    params,
-                    [joint_id],
-                    body1,
-                    body2,
-                    i - DIM,
-                    [joint.limits[i].min, joint.limits[i].max],
-                    WritebackId::Limit(i),
-                );
+                out[len] = builder
+                    .with_regularization(
+                        joint.limit_erp_inv_dt(i, params),
+                        joint.limit_cfm_coeff(i, params),
+                    )
+                    .limit_angular_one_body(
+                        [joint_id],
+                        body1,
+                        body2,
+                        i - DIM,
+                        [joint.limits[i].min, joint.limits[i].max],
+                        WritebackId::Limit(i),
+                    );
+                out[len].restitution = joint.limits[i].restitution;
+                out[len].restitution_velocity_threshold =
+                    joint.limit_restitution_velocity_threshold(i, params);
                 len += 1;
             }
         }
         for i in 0..DIM {
             if (limit_axes & !coupled_axes) & (1 << i) != 0 {
-                out[len] = builder.limit_linear_one_body(
-                    params,
-                    [joint_id],
-                    body1,
-                    body2,
-                    i,
-                    [joint.limits[i].min, joint.limits[i].max],
-                    WritebackId::Limit(i),
-                );
+                out[len] = builder
+                    .with_regularization(
+                        joint.limit_erp_inv_dt(i, params),
+                        joint.limit_cfm_coeff(i, params),
+                    )
+                    .limit_linear_one_body(
+                        [joint_id],
+                        body1,
+                        body2,
+                        i,
+                        [joint.limits[i].min, joint.limits[i].max],
+                        WritebackId::Limit(i),
+                    );
+                out[len].restitution = joint.limits[i].restitution;
+                out[len].restitution_velocity_threshold =
+                    joint.limit_restitution_velocity_threshold(i, params);
                 len += 1;
             }
         }
 
         #[cfg(feature = "dim3")]
         if has_ang_coupling && (limit_axes & (1 << first_coupled_ang_axis_id)) != 0 {
-            out[len] = builder.limit_angular_coupled_one_body(
-                params,
-                [joint_id],
-                body1,
-                body2,
-                coupled_axes,
-                [
-                    joint.limits[first_coupled_ang_axis_id].min,
-                    joint.limits[first_coupled_ang_axis_id].max,
-                ],
-                WritebackId::Limit(first_coupled_ang_axis_id),
-            );
+            out[len] = builder
+                .with_regularization(
+                    joint.limit_erp_inv_dt(first_coupled_ang_axis_id, params),
+                    joint.limit_cfm_coeff(first_coupled_ang_axis_id, params),
+                )
+                .limit_angular_coupled_one_body(
+                    [joint_id],
+                    body1,
+                    body2,
+                    coupled_axes,
+                    [
+                        joint.limits[first_coupled_ang_axis_id].min,
+                        joint.limits[first_coupled_ang_axis_id].max,
+                    ],
+                    WritebackId::Limit(first_coupled_ang_axis_id),
+                );
             len += 1;
         }
 
         if has_lin_coupling && (limit_axes & (1 << first_coupled_lin_axis_id)) != 0 {
-            out[len] = builder.limit_linear_coupled_one_body(
-                params,
-                [joint_id],
-                body1,
-                body2,
-                coupled_axes,
-                [
-                    joint.limits[first_coupled_lin_axis_id].min,
-                    joint.limits[first_coupled_lin_axis_id].max,
-                ],
-                WritebackId::Limit(first_coupled_lin_axis_id),
-            );
+            out[len] = builder
+                .with_regularization(
+                    joint.limit_erp_inv_dt(first_coupled_lin_axis_id, params),
+                    joint.limit_cfm_coeff(first_coupled_lin_axis_id, params),
+                )
+                .limit_linear_coupled_one_body(
+                    params,
+                    [joint_id],
+                    body1,
+                    body2,
+                    coupled_axes,
+                    [
+                        joint.limits[first_coupled_lin_axis_id].min,
+                        joint.limits[first_coupled_lin_axis_id].max,
+                    ],
+                    WritebackId::Limit(first_coupled_lin_axis_id),
+                );
             len += 1;
         }
         JointTwoBodyConstraintHelper::finalize_one_body_constraints(&mut out[start..len]);
@@ -674,6 +755,23 @@ impl JointOneBodyConstraint<Real, 1> {
 
     pub fn solve(&mut self, solver_vels: &mut [SolverVel<Real>]) {
         let mut solver_vel2 = solver_vels[self.solver_vel2[0]];
+
+        if self.restitution != 0.0 {
+            // See `JointTwoBodyConstraint::solve`.
+            let dvel = self.lin_jac.dot(&solver_vel2.linear)
+                + self.ang_jac2.gdot(solver_vel2.angular)
+                + self.rhs_wo_bias;
+            let bounce = limit_restitution_rhs(
+                self.restitution,
+                self.restitution_velocity_threshold,
+                self.impulse_bounds,
+                dvel,
+            );
+            self.rhs += bounce;
+            self.rhs_wo_bias += bounce;
+            self.restitution = 0.0;
+        }
+
         self.solve_generic(&mut solver_vel2);
         solver_vels[self.solver_vel2[0]] = solver_vel2;
     }
@@ -707,25 +805,20 @@ impl JointOneBodyConstraint<SimdReal, SIMD_WIDTH> {
             &body1.world_com,
             &body2.world_com,
             locked_axes,
+            SimdReal::splat(params.joint_erp_inv_dt()),
+            SimdReal::splat(params.joint_cfm_coeff()),
         );
 
         for i in 0..DIM {
             if locked_axes & (1 << i) != 0 {
-                out[len] = builder.lock_linear_one_body(
-                    params,
-                    joint_id,
-                    body1,
-                    body2,
-                    i,
-                    WritebackId::Dof(i),
-                );
+                out[len] =
+                    builder.lock_linear_one_body(joint_id, body1, body2, i, WritebackId::Dof(i));
                 len += 1;
             }
         }
         for i in DIM..SPATIAL_DIM {
             if locked_axes & (1 << i) != 0 {
                 out[len] = builder.lock_angular_one_body(
-                    params,
                     joint_id,
                     body1,
                     body2,
@@ -0,0 +1,302 @@
+use crate::dynamics::solver::solver_body::SolverBody;
+use crate::dynamics::{
+    IntegrationParameters, JointGraphEdge, JointIndex, MultibodyJointSet, RigidBodyHandle,
+    RigidBodySet,
+};
+use crate::math::{
+    AngVector, AngularInertia, Isometry, Point, Real, Rotation, Vector, ANG_DIM, DIM, SPATIAL_DIM,
+};
+use crate::utils::{SimdAngularInertia, SimdCross, SimdDot};
+use na::{SMatrix, SVector};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+#[cfg(feature = "dim2")]
+use crate::num::Zero;
+
+/// The state of a rigid-body seen by the joint position solver.
+#[derive(Copy, Clone)]
+struct PositionSolverBody {
+    position: Isometry<Real>,
+    world_com: Point<Real>,
+    local_com: Point<Real>,
+    im: Vector<Real>,
+    ii: AngularInertia<Real>,
+    /// The index of this body in the solver bodies, or `None` if it isn’t dynamic.
+    solver_id: Option<usize>,
+}
+
+impl PositionSolverBody {
+    fn new(handle: RigidBodyHandle, bodies: &RigidBodySet, solver_bodies: &[SolverBody]) -> Self {
+        let rb = &bodies[handle];
+
+        if let Some(solver_id) = solver_id(handle, bodies) {
+            let solver_body = &solver_bodies[solver_id];
+            Self {
+                position: solver_body.position,
+                world_com: solver_body.world_com,
+                local_com: solver_body.local_com,
+                im: solver_body.im,
+                ii: solver_body.sqrt_ii.squared(),
+                solver_id: Some(solver_id),
+            }
+        } else {
+            let local_com = rb.mprops.local_mprops.local_com;
+            Self {
+                position: rb.pos.next_position,
+                world_com: rb.pos.next_position * local_com,
+                local_com,
+                im: Vector::zeros(),
+                ii: AngularInertia::zero(),
+                solver_id: None,
+            }
+        }
+    }
+
+    /// Prevents the joint projections from moving this body.
+    fn make_immovable(&mut self) {
+        self.im = Vector::zeros();
+        self.ii = AngularInertia::zero();
+    }
+
+    /// Translates this body and rotates it around its center-of-mass.
+    fn apply_displacement(&mut self, dpos: Vector<Real>, dangle: AngVector<Real>) {
+        self.world_com += dpos;
+        self.position.rotation = Rotation::new(dangle) * self.position.rotation;
+        self.position.translation.vector =
+            self.world_com.coords - self.position.rotation * self.local_com.coords;
+    }
+
+    fn writeback(&self, solver_bodies: &mut [SolverBody]) {
+        if let Some(solver_id) = self.solver_id {
+            let solver_body = &mut solver_bodies[solver_id];
+            solver_body.position = self.position;
+            solver_body.world_com = self.world_com;
+        }
+    }
+}
+
+/// The index of the given body in the solver bodies, or `None` if it isn’t dynamic.
+fn solver_id(handle: RigidBodyHandle, bodies: &RigidBodySet) -> Option<usize> {
+    let rb = &bodies[handle];
+    rb.is_dynamic().then_some(rb.ids.active_set_offset)
+}
+
+/// The jacobian of a locked axis of a joint.
+#[derive(Copy, Clone)]
+struct JacobianRow {
+    lin1: Vector<Real>,
+    ang1: AngVector<Real>,
+    lin2: Vector<Real>,
+    ang2: AngVector<Real>,
+}
+
+impl JacobianRow {
+    fn zero() -> Self {
+        Self {
+            lin1: Vector::zeros(),
+            ang1: na::zero(),
+            lin2: Vector::zeros(),
+            ang2: na::zero(),
+        }
+    }
+}
+
+/// Runs the nonlinear position iterations of the impulse joints with their
+/// [`GenericJoint::position_solver_enabled`](crate::dynamics::GenericJoint::position_solver_enabled)
+/// flag set.
+///
+/// The locked axes of each of these joints are projected together by moving the attached
+/// solver bodies so that their linearized drift vanishes. The joints are projected one after
+/// the other, starting from the ones attached to non-dynamic bodies and moving outward. The
+/// body of a joint closest (in the joint graph) to a non-dynamic body is treated as immovable
+/// so that a single sweep is enough to correct the drift of a tree of joints, e.g., a welded
+/// beam. The bodies equally far from a non-dynamic body are moved proportionally to their
+/// effective inverse masses and inertias instead.
+pub(crate) fn solve_joint_positions(
+    params: &IntegrationParameters,
+    bodies: &RigidBodySet,
+    multibodies: &MultibodyJointSet,
+    solver_bodies: &mut [SolverBody],
+    joints_all: &[JointGraphEdge],
+    joint_indices: &[JointIndex],
+) {
+    if params.num_joint_position_iterations == 0 {
+        return;
+    }
+
+    // The joints to project, with the solver ids of their attached bodies.
+    let mut joints: Vec<_> = joint_indices
+        .iter()
+        .filter_map(|joint_i| {
+            let joint = &joints_all[*joint_i].weight;
+
+            if !joint.data.position_solver_enabled
+                || !joint.data.is_enabled()
+                || multibodies.rigid_body_link(joint.body1).is_some()
+                || multibodies.rigid_body_link(joint.body2).is_some()
+            {
+                return None;
+            }
+
+            let id1 = solver_id(joint.body1, bodies);
+            let id2 = solver_id(joint.body2, bodies);
+            (id1.is_some() || id2.is_some()).then_some((*joint_i, id1, id2))
+        })
+        .collect();
+
+    if joints.is_empty() {
+        return;
+    }
+
+    // Breadth-first traversal of the joint graph, starting from the non-dynamic bodies, to
+    // compute the depth of each solver body. The bodies unreachable from a non-dynamic body
+    // keep an infinite depth.
+    let mut depths = vec![usize::MAX; solver_bodies.len()];
+    let mut adjacency = vec![vec![]; solver_bodies.len()];
+    let mut queue = VecDeque::new();
+
+    for (_, id1, id2) in &joints {
+        match (*id1, *id2) {
+            (Some(id1), Some(id2)) => {
+                adjacency[id1].push(id2);
+                adjacency[id2].push(id1);
+            }
+            (None, Some(id)) | (Some(id), None) => {
+                if depths[id] == usize::MAX {
+                    depths[id] = 1;
+                    queue.push_back(id);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    while let Some(id) = queue.pop_front() {
+        for &neighbor in &adjacency[id] {
+            if depths[neighbor] == usize::MAX {
+                depths[neighbor] = depths[id] + 1;
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    let depth = |id: Option<usize>| id.map(|id| depths[id]).unwrap_or(0);
+    joints.sort_by_key(|(_, id1, id2)| depth(*id1).min(depth(*id2)));
+
+    for _ in 0..params.num_joint_position_iterations {
+        for (joint_i, id1, id2) in &joints {
+            let joint = &joints_all[*joint_i].weight;
+            let mut body1 = PositionSolverBody::new(joint.body1, bodies, solver_bodies);
+            let mut body2 = PositionSolverBody::new(joint.body2, bodies, solver_bodies);
+
+            match depth(*id1).cmp(&depth(*id2)) {
+                Ordering::Less => body1.make_immovable(),
+                Ordering::Greater => body2.make_immovable(),
+                Ordering::Equal => {}
+            }
+
+            let locked_axes = joint.data.locked_axes.bits();
+            let frame1 = body1.position * joint.data.local_frame1;
+            let frame2 = body2.position * joint.data.local_frame2;
+            let r1 = frame1.translation.vector - body1.world_com.coords;
+            let r2 = frame2.translation.vector - body2.world_com.coords;
+
+            // The jacobian and drift of each locked axis. The unlocked ones are left to zero.
+            let mut rows = [JacobianRow::zero(); SPATIAL_DIM];
+            let mut locked = [false; SPATIAL_DIM];
+            let mut errors = SVector::<Real, SPATIAL_DIM>::zeros();
+
+            for i in 0..DIM {
+                if locked_axes & (1 << i) != 0 {
+                    let axis = frame1.rotation * Vector::ith(i, 1.0);
+                    locked[i] = true;
+                    rows[i] = JacobianRow {
+                        lin1: -axis,
+                        ang1: -r1.gcross(axis),
+                        lin2: axis,
+                        ang2: r2.gcross(axis),
+                    };
+                    errors[i] = (frame2.translation.vector - frame1.translation.vector).dot(&axis);
+                }
+            }
+
+            for i in 0..ANG_DIM {
+                if locked_axes & (1 << (DIM + i)) != 0 {
+                    let (axis, error) = angular_error(&frame1, &frame2, i);
+                    locked[DIM + i] = true;
+                    rows[DIM + i] = JacobianRow {
+                        lin1: Vector::zeros(),
+                        ang1: -axis,
+                        lin2: Vector::zeros(),
+                        ang2: axis,
+                    };
+                    errors[DIM + i] = error;
+                }
+            }
+
+            // Solve all the locked axes at once since they are strongly coupled, e.g., fixing a
+            // linear drift rotates the bodies, which adds an angular drift.
+            let mut lhs = SMatrix::<Real, SPATIAL_DIM, SPATIAL_DIM>::identity();
+            for i in 0..SPATIAL_DIM {
+                for j in 0..SPATIAL_DIM {
+                    if !locked[i] || !locked[j] {
+                        continue;
+                    }
+
+                    lhs[(i, j)] = rows[i].lin1.dot(&body1.im.component_mul(&rows[j].lin1))
+                        + rows[i].ang1.gdot(body1.ii.transform_vector(rows[j].ang1))
+                        + rows[i].lin2.dot(&body2.im.component_mul(&rows[j].lin2))
+                        + rows[i].ang2.gdot(body2.ii.transform_vector(rows[j].ang2));
+                }
+            }
+
+            let Some(inv_lhs) = lhs.try_inverse() else {
+                continue;
+            };
+            let lambdas = inv_lhs * -errors;
+
+            let mut impulse1 = (Vector::zeros(), na::zero());
+            let mut impulse2 = (Vector::zeros(), na::zero());
+            for (row, lambda) in rows.iter().zip(lambdas.iter()) {
+                impulse1.0 += row.lin1 * *lambda;
+                impulse1.1 += row.ang1 * *lambda;
+                impulse2.0 += row.lin2 * *lambda;
+                impulse2.1 += row.ang2 * *lambda;
+            }
+
+            body1.apply_displacement(
+                impulse1.0.component_mul(&body1.im),
+                body1.ii.transform_vector(impulse1.1),
+            );
+            body2.apply_displacement(
+                impulse2.0.component_mul(&body2.im),
+                body2.ii.transform_vector(impulse2.1),
+            );
+
+            body1.writeback(solver_bodies);
+            body2.writeback(solver_bodies);
+        }
+    }
+}
+
+/// The world-space `i`-th angular axis of `frame1` and the relative rotation of `frame2`
+/// around it.
+#[cfg(feature = "dim2")]
+fn angular_error(frame1: &Isometry<Real>, frame2: &Isometry<Real>, _i: usize) -> (Real, Real) {
+    (1.0, frame1.rotation.angle_to(&frame2.rotation))
+}
+
+/// The world-space `i`-th angular axis of `frame1` and the relative rotation of `frame2`
+/// around it.
+#[cfg(feature = "dim3")]
+fn angular_error(
+    frame1: &Isometry<Real>,
+    frame2: &Isometry<Real>,
+    i: usize,
+) -> (Vector<Real>, Real) {
+    let rel_rot = frame1.rotation.inverse() * frame2.rotation;
+    // Small-angle approximation of the relative rotation’s scaled axis, taking the shortest arc.
+    let scaled_axis = rel_rot.imag() * (2.0 * rel_rot.w.signum());
+    (frame1.rotation * Vector::ith(i, 1.0), scaled_axis[i])
+}
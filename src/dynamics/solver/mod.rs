@@ -15,6 +15,7 @@ use contact_constraint::*;
 use interaction_groups::*;
 pub(crate) use joint_constraint::MotorParameters;
 pub use joint_constraint::*;
+use joint_position_solver::solve_joint_positions;
 use solver_body::SolverBody;
 use solver_constraints_set::{AnyConstraintMut, ConstraintTypes};
 use solver_vel::SolverVel;
@@ -25,6 +26,7 @@ mod interaction_groups;
 // #[cfg(not(feature = "parallel"))]
 mod island_solver;
 mod joint_constraint;
+mod joint_position_solver;
 // #[cfg(feature = "parallel")]
 // mod parallel_island_solver;
 // #[cfg(feature = "parallel")]
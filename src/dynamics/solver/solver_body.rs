@@ -1,4 +1,4 @@
-use crate::dynamics::{RigidBody, RigidBodyVelocity};
+use crate::dynamics::{IntegrationParameters, RigidBody, RigidBodyVelocity};
 use crate::math::{AngularInertia, Isometry, Point, Real, Vector};
 use crate::prelude::RigidBodyDamping;
 
@@ -46,14 +46,14 @@ impl SolverBody {
         }
     }
 
-    pub fn copy_from(&mut self, rb: &RigidBody) {
+    pub fn copy_from(&mut self, rb: &RigidBody, params: &IntegrationParameters) {
         self.position = rb.pos.position;
         self.integrated_vels = RigidBodyVelocity::zero();
         self.im = rb.mprops.effective_inv_mass;
         self.sqrt_ii = rb.mprops.effective_world_inv_inertia_sqrt;
         self.world_com = rb.mprops.world_com;
         self.ccd_thickness = rb.ccd.ccd_thickness;
-        self.damping = rb.damping;
+        self.damping = rb.effective_damping(params);
         self.local_com = rb.mprops.local_mprops.local_com;
     }
 }
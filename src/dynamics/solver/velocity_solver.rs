@@ -110,7 +110,7 @@ impl VelocitySolver {
                 let solver_vel = &mut self.solver_vels[rb.ids.active_set_offset];
                 let solver_vel_incr = &mut self.solver_vels_increment[rb.ids.active_set_offset];
                 let solver_body = &mut self.solver_bodies[rb.ids.active_set_offset];
-                solver_body.copy_from(rb);
+                solver_body.copy_from(rb, params);
 
                 // NOTE: `dvel.angular` is actually storing angular velocity delta multiplied
                 //       by the square root of the inertia tensor:
@@ -235,7 +235,9 @@ impl VelocitySolver {
             let angvel = solver_body.sqrt_ii.transform_vector(solver_vels.angular);
 
             let mut new_vels = RigidBodyVelocity { linvel, angvel };
-            new_vels = new_vels.apply_damping(params.dt, &solver_body.damping);
+            new_vels = new_vels
+                .apply_damping(params.dt, &solver_body.damping)
+                .clamp_magnitudes(params.max_linear_velocity, params.max_angular_velocity);
             let new_pos =
                 new_vels.integrate(params.dt, &solver_body.position, &solver_body.local_com);
             solver_body.integrated_vels += new_vels;
@@ -309,7 +311,9 @@ impl VelocitySolver {
                     linvel: solver_vels.linear,
                     angvel: dangvel,
                 };
-                new_vels = new_vels.apply_damping(params.dt, &solver_body.damping);
+                new_vels = new_vels
+                    .apply_damping(params.dt, &solver_body.damping)
+                    .clamp_magnitudes(params.max_linear_velocity, params.max_angular_velocity);
 
                 // NOTE: using integrated_vels instead of interpolation is interesting for
                 //       high angular velocities. However, it is a bit inexact due to the
@@ -1,6 +1,6 @@
 use crate::dynamics::RigidBodySet;
-use crate::geometry::{BroadPhasePairEvent, ColliderHandle, ColliderSet};
-use parry::math::Real;
+use crate::geometry::{Aabb, BroadPhasePairEvent, ColliderHandle, ColliderSet};
+use parry::math::{Real, Vector};
 
 /// An internal index stored in colliders by some broad-phase algorithms.
 pub type BroadPhaseProxyIndex = u32;
@@ -12,6 +12,23 @@ pub type BroadPhaseProxyIndex = u32;
 /// two objects don’t actually touch, but it is incorrect to remove a pair between two objects
 /// that are still touching. In other words, it can have false-positive (though these induce
 /// some computational overhead on the narrow-phase), but cannot have false-negative.
+///
+/// Rapier ships two implementations: the [`BroadPhaseMultiSap`](crate::geometry::BroadPhaseMultiSap),
+/// used by default, and the much simpler [`BroadPhaseHashGrid`](crate::geometry::BroadPhaseHashGrid)
+/// that can serve as a starting point for custom implementations.
+///
+/// # Proxies
+/// The broad-phase is responsible for tracking its own proxies: a proxy must be created the
+/// first time a collider appears in the `modified_colliders` of [`BroadPhase::update`], updated
+/// whenever it appears there again, and deleted when it appears in the `removed_colliders`.
+/// Disabled colliders are reported as removed, and reported as modified again once re-enabled.
+///
+/// # Serialization
+/// The broad-phase is serialized alongside the collider set it was updated with. If it keeps
+/// an index into its internal structures through [`Collider::set_internal_broad_phase_proxy_index`](crate::geometry::Collider::set_internal_broad_phase_proxy_index),
+/// that index is serialized with the collider, so both must be restored together. With the
+/// `serde-serialize` feature enabled, implementations are expected to implement `Serialize` and
+/// `Deserialize` so they can be snapshotted with the rest of the physics world.
 pub trait BroadPhase: Send + Sync + 'static {
     /// Updates the broad-phase.
     ///
@@ -47,4 +64,38 @@ pub trait BroadPhase: Send + Sync + 'static {
         removed_colliders: &[ColliderHandle],
         events: &mut Vec<BroadPhasePairEvent>,
     );
+
+    /// Calls `callback` with the handle of every collider with a broad-phase proxy intersecting
+    /// `aabb`, until it returns `false`.
+    ///
+    /// This reads the internal structures of the broad-phase directly, without allocating, and
+    /// is meant for very frequent coarse queries that don’t justify maintaining a
+    /// [`QueryPipeline`](crate::pipeline::QueryPipeline). The result is only approximate: the
+    /// proxies are the colliders broad-phase AABBs as of the last [`BroadPhase::update`] (see
+    /// [`Collider::compute_broad_phase_aabb`](crate::geometry::Collider::compute_broad_phase_aabb)),
+    /// enlarged by the prediction distance and, for rigid-bodies with soft-CCD, by their
+    /// predicted motion.
+    /// Changes made to the colliders since the last update are not taken into account.
+    ///
+    /// Each collider is reported at most once, in no particular order.
+    fn colliders_in_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(ColliderHandle) -> bool);
+
+    /// Removes every proxy from this broad-phase, without generating any event.
+    ///
+    /// This is meant for emptying a whole world in-place, together with the
+    /// [`ColliderSet::clear`] and [`NarrowPhase::clear`](crate::geometry::NarrowPhase::clear)
+    /// methods. Implementations should keep their allocated memory for reuse.
+    fn clear(&mut self);
+
+    /// Notifies the broad-phase that the world origin has been moved to `offset`.
+    ///
+    /// This is meant for floating-origin setups: call this right after translating every
+    /// rigid-body and collider by `-offset`. The broad-phase can then keep its internal
+    /// state instead of seeing every collider jump by `-offset` at the next update.
+    ///
+    /// The default implementation does nothing, which is only suitable for broad-phases that
+    /// don’t keep the collider positions between two updates.
+    fn shift_origin(&mut self, offset: Vector<Real>) {
+        let _ = offset;
+    }
 }
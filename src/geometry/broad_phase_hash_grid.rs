@@ -0,0 +1,569 @@
+use crate::dynamics::RigidBodySet;
+use crate::geometry::{
+    Aabb, BroadPhase, BroadPhasePairEvent, Collider, ColliderHandle, ColliderPair, ColliderSet,
+};
+use crate::math::{Isometry, Point, Real, Vector};
+use parry::bounding_volume::BoundingVolume;
+use parry::utils::hashmap::HashMap;
+
+type CellKey = Point<i32>;
+
+/// Colliders spanning more cells than this in total, like half-spaces, aren’t inserted into the
+/// grid but tested against every other collider instead.
+const MAX_CELLS: i128 = 4096;
+
+/// A simple broad-phase based on a uniform spatial hash-grid.
+///
+/// Every collider AABB is inserted into all the grid cells it intersects. At each update, the
+/// moved colliders are only tested against the colliders sharing at least one cell with them.
+///
+/// This broad-phase is much simpler than the [`BroadPhaseMultiSap`](crate::geometry::BroadPhaseMultiSap)
+/// and serves as a reference implementation of the [`BroadPhase`] trait. It performs well when
+/// all the colliders have a size similar to the cell size. Colliders much larger than a cell
+/// will be inserted into a large number of cells, and many colliders smaller than a cell
+/// sharing the same cell will all be tested against each other. Colliders spanning more than
+/// 4096 cells, like half-spaces, aren’t stored in the grid at all: they are tested against every
+/// other collider.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct BroadPhaseHashGrid {
+    cell_size: Real,
+    // The loosened AABB of every collider known by this broad-phase.
+    aabbs: HashMap<ColliderHandle, Aabb>,
+    cells: HashMap<CellKey, Vec<ColliderHandle>>,
+    // The colliders too large to be inserted into the cells.
+    unbounded: Vec<ColliderHandle>,
+    // The colliders each collider currently forms a pair with. This is symmetric.
+    neighbors: HashMap<ColliderHandle, Vec<ColliderHandle>>,
+}
+
+impl Default for BroadPhaseHashGrid {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl BroadPhaseHashGrid {
+    /// Creates a new empty broad-phase with cells of the given width.
+    pub fn new(cell_size: Real) -> Self {
+        assert!(cell_size > 0.0, "The cell size must be positive.");
+        Self {
+            cell_size,
+            aabbs: HashMap::default(),
+            cells: HashMap::default(),
+            unbounded: Vec::new(),
+            neighbors: HashMap::default(),
+        }
+    }
+
+    /// The width of each cell of this grid.
+    pub fn cell_size(&self) -> Real {
+        self.cell_size
+    }
+
+    fn cell_key(&self, point: Point<Real>) -> CellKey {
+        (point / self.cell_size).map(|e| e.floor() as i32)
+    }
+
+    fn cell_range(&self, aabb: &Aabb) -> (CellKey, CellKey) {
+        (self.cell_key(aabb.mins), self.cell_key(aabb.maxs))
+    }
+
+    fn is_unbounded(&self, aabb: &Aabb) -> bool {
+        let (mins, maxs) = self.cell_range(aabb);
+        // NOTE: this can’t overflow since each factor fits in 33 bits.
+        let num_cells: i128 = (0..mins.len())
+            .map(|i| maxs[i] as i128 - mins[i] as i128 + 1)
+            .product();
+        num_cells > MAX_CELLS
+    }
+
+    fn for_each_cell((mins, maxs): (CellKey, CellKey), mut f: impl FnMut(CellKey)) {
+        for i in mins.x..=maxs.x {
+            for j in mins.y..=maxs.y {
+                #[cfg(feature = "dim2")]
+                f(Point::new(i, j));
+                #[cfg(feature = "dim3")]
+                for k in mins.z..=maxs.z {
+                    f(Point::new(i, j, k));
+                }
+            }
+        }
+    }
+
+    fn insert_in_cells(&mut self, handle: ColliderHandle, aabb: &Aabb) {
+        if self.is_unbounded(aabb) {
+            self.unbounded.push(handle);
+            return;
+        }
+
+        let range = self.cell_range(aabb);
+        let cells = &mut self.cells;
+        Self::for_each_cell(range, |key| cells.entry(key).or_default().push(handle));
+    }
+
+    fn remove_from_cells(&mut self, handle: ColliderHandle, aabb: &Aabb) {
+        if self.is_unbounded(aabb) {
+            self.unbounded.retain(|h| *h != handle);
+            return;
+        }
+
+        let range = self.cell_range(aabb);
+        let cells = &mut self.cells;
+        Self::for_each_cell(range, |key| {
+            if let Some(cell) = cells.get_mut(&key) {
+                cell.retain(|h| *h != handle);
+
+                if cell.is_empty() {
+                    let _ = cells.remove(&key);
+                }
+            }
+        });
+    }
+
+    fn remove_neighbor(&mut self, handle: ColliderHandle, neighbor: ColliderHandle) {
+        if let Some(neighbors) = self.neighbors.get_mut(&handle) {
+            neighbors.retain(|h| *h != neighbor);
+        }
+    }
+
+    fn remove_collider(&mut self, handle: ColliderHandle) {
+        if let Some(aabb) = self.aabbs.remove(&handle) {
+            self.remove_from_cells(handle, &aabb);
+        }
+
+        // Pairs involving removed colliders are cleaned-up by the narrow-phase, so
+        // no `DeletePair` event needs to be emitted.
+        for neighbor in self.neighbors.remove(&handle).unwrap_or_default() {
+            self.remove_neighbor(neighbor, handle);
+        }
+    }
+
+    fn collider_aabb(
+        collider: &Collider,
+        prediction_distance: Real,
+        next_position: Option<&Isometry<Real>>,
+    ) -> Option<Aabb> {
+        let margin = collider.broad_phase_prediction_margin(prediction_distance);
+        let mut aabb = collider.compute_broad_phase_aabb(margin);
+
+        if let Some(next_position) = next_position {
+            aabb.merge(&collider.compute_broad_phase_aabb_at(next_position, margin));
+        }
+
+        // Reject Aabbs with non-finite values.
+        (aabb.mins.coords.iter().all(|e| e.is_finite())
+            && aabb.maxs.coords.iter().all(|e| e.is_finite()))
+        .then_some(aabb)
+    }
+
+    fn find_pairs(&mut self, handle: ColliderHandle, events: &mut Vec<BroadPhasePairEvent>) {
+        let Some(aabb) = self.aabbs.get(&handle).copied() else {
+            return;
+        };
+
+        // Remove the pairs that no longer overlap.
+        let neighbors = self.neighbors.get(&handle).cloned().unwrap_or_default();
+        for neighbor in neighbors {
+            if !self.aabbs[&neighbor].intersects(&aabb) {
+                self.remove_neighbor(handle, neighbor);
+                self.remove_neighbor(neighbor, handle);
+                events.push(BroadPhasePairEvent::DeletePair(ColliderPair::new(
+                    handle, neighbor,
+                )));
+            }
+        }
+
+        // Add the new overlapping pairs.
+        let mut candidates = self.unbounded.clone();
+        if self.is_unbounded(&aabb) {
+            candidates.extend(self.aabbs.keys().copied());
+        } else {
+            Self::for_each_cell(self.cell_range(&aabb), |key| {
+                if let Some(cell) = self.cells.get(&key) {
+                    candidates.extend_from_slice(cell);
+                }
+            });
+        }
+        candidates.sort_unstable_by_key(|h| h.into_raw_parts());
+        candidates.dedup();
+
+        for candidate in candidates {
+            if candidate == handle
+                || !self.aabbs[&candidate].intersects(&aabb)
+                || self.neighbors[&handle].contains(&candidate)
+            {
+                continue;
+            }
+
+            self.neighbors.entry(handle).or_default().push(candidate);
+            self.neighbors.entry(candidate).or_default().push(handle);
+            events.push(BroadPhasePairEvent::AddPair(ColliderPair::new(
+                handle, candidate,
+            )));
+        }
+    }
+}
+
+impl BroadPhase for BroadPhaseHashGrid {
+    fn update(
+        &mut self,
+        dt: Real,
+        prediction_distance: Real,
+        colliders: &mut ColliderSet,
+        bodies: &RigidBodySet,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) {
+        for removed in removed_colliders {
+            self.remove_collider(*removed);
+        }
+
+        let mut moved = vec![];
+
+        for handle in modified_colliders {
+            // NOTE: we use `get` because the collider may no longer
+            //       exist if it has been removed.
+            let Some(co) = colliders.get(*handle) else {
+                continue;
+            };
+
+            if !co.is_enabled() || !co.changes.needs_broad_phase_update() {
+                continue;
+            }
+
+            let next_pos = co.parent.and_then(|p| {
+                let parent = bodies.get(p.handle)?;
+                (parent.soft_ccd_prediction() > 0.0).then(|| {
+                    parent.predict_position_using_velocity_and_forces_with_max_dist(
+                        dt,
+                        parent.soft_ccd_prediction(),
+                    ) * p.pos_wrt_parent
+                })
+            });
+
+            let Some(aabb) = Self::collider_aabb(co, prediction_distance, next_pos.as_ref()) else {
+                continue;
+            };
+
+            if let Some(prev_aabb) = self.aabbs.insert(*handle, aabb) {
+                self.remove_from_cells(*handle, &prev_aabb);
+            }
+
+            self.insert_in_cells(*handle, &aabb);
+            let _ = self.neighbors.entry(*handle).or_default();
+            moved.push(*handle);
+        }
+
+        for handle in moved {
+            self.find_pairs(handle, events);
+        }
+    }
+
+    fn colliders_in_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(ColliderHandle) -> bool) {
+        for handle in &self.unbounded {
+            if self.aabbs[handle].intersects(aabb) && !callback(*handle) {
+                return;
+            }
+        }
+
+        if self.is_unbounded(aabb) {
+            for (handle, co_aabb) in &self.aabbs {
+                if !self.is_unbounded(co_aabb) && co_aabb.intersects(aabb) && !callback(*handle) {
+                    return;
+                }
+            }
+        } else {
+            let mut stopped = false;
+
+            Self::for_each_cell(self.cell_range(aabb), |key| {
+                let Some(cell) = self.cells.get(&key).filter(|_| !stopped) else {
+                    return;
+                };
+
+                for handle in cell {
+                    let co_aabb = &self.aabbs[handle];
+
+                    // Only report the collider from the cell containing the min corner of
+                    // the intersection of the AABBs, so it is reported only once.
+                    if co_aabb.intersects(aabb)
+                        && self.cell_key(co_aabb.mins.sup(&aabb.mins)) == key
+                        && !callback(*handle)
+                    {
+                        stopped = true;
+                        return;
+                    }
+                }
+            });
+        }
+    }
+
+    fn clear(&mut self) {
+        self.aabbs.clear();
+        self.cells.clear();
+        self.unbounded.clear();
+        self.neighbors.clear();
+    }
+
+    fn shift_origin(&mut self, offset: Vector<Real>) {
+        let handles: Vec<_> = self.aabbs.keys().copied().collect();
+        self.cells.clear();
+        self.unbounded.clear();
+
+        for handle in handles {
+            let aabb = self.aabbs.get_mut(&handle).unwrap();
+            aabb.mins -= offset;
+            aabb.maxs -= offset;
+            let aabb = *aabb;
+            self.insert_in_cells(handle, &aabb);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{
+        ActiveCollisionTypes, BroadPhase, BroadPhaseHashGrid, ColliderBuilder, ColliderHandle,
+        Cuboid, SharedShape,
+    };
+    use crate::math::{Real, Vector};
+    use crate::pipeline::PhysicsWorld;
+    use std::collections::HashSet;
+
+    // A small deterministic linear congruential generator.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> Real {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 40) as Real / (1u64 << 24) as Real
+        }
+
+        fn vector(&mut self, extent: Real) -> Vector<Real> {
+            Vector::from_fn(|_, _| (self.next() * 2.0 - 1.0) * extent)
+        }
+    }
+
+    fn step(world: &mut PhysicsWorld, broad_phase: &mut dyn BroadPhase) {
+        world.physics_pipeline.step(
+            &world.gravity,
+            &world.integration_parameters,
+            &mut world.islands,
+            broad_phase,
+            &mut world.narrow_phase,
+            &mut world.bodies,
+            &mut world.colliders,
+            &mut world.impulse_joints,
+            &mut world.multibody_joints,
+            &mut world.ccd_solver,
+            None,
+            &(),
+            &(),
+        );
+    }
+
+    fn contact_pairs(world: &PhysicsWorld) -> HashSet<(ColliderHandle, ColliderHandle)> {
+        world
+            .narrow_phase
+            .contact_pairs()
+            .filter(|pair| pair.has_any_active_contact)
+            .map(|pair| {
+                let (h1, h2) = (pair.collider1, pair.collider2);
+                if h1.into_raw_parts() < h2.into_raw_parts() {
+                    (h1, h2)
+                } else {
+                    (h2, h1)
+                }
+            })
+            .collect()
+    }
+
+    // Builds the same randomized scene of moving, appearing, and disappearing colliders
+    // with the given broad-phase, and records the contact pairs after each step.
+    fn randomized_scene_contacts(
+        mut broad_phase: impl BroadPhase,
+    ) -> Vec<HashSet<(ColliderHandle, ColliderHandle)>> {
+        let mut rng = Lcg(42);
+        let mut world = PhysicsWorld::default();
+        let mut handles = vec![];
+        let mut result = vec![];
+
+        let body = world.insert_body(RigidBodyBuilder::fixed());
+
+        for i in 0..80 {
+            let co = if i % 2 == 0 {
+                ColliderBuilder::ball(0.2 + rng.next())
+            } else {
+                ColliderBuilder::new(SharedShape::new(Cuboid::new(
+                    rng.vector(1.0).abs() * 0.5 + Vector::repeat(0.1),
+                )))
+            }
+            .active_collision_types(ActiveCollisionTypes::all())
+            .translation(rng.vector(8.0));
+
+            if i % 5 == 0 {
+                handles.push(world.insert_collider_with_parent(co, body));
+            } else {
+                handles.push(world.insert_collider(co));
+            }
+        }
+
+        // A half-space is too large to be inserted into the grid cells.
+        handles.push(
+            world.insert_collider(
+                ColliderBuilder::halfspace(Vector::y_axis())
+                    .active_collision_types(ActiveCollisionTypes::all())
+                    .translation(-Vector::y() * 4.0),
+            ),
+        );
+
+        for _ in 0..20 {
+            for handle in &handles {
+                if rng.next() < 0.5 {
+                    let translation = world.colliders[*handle].translation() + rng.vector(1.0);
+                    world.colliders[*handle].set_translation(translation);
+                }
+            }
+
+            let removed = handles.swap_remove((rng.next() * handles.len() as Real) as usize);
+            let _ = world.remove_collider(removed);
+            let co = ColliderBuilder::ball(0.2 + rng.next())
+                .active_collision_types(ActiveCollisionTypes::all())
+                .translation(rng.vector(8.0));
+            handles.push(world.insert_collider(co));
+
+            step(&mut world, &mut broad_phase);
+            result.push(contact_pairs(&world));
+        }
+
+        result
+    }
+
+    #[test]
+    fn same_contact_pairs_as_multi_sap() {
+        let expected = randomized_scene_contacts(crate::geometry::BroadPhaseMultiSap::new());
+        assert!(expected.iter().all(|pairs| !pairs.is_empty()));
+        assert_eq!(
+            randomized_scene_contacts(BroadPhaseHashGrid::new(1.0)),
+            expected
+        );
+        assert_eq!(
+            randomized_scene_contacts(BroadPhaseHashGrid::new(0.3)),
+            expected
+        );
+    }
+
+    // Checks that `colliders_in_aabb` reports each collider with an AABB intersecting randomized
+    // query AABBs exactly once, after the randomized scene has been simulated for a few steps.
+    fn check_colliders_in_aabb(mut broad_phase: impl BroadPhase) {
+        use crate::geometry::Aabb;
+        use crate::math::Point;
+        use parry::bounding_volume::BoundingVolume;
+
+        let mut rng = Lcg(7);
+        let mut world = PhysicsWorld::default();
+        let mut handles = vec![];
+
+        for i in 0..200 {
+            let co = if i % 2 == 0 {
+                ColliderBuilder::ball(0.1 + rng.next())
+            } else {
+                ColliderBuilder::new(SharedShape::new(Cuboid::new(
+                    rng.vector(2.0).abs() + Vector::repeat(0.1),
+                )))
+            }
+            .translation(rng.vector(20.0));
+            handles.push(world.insert_collider(co));
+        }
+
+        // Includes a huge collider, and a collider far from the others.
+        handles.push(world.insert_collider(ColliderBuilder::halfspace(Vector::y_axis())));
+        handles.push(
+            world.insert_collider(ColliderBuilder::ball(0.5).translation(Vector::repeat(1.0e4))),
+        );
+
+        for _ in 0..5 {
+            for handle in &handles[..200] {
+                let translation = world.colliders[*handle].translation() + rng.vector(2.0);
+                world.colliders[*handle].set_translation(translation);
+            }
+            step(&mut world, &mut broad_phase);
+        }
+
+        let prediction = world.integration_parameters.prediction_distance();
+        let num_queries = 200;
+
+        for i in 0..num_queries {
+            let extent = [0.01, 1.0, 10.0, 1.0e5][i % 4];
+            let center = Point::from(rng.vector(25.0));
+            let aabb = Aabb::from_half_extents(center, rng.vector(extent).abs());
+
+            let mut found = vec![];
+            broad_phase.colliders_in_aabb(&aabb, &mut |handle| {
+                found.push(handle);
+                true
+            });
+
+            let expected: HashSet<_> = world
+                .colliders
+                .iter()
+                .filter(|(_, co)| {
+                    co.compute_broad_phase_aabb(co.broad_phase_prediction_margin(prediction))
+                        .intersects(&aabb)
+                })
+                .map(|(handle, _)| handle)
+                .collect();
+
+            assert_eq!(found.len(), expected.len(), "Colliders reported twice.");
+            assert_eq!(found.into_iter().collect::<HashSet<_>>(), expected);
+        }
+
+        // The traversal stops as soon as the callback returns `false`.
+        let everything = Aabb::from_half_extents(Point::origin(), Vector::repeat(1.0e6));
+        let mut num_found = 0;
+        broad_phase.colliders_in_aabb(&everything, &mut |_| {
+            num_found += 1;
+            num_found < 10
+        });
+        assert_eq!(num_found, 10);
+    }
+
+    #[test]
+    fn colliders_spanning_many_cells_arent_inserted_into_the_grid() {
+        let mut broad_phase = BroadPhaseHashGrid::new(1.0);
+        let mut world = PhysicsWorld::default();
+        // Less than 1024 cells along each axis, but far too many cells in total.
+        let large = world.insert_collider(
+            ColliderBuilder::new(SharedShape::new(Cuboid::new(Vector::repeat(300.0))))
+                .translation(-Vector::y() * 300.0),
+        );
+        let ball = world.insert_collider(
+            ColliderBuilder::ball(0.5)
+                .active_collision_types(ActiveCollisionTypes::all())
+                .translation(Vector::y() * 0.4),
+        );
+        step(&mut world, &mut broad_phase);
+
+        assert_eq!(broad_phase.unbounded, vec![large]);
+        assert!(broad_phase.cells.len() <= 8);
+        assert_eq!(contact_pairs(&world).len(), 1);
+
+        let _ = world.remove_collider(ball);
+        let _ = world.remove_collider(large);
+        step(&mut world, &mut broad_phase);
+        assert!(broad_phase.unbounded.is_empty());
+        assert!(broad_phase.cells.is_empty());
+    }
+
+    #[test]
+    fn colliders_in_aabb_match_brute_force() {
+        check_colliders_in_aabb(crate::geometry::BroadPhaseMultiSap::new());
+        check_colliders_in_aabb(BroadPhaseHashGrid::new(1.0));
+        check_colliders_in_aabb(BroadPhaseHashGrid::new(0.3));
+    }
+}
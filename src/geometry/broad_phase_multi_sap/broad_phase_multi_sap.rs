@@ -2,10 +2,10 @@ use super::{
     BroadPhasePairEvent, ColliderPair, SAPLayer, SAPProxies, SAPProxy, SAPProxyData, SAPRegionPool,
 };
 use crate::geometry::{
-    BroadPhaseProxyIndex, Collider, ColliderBroadPhaseData, ColliderChanges, ColliderHandle,
+    Aabb, BroadPhaseProxyIndex, Collider, ColliderBroadPhaseData, ColliderChanges, ColliderHandle,
     ColliderSet,
 };
-use crate::math::{Isometry, Real};
+use crate::math::{Isometry, Real, Vector};
 use crate::prelude::{BroadPhase, RigidBodySet};
 use crate::utils::IndexMut2;
 use parry::bounding_volume::BoundingVolume;
@@ -64,6 +64,12 @@ use parry::utils::hashmap::HashMap;
 /// this has the benefit of keep the overall complexity of the algorithm O(1) in the typical specially
 /// coherent scenario.
 ///
+/// Far from the origin, the floating-point precision of the Aabbs decreases. To remain accurate
+/// there, the regions are never narrower than a small fraction (2^-16) of their distance to the
+/// origin of the broad-phase: small colliders far from the origin are moved to a larger layer.
+/// Applications using a floating origin should call [`BroadPhase::shift_origin`] whenever they
+/// translate the whole world.
+///
 /// From an implementation point-of-view, our hierarchical SAP is implemented with the following structures:
 /// - There is one `SAPLayer` per layer of the hierarchical grid.
 /// - Each `SAPLayer` contains multiple `SAPRegion` (each being a region of the grid represented by that layer).
@@ -79,6 +85,9 @@ pub struct BroadPhaseMultiSap {
     layers: Vec<SAPLayer>,
     smallest_layer: u8,
     largest_layer: u8,
+    // The proxies Aabbs are stored relative to this point to keep regions
+    // small and accurate when colliders are far from the world origin.
+    origin: Vector<Real>,
     // NOTE: we maintain this hashmap to simplify collider removal.
     //       This information is also present in the ColliderProxyId
     //       component. However if that component is removed, we need
@@ -128,12 +137,62 @@ impl BroadPhaseMultiSap {
             layers: Vec::new(),
             smallest_layer: 0,
             largest_layer: 0,
+            origin: Vector::zeros(),
             region_pool: Vec::new(),
             reporting: HashMap::default(),
             colliders_proxy_ids: HashMap::default(),
         }
     }
 
+    /// The point, in world-space, relative to which this broad-phase stores the colliders Aabbs.
+    ///
+    /// See [`BroadPhase::shift_origin`].
+    pub fn origin(&self) -> Vector<Real> {
+        self.origin
+    }
+
+    /// Checks that this broad-phase is up-to-date with the given colliders.
+    ///
+    /// This is meant to check a broad-phase deserialized together with its collider set (e.g.
+    /// from a level file) before using it. It checks that every enabled collider, and only
+    /// them, has a proxy matching the proxy index stored in the collider, with an AABB
+    /// containing the collider’s AABB enlarged by the `prediction_distance`. The internal
+    /// regions aren’t checked: they are consistent with the proxies if both were serialized
+    /// together.
+    pub fn validate(&self, colliders: &ColliderSet, prediction_distance: Real) -> bool {
+        if self.colliders_proxy_ids.len() != colliders.iter_enabled().count() {
+            return false;
+        }
+
+        for (handle, proxy_id) in &self.colliders_proxy_ids {
+            let Some(co) = colliders.get(*handle).filter(|co| co.is_enabled()) else {
+                return false;
+            };
+            let Some(proxy) = self.proxies.get(*proxy_id) else {
+                return false;
+            };
+
+            if co.bf_data.proxy_index != *proxy_id
+                || !matches!(proxy.data, SAPProxyData::Collider(h) if h == *handle)
+            {
+                return false;
+            }
+
+            let aabb =
+                co.compute_broad_phase_aabb(co.broad_phase_prediction_margin(prediction_distance));
+            let aabb = Aabb::new(
+                super::clamp_point(aabb.mins - self.origin),
+                super::clamp_point(aabb.maxs - self.origin),
+            );
+
+            if !proxy.aabb.contains(&aabb) {
+                return false;
+            }
+        }
+
+        true
+    }
+
     /// Maintain the broad-phase internal state by taking collider removal into account.
     ///
     /// For each colliders marked as removed, we make their containing layer mark
@@ -356,14 +415,11 @@ impl BroadPhaseMultiSap {
         collider: &Collider,
         next_position: Option<&Isometry<Real>>,
     ) -> bool {
-        let mut aabb = collider.compute_collision_aabb(prediction_distance / 2.0);
+        let margin = collider.broad_phase_prediction_margin(prediction_distance);
+        let mut aabb = collider.compute_broad_phase_aabb(margin);
 
         if let Some(next_position) = next_position {
-            let next_aabb = collider
-                .shape
-                .compute_aabb(next_position)
-                .loosened(collider.contact_skin() + prediction_distance / 2.0);
-            aabb.merge(&next_aabb);
+            aabb.merge(&collider.compute_broad_phase_aabb_at(next_position, margin));
         }
 
         if aabb.mins.coords.iter().any(|e| !e.is_finite())
@@ -373,31 +429,52 @@ impl BroadPhaseMultiSap {
             return false;
         }
 
-        aabb.mins = super::clamp_point(aabb.mins);
-        aabb.maxs = super::clamp_point(aabb.maxs);
+        aabb.mins = super::clamp_point(aabb.mins - self.origin);
+        aabb.maxs = super::clamp_point(aabb.maxs - self.origin);
 
         let prev_aabb;
 
         let layer_id = if let Some(proxy) = self.proxies.get_mut(*proxy_index) {
+            if proxy.aabb == aabb
+                && !collider.changes.intersects(
+                    ColliderChanges::PARENT
+                        | ColliderChanges::SHAPE
+                        | ColliderChanges::SHAPE_DEFORMED
+                        | ColliderChanges::ENABLED_OR_DISABLED,
+                )
+            {
+                // The collider moved without changing its proxy, e.g., a bounding-sphere
+                // proxy rotating in place. No region or pair needs to be updated.
+                return false;
+            }
+
             let mut layer_id = proxy.layer_id;
             prev_aabb = proxy.aabb;
             proxy.aabb = aabb;
 
-            if collider.changes.contains(ColliderChanges::SHAPE) {
-                // If the shape was changed, then we need to see if this proxy should be
-                // migrated to a larger layer. Indeed, if the shape was replaced by
-                // a much larger shape, we need to promote the proxy to a bigger layer
-                // to avoid the O(n²) discretization problem.
-                let new_layer_depth = super::layer_containing_aabb(&aabb);
-                if new_layer_depth > proxy.layer_depth {
-                    self.layers[proxy.layer_id as usize]
-                        .proper_proxy_moved_to_bigger_layer(&mut self.proxies, *proxy_index);
-
-                    // We need to promote the proxy to the bigger layer.
-                    layer_id = self.ensure_layer_exists(new_layer_depth);
-                    self.proxies[*proxy_index].layer_id = layer_id;
-                    self.proxies[*proxy_index].layer_depth = new_layer_depth;
-                }
+            // If the shape was changed or deformed, then we need to see if this proxy should be
+            // migrated to a larger layer. Indeed, if the shape was replaced by
+            // a much larger shape, we need to promote the proxy to a bigger layer
+            // to avoid the O(n²) discretization problem.
+            // Similarly, if the collider moved far from the origin, its current layer
+            // may have regions too small to be represented accurately at its new position.
+            let new_layer_depth = if collider
+                .changes
+                .intersects(ColliderChanges::SHAPE | ColliderChanges::SHAPE_DEFORMED)
+            {
+                super::layer_containing_aabb(&aabb)
+            } else {
+                super::min_layer_depth_at(&aabb)
+            };
+
+            if new_layer_depth > proxy.layer_depth {
+                self.layers[proxy.layer_id as usize]
+                    .proper_proxy_moved_to_bigger_layer(&mut self.proxies, *proxy_index);
+
+                // We need to promote the proxy to the bigger layer.
+                layer_id = self.ensure_layer_exists(new_layer_depth);
+                self.proxies[*proxy_index].layer_id = layer_id;
+                self.proxies[*proxy_index].layer_depth = new_layer_depth;
             }
 
             layer_id
@@ -636,6 +713,35 @@ impl BroadPhase for BroadPhaseMultiSap {
         // layers to possible remove regions from larger layers that would become empty that way.
         self.complete_removals(colliders, removed_colliders);
     }
+
+    fn colliders_in_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(ColliderHandle) -> bool) {
+        // The proxies Aabbs are relative to the broad-phase origin.
+        let aabb = Aabb::new(
+            super::clamp_point(aabb.mins - self.origin),
+            super::clamp_point(aabb.maxs - self.origin),
+        );
+
+        for layer in &self.layers {
+            if !layer.colliders_in_aabb(&aabb, &self.proxies, callback) {
+                return;
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.proxies.clear();
+        self.layers.clear();
+        self.smallest_layer = 0;
+        self.largest_layer = 0;
+        self.colliders_proxy_ids.clear();
+        self.reporting.clear();
+    }
+
+    fn shift_origin(&mut self, offset: Vector<Real>) {
+        // The colliders Aabbs are stored relative to the origin, so they are left unchanged
+        // by moving the origin together with the colliders.
+        self.origin -= offset;
+    }
 }
 
 #[cfg(test)]
@@ -643,7 +749,89 @@ mod test {
     use crate::dynamics::{
         ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodyBuilder, RigidBodySet,
     };
-    use crate::geometry::{BroadPhase, BroadPhaseMultiSap, ColliderBuilder, ColliderSet};
+    use crate::geometry::{
+        ActiveCollisionTypes, BroadPhase, BroadPhaseMultiSap, BroadPhasePairEvent,
+        BroadPhaseProxyShape, ColliderBuilder, ColliderHandle, ColliderSet,
+    };
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::PhysicsWorld;
+    use parry::bounding_volume::{Aabb, BoundingVolume};
+    use std::collections::HashSet;
+
+    fn pairs(events: &[BroadPhasePairEvent]) -> HashSet<(ColliderHandle, ColliderHandle)> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                BroadPhasePairEvent::AddPair(pair) => {
+                    let (h1, h2) = (pair.collider1, pair.collider2);
+                    Some(if h1.into_raw_parts() < h2.into_raw_parts() {
+                        (h1, h2)
+                    } else {
+                        (h2, h1)
+                    })
+                }
+                BroadPhasePairEvent::DeletePair(_) => None,
+            })
+            .collect()
+    }
+
+    // A row of balls, some overlapping and some not, on top of a large ground.
+    fn scene(
+        offset: Vector<Real>,
+    ) -> (
+        BroadPhaseMultiSap,
+        ColliderSet,
+        Vec<ColliderHandle>,
+        HashSet<(ColliderHandle, ColliderHandle)>,
+    ) {
+        let mut broad_phase = BroadPhaseMultiSap::new();
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(100.0, 1.0);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(100.0, 1.0, 100.0);
+        let mut handles = vec![colliders.insert(ground.translation(offset))];
+
+        for (i, x) in [0.0, 0.75, 2.0, 2.5, 4.0, 6.0, 6.75].iter().enumerate() {
+            let y = if i % 2 == 0 { 1.5 } else { 4.0 };
+            let co =
+                ColliderBuilder::ball(0.5).translation(offset + Vector::x() * *x + Vector::y() * y);
+            handles.push(colliders.insert(co));
+        }
+
+        // Tiny colliders, much smaller than the floating-point precision at large coordinates.
+        for x in [10.0, 10.0, 11.0, 12.0] {
+            let co = ColliderBuilder::ball(1.0e-3).translation(offset + Vector::x() * x);
+            handles.push(colliders.insert(co));
+        }
+
+        let mut events = Vec::new();
+        broad_phase.update(
+            0.0,
+            0.0,
+            &mut colliders,
+            &bodies,
+            &handles,
+            &[],
+            &mut events,
+        );
+        let pairs = pairs(&events);
+        (broad_phase, colliders, handles, pairs)
+    }
+
+    fn scene_pairs(offset: Vector<Real>) -> HashSet<(ColliderHandle, ColliderHandle)> {
+        scene(offset).3
+    }
+
+    #[test]
+    fn large_coordinates_generate_the_same_pairs() {
+        let expected = scene_pairs(Vector::zeros());
+        assert!(!expected.is_empty());
+        assert_eq!(scene_pairs(Vector::repeat(1.0e6)), expected);
+        assert_eq!(scene_pairs(Vector::repeat(-1.0e6)), expected);
+    }
 
     #[test]
     fn test_add_update_remove() {
@@ -681,4 +869,192 @@ mod test {
         // Make sure the proxy handles is recycled properly.
         broad_phase.update(0.0, 0.0, &mut colliders, &bodies, &[coh], &[], &mut events);
     }
+
+    #[test]
+    fn shift_origin() {
+        let offset = Vector::repeat(1.0e6);
+        let (mut broad_phase, mut colliders, handles, _) = scene(offset);
+        let bodies = RigidBodySet::new();
+
+        // Move the world back to the origin.
+        broad_phase.shift_origin(offset);
+        for (_, co) in colliders.iter_mut() {
+            co.set_translation(co.translation() - offset);
+        }
+
+        let mut events = Vec::new();
+        broad_phase.update(
+            0.0,
+            0.0,
+            &mut colliders,
+            &bodies,
+            &handles,
+            &[],
+            &mut events,
+        );
+        assert!(events.is_empty());
+        assert_eq!(broad_phase.origin(), -offset);
+
+        // New pairs are still detected after the shift.
+        colliders[handles[2]].set_translation(Vector::x() * 0.75 + Vector::y() * 1.25);
+        broad_phase.update(
+            0.0,
+            0.0,
+            &mut colliders,
+            &bodies,
+            &[handles[2]],
+            &[],
+            &mut events,
+        );
+        assert_eq!(
+            pairs(&events),
+            [(handles[0], handles[2]), (handles[1], handles[2])]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn batch_removal() {
+        let (mut broad_phase, mut colliders, handles, _) = scene(Vector::zeros());
+        let mut bodies = RigidBodySet::new();
+        let mut islands = IslandManager::new();
+
+        // Remove every other collider at once, including the ground.
+        let to_remove: Vec<_> = handles.iter().copied().step_by(2).collect();
+        let removed = colliders.remove_batch(&to_remove, &mut islands, &mut bodies, true);
+        assert_eq!(
+            removed
+                .iter()
+                .map(|(handle, _)| *handle)
+                .collect::<Vec<_>>(),
+            to_remove
+        );
+
+        let mut events = Vec::new();
+        broad_phase.update(
+            0.0,
+            0.0,
+            &mut colliders,
+            &bodies,
+            &[],
+            &to_remove,
+            &mut events,
+        );
+
+        let aabb = Aabb::new(Point::origin(), Point::origin()).loosened(200.0);
+        let mut found = HashSet::new();
+        broad_phase.colliders_in_aabb(&aabb, &mut |handle| {
+            let _ = found.insert(handle);
+            true
+        });
+        let remaining: HashSet<_> = handles
+            .iter()
+            .copied()
+            .filter(|handle| !to_remove.contains(handle))
+            .collect();
+        assert_eq!(found, remaining);
+
+        // The remaining colliders still generate new pairs.
+        colliders[handles[3]].set_translation(Vector::x() * 0.75 + Vector::y() * 1.5);
+        events.clear();
+        broad_phase.update(
+            0.0,
+            0.0,
+            &mut colliders,
+            &bodies,
+            &[handles[3]],
+            &[],
+            &mut events,
+        );
+        assert_eq!(
+            pairs(&events),
+            [(handles[1], handles[3])].into_iter().collect()
+        );
+    }
+
+    // A grid of rods spinning in place, close enough for neighbors to touch.
+    fn spinning_rods(proxy_shape: BroadPhaseProxyShape) -> (PhysicsWorld, Vec<ColliderHandle>) {
+        let mut world = PhysicsWorld::default();
+        let mut handles = vec![];
+
+        for i in 0..6 {
+            for j in 0..6 {
+                let speed = 0.5 + ((i * 6 + j) % 5) as Real * 0.7;
+                #[cfg(feature = "dim2")]
+                let (position, angvel, rod) = (
+                    Vector::new(i as Real, j as Real) * 1.8,
+                    speed,
+                    ColliderBuilder::cuboid(1.0, 0.05),
+                );
+                #[cfg(feature = "dim3")]
+                let (position, angvel, rod) = (
+                    Vector::new(i as Real, 0.0, j as Real) * 1.8,
+                    Vector::y() * speed,
+                    ColliderBuilder::cuboid(1.0, 0.05, 0.05),
+                );
+
+                let body = world.insert_body(
+                    RigidBodyBuilder::kinematic_velocity_based()
+                        .translation(position)
+                        .angvel(angvel),
+                );
+                let rod = rod
+                    .active_collision_types(ActiveCollisionTypes::all())
+                    .broad_phase_proxy_shape(proxy_shape);
+                handles.push(world.insert_collider_with_parent(rod, body));
+            }
+        }
+
+        (world, handles)
+    }
+
+    fn touching_pairs(world: &PhysicsWorld) -> HashSet<(ColliderHandle, ColliderHandle)> {
+        world
+            .narrow_phase
+            .contact_pairs()
+            .filter(|pair| pair.has_any_active_contact)
+            .map(|pair| (pair.collider1, pair.collider2))
+            .collect()
+    }
+
+    #[test]
+    fn bounding_sphere_proxies_generate_the_same_contacts() {
+        let (mut aabbs, _) = spinning_rods(BroadPhaseProxyShape::Aabb);
+        let (mut spheres, handles) = spinning_rods(BroadPhaseProxyShape::BoundingSphere);
+        let automatic = BroadPhaseProxyShape::Automatic {
+            max_aspect_ratio: 10.0,
+        };
+        let (mut auto, _) = spinning_rods(automatic);
+
+        spheres.step();
+        let proxy_aabbs: Vec<_> = handles
+            .iter()
+            .map(|handle| {
+                let proxy_id = spheres.colliders[*handle].internal_broad_phase_proxy_index();
+                spheres.broad_phase.proxies[proxy_id].aabb
+            })
+            .collect();
+        aabbs.step();
+        auto.step();
+
+        let mut num_touching = 0;
+        for _ in 0..100 {
+            aabbs.step();
+            spheres.step();
+            auto.step();
+
+            let touching = touching_pairs(&aabbs);
+            num_touching += touching.len();
+            assert_eq!(touching_pairs(&spheres), touching);
+            assert_eq!(touching_pairs(&auto), touching);
+        }
+        assert!(num_touching > 0);
+
+        // The proxies of the rods never changed while they rotated in place.
+        for (handle, aabb) in handles.iter().zip(proxy_aabbs.iter()) {
+            let proxy_id = spheres.colliders[*handle].internal_broad_phase_proxy_index();
+            assert_eq!(spheres.broad_phase.proxies[proxy_id].aabb, *aabb);
+        }
+    }
 }
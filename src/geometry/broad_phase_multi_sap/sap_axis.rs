@@ -191,7 +191,7 @@ impl SAPAxis {
     /// equal to DELETED_AABB_VALUE, indicating that the endpoints should be deleted.
     ///
     /// Returns the number of deleted proxies such that `proxy.layer_depth <= layer_depth`.
-    pub fn delete_deleted_proxies_and_endpoints_after_subregion_removal(
+    pub fn delete_deleted_proxies_and_endpoints(
         &mut self,
         proxies: &SAPProxies,
         existing_proxies: &mut BitVec,
@@ -1,6 +1,6 @@
-use super::{SAPProxies, SAPProxy, SAPRegion, SAPRegionPool};
+use super::{SAPProxies, SAPProxy, SAPProxyData, SAPRegion, SAPRegionPool};
 use crate::geometry::broad_phase_multi_sap::DELETED_AABB_VALUE;
-use crate::geometry::{Aabb, BroadPhaseProxyIndex};
+use crate::geometry::{Aabb, BroadPhaseProxyIndex, ColliderHandle};
 use crate::math::{Point, Real};
 use parry::bounding_volume::BoundingVolume;
 use parry::utils::hashmap::{Entry, HashMap};
@@ -266,6 +266,92 @@ impl SAPLayer {
         }
     }
 
+    /// Calls `callback` with every collider proxy of this layer intersecting `aabb`, until it
+    /// returns `false`.
+    ///
+    /// Only the proxies belonging to this layer are reported, even if smaller layers’ proxies
+    /// were also added to its regions. Returns `false` if `callback` stopped the traversal.
+    pub fn colliders_in_aabb(
+        &self,
+        aabb: &Aabb,
+        proxies: &SAPProxies,
+        callback: &mut dyn FnMut(ColliderHandle) -> bool,
+    ) -> bool {
+        let start = super::point_key(aabb.mins, self.region_width);
+        let end = super::point_key(aabb.maxs, self.region_width);
+        let num_keys = (0..start.len())
+            .map(|i| end[i] as i64 - start[i] as i64 + 1)
+            .product::<i64>();
+
+        // A proxy intersecting `aabb` is in every region containing part of that intersection.
+        // To report it only once, it is only reported by the region containing the min corner
+        // of the intersection.
+        let mut visit_region = |region_key: Point<i32>, region_id: BroadPhaseProxyIndex| {
+            let region = proxies[region_id].data.as_region();
+
+            for endpoint in &region.axes[0].endpoints {
+                if endpoint.value > aabb.maxs.x {
+                    break;
+                }
+
+                if !endpoint.is_start() || endpoint.is_sentinel() {
+                    continue;
+                }
+
+                let proxy = &proxies[endpoint.proxy()];
+
+                if let SAPProxyData::Collider(handle) = proxy.data {
+                    if proxy.layer_id == self.layer_id
+                        && proxy.aabb.intersects(aabb)
+                        && super::point_key(proxy.aabb.mins.sup(&aabb.mins), self.region_width)
+                            == region_key
+                        && !callback(handle)
+                    {
+                        return false;
+                    }
+                }
+            }
+
+            true
+        };
+
+        if num_keys > self.regions.len() as i64 {
+            // The aabb covers more regions than there are in this layer.
+            for (region_key, region_id) in &self.regions {
+                let in_range =
+                    (0..start.len()).all(|i| region_key[i] >= start[i] && region_key[i] <= end[i]);
+
+                if in_range && !visit_region(*region_key, *region_id) {
+                    return false;
+                }
+            }
+        } else {
+            #[cfg(feature = "dim2")]
+            let k_range = 0..1;
+            #[cfg(feature = "dim3")]
+            let k_range = start.z..=end.z;
+
+            for i in start.x..=end.x {
+                for j in start.y..=end.y {
+                    for _k in k_range.clone() {
+                        #[cfg(feature = "dim2")]
+                        let region_key = Point::new(i, j);
+                        #[cfg(feature = "dim3")]
+                        let region_key = Point::new(i, j, _k);
+
+                        if let Some(region_id) = self.regions.get(&region_key) {
+                            if !visit_region(region_key, *region_id) {
+                                return false;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
     pub fn predelete_proxy(&mut self, proxies: &mut SAPProxies, proxy_index: BroadPhaseProxyIndex) {
         // Discretize the Aabb to find the regions that need to be invalidated.
         let proxy_aabb = &mut proxies[proxy_index].aabb;
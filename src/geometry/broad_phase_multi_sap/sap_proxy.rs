@@ -96,6 +96,11 @@ impl SAPProxies {
         }
     }
 
+    pub fn clear(&mut self) {
+        self.elements.clear();
+        self.first_free = NEXT_FREE_SENTINEL;
+    }
+
     pub fn insert(&mut self, proxy: SAPProxy) -> BroadPhaseProxyIndex {
         if self.first_free != NEXT_FREE_SENTINEL {
             let proxy_id = self.first_free;
@@ -18,6 +18,8 @@ pub struct SAPRegion {
     pub id_in_parent_subregion: u32,
     pub update_count: u8,
     pub needs_update_after_subregion_removal: bool,
+    // Set when some of the proxies of this region were pre-deleted since its last update.
+    pub has_predeleted_proxies: bool,
     // Number of proxies (added to this region) that originates
     // from the layer at depth <= the depth of the layer containing
     // this region.
@@ -40,6 +42,7 @@ impl SAPRegion {
             id_in_parent_subregion: crate::INVALID_U32,
             update_count: 0,
             needs_update_after_subregion_removal: false,
+            has_predeleted_proxies: false,
             subproper_proxy_count: 0,
         }
     }
@@ -65,6 +68,7 @@ impl SAPRegion {
         old.id_in_parent_subregion = crate::INVALID_U32;
         old.subregions.clear();
         old.needs_update_after_subregion_removal = false;
+        old.has_predeleted_proxies = false;
 
         // The rest of the fields should be "empty"
         assert_eq!(old.subproper_proxy_count, 0);
@@ -147,6 +151,7 @@ impl SAPRegion {
         // method. However we don't actually need it because the deletion will be
         // handled transparently during the next update.
         self.update_count = self.update_count.max(1);
+        self.has_predeleted_proxies = true;
     }
 
     pub fn mark_as_dirty(&mut self) {
@@ -192,12 +197,11 @@ impl SAPRegion {
     pub fn update_after_subregion_removal(&mut self, proxies: &SAPProxies, layer_depth: i8) {
         if self.needs_update_after_subregion_removal {
             for axis in &mut self.axes {
-                self.subproper_proxy_count -= axis
-                    .delete_deleted_proxies_and_endpoints_after_subregion_removal(
-                        proxies,
-                        &mut self.existing_proxies,
-                        layer_depth,
-                    );
+                self.subproper_proxy_count -= axis.delete_deleted_proxies_and_endpoints(
+                    proxies,
+                    &mut self.existing_proxies,
+                    layer_depth,
+                );
             }
             self.needs_update_after_subregion_removal = false;
         }
@@ -214,6 +218,20 @@ impl SAPRegion {
             let mut total_deleted = 0;
             let mut total_deleted_subproper = 0;
 
+            if self.has_predeleted_proxies {
+                // Remove the endpoints of all the pre-deleted proxies in a single pass. Otherwise,
+                // `update_endpoints` would move them one by one past all the other endpoints,
+                // which is quadratic when removing many proxies at once.
+                for axis in &mut self.axes {
+                    self.subproper_proxy_count -= axis.delete_deleted_proxies_and_endpoints(
+                        proxies,
+                        &mut self.existing_proxies,
+                        layer_depth,
+                    );
+                }
+                self.has_predeleted_proxies = false;
+            }
+
             for dim in 0..DIM {
                 self.axes[dim].update_endpoints(dim, proxies, reporting);
                 let (num_deleted, num_deleted_subproper) = self.axes[dim]
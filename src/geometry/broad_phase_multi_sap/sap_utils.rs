@@ -8,6 +8,11 @@ pub(crate) const DELETED_AABB_VALUE: Real = SENTINEL_VALUE / 2.0;
 pub(crate) const MAX_AABB_EXTENT: Real = SENTINEL_VALUE / 4.0;
 pub(crate) const REGION_WIDTH_BASE: Real = 1.0;
 pub(crate) const REGION_WIDTH_POWER_BASIS: Real = 5.0;
+/// Regions are never smaller than this fraction of their distance to the broad-phase origin.
+///
+/// Smaller regions would be thinner than the floating-point precision at their location and
+/// their integer keys would overflow far from the origin.
+pub(crate) const REGION_WIDTH_MIN_RELATIVE_TO_ORIGIN: Real = 1.0 / 65536.0;
 
 pub(crate) fn sort2(a: u32, b: u32) -> (u32, u32) {
     assert_ne!(a, b);
@@ -49,11 +54,30 @@ pub(crate) fn region_width(depth: i8) -> Real {
 ///
 /// If the code bellow, we select a layer such that each region can
 /// contain at least a chain of 10 contiguous objects with that Aabb.
+/// Layers with regions too small for the Aabb position are skipped
+/// (see [`min_layer_depth_at`]).
 pub(crate) fn layer_containing_aabb(aabb: &Aabb) -> i8 {
     // Max number of elements of this size we would like one region to be able to contain.
     const NUM_ELEMENTS_PER_DIMENSION: Real = 10.0;
 
     let width = 2.0 * aabb.half_extents().norm() * NUM_ELEMENTS_PER_DIMENSION;
+    layer_depth_for_width(width).max(min_layer_depth_at(aabb))
+}
+
+/// Computes the depth of the smallest layer with regions wide enough to be accurately
+/// represented at the location of the given Aabb.
+pub(crate) fn min_layer_depth_at(aabb: &Aabb) -> i8 {
+    let dist_to_origin = aabb.mins.coords.amax().max(aabb.maxs.coords.amax());
+    let min_width = dist_to_origin.max(1.0) * REGION_WIDTH_MIN_RELATIVE_TO_ORIGIN;
+    // Round up so the region width is never smaller than `min_width`.
+    (min_width / REGION_WIDTH_BASE)
+        .log(REGION_WIDTH_POWER_BASIS)
+        .ceil()
+        .max(i8::MIN as Real)
+        .min(i8::MAX as Real) as i8
+}
+
+fn layer_depth_for_width(width: Real) -> i8 {
     (width / REGION_WIDTH_BASE)
         .log(REGION_WIDTH_POWER_BASIS)
         .round()
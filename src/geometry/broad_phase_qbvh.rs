@@ -58,7 +58,9 @@ impl BroadPhaseQbvh {
                 colliders.iter().map(|(handle, collider)| {
                     (
                         handle,
-                        collider.compute_collision_aabb(prediction_distance / 2.0),
+                        collider.compute_broad_phase_aabb(
+                            collider.broad_phase_prediction_margin(prediction_distance),
+                        ),
                     )
                 }),
                 margin,
@@ -75,7 +77,10 @@ impl BroadPhaseQbvh {
             }
 
             let _ = self.qbvh.refit(margin, &mut self.workspace, |handle| {
-                colliders[*handle].compute_collision_aabb(prediction_distance / 2.0)
+                let collider = &colliders[*handle];
+                collider.compute_broad_phase_aabb(
+                    collider.broad_phase_prediction_margin(prediction_distance),
+                )
             });
             self.qbvh
                 .traverse_modified_bvtt_with_stack(&self.qbvh, &mut visitor, &mut self.stack);
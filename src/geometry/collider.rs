@@ -1,12 +1,16 @@
 use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle};
+#[cfg(feature = "dim3")]
+use crate::geometry::HeightFieldCellStatus;
 use crate::geometry::{
-    ActiveCollisionTypes, BroadPhaseProxyIndex, ColliderBroadPhaseData, ColliderChanges,
-    ColliderFlags, ColliderMassProps, ColliderMaterial, ColliderParent, ColliderPosition,
-    ColliderShape, ColliderType, InteractionGroups, SharedShape,
+    ActiveCollisionTypes, AnisotropicFriction, BroadPhaseProxyIndex, BroadPhaseProxyShape,
+    ColliderBroadPhaseData, ColliderChanges, ColliderFlags, ColliderMassProps, ColliderMaterial,
+    ColliderParent, ColliderPosition, ColliderShape, ColliderType, DeformableTriMesh, ForceField,
+    HeightField, InteractionGroups, MaterialMap, PointProjection, Ray, RayIntersection, ShapeType,
+    SharedShape, SharedShapeExt, SubShapeMaterial, Voxels,
 };
-use crate::math::{AngVector, Isometry, Point, Real, Rotation, Vector, DIM};
+use crate::math::{AngVector, Isometry, Point, Real, Rotation, UnitVector, Vector, DIM};
 use crate::parry::transformation::vhacd::VHACDParameters;
-use crate::pipeline::{ActiveEvents, ActiveHooks};
+use crate::pipeline::{cast_ray_on_shape, ActiveEvents, ActiveHooks, InvalidDensity};
 use crate::prelude::ColliderEnabled;
 use na::Unit;
 use parry::bounding_volume::{Aabb, BoundingVolume};
@@ -31,7 +35,12 @@ pub struct Collider {
     pub(crate) flags: ColliderFlags,
     pub(crate) bf_data: ColliderBroadPhaseData,
     contact_skin: Real,
+    contact_prediction_distance: Option<Real>,
+    sensor_margin: Real,
     contact_force_event_threshold: Real,
+    force_field: Option<ForceField>,
+    material_map: Option<MaterialMap>,
+    broad_phase_proxy_shape: BroadPhaseProxyShape,
     /// User-defined data associated to this collider.
     pub user_data: u128,
 }
@@ -111,6 +120,11 @@ impl Collider {
             contact_force_event_threshold,
             user_data,
             contact_skin,
+            contact_prediction_distance,
+            sensor_margin,
+            force_field,
+            material_map,
+            broad_phase_proxy_shape,
         } = other;
 
         if self.parent.is_none() {
@@ -126,6 +140,11 @@ impl Collider {
         self.flags = *flags;
         self.changes = ColliderChanges::all();
         self.contact_skin = *contact_skin;
+        self.contact_prediction_distance = *contact_prediction_distance;
+        self.sensor_margin = *sensor_margin;
+        self.force_field = *force_field;
+        self.material_map = material_map.clone();
+        self.broad_phase_proxy_shape = *broad_phase_proxy_shape;
     }
 
     /// The physics hooks enabled for this collider.
@@ -172,6 +191,68 @@ impl Collider {
         self.contact_skin = skin_thickness;
     }
 
+    /// The contact prediction distance override of this collider.
+    ///
+    /// See the documentation of [`ColliderBuilder::contact_prediction_distance`] for details.
+    pub fn contact_prediction_distance(&self) -> Option<Real> {
+        self.contact_prediction_distance
+    }
+
+    /// Sets the contact prediction distance override of this collider.
+    ///
+    /// See the documentation of [`ColliderBuilder::contact_prediction_distance`] for details.
+    pub fn set_contact_prediction_distance(&mut self, distance: Option<Real>) {
+        self.contact_prediction_distance = distance;
+    }
+
+    /// The hysteresis margin of the intersections involving this collider.
+    ///
+    /// See the documentation of [`ColliderBuilder::sensor_margin`] for details.
+    pub fn sensor_margin(&self) -> Real {
+        self.sensor_margin
+    }
+
+    /// Sets the hysteresis margin of the intersections involving this collider.
+    ///
+    /// See the documentation of [`ColliderBuilder::sensor_margin`] for details.
+    pub fn set_sensor_margin(&mut self, margin: Real) {
+        self.sensor_margin = margin;
+    }
+
+    /// The force field applied by this collider to the dynamic rigid-bodies intersecting it.
+    ///
+    /// See the documentation of [`ColliderBuilder::force_field`] for details.
+    pub fn force_field(&self) -> Option<&ForceField> {
+        self.force_field.as_ref()
+    }
+
+    /// Sets the force field applied by this collider to the dynamic rigid-bodies intersecting it.
+    ///
+    /// See the documentation of [`ColliderBuilder::force_field`] for details.
+    pub fn set_force_field(&mut self, force_field: Option<ForceField>) {
+        self.force_field = force_field;
+    }
+
+    /// The prediction distance used for generating the predictive contacts between
+    /// `self` and `other`.
+    ///
+    /// This is the largest of the two colliders’ prediction distance overrides, or
+    /// `default_prediction_distance` if none of them has an override.
+    pub fn effective_contact_prediction_distance(
+        &self,
+        other: &Collider,
+        default_prediction_distance: Real,
+    ) -> Real {
+        match (
+            self.contact_prediction_distance,
+            other.contact_prediction_distance,
+        ) {
+            (Some(distance1), Some(distance2)) => distance1.max(distance2),
+            (Some(distance), None) | (None, Some(distance)) => distance,
+            (None, None) => default_prediction_distance,
+        }
+    }
+
     /// The friction coefficient of this collider.
     pub fn friction(&self) -> Real {
         self.material.friction
@@ -196,6 +277,110 @@ impl Collider {
         self.material.friction_combine_rule = rule;
     }
 
+    /// The anisotropic friction of this collider.
+    ///
+    /// See the documentation of [`ColliderBuilder::anisotropic_friction`] for details.
+    pub fn anisotropic_friction(&self) -> Option<AnisotropicFriction> {
+        self.material.anisotropic_friction
+    }
+
+    /// Sets the anisotropic friction of this collider.
+    ///
+    /// See the documentation of [`ColliderBuilder::anisotropic_friction`] for details.
+    pub fn set_anisotropic_friction(&mut self, anisotropic_friction: Option<AnisotropicFriction>) {
+        self.material.anisotropic_friction = anisotropic_friction;
+    }
+
+    /// The friction coefficient of this collider for a contact sliding along the given
+    /// world-space direction.
+    ///
+    /// This is [`Self::friction`] if this collider has no anisotropic friction, or if the sliding
+    /// direction is `None`.
+    pub fn friction_along(&self, sliding_direction: Option<UnitVector<Real>>) -> Real {
+        match (self.material.anisotropic_friction, sliding_direction) {
+            (Some(anisotropy), Some(sliding_direction)) => {
+                let direction = self.pos.rotation * anisotropy.local_direction;
+                let sq_cos = direction.dot(&sliding_direction).powi(2);
+                self.material.friction + (anisotropy.friction - self.material.friction) * sq_cos
+            }
+            _ => self.material.friction,
+        }
+    }
+
+    /// The friction and restitution coefficients varying across the parts of this collider’s
+    /// shape, if any.
+    pub fn material_map(&self) -> Option<&MaterialMap> {
+        self.material_map.as_ref()
+    }
+
+    /// Sets the friction and restitution coefficients varying across the parts of this
+    /// collider’s shape.
+    ///
+    /// See the documentation of [`MaterialMap`] for details.
+    pub fn set_material_map(&mut self, material_map: Option<MaterialMap>) {
+        self.material_map = material_map;
+    }
+
+    /// The bounding volume tracking this collider in the broad-phase.
+    ///
+    /// See the documentation of [`BroadPhaseProxyShape`] for details.
+    pub fn broad_phase_proxy_shape(&self) -> BroadPhaseProxyShape {
+        self.broad_phase_proxy_shape
+    }
+
+    /// Sets the bounding volume tracking this collider in the broad-phase.
+    ///
+    /// See the documentation of [`BroadPhaseProxyShape`] for details.
+    pub fn set_broad_phase_proxy_shape(&mut self, proxy_shape: BroadPhaseProxyShape) {
+        if self.broad_phase_proxy_shape != proxy_shape {
+            // Mark the position as modified so the broad-phase recomputes the proxy.
+            self.changes.insert(ColliderChanges::POSITION);
+            self.broad_phase_proxy_shape = proxy_shape;
+        }
+    }
+
+    /// The friction and restitution coefficients of the given sub-shape of this collider, as
+    /// identified by the `subshape1` or `subshape2` field of a
+    /// [`ContactManifold`](crate::geometry::ContactManifold).
+    ///
+    /// These are given by the [`MaterialMap`] of this collider if it has one covering this
+    /// sub-shape, and by [`Self::friction`] and [`Self::restitution`] otherwise.
+    pub fn subshape_friction_and_restitution(&self, subshape: u32) -> (Real, Real) {
+        self.subshape_material(subshape)
+            .map(|material| (material.friction, material.restitution))
+            .unwrap_or((self.material.friction, self.material.restitution))
+    }
+
+    fn subshape_material(&self, subshape: u32) -> Option<&SubShapeMaterial> {
+        let material_map = self.material_map.as_ref()?;
+        let part = match self.shape.shape_type() {
+            ShapeType::TriMesh | ShapeType::Polyline | ShapeType::Compound => subshape as usize,
+            #[cfg(feature = "dim2")]
+            ShapeType::HeightField => subshape as usize,
+            // The two triangles of each cell are numbered like the cells, with the second
+            // triangles after all the first ones.
+            #[cfg(feature = "dim3")]
+            ShapeType::HeightField => {
+                let heightfield = self.shape.as_heightfield()?;
+                subshape as usize % (heightfield.nrows() * heightfield.ncols()).max(1)
+            }
+            _ => return None,
+        };
+        material_map.material(part)
+    }
+
+    /// The rolling friction coefficient of this collider.
+    pub fn rolling_friction(&self) -> Real {
+        self.material.rolling_friction
+    }
+
+    /// Sets the rolling friction coefficient of this collider.
+    ///
+    /// See the documentation of [`ColliderBuilder::rolling_friction`] for details.
+    pub fn set_rolling_friction(&mut self, coefficient: Real) {
+        self.material.rolling_friction = coefficient;
+    }
+
     /// The restitution coefficient of this collider.
     pub fn restitution(&self) -> Real {
         self.material.restitution
@@ -220,6 +405,39 @@ impl Collider {
         self.material.restitution_combine_rule = rule;
     }
 
+    /// The restitution velocity threshold override of this collider.
+    ///
+    /// See the documentation of [`ColliderBuilder::restitution_velocity_threshold`] for details.
+    pub fn restitution_velocity_threshold(&self) -> Option<Real> {
+        self.material.restitution_velocity_threshold
+    }
+
+    /// Sets the restitution velocity threshold override of this collider.
+    ///
+    /// See the documentation of [`ColliderBuilder::restitution_velocity_threshold`] for details.
+    pub fn set_restitution_velocity_threshold(&mut self, threshold: Option<Real>) {
+        self.material.restitution_velocity_threshold = threshold;
+    }
+
+    /// The restitution velocity threshold used for the contacts between `self` and `other`.
+    ///
+    /// This is the largest of the two colliders’ restitution velocity threshold overrides, or
+    /// `default_threshold` if none of them has an override.
+    pub fn effective_restitution_velocity_threshold(
+        &self,
+        other: &Collider,
+        default_threshold: Real,
+    ) -> Real {
+        match (
+            self.material.restitution_velocity_threshold,
+            other.material.restitution_velocity_threshold,
+        ) {
+            (Some(threshold1), Some(threshold2)) => threshold1.max(threshold2),
+            (Some(threshold), None) | (None, Some(threshold)) => threshold,
+            (None, None) => default_threshold,
+        }
+    }
+
     /// Sets the total force magnitude beyond which a contact force event can be emitted.
     pub fn set_contact_force_event_threshold(&mut self, threshold: Real) {
         self.contact_force_event_threshold = threshold;
@@ -392,9 +610,22 @@ impl Collider {
     /// for this collider.
     ///
     /// The mass and angular inertia of this collider will be computed automatically based on its
-    /// shape.
-    pub fn set_density(&mut self, density: Real) {
+    /// shape. The mass-properties of its parent rigid-body are updated, and the rigid-body is
+    /// woken up, at the next timestep.
+    ///
+    /// A density of zero is allowed: the collider still generates contacts but doesn’t contribute
+    /// to the mass of its parent. If none of the colliders of a dynamic rigid-body contribute
+    /// to its mass, the rigid-body behaves as if its mass was infinite.
+    ///
+    /// Returns an error, and leaves the mass-properties unchanged, if `density` is negative or
+    /// not finite.
+    pub fn set_density(&mut self, density: Real) -> Result<(), InvalidDensity> {
+        if !density.is_finite() || density < 0.0 {
+            return Err(InvalidDensity(density));
+        }
+
         self.do_set_mass_properties(ColliderMassProps::Density(density));
+        Ok(())
     }
 
     /// Sets the mass of this collider.
@@ -442,6 +673,68 @@ impl Collider {
         self.shape.make_mut()
     }
 
+    /// Moves the vertices of this collider’s [`DeformableTriMesh`] shape, keeping its topology.
+    ///
+    /// Contrary to modifying the shape through [`Self::shape_mut`], this preserves the contacts
+    /// involving the triangles of this mesh (and their warmstarting data).
+    ///
+    /// # Panics
+    /// Panics if the shape of this collider isn’t a [`DeformableTriMesh`], or if `vertices` doesn’t
+    /// have the same length as its current vertex buffer.
+    pub fn set_trimesh_vertices(&mut self, vertices: &[Point<Real>]) {
+        self.changes.insert(ColliderChanges::SHAPE_DEFORMED);
+        self.shape
+            .make_mut()
+            .as_shape_mut::<DeformableTriMesh>()
+            .expect("The collider shape isn’t a DeformableTriMesh.")
+            .set_vertices(vertices)
+    }
+
+    /// Sets the status of the `i`-th cell of this collider’s [`HeightField`] shape.
+    ///
+    /// Setting `removed` to `true` makes a hole that neither generates contacts nor is hit by
+    /// ray-casts or point projections. The bodies touching this collider are woken up and,
+    /// contrary to modifying the shape through [`Self::shape_mut`], the contacts with the
+    /// remaining cells are preserved.
+    ///
+    /// # Panics
+    /// Panics if the shape of this collider isn’t a [`HeightField`], or if `i` is out of bounds.
+    #[cfg(feature = "dim2")]
+    pub fn set_heightfield_cell_status(&mut self, i: usize, removed: bool) {
+        self.changes.insert(ColliderChanges::SHAPE_DEFORMED);
+        self.shape
+            .make_mut()
+            .as_shape_mut::<HeightField>()
+            .expect("The collider shape isn’t a HeightField.")
+            .set_segment_removed(i, removed)
+    }
+
+    /// Sets the status of the cell at the `i`-th row and `j`-th column of this collider’s
+    /// [`HeightField`] shape.
+    ///
+    /// Setting it to [`HeightFieldCellStatus::CELL_REMOVED`] (or removing only one of its
+    /// triangles) makes a hole that neither generates contacts nor is hit by ray-casts or point
+    /// projections. The bodies touching this collider are woken up and, contrary to modifying
+    /// the shape through [`Self::shape_mut`], the contacts with the remaining cells are preserved.
+    ///
+    /// # Panics
+    /// Panics if the shape of this collider isn’t a [`HeightField`], or if `(i, j)` is out of
+    /// bounds.
+    #[cfg(feature = "dim3")]
+    pub fn set_heightfield_cell_status(
+        &mut self,
+        i: usize,
+        j: usize,
+        status: HeightFieldCellStatus,
+    ) {
+        self.changes.insert(ColliderChanges::SHAPE_DEFORMED);
+        self.shape
+            .make_mut()
+            .as_shape_mut::<HeightField>()
+            .expect("The collider shape isn’t a HeightField.")
+            .set_cell_status(i, j, status)
+    }
+
     /// Sets the shape of this collider.
     pub fn set_shape(&mut self, shape: SharedShape) {
         self.changes.insert(ColliderChanges::SHAPE);
@@ -469,12 +762,114 @@ impl Collider {
             .loosened(self.contact_skin + prediction)
     }
 
+    /// Compute the bounding box of the broad-phase proxy of this collider, taking into account
+    /// the [`Collider::contact_skin`] and prediction distance.
+    ///
+    /// This is the same as [`Collider::compute_collision_aabb`], unless this collider’s
+    /// [`Collider::broad_phase_proxy_shape`] selects its bounding sphere in which case this is
+    /// the (larger) AABB of that sphere.
+    pub fn compute_broad_phase_aabb(&self, prediction: Real) -> Aabb {
+        self.compute_broad_phase_aabb_at(&self.pos, prediction)
+    }
+
+    /// Compute the bounding box of the broad-phase proxy of this collider as if it was at the
+    /// given `position`.
+    ///
+    /// See [`Collider::compute_broad_phase_aabb`].
+    pub fn compute_broad_phase_aabb_at(&self, position: &Isometry<Real>, prediction: Real) -> Aabb {
+        let aabb = if self
+            .broad_phase_proxy_shape
+            .uses_bounding_sphere(&*self.shape)
+        {
+            let bsphere = self.shape.compute_bounding_sphere(position);
+            Aabb::from_half_extents(bsphere.center, Vector::repeat(bsphere.radius))
+        } else {
+            self.shape.compute_aabb(position)
+        };
+
+        aabb.loosened(self.contact_skin + prediction)
+    }
+
+    /// The margin the broad-phase should apply to this collider’s AABB so that all the pairs
+    /// that may generate predictive contacts are detected.
+    ///
+    /// This is half the `default_prediction_distance`, unless this collider has a
+    /// [`Collider::contact_prediction_distance`] override in which case the override is used
+    /// as a whole.
+    pub fn broad_phase_prediction_margin(&self, default_prediction_distance: Real) -> Real {
+        self.contact_prediction_distance
+            .unwrap_or(default_prediction_distance / 2.0)
+    }
+
     /// Compute the axis-aligned bounding box of this collider moving from its current position
     /// to the given `next_position`
     pub fn compute_swept_aabb(&self, next_position: &Isometry<Real>) -> Aabb {
         self.shape.compute_swept_aabb(&self.pos, next_position)
     }
 
+    /// Compute the axis-aligned bounding box of this collider moving from its current position
+    /// to the given `next_position` while rotating by `angle` radians around `center_of_rotation`.
+    ///
+    /// Unlike [`Collider::compute_swept_aabb`], this also encloses the arcs described by the
+    /// collider’s points during the rotation, even if `angle` is larger than a full turn. This
+    /// assumes that the motion has a constant linear and angular velocity.
+    pub fn compute_rotating_swept_aabb(
+        &self,
+        next_position: &Isometry<Real>,
+        center_of_rotation: &Point<Real>,
+        angle: Real,
+    ) -> Aabb {
+        let aabb = self.compute_swept_aabb(next_position);
+
+        if angle == 0.0 {
+            return aabb;
+        }
+
+        // Each point is at most at `max_dist` from the rotation axis so its second derivative
+        // w.r.t. the normalized time is bounded by `max_dist * angle²`. Hence, it never deviates
+        // from the segment between its initial and final positions by more than
+        // `max_dist * angle² / 8`, nor by more than the diameter of its circular trajectory.
+        let bsphere = self.shape.compute_bounding_sphere(&self.pos);
+        let max_dist = na::distance(&bsphere.center, center_of_rotation) + bsphere.radius;
+        aabb.loosened(max_dist * (angle * angle / 8.0).min(2.0))
+    }
+
+    /// Computes the time of impact of a ray with this collider, at its current position.
+    ///
+    /// Unlike [`QueryPipeline::cast_ray`](crate::pipeline::QueryPipeline::cast_ray), this only
+    /// tests this collider and doesn’t require any query pipeline. See its documentation for
+    /// the meaning of `max_toi` and `solid`.
+    pub fn cast_ray(&self, ray: &Ray, max_toi: Real, solid: bool) -> Option<Real> {
+        self.cast_ray_and_get_normal(ray, max_toi, solid)
+            .map(|hit| hit.time_of_impact)
+    }
+
+    /// Computes the time of impact and normal of a ray with this collider, at its current
+    /// position.
+    ///
+    /// See [`Collider::cast_ray`] for details.
+    pub fn cast_ray_and_get_normal(
+        &self,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        cast_ray_on_shape(&*self.shape, &self.pos, ray, max_toi, solid, false)
+    }
+
+    /// Tests if the given world-space point is inside of this collider.
+    pub fn contains_point(&self, point: &Point<Real>) -> bool {
+        self.shape.contains_point(&self.pos, point)
+    }
+
+    /// Projects the given world-space point on this collider.
+    ///
+    /// If `solid` is `true` and the point is inside of this collider, the projection is the
+    /// point itself. Otherwise, the point is projected on the boundary of the collider.
+    pub fn project_point(&self, point: &Point<Real>, solid: bool) -> PointProjection {
+        self.shape.project_point(&self.pos, point, solid)
+    }
+
     /// Compute the local-space mass properties of this collider.
     pub fn mass_properties(&self) -> MassProperties {
         self.mprops.mass_properties(&*self.shape)
@@ -503,6 +898,12 @@ pub struct ColliderBuilder {
     pub restitution: Real,
     /// The rule used to combine two restitution coefficients.
     pub restitution_combine_rule: CoefficientCombineRule,
+    /// The restitution velocity threshold override of the collider to be built.
+    pub restitution_velocity_threshold: Option<Real>,
+    /// The anisotropic friction of the collider to be built.
+    pub anisotropic_friction: Option<AnisotropicFriction>,
+    /// The rolling friction coefficient of the collider to be built.
+    pub rolling_friction: Real,
     /// The position of this collider.
     pub position: Isometry<Real>,
     /// Is this collider a sensor?
@@ -525,6 +926,17 @@ pub struct ColliderBuilder {
     pub contact_force_event_threshold: Real,
     /// An extra thickness around the collider shape to keep them further apart when colliding.
     pub contact_skin: Real,
+    /// Overrides the global prediction distance for contacts involving this collider.
+    pub contact_prediction_distance: Option<Real>,
+    /// The hysteresis margin of the intersections involving this collider.
+    pub sensor_margin: Real,
+    /// The force field applied by the collider to be built.
+    pub force_field: Option<ForceField>,
+    /// The friction and restitution coefficients varying across the parts of the shape of the
+    /// collider to be built.
+    pub material_map: Option<MaterialMap>,
+    /// The bounding volume tracking the collider to be built in the broad-phase.
+    pub broad_phase_proxy_shape: BroadPhaseProxyShape,
 }
 
 impl ColliderBuilder {
@@ -542,12 +954,20 @@ impl ColliderBuilder {
             solver_groups: InteractionGroups::all(),
             friction_combine_rule: CoefficientCombineRule::Average,
             restitution_combine_rule: CoefficientCombineRule::Average,
+            restitution_velocity_threshold: None,
+            anisotropic_friction: None,
+            rolling_friction: 0.0,
             active_collision_types: ActiveCollisionTypes::default(),
             active_hooks: ActiveHooks::empty(),
             active_events: ActiveEvents::empty(),
             enabled: true,
             contact_force_event_threshold: 0.0,
             contact_skin: 0.0,
+            contact_prediction_distance: None,
+            sensor_margin: 0.0,
+            force_field: None,
+            material_map: None,
+            broad_phase_proxy_shape: BroadPhaseProxyShape::default(),
         }
     }
 
@@ -563,6 +983,9 @@ impl ColliderBuilder {
 
     /// Initialize a new collider build with a half-space shape defined by the outward normal
     /// of its planar boundary.
+    ///
+    /// The boundary passes through the collider’s origin. A half-space has no mass so it can’t
+    /// be attached to a dynamic rigid-body, see [`ValidationError::HalfSpaceOnDynamicBody`](crate::pipeline::ValidationError::HalfSpaceOnDynamicBody).
     pub fn halfspace(outward_normal: Unit<Vector<Real>>) -> Self {
         Self::new(SharedShape::halfspace(outward_normal))
     }
@@ -609,6 +1032,18 @@ impl ColliderBuilder {
 
     /// Initialize a new collider builder with a round cuboid shape defined by its half-extents
     /// and border radius.
+    ///
+    /// The border radius is added to the half-extents, e.g., for a smooth platform edge:
+    #[cfg_attr(
+        feature = "f32",
+        doc = r#"
+```
+# use rapier2d::prelude::*;
+let platform = ColliderBuilder::round_cuboid(1.9, 0.1, 0.1).build();
+assert_eq!(platform.compute_aabb().half_extents(), vector![2.0, 0.2]);
+```
+"#
+    )]
     #[cfg(feature = "dim2")]
     pub fn round_cuboid(hx: Real, hy: Real, border_radius: Real) -> Self {
         Self::new(SharedShape::round_cuboid(hx, hy, border_radius))
@@ -692,6 +1127,18 @@ impl ColliderBuilder {
         Self::new(SharedShape::trimesh_with_flags(vertices, indices, flags))
     }
 
+    /// Initializes a collider builder with a voxel shape made of square (2D) or cubic (3D) cells of
+    /// width `cell_size`, identified by their integer keys.
+    pub fn voxels(cell_size: Real, cells: &[Point<i32>]) -> Self {
+        Self::new(SharedShape::new(Voxels::new(cell_size, cells)))
+    }
+
+    /// Initializes a collider builder with a triangle mesh shape which vertices can be moved
+    /// afterward with [`Collider::set_trimesh_vertices`].
+    pub fn deformable_trimesh(vertices: Vec<Point<Real>>, indices: Vec<[u32; 3]>) -> Self {
+        Self::new(SharedShape::new(DeformableTriMesh::new(vertices, indices)))
+    }
+
     /// Initializes a collider builder with a compound shape obtained from the decomposition of
     /// the given trimesh (in 3D) or polyline (in 2D) into convex parts.
     pub fn convex_decomposition(vertices: &[Point<Real>], indices: &[[u32; DIM]]) -> Self {
@@ -826,6 +1273,12 @@ impl ColliderBuilder {
 
     /// Sets an arbitrary user-defined 128-bit integer associated to the colliders built by this builder.
     pub fn user_data(mut self, data: u128) -> Self {
+        self.set_user_data(data);
+        self
+    }
+
+    /// Borrowing variant of [`Self::user_data`].
+    pub fn set_user_data(&mut self, data: u128) -> &mut Self {
         self.user_data = data;
         self
     }
@@ -835,6 +1288,12 @@ impl ColliderBuilder {
     /// Two colliders will interact iff. their collision groups are compatible.
     /// See [InteractionGroups::test] for details.
     pub fn collision_groups(mut self, groups: InteractionGroups) -> Self {
+        self.set_collision_groups(groups);
+        self
+    }
+
+    /// Borrowing variant of [`Self::collision_groups`].
+    pub fn set_collision_groups(&mut self, groups: InteractionGroups) -> &mut Self {
         self.collision_groups = groups;
         self
     }
@@ -844,58 +1303,183 @@ impl ColliderBuilder {
     /// Forces between two colliders in contact will be computed iff their solver groups are
     /// compatible. See [InteractionGroups::test] for details.
     pub fn solver_groups(mut self, groups: InteractionGroups) -> Self {
+        self.set_solver_groups(groups);
+        self
+    }
+
+    /// Borrowing variant of [`Self::solver_groups`].
+    pub fn set_solver_groups(&mut self, groups: InteractionGroups) -> &mut Self {
         self.solver_groups = groups;
         self
     }
 
     /// Sets whether or not the collider built by this builder is a sensor.
+    ///
+    /// Sensors attached to fixed or sleeping rigid-bodies still detect the colliders moving
+    /// into them, without being woken up. An intersection is only re-evaluated when at least one
+    /// of its colliders moved or changed though: if both colliders are fixed or asleep, its
+    /// state is frozen and no collision event is generated until one of them moves again.
     pub fn sensor(mut self, is_sensor: bool) -> Self {
+        self.set_sensor(is_sensor);
+        self
+    }
+
+    /// Borrowing variant of [`Self::sensor`].
+    pub fn set_sensor(&mut self, is_sensor: bool) -> &mut Self {
         self.is_sensor = is_sensor;
         self
     }
 
     /// The set of physics hooks enabled for this collider.
     pub fn active_hooks(mut self, active_hooks: ActiveHooks) -> Self {
+        self.set_active_hooks(active_hooks);
+        self
+    }
+
+    /// Borrowing variant of [`Self::active_hooks`].
+    pub fn set_active_hooks(&mut self, active_hooks: ActiveHooks) -> &mut Self {
         self.active_hooks = active_hooks;
         self
     }
 
     /// The set of events enabled for this collider.
     pub fn active_events(mut self, active_events: ActiveEvents) -> Self {
+        self.set_active_events(active_events);
+        self
+    }
+
+    /// Borrowing variant of [`Self::active_events`].
+    pub fn set_active_events(&mut self, active_events: ActiveEvents) -> &mut Self {
         self.active_events = active_events;
         self
     }
 
     /// The set of active collision types for this collider.
     pub fn active_collision_types(mut self, active_collision_types: ActiveCollisionTypes) -> Self {
+        self.set_active_collision_types(active_collision_types);
+        self
+    }
+
+    /// Borrowing variant of [`Self::active_collision_types`].
+    pub fn set_active_collision_types(
+        &mut self,
+        active_collision_types: ActiveCollisionTypes,
+    ) -> &mut Self {
         self.active_collision_types = active_collision_types;
         self
     }
 
     /// Sets the friction coefficient of the collider this builder will build.
     pub fn friction(mut self, friction: Real) -> Self {
+        self.set_friction(friction);
+        self
+    }
+
+    /// Borrowing variant of [`Self::friction`].
+    pub fn set_friction(&mut self, friction: Real) -> &mut Self {
         self.friction = friction;
         self
     }
 
     /// Sets the rule to be used to combine two friction coefficients in a contact.
     pub fn friction_combine_rule(mut self, rule: CoefficientCombineRule) -> Self {
+        self.set_friction_combine_rule(rule);
+        self
+    }
+
+    /// Borrowing variant of [`Self::friction_combine_rule`].
+    pub fn set_friction_combine_rule(&mut self, rule: CoefficientCombineRule) -> &mut Self {
         self.friction_combine_rule = rule;
         self
     }
 
+    /// Sets a secondary friction coefficient applied to contacts sliding along the given
+    /// direction of the collider this builder will build.
+    ///
+    /// The `local_direction` is expressed in the collider’s local-space. Contacts sliding
+    /// orthogonally to it use the regular friction coefficient (see [`Self::friction`]).
+    pub fn anisotropic_friction(
+        mut self,
+        local_direction: UnitVector<Real>,
+        friction: Real,
+    ) -> Self {
+        self.set_anisotropic_friction(local_direction, friction);
+        self
+    }
+
+    /// Borrowing variant of [`Self::anisotropic_friction`].
+    pub fn set_anisotropic_friction(
+        &mut self,
+        local_direction: UnitVector<Real>,
+        friction: Real,
+    ) -> &mut Self {
+        self.anisotropic_friction = Some(AnisotropicFriction {
+            local_direction,
+            friction,
+        });
+        self
+    }
+
+    /// Sets the rolling friction coefficient of the collider this builder will build.
+    ///
+    /// At each contact, the relative angular velocity of the two rigid-bodies is opposed by an
+    /// angular impulse with a magnitude up to this coefficient multiplied by the normal impulse.
+    /// The rolling friction coefficients of two colliders in contact are combined with their
+    /// friction combine rules.
+    pub fn rolling_friction(mut self, coefficient: Real) -> Self {
+        self.set_rolling_friction(coefficient);
+        self
+    }
+
+    /// Borrowing variant of [`Self::rolling_friction`].
+    pub fn set_rolling_friction(&mut self, coefficient: Real) -> &mut Self {
+        self.rolling_friction = coefficient;
+        self
+    }
+
     /// Sets the restitution coefficient of the collider this builder will build.
     pub fn restitution(mut self, restitution: Real) -> Self {
+        self.set_restitution(restitution);
+        self
+    }
+
+    /// Borrowing variant of [`Self::restitution`].
+    pub fn set_restitution(&mut self, restitution: Real) -> &mut Self {
         self.restitution = restitution;
         self
     }
 
     /// Sets the rule to be used to combine two restitution coefficients in a contact.
     pub fn restitution_combine_rule(mut self, rule: CoefficientCombineRule) -> Self {
+        self.set_restitution_combine_rule(rule);
+        self
+    }
+
+    /// Borrowing variant of [`Self::restitution_combine_rule`].
+    pub fn set_restitution_combine_rule(&mut self, rule: CoefficientCombineRule) -> &mut Self {
         self.restitution_combine_rule = rule;
         self
     }
 
+    /// Sets the relative normal velocity below which contacts with the collider this builder
+    /// will build don’t bounce.
+    ///
+    /// This overrides [`IntegrationParameters::restitution_velocity_threshold`](crate::dynamics::IntegrationParameters::restitution_velocity_threshold)
+    /// for contacts involving this collider. Lower it for small bouncy objects that would
+    /// otherwise stop bouncing too early, and raise it for large objects that should come to
+    /// rest instead of jittering. If both colliders in contact have an override, the largest one
+    /// is used.
+    pub fn restitution_velocity_threshold(mut self, threshold: Real) -> Self {
+        self.set_restitution_velocity_threshold(threshold);
+        self
+    }
+
+    /// Borrowing variant of [`Self::restitution_velocity_threshold`].
+    pub fn set_restitution_velocity_threshold(&mut self, threshold: Real) -> &mut Self {
+        self.restitution_velocity_threshold = Some(threshold);
+        self
+    }
+
     /// Sets the uniform density of the collider this builder will build.
     ///
     /// This will be overridden by a call to [`Self::mass`] or [`Self::mass_properties`] so it only
@@ -904,6 +1488,12 @@ impl ColliderBuilder {
     /// The mass and angular inertia of this collider will be computed automatically based on its
     /// shape.
     pub fn density(mut self, density: Real) -> Self {
+        self.set_density(density);
+        self
+    }
+
+    /// Borrowing variant of [`Self::density`].
+    pub fn set_density(&mut self, density: Real) -> &mut Self {
         self.mass_properties = ColliderMassProps::Density(density);
         self
     }
@@ -916,6 +1506,12 @@ impl ColliderBuilder {
     /// The angular inertia of this collider will be computed automatically based on its shape
     /// and this mass value.
     pub fn mass(mut self, mass: Real) -> Self {
+        self.set_mass(mass);
+        self
+    }
+
+    /// Borrowing variant of [`Self::mass`].
+    pub fn set_mass(&mut self, mass: Real) -> &mut Self {
         self.mass_properties = ColliderMassProps::Mass(mass);
         self
     }
@@ -925,12 +1521,37 @@ impl ColliderBuilder {
     /// This will be overridden by a call to [`Self::density`] or [`Self::mass`] so it only
     /// makes sense to call either [`Self::density`] or [`Self::mass`] or [`Self::mass_properties`].
     pub fn mass_properties(mut self, mass_properties: MassProperties) -> Self {
+        self.set_mass_properties(mass_properties);
+        self
+    }
+
+    /// Borrowing variant of [`Self::mass_properties`].
+    pub fn set_mass_properties(&mut self, mass_properties: MassProperties) -> &mut Self {
         self.mass_properties = ColliderMassProps::MassProperties(Box::new(mass_properties));
         self
     }
 
+    /// Scales the shape of the collider this builder will build along each of its local axes.
+    ///
+    /// See [`SharedShapeExt::scaled`] for the shapes that can be scaled exactly, and the ones
+    /// approximated with `num_subdivisions` subdivisions. Returns `None` if the shape can’t be
+    /// scaled.
+    ///
+    /// Mass properties computed from a density or a mass will reflect the scaled shape. Mass
+    /// properties set explicitly with [`Self::mass_properties`] are left unchanged.
+    pub fn scale(mut self, scale: Vector<Real>, num_subdivisions: u32) -> Option<Self> {
+        self.shape = self.shape.scaled(&scale, num_subdivisions)?;
+        Some(self)
+    }
+
     /// Sets the total force magnitude beyond which a contact force event can be emitted.
     pub fn contact_force_event_threshold(mut self, threshold: Real) -> Self {
+        self.set_contact_force_event_threshold(threshold);
+        self
+    }
+
+    /// Borrowing variant of [`Self::contact_force_event_threshold`].
+    pub fn set_contact_force_event_threshold(&mut self, threshold: Real) -> &mut Self {
         self.contact_force_event_threshold = threshold;
         self
     }
@@ -940,6 +1561,12 @@ impl ColliderBuilder {
     /// If the collider will be attached to a rigid-body, this sets the translation relative to the
     /// rigid-body it will be attached to.
     pub fn translation(mut self, translation: Vector<Real>) -> Self {
+        self.set_translation(translation);
+        self
+    }
+
+    /// Borrowing variant of [`Self::translation`].
+    pub fn set_translation(&mut self, translation: Vector<Real>) -> &mut Self {
         self.position.translation.vector = translation;
         self
     }
@@ -949,6 +1576,12 @@ impl ColliderBuilder {
     /// If the collider will be attached to a rigid-body, this sets the orientation relative to the
     /// rigid-body it will be attached to.
     pub fn rotation(mut self, angle: AngVector<Real>) -> Self {
+        self.set_rotation(angle);
+        self
+    }
+
+    /// Borrowing variant of [`Self::rotation`].
+    pub fn set_rotation(&mut self, angle: AngVector<Real>) -> &mut Self {
         self.position.rotation = Rotation::new(angle);
         self
     }
@@ -958,6 +1591,12 @@ impl ColliderBuilder {
     /// If the collider will be attached to a rigid-body, this sets the position relative
     /// to the rigid-body it will be attached to.
     pub fn position(mut self, pos: Isometry<Real>) -> Self {
+        self.set_position(pos);
+        self
+    }
+
+    /// Borrowing variant of [`Self::position`].
+    pub fn set_position(&mut self, pos: Isometry<Real>) -> &mut Self {
         self.position = pos;
         self
     }
@@ -987,12 +1626,108 @@ impl ColliderBuilder {
     /// skin is sufficiently small, this might not be visually significant or can be hidden by the
     /// rendering assets.
     pub fn contact_skin(mut self, skin_thickness: Real) -> Self {
+        self.set_contact_skin(skin_thickness);
+        self
+    }
+
+    /// Borrowing variant of [`Self::contact_skin`].
+    pub fn set_contact_skin(&mut self, skin_thickness: Real) -> &mut Self {
         self.contact_skin = skin_thickness;
         self
     }
 
+    /// Sets the distance below which predictive contacts are generated for pairs involving
+    /// this collider, overriding [`IntegrationParameters::prediction_distance`](crate::dynamics::IntegrationParameters::prediction_distance).
+    ///
+    /// Larger values help fast-moving objects detect contacts early enough to avoid penetrations,
+    /// while smaller values avoid visible gaps between small objects. If both colliders of a pair
+    /// set this value, the largest one is used.
+    pub fn contact_prediction_distance(mut self, distance: Real) -> Self {
+        self.set_contact_prediction_distance(distance);
+        self
+    }
+
+    /// Borrowing variant of [`Self::contact_prediction_distance`].
+    pub fn set_contact_prediction_distance(&mut self, distance: Real) -> &mut Self {
+        self.contact_prediction_distance = Some(distance);
+        self
+    }
+
+    /// Sets the hysteresis margin of the intersections involving this collider.
+    ///
+    /// With a non-zero margin, an intersection between a sensor and another collider only
+    /// starts once the shapes penetrate by more than `margin`, and only stops once they are
+    /// separated by more than `margin`. In-between, the intersection keeps its previous state,
+    /// which avoids a flood of collision events when an object rests on the boundary of a
+    /// sensor. If both colliders of a pair set this value, the largest one is used.
+    pub fn sensor_margin(mut self, margin: Real) -> Self {
+        self.set_sensor_margin(margin);
+        self
+    }
+
+    /// Borrowing variant of [`Self::sensor_margin`].
+    pub fn set_sensor_margin(&mut self, margin: Real) -> &mut Self {
+        self.sensor_margin = margin;
+        self
+    }
+
+    /// Sets the force field applied by the collider to the dynamic rigid-bodies intersecting it.
+    ///
+    /// The field only has an effect if the collider is a sensor. It is applied to every dynamic
+    /// rigid-body (other than the collider’s parent) with at least one non-sensor collider
+    /// intersecting it, and keeps these rigid-bodies awake. The fields of overlapping sensors
+    /// add up.
+    pub fn force_field(mut self, force_field: ForceField) -> Self {
+        self.set_force_field(force_field);
+        self
+    }
+
+    /// Borrowing variant of [`Self::force_field`].
+    pub fn set_force_field(&mut self, force_field: ForceField) -> &mut Self {
+        self.force_field = Some(force_field);
+        self
+    }
+
+    /// Sets the friction and restitution coefficients varying across the parts of the shape of
+    /// the collider, e.g., the cells of a terrain heightfield made of several materials.
+    ///
+    /// See the documentation of [`MaterialMap`] for details.
+    pub fn material_map(mut self, material_map: MaterialMap) -> Self {
+        self.set_material_map(material_map);
+        self
+    }
+
+    /// Borrowing variant of [`Self::material_map`].
+    pub fn set_material_map(&mut self, material_map: MaterialMap) -> &mut Self {
+        self.material_map = Some(material_map);
+        self
+    }
+
+    /// Sets the bounding volume tracking the collider to be built in the broad-phase.
+    ///
+    /// Using [`BroadPhaseProxyShape::BoundingSphere`] for long thin colliders rotating in place
+    /// (masts, rails, rotating blades) avoids updating their proxy at each step, at the cost of
+    /// a larger proxy reporting more candidate pairs. The contacts computed by the
+    /// narrow-phase are the same with both kinds of proxies.
+    pub fn broad_phase_proxy_shape(mut self, proxy_shape: BroadPhaseProxyShape) -> Self {
+        self.set_broad_phase_proxy_shape(proxy_shape);
+        self
+    }
+
+    /// Borrowing variant of [`Self::broad_phase_proxy_shape`].
+    pub fn set_broad_phase_proxy_shape(&mut self, proxy_shape: BroadPhaseProxyShape) -> &mut Self {
+        self.broad_phase_proxy_shape = proxy_shape;
+        self
+    }
+
     /// Enable or disable the collider after its creation.
     pub fn enabled(mut self, enabled: bool) -> Self {
+        self.set_enabled(enabled);
+        self
+    }
+
+    /// Borrowing variant of [`Self::enabled`].
+    pub fn set_enabled(&mut self, enabled: bool) -> &mut Self {
         self.enabled = enabled;
         self
     }
@@ -1005,6 +1740,9 @@ impl ColliderBuilder {
             restitution: self.restitution,
             friction_combine_rule: self.friction_combine_rule,
             restitution_combine_rule: self.restitution_combine_rule,
+            restitution_velocity_threshold: self.restitution_velocity_threshold,
+            anisotropic_friction: self.anisotropic_friction,
+            rolling_friction: self.rolling_friction,
         };
         let flags = ColliderFlags {
             collision_groups: self.collision_groups,
@@ -1039,6 +1777,11 @@ impl ColliderBuilder {
             coll_type,
             contact_force_event_threshold: self.contact_force_event_threshold,
             contact_skin: self.contact_skin,
+            contact_prediction_distance: self.contact_prediction_distance,
+            sensor_margin: self.sensor_margin,
+            force_field: self.force_field,
+            material_map: self.material_map.clone(),
+            broad_phase_proxy_shape: self.broad_phase_proxy_shape,
             user_data: self.user_data,
         }
     }
@@ -1049,3 +1792,671 @@ impl From<ColliderBuilder> for Collider {
         val.build()
     }
 }
+
+impl From<&Collider> for ColliderBuilder {
+    /// Initializes a builder with the configuration of an existing collider.
+    ///
+    /// This can be used to spawn variants of an existing collider. The position of the builder
+    /// is the collider’s position relative to its parent if it has one, or its world-space
+    /// position otherwise. The shape is shared with the existing collider.
+    fn from(co: &Collider) -> ColliderBuilder {
+        Self {
+            shape: co.shape.clone(),
+            mass_properties: co.mprops.clone(),
+            friction: co.material.friction,
+            friction_combine_rule: co.material.friction_combine_rule,
+            restitution: co.material.restitution,
+            restitution_combine_rule: co.material.restitution_combine_rule,
+            restitution_velocity_threshold: co.material.restitution_velocity_threshold,
+            anisotropic_friction: co.material.anisotropic_friction,
+            rolling_friction: co.material.rolling_friction,
+            position: co.position_wrt_parent().copied().unwrap_or(co.pos.0),
+            is_sensor: co.is_sensor(),
+            active_collision_types: co.flags.active_collision_types,
+            active_hooks: co.flags.active_hooks,
+            active_events: co.flags.active_events,
+            user_data: co.user_data,
+            collision_groups: co.flags.collision_groups,
+            solver_groups: co.flags.solver_groups,
+            enabled: co.flags.enabled != ColliderEnabled::Disabled,
+            contact_force_event_threshold: co.contact_force_event_threshold,
+            contact_skin: co.contact_skin,
+            contact_prediction_distance: co.contact_prediction_distance,
+            sensor_margin: co.sensor_margin,
+            force_field: co.force_field,
+            material_map: co.material_map.clone(),
+            broad_phase_proxy_shape: co.broad_phase_proxy_shape,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Collider, ColliderBuilder};
+    use crate::dynamics::RigidBodySet;
+    use crate::geometry::{ColliderSet, Ray};
+    use crate::math::{Isometry, Point, Real, Vector};
+    use crate::pipeline::{QueryFilter, QueryPipeline};
+
+    /// The built-in shapes, and whether they have an interior.
+    fn built_in_shapes() -> Vec<(&'static str, ColliderBuilder, bool)> {
+        let square = [
+            Point::origin() - Vector::x(),
+            Point::origin() + Vector::x(),
+            Point::origin() + Vector::x() - Vector::y(),
+            Point::origin() - Vector::x() - Vector::y(),
+        ];
+        let voxels = [Point::origin(), Point::origin() - Vector::<i32>::x()];
+
+        #[cfg(feature = "dim2")]
+        let shapes = vec![
+            ("ball", ColliderBuilder::ball(0.5), true),
+            ("cuboid", ColliderBuilder::cuboid(1.0, 0.5), true),
+            (
+                "round_cuboid",
+                ColliderBuilder::round_cuboid(1.0, 0.5, 0.1),
+                true,
+            ),
+            ("capsule", ColliderBuilder::capsule_y(0.5, 0.3), true),
+            (
+                "segment",
+                ColliderBuilder::segment(square[0], square[1]),
+                false,
+            ),
+            (
+                "triangle",
+                ColliderBuilder::triangle(square[0], square[1], square[2]),
+                true,
+            ),
+            (
+                "convex_hull",
+                ColliderBuilder::convex_hull(&square).unwrap(),
+                true,
+            ),
+            (
+                "polyline",
+                ColliderBuilder::polyline(square.to_vec(), None),
+                false,
+            ),
+            (
+                "heightfield",
+                ColliderBuilder::heightfield(
+                    na::DVector::from_vec(vec![0.0, 0.5, 0.2, 0.3]),
+                    Vector::new(4.0, 1.0),
+                ),
+                false,
+            ),
+            ("voxels", ColliderBuilder::voxels(0.5, &voxels), true),
+            (
+                "compound",
+                ColliderBuilder::compound(vec![
+                    (
+                        Isometry::translation(0.5, 0.0),
+                        crate::geometry::SharedShape::ball(0.5),
+                    ),
+                    (
+                        Isometry::translation(-0.5, 0.0),
+                        crate::geometry::SharedShape::cuboid(0.5, 0.5),
+                    ),
+                ]),
+                true,
+            ),
+        ];
+
+        #[cfg(feature = "dim3")]
+        let shapes = vec![
+            ("ball", ColliderBuilder::ball(0.5), true),
+            ("cuboid", ColliderBuilder::cuboid(1.0, 0.5, 0.7), true),
+            (
+                "round_cuboid",
+                ColliderBuilder::round_cuboid(1.0, 0.5, 0.7, 0.1),
+                true,
+            ),
+            ("capsule", ColliderBuilder::capsule_y(0.5, 0.3), true),
+            ("cylinder", ColliderBuilder::cylinder(0.5, 0.3), true),
+            (
+                "round_cylinder",
+                ColliderBuilder::round_cylinder(0.5, 0.3, 0.1),
+                true,
+            ),
+            ("cone", ColliderBuilder::cone(0.5, 0.3), true),
+            (
+                "triangle",
+                ColliderBuilder::triangle(square[0], square[1], square[3] + Vector::z()),
+                false,
+            ),
+            (
+                "convex_hull",
+                ColliderBuilder::convex_hull(&[
+                    square[0],
+                    square[1],
+                    square[2],
+                    square[3],
+                    square[0] + Vector::z(),
+                    square[3] + Vector::z(),
+                ])
+                .unwrap(),
+                true,
+            ),
+            (
+                "trimesh",
+                ColliderBuilder::trimesh(
+                    square
+                        .iter()
+                        .map(|pt| Point::new(pt.x, 0.0, pt.y + 0.3))
+                        .collect(),
+                    vec![[0, 1, 2], [0, 2, 3]],
+                ),
+                false,
+            ),
+            (
+                "heightfield",
+                ColliderBuilder::heightfield(
+                    na::DMatrix::from_row_slice(
+                        3,
+                        3,
+                        &[0.0, 0.5, 0.2, 0.3, 0.1, 0.4, 0.2, 0.0, 0.1],
+                    ),
+                    Vector::new(4.0, 1.0, 4.0),
+                ),
+                false,
+            ),
+            ("voxels", ColliderBuilder::voxels(0.5, &voxels), true),
+            (
+                "compound",
+                ColliderBuilder::compound(vec![
+                    (
+                        Isometry::translation(0.5, 0.0, 0.0),
+                        crate::geometry::SharedShape::ball(0.5),
+                    ),
+                    (
+                        Isometry::translation(-0.5, 0.0, 0.0),
+                        crate::geometry::SharedShape::cuboid(0.5, 0.5, 0.5),
+                    ),
+                ]),
+                true,
+            ),
+        ];
+
+        shapes
+    }
+
+    #[test]
+    fn collider_queries_match_the_query_pipeline() {
+        #[cfg(feature = "dim2")]
+        let position = Isometry::new(Vector::new(10.0, 5.0), 0.1);
+        #[cfg(feature = "dim3")]
+        let position = Isometry::new(Vector::new(10.0, 5.0, -3.0), Vector::new(0.1, 0.2, 0.0));
+
+        let bodies = RigidBodySet::new();
+        let center = Point::from(position.translation.vector);
+        let above = center + Vector::y() * 20.0 + Vector::x() * 0.01;
+
+        for (name, builder, has_interior) in built_in_shapes() {
+            let collider = builder.position(position).build();
+            let mut colliders = ColliderSet::new();
+            colliders.insert(collider.clone());
+            let mut query_pipeline = QueryPipeline::new();
+            query_pipeline.update(&bodies, &colliders);
+
+            for ray in [
+                Ray::new(above, -Vector::y()),
+                Ray::new(above, Vector::y()),
+                Ray::new(center, Vector::x()),
+            ] {
+                let hit = collider.cast_ray_and_get_normal(&ray, 100.0, true);
+                let expected = query_pipeline
+                    .cast_ray_and_get_normal(
+                        &bodies,
+                        &colliders,
+                        &ray,
+                        100.0,
+                        true,
+                        QueryFilter::default(),
+                    )
+                    .map(|(_, hit)| hit);
+                assert_eq!(hit.is_some(), expected.is_some(), "{name}: {ray:?}");
+                if let (Some(hit), Some(expected)) = (hit, expected) {
+                    approx::assert_relative_eq!(
+                        hit.time_of_impact,
+                        expected.time_of_impact,
+                        epsilon = 1.0e-5
+                    );
+                    // The normal is undefined for rays starting inside of the shape.
+                    if hit.time_of_impact > 0.0 {
+                        approx::assert_relative_eq!(hit.normal, expected.normal, epsilon = 1.0e-5);
+                    }
+                }
+                assert_eq!(
+                    collider.cast_ray(&ray, 100.0, true),
+                    hit.map(|hit| hit.time_of_impact),
+                    "{name}"
+                );
+            }
+
+            // The shape is below the ray origin, and not above it.
+            assert!(
+                collider
+                    .cast_ray(&Ray::new(above, -Vector::y()), 100.0, true)
+                    .is_some(),
+                "{name}"
+            );
+            assert!(
+                collider
+                    .cast_ray(&Ray::new(above, Vector::y()), 100.0, true)
+                    .is_none(),
+                "{name}"
+            );
+
+            assert!(!collider.contains_point(&above), "{name}");
+            if has_interior {
+                assert!(collider.contains_point(&center), "{name}");
+            }
+
+            for solid in [true, false] {
+                let projection = collider.project_point(&above, solid);
+                let (_, expected) = query_pipeline
+                    .project_point(&bodies, &colliders, &above, solid, QueryFilter::default())
+                    .unwrap();
+                assert_eq!(projection.is_inside, expected.is_inside, "{name}");
+                approx::assert_relative_eq!(projection.point, expected.point, epsilon = 1.0e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn uniformly_scaled_ball_matches_a_bigger_ball() {
+        use crate::dynamics::RigidBodyBuilder;
+        use crate::pipeline::PhysicsWorld;
+
+        let scaled = ColliderBuilder::ball(0.5)
+            .density(2.0)
+            .scale(Vector::repeat(2.0), 10)
+            .unwrap();
+        let bigger = ColliderBuilder::ball(1.0).density(2.0);
+        assert_eq!(scaled.shape.as_ball(), bigger.shape.as_ball());
+        assert_eq!(
+            scaled.build().mass_properties(),
+            bigger.build().mass_properties()
+        );
+
+        let trajectory = |builder: ColliderBuilder| {
+            let mut world = PhysicsWorld::default();
+            #[cfg(feature = "dim2")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+            world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+            let body = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * 3.0)
+                    .linvel(Vector::x() * 2.0),
+            );
+            world.insert_collider_with_parent(builder, body);
+
+            (0..100)
+                .map(|_| {
+                    world.step();
+                    *world.bodies()[body].position()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(trajectory(scaled), trajectory(bigger));
+    }
+
+    #[test]
+    fn scaled_shapes_have_scaled_mass_properties() {
+        #[cfg(feature = "dim2")]
+        let (scale, cuboid, scaled_cuboid) = (
+            Vector::new(2.0, 3.0),
+            ColliderBuilder::cuboid(1.0, 0.5),
+            ColliderBuilder::cuboid(2.0, 1.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (scale, cuboid, scaled_cuboid) = (
+            Vector::new(2.0, 3.0, 4.0),
+            ColliderBuilder::cuboid(1.0, 0.5, 0.25),
+            ColliderBuilder::cuboid(2.0, 1.5, 1.0),
+        );
+        let volume_ratio = scale.iter().product::<crate::math::Real>();
+
+        // Cuboids are scaled exactly.
+        let cuboid = cuboid.scale(scale, 10).unwrap().build();
+        assert_eq!(cuboid.shape().as_cuboid(), scaled_cuboid.shape.as_cuboid());
+        approx::assert_relative_eq!(
+            cuboid.mass_properties(),
+            scaled_cuboid.build().mass_properties(),
+            epsilon = 1.0e-5
+        );
+
+        // Non-uniformly scaled balls are approximated by a convex shape.
+        let ball = ColliderBuilder::ball(0.5).build();
+        let ellipsoid = ColliderBuilder::ball(0.5).scale(scale, 32).unwrap().build();
+        assert!(ellipsoid.shape().as_ball().is_none());
+        approx::assert_relative_eq!(
+            ellipsoid.mass(),
+            ball.mass() * volume_ratio,
+            max_relative = 0.02
+        );
+
+        assert!(ColliderBuilder::ball(0.5)
+            .scale(-Vector::repeat(1.0), 10)
+            .is_none());
+    }
+
+    #[test]
+    fn attached_collider_lists_stay_consistent() {
+        use crate::dynamics::{
+            ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodyBuilder, RigidBodyHandle,
+        };
+
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut islands = IslandManager::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+
+        let body1 = bodies.insert(RigidBodyBuilder::dynamic());
+        let body2 = bodies.insert(RigidBodyBuilder::dynamic());
+        let mut attach =
+            |body| colliders.insert_with_parent(ColliderBuilder::ball(0.5), body, &mut bodies);
+        let [co1, co2, co3, co4] = [attach(body1), attach(body1), attach(body1), attach(body2)];
+
+        let check = |bodies: &RigidBodySet, colliders: &ColliderSet| {
+            for (handle, body) in bodies.iter() {
+                let attached: Vec<_> = colliders.iter_attached(bodies, handle).collect();
+                assert_eq!(attached.len(), body.colliders().len());
+                for (co_handle, co) in attached {
+                    assert_eq!(co.parent(), Some(handle));
+                    assert!(body.colliders().contains(&co_handle));
+                }
+            }
+
+            for (handle, co) in colliders.iter() {
+                if let Some(parent) = co.parent() {
+                    assert!(bodies[parent].colliders().contains(&handle));
+                }
+            }
+        };
+        let sorted = |bodies: &RigidBodySet, body: RigidBodyHandle| {
+            let mut handles = bodies[body].colliders().to_vec();
+            handles.sort_by_key(|h| h.into_raw_parts());
+            handles
+        };
+
+        assert_eq!(sorted(&bodies, body1), [co1, co2, co3]);
+        check(&bodies, &colliders);
+
+        // Removing a collider from the middle of the list.
+        colliders.remove(co2, &mut islands, &mut bodies, true);
+        assert_eq!(sorted(&bodies, body1), [co1, co3]);
+        check(&bodies, &colliders);
+
+        // Moving a collider to another rigid-body, and detaching another one.
+        colliders.set_parent(co1, Some(body2), &mut bodies);
+        colliders.set_parent(co4, None, &mut bodies);
+        assert_eq!(sorted(&bodies, body1), [co3]);
+        assert_eq!(sorted(&bodies, body2), [co1]);
+        assert_eq!(colliders[co4].parent(), None);
+        check(&bodies, &colliders);
+
+        // Removing a rigid-body removes its colliders.
+        bodies.remove(
+            body2,
+            &mut islands,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            true,
+        );
+        assert!(!colliders.contains(co1));
+        assert_eq!(colliders.iter_attached(&bodies, body2).count(), 0);
+        check(&bodies, &colliders);
+
+        // Or detaches them.
+        bodies.remove(
+            body1,
+            &mut islands,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            false,
+        );
+        assert_eq!(colliders[co3].parent(), None);
+        assert_eq!(colliders.len(), 2);
+        check(&bodies, &colliders);
+    }
+
+    #[test]
+    fn density_changes_update_the_parent_mass() {
+        use crate::dynamics::{RevoluteJointBuilder, RigidBodyBuilder};
+        use crate::pipeline::PhysicsWorld;
+
+        // A see-saw: a plank with a weight at each end, attached to a fixed pivot above the ground.
+        let mut world = PhysicsWorld::default();
+        let pivot = world.insert_body(RigidBodyBuilder::fixed());
+        let plank = world.insert_body(RigidBodyBuilder::dynamic());
+        #[cfg(feature = "dim2")]
+        let (plank_shape, weight_shape, joint) = (
+            ColliderBuilder::cuboid(2.0, 0.05),
+            ColliderBuilder::cuboid(0.2, 0.2),
+            RevoluteJointBuilder::new(),
+        );
+        #[cfg(feature = "dim3")]
+        let (plank_shape, weight_shape, joint) = (
+            ColliderBuilder::cuboid(2.0, 0.05, 0.2),
+            ColliderBuilder::cuboid(0.2, 0.2, 0.2),
+            RevoluteJointBuilder::new(Vector::z_axis()),
+        );
+        let _ = world.insert_collider_with_parent(plank_shape, plank);
+        // The ground the ends of the plank rest on when it tips over.
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(5.0, 0.1);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(5.0, 0.1, 5.0);
+        let _ = world.insert_collider(ground.translation(Vector::y() * -0.6));
+        let left = world.insert_collider_with_parent(
+            weight_shape.clone().translation(Vector::x() * -1.8),
+            plank,
+        );
+        let _ =
+            world.insert_collider_with_parent(weight_shape.translation(Vector::x() * 1.8), plank);
+        let _ = world.insert_impulse_joint(pivot, plank, joint);
+        let angle = |world: &PhysicsWorld| {
+            #[cfg(feature = "dim2")]
+            return world.bodies[plank].rotation().angle();
+            #[cfg(feature = "dim3")]
+            return world.bodies[plank].rotation().scaled_axis().z;
+        };
+
+        // Balanced.
+        for _ in 0..60 {
+            world.step();
+        }
+        assert!(angle(&world).abs() < 1.0e-3);
+
+        // Making the left weight heavier wakes the plank up, and tips its left end down.
+        world.bodies[plank].sleep();
+        let mass = world.bodies[plank].mass();
+        world.colliders[left].set_density(10.0).unwrap();
+        for _ in 0..60 {
+            world.step();
+        }
+        assert!(world.bodies[plank].mass() > mass);
+        assert!(angle(&world) > 0.1);
+
+        // A zero density makes the left weight massless (but still solid), so the
+        // plank tips the other way.
+        world.colliders[left].set_density(0.0).unwrap();
+        for _ in 0..120 {
+            world.step();
+        }
+        assert!(angle(&world) < -0.1);
+        assert!(world.colliders[left].mass() == 0.0);
+
+        // Without any mass, the plank behaves as if its mass was infinite.
+        for handle in world.bodies[plank].colliders().to_vec() {
+            world.colliders[handle].set_density(0.0).unwrap();
+        }
+        let resting_angle = angle(&world);
+        for _ in 0..60 {
+            world.step();
+        }
+        assert_eq!(world.bodies[plank].mass(), 0.0);
+        assert!(angle(&world).is_finite());
+        assert!((angle(&world) - resting_angle).abs() < 1.0e-3);
+
+        // Negative densities are rejected.
+        assert!(world.colliders[left].set_density(-1.0).is_err());
+        assert_eq!(world.colliders[left].density(), 0.0);
+    }
+
+    /// A flat heightfield with 4 cells of width 1 along each horizontal axis, at `y = height`.
+    fn flat_heightfield(height: Real) -> ColliderBuilder {
+        #[cfg(feature = "dim2")]
+        let builder = ColliderBuilder::heightfield(na::DVector::zeros(5), Vector::new(4.0, 1.0));
+        #[cfg(feature = "dim3")]
+        let builder =
+            ColliderBuilder::heightfield(na::DMatrix::zeros(5, 5), Vector::new(4.0, 1.0, 4.0));
+        builder.translation(Vector::y() * height)
+    }
+
+    /// Removes the heightfield cell containing the given point.
+    fn remove_heightfield_cell(collider: &mut Collider, point: Point<Real>) {
+        let heightfield = collider.shape().as_heightfield().unwrap();
+        #[cfg(feature = "dim2")]
+        {
+            let i = heightfield.cell_at_point(&point).unwrap();
+            collider.set_heightfield_cell_status(i, true);
+        }
+        #[cfg(feature = "dim3")]
+        {
+            use crate::geometry::HeightFieldCellStatus;
+            let (i, j) = heightfield.cell_at_point(&point).unwrap();
+            collider.set_heightfield_cell_status(i, j, HeightFieldCellStatus::CELL_REMOVED);
+        }
+    }
+
+    #[test]
+    fn rays_go_through_heightfield_holes() {
+        use crate::pipeline::PhysicsWorld;
+
+        let mut world = PhysicsWorld::default();
+        let terrain = world.insert_collider(flat_heightfield(0.0));
+        let cave = world.insert_collider(flat_heightfield(-2.0));
+
+        // The centers of a cell, and of its neighbor.
+        let hole = Point::origin() + (Vector::repeat(0.5) - Vector::y() * 0.5);
+        let neighbor = hole - Vector::x();
+        remove_heightfield_cell(&mut world.colliders[terrain], hole);
+        world.step();
+        world.update_query_pipeline();
+
+        let cast_down = |world: &PhysicsWorld, point: Point<Real>| {
+            let ray = Ray::new(point + Vector::y() * 5.0, -Vector::y());
+            world.cast_ray(&ray, Real::MAX, true, QueryFilter::default())
+        };
+        assert_eq!(cast_down(&world, hole), Some((cave, 7.0)));
+        assert_eq!(cast_down(&world, neighbor), Some((terrain, 5.0)));
+
+        // Point projections ignore the hole too.
+        let projection = world.colliders[terrain].project_point(&hole, true);
+        approx::assert_relative_eq!((projection.point - hole).norm(), 0.5, epsilon = 1.0e-5);
+    }
+
+    #[test]
+    fn removing_a_heightfield_cell_wakes_up_resting_bodies() {
+        use crate::dynamics::RigidBodyBuilder;
+        use crate::pipeline::PhysicsWorld;
+
+        let mut world = PhysicsWorld::default();
+        let terrain = world.insert_collider(flat_heightfield(0.0));
+        let center = Point::origin() + (Vector::repeat(0.5) - Vector::y() * 0.5);
+        let body = world.insert_body(
+            RigidBodyBuilder::dynamic().translation(center.coords + Vector::y() * 0.25),
+        );
+        #[cfg(feature = "dim2")]
+        let cuboid = ColliderBuilder::cuboid(0.25, 0.25);
+        #[cfg(feature = "dim3")]
+        let cuboid = ColliderBuilder::cuboid(0.25, 0.25, 0.25);
+        world.insert_collider_with_parent(cuboid, body);
+
+        for _ in 0..300 {
+            world.step();
+        }
+        assert!(world.bodies[body].is_sleeping());
+
+        remove_heightfield_cell(&mut world.colliders[terrain], center);
+        for _ in 0..60 {
+            world.step();
+        }
+        assert!(world.bodies[body].translation().y < -1.0);
+    }
+
+    #[test]
+    fn material_maps_change_friction_at_material_boundaries() {
+        use crate::dynamics::{CoefficientCombineRule, RigidBodyBuilder};
+        use crate::geometry::{MaterialMap, SubShapeMaterial};
+        use crate::pipeline::PhysicsWorld;
+
+        // A terrain 2 cells wide along `x`, covered with ice for `x < 0` and mud for `x > 0`. Its
+        // only internal edge is at the boundary, so the ball doesn’t bump into any other one.
+        let ice = SubShapeMaterial {
+            friction: 0.0,
+            restitution: 0.0,
+        };
+        let mud = SubShapeMaterial {
+            friction: 0.5,
+            restitution: 0.0,
+        };
+        #[cfg(feature = "dim2")]
+        let (terrain, indices) = (
+            ColliderBuilder::heightfield(na::DVector::zeros(3), Vector::new(20.0, 1.0)),
+            vec![0, 1],
+        );
+        #[cfg(feature = "dim3")]
+        let (terrain, indices) = (
+            ColliderBuilder::heightfield(na::DMatrix::zeros(3, 3), Vector::new(20.0, 1.0, 20.0)),
+            // The cell at row `i` and column `j` has index `i + j * 2`, and columns go along `x`.
+            vec![0, 0, 1, 1],
+        );
+        let mut world = PhysicsWorld::default();
+        world.insert_collider(terrain.material_map(MaterialMap::new(vec![ice, mud], indices)));
+
+        let body = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * -8.0 + Vector::y() * 0.5)
+                .linvel(Vector::x() * 5.0)
+                .lock_rotations(),
+        );
+        let ball = ColliderBuilder::ball(0.5)
+            .friction(1.0)
+            .friction_combine_rule(CoefficientCombineRule::Multiply);
+        world.insert_collider_with_parent(ball, body);
+
+        let dt = world.integration_parameters.dt;
+        let mut num_steps_on_ice = 0;
+        let mut num_steps_on_mud = 0;
+        for _ in 0..240 {
+            let x = world.bodies[body].translation().x;
+            let vel = world.bodies[body].linvel().x;
+            world.step();
+            let accel = (world.bodies[body].linvel().x - vel) / dt;
+
+            // Skip the steps around the boundary, and the one where the ball stops.
+            if x < -0.6 {
+                approx::assert_relative_eq!(accel, 0.0, epsilon = 1.0e-2);
+                num_steps_on_ice += 1;
+            } else if x > 0.6 && vel > 0.1 {
+                approx::assert_relative_eq!(accel, -0.5 * 9.81, max_relative = 0.05);
+                num_steps_on_mud += 1;
+            }
+        }
+
+        assert!(num_steps_on_ice > 60);
+        assert!(num_steps_on_mud > 30);
+        assert!(world.bodies[body].linvel().x.abs() < 1.0e-3);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle, RigidBodyType};
 use crate::geometry::{BroadPhaseProxyIndex, InteractionGroups, Shape, SharedShape};
-use crate::math::{Isometry, Real};
+use crate::math::{Isometry, Point, Real, UnitVector, Vector, DEFAULT_EPSILON};
 use crate::parry::partitioning::IndexedData;
 use crate::pipeline::{ActiveEvents, ActiveHooks};
 use std::ops::{Deref, DerefMut};
@@ -31,6 +31,12 @@ impl ColliderHandle {
     }
 }
 
+impl std::fmt::Display for ColliderHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ColliderHandle({})", self.0)
+    }
+}
+
 impl IndexedData for ColliderHandle {
     fn default() -> Self {
         Self(IndexedData::default())
@@ -66,6 +72,9 @@ bitflags::bitflags! {
         const PARENT_EFFECTIVE_DOMINANCE = 1 << 7; // NF update.
         /// Flag indicating that whether or not the collider is enabled was changed.
         const ENABLED_OR_DISABLED = 1 << 8; // BF & NF updates.
+        /// Flag indicating that the `ColliderShape` component of the collider has been deformed
+        /// in-place, without changing its topology (e.g., the vertices of a triangle mesh moved).
+        const SHAPE_DEFORMED = 1 << 9; // => BF & NF update. NF contact cache invalidation.
     }
 }
 
@@ -82,6 +91,7 @@ impl ColliderChanges {
             ColliderChanges::PARENT
                 | ColliderChanges::POSITION
                 | ColliderChanges::SHAPE
+                | ColliderChanges::SHAPE_DEFORMED
                 | ColliderChanges::ENABLED_OR_DISABLED,
         )
     }
@@ -275,6 +285,300 @@ pub struct ColliderMaterial {
     pub friction_combine_rule: CoefficientCombineRule,
     /// The rule applied to combine the restitution coefficients of two colliders.
     pub restitution_combine_rule: CoefficientCombineRule,
+    /// Override of [`IntegrationParameters::restitution_velocity_threshold`](crate::dynamics::IntegrationParameters::restitution_velocity_threshold)
+    /// for contacts involving this collider.
+    ///
+    /// Contacts with a relative normal velocity smaller than this threshold don’t bounce. If
+    /// both colliders in contact have an override, the largest one is used.
+    pub restitution_velocity_threshold: Option<Real>,
+    /// A secondary friction coefficient applied along a specific direction of this collider.
+    ///
+    /// If `None`, the friction is isotropic and only [`Self::friction`] is used.
+    pub anisotropic_friction: Option<AnisotropicFriction>,
+    /// The rolling friction coefficient of this collider (default: `0.0`).
+    ///
+    /// At each contact, the relative angular velocity of the two rigid-bodies is opposed by an
+    /// angular impulse with a magnitude up to this coefficient multiplied by the normal impulse.
+    /// This lets round shapes come to rest instead of rolling forever. It is combined with the
+    /// rolling friction of the other collider with the friction combine rules. It is ignored
+    /// for contacts involving a multibody link.
+    pub rolling_friction: Real,
+}
+
+/// A friction coefficient applied along a specific direction of a collider.
+///
+/// Contacts sliding along [`Self::local_direction`] use [`Self::friction`], while contacts
+/// sliding orthogonally to it use the [`ColliderMaterial::friction`] coefficient. In-between
+/// directions interpolate between both coefficients. This is useful for, e.g., skis and skates.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct AnisotropicFriction {
+    /// The direction, in the collider’s local-space, along which [`Self::friction`] applies.
+    pub local_direction: UnitVector<Real>,
+    /// The friction coefficient applied to contacts sliding along [`Self::local_direction`].
+    pub friction: Real,
+}
+
+/// The friction and restitution coefficients of a part of a collider with a [`MaterialMap`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct SubShapeMaterial {
+    /// The friction coefficient of the parts with this material.
+    pub friction: Real,
+    /// The restitution coefficient of the parts with this material.
+    pub restitution: Real,
+}
+
+/// Friction and restitution coefficients varying across the parts of a collider’s shape.
+///
+/// This lets a single triangle mesh, polyline, or heightfield collider be made of several
+/// materials, e.g., a terrain made of ice, rock, and mud, instead of splitting it into one
+/// collider per material. Each part of the shape is assigned an index into [`Self::materials`]
+/// by [`Self::indices`]:
+/// - the `i`-th triangle of a triangle mesh, segment of a polyline, or sub-shape of a compound
+///   shape, is the `i`-th part.
+/// - the cell at the `i`-th row and `j`-th column of a 3D heightfield is the part `i + j * nrows`,
+///   where `nrows` is the number of rows of cells of the heightfield. Both its triangles share
+///   its material.
+/// - the `i`-th cell of a 2D heightfield is the `i`-th part.
+///
+/// The contacts with parts beyond the end of [`Self::indices`], or with an invalid material index,
+/// as well as the contacts with any other shape, use the [`ColliderMaterial`] coefficients. The
+/// [`AnisotropicFriction`] of a collider ignores its material map.
+#[derive(Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct MaterialMap {
+    /// The table of the materials of this map.
+    pub materials: Vec<SubShapeMaterial>,
+    /// The index, into [`Self::materials`], of the material of each part of the shape.
+    pub indices: Vec<u16>,
+}
+
+impl MaterialMap {
+    /// Creates a material map from a table of materials, and the index of the material of each
+    /// part of the shape.
+    pub fn new(materials: Vec<SubShapeMaterial>, indices: Vec<u16>) -> Self {
+        Self { materials, indices }
+    }
+
+    /// The material of the `i`-th part of the shape, if it has a valid one.
+    pub fn material(&self, i: usize) -> Option<&SubShapeMaterial> {
+        self.indices
+            .get(i)
+            .and_then(|id| self.materials.get(*id as usize))
+    }
+}
+
+/// The bounding volume tracking a collider in the broad-phase.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum BroadPhaseProxyShape {
+    /// The tight AABB of the collider, recomputed whenever it moves.
+    #[default]
+    Aabb,
+    /// The AABB of the collider’s bounding sphere.
+    ///
+    /// It is larger than the tight AABB, but it doesn’t change while the collider only rotates
+    /// around the center of its bounding sphere. This avoids updating the broad-phase for
+    /// long thin colliders spinning in place.
+    BoundingSphere,
+    /// Uses [`BroadPhaseProxyShape::BoundingSphere`] if the ratio between the largest and
+    /// smallest extents of the collider’s local AABB exceeds `max_aspect_ratio`, and
+    /// [`BroadPhaseProxyShape::Aabb`] otherwise.
+    Automatic {
+        /// The aspect ratio above which a bounding sphere is used.
+        max_aspect_ratio: Real,
+    },
+}
+
+impl BroadPhaseProxyShape {
+    /// Does a collider with the given shape use a bounding-sphere proxy?
+    pub fn uses_bounding_sphere(&self, shape: &dyn Shape) -> bool {
+        match *self {
+            Self::Aabb => false,
+            Self::BoundingSphere => true,
+            Self::Automatic { max_aspect_ratio } => {
+                let extents = shape.compute_local_aabb().extents();
+                extents.max() > extents.min() * max_aspect_ratio
+            }
+        }
+    }
+}
+
+/// How the radial acceleration of a [`ForceField`] decreases with the distance to its center.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum ForceFieldFalloff {
+    /// The radial acceleration has the same magnitude everywhere.
+    #[default]
+    Constant,
+    /// The radial acceleration decreases linearly from its full magnitude at the center
+    /// to zero at `radius` (and beyond).
+    Linear {
+        /// The distance from the center where the radial acceleration vanishes.
+        radius: Real,
+    },
+    /// The radial acceleration decreases with the inverse square of the distance to the
+    /// center, and has its full magnitude at `min_distance` or closer.
+    InverseSquare {
+        /// The distance below which the radial acceleration stops growing.
+        min_distance: Real,
+    },
+}
+
+impl ForceFieldFalloff {
+    /// The factor multiplying the radial acceleration at the given distance from the center.
+    pub fn factor(&self, distance: Real) -> Real {
+        match *self {
+            Self::Constant => 1.0,
+            Self::Linear { radius } => (1.0 - distance / radius).max(0.0),
+            Self::InverseSquare { min_distance } => {
+                let ratio = min_distance / distance.max(min_distance);
+                ratio * ratio
+            }
+        }
+    }
+}
+
+/// Forces applied by a sensor collider to every dynamic rigid-body intersecting it.
+///
+/// This can model wind zones (constant force), water volumes (upward acceleration combined with
+/// drag), or gravity wells (radial acceleration). All the contributions of a field are added
+/// together, and the fields of overlapping sensors add up as well.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct ForceField {
+    /// A world-space force applied to the center-of-mass of each body inside the field.
+    pub force: Vector<Real>,
+    /// A world-space acceleration applied to each body inside the field, independently
+    /// from its mass (just like gravity).
+    pub acceleration: Vector<Real>,
+    /// Coefficient of the deceleration opposing the linear velocity of the bodies inside the field.
+    pub linear_drag: Real,
+    /// The acceleration pulling the bodies inside the field toward the origin of the sensor
+    /// collider. A negative value pushes them away instead.
+    pub radial_acceleration: Real,
+    /// How [`Self::radial_acceleration`] decreases with the distance to the sensor origin.
+    pub radial_falloff: ForceFieldFalloff,
+    /// The depth into the sensor over which the field fades in from its boundary.
+    ///
+    /// The field is scaled by the depth of the body’s center-of-mass inside the sensor divided
+    /// by this width (clamped to 1). This makes, e.g., the buoyancy of a water volume increase
+    /// with the depth of a floating body instead of switching on and off as it crosses the
+    /// surface, letting it settle. If zero, the field applies fully as soon as the body
+    /// touches the sensor.
+    pub boundary_width: Real,
+}
+
+impl ForceField {
+    /// A force field that doesn’t apply any force.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the world-space force applied to each body inside the field.
+    pub fn force(mut self, force: Vector<Real>) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Sets the world-space acceleration applied to each body inside the field.
+    pub fn acceleration(mut self, acceleration: Vector<Real>) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    /// Sets the coefficient of the drag opposing the linear velocity of the bodies inside the field.
+    pub fn linear_drag(mut self, linear_drag: Real) -> Self {
+        self.linear_drag = linear_drag;
+        self
+    }
+
+    /// Sets the acceleration pulling the bodies inside the field toward the origin of the sensor.
+    pub fn radial_acceleration(mut self, acceleration: Real, falloff: ForceFieldFalloff) -> Self {
+        self.radial_acceleration = acceleration;
+        self.radial_falloff = falloff;
+        self
+    }
+
+    /// Sets the depth into the sensor over which the field fades in from its boundary.
+    pub fn boundary_width(mut self, width: Real) -> Self {
+        self.boundary_width = width;
+        self
+    }
+
+    /// The factor scaling this field, attached to a sensor with the given shape and position,
+    /// at the given world-space point.
+    pub fn boundary_factor(
+        &self,
+        field_shape: &dyn Shape,
+        field_pos: &Isometry<Real>,
+        point: &Point<Real>,
+    ) -> Real {
+        if self.boundary_width <= 0.0 {
+            return 1.0;
+        }
+
+        let proj = field_shape.project_point(field_pos, point, false);
+        if proj.is_inside {
+            (na::distance(&proj.point, point) / self.boundary_width).min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// The mass-independent acceleration (i.e., the constant and radial accelerations) of this field,
+    /// not scaled by its boundary factor.
+    fn unscaled_gravity(
+        &self,
+        field_pos: &Isometry<Real>,
+        world_com: &Point<Real>,
+    ) -> Vector<Real> {
+        let mut acceleration = self.acceleration;
+
+        if self.radial_acceleration != 0.0 {
+            let to_center = field_pos.translation.vector - world_com.coords;
+            let distance = to_center.norm();
+
+            if distance > DEFAULT_EPSILON {
+                let magnitude = self.radial_acceleration * self.radial_falloff.factor(distance);
+                acceleration += to_center * (magnitude / distance);
+            }
+        }
+
+        acceleration
+    }
+
+    /// The gravitational acceleration applied by this field, attached to a sensor with the given
+    /// shape and position, to a body with the given center-of-mass.
+    ///
+    /// This is the part of [`Self::force_on_body`] that doesn’t depend on the body’s mass and
+    /// velocity: the constant and radial accelerations, but not the force and drag.
+    pub fn gravity_on_body(
+        &self,
+        field_shape: &dyn Shape,
+        field_pos: &Isometry<Real>,
+        world_com: &Point<Real>,
+    ) -> Vector<Real> {
+        self.unscaled_gravity(field_pos, world_com)
+            * self.boundary_factor(field_shape, field_pos, world_com)
+    }
+
+    /// The force applied by this field, attached to a sensor with the given shape and position,
+    /// to a body with the given mass, center-of-mass, and linear velocity.
+    pub fn force_on_body(
+        &self,
+        field_shape: &dyn Shape,
+        field_pos: &Isometry<Real>,
+        mass: &Vector<Real>,
+        world_com: &Point<Real>,
+        linvel: &Vector<Real>,
+    ) -> Vector<Real> {
+        let acceleration = self.unscaled_gravity(field_pos, world_com) - linvel * self.linear_drag;
+
+        (self.force + acceleration.component_mul(mass))
+            * self.boundary_factor(field_shape, field_pos, world_com)
+    }
 }
 
 impl ColliderMaterial {
@@ -295,6 +599,9 @@ impl Default for ColliderMaterial {
             restitution: 0.0,
             friction_combine_rule: CoefficientCombineRule::default(),
             restitution_combine_rule: CoefficientCombineRule::default(),
+            restitution_velocity_threshold: None,
+            anisotropic_friction: None,
+            rolling_friction: 0.0,
         }
     }
 }
@@ -303,6 +610,9 @@ bitflags::bitflags! {
     #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
     /// Flags affecting whether or not collision-detection happens between two colliders
     /// depending on the type of rigid-bodies they are attached to.
+    ///
+    /// Pairs involving no dynamic rigid-body (e.g. [`Self::KINEMATIC_FIXED`]) generate contact
+    /// manifolds and collision events but are never handed to the constraints solver.
     pub struct ActiveCollisionTypes: u16 {
         /// Enable collision-detection between a collider attached to a dynamic body
         /// and another collider attached to a dynamic body.
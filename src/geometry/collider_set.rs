@@ -1,9 +1,37 @@
 use crate::data::arena::Arena;
 use crate::dynamics::{IslandManager, RigidBodyHandle, RigidBodySet};
-use crate::geometry::{Collider, ColliderChanges, ColliderHandle, ColliderParent};
-use crate::math::Isometry;
+use crate::geometry::{
+    Collider, ColliderChanges, ColliderHandle, ColliderParent, ColliderPosition,
+};
+use crate::math::{Isometry, Real};
+use crate::pipeline::InvalidHandle;
+use std::collections::HashMap;
 use std::ops::{Index, IndexMut};
 
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// Information about a collider that was removed from a [`ColliderSet`].
+///
+/// See [`ColliderSet::removed_collider`].
+pub struct RemovedCollider {
+    /// The user-defined data of the removed collider.
+    pub user_data: u128,
+    /// The rigid-body the removed collider was attached to, if any.
+    pub parent: Option<RigidBodyHandle>,
+    /// The world-space position of the removed collider when it was removed.
+    pub position: Isometry<Real>,
+}
+
+impl RemovedCollider {
+    fn new(collider: &Collider) -> Self {
+        Self {
+            user_data: collider.user_data,
+            parent: collider.parent(),
+            position: *collider.position(),
+        }
+    }
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Default)]
 /// A set of colliders that can be handled by a physics `World`.
@@ -11,6 +39,10 @@ pub struct ColliderSet {
     pub(crate) colliders: Arena<Collider>,
     pub(crate) modified_colliders: Vec<ColliderHandle>,
     pub(crate) removed_colliders: Vec<ColliderHandle>,
+    // Colliders removed since the last call to `take_removed`.
+    pub(crate) removed_colliders_info: HashMap<ColliderHandle, RemovedCollider>,
+    // Colliders removed before the last call to `take_removed`, kept until the next one.
+    pub(crate) last_removed_colliders_info: HashMap<ColliderHandle, RemovedCollider>,
 }
 
 impl ColliderSet {
@@ -20,6 +52,8 @@ impl ColliderSet {
             colliders: Arena::new(),
             modified_colliders: Vec::new(),
             removed_colliders: Vec::new(),
+            removed_colliders_info: HashMap::new(),
+            last_removed_colliders_info: HashMap::new(),
         }
     }
 
@@ -28,9 +62,27 @@ impl ColliderSet {
     }
 
     pub(crate) fn take_removed(&mut self) -> Vec<ColliderHandle> {
+        self.last_removed_colliders_info = std::mem::take(&mut self.removed_colliders_info);
         std::mem::take(&mut self.removed_colliders)
     }
 
+    /// Information about a collider that was recently removed from this set.
+    ///
+    /// The handle of a removed collider can still show up in the `CollisionEvent::Stopped`
+    /// events emitted by the simulation step following its removal, but it can no longer be used
+    /// with [`Self::get`]. This method gives access to its user-data, parent rigid-body, and last
+    /// known position instead.
+    ///
+    /// This information is kept from the removal of the collider until the end of the second
+    /// simulation step following it. So it is available from the
+    /// [`EventHandler`](crate::pipeline::EventHandler) during the step emitting the events, as
+    /// well as when reading collected events after that step.
+    pub fn removed_collider(&self, handle: ColliderHandle) -> Option<&RemovedCollider> {
+        self.removed_colliders_info
+            .get(&handle)
+            .or_else(|| self.last_removed_colliders_info.get(&handle))
+    }
+
     /// An always-invalid collider handle.
     pub fn invalid_handle() -> ColliderHandle {
         ColliderHandle::from_raw_parts(crate::INVALID_U32, crate::INVALID_U32)
@@ -66,6 +118,24 @@ impl ColliderSet {
         self.iter_mut().filter(|(_, c)| c.is_enabled())
     }
 
+    /// Iterate through all the colliders attached to the given rigid-body.
+    ///
+    /// This relies on the list of collider handles stored on the rigid-body (see
+    /// [`RigidBody::colliders`](crate::dynamics::RigidBody::colliders)) instead of scanning
+    /// the whole set. The iterator is empty if the rigid-body doesn’t exist.
+    pub fn iter_attached<'a>(
+        &'a self,
+        bodies: &'a RigidBodySet,
+        body_handle: RigidBodyHandle,
+    ) -> impl Iterator<Item = (ColliderHandle, &'a Collider)> {
+        bodies
+            .get(body_handle)
+            .map(|rb| rb.colliders())
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|handle| Some((*handle, self.get(*handle)?)))
+    }
+
     /// The number of colliders on this set.
     pub fn len(&self) -> usize {
         self.colliders.len()
@@ -133,7 +203,34 @@ impl ColliderSet {
         handle
     }
 
+    /// Inserts a new collider to this set and attach it to the given rigid-body, after checking
+    /// that this rigid-body exists.
+    ///
+    /// Returns an error instead of panicking if `parent_handle` doesn’t identify any rigid-body
+    /// of `bodies`. See [`Self::insert_with_parent`] for details.
+    pub fn try_insert_with_parent(
+        &mut self,
+        coll: impl Into<Collider>,
+        parent_handle: RigidBodyHandle,
+        bodies: &mut RigidBodySet,
+    ) -> Result<ColliderHandle, InvalidHandle> {
+        if !bodies.contains(parent_handle) {
+            return Err(InvalidHandle::RigidBody(parent_handle));
+        }
+
+        Ok(self.insert_with_parent(coll, parent_handle, bodies))
+    }
+
     /// Sets the parent of the given collider.
+    ///
+    /// The collider is detached from its current rigid-body (if any) and attached to
+    /// `new_parent_handle`, or left without parent if it is `None`. Its world-space pose is
+    /// preserved: its position relative to the new parent is recomputed from the current
+    /// positions of both rigid-bodies. The mass properties of both rigid-bodies are updated.
+    ///
+    /// The collider keeps its handle and broad-phase proxy. Its contacts with other colliders
+    /// are kept, except with the colliders now attached to the same rigid-body, and it starts
+    /// interacting with the colliders of its previous rigid-body.
     // TODO: find a way to define this as a method of Collider.
     pub fn set_parent(
         &mut self,
@@ -149,6 +246,14 @@ impl ColliderSet {
 
             collider.changes |= ColliderChanges::PARENT;
 
+            // The collider position is only updated during the next step, so recompute it from
+            // its parent in case the parent was moved since then.
+            let world_pos = collider
+                .parent
+                .and_then(|p| Some(bodies.get(p.handle)?.position() * p.pos_wrt_parent))
+                .unwrap_or(collider.pos.0);
+            collider.pos = ColliderPosition(world_pos);
+
             if let Some(parent_handle) = curr_parent {
                 if let Some(rb) = bodies.get_mut(parent_handle) {
                     rb.remove_collider_internal(handle);
@@ -157,14 +262,14 @@ impl ColliderSet {
 
             match new_parent_handle {
                 Some(new_parent_handle) => {
-                    if let Some(parent) = &mut collider.parent {
-                        parent.handle = new_parent_handle;
-                    } else {
-                        collider.parent = Some(ColliderParent {
-                            handle: new_parent_handle,
-                            pos_wrt_parent: Isometry::identity(),
-                        })
-                    };
+                    let pos_wrt_parent = bodies
+                        .get(new_parent_handle)
+                        .map(|rb| rb.position().inv_mul(&world_pos))
+                        .unwrap_or_else(Isometry::identity);
+                    collider.parent = Some(ColliderParent {
+                        handle: new_parent_handle,
+                        pos_wrt_parent,
+                    });
 
                     if let Some(rb) = bodies.get_mut(new_parent_handle) {
                         rb.add_collider(
@@ -185,6 +290,12 @@ impl ColliderSet {
     ///
     /// If `wake_up` is `true`, the rigid-body the removed collider is attached to
     /// will be woken up.
+    ///
+    /// The contact and intersection pairs involving the removed collider are cleaned-up by the
+    /// narrow-phase at the beginning of the next simulation step. At this point, the relevant
+    /// `CollisionEvent::Stopped` events are emitted and every other rigid-body that was touching
+    /// the removed collider is woken up. Information about the removed collider remains available
+    /// through [`Self::removed_collider`] while these events are processed.
     pub fn remove(
         &mut self,
         handle: ColliderHandle,
@@ -215,10 +326,81 @@ impl ColliderSet {
          * Publish removal.
          */
         self.removed_colliders.push(handle);
+        self.removed_colliders_info
+            .insert(handle, RemovedCollider::new(&collider));
 
         Some(collider)
     }
 
+    /// Removes several colliders from this set and update their parents accordingly.
+    ///
+    /// This is equivalent to calling [`Self::remove`] on each handle, except that each parent
+    /// rigid-body is updated only once. This keeps the removal of many colliders attached to the
+    /// same rigid-body (e.g., when unloading a whole terrain chunk) linear in the number of
+    /// colliders instead of quadratic. Handles of colliders that don’t exist are ignored.
+    ///
+    /// If `wake_up` is `true`, the rigid-bodies the removed colliders are attached to will be
+    /// woken up. Like with [`Self::remove`], the broad-phase and the narrow-phase are cleaned-up
+    /// at the beginning of the next simulation step, all at once.
+    ///
+    /// Returns the removed colliders with their handles, in the order of `handles`.
+    pub fn remove_batch(
+        &mut self,
+        handles: &[ColliderHandle],
+        islands: &mut IslandManager,
+        bodies: &mut RigidBodySet,
+        wake_up: bool,
+    ) -> Vec<(ColliderHandle, Collider)> {
+        let mut removed = Vec::with_capacity(handles.len());
+        let mut parents = Vec::new();
+
+        for handle in handles {
+            if let Some(collider) = self.colliders.remove(handle.0) {
+                if let Some(parent) = &collider.parent {
+                    parents.push(parent.handle);
+                }
+
+                self.removed_colliders.push(*handle);
+                self.removed_colliders_info
+                    .insert(*handle, RemovedCollider::new(&collider));
+                removed.push((*handle, collider));
+            }
+        }
+
+        /*
+         * Delete the colliders from their parent bodies, one body at a time.
+         */
+        parents.sort_unstable_by_key(|handle| handle.into_raw_parts());
+        parents.dedup();
+
+        for parent in parents {
+            if let Some(parent_rb) = bodies.get_mut_internal_with_modification_tracking(parent) {
+                parent_rb.retain_colliders_internal(|handle| self.colliders.contains(handle.0));
+
+                if wake_up {
+                    islands.wake_up(bodies, parent, true);
+                }
+            }
+        }
+
+        removed
+    }
+
+    /// Removes all the colliders from this set, but keeps its allocated memory.
+    ///
+    /// Unlike [`Self::remove`], this doesn’t notify the other structures of the removals. This is
+    /// meant for emptying a whole world in-place, along with [`RigidBodySet::clear`],
+    /// [`NarrowPhase::clear`](crate::geometry::NarrowPhase::clear),
+    /// [`BroadPhase::clear`](crate::geometry::BroadPhase::clear), etc. See
+    /// [`PhysicsWorld::clear`](crate::pipeline::PhysicsWorld::clear) for an example.
+    pub fn clear(&mut self) {
+        self.colliders.clear();
+        self.modified_colliders.clear();
+        self.removed_colliders.clear();
+        self.removed_colliders_info.clear();
+        self.last_removed_colliders_info.clear();
+    }
+
     /// Gets the collider with the given handle without a known generation.
     ///
     /// This is useful when you know you want the collider at position `i` but
@@ -308,14 +490,18 @@ impl Index<ColliderHandle> for ColliderSet {
     type Output = Collider;
 
     fn index(&self, index: ColliderHandle) -> &Collider {
-        &self.colliders[index.0]
+        self.get(index)
+            .unwrap_or_else(|| panic!("{}.", InvalidHandle::Collider(index)))
     }
 }
 
 #[cfg(not(feature = "dev-remove-slow-accessors"))]
 impl IndexMut<ColliderHandle> for ColliderSet {
     fn index_mut(&mut self, handle: ColliderHandle) -> &mut Collider {
-        let collider = &mut self.colliders[handle.0];
+        let collider = self
+            .colliders
+            .get_mut(handle.0)
+            .unwrap_or_else(|| panic!("{}.", InvalidHandle::Collider(handle)));
         Self::mark_as_modified(handle, collider, &mut self.modified_colliders);
         collider
     }
@@ -1,9 +1,11 @@
 use crate::dynamics::{RigidBodyHandle, RigidBodySet};
 use crate::geometry::{ColliderHandle, ColliderSet, Contact, ContactManifold};
-use crate::math::{Point, Real, TangentImpulse, Vector};
+use crate::math::{Isometry, Point, Real, TangentImpulse, Vector};
 use crate::pipeline::EventHandler;
 use crate::prelude::CollisionEventFlags;
 use parry::query::ContactManifoldsWorkspace;
+use parry::shape::FeatureId;
+use parry::utils::IsometryOpt;
 
 use super::CollisionEvent;
 
@@ -38,6 +40,15 @@ pub struct ContactData {
     pub warmstart_impulse: Real,
     /// The friction impulse retained for warmstarting the next simulation step.
     pub warmstart_tangent_impulse: TangentImpulse<Real>,
+    /// The number of consecutive contact updates this contact point was kept through.
+    ///
+    /// This is reset to zero when the contact point is replaced by one between other features
+    /// that isn’t within
+    /// [`IntegrationParameters::contact_matching_tolerance`](crate::dynamics::IntegrationParameters::contact_matching_tolerance)
+    /// of it, or when one of the colliders was teleported or given a new shape by the user. Contact points younger
+    /// than [`IntegrationParameters::warmstart_min_contact_age`](crate::dynamics::IntegrationParameters::warmstart_min_contact_age)
+    /// aren’t warmstarted.
+    pub age: u32,
 }
 
 impl Default for ContactData {
@@ -47,6 +58,7 @@ impl Default for ContactData {
             tangent_impulse: na::zero(),
             warmstart_impulse: 0.0,
             warmstart_tangent_impulse: na::zero(),
+            age: 0,
         }
     }
 }
@@ -57,6 +69,12 @@ impl Default for ContactData {
 pub struct IntersectionPair {
     /// Are the colliders intersecting?
     pub intersecting: bool,
+    /// Are the shapes closer than the sensor margin to the boundary of the intersection?
+    ///
+    /// This is only set if one of the colliders has a non-zero
+    /// [`Collider::sensor_margin`](crate::geometry::Collider::sensor_margin). While it is `true`,
+    /// `intersecting` keeps the value it had when the shapes entered the margin.
+    pub within_margin: bool,
     /// Was a `CollisionEvent::Started` emitted for this collider?
     pub(crate) start_event_emited: bool,
 }
@@ -65,6 +83,7 @@ impl IntersectionPair {
     pub(crate) fn new() -> Self {
         Self {
             intersecting: false,
+            within_margin: false,
             start_event_emited: false,
         }
     }
@@ -76,9 +95,11 @@ impl IntersectionPair {
         collider1: ColliderHandle,
         collider2: ColliderHandle,
         events: &dyn EventHandler,
+        step_id: u64,
     ) {
         self.start_event_emited = true;
         events.handle_collision_event(
+            step_id,
             bodies,
             colliders,
             CollisionEvent::Started(collider1, collider2, CollisionEventFlags::SENSOR),
@@ -93,9 +114,11 @@ impl IntersectionPair {
         collider1: ColliderHandle,
         collider2: ColliderHandle,
         events: &dyn EventHandler,
+        step_id: u64,
     ) {
         self.start_event_emited = false;
         events.handle_collision_event(
+            step_id,
             bodies,
             colliders,
             CollisionEvent::Stopped(collider1, collider2, CollisionEventFlags::SENSOR),
@@ -104,11 +127,85 @@ impl IntersectionPair {
     }
 }
 
+/// A pair of colliders that started or stopped touching during the last step.
+///
+/// See [`NarrowPhase::contacts_started`](crate::geometry::NarrowPhase::contacts_started) and
+/// [`NarrowPhase::contacts_stopped`](crate::geometry::NarrowPhase::contacts_stopped).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ContactTransition {
+    /// The first collider involved in the contact pair.
+    pub collider1: ColliderHandle,
+    /// The second collider involved in the contact pair.
+    pub collider2: ColliderHandle,
+    /// Does any contact manifold of this pair have contact points?
+    ///
+    /// This is `false` if the pair was removed from the narrow-phase.
+    pub has_any_active_contact: bool,
+    /// Was this transition caused by the removal (or disabling) of one of the colliders?
+    pub removed: bool,
+}
+
+/// A pair of colliders, involving at least one sensor, that started or stopped intersecting
+/// during the last step.
+///
+/// See [`NarrowPhase::proximities_changed`](crate::geometry::NarrowPhase::proximities_changed).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IntersectionTransition {
+    /// The first collider involved in the intersection pair.
+    pub collider1: ColliderHandle,
+    /// The second collider involved in the intersection pair.
+    pub collider2: ColliderHandle,
+    /// Did the colliders start intersecting (`true`) or stop intersecting (`false`)?
+    pub intersecting: bool,
+    /// Was this transition caused by the removal (or disabling) of one of the colliders?
+    pub removed: bool,
+}
+
+/// The stable identifier of a contact pair in the narrow-phase.
+///
+/// It remains the same for as long as the contact pair exists, i.e., until the broad-phase stops
+/// reporting the two colliders as potentially overlapping or one of them is removed. After that,
+/// the identifier is invalidated and [`NarrowPhase::contact_pair_by_id`](crate::geometry::NarrowPhase::contact_pair_by_id)
+/// returns `None`, even if a new contact pair is later created between the same colliders.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[repr(transparent)]
+pub struct ContactPairId(pub crate::data::arena::Index);
+
+impl ContactPairId {
+    /// Converts this identifier into its (index, generation) components.
+    pub fn into_raw_parts(self) -> (u32, u32) {
+        self.0.into_raw_parts()
+    }
+
+    /// Reconstructs an identifier from its (index, generation) components.
+    pub fn from_raw_parts(id: u32, generation: u32) -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(id, generation))
+    }
+
+    /// An always-invalid contact pair identifier.
+    pub fn invalid() -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(
+            crate::INVALID_U32,
+            crate::INVALID_U32,
+        ))
+    }
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
 /// The description of all the contacts between a pair of colliders.
+///
+/// The first collider of the pair is always the one with the smallest user-data, or the smallest
+/// handle if their user-data are equal, so the order doesn’t depend on the order the colliders
+/// started interacting in. All the contact normals of
+/// the pair point from [`Self::collider1`] toward [`Self::collider2`]. Use
+/// [`ContactManifoldExt::normal_wrt`] to get the normal relative to a specific collider.
 pub struct ContactPair {
     /// The first collider involved in the contact pair.
+    ///
+    /// This is the collider with the smallest user-data of the pair, or the smallest handle if
+    /// both have the same user-data.
     pub collider1: ColliderHandle,
     /// The second collider involved in the contact pair.
     pub collider2: ColliderHandle,
@@ -124,6 +221,11 @@ pub struct ContactPair {
     /// Was a `CollisionEvent::Started` emitted for this collider?
     pub(crate) start_event_emited: bool,
     pub(crate) workspace: Option<ContactManifoldsWorkspace>,
+    pub(crate) id: ContactPairId,
+    /// Buffer reused by the narrow-phase to keep the previous contact points while computing the
+    /// new ones.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    pub(crate) previous_contacts: Vec<(u32, u32, Contact)>,
 }
 
 impl ContactPair {
@@ -135,9 +237,18 @@ impl ContactPair {
             manifolds: Vec::new(),
             start_event_emited: false,
             workspace: None,
+            id: ContactPairId::invalid(),
+            previous_contacts: Vec::new(),
         }
     }
 
+    /// The stable identifier of this contact pair.
+    ///
+    /// See [`ContactPairId`] for details about its lifetime.
+    pub fn id(&self) -> ContactPairId {
+        self.id
+    }
+
     /// Clears all the contacts of this contact pair.
     pub fn clear(&mut self) {
         self.manifolds.clear();
@@ -145,6 +256,25 @@ impl ContactPair {
         self.workspace = None;
     }
 
+    /// Resets the impulses cached by all the contacts of this pair, so they aren’t used to
+    /// warmstart the next simulation step.
+    ///
+    /// The geometric data of the contact manifolds is kept. See
+    /// [`NarrowPhase::clear_cached_impulses_for`](crate::geometry::NarrowPhase::clear_cached_impulses_for)
+    /// for when this is advisable.
+    pub fn clear_warmstart(&mut self) {
+        for manifold in &mut self.manifolds {
+            for contact in &mut manifold.points {
+                contact.data = ContactData::default();
+            }
+
+            for solver_contact in &mut manifold.data.solver_contacts {
+                solver_contact.warmstart_impulse = 0.0;
+                solver_contact.warmstart_tangent_impulse = na::zero();
+            }
+        }
+    }
+
     /// The sum of all the impulses applied by contacts on this contact pair.
     pub fn total_impulse(&self) -> Vector<Real> {
         self.manifolds
@@ -204,15 +334,56 @@ impl ContactPair {
         deepest
     }
 
+    /// Iterates through all the contacts of this contact pair, as seen in world-space.
+    ///
+    /// The contact points are stored in the local-space of the colliders’ shapes. The returned
+    /// views transform them lazily with the current positions of the colliders from `colliders`.
+    /// Nothing is yielded if any of the colliders is no longer part of `colliders`.
+    pub fn world_contacts<'a>(
+        &'a self,
+        colliders: &ColliderSet,
+    ) -> impl Iterator<Item = ContactView<'a>> + 'a {
+        let positions = colliders
+            .get(self.collider1)
+            .zip(colliders.get(self.collider2))
+            .map(|(co1, co2)| (*co1.position(), *co2.position()));
+
+        positions.into_iter().flat_map(move |(pos1, pos2)| {
+            self.manifolds.iter().flat_map(move |manifold| {
+                let subshape_pos1 = manifold.subshape_pos1.prepend_to(&pos1);
+                let subshape_pos2 = manifold.subshape_pos2.prepend_to(&pos2);
+                manifold.contacts().iter().map(move |contact| ContactView {
+                    manifold,
+                    contact,
+                    subshape_pos1,
+                    subshape_pos2,
+                })
+            })
+        })
+    }
+
+    /// Finds the contact with the smallest signed distance, as seen in world-space.
+    ///
+    /// See [`Self::find_deepest_contact`] and [`Self::world_contacts`] for details.
+    pub fn find_deepest_world_contact<'a>(
+        &'a self,
+        colliders: &ColliderSet,
+    ) -> Option<ContactView<'a>> {
+        self.world_contacts(colliders)
+            .min_by(|a, b| a.contact.dist.total_cmp(&b.contact.dist))
+    }
+
     pub(crate) fn emit_start_event(
         &mut self,
         bodies: &RigidBodySet,
         colliders: &ColliderSet,
         events: &dyn EventHandler,
+        step_id: u64,
     ) {
         self.start_event_emited = true;
 
         events.handle_collision_event(
+            step_id,
             bodies,
             colliders,
             CollisionEvent::Started(self.collider1, self.collider2, CollisionEventFlags::empty()),
@@ -225,10 +396,12 @@ impl ContactPair {
         bodies: &RigidBodySet,
         colliders: &ColliderSet,
         events: &dyn EventHandler,
+        step_id: u64,
     ) {
         self.start_event_emited = false;
 
         events.handle_collision_event(
+            step_id,
             bodies,
             colliders,
             CollisionEvent::Stopped(self.collider1, self.collider2, CollisionEventFlags::empty()),
@@ -245,6 +418,10 @@ impl ContactPair {
 /// part of the same contact manifold share the same contact normal and contact kinematics.
 pub struct ContactManifoldData {
     // The following are set by the narrow-phase.
+    /// The first collider involved in this contact manifold.
+    pub collider1: ColliderHandle,
+    /// The second collider involved in this contact manifold.
+    pub collider2: ColliderHandle,
     /// The first rigid-body involved in this contact manifold.
     pub rigid_body1: Option<RigidBodyHandle>,
     /// The second rigid-body involved in this contact manifold.
@@ -254,6 +431,8 @@ pub struct ContactManifoldData {
     /// Flags used to control some aspects of the constraints solver for this contact manifold.
     pub solver_flags: SolverFlags,
     /// The world-space contact normal shared by all the contact in this contact manifold.
+    ///
+    /// It points from [`Self::collider1`] toward [`Self::collider2`].
     // NOTE: read the comment of `solver_contacts` regarding serialization. It applies
     // to this field as well.
     pub normal: Vector<Real>,
@@ -281,6 +460,65 @@ pub struct ContactManifoldData {
     pub user_data: u32,
 }
 
+/// A contact of a [`ContactPair`], with its geometry expressed in world-space.
+///
+/// This is obtained with [`ContactPair::world_contacts`].
+#[derive(Copy, Clone, Debug)]
+pub struct ContactView<'a> {
+    /// The contact manifold this contact is part of.
+    pub manifold: &'a ContactManifold,
+    /// The contact, with its geometry expressed in the local-space of the shapes.
+    pub contact: &'a Contact,
+    /// The world-space position of the shape of the first collider the contact is expressed in.
+    ///
+    /// This is the position of the sub-shape for composite shapes.
+    pub subshape_pos1: Isometry<Real>,
+    /// The world-space position of the shape of the second collider the contact is expressed in.
+    ///
+    /// This is the position of the sub-shape for composite shapes.
+    pub subshape_pos2: Isometry<Real>,
+}
+
+impl<'a> ContactView<'a> {
+    /// The contact point on the first collider, in world-space.
+    pub fn point1(&self) -> Point<Real> {
+        self.subshape_pos1 * self.contact.local_p1
+    }
+
+    /// The contact point on the second collider, in world-space.
+    pub fn point2(&self) -> Point<Real> {
+        self.subshape_pos2 * self.contact.local_p2
+    }
+
+    /// The contact normal, in world-space, pointing from the first collider toward the second.
+    pub fn normal(&self) -> Vector<Real> {
+        self.subshape_pos1 * self.manifold.local_n1
+    }
+
+    /// The feature (face, edge, or vertex) of the first collider’s shape involved in this contact.
+    ///
+    /// For composite shapes, this is a feature of the sub-shape identified by
+    /// [`ContactManifold::subshape1`].
+    pub fn feature1(&self) -> FeatureId {
+        self.contact.fid1.unpack()
+    }
+
+    /// The feature (face, edge, or vertex) of the second collider’s shape involved in this contact.
+    ///
+    /// For composite shapes, this is a feature of the sub-shape identified by
+    /// [`ContactManifold::subshape2`].
+    pub fn feature2(&self) -> FeatureId {
+        self.contact.fid2.unpack()
+    }
+
+    /// The signed distance between the two contact points along the normal.
+    ///
+    /// If negative, this is the penetration depth.
+    pub fn dist(&self) -> Real {
+        self.contact.dist
+    }
+}
+
 /// A contact seen by the constraints solver for computing forces.
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -296,6 +534,13 @@ pub struct SolverContact {
     pub friction: Real,
     /// The effective restitution coefficient at this contact point.
     pub restitution: Real,
+    /// The relative normal velocity below which this contact doesn’t bounce.
+    pub restitution_velocity_threshold: Real,
+    /// The effective rolling friction coefficient at this contact point.
+    ///
+    /// The relative angular velocity of the two rigid-bodies is opposed by an angular impulse
+    /// bounded by this coefficient multiplied by the normal impulse at this contact.
+    pub rolling_friction: Real,
     /// The desired tangent relative velocity at the contact point.
     ///
     /// This is set to zero by default. Set to a non-zero value to
@@ -311,16 +556,14 @@ pub struct SolverContact {
 
 impl SolverContact {
     /// Should we treat this contact as a bouncy contact?
-    /// If `true`, use [`Self::restitution`].
-    pub fn is_bouncy(&self) -> bool {
-        if self.is_new {
-            // Treat new collisions as bouncing at first, unless we have zero restitution.
-            self.restitution > 0.0
-        } else {
-            // If the contact is still here one step later, it is now a resting contact.
-            // The exception is very high restitutions, which can never rest
-            self.restitution >= 1.0
-        }
+    ///
+    /// The `approach_velocity` is the relative velocity at which the two contact points move
+    /// toward each other along the contact normal. The contact bounces only if it has a non-zero
+    /// restitution and if the approach velocity exceeds [`Self::restitution_velocity_threshold`].
+    /// Otherwise, the contact is solved with a zero relative normal velocity so it can come to
+    /// rest. If `true`, use [`Self::restitution`].
+    pub fn is_bouncy(&self, approach_velocity: Real) -> bool {
+        self.restitution > 0.0 && approach_velocity > self.restitution_velocity_threshold
     }
 }
 
@@ -337,6 +580,8 @@ impl ContactManifoldData {
         solver_flags: SolverFlags,
     ) -> ContactManifoldData {
         Self {
+            collider1: ColliderHandle::invalid(),
+            collider2: ColliderHandle::invalid(),
             rigid_body1,
             rigid_body2,
             solver_flags,
@@ -359,10 +604,44 @@ impl ContactManifoldData {
 pub trait ContactManifoldExt {
     /// Computes the sum of all the impulses applied by contacts from this contact manifold.
     fn total_impulse(&self) -> Real;
+
+    /// The world-space contact normal pointing from `collider` toward the other collider.
+    ///
+    /// Returns `None` if `collider` isn’t involved in this contact manifold.
+    fn normal_wrt(&self, collider: ColliderHandle) -> Option<Vector<Real>>;
+
+    /// The feature (face, edge, or vertex) of `collider`’s shape involved in the `i`-th contact
+    /// of this manifold.
+    ///
+    /// For composite shapes, this is a feature of the sub-shape involved in this manifold.
+    /// Returns `None` if `collider` isn’t involved in this contact manifold or if there is no
+    /// `i`-th contact.
+    fn feature_wrt(&self, i: usize, collider: ColliderHandle) -> Option<FeatureId>;
 }
 
 impl ContactManifoldExt for ContactManifold {
     fn total_impulse(&self) -> Real {
         self.points.iter().map(|pt| pt.data.impulse).sum()
     }
+
+    fn normal_wrt(&self, collider: ColliderHandle) -> Option<Vector<Real>> {
+        if collider == self.data.collider1 {
+            Some(self.data.normal)
+        } else if collider == self.data.collider2 {
+            Some(-self.data.normal)
+        } else {
+            None
+        }
+    }
+
+    fn feature_wrt(&self, i: usize, collider: ColliderHandle) -> Option<FeatureId> {
+        let contact = self.points.get(i)?;
+        if collider == self.data.collider1 {
+            Some(contact.fid1.unpack())
+        } else if collider == self.data.collider2 {
+            Some(contact.fid2.unpack())
+        } else {
+            None
+        }
+    }
 }
@@ -0,0 +1,503 @@
+use crate::geometry::{Aabb, Ray, RayIntersection, Triangle};
+use crate::math::{Isometry, Point, Real};
+use na::RealField;
+use parry::bounding_volume::BoundingSphere;
+use parry::mass_properties::MassProperties;
+use parry::partitioning::{Qbvh, QbvhUpdateWorkspace};
+use parry::query::details::{
+    NormalConstraints, PointCompositeShapeProjBestFirstVisitor,
+    RayCompositeShapeToiAndNormalBestFirstVisitor, RayCompositeShapeToiBestFirstVisitor,
+};
+use parry::query::{PointProjection, PointQuery, RayCast};
+use parry::shape::{
+    FeatureId, Shape, ShapeType, SimdCompositeShape, TriMesh, TypedShape, TypedSimdCompositeShape,
+};
+
+/// A triangle mesh which vertices can be moved after its creation.
+///
+/// Contrary to [`TriMesh`], the positions of the vertices of this mesh can be modified in-place
+/// with [`DeformableTriMesh::set_vertices`] (as long as its topology doesn’t change). This only
+/// refits the bounding volume hierarchy of the mesh instead of rebuilding it. The vertices of a
+/// collider with this shape should be modified with [`Collider::set_trimesh_vertices`](crate::geometry::Collider::set_trimesh_vertices)
+/// so that its contacts are updated without losing their warmstarting data.
+///
+/// This is a custom shape from the point of view of `parry`: colliders with this shape cannot be
+/// deserialized.
+#[derive(Clone, Debug)]
+pub struct DeformableTriMesh {
+    vertices: Vec<Point<Real>>,
+    indices: Vec<[u32; 3]>,
+    aabb: Aabb,
+    qbvh: Qbvh<u32>,
+}
+
+impl DeformableTriMesh {
+    /// Creates a new deformable triangle mesh from a vertex buffer and an index buffer.
+    pub fn new(vertices: Vec<Point<Real>>, indices: Vec<[u32; 3]>) -> Self {
+        assert!(
+            !indices.is_empty(),
+            "A triangle mesh must contain at least one triangle."
+        );
+
+        let mut result = Self {
+            aabb: Aabb::from_points(&vertices),
+            vertices,
+            indices,
+            qbvh: Qbvh::new(),
+        };
+        result.rebuild_qbvh();
+        result
+    }
+
+    /// The vertex buffer of this mesh.
+    pub fn vertices(&self) -> &[Point<Real>] {
+        &self.vertices
+    }
+
+    /// The index buffer of this mesh.
+    pub fn indices(&self) -> &[[u32; 3]] {
+        &self.indices
+    }
+
+    /// The number of triangles of this mesh.
+    pub fn num_triangles(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// The `i`-th triangle of this mesh.
+    pub fn triangle(&self, i: u32) -> Triangle {
+        Self::triangle_of(&self.vertices, &self.indices, i)
+    }
+
+    /// An iterator through all the triangles of this mesh.
+    pub fn triangles(&self) -> impl ExactSizeIterator<Item = Triangle> + '_ {
+        (0..self.indices.len() as u32).map(|i| self.triangle(i))
+    }
+
+    /// Converts this mesh into a regular, non-deformable, [`TriMesh`].
+    pub fn to_trimesh(&self) -> TriMesh {
+        TriMesh::new(self.vertices.clone(), self.indices.clone())
+    }
+
+    /// Moves the vertices of this mesh, keeping its topology unchanged.
+    ///
+    /// The bounding volume hierarchy of this mesh is refitted bottom-up instead of being rebuilt.
+    ///
+    /// # Panics
+    /// Panics if `vertices` doesn’t contain as many elements as [`Self::vertices`].
+    pub fn set_vertices(&mut self, vertices: &[Point<Real>]) {
+        assert_eq!(
+            vertices.len(),
+            self.vertices.len(),
+            "The number of vertices of a deformable triangle mesh cannot change."
+        );
+        self.vertices.copy_from_slice(vertices);
+        self.aabb = Aabb::from_points(&self.vertices);
+
+        for i in 0..self.indices.len() as u32 {
+            self.qbvh.pre_update_or_insert(i);
+        }
+
+        let (vertices, indices) = (&self.vertices, &self.indices);
+        let mut workspace = QbvhUpdateWorkspace::default();
+        let _ = self.qbvh.refit(0.0, &mut workspace, |i| {
+            Self::triangle_of(vertices, indices, *i).local_aabb()
+        });
+    }
+
+    /// Rebuilds the bounding volume hierarchy of this mesh from scratch.
+    ///
+    /// Refitting keeps the topology of the hierarchy unchanged, so it may become less efficient
+    /// after large deformations. Calling this from time to time restores its quality.
+    pub fn rebuild_qbvh(&mut self) {
+        let leaves = (0..self.indices.len() as u32).map(|i| (i, self.triangle(i).local_aabb()));
+        let mut qbvh = Qbvh::new();
+        qbvh.clear_and_rebuild(leaves, 0.0);
+        self.qbvh = qbvh;
+    }
+
+    fn triangle_of(vertices: &[Point<Real>], indices: &[[u32; 3]], i: u32) -> Triangle {
+        let idx = indices[i as usize];
+        Triangle::new(
+            vertices[idx[0] as usize],
+            vertices[idx[1] as usize],
+            vertices[idx[2] as usize],
+        )
+    }
+}
+
+impl From<&TriMesh> for DeformableTriMesh {
+    fn from(trimesh: &TriMesh) -> Self {
+        Self::new(trimesh.vertices().to_vec(), trimesh.indices().to_vec())
+    }
+}
+
+impl TypedSimdCompositeShape for DeformableTriMesh {
+    type PartShape = Triangle;
+    type PartNormalConstraints = ();
+    type PartId = u32;
+
+    fn map_typed_part_at(
+        &self,
+        i: u32,
+        mut f: impl FnMut(Option<&Isometry<Real>>, &Triangle, Option<&()>),
+    ) {
+        if (i as usize) < self.indices.len() {
+            f(None, &self.triangle(i), None)
+        }
+    }
+
+    fn map_untyped_part_at(
+        &self,
+        i: u32,
+        mut f: impl FnMut(Option<&Isometry<Real>>, &dyn Shape, Option<&dyn NormalConstraints>),
+    ) {
+        if (i as usize) < self.indices.len() {
+            f(None, &self.triangle(i), None)
+        }
+    }
+
+    fn typed_qbvh(&self) -> &Qbvh<u32> {
+        &self.qbvh
+    }
+}
+
+impl SimdCompositeShape for DeformableTriMesh {
+    fn map_part_at(
+        &self,
+        i: u32,
+        f: &mut dyn FnMut(Option<&Isometry<Real>>, &dyn Shape, Option<&dyn NormalConstraints>),
+    ) {
+        self.map_untyped_part_at(i, f)
+    }
+
+    fn qbvh(&self) -> &Qbvh<u32> {
+        &self.qbvh
+    }
+}
+
+impl RayCast for DeformableTriMesh {
+    fn cast_local_ray(&self, ray: &Ray, max_toi: Real, solid: bool) -> Option<Real> {
+        let mut visitor = RayCompositeShapeToiBestFirstVisitor::new(self, ray, max_toi, solid);
+        self.qbvh
+            .traverse_best_first(&mut visitor)
+            .map(|(_, (_, toi))| toi)
+    }
+
+    fn cast_local_ray_and_get_normal(
+        &self,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        let mut visitor =
+            RayCompositeShapeToiAndNormalBestFirstVisitor::new(self, ray, max_toi, solid);
+        self.qbvh
+            .traverse_best_first(&mut visitor)
+            .map(|(_, (i, mut hit))| {
+                hit.feature = FeatureId::Face(i);
+                hit
+            })
+    }
+}
+
+impl PointQuery for DeformableTriMesh {
+    fn project_local_point(&self, point: &Point<Real>, solid: bool) -> PointProjection {
+        let mut visitor = PointCompositeShapeProjBestFirstVisitor::new(self, point, solid);
+        self.qbvh
+            .traverse_best_first(&mut visitor)
+            .map(|(_, (proj, _))| proj)
+            .unwrap_or_else(|| PointProjection::new(false, *point))
+    }
+
+    fn project_local_point_and_get_feature(
+        &self,
+        point: &Point<Real>,
+    ) -> (PointProjection, FeatureId) {
+        let mut visitor = PointCompositeShapeProjBestFirstVisitor::new(self, point, false);
+        self.qbvh
+            .traverse_best_first(&mut visitor)
+            .map(|(_, (proj, i))| (proj, FeatureId::Face(i)))
+            .unwrap_or_else(|| (PointProjection::new(false, *point), FeatureId::Unknown))
+    }
+}
+
+impl Shape for DeformableTriMesh {
+    fn compute_local_aabb(&self) -> Aabb {
+        self.aabb
+    }
+
+    fn compute_local_bounding_sphere(&self) -> BoundingSphere {
+        self.aabb.bounding_sphere()
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn mass_properties(&self, density: Real) -> MassProperties {
+        MassProperties::from_trimesh(density, &self.vertices, &self.indices)
+    }
+
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Custom
+    }
+
+    fn as_typed_shape(&self) -> TypedShape<'_> {
+        TypedShape::Custom(2)
+    }
+
+    fn ccd_thickness(&self) -> Real {
+        0.0
+    }
+
+    fn ccd_angular_thickness(&self) -> Real {
+        Real::frac_pi_4()
+    }
+
+    fn as_composite_shape(&self) -> Option<&dyn SimdCompositeShape> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DeformableTriMesh;
+    use crate::dynamics::{RigidBodyBuilder, RigidBodyHandle};
+    use crate::geometry::{ColliderBuilder, ColliderHandle, Ray, Shape};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::PhysicsWorld;
+    use parry::query::{PointQuery, RayCast};
+    use parry::shape::PackedFeatureId;
+    use parry::utils::IsometryOpt;
+
+    /// A flat grid of `n * n` quads (3D), or a strip of `n` quads (2D), with its top at `y = 0`.
+    fn grid(n: u32) -> (Vec<Point<Real>>, Vec<[u32; 3]>) {
+        let mut vertices = vec![];
+        let mut indices = vec![];
+        let width = 20.0;
+
+        #[cfg(feature = "dim2")]
+        {
+            for i in 0..=n {
+                let x = i as Real * width / n as Real - width / 2.0;
+                vertices.push(Point::new(x, 0.0));
+                vertices.push(Point::new(x, -1.0));
+            }
+
+            for i in 0..n {
+                indices.push([2 * i, 2 * i + 1, 2 * i + 2]);
+                indices.push([2 * i + 2, 2 * i + 1, 2 * i + 3]);
+            }
+        }
+
+        #[cfg(feature = "dim3")]
+        {
+            for i in 0..=n {
+                for j in 0..=n {
+                    let x = i as Real * width / n as Real - width / 2.0;
+                    let z = j as Real * width / n as Real - width / 2.0;
+                    vertices.push(Point::new(x, 0.0, z));
+                }
+            }
+
+            for i in 0..n {
+                for j in 0..n {
+                    let id = i * (n + 1) + j;
+                    indices.push([id, id + 1, id + n + 1]);
+                    indices.push([id + 1, id + n + 2, id + n + 1]);
+                }
+            }
+        }
+
+        (vertices, indices)
+    }
+
+    fn deformed(vertices: &[Point<Real>], t: Real) -> Vec<Point<Real>> {
+        vertices
+            .iter()
+            .map(|pt| pt + Vector::y() * (pt.x * 0.5 + t).sin())
+            .collect()
+    }
+
+    #[test]
+    fn refit_matches_rebuild() {
+        let (vertices, indices) = grid(16);
+        let mut refitted = DeformableTriMesh::new(vertices.clone(), indices.clone());
+
+        for t in [0.5, 1.0, 2.0] {
+            let new_vertices = deformed(&vertices, t);
+            refitted.set_vertices(&new_vertices);
+            let rebuilt = DeformableTriMesh::new(new_vertices, indices.clone());
+            assert_eq!(refitted.compute_local_aabb(), rebuilt.compute_local_aabb());
+
+            for i in -9..9 {
+                let origin = Point::from(Vector::x() * (i as Real + 0.25) + Vector::y() * 5.0);
+                let ray = Ray::new(origin, -Vector::y());
+                let toi1 = refitted.cast_local_ray(&ray, Real::MAX, true).unwrap();
+                let toi2 = rebuilt.cast_local_ray(&ray, Real::MAX, true).unwrap();
+                assert!((toi1 - toi2).abs() < 1.0e-5);
+
+                let proj1 = refitted.project_local_point(&origin, true);
+                let proj2 = rebuilt.project_local_point(&origin, true);
+                assert!((proj1.point - proj2.point).norm() < 1.0e-5);
+            }
+        }
+    }
+
+    fn box_on_deformable_ground() -> (PhysicsWorld, ColliderHandle, RigidBodyHandle) {
+        let mut world = PhysicsWorld::default();
+        let (vertices, indices) = grid(10);
+        let ground = world.insert_collider(ColliderBuilder::deformable_trimesh(vertices, indices));
+        let body = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        #[cfg(feature = "dim2")]
+        let collider = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let collider = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        world.insert_collider_with_parent(collider, body);
+
+        for _ in 0..20 {
+            world.step();
+        }
+
+        (world, ground, body)
+    }
+
+    fn total_impulse(world: &PhysicsWorld, ground: ColliderHandle) -> Real {
+        world
+            .narrow_phase
+            .contact_pairs_with(ground)
+            .flat_map(|pair| pair.manifolds.iter())
+            .flat_map(|manifold| manifold.points.iter())
+            .map(|pt| pt.data.impulse)
+            .sum()
+    }
+
+    #[test]
+    fn setting_vertices_preserves_contacts() {
+        let (mut reference, _, body) = box_on_deformable_ground();
+        let (mut world, ground, _) = box_on_deformable_ground();
+        let vertices = world.colliders[ground]
+            .shape()
+            .as_shape::<DeformableTriMesh>()
+            .unwrap()
+            .vertices()
+            .to_vec();
+
+        for _ in 0..10 {
+            world.colliders[ground].set_trimesh_vertices(&vertices);
+            world.step();
+            reference.step();
+
+            // Without warmstarting (e.g. if the shape was modified through `shape_mut`),
+            // the contact impulses would be about 20% off.
+            let impulse = total_impulse(&world, ground);
+            let reference_impulse = total_impulse(&reference, ground);
+            assert!(reference_impulse > 0.0);
+            assert!((impulse - reference_impulse).abs() < reference_impulse * 0.05);
+        }
+
+        let pos = world.bodies()[body].position();
+        let reference_pos = reference.bodies()[body].position();
+        assert!((pos.translation.vector - reference_pos.translation.vector).norm() < 1.0e-4);
+    }
+
+    /// The contact points of the ground, with their sub-shape and feature ids, their age, and their
+    /// world-space position on the ground.
+    fn ground_contacts(
+        world: &PhysicsWorld,
+        ground: ColliderHandle,
+    ) -> Vec<(u32, u32, PackedFeatureId, PackedFeatureId, u32, Point<Real>)> {
+        let mut result = vec![];
+        for pair in world.narrow_phase.contact_pairs_with(ground) {
+            assert_eq!(pair.collider1, ground);
+            let ground_pos = world.colliders[ground].position();
+            for manifold in &pair.manifolds {
+                let pos = manifold.subshape_pos1.prepend_to(ground_pos);
+                result.extend(manifold.points.iter().map(|pt| {
+                    (
+                        manifold.subshape1,
+                        manifold.subshape2,
+                        pt.fid1,
+                        pt.fid2,
+                        pt.data.age,
+                        pos * pt.local_p1,
+                    )
+                }));
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn contacts_follow_the_deformed_vertices_and_stay_warmstarted() {
+        let (mut world, ground, _) = box_on_deformable_ground();
+        let mesh = world.colliders[ground]
+            .shape()
+            .as_shape::<DeformableTriMesh>()
+            .unwrap()
+            .clone();
+        let raised: Vec<_> = mesh
+            .vertices()
+            .iter()
+            .map(|pt| pt + Vector::y() * 0.01)
+            .collect();
+        let raised_mesh = DeformableTriMesh::new(raised.clone(), mesh.indices().to_vec());
+        let contacts = ground_contacts(&world, ground);
+
+        world.colliders[ground].set_trimesh_vertices(&raised);
+        world.step();
+
+        let deformed_contacts = ground_contacts(&world, ground);
+        assert!(!deformed_contacts.is_empty());
+        let mut num_kept = 0;
+        for (subshape1, subshape2, fid1, fid2, age, pt) in deformed_contacts {
+            // The contact points lie on the raised mesh, not where it used to be.
+            let new_dist = raised_mesh.distance_to_local_point(&pt, false).abs();
+            let old_dist = mesh.distance_to_local_point(&pt, false).abs();
+            assert!(new_dist < 1.0e-4, "{new_dist}");
+            assert!(old_dist > 5.0e-3, "{old_dist}");
+
+            // The points between the same features as before the deformation kept their data, so
+            // they are still warmstarted.
+            if let Some(old) = contacts
+                .iter()
+                .find(|old| (old.0, old.1, old.2, old.3) == (subshape1, subshape2, fid1, fid2))
+            {
+                assert!(age > old.4, "{age}");
+                num_kept += 1;
+            }
+        }
+        assert!(num_kept > 0);
+    }
+
+    #[test]
+    fn deforming_the_ground_moves_resting_bodies() {
+        let (mut world, ground, body) = box_on_deformable_ground();
+        let vertices = world.colliders[ground]
+            .shape()
+            .as_shape::<DeformableTriMesh>()
+            .unwrap()
+            .vertices()
+            .to_vec();
+
+        for i in 1..=20 {
+            let raised: Vec<_> = vertices
+                .iter()
+                .map(|pt| pt + Vector::y() * 0.01 * i as Real)
+                .collect();
+            world.colliders[ground].set_trimesh_vertices(&raised);
+            world.step();
+        }
+
+        for _ in 0..20 {
+            world.step();
+        }
+
+        let y = world.bodies()[body].translation().y;
+        assert!(
+            (y - 0.7).abs() < 0.01,
+            "the box should follow the ground: {y}"
+        );
+    }
+}
@@ -33,6 +33,11 @@ impl<N: Copy, E> InteractionGraph<N, E> {
         &self.graph
     }
 
+    pub(crate) fn clear(&mut self) {
+        self.graph.nodes.clear();
+        self.graph.edges.clear();
+    }
+
     pub(crate) fn invalid_graph_index() -> ColliderGraphIndex {
         ColliderGraphIndex::new(crate::INVALID_U32)
     }
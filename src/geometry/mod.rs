@@ -1,10 +1,13 @@
 //! Structures related to geometry: colliders, shapes, etc.
 
 pub use self::broad_phase::BroadPhase;
+pub use self::broad_phase_hash_grid::BroadPhaseHashGrid;
 pub use self::broad_phase_multi_sap::{BroadPhaseMultiSap, BroadPhasePairEvent, ColliderPair};
 pub use self::collider_components::*;
 pub use self::contact_pair::{
-    ContactData, ContactManifoldData, ContactPair, IntersectionPair, SolverContact, SolverFlags,
+    ContactData, ContactManifoldData, ContactManifoldExt, ContactPair, ContactPairId,
+    ContactTransition, ContactView, IntersectionPair, IntersectionTransition, SolverContact,
+    SolverFlags,
 };
 pub use self::interaction_graph::{
     ColliderGraphIndex, InteractionGraph, RigidBodyGraphIndex, TemporaryInteractionIndex,
@@ -13,11 +16,16 @@ pub use self::interaction_groups::{Group, InteractionGroups};
 pub use self::narrow_phase::NarrowPhase;
 
 pub use self::collider::{Collider, ColliderBuilder};
-pub use self::collider_set::ColliderSet;
+pub use self::collider_set::{ColliderSet, RemovedCollider};
+pub use self::deformable_trimesh::DeformableTriMesh;
+pub use self::shape_ext::ShapeExt;
+pub use self::shape_scaling::SharedShapeExt;
+pub use self::support_map_fallback_dispatcher::SupportMapFallbackDispatcher;
+pub use self::voxels::Voxels;
 
 pub use parry::bounding_volume::BoundingVolume;
 pub use parry::query::{PointQuery, PointQueryWithLocation, RayCast, TrackedContact};
-pub use parry::shape::SharedShape;
+pub use parry::shape::{FeatureId, SharedShape};
 
 use crate::math::{Real, Vector};
 
@@ -37,6 +45,9 @@ pub type Ball = parry::shape::Ball;
 pub type Capsule = parry::shape::Capsule;
 /// A heightfield shape.
 pub type HeightField = parry::shape::HeightField;
+/// The status of the cell of a heightfield, indicating which of its triangles are removed.
+#[cfg(feature = "dim3")]
+pub type HeightFieldCellStatus = parry::shape::HeightFieldCellStatus;
 /// A cylindrical shape.
 #[cfg(feature = "dim3")]
 pub type Cylinder = parry::shape::Cylinder;
@@ -114,6 +125,10 @@ impl CollisionEvent {
     }
 
     /// Was at least one of the colliders involved in the collision removed?
+    ///
+    /// The handle of a collider removed from its set is no longer valid, but
+    /// [`ColliderSet::removed_collider`] still gives access to its user-data, parent and last
+    /// position. Colliders that were only disabled can still be accessed with [`ColliderSet::get`].
     pub fn removed(self) -> bool {
         match self {
             Self::Started(_, _, f) | Self::Stopped(_, _, f) => {
@@ -123,6 +138,45 @@ impl CollisionEvent {
     }
 }
 
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+/// Events occurring when the bounding volumes of two colliders start or stop overlapping.
+///
+/// These are only generated for colliders with the
+/// [`ActiveEvents::BROAD_PHASE_EVENTS`](crate::pipeline::ActiveEvents::BROAD_PHASE_EVENTS) flag set.
+pub enum BroadPhaseEvent {
+    /// Event occurring when the bounding volumes of two colliders start overlapping.
+    PairStarted(ColliderHandle, ColliderHandle),
+    /// Event occurring when the bounding volumes of two colliders stop overlapping.
+    PairStopped(ColliderHandle, ColliderHandle),
+}
+
+impl BroadPhaseEvent {
+    /// Is this a `PairStarted` event?
+    pub fn started(self) -> bool {
+        matches!(self, BroadPhaseEvent::PairStarted(..))
+    }
+
+    /// Is this a `PairStopped` event?
+    pub fn stopped(self) -> bool {
+        matches!(self, BroadPhaseEvent::PairStopped(..))
+    }
+
+    /// The handle of the first collider involved in this event.
+    pub fn collider1(self) -> ColliderHandle {
+        match self {
+            Self::PairStarted(h, _) | Self::PairStopped(h, _) => h,
+        }
+    }
+
+    /// The handle of the second collider involved in this event.
+    pub fn collider2(self) -> ColliderHandle {
+        match self {
+            Self::PairStarted(_, h) | Self::PairStopped(_, h) => h,
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Debug, Default)]
 /// Event occurring when the sum of the magnitudes of the contact forces
 /// between two colliders exceed a threshold.
@@ -143,6 +197,14 @@ pub struct ContactForceEvent {
     pub max_force_direction: Vector<Real>,
     /// The magnitude of the largest force at a contact point of this contact pair.
     pub max_force_magnitude: Real,
+    /// The identifier of the contact pair this event was generated for.
+    pub pair_id: ContactPairId,
+    /// The [`PhysicsPipeline::step_id`](crate::pipeline::PhysicsPipeline::step_id) of the step
+    /// during which the forces were applied.
+    ///
+    /// This is set by [`ChannelEventCollector`](crate::pipeline::ChannelEventCollector), but left
+    /// to zero by [`Self::from_contact_pair`].
+    pub step_id: u64,
 }
 
 impl ContactForceEvent {
@@ -152,6 +214,7 @@ impl ContactForceEvent {
             collider1: pair.collider1,
             collider2: pair.collider2,
             total_force_magnitude,
+            pair_id: pair.id,
             ..ContactForceEvent::default()
         };
 
@@ -188,7 +251,7 @@ pub use parry::shape::*;
 #[cfg(feature = "serde-serialize")]
 pub(crate) fn default_persistent_query_dispatcher(
 ) -> std::sync::Arc<dyn parry::query::PersistentQueryDispatcher<ContactManifoldData, ContactData>> {
-    std::sync::Arc::new(parry::query::DefaultQueryDispatcher)
+    std::sync::Arc::new(SupportMapFallbackDispatcher)
 }
 
 #[cfg(feature = "serde-serialize")]
@@ -204,6 +267,12 @@ mod interaction_groups;
 mod narrow_phase;
 
 mod broad_phase;
+mod broad_phase_hash_grid;
 mod broad_phase_qbvh;
 mod collider;
 mod collider_set;
+mod deformable_trimesh;
+mod shape_ext;
+mod shape_scaling;
+mod support_map_fallback_dispatcher;
+mod voxels;
@@ -2,26 +2,28 @@
 use rayon::prelude::*;
 
 use crate::data::graph::EdgeIndex;
-use crate::data::Coarena;
+use crate::data::{Arena, Coarena};
 use crate::dynamics::{
-    CoefficientCombineRule, ImpulseJointSet, IslandManager, RigidBodyDominance, RigidBodySet,
-    RigidBodyType,
+    CoefficientCombineRule, ImpulseJointSet, IslandManager, RigidBody, RigidBodyChanges,
+    RigidBodyDominance, RigidBodyHandle, RigidBodySet, RigidBodyType,
 };
 use crate::geometry::{
-    BoundingVolume, BroadPhasePairEvent, ColliderChanges, ColliderGraphIndex, ColliderHandle,
-    ColliderPair, ColliderSet, CollisionEvent, ContactData, ContactManifold, ContactManifoldData,
-    ContactPair, InteractionGraph, IntersectionPair, SolverContact, SolverFlags,
-    TemporaryInteractionIndex,
+    BoundingVolume, BroadPhaseEvent, BroadPhasePairEvent, Collider, ColliderChanges,
+    ColliderGraphIndex, ColliderHandle, ColliderPair, ColliderSet, CollisionEvent, Contact,
+    ContactData, ContactManifold, ContactManifoldData, ContactPair, ContactPairId,
+    ContactTransition, InteractionGraph, IntersectionPair, IntersectionTransition, Shape,
+    ShapeType, SolverContact, SolverFlags, SupportMapFallbackDispatcher, TemporaryInteractionIndex,
 };
-use crate::math::{Real, Vector};
+use crate::math::{Isometry, Real, UnitVector, Vector, DEFAULT_EPSILON};
 use crate::pipeline::{
     ActiveEvents, ActiveHooks, ContactModificationContext, EventHandler, PairFilterContext,
     PhysicsHooks,
 };
 use crate::prelude::{CollisionEventFlags, MultibodyJointSet};
-use parry::query::{DefaultQueryDispatcher, PersistentQueryDispatcher};
+use parry::query::PersistentQueryDispatcher;
 use parry::utils::IsometryOpt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -40,6 +42,59 @@ impl ColliderGraphIndices {
     }
 }
 
+/// The contact and intersection pairs that changed state during the last step.
+#[derive(Clone, Default)]
+struct StepTransitions {
+    contacts_started: Vec<ContactTransition>,
+    contacts_stopped: Vec<ContactTransition>,
+    proximities_changed: Vec<IntersectionTransition>,
+}
+
+impl StepTransitions {
+    fn clear(&mut self) {
+        self.contacts_started.clear();
+        self.contacts_stopped.clear();
+        self.proximities_changed.clear();
+    }
+
+    fn record_contact(
+        &mut self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+        started: bool,
+        has_any_active_contact: bool,
+        removed: bool,
+    ) {
+        let transition = ContactTransition {
+            collider1,
+            collider2,
+            has_any_active_contact,
+            removed,
+        };
+
+        if started {
+            self.contacts_started.push(transition);
+        } else {
+            self.contacts_stopped.push(transition);
+        }
+    }
+
+    fn record_intersection(
+        &mut self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+        intersecting: bool,
+        removed: bool,
+    ) {
+        self.proximities_changed.push(IntersectionTransition {
+            collider1,
+            collider2,
+            intersecting,
+            removed,
+        });
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 enum PairRemovalMode {
     FromContactGraph,
@@ -57,8 +112,19 @@ pub struct NarrowPhase {
     )]
     query_dispatcher: Arc<dyn PersistentQueryDispatcher<ContactManifoldData, ContactData>>,
     contact_graph: InteractionGraph<ColliderHandle, ContactPair>,
+    // Maps the stable id of each contact pair to its current edge in the contact graph.
+    contact_pair_ids: Arena<TemporaryInteractionIndex>,
     intersection_graph: InteractionGraph<ColliderHandle, IntersectionPair>,
     graph_indices: Coarena<ColliderGraphIndices>,
+    collision_exceptions: HashSet<(RigidBodyHandle, RigidBodyHandle)>,
+    // The pairs of colliders, with at least one generating broad-phase events, that
+    // were reported as overlapping by the broad-phase.
+    broad_phase_event_pairs: HashSet<(ColliderHandle, ColliderHandle)>,
+    // The subset of `broad_phase_event_pairs` the broad-phase no longer reports but
+    // that are still within the hysteresis margin.
+    separating_broad_phase_event_pairs: HashSet<(ColliderHandle, ColliderHandle)>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    step_transitions: StepTransitions,
 }
 
 pub(crate) type ContactManifoldIndex = usize;
@@ -72,7 +138,7 @@ impl Default for NarrowPhase {
 impl NarrowPhase {
     /// Creates a new empty narrow-phase.
     pub fn new() -> Self {
-        Self::with_query_dispatcher(DefaultQueryDispatcher)
+        Self::with_query_dispatcher(SupportMapFallbackDispatcher)
     }
 
     /// Creates a new empty narrow-phase with a custom query dispatcher.
@@ -83,11 +149,33 @@ impl NarrowPhase {
         Self {
             query_dispatcher: Arc::new(d),
             contact_graph: InteractionGraph::new(),
+            contact_pair_ids: Arena::new(),
             intersection_graph: InteractionGraph::new(),
             graph_indices: Coarena::new(),
+            collision_exceptions: HashSet::new(),
+            broad_phase_event_pairs: HashSet::new(),
+            separating_broad_phase_event_pairs: HashSet::new(),
+            step_transitions: StepTransitions::default(),
         }
     }
 
+    /// Removes every contact and intersection pair, as well as every collision exception, from
+    /// this narrow-phase, without generating any event.
+    ///
+    /// This is meant for emptying a whole world in-place, together with the
+    /// [`ColliderSet::clear`] and [`BroadPhase::clear`](crate::geometry::BroadPhase::clear)
+    /// methods.
+    pub fn clear(&mut self) {
+        self.contact_graph.clear();
+        self.contact_pair_ids.clear();
+        self.intersection_graph.clear();
+        self.graph_indices.clear();
+        self.collision_exceptions.clear();
+        self.broad_phase_event_pairs.clear();
+        self.separating_broad_phase_event_pairs.clear();
+        self.step_transitions.clear();
+    }
+
     /// The query dispatcher used by this narrow-phase to select the right collision-detection
     /// algorithms depending of the shape types.
     pub fn query_dispatcher(
@@ -96,6 +184,84 @@ impl NarrowPhase {
         &*self.query_dispatcher
     }
 
+    /// The pairs of built-in shape types the default query dispatcher can generate contacts for.
+    ///
+    /// Each unordered pair is listed once. Contacts between the pairs of built-in shapes missing
+    /// from this list are never generated, and a warning is logged in debug builds the first time
+    /// such a pair is encountered. Custom shapes are supported if they are composite shapes or
+    /// support-mapped shapes (see [`SupportMapFallbackDispatcher`]), otherwise the same warning
+    /// is logged.
+    pub fn supported_pairs() -> Vec<(ShapeType, ShapeType)> {
+        use super::support_map_fallback_dispatcher::{is_supported_pair, BUILTIN_SHAPE_TYPES};
+
+        let mut pairs = vec![];
+        for (i, type1) in BUILTIN_SHAPE_TYPES.iter().enumerate() {
+            for type2 in &BUILTIN_SHAPE_TYPES[i..] {
+                if is_supported_pair(*type1, *type2) {
+                    pairs.push((*type1, *type2));
+                }
+            }
+        }
+        pairs
+    }
+
+    fn collision_exception_key(
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+    ) -> (RigidBodyHandle, RigidBodyHandle) {
+        if body1.0 <= body2.0 {
+            (body1, body2)
+        } else {
+            (body2, body1)
+        }
+    }
+
+    /// Prevents any contact or intersection from being computed between the colliders attached
+    /// to `body1` and the colliders attached to `body2`.
+    ///
+    /// This is useful for disabling collisions between two specific rigid-bodies without
+    /// having to dedicate a collision group to them. The exception is automatically removed
+    /// when either rigid-body is removed from its set. It applies the next time the contacts
+    /// between these bodies are updated.
+    ///
+    /// Returns `false` if this exception already existed.
+    pub fn add_collision_exception(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+    ) -> bool {
+        self.collision_exceptions
+            .insert(Self::collision_exception_key(body1, body2))
+    }
+
+    /// Removes the collision exception between `body1` and `body2`, previously added with
+    /// [`NarrowPhase::add_collision_exception`].
+    ///
+    /// Returns `false` if there was no such exception.
+    pub fn remove_collision_exception(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+    ) -> bool {
+        self.collision_exceptions
+            .remove(&Self::collision_exception_key(body1, body2))
+    }
+
+    /// Is there a collision exception between `body1` and `body2`?
+    pub fn has_collision_exception(&self, body1: RigidBodyHandle, body2: RigidBodyHandle) -> bool {
+        !self.collision_exceptions.is_empty()
+            && self
+                .collision_exceptions
+                .contains(&Self::collision_exception_key(body1, body2))
+    }
+
+    /// All the collision exceptions registered in this narrow-phase.
+    pub fn collision_exceptions(
+        &self,
+    ) -> impl ExactSizeIterator<Item = (RigidBodyHandle, RigidBodyHandle)> + '_ {
+        self.collision_exceptions.iter().copied()
+    }
+
     /// The contact graph containing all contact pairs and their contact information.
     pub fn contact_graph(&self) -> &InteractionGraph<ColliderHandle, ContactPair> {
         &self.contact_graph
@@ -139,6 +305,48 @@ impl NarrowPhase {
             .map(|pair| pair.2)
     }
 
+    /// All the rigid-bodies touching the given rigid-body.
+    ///
+    /// Only the contact pairs with at least one active contact point are taken into account.
+    /// A rigid-body touching `body` through several pairs of colliders is yielded once per pair.
+    /// See [`crate::utils::connected_component`] to find all the rigid-bodies transitively
+    /// touching `body`.
+    pub fn bodies_in_contact_with<'a>(
+        &'a self,
+        body: RigidBodyHandle,
+        bodies: &'a RigidBodySet,
+        colliders: &'a ColliderSet,
+    ) -> impl Iterator<Item = RigidBodyHandle> + 'a {
+        self.bodies_interacting_with(body, bodies, colliders, true)
+    }
+
+    /// All the rigid-bodies sharing a contact pair with the given rigid-body.
+    ///
+    /// If `only_active_contacts` is `false`, this includes pairs of colliders with overlapping
+    /// bounding-volumes but no actual contact.
+    pub(crate) fn bodies_interacting_with<'a>(
+        &'a self,
+        body: RigidBodyHandle,
+        bodies: &'a RigidBodySet,
+        colliders: &'a ColliderSet,
+        only_active_contacts: bool,
+    ) -> impl Iterator<Item = RigidBodyHandle> + 'a {
+        bodies
+            .get(body)
+            .into_iter()
+            .flat_map(|rb| rb.colliders())
+            .flat_map(move |collider| {
+                self.contact_pairs_with(*collider)
+                    .filter(move |pair| !only_active_contacts || pair.has_any_active_contact)
+                    .filter_map(move |pair| {
+                        let other =
+                            crate::utils::select_other((pair.collider1, pair.collider2), *collider);
+                        colliders.get(other)?.parent()
+                    })
+            })
+            .filter(move |other| *other != body)
+    }
+
     /// All the intersection pairs involving the given collider.
     ///
     /// It is strongly recommended to use the [`NarrowPhase::intersections_with`]  method instead.
@@ -184,6 +392,16 @@ impl NarrowPhase {
         &self.contact_graph.graph.edges[id.index()].weight
     }
 
+    /// The contact pair with the given stable identifier.
+    ///
+    /// This is an O(1) lookup. The identifier is invalidated as soon as the contact pair is
+    /// destroyed, in which case this returns `None`.
+    pub fn contact_pair_by_id(&self, id: ContactPairId) -> Option<&ContactPair> {
+        let edge = self.contact_pair_ids.get(id.0)?;
+        let pair = &self.contact_graph.graph.edges.get(edge.index())?.weight;
+        (pair.id == id).then_some(pair)
+    }
+
     /// The contact pair involving two specific colliders.
     ///
     /// It is strongly recommended to use the [`NarrowPhase::contact_pair`] method instead. This
@@ -262,6 +480,88 @@ impl NarrowPhase {
             .map(|e| (e.0, e.1, e.2.intersecting))
     }
 
+    /// The contact pairs that started touching during the last step.
+    ///
+    /// This is the polling counterpart of the `CollisionEvent::Started` events (without the
+    /// `SENSOR` flag) received by the [`EventHandler`], in the same order: it only lists pairs
+    /// involving at least one collider with [`ActiveEvents::COLLISION_EVENTS`] enabled. This
+    /// list is valid until the next step, and cleared automatically at its start.
+    pub fn contacts_started(&self) -> impl ExactSizeIterator<Item = &ContactTransition> {
+        self.step_transitions.contacts_started.iter()
+    }
+
+    /// The contact pairs that stopped touching during the last step, including the ones removed
+    /// with one of their colliders.
+    ///
+    /// This is the polling counterpart of the `CollisionEvent::Stopped` events (without the
+    /// `SENSOR` flag) received by the [`EventHandler`]. See [`Self::contacts_started`].
+    pub fn contacts_stopped(&self) -> impl ExactSizeIterator<Item = &ContactTransition> {
+        self.step_transitions.contacts_stopped.iter()
+    }
+
+    /// The intersection pairs (involving sensors) that started or stopped intersecting during
+    /// the last step.
+    ///
+    /// This is the polling counterpart of the collision events with the `SENSOR` flag received
+    /// by the [`EventHandler`], in the same order, except for the pairs of events the CCD solver
+    /// emits for sensors crossed entirely within a single step. See [`Self::contacts_started`].
+    pub fn proximities_changed(&self) -> impl ExactSizeIterator<Item = &IntersectionTransition> {
+        self.step_transitions.proximities_changed.iter()
+    }
+
+    /// Clears the lists of pairs that changed state during the last step.
+    ///
+    /// This is called by the pipelines at the start of each step.
+    pub(crate) fn clear_step_transitions(&mut self) {
+        self.step_transitions.clear();
+    }
+
+    /// Resets the impulses cached by the contacts involving the given collider, so they aren’t
+    /// used to warmstart the next simulation step.
+    ///
+    /// The geometric data of the contact manifolds is kept. Teleporting a collider resets the
+    /// cached impulses of its contacts automatically, unless the colliders it touches are
+    /// teleported with it. Call this after such scripted changes (e.g. a whole stack moved by a
+    /// cutscene, or stopped abruptly) so the impulses that held the bodies in their previous
+    /// configuration don’t cause ghost forces. This isn’t needed during a regular simulation:
+    /// cached impulses make stacks converge faster.
+    ///
+    /// The impulses of joints aren’t affected, they can be reset through
+    /// [`ImpulseJoint::impulses`](crate::dynamics::ImpulseJoint::impulses).
+    pub fn clear_cached_impulses_for(&mut self, collider: ColliderHandle) {
+        let Some(id) = self.graph_indices.get(collider.0) else {
+            return;
+        };
+
+        for (_, _, _, pair) in self
+            .contact_graph
+            .interactions_with_mut(id.contact_graph_index)
+        {
+            pair.clear_warmstart();
+        }
+    }
+
+    /// Resets the impulses cached by the contacts involving any collider attached to the given
+    /// rigid-body.
+    ///
+    /// See [`Self::clear_cached_impulses_for`] for details.
+    pub fn clear_cached_impulses_for_body(&mut self, body: RigidBodyHandle, bodies: &RigidBodySet) {
+        if let Some(rb) = bodies.get(body) {
+            for collider in rb.colliders() {
+                self.clear_cached_impulses_for(*collider);
+            }
+        }
+    }
+
+    /// Resets the impulses cached by all the contacts of this narrow-phase.
+    ///
+    /// See [`Self::clear_cached_impulses_for`] for details.
+    pub fn clear_all_cached_impulses(&mut self) {
+        for edge in &mut self.contact_graph.graph.edges {
+            edge.weight.clear_warmstart();
+        }
+    }
+
     // #[cfg(feature = "parallel")]
     // pub(crate) fn contact_pairs_vec_mut(&mut self) -> &mut Vec<ContactPair> {
     //     &mut self.contact_graph.interactions
@@ -276,6 +576,7 @@ impl NarrowPhase {
         colliders: &mut ColliderSet,
         bodies: &mut RigidBodySet,
         events: &dyn EventHandler,
+        step_id: u64,
     ) {
         // TODO: avoid these hash-maps.
         // They are necessary to handle the swap-remove done internally
@@ -283,6 +584,12 @@ impl NarrowPhase {
         let mut prox_id_remap = HashMap::new();
         let mut contact_id_remap = HashMap::new();
 
+        // Forget about the collision exceptions involving removed rigid-bodies.
+        if !self.collision_exceptions.is_empty() {
+            self.collision_exceptions
+                .retain(|(body1, body2)| bodies.contains(*body1) && bodies.contains(*body2));
+        }
+
         for collider in removed_colliders {
             // NOTE: if the collider does not have any graph indices currently, there is nothing
             // to remove in the narrow-phase for this collider.
@@ -308,6 +615,7 @@ impl NarrowPhase {
                     &mut prox_id_remap,
                     &mut contact_id_remap,
                     events,
+                    step_id,
                 );
             }
         }
@@ -318,6 +626,7 @@ impl NarrowPhase {
             colliders,
             bodies,
             events,
+            step_id,
         );
     }
 
@@ -325,15 +634,16 @@ impl NarrowPhase {
         &mut self,
         intersection_graph_id: ColliderGraphIndex,
         contact_graph_id: ColliderGraphIndex,
-        islands: Option<&mut IslandManager>,
+        mut islands: Option<&mut IslandManager>,
         colliders: &mut ColliderSet,
         bodies: &mut RigidBodySet,
         prox_id_remap: &mut HashMap<ColliderHandle, ColliderGraphIndex>,
         contact_id_remap: &mut HashMap<ColliderHandle, ColliderGraphIndex>,
         events: &dyn EventHandler,
+        step_id: u64,
     ) {
         // Wake up every body in contact with the deleted collider and generate Stopped collision events.
-        if let Some(islands) = islands {
+        if let Some(islands) = islands.as_deref_mut() {
             for (a, b, pair) in self.contact_graph.interactions_with(contact_graph_id) {
                 if let Some(parent) = colliders.get(a).and_then(|c| c.parent.as_ref()) {
                     islands.wake_up(bodies, parent.handle, true)
@@ -345,11 +655,14 @@ impl NarrowPhase {
 
                 if pair.start_event_emited {
                     events.handle_collision_event(
+                        step_id,
                         bodies,
                         colliders,
                         CollisionEvent::Stopped(a, b, CollisionEventFlags::REMOVED),
                         Some(pair),
                     );
+                    self.step_transitions
+                        .record_contact(a, b, false, false, true);
                 }
             }
         } else {
@@ -357,22 +670,39 @@ impl NarrowPhase {
             for (a, b, pair) in self.contact_graph.interactions_with(contact_graph_id) {
                 if pair.start_event_emited {
                     events.handle_collision_event(
+                        step_id,
                         bodies,
                         colliders,
                         CollisionEvent::Stopped(a, b, CollisionEventFlags::REMOVED),
                         Some(pair),
                     );
+                    self.step_transitions
+                        .record_contact(a, b, false, false, true);
                 }
             }
         }
 
-        // Generate Stopped collision events for intersections.
+        // Wake up every body intersecting the deleted collider and generate Stopped collision
+        // events for intersections.
         for (a, b, pair) in self
             .intersection_graph
             .interactions_with(intersection_graph_id)
         {
+            if let Some(islands) = islands.as_deref_mut() {
+                if pair.intersecting {
+                    if let Some(parent) = colliders.get(a).and_then(|c| c.parent.as_ref()) {
+                        islands.wake_up(bodies, parent.handle, true)
+                    }
+
+                    if let Some(parent) = colliders.get(b).and_then(|c| c.parent.as_ref()) {
+                        islands.wake_up(bodies, parent.handle, true)
+                    }
+                }
+            }
+
             if pair.start_event_emited {
                 events.handle_collision_event(
+                    step_id,
                     bodies,
                     colliders,
                     CollisionEvent::Stopped(
@@ -382,6 +712,7 @@ impl NarrowPhase {
                     ),
                     None,
                 );
+                self.step_transitions.record_intersection(a, b, false, true);
             }
         }
 
@@ -399,6 +730,16 @@ impl NarrowPhase {
             }
         }
 
+        // Remove the contact pairs one by one so their ids stay in sync with the contact graph.
+        let removed_ids: Vec<_> = self
+            .contact_graph
+            .interactions_with(contact_graph_id)
+            .map(|(_, _, pair)| pair.id)
+            .collect();
+        for id in removed_ids {
+            let _ = self.remove_contact_pair_by_id(id);
+        }
+
         if let Some(replacement) = self.contact_graph.remove_node(contact_graph_id) {
             if let Some(replacement) = self.graph_indices.get_mut(replacement.0) {
                 replacement.contact_graph_index = contact_graph_id;
@@ -419,6 +760,7 @@ impl NarrowPhase {
         colliders: &ColliderSet,
         bodies: &mut RigidBodySet,
         events: &dyn EventHandler,
+        step_id: u64,
     ) {
         let mut pairs_to_remove = vec![];
 
@@ -503,6 +845,7 @@ impl NarrowPhase {
                 bodies,
                 &pair.0,
                 events,
+                step_id,
                 pair.1,
             );
         }
@@ -520,6 +863,7 @@ impl NarrowPhase {
         bodies: &mut RigidBodySet,
         pair: &ColliderPair,
         events: &dyn EventHandler,
+        step_id: u64,
         mode: PairRemovalMode,
     ) {
         if let (Some(co1), Some(co2)) =
@@ -538,28 +882,36 @@ impl NarrowPhase {
                         .intersection_graph
                         .remove_edge(gid1.intersection_graph_index, gid2.intersection_graph_index);
 
-                    // Emit an intersection lost event if we had an intersection before removing the edge.
+                    // Emit an intersection lost event if an intersection started event was
+                    // emitted before removing the edge.
                     if let Some(mut intersection) = intersection {
-                        if intersection.intersecting
-                            && (co1.flags.active_events | co2.flags.active_events)
-                                .contains(ActiveEvents::COLLISION_EVENTS)
-                        {
+                        if intersection.start_event_emited {
                             intersection.emit_stop_event(
                                 bodies,
                                 colliders,
                                 pair.collider1,
                                 pair.collider2,
                                 events,
-                            )
+                                step_id,
+                            );
+                            self.step_transitions.record_intersection(
+                                pair.collider1,
+                                pair.collider2,
+                                false,
+                                false,
+                            );
                         }
                     }
                 } else {
                     let contact_pair = self
                         .contact_graph
-                        .remove_edge(gid1.contact_graph_index, gid2.contact_graph_index);
+                        .interaction_pair(gid1.contact_graph_index, gid2.contact_graph_index)
+                        .map(|(_, _, pair)| pair.id);
+                    let contact_pair =
+                        contact_pair.and_then(|id| self.remove_contact_pair_by_id(id));
 
-                    // Emit a contact stopped event if we had a contact before removing the edge.
-                    // Also wake up the dynamic bodies that were in contact.
+                    // Wake up the dynamic bodies that were in contact, and emit a contact stopped
+                    // event if a contact started event was emitted before removing the edge.
                     if let Some(mut ctct) = contact_pair {
                         if ctct.has_any_active_contact {
                             if let Some(islands) = islands {
@@ -571,12 +923,17 @@ impl NarrowPhase {
                                     islands.wake_up(bodies, co_parent2.handle, true);
                                 }
                             }
+                        }
 
-                            if (co1.flags.active_events | co2.flags.active_events)
-                                .contains(ActiveEvents::COLLISION_EVENTS)
-                            {
-                                ctct.emit_stop_event(bodies, colliders, events);
-                            }
+                        if ctct.start_event_emited {
+                            ctct.emit_stop_event(bodies, colliders, events, step_id);
+                            self.step_transitions.record_contact(
+                                ctct.collider1,
+                                ctct.collider2,
+                                false,
+                                false,
+                                false,
+                            );
                         }
                     }
                 }
@@ -584,17 +941,35 @@ impl NarrowPhase {
         }
     }
 
+    /// Removes the contact pair with the given id from the contact graph, keeping the ids of
+    /// the other pairs in sync with the edge swapped into its place.
+    fn remove_contact_pair_by_id(&mut self, id: ContactPairId) -> Option<ContactPair> {
+        let edge = self.contact_pair_ids.remove(id.0)?;
+        let removed = self.contact_graph.graph.remove_edge(edge)?;
+
+        if let Some(moved) = self.contact_graph.graph.edges.get(edge.index()) {
+            if let Some(moved_edge) = self.contact_pair_ids.get_mut(moved.weight.id.0) {
+                *moved_edge = edge;
+            }
+        }
+
+        Some(removed)
+    }
+
     fn add_pair(&mut self, colliders: &ColliderSet, pair: &ColliderPair) {
         if let (Some(co1), Some(co2)) =
             (colliders.get(pair.collider1), colliders.get(pair.collider2))
         {
-            if co1.parent.map(|p| p.handle) == co2.parent.map(|p| p.handle) && co1.parent.is_some()
-            {
-                // Same parents. Ignore collisions.
-                return;
-            }
-
-            // These colliders have no parents - continue.
+            // Order the pair by collider user-data, then by handle, so that the contact normals
+            // of the pair don’t depend on the order the broad-phase reported it in, nor on the
+            // order the colliders were inserted in if they have different user-data.
+            let key =
+                |co: &Collider, handle: ColliderHandle| (co.user_data, handle.into_raw_parts());
+            let (pair, co1, co2) = if key(co2, pair.collider2) < key(co1, pair.collider1) {
+                (&pair.swap(), co2, co1)
+            } else {
+                (pair, co1, co2)
+            };
 
             let (gid1, gid2) = self.graph_indices.ensure_pair_exists(
                 pair.collider1.0,
@@ -649,7 +1024,10 @@ impl NarrowPhase {
                     .find_edge(gid1.contact_graph_index, gid2.contact_graph_index)
                     .is_none()
                 {
-                    let interaction = ContactPair::new(pair.collider1, pair.collider2);
+                    let mut interaction = ContactPair::new(pair.collider1, pair.collider2);
+                    let edge =
+                        TemporaryInteractionIndex::new(self.contact_graph.graph.edges.len() as u32);
+                    interaction.id = ContactPairId(self.contact_pair_ids.insert(edge));
                     let _ = self.contact_graph.add_edge(
                         gid1.contact_graph_index,
                         gid2.contact_graph_index,
@@ -667,6 +1045,7 @@ impl NarrowPhase {
         bodies: &mut RigidBodySet,
         broad_phase_events: &[BroadPhasePairEvent],
         events: &dyn EventHandler,
+        step_id: u64,
     ) {
         for event in broad_phase_events {
             match event {
@@ -680,6 +1059,7 @@ impl NarrowPhase {
                         bodies,
                         pair,
                         events,
+                        step_id,
                         PairRemovalMode::Auto,
                     );
                 }
@@ -687,6 +1067,106 @@ impl NarrowPhase {
         }
     }
 
+    /// Emits the broad-phase events for the pairs created or removed by the broad-phase.
+    ///
+    /// A pair that is no longer reported by the broad-phase is only considered stopped once the
+    /// collider AABBs no longer overlap after being enlarged by an additional half
+    /// `prediction_distance`, to avoid emitting events repeatedly at the overlap boundary.
+    pub(crate) fn emit_broad_phase_events(
+        &mut self,
+        prediction_distance: Real,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        broad_phase_events: &[BroadPhasePairEvent],
+        events: &dyn EventHandler,
+        step_id: u64,
+    ) {
+        let wants_events = |handle| {
+            colliders.get(handle).is_some_and(|co: &Collider| {
+                co.flags
+                    .active_events
+                    .contains(ActiveEvents::BROAD_PHASE_EVENTS)
+            })
+        };
+
+        for event in broad_phase_events {
+            match event {
+                BroadPhasePairEvent::AddPair(pair) => {
+                    if !wants_events(pair.collider1) && !wants_events(pair.collider2) {
+                        continue;
+                    }
+
+                    let key = Self::broad_phase_event_key(pair);
+                    let _ = self.separating_broad_phase_event_pairs.remove(&key);
+
+                    if self.broad_phase_event_pairs.insert(key) {
+                        events.handle_broad_phase_event(
+                            step_id,
+                            bodies,
+                            colliders,
+                            BroadPhaseEvent::PairStarted(pair.collider1, pair.collider2),
+                        );
+                    }
+                }
+                BroadPhasePairEvent::DeletePair(pair) => {
+                    let key = Self::broad_phase_event_key(pair);
+
+                    if self.broad_phase_event_pairs.contains(&key) {
+                        let _ = self.separating_broad_phase_event_pairs.insert(key);
+                    }
+                }
+            }
+        }
+
+        if self.broad_phase_event_pairs.is_empty() {
+            return;
+        }
+
+        let aabb = |co: &Collider| {
+            co.compute_collision_aabb(
+                co.broad_phase_prediction_margin(prediction_distance) + prediction_distance / 2.0,
+            )
+        };
+        let mut stopped: Vec<_> = self
+            .broad_phase_event_pairs
+            .iter()
+            .filter(|(handle1, handle2)| {
+                let (Some(co1), Some(co2)) = (colliders.get(*handle1), colliders.get(*handle2))
+                else {
+                    return true;
+                };
+
+                !co1.is_enabled()
+                    || !co2.is_enabled()
+                    || (self
+                        .separating_broad_phase_event_pairs
+                        .contains(&(*handle1, *handle2))
+                        && !aabb(co1).intersects(&aabb(co2)))
+            })
+            .copied()
+            .collect();
+        stopped.sort_unstable_by_key(|(h1, h2)| (h1.into_raw_parts(), h2.into_raw_parts()));
+
+        for key in stopped {
+            let _ = self.broad_phase_event_pairs.remove(&key);
+            let _ = self.separating_broad_phase_event_pairs.remove(&key);
+            events.handle_broad_phase_event(
+                step_id,
+                bodies,
+                colliders,
+                BroadPhaseEvent::PairStopped(key.0, key.1),
+            );
+        }
+    }
+
+    pub(crate) fn broad_phase_event_key(pair: &ColliderPair) -> (ColliderHandle, ColliderHandle) {
+        if pair.collider1.0 <= pair.collider2.0 {
+            (pair.collider1, pair.collider2)
+        } else {
+            (pair.collider2, pair.collider1)
+        }
+    }
+
     pub(crate) fn compute_intersections(
         &mut self,
         bodies: &RigidBodySet,
@@ -694,6 +1174,7 @@ impl NarrowPhase {
         modified_colliders: &[ColliderHandle],
         hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
+        step_id: u64,
     ) {
         if modified_colliders.is_empty() {
             return;
@@ -701,16 +1182,16 @@ impl NarrowPhase {
 
         let nodes = &self.intersection_graph.graph.nodes;
         let query_dispatcher = &*self.query_dispatcher;
+        let collision_exceptions = &self.collision_exceptions;
 
         // TODO: don't iterate on all the edges.
         par_iter_mut!(&mut self.intersection_graph.graph.edges).for_each(|edge| {
             let handle1 = nodes[edge.source().index()].weight;
             let handle2 = nodes[edge.target().index()].weight;
-            let had_intersection = edge.weight.intersecting;
             let co1 = &colliders[handle1];
             let co2 = &colliders[handle2];
 
-            'emit_events: {
+            'update: {
                 if !co1.changes.needs_narrow_phase_update()
                     && !co2.changes.needs_narrow_phase_update()
                 {
@@ -718,16 +1199,35 @@ impl NarrowPhase {
                     return;
                 }
 
+                edge.weight.within_margin = false;
+
+                // Colliders attached to the same rigid-body don’t intersect.
+                if have_same_parent(co1, co2) {
+                    edge.weight.intersecting = false;
+                    break 'update;
+                }
+
                 // TODO: avoid lookup into bodies.
                 let mut rb_type1 = RigidBodyType::Fixed;
                 let mut rb_type2 = RigidBodyType::Fixed;
 
                 if let Some(co_parent1) = &co1.parent {
-                    rb_type1 = bodies[co_parent1.handle].body_type;
+                    rb_type1 = bodies[co_parent1.handle].unfrozen_body_type();
                 }
 
                 if let Some(co_parent2) = &co2.parent {
-                    rb_type2 = bodies[co_parent2.handle].body_type;
+                    rb_type2 = bodies[co_parent2.handle].unfrozen_body_type();
+                }
+
+                // Filter based on the collision exceptions.
+                if let (Some(co_parent1), Some(co_parent2)) = (&co1.parent, &co2.parent) {
+                    if collision_exceptions.contains(&Self::collision_exception_key(
+                        co_parent1.handle,
+                        co_parent2.handle,
+                    )) {
+                        edge.weight.intersecting = false;
+                        break 'update;
+                    }
                 }
 
                 // Filter based on the rigid-body types.
@@ -735,13 +1235,13 @@ impl NarrowPhase {
                     && !co2.flags.active_collision_types.test(rb_type1, rb_type2)
                 {
                     edge.weight.intersecting = false;
-                    break 'emit_events;
+                    break 'update;
                 }
 
                 // Filter based on collision groups.
                 if !co1.flags.collision_groups.test(co2.flags.collision_groups) {
                     edge.weight.intersecting = false;
-                    break 'emit_events;
+                    break 'update;
                 }
 
                 let active_hooks = co1.flags.active_hooks | co2.flags.active_hooks;
@@ -759,36 +1259,94 @@ impl NarrowPhase {
                     if !hooks.filter_intersection_pair(&context) {
                         // No intersection allowed.
                         edge.weight.intersecting = false;
-                        break 'emit_events;
+                        break 'update;
                     }
                 }
 
                 let pos12 = co1.pos.inv_mul(&co2.pos);
+                let margin = co1.sensor_margin().max(co2.sensor_margin());
+
+                if margin > 0.0 {
+                    // Hysteresis: entering requires a penetration deeper than the margin, and
+                    // leaving requires a separation larger than the margin.
+                    if let Ok(contact) =
+                        query_dispatcher.contact(&pos12, &*co1.shape, &*co2.shape, margin)
+                    {
+                        match contact {
+                            Some(contact) if contact.dist < -margin => {
+                                edge.weight.intersecting = true
+                            }
+                            Some(_) => edge.weight.within_margin = true,
+                            None => edge.weight.intersecting = false,
+                        }
+                        break 'update;
+                    }
+                }
+
                 edge.weight.intersecting = query_dispatcher
                     .intersection_test(&pos12, &*co1.shape, &*co2.shape)
                     .unwrap_or(false);
             }
+        });
 
-            let active_events = co1.flags.active_events | co2.flags.active_events;
+        // Emit the collision events sequentially so their order doesn’t depend on the
+        // parallel iteration above.
+        let nodes = &self.intersection_graph.graph.nodes;
+        let mut pending_events: Vec<_> = self
+            .intersection_graph
+            .graph
+            .edges
+            .iter()
+            .enumerate()
+            .filter_map(|(i, edge)| {
+                let handle1 = nodes[edge.source().index()].weight;
+                let handle2 = nodes[edge.target().index()].weight;
+                Self::needs_collision_event(
+                    edge.weight.intersecting,
+                    edge.weight.start_event_emited,
+                    &colliders[handle1],
+                    &colliders[handle2],
+                )
+                .then(|| (Self::collision_event_key(handle1, handle2), i))
+            })
+            .collect();
+        pending_events.sort_unstable();
 
-            if active_events.contains(ActiveEvents::COLLISION_EVENTS)
-                && had_intersection != edge.weight.intersecting
-            {
-                if edge.weight.intersecting {
-                    edge.weight
-                        .emit_start_event(bodies, colliders, handle1, handle2, events);
-                } else {
-                    edge.weight
-                        .emit_stop_event(bodies, colliders, handle1, handle2, events);
-                }
+        for (_, i) in pending_events {
+            let edge = &mut self.intersection_graph.graph.edges[i];
+            let handle1 = self.intersection_graph.graph.nodes[edge.source().index()].weight;
+            let handle2 = self.intersection_graph.graph.nodes[edge.target().index()].weight;
+
+            if edge.weight.intersecting {
+                edge.weight
+                    .emit_start_event(bodies, colliders, handle1, handle2, events, step_id);
+            } else {
+                edge.weight
+                    .emit_stop_event(bodies, colliders, handle1, handle2, events, step_id);
             }
-        });
+            self.step_transitions.record_intersection(
+                handle1,
+                handle2,
+                edge.weight.intersecting,
+                false,
+            );
+        }
     }
 
+    /// Computes the contacts of the pairs involving modified colliders.
+    ///
+    /// Returns the number of pairs skipped because their shapes are too far apart, if
+    /// `culling_enabled` is `true`.
     pub(crate) fn compute_contacts(
         &mut self,
         prediction_distance: Real,
+        restitution_velocity_threshold: Real,
         dt: Real,
+        warmstart_min_contact_age: u32,
+        warmstart_max_teleport_displacement: Real,
+        contact_matching_tolerance: Real,
+        max_solver_contacts: usize,
+        culling_enabled: bool,
         bodies: &RigidBodySet,
         colliders: &ColliderSet,
         impulse_joints: &ImpulseJointSet,
@@ -796,21 +1354,23 @@ impl NarrowPhase {
         modified_colliders: &[ColliderHandle],
         hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
-    ) {
+        step_id: u64,
+    ) -> usize {
         if modified_colliders.is_empty() {
-            return;
+            return 0;
         }
 
         let query_dispatcher = &*self.query_dispatcher;
+        let collision_exceptions = &self.collision_exceptions;
+        let num_culled_pairs = AtomicUsize::new(0);
 
         // TODO: don't iterate on all the edges.
         par_iter_mut!(&mut self.contact_graph.graph.edges).for_each(|edge| {
             let pair = &mut edge.weight;
-            let had_any_active_contact = pair.has_any_active_contact;
             let co1 = &colliders[pair.collider1];
             let co2 = &colliders[pair.collider2];
 
-            'emit_events: {
+            'update: {
                 if !co1.changes.needs_narrow_phase_update()
                     && !co2.changes.needs_narrow_phase_update()
                 {
@@ -824,6 +1384,13 @@ impl NarrowPhase {
                 let rb_type1 = rb1.map(|rb| rb.body_type).unwrap_or(RigidBodyType::Fixed);
                 let rb_type2 = rb2.map(|rb| rb.body_type).unwrap_or(RigidBodyType::Fixed);
 
+                // Colliders attached to the same rigid-body don’t collide. Their pair is kept in
+                // case one of them is moved to another rigid-body with `ColliderSet::set_parent`.
+                if have_same_parent(co1, co2) {
+                    pair.clear();
+                    break 'update;
+                }
+
                 // Deal with contacts disabled between bodies attached by joints.
                 if let (Some(co_parent1), Some(co_parent2)) = (&co1.parent, &co2.parent) {
                     for (_, joint) in
@@ -831,7 +1398,7 @@ impl NarrowPhase {
                     {
                         if !joint.data.contacts_enabled {
                             pair.clear();
-                            break 'emit_events;
+                            break 'update;
                         }
                     }
 
@@ -840,23 +1407,39 @@ impl NarrowPhase {
                     {
                         if !mb_link.joint.data.contacts_enabled {
                             pair.clear();
-                            break 'emit_events;
+                            break 'update;
                         }
                     }
+
+                    // Filter based on the collision exceptions.
+                    if collision_exceptions.contains(&Self::collision_exception_key(
+                        co_parent1.handle,
+                        co_parent2.handle,
+                    )) {
+                        pair.clear();
+                        break 'update;
+                    }
                 }
 
-                // Filter based on the rigid-body types.
-                if !co1.flags.active_collision_types.test(rb_type1, rb_type2)
-                    && !co2.flags.active_collision_types.test(rb_type1, rb_type2)
+                // Filter based on the rigid-body types. Bodies frozen because their step group
+                // isn’t stepped keep the contacts they would have as dynamic bodies.
+                let filter_type1 = rb1
+                    .map(|rb| rb.unfrozen_body_type())
+                    .unwrap_or(RigidBodyType::Fixed);
+                let filter_type2 = rb2
+                    .map(|rb| rb.unfrozen_body_type())
+                    .unwrap_or(RigidBodyType::Fixed);
+                if !co1.flags.active_collision_types.test(filter_type1, filter_type2)
+                    && !co2.flags.active_collision_types.test(filter_type1, filter_type2)
                 {
                     pair.clear();
-                    break 'emit_events;
+                    break 'update;
                 }
 
                 // Filter based on collision groups.
                 if !co1.flags.collision_groups.test(co2.flags.collision_groups) {
                     pair.clear();
-                    break 'emit_events;
+                    break 'update;
                 }
 
                 let active_hooks = co1.flags.active_hooks | co2.flags.active_hooks;
@@ -876,7 +1459,7 @@ impl NarrowPhase {
                     } else {
                         // No contact allowed.
                         pair.clear();
-                        break 'emit_events;
+                        break 'update;
                     }
                 } else {
                     SolverFlags::default()
@@ -893,7 +1476,40 @@ impl NarrowPhase {
                     pair.workspace = None;
                 }
 
+                // Age the contact points kept since the last update. The impulses of the points
+                // of colliders with a new shape, or of colliders teleported apart, are stale:
+                // their age is reset so they aren’t warmstarted. Deformations keep warmstarting
+                // (see below).
+                let reshaped = co1.changes.contains(ColliderChanges::SHAPE)
+                    || co2.changes.contains(ColliderChanges::SHAPE);
+                let deformed = !reshaped
+                    && (co1.changes.contains(ColliderChanges::SHAPE_DEFORMED)
+                        || co2.changes.contains(ColliderChanges::SHAPE_DEFORMED));
+                let teleported = was_teleported(co1, rb1) || was_teleported(co2, rb2);
+                for manifold in &mut pair.manifolds {
+                    let world_pos1 = manifold.subshape_pos1.prepend_to(&co1.pos);
+                    let world_pos2 = manifold.subshape_pos2.prepend_to(&co2.pos);
+                    let normal = world_pos1 * manifold.local_n1;
+
+                    for contact in &mut manifold.points {
+                        let is_stale = reshaped
+                            || teleported && {
+                                let dpt = world_pos2 * contact.local_p2
+                                    - world_pos1 * contact.local_p1;
+                                (dpt - normal * contact.dist).norm()
+                                    > warmstart_max_teleport_displacement
+                            };
+                        contact.data.age = if is_stale {
+                            0
+                        } else {
+                            contact.data.age.saturating_add(1)
+                        };
+                    }
+                }
+
                 let pos12 = co1.pos.inv_mul(&co2.pos);
+                let prediction_distance =
+                    co1.effective_contact_prediction_distance(co2, prediction_distance);
 
                 let contact_skin_sum = co1.contact_skin() + co2.contact_skin();
                 let soft_ccd_prediction1 = rb1.map(|rb| rb.soft_ccd_prediction()).unwrap_or(0.0);
@@ -910,7 +1526,7 @@ impl NarrowPhase {
 
                         if !aabb1.intersects(&aabb2) && !aabb1.intersects_moving_aabb(&aabb2, linvel2 - linvel1) {
                             pair.clear();
-                            break 'emit_events;
+                            break 'update;
                         }
 
 
@@ -920,6 +1536,44 @@ impl NarrowPhase {
                     prediction_distance + contact_skin_sum
                 };
 
+                if culling_enabled
+                    && are_further_apart_than(
+                        &pos12,
+                        &*co1.shape,
+                        &*co2.shape,
+                        effective_prediction_distance,
+                    )
+                {
+                    num_culled_pairs.fetch_add(1, Ordering::Relaxed);
+                    pair.clear();
+                    break 'update;
+                }
+
+                // Keep the current contact points so the ones replaced by points between other
+                // features can still pass their impulses on to a nearby new point.
+                pair.previous_contacts.clear();
+                if contact_matching_tolerance > 0.0 && !reshaped {
+                    pair.previous_contacts
+                        .extend(pair.manifolds.iter().flat_map(collider_space_contacts));
+                }
+
+                // The shape was deformed without changing its topology, so the workspace and
+                // manifolds are kept. However, the cached contact points may no longer lie on
+                // the shape: they are removed so the contact generators compute them from
+                // scratch, and their data is given back to the new points between the same
+                // features so they are still warmstarted.
+                let mut deformed_contacts = vec![];
+                if deformed {
+                    for manifold in &mut pair.manifolds {
+                        deformed_contacts.extend(
+                            manifold
+                                .points
+                                .drain(..)
+                                .map(|pt| (manifold.subshape1, manifold.subshape2, pt)),
+                        );
+                    }
+                }
+
                 let _ = query_dispatcher.contact_manifolds(
                     &pos12,
                     &*co1.shape,
@@ -929,18 +1583,20 @@ impl NarrowPhase {
                     &mut pair.workspace,
                 );
 
-                let friction = CoefficientCombineRule::combine(
-                    co1.material.friction,
-                    co2.material.friction,
-                    co1.material.friction_combine_rule as u8,
-                    co2.material.friction_combine_rule as u8,
-                );
-                let restitution = CoefficientCombineRule::combine(
-                    co1.material.restitution,
-                    co2.material.restitution,
-                    co1.material.restitution_combine_rule as u8,
-                    co2.material.restitution_combine_rule as u8,
-                );
+                if !deformed_contacts.is_empty() {
+                    match_contacts_by_features(&mut pair.manifolds, &deformed_contacts);
+                }
+
+                if !pair.previous_contacts.is_empty() {
+                    match_contacts_by_proximity(
+                        &mut pair.manifolds,
+                        &mut pair.previous_contacts,
+                        contact_matching_tolerance,
+                    );
+                }
+
+                let restitution_velocity_threshold = co1
+                    .effective_restitution_velocity_threshold(co2, restitution_velocity_threshold);
 
                 let zero = RigidBodyDominance(0); // The value doesn't matter, it will be MAX because of the effective groups.
                 let dominance1 = rb1.map(|rb| rb.dominance).unwrap_or(zero);
@@ -951,7 +1607,34 @@ impl NarrowPhase {
                 for manifold in &mut pair.manifolds {
                     let world_pos1 = manifold.subshape_pos1.prepend_to(&co1.pos);
                     let world_pos2 = manifold.subshape_pos2.prepend_to(&co2.pos);
+
+                    // The material of composite shapes may depend on the contacted sub-shape.
+                    let (friction1, restitution1) =
+                        co1.subshape_friction_and_restitution(manifold.subshape1);
+                    let (friction2, restitution2) =
+                        co2.subshape_friction_and_restitution(manifold.subshape2);
+                    let friction = CoefficientCombineRule::combine(
+                        friction1,
+                        friction2,
+                        co1.material.friction_combine_rule as u8,
+                        co2.material.friction_combine_rule as u8,
+                    );
+                    let restitution = CoefficientCombineRule::combine(
+                        restitution1,
+                        restitution2,
+                        co1.material.restitution_combine_rule as u8,
+                        co2.material.restitution_combine_rule as u8,
+                    );
+                    let rolling_friction = CoefficientCombineRule::combine(
+                        co1.material.rolling_friction,
+                        co2.material.rolling_friction,
+                        co1.material.friction_combine_rule as u8,
+                        co2.material.friction_combine_rule as u8,
+                    );
+
                     manifold.data.solver_contacts.clear();
+                    manifold.data.collider1 = pair.collider1;
+                    manifold.data.collider2 = pair.collider2;
                     manifold.data.rigid_body1 = co1.parent.map(|p| p.handle);
                     manifold.data.rigid_body2 = co2.parent.map(|p| p.handle);
                     manifold.data.solver_flags = solver_flags;
@@ -982,16 +1665,45 @@ impl NarrowPhase {
                             let world_pt2 = world_pos2 * contact.local_p2;
                             let effective_point = na::center(&world_pt1, &world_pt2);
 
+                            let friction = if co1.material.anisotropic_friction.is_some()
+                                || co2.material.anisotropic_friction.is_some()
+                            {
+                                // The anisotropic friction depends on the sliding direction.
+                                let vel1 = rb1.map(|rb| rb.velocity_at_point(&world_pt1)).unwrap_or_default();
+                                let vel2 = rb2.map(|rb| rb.velocity_at_point(&world_pt2)).unwrap_or_default();
+                                let normal = manifold.data.normal;
+                                let dvel = vel2 - vel1;
+                                let tangent_dvel = dvel - normal * normal.dot(&dvel);
+                                let sliding_direction = UnitVector::try_new(tangent_dvel, DEFAULT_EPSILON);
+                                CoefficientCombineRule::combine(
+                                    co1.friction_along(sliding_direction),
+                                    co2.friction_along(sliding_direction),
+                                    co1.material.friction_combine_rule as u8,
+                                    co2.material.friction_combine_rule as u8,
+                                )
+                            } else {
+                                friction
+                            };
+
+                            let (warmstart_impulse, warmstart_tangent_impulse) =
+                                if contact.data.age >= warmstart_min_contact_age {
+                                    (contact.data.warmstart_impulse, contact.data.warmstart_tangent_impulse)
+                                } else {
+                                    (0.0, na::zero())
+                                };
+
                             let solver_contact = SolverContact {
                                 contact_id: contact_id as u8,
                                 point: effective_point,
                                 dist: effective_contact_dist,
                                 friction,
                                 restitution,
+                                restitution_velocity_threshold,
+                                rolling_friction,
                                 tangent_velocity: Vector::zeros(),
                                 is_new: contact.data.impulse == 0.0,
-                                warmstart_impulse: contact.data.warmstart_impulse,
-                                warmstart_tangent_impulse: contact.data.warmstart_tangent_impulse,
+                                warmstart_impulse,
+                                warmstart_tangent_impulse,
                             };
 
                             manifold.data.solver_contacts.push(solver_contact);
@@ -999,6 +1711,8 @@ impl NarrowPhase {
                         }
                     }
 
+                    reduce_solver_contacts(manifold, max_solver_contacts);
+
                     // Apply the user-defined contact modification.
                     if active_hooks.contains(ActiveHooks::MODIFY_SOLVER_CONTACTS) {
                         let mut modifiable_solver_contacts =
@@ -1056,19 +1770,128 @@ impl NarrowPhase {
                     */
                 }
             }
+        });
 
-            let active_events = co1.flags.active_events | co2.flags.active_events;
+        // Emit the collision events sequentially so their order doesn’t depend on the
+        // parallel iteration above.
+        let mut pending_events: Vec<_> = self
+            .contact_graph
+            .graph
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(_, edge)| {
+                let pair = &edge.weight;
+                Self::needs_collision_event(
+                    pair.has_any_active_contact,
+                    pair.start_event_emited,
+                    &colliders[pair.collider1],
+                    &colliders[pair.collider2],
+                )
+            })
+            .map(|(i, edge)| {
+                let pair = &edge.weight;
+                (Self::collision_event_key(pair.collider1, pair.collider2), i)
+            })
+            .collect();
+        pending_events.sort_unstable();
 
-            if pair.has_any_active_contact != had_any_active_contact
-                && active_events.contains(ActiveEvents::COLLISION_EVENTS)
-            {
-                if pair.has_any_active_contact {
-                    pair.emit_start_event(bodies, colliders, events);
-                } else {
-                    pair.emit_stop_event(bodies, colliders, events);
+        for (_, i) in pending_events {
+            let pair = &mut self.contact_graph.graph.edges[i].weight;
+
+            if pair.has_any_active_contact {
+                pair.emit_start_event(bodies, colliders, events, step_id);
+            } else {
+                pair.emit_stop_event(bodies, colliders, events, step_id);
+            }
+            self.step_transitions.record_contact(
+                pair.collider1,
+                pair.collider2,
+                pair.has_any_active_contact,
+                pair.has_any_active_contact,
+                false,
+            );
+        }
+
+        num_culled_pairs.into_inner()
+    }
+
+    /// Does a pair need to emit a collision event to reflect its new state?
+    ///
+    /// A `Started` event is emitted if the colliders are touching, no `Started` event was emitted
+    /// yet, and one of the colliders enabled collision events. A `Stopped` event is emitted
+    /// if the colliders are no longer touching and a `Started` event was emitted before. This
+    /// guarantees that every `Stopped` event is preceded by exactly one `Started` event.
+    fn needs_collision_event(
+        touching: bool,
+        start_event_emitted: bool,
+        co1: &Collider,
+        co2: &Collider,
+    ) -> bool {
+        if touching {
+            !start_event_emitted
+                && (co1.flags.active_events | co2.flags.active_events)
+                    .contains(ActiveEvents::COLLISION_EVENTS)
+        } else {
+            start_event_emitted
+        }
+    }
+
+    /// The key used to sort the collision events emitted by the same stage of the narrow-phase.
+    fn collision_event_key(collider1: ColliderHandle, collider2: ColliderHandle) -> (u32, u32) {
+        let (id1, id2) = (
+            collider1.0.into_raw_parts().0,
+            collider2.0.into_raw_parts().0,
+        );
+        (id1.min(id2), id1.max(id2))
+    }
+
+    /// Translates the world-space contact points by `-offset`.
+    ///
+    /// The other contact data are expressed in the local-space of the colliders so they
+    /// are not affected by a translation of the whole world.
+    pub(crate) fn shift_origin(&mut self, offset: Vector<Real>) {
+        for edge in &mut self.contact_graph.graph.edges {
+            for manifold in &mut edge.weight.manifolds {
+                for contact in &mut manifold.data.solver_contacts {
+                    contact.point -= offset;
                 }
             }
-        });
+        }
+    }
+
+    /// Recomputes the relative dominance of the contact manifolds involving the given collider.
+    ///
+    /// This is the only part of the contact manifolds depending on the types of the rigid-bodies,
+    /// so this is enough to take into account a type change that didn’t move the collider.
+    pub(crate) fn update_relative_dominances(
+        &mut self,
+        collider: ColliderHandle,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+    ) {
+        let Some(id) = self.graph_indices.get(collider.0) else {
+            return;
+        };
+
+        let effective_group = |co: ColliderHandle| {
+            let rb = colliders[co]
+                .parent
+                .map(|co_parent| &bodies[co_parent.handle]);
+            rb.map(|rb| rb.dominance.effective_group(&rb.body_type))
+                .unwrap_or(i8::MAX as i16 + 1)
+        };
+
+        for (_, _, _, pair) in self
+            .contact_graph
+            .interactions_with_mut(id.contact_graph_index)
+        {
+            let relative_dominance =
+                effective_group(pair.collider1) - effective_group(pair.collider2);
+            for manifold in &mut pair.manifolds {
+                manifold.data.relative_dominance = relative_dominance;
+            }
+        }
     }
 
     /// Retrieve all the interactions with at least one contact point, happening between two active bodies.
@@ -1143,3 +1966,836 @@ impl NarrowPhase {
         }
     }
 }
+
+/// Are the two shapes, with `shape2` at the position `pos12` relative to `shape1`, further apart
+/// than `margin`?
+///
+/// This is a conservative test comparing the bounding spheres of both shapes, then the local
+/// AABB of `shape1` with the AABB of `shape2` in the local frame of `shape1`. The latter catches
+/// elongated shapes whose bounding spheres overlap.
+fn are_further_apart_than(
+    pos12: &Isometry<Real>,
+    shape1: &dyn Shape,
+    shape2: &dyn Shape,
+    margin: Real,
+) -> bool {
+    let sphere1 = shape1.compute_local_bounding_sphere();
+    let sphere2 = shape2.compute_bounding_sphere(pos12);
+    let center_dist = na::distance(sphere1.center(), sphere2.center());
+
+    if center_dist > sphere1.radius() + sphere2.radius() + margin {
+        return true;
+    }
+
+    let aabb1 = shape1.compute_local_aabb().loosened(margin);
+    let aabb2 = shape2.compute_aabb(pos12);
+    !aabb1.intersects(&aabb2)
+}
+
+/// Was the collider moved by the user, rather than by the simulation, since the last contact
+/// update?
+///
+/// The simulation only moves colliders through their parent, and doesn’t set the
+/// `RigidBodyChanges::POSITION` flag of the rigid-bodies it moves.
+fn was_teleported(co: &Collider, rb: Option<&RigidBody>) -> bool {
+    match rb {
+        Some(rb) => {
+            rb.changes.contains(RigidBodyChanges::POSITION)
+                || co.changes.contains(ColliderChanges::PARENT)
+        }
+        None => co.changes.contains(ColliderChanges::POSITION),
+    }
+}
+
+/// Gives the data of the `previous` contact points, with their subshape ids, to the points of
+/// `manifolds` between the same sub-shapes and features.
+fn match_contacts_by_features(manifolds: &mut [ContactManifold], previous: &[(u32, u32, Contact)]) {
+    for manifold in manifolds {
+        for pt in &mut manifold.points {
+            if let Some((_, _, old)) = previous.iter().find(|(subshape1, subshape2, old)| {
+                *subshape1 == manifold.subshape1
+                    && *subshape2 == manifold.subshape2
+                    && old.fid1 == pt.fid1
+                    && old.fid2 == pt.fid2
+            }) {
+                pt.data = old.data;
+            }
+        }
+    }
+}
+
+/// The contact points of `manifold`, with their subshape ids, expressed in the local frames of the
+/// colliders instead of their sub-shapes.
+fn collider_space_contacts(
+    manifold: &ContactManifold,
+) -> impl Iterator<Item = (u32, u32, Contact)> + '_ {
+    let pos1 = manifold.subshape_pos1.unwrap_or_else(Isometry::identity);
+    let pos2 = manifold.subshape_pos2.unwrap_or_else(Isometry::identity);
+    manifold.points.iter().map(move |pt| {
+        let mut pt = *pt;
+        pt.local_p1 = pos1 * pt.local_p1;
+        pt.local_p2 = pos2 * pt.local_p2;
+        (manifold.subshape1, manifold.subshape2, pt)
+    })
+}
+
+/// Transfers the data of the `previous` contact points of a pair to its new contact points that
+/// didn’t inherit any because their features changed.
+///
+/// The `previous` points are expressed in the local frames of the colliders, as given by
+/// [`collider_space_contacts`]. Each new point without data inherits the data of the closest
+/// previous point, not matched by features, lying within `tolerance` of it on either collider.
+/// Comparing the points on both colliders lets the contacts sliding along one of them, possibly
+/// from one sub-shape to another, be matched too.
+fn match_contacts_by_proximity(
+    manifolds: &mut [ContactManifold],
+    previous: &mut Vec<(u32, u32, Contact)>,
+    tolerance: Real,
+) {
+    // The previous points still between the same features already passed their data on.
+    previous.retain(|(subshape1, subshape2, old)| {
+        !manifolds.iter().any(|m| {
+            m.subshape1 == *subshape1
+                && m.subshape2 == *subshape2
+                && m.points
+                    .iter()
+                    .any(|pt| pt.fid1 == old.fid1 && pt.fid2 == old.fid2)
+        })
+    });
+
+    for manifold in manifolds {
+        let pos1 = manifold.subshape_pos1.unwrap_or_else(Isometry::identity);
+        let pos2 = manifold.subshape_pos2.unwrap_or_else(Isometry::identity);
+
+        for contact in &mut manifold.points {
+            if previous.is_empty() {
+                return;
+            }
+
+            if contact.data.age != 0 {
+                // This point inherited the data of a previous point between the same features.
+                continue;
+            }
+
+            let pt1 = pos1 * contact.local_p1;
+            let pt2 = pos2 * contact.local_p2;
+            let closest = previous
+                .iter()
+                .map(|(_, _, old)| {
+                    na::distance(&old.local_p1, &pt1).min(na::distance(&old.local_p2, &pt2))
+                })
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            if let Some((i, dist)) = closest {
+                if dist <= tolerance {
+                    contact.data = previous.swap_remove(i).2.data;
+                }
+            }
+        }
+    }
+}
+
+/// Are both colliders attached to the same rigid-body?
+fn have_same_parent(co1: &Collider, co2: &Collider) -> bool {
+    co1.parent.is_some() && co1.parent.map(|p| p.handle) == co2.parent.map(|p| p.handle)
+}
+
+/// Reduces the solver contacts of `manifold` to at most `max_contacts` points.
+///
+/// The deepest contact is always kept. The other ones are selected greedily to be as far apart as
+/// possible on the contact plane: the second one is the furthest from the deepest contact and, in
+/// 3D, the next ones maximize the area of the contact polygon. The manifold contacts dropped here
+/// have their impulses reset so stale values aren’t used for warmstarting if they are kept again
+/// later.
+fn reduce_solver_contacts(manifold: &mut ContactManifold, max_contacts: usize) {
+    let contacts = &mut manifold.data.solver_contacts;
+    let max_contacts = max_contacts.max(1);
+
+    if contacts.len() <= max_contacts {
+        return;
+    }
+
+    let normal = manifold.data.normal;
+    let project = |contact: &SolverContact| {
+        let pt = contact.point.coords;
+        pt - normal * normal.dot(&pt)
+    };
+
+    // The selected contacts are moved to the beginning of the array.
+    let deepest = (0..contacts.len())
+        .min_by(|a, b| contacts[*a].dist.total_cmp(&contacts[*b].dist))
+        .unwrap_or(0);
+    contacts.swap(0, deepest);
+
+    for num_selected in 1..max_contacts {
+        let selected: Vec<_> = contacts[..num_selected].iter().map(project).collect();
+        let spread = |contact: &SolverContact| {
+            let pt = project(contact);
+
+            #[cfg(feature = "dim3")]
+            if selected.len() > 1 {
+                // The area added to the contact polygon if `pt` is outside of one of its edges.
+                let center = selected.iter().sum::<Vector<Real>>() / selected.len() as Real;
+                let mut added_area: Real = 0.0;
+
+                for (i, a) in selected.iter().enumerate() {
+                    for b in &selected[i + 1..] {
+                        let area = (b - a).cross(&(pt - a)).dot(&normal);
+                        let center_side = (b - a).cross(&(center - a)).dot(&normal);
+
+                        if area * center_side <= 0.0 {
+                            added_area = added_area.max(area.abs() / 2.0);
+                        }
+                    }
+                }
+
+                return added_area;
+            }
+
+            selected
+                .iter()
+                .map(|selected_pt| (pt - selected_pt).norm())
+                .fold(Real::MAX, Real::min)
+        };
+
+        // Among equally spread contacts, prefer the deepest.
+        let best = (num_selected..contacts.len())
+            .max_by(|a, b| {
+                let (a, b) = (&contacts[*a], &contacts[*b]);
+                spread(a)
+                    .total_cmp(&spread(b))
+                    .then(b.dist.total_cmp(&a.dist))
+            })
+            .unwrap_or(num_selected);
+        contacts.swap(num_selected, best);
+    }
+
+    for dropped in contacts.drain(max_contacts..) {
+        manifold.points[dropped.contact_id as usize].data = ContactData::default();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::are_further_apart_than;
+    use crate::dynamics::{CoefficientCombineRule, RigidBodyBuilder};
+    use crate::geometry::{ColliderBuilder, SharedShape};
+    use crate::math::{Real, Vector};
+    use crate::pipeline::PhysicsWorld;
+    use std::num::NonZeroUsize;
+
+    /// Rods and balls falling on the ground with random orientations and velocities.
+    fn rods_scene(culling_enabled: bool) -> PhysicsWorld {
+        // Simple deterministic pseudo-random number generator.
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut rand = move || {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (seed >> 40) as Real / (1u64 << 24) as Real
+        };
+
+        let mut world = PhysicsWorld::default();
+        world.integration_parameters.narrow_phase_culling_enabled = culling_enabled;
+        // A large prediction distance makes the AABBs of many separated pairs overlap.
+        world.integration_parameters.normalized_prediction_distance = 0.5;
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+        for i in 0..60 {
+            let position = Vector::from_fn(|_, _| rand() * 10.0 - 5.0) + Vector::y() * 8.0;
+            let body = RigidBodyBuilder::dynamic()
+                .translation(position)
+                .linvel(Vector::from_fn(|_, _| rand() * 10.0 - 5.0));
+            #[cfg(feature = "dim2")]
+            let body = body.rotation(rand() * 6.0);
+            #[cfg(feature = "dim3")]
+            let body = body.rotation(Vector::from_fn(|_, _| rand() * 6.0));
+            let body = world.insert_body(body);
+
+            let length = 1.0 + rand() * 4.0;
+            let shape = match i % 3 {
+                0 => ColliderBuilder::capsule_x(length, 0.1),
+                #[cfg(feature = "dim2")]
+                1 => ColliderBuilder::cuboid(length, 0.1),
+                #[cfg(feature = "dim3")]
+                1 => ColliderBuilder::cuboid(length, 0.1, 0.1),
+                _ => ColliderBuilder::ball(0.2 + rand() * 0.5),
+            };
+            world.insert_collider_with_parent(shape.contact_skin(rand() * 0.1), body);
+        }
+
+        world
+    }
+
+    #[test]
+    fn culled_pairs_are_further_apart_than_their_prediction_distance() {
+        let mut world = rods_scene(false);
+        let prediction = world.integration_parameters.prediction_distance();
+        let mut num_culled = 0;
+
+        for _ in 0..120 {
+            world.step();
+
+            let colliders: Vec<_> = world.colliders.iter().map(|(_, co)| co).collect();
+            for (i, co1) in colliders.iter().enumerate() {
+                for co2 in &colliders[i + 1..] {
+                    let margin = prediction + co1.contact_skin() + co2.contact_skin();
+                    let pos12 = co1.position().inv_mul(co2.position());
+
+                    if are_further_apart_than(&pos12, co1.shape(), co2.shape(), margin) {
+                        let dist = parry::query::distance(
+                            co1.position(),
+                            co1.shape(),
+                            co2.position(),
+                            co2.shape(),
+                        )
+                        .unwrap();
+                        assert!(dist > margin, "{dist} <= {margin}");
+                        num_culled += 1;
+                    }
+                }
+            }
+        }
+
+        assert!(num_culled > 0);
+    }
+
+    #[test]
+    fn culled_pairs_are_counted() {
+        let mut culled = rods_scene(true);
+        let mut unculled = rods_scene(false);
+        let mut total_culled_pairs = 0;
+
+        for _ in 0..60 {
+            culled.step();
+            unculled.step();
+            total_culled_pairs += culled.physics_pipeline.counters.cd.nculled_pairs;
+            assert_eq!(unculled.physics_pipeline.counters.cd.nculled_pairs, 0);
+        }
+
+        assert!(total_culled_pairs > 0);
+    }
+
+    #[test]
+    fn contact_pair_ids_are_stable_and_not_reused() {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        let ground = world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+        let balls: Vec<_> = (0..4)
+            .map(|i| {
+                let body =
+                    world
+                        .insert_body(RigidBodyBuilder::dynamic().translation(
+                            Vector::x() * (i as Real * 2.0 - 3.0) + Vector::y() * 0.5,
+                        ));
+                world.insert_collider_with_parent(ColliderBuilder::ball(0.5), body)
+            })
+            .collect();
+
+        world.step();
+        let ids: Vec<_> = balls
+            .iter()
+            .map(|ball| world.narrow_phase.contact_pair(ground, *ball).unwrap().id())
+            .collect();
+
+        // The ids don’t change while the balls rest on the ground.
+        for _ in 0..30 {
+            world.step();
+            for (ball, id) in balls.iter().zip(&ids) {
+                let pair = world.narrow_phase.contact_pair_by_id(*id).unwrap();
+                assert!(pair.has_any_active_contact);
+                assert_eq!(pair.id(), *id);
+                assert!(pair.collider1 == *ball || pair.collider2 == *ball);
+            }
+        }
+
+        // Replace the first ball within the same step: its pair id must be invalidated and not
+        // be reused by the new pair, while the other ids remain valid.
+        let parent = world.colliders[balls[0]].parent().unwrap();
+        let _ = world.remove_collider(balls[0]);
+        let new_ball = world.insert_collider_with_parent(ColliderBuilder::ball(0.5), parent);
+        world.step();
+
+        let new_id = world
+            .narrow_phase
+            .contact_pair(ground, new_ball)
+            .unwrap()
+            .id();
+        assert!(world.narrow_phase.contact_pair_by_id(ids[0]).is_none());
+        assert!(!ids.contains(&new_id));
+
+        for (ball, id) in balls.iter().zip(&ids).skip(1) {
+            let pair = world.narrow_phase.contact_pair_by_id(*id).unwrap();
+            assert!(pair.collider1 == *ball || pair.collider2 == *ball);
+        }
+    }
+
+    #[test]
+    fn contact_normals_and_features_dont_depend_on_insertion_order() {
+        use crate::geometry::{ColliderHandle, ContactManifoldExt, FeatureId};
+
+        fn insert_floor(world: &mut PhysicsWorld) -> ColliderHandle {
+            #[cfg(feature = "dim2")]
+            let floor = ColliderBuilder::cuboid(10.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let floor = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+            world.insert_collider(floor.translation(Vector::y() * -0.5))
+        }
+
+        fn insert_box(world: &mut PhysicsWorld) -> ColliderHandle {
+            let body = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y()));
+            #[cfg(feature = "dim2")]
+            let cube = ColliderBuilder::cuboid(0.5, 0.5);
+            #[cfg(feature = "dim3")]
+            let cube = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+            world.insert_collider_with_parent(cube, body)
+        }
+
+        for floor_first in [true, false] {
+            let mut world = PhysicsWorld::default();
+            let (floor, cube) = if floor_first {
+                let floor = insert_floor(&mut world);
+                (floor, insert_box(&mut world))
+            } else {
+                let cube = insert_box(&mut world);
+                (insert_floor(&mut world), cube)
+            };
+
+            for _ in 0..60 {
+                world.step();
+            }
+
+            let pair = world.narrow_phase.contact_pair(floor, cube).unwrap();
+            assert!(pair.has_any_active_contact);
+            // Both colliders have the same user-data so the pair is ordered by handle.
+            assert!(pair.collider1.into_raw_parts() < pair.collider2.into_raw_parts());
+
+            // The feature ids of cuboids are numbered the same way as their support faces.
+            let top_face = world.colliders[floor]
+                .shape()
+                .as_cuboid()
+                .unwrap()
+                .support_face(Vector::y())
+                .fid
+                .unpack();
+            assert!(matches!(top_face, FeatureId::Face(_)));
+            for manifold in &pair.manifolds {
+                let normal = manifold.normal_wrt(floor).unwrap();
+                assert!((normal - Vector::y()).norm() < 1.0e-3);
+                assert_eq!(manifold.normal_wrt(cube), Some(-normal));
+                assert!(!manifold.points.is_empty());
+
+                for i in 0..manifold.points.len() {
+                    assert_eq!(manifold.feature_wrt(i, floor), Some(top_face));
+                }
+
+                assert_eq!(manifold.feature_wrt(manifold.points.len(), floor), None);
+                assert_eq!(manifold.normal_wrt(ColliderHandle::invalid()), None);
+            }
+
+            let contact = pair.find_deepest_world_contact(&world.colliders).unwrap();
+            let floor_feature = if pair.collider1 == floor {
+                contact.feature1()
+            } else {
+                contact.feature2()
+            };
+            assert_eq!(floor_feature, top_face);
+        }
+    }
+
+    /// The variance of the total normal impulse applied by a heightfield to a stack of boxes
+    /// pushed slowly across it, with the given contact matching tolerance.
+    fn sliding_stack_impulse_variance(contact_matching_tolerance: Real) -> Real {
+        let mut world = PhysicsWorld::default();
+        world
+            .integration_parameters
+            .normalized_contact_matching_tolerance = contact_matching_tolerance;
+        // A single iteration, so the solver relies on warmstarting to support the stack.
+        world.integration_parameters.num_solver_iterations = NonZeroUsize::new(1).unwrap();
+        // Small cells so the contacts frequently move from one cell to another.
+        #[cfg(feature = "dim2")]
+        let floor = ColliderBuilder::heightfield(na::DVector::zeros(81), Vector::new(20.0, 1.0));
+        #[cfg(feature = "dim3")]
+        let floor =
+            ColliderBuilder::heightfield(na::DMatrix::zeros(81, 81), Vector::new(20.0, 1.0, 20.0));
+        let floor = world.insert_collider(
+            floor
+                .friction(0.0)
+                .friction_combine_rule(CoefficientCombineRule::Min),
+        );
+
+        let stack: Vec<_> = (0..4)
+            .map(|i| {
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic()
+                        .translation(Vector::x() * -5.0 + Vector::y() * (i as Real + 0.5))
+                        .lock_rotations(),
+                );
+                // Rounded, so they don’t catch on the internal edges of the heightfield.
+                #[cfg(feature = "dim2")]
+                let cube = ColliderBuilder::round_cuboid(0.4, 0.4, 0.1);
+                #[cfg(feature = "dim3")]
+                let cube = ColliderBuilder::round_cuboid(0.4, 0.4, 0.4, 0.1);
+                (body, world.insert_collider_with_parent(cube, body))
+            })
+            .collect();
+        let (bottom, bottom_cube) = stack[0];
+
+        let mut impulses = vec![];
+        for i in 0..480 {
+            let vertical_vel = world.bodies[bottom].linvel().y;
+            world.bodies[bottom].set_linvel(Vector::x() * 0.5 + Vector::y() * vertical_vel, true);
+            world.step();
+
+            // Let the stack settle before measuring.
+            if i >= 120 {
+                let pair = world.narrow_phase.contact_pair(floor, bottom_cube).unwrap();
+                let impulse: Real = pair
+                    .manifolds
+                    .iter()
+                    .flat_map(|m| &m.points)
+                    .map(|pt| pt.data.impulse)
+                    .sum();
+                impulses.push(impulse);
+            }
+        }
+
+        let mean = impulses.iter().sum::<Real>() / impulses.len() as Real;
+        impulses.iter().map(|i| (i - mean).powi(2)).sum::<Real>() / impulses.len() as Real
+    }
+
+    #[test]
+    fn contacts_changing_features_keep_their_impulses() {
+        let matched = sliding_stack_impulse_variance(0.01);
+        let unmatched = sliding_stack_impulse_variance(0.0);
+        assert!(matched < unmatched * 0.5, "{matched} >= {unmatched} * 0.5");
+    }
+
+    #[test]
+    fn cleared_impulses_arent_applied_after_teleporting_a_stack() {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+        let stack: Vec<_> = (0..4)
+            .map(|i| {
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic().translation(Vector::y() * (i as Real + 0.5)),
+                );
+                #[cfg(feature = "dim2")]
+                let cube = ColliderBuilder::cuboid(0.5, 0.5);
+                #[cfg(feature = "dim3")]
+                let cube = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+                world.insert_collider_with_parent(cube, body);
+                body
+            })
+            .collect();
+
+        for _ in 0..120 {
+            world.step();
+        }
+        assert!(world.narrow_phase.contact_pairs().any(|pair| pair
+            .manifolds
+            .iter()
+            .flat_map(|m| &m.points)
+            .any(|pt| pt.data.warmstart_impulse > 0.0)));
+
+        // Teleport the whole stack in the air: the boxes are still touching each other, so the
+        // impulses of their contacts would be warmstarted.
+        for body in &stack {
+            let translation = world.bodies[*body].translation() + Vector::y() * 10.0;
+            world.bodies[*body].set_translation(translation, true);
+            world
+                .narrow_phase
+                .clear_cached_impulses_for_body(*body, &world.bodies);
+        }
+
+        for pair in world.narrow_phase.contact_pairs() {
+            for manifold in &pair.manifolds {
+                assert!(manifold
+                    .points
+                    .iter()
+                    .all(|pt| pt.data.impulse == 0.0 && pt.data.warmstart_impulse == 0.0));
+                assert!(manifold
+                    .data
+                    .solver_contacts
+                    .iter()
+                    .all(|c| c.warmstart_impulse == 0.0));
+            }
+        }
+
+        // The falling boxes don’t push each other.
+        world.step();
+        let gravity_impulse =
+            world.bodies[stack[0]].mass() * 9.81 * world.integration_parameters.dt;
+        for pair in world.narrow_phase.contact_pairs() {
+            assert!(pair.total_impulse_magnitude() < gravity_impulse);
+        }
+
+        world.narrow_phase.clear_all_cached_impulses();
+        assert!(world
+            .narrow_phase
+            .contact_pairs()
+            .all(|pair| pair.total_impulse_magnitude() == 0.0));
+    }
+
+    #[test]
+    fn world_contacts_match_transformed_manifolds() {
+        use crate::geometry::{ActiveCollisionTypes, Cuboid};
+        use crate::math::Isometry;
+        use parry::utils::IsometryOpt;
+
+        #[cfg(feature = "dim2")]
+        let (pos1, pos2, half_extents) = (
+            Isometry::new(Vector::new(1.0, 2.0), 0.3),
+            Isometry::new(Vector::new(1.5, 3.2), -0.4),
+            Vector::new(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (pos1, pos2, half_extents) = (
+            Isometry::new(Vector::new(1.0, 2.0, 3.0), Vector::new(0.3, 0.1, -0.2)),
+            Isometry::new(Vector::new(1.5, 3.2, 3.0), Vector::new(-0.4, 0.2, 0.1)),
+            Vector::new(0.5, 0.5, 0.5),
+        );
+
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        // A compound, so the contacts are expressed relative to its sub-shapes.
+        let parts = vec![
+            (
+                Isometry::from(Vector::y() * 0.6),
+                SharedShape::new(Cuboid::new(half_extents)),
+            ),
+            (
+                Isometry::from(Vector::y() * -0.6),
+                SharedShape::new(Cuboid::new(half_extents)),
+            ),
+        ];
+        let collider1 = world.insert_collider(
+            ColliderBuilder::compound(parts)
+                .position(pos1)
+                .active_collision_types(ActiveCollisionTypes::all()),
+        );
+        let collider2 = world.insert_collider(
+            ColliderBuilder::new(SharedShape::new(Cuboid::new(half_extents)))
+                .position(pos2)
+                .active_collision_types(ActiveCollisionTypes::all()),
+        );
+        world.step();
+
+        let pair = world
+            .narrow_phase
+            .contact_pair(collider1, collider2)
+            .unwrap();
+        let views: Vec<_> = pair.world_contacts(&world.colliders).collect();
+        let expected: Vec<_> = pair
+            .manifolds
+            .iter()
+            .flat_map(|m| m.contacts().iter().map(move |c| (m, c)))
+            .collect();
+        assert!(!views.is_empty());
+        assert_eq!(views.len(), expected.len());
+
+        for (view, (manifold, contact)) in views.iter().zip(expected) {
+            let subshape_pos1 = manifold.subshape_pos1.prepend_to(&pos1);
+            let subshape_pos2 = manifold.subshape_pos2.prepend_to(&pos2);
+            approx::assert_relative_eq!(view.point1(), subshape_pos1 * contact.local_p1);
+            approx::assert_relative_eq!(view.point2(), subshape_pos2 * contact.local_p2);
+            approx::assert_relative_eq!(view.normal(), subshape_pos1 * manifold.local_n1);
+            approx::assert_relative_eq!(view.normal(), manifold.data.normal, epsilon = 1.0e-5);
+            approx::assert_relative_eq!(
+                view.point2(),
+                view.point1() + view.normal() * view.dist(),
+                epsilon = 1.0e-5
+            );
+        }
+
+        let deepest = pair.find_deepest_world_contact(&world.colliders).unwrap();
+        let (_, expected) = pair.find_deepest_contact().unwrap();
+        assert_eq!(deepest.dist(), expected.dist);
+
+        // Removed colliders don’t have any world-space contacts anymore.
+        let mut colliders = world.colliders.clone();
+        colliders.remove(collider2, &mut world.islands, &mut world.bodies, false);
+        assert_eq!(pair.world_contacts(&colliders).count(), 0);
+    }
+
+    #[test]
+    fn solver_contacts_are_reduced_per_manifold() {
+        #[cfg(feature = "dim3")]
+        use na::RealField;
+        use std::num::NonZeroUsize;
+
+        // A crate rotated relative to the ground, so their contact manifold has many points.
+        let crate_on_ground = |max_contacts: usize| {
+            let mut world = PhysicsWorld::default();
+            world.integration_parameters.max_contact_points_per_manifold =
+                NonZeroUsize::new(max_contacts).unwrap();
+            #[cfg(feature = "dim2")]
+            let (ground, crate_) = (
+                ColliderBuilder::cuboid(10.0, 0.5),
+                ColliderBuilder::cuboid(1.0, 1.0),
+            );
+            #[cfg(feature = "dim3")]
+            let (ground, crate_) = (
+                ColliderBuilder::cuboid(1.0, 0.5, 1.0),
+                ColliderBuilder::cuboid(1.0, 1.0, 1.0).rotation(Vector::y() * Real::frac_pi_4()),
+            );
+            let ground = world.insert_collider(ground.translation(Vector::y() * -0.5));
+            let body = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y()));
+            let crate_ = world.insert_collider_with_parent(crate_, body);
+
+            for _ in 0..100 {
+                world.step();
+            }
+
+            let y = world.bodies()[body].translation().y;
+            assert!((y - 1.0).abs() < 0.05, "{y}");
+            (world, ground, crate_)
+        };
+
+        let (world, ground, crate_) = crate_on_ground(usize::MAX);
+        let pair = world.narrow_phase.contact_pair(ground, crate_).unwrap();
+        let num_points = pair.manifolds[0].points.len();
+        #[cfg(feature = "dim3")]
+        assert_eq!(num_points, 8);
+        assert_eq!(pair.manifolds[0].data.solver_contacts.len(), num_points);
+
+        let max_contacts = num_points / 2;
+        let (world, ground, crate_) = crate_on_ground(max_contacts);
+        let pair = world.narrow_phase.contact_pair(ground, crate_).unwrap();
+        let manifold = &pair.manifolds[0];
+        let solver_contacts = &manifold.data.solver_contacts;
+        assert_eq!(solver_contacts.len(), max_contacts);
+
+        // The deepest contact is kept, and the other ones are spread as far as possible.
+        let deepest = manifold.find_deepest_contact().unwrap();
+        assert!(solver_contacts.iter().any(|c| c.dist <= deepest.dist));
+        for (i, a) in solver_contacts.iter().enumerate() {
+            for b in &solver_contacts[i + 1..] {
+                let dist = na::distance(&a.point, &b.point);
+                assert!(dist > 1.0, "{dist}");
+            }
+        }
+
+        // The dropped contacts don’t keep any impulse.
+        let kept: Vec<_> = solver_contacts
+            .iter()
+            .map(|c| c.contact_id as usize)
+            .collect();
+        for (id, contact) in manifold.points.iter().enumerate() {
+            assert_eq!(contact.data.impulse != 0.0, kept.contains(&id));
+        }
+    }
+
+    #[test]
+    fn teleported_contacts_arent_warmstarted() {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let (ground, cube) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cube) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+        // A very heavy box resting on a light one: the contacts under the light box hold both.
+        let bottom = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        world.insert_collider_with_parent(cube.clone(), bottom);
+        let top = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 1.5));
+        world.insert_collider_with_parent(cube.density(1000.0), top);
+
+        for _ in 0..100 {
+            world.step();
+        }
+
+        // Teleport the light box sideways, from under the heavy one. It still rests on the ground
+        // with the same features, but the impulses holding the heavy box no longer apply.
+        let rest_y = world.bodies()[bottom].translation().y;
+        let body = world.bodies.get_mut(bottom).unwrap();
+        body.set_translation(Vector::x() * 3.0 + Vector::y() * rest_y, true);
+        world.step();
+
+        for pair in world
+            .narrow_phase
+            .contact_pairs_with(world.bodies()[bottom].colliders()[0])
+        {
+            for manifold in &pair.manifolds {
+                assert!(manifold.points.iter().all(|pt| pt.data.age <= 1));
+            }
+        }
+
+        // Warmstarting with the stale impulses would launch the light box at about 1m/s.
+        let linvel = world.bodies()[bottom].linvel();
+        assert!(linvel.y < 0.1, "{linvel}");
+    }
+
+    #[test]
+    fn removed_collider_info_is_available_from_stopped_events() {
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        let ground = world.insert_collider(ground.translation(Vector::y() * -0.5));
+        let ball = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        let ball_co = world.insert_collider_with_parent(
+            ColliderBuilder::ball(0.5)
+                .user_data(42)
+                .active_events(ActiveEvents::COLLISION_EVENTS),
+            ball,
+        );
+
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, _) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        world.step_with_events(&(), &events);
+        let started = collision_recv.try_recv().unwrap();
+        assert!(started.started());
+
+        let last_pos = *world.colliders()[ball_co].position();
+        world.remove_collider(ball_co);
+        world.step_with_events(&(), &events);
+
+        let stopped = collision_recv.try_recv().unwrap();
+        assert!(stopped.stopped() && stopped.removed());
+        assert!(collision_recv.try_recv().is_err());
+
+        let removed = if stopped.collider1() == ground {
+            stopped.collider2()
+        } else {
+            stopped.collider1()
+        };
+        assert_eq!(removed, ball_co);
+        assert!(world.colliders().get(removed).is_none());
+        // The other collider of the pair is still there.
+        assert!(world.colliders().get(ground).is_some());
+
+        let info = world.colliders().removed_collider(removed).unwrap();
+        assert_eq!(info.user_data, 42);
+        assert_eq!(info.parent, Some(ball));
+        assert_eq!(info.position, last_pos);
+
+        // The information is dropped after the next step.
+        world.step_with_events(&(), &events);
+        assert!(world.colliders().removed_collider(removed).is_none());
+    }
+}
@@ -0,0 +1,206 @@
+use crate::geometry::{DeformableTriMesh, Shape, Voxels};
+
+/// Additional methods for shapes, to downcast them to the custom shapes defined by Rapier.
+///
+/// Built-in shapes are matched with [`Shape::shape_type`] (cheap to compare),
+/// [`Shape::as_typed_shape`] (giving a reference to the concrete shape), or the direct accessors
+/// like `as_ball` and `as_cuboid` implemented for `dyn Shape`. Rapier’s own shapes are reported
+/// as [`ShapeType::Custom`](crate::geometry::ShapeType::Custom) and
+/// [`TypedShape::Custom`](crate::geometry::TypedShape::Custom) by these: use the methods of this
+/// trait to access them instead.
+pub trait ShapeExt {
+    /// Converts this shape to voxels, if it is one.
+    fn as_voxels(&self) -> Option<&Voxels>;
+
+    /// Converts this shape to a deformable triangle mesh, if it is one.
+    fn as_deformable_trimesh(&self) -> Option<&DeformableTriMesh>;
+}
+
+impl ShapeExt for dyn Shape {
+    fn as_voxels(&self) -> Option<&Voxels> {
+        self.as_shape::<Voxels>()
+    }
+
+    fn as_deformable_trimesh(&self) -> Option<&DeformableTriMesh> {
+        self.as_shape::<DeformableTriMesh>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShapeExt;
+    use crate::geometry::{
+        Cuboid, DeformableTriMesh, HalfSpace, HeightField, RoundCuboid, ShapeType, SharedShape,
+        TypedShape, Voxels,
+    };
+    use crate::math::{Isometry, Point, Real, Vector, DIM};
+
+    fn typed_shape_type(shape: TypedShape) -> ShapeType {
+        match shape {
+            TypedShape::Ball(_) => ShapeType::Ball,
+            TypedShape::Cuboid(_) => ShapeType::Cuboid,
+            TypedShape::Capsule(_) => ShapeType::Capsule,
+            TypedShape::Segment(_) => ShapeType::Segment,
+            TypedShape::Triangle(_) => ShapeType::Triangle,
+            TypedShape::TriMesh(_) => ShapeType::TriMesh,
+            TypedShape::Polyline(_) => ShapeType::Polyline,
+            TypedShape::HalfSpace(_) => ShapeType::HalfSpace,
+            TypedShape::HeightField(_) => ShapeType::HeightField,
+            TypedShape::Compound(_) => ShapeType::Compound,
+            #[cfg(feature = "dim2")]
+            TypedShape::ConvexPolygon(_) => ShapeType::ConvexPolygon,
+            #[cfg(feature = "dim3")]
+            TypedShape::ConvexPolyhedron(_) => ShapeType::ConvexPolyhedron,
+            #[cfg(feature = "dim3")]
+            TypedShape::Cylinder(_) => ShapeType::Cylinder,
+            #[cfg(feature = "dim3")]
+            TypedShape::Cone(_) => ShapeType::Cone,
+            TypedShape::RoundCuboid(_) => ShapeType::RoundCuboid,
+            TypedShape::RoundTriangle(_) => ShapeType::RoundTriangle,
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundCylinder(_) => ShapeType::RoundCylinder,
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundCone(_) => ShapeType::RoundCone,
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundConvexPolyhedron(_) => ShapeType::RoundConvexPolyhedron,
+            #[cfg(feature = "dim2")]
+            TypedShape::RoundConvexPolygon(_) => ShapeType::RoundConvexPolygon,
+            TypedShape::Custom(_) => ShapeType::Custom,
+        }
+    }
+
+    #[test]
+    fn every_shape_round_trips_through_its_typed_shape() {
+        #[cfg(feature = "dim2")]
+        let (a, b, c, heightfield) = (
+            Point::new(-0.5, 0.0),
+            Point::new(0.5, 0.0),
+            Point::new(0.0, 0.5),
+            HeightField::new(na::DVector::zeros(5), Vector::new(1.0, 1.0)),
+        );
+        #[cfg(feature = "dim3")]
+        let (a, b, c, heightfield) = (
+            Point::new(-0.5, 0.0, 0.0),
+            Point::new(0.5, 0.0, 0.0),
+            Point::new(0.0, 0.5, 0.0),
+            HeightField::new(na::DMatrix::zeros(5, 5), Vector::new(1.0, 1.0, 1.0)),
+        );
+        let cube: Vec<_> = (0..1 << DIM)
+            .map(|i| Point::from(Vector::from_fn(|k, _| ((i >> k) & 1) as Real - 0.5)))
+            .collect();
+        let half_extents = Vector::repeat(0.5);
+
+        let mut shapes = vec![
+            (SharedShape::ball(0.5), ShapeType::Ball),
+            (
+                SharedShape::new(Cuboid::new(half_extents)),
+                ShapeType::Cuboid,
+            ),
+            (SharedShape::capsule_y(0.25, 0.25), ShapeType::Capsule),
+            (SharedShape::segment(a, b), ShapeType::Segment),
+            (SharedShape::triangle(a, b, c), ShapeType::Triangle),
+            (
+                SharedShape::trimesh(vec![a, b, c], vec![[0, 1, 2]]),
+                ShapeType::TriMesh,
+            ),
+            (
+                SharedShape::polyline(vec![a, b, c], None),
+                ShapeType::Polyline,
+            ),
+            (
+                SharedShape::new(HalfSpace::new(Vector::y_axis())),
+                ShapeType::HalfSpace,
+            ),
+            (SharedShape::new(heightfield), ShapeType::HeightField),
+            (
+                SharedShape::compound(vec![(Isometry::identity(), SharedShape::ball(0.5))]),
+                ShapeType::Compound,
+            ),
+            (
+                SharedShape::new(RoundCuboid {
+                    inner_shape: Cuboid::new(half_extents * 0.8),
+                    border_radius: 0.1,
+                }),
+                ShapeType::RoundCuboid,
+            ),
+            (
+                SharedShape::round_triangle(a, b, c, 0.1),
+                ShapeType::RoundTriangle,
+            ),
+        ];
+        #[cfg(feature = "dim2")]
+        shapes.extend([
+            (
+                SharedShape::convex_hull(&cube).unwrap(),
+                ShapeType::ConvexPolygon,
+            ),
+            (
+                SharedShape::round_convex_hull(&cube, 0.1).unwrap(),
+                ShapeType::RoundConvexPolygon,
+            ),
+        ]);
+        #[cfg(feature = "dim3")]
+        shapes.extend([
+            (
+                SharedShape::convex_hull(&cube).unwrap(),
+                ShapeType::ConvexPolyhedron,
+            ),
+            (SharedShape::cylinder(0.5, 0.5), ShapeType::Cylinder),
+            (SharedShape::cone(0.5, 0.5), ShapeType::Cone),
+            (
+                SharedShape::round_cylinder(0.4, 0.4, 0.1),
+                ShapeType::RoundCylinder,
+            ),
+            (SharedShape::round_cone(0.4, 0.4, 0.1), ShapeType::RoundCone),
+            (
+                SharedShape::round_convex_hull(&cube, 0.1).unwrap(),
+                ShapeType::RoundConvexPolyhedron,
+            ),
+        ]);
+
+        for (shape, shape_type) in &shapes {
+            assert_eq!(shape.shape_type(), *shape_type);
+            assert_eq!(typed_shape_type(shape.as_typed_shape()), *shape_type);
+            assert_eq!(shape.as_ball().is_some(), *shape_type == ShapeType::Ball);
+            assert_eq!(
+                shape.as_cuboid().is_some(),
+                *shape_type == ShapeType::Cuboid
+            );
+            assert_eq!(
+                shape.as_capsule().is_some(),
+                *shape_type == ShapeType::Capsule
+            );
+            assert_eq!(
+                shape.as_trimesh().is_some(),
+                *shape_type == ShapeType::TriMesh
+            );
+            assert_eq!(
+                shape.as_heightfield().is_some(),
+                *shape_type == ShapeType::HeightField
+            );
+            assert_eq!(
+                shape.as_compound().is_some(),
+                *shape_type == ShapeType::Compound
+            );
+            assert!(shape.as_voxels().is_none());
+            assert!(shape.as_deformable_trimesh().is_none());
+        }
+
+        match shapes[0].0.as_typed_shape() {
+            TypedShape::Ball(ball) => assert_eq!(ball.radius, 0.5),
+            _ => unreachable!(),
+        }
+        assert_eq!(shapes[1].0.as_cuboid().unwrap().half_extents, half_extents);
+
+        let voxels = SharedShape::new(Voxels::new(1.0, &[Point::origin()]));
+        let trimesh = SharedShape::new(DeformableTriMesh::new(vec![a, b, c], vec![[0, 1, 2]]));
+        for shape in [&voxels, &trimesh] {
+            assert_eq!(shape.shape_type(), ShapeType::Custom);
+            assert!(matches!(shape.as_typed_shape(), TypedShape::Custom(_)));
+        }
+        assert_eq!(voxels.as_voxels().unwrap().cell_size(), 1.0);
+        assert!(voxels.as_deformable_trimesh().is_none());
+        assert_eq!(trimesh.as_deformable_trimesh().unwrap().vertices().len(), 3);
+        assert!(trimesh.as_voxels().is_none());
+    }
+}
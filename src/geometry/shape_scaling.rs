@@ -0,0 +1,106 @@
+use crate::geometry::{Compound, HalfSpace, RoundShape, SharedShape, TypedShape};
+use crate::math::{Isometry, Real, Rotation, Vector};
+
+/// Additional methods for shared shapes.
+pub trait SharedShapeExt {
+    /// Computes a copy of this shape scaled by `scale` along each of its local axes.
+    ///
+    /// Cuboids, segments, triangles, convex polygons/polyhedra, triangle meshes, polylines,
+    /// heightfields and half-spaces are scaled exactly. Balls and capsules (and, in 3D,
+    /// cylinders and cones) are scaled exactly if the scale is uniform (or, for cylinders
+    /// and cones, if it is the same along `x` and `z`). Otherwise they can no longer be
+    /// represented by the same primitive and are approximated by the convex hull of
+    /// `num_subdivisions` samples of their boundary, scaled exactly.
+    ///
+    /// Rounded shapes can only be scaled uniformly. The parts of a compound shape are scaled
+    /// recursively, which is only possible if the scale is uniform or if none of them are rotated
+    /// relative to the compound.
+    ///
+    /// Returns `None` if any component of `scale` isn’t strictly positive, if the shape can’t be
+    /// scaled as described above, or if it is a custom shape.
+    fn scaled(&self, scale: &Vector<Real>, num_subdivisions: u32) -> Option<SharedShape>;
+}
+
+impl SharedShapeExt for SharedShape {
+    fn scaled(&self, scale: &Vector<Real>, num_subdivisions: u32) -> Option<SharedShape> {
+        if scale.iter().any(|s| *s <= 0.0) {
+            return None;
+        }
+
+        let uniform_scale = scale.iter().all(|s| *s == scale.x).then_some(scale.x);
+
+        let scaled = match self.as_typed_shape() {
+            TypedShape::Ball(s) => s
+                .scaled(scale, num_subdivisions)?
+                .either(SharedShape::new, SharedShape::new),
+            TypedShape::Cuboid(s) => SharedShape::new(s.scaled(scale)),
+            TypedShape::Capsule(s) => s
+                .scaled(scale, num_subdivisions)?
+                .either(SharedShape::new, SharedShape::new),
+            TypedShape::Segment(s) => SharedShape::new(s.scaled(scale)),
+            TypedShape::Triangle(s) => SharedShape::new(s.scaled(scale)),
+            TypedShape::TriMesh(s) => SharedShape::new(s.clone().scaled(scale)),
+            TypedShape::Polyline(s) => SharedShape::new(s.clone().scaled(scale)),
+            TypedShape::HalfSpace(s) => SharedShape::new(HalfSpace::scaled(*s, scale)?),
+            TypedShape::HeightField(s) => SharedShape::new(s.clone().scaled(scale)),
+            TypedShape::Compound(s) => {
+                let mut parts = Vec::with_capacity(s.shapes().len());
+
+                for (pos, part) in s.shapes() {
+                    if uniform_scale.is_none() && pos.rotation != Rotation::identity() {
+                        return None;
+                    }
+
+                    let translation = pos.translation.vector.component_mul(scale);
+                    let pos = Isometry::from_parts(translation.into(), pos.rotation);
+                    parts.push((pos, part.scaled(scale, num_subdivisions)?));
+                }
+
+                SharedShape::new(Compound::new(parts))
+            }
+            #[cfg(feature = "dim2")]
+            TypedShape::ConvexPolygon(s) => SharedShape::new(s.clone().scaled(scale)?),
+            #[cfg(feature = "dim3")]
+            TypedShape::ConvexPolyhedron(s) => SharedShape::new(s.clone().scaled(scale)?),
+            #[cfg(feature = "dim3")]
+            TypedShape::Cylinder(s) => s
+                .scaled(scale, num_subdivisions)?
+                .either(SharedShape::new, SharedShape::new),
+            #[cfg(feature = "dim3")]
+            TypedShape::Cone(s) => s
+                .scaled(scale, num_subdivisions)?
+                .either(SharedShape::new, SharedShape::new),
+            TypedShape::RoundCuboid(s) => SharedShape::new(RoundShape {
+                inner_shape: s.inner_shape.scaled(scale),
+                border_radius: s.border_radius * uniform_scale?,
+            }),
+            TypedShape::RoundTriangle(s) => SharedShape::new(RoundShape {
+                inner_shape: s.inner_shape.scaled(scale),
+                border_radius: s.border_radius * uniform_scale?,
+            }),
+            #[cfg(feature = "dim2")]
+            TypedShape::RoundConvexPolygon(s) => SharedShape::new(RoundShape {
+                border_radius: s.border_radius * uniform_scale?,
+                inner_shape: s.inner_shape.clone().scaled(scale)?,
+            }),
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundConvexPolyhedron(s) => SharedShape::new(RoundShape {
+                border_radius: s.border_radius * uniform_scale?,
+                inner_shape: s.inner_shape.clone().scaled(scale)?,
+            }),
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundCylinder(s) => SharedShape::new(RoundShape {
+                border_radius: s.border_radius * uniform_scale?,
+                inner_shape: s.inner_shape.scaled(scale, num_subdivisions)?.left()?,
+            }),
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundCone(s) => SharedShape::new(RoundShape {
+                border_radius: s.border_radius * uniform_scale?,
+                inner_shape: s.inner_shape.scaled(scale, num_subdivisions)?.left()?,
+            }),
+            TypedShape::Custom(_) => return None,
+        };
+
+        Some(scaled)
+    }
+}
@@ -0,0 +1,1072 @@
+use crate::math::{Isometry, Point, Real, Vector, MAX_MANIFOLD_POINTS};
+use crate::utils::SimdBasis;
+use na::Unit;
+use parry::query::details::{
+    contact_manifold_ball_ball, contact_manifolds_composite_shape_composite_shape,
+    contact_manifolds_composite_shape_shape, contact_manifolds_heightfield_composite_shape,
+    contact_manifolds_heightfield_shape_shapes, contact_manifolds_trimesh_shape_shapes,
+    contact_support_map_support_map, ContactManifoldsWorkspace, NormalConstraints,
+};
+use parry::query::{
+    ClosestPoints, Contact, ContactManifold, DefaultQueryDispatcher, NonlinearRigidMotion,
+    PersistentQueryDispatcher, PointProjection, PointQuery, QueryDispatcher, ShapeCastHit,
+    ShapeCastOptions, TrackedContact, Unsupported,
+};
+use parry::shape::{Ball, FeatureId, PackedFeatureId, Shape, ShapeType, SupportMap, TypedShape};
+#[cfg(debug_assertions)]
+use std::sync::Mutex;
+
+/// The tangent of the angle by which the contact normal is tilted to find the extremities of the
+/// contact area between two support-mapped shapes.
+const NORMAL_PERTURBATION: Real = 0.1;
+
+/// The distance (relative to the distance to the deepest contact) by which a surface may deviate
+/// from the contact plane while still being considered flat.
+const FLATNESS_TOLERANCE: Real = 1.0e-2;
+
+/// The types of the shapes provided by `parry`, i.e., all the shape types except
+/// [`ShapeType::Custom`].
+pub(crate) const BUILTIN_SHAPE_TYPES: &[ShapeType] = &[
+    ShapeType::Ball,
+    ShapeType::Cuboid,
+    ShapeType::Capsule,
+    ShapeType::Segment,
+    ShapeType::Triangle,
+    ShapeType::TriMesh,
+    ShapeType::Polyline,
+    ShapeType::HalfSpace,
+    ShapeType::HeightField,
+    ShapeType::Compound,
+    #[cfg(feature = "dim2")]
+    ShapeType::ConvexPolygon,
+    #[cfg(feature = "dim3")]
+    ShapeType::ConvexPolyhedron,
+    #[cfg(feature = "dim3")]
+    ShapeType::Cylinder,
+    #[cfg(feature = "dim3")]
+    ShapeType::Cone,
+    ShapeType::RoundCuboid,
+    ShapeType::RoundTriangle,
+    #[cfg(feature = "dim3")]
+    ShapeType::RoundCylinder,
+    #[cfg(feature = "dim3")]
+    ShapeType::RoundCone,
+    #[cfg(feature = "dim3")]
+    ShapeType::RoundConvexPolyhedron,
+    #[cfg(feature = "dim2")]
+    ShapeType::RoundConvexPolygon,
+];
+
+/// The pairs of built-in shape types (in any order) the [`SupportMapFallbackDispatcher`] can’t
+/// generate contact manifolds for.
+const UNSUPPORTED_PAIRS: &[(ShapeType, ShapeType)] = &[
+    (ShapeType::HalfSpace, ShapeType::HalfSpace),
+    (ShapeType::HeightField, ShapeType::HeightField),
+];
+
+/// The pairs of shape types for which a failed contact manifold generation was already reported.
+#[cfg(debug_assertions)]
+static REPORTED_PAIRS: Mutex<Vec<(ShapeType, ShapeType)>> = Mutex::new(Vec::new());
+
+/// A query dispatcher generating contact manifolds between user-defined convex shapes.
+///
+/// It behaves like `parry`’s [`DefaultQueryDispatcher`] except for pairs of convex shapes
+/// this dispatcher doesn’t know how to generate contact manifolds for, like custom shapes
+/// that only implement [`SupportMap`] (and return themselves from [`Shape::as_support_map`]).
+/// For these, the deepest contact is computed with GJK/EPA, and additional contact points at
+/// the extremities of the contact area are found by tilting the contact normal slightly. This
+/// lets, e.g., a flat-bottomed custom shape rest stably on the ground.
+///
+/// This also applies to the parts of composite shapes (compound shapes, triangle meshes,
+/// heightfields) colliding with these custom shapes. This is the default dispatcher of the
+/// [`NarrowPhase`](crate::geometry::NarrowPhase). Other queries (ray-casts, shape-casts, etc.)
+/// are forwarded to the [`DefaultQueryDispatcher`] which already supports any pair of
+/// support-mapped shapes.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SupportMapFallbackDispatcher;
+
+impl QueryDispatcher for SupportMapFallbackDispatcher {
+    fn intersection_test(
+        &self,
+        pos12: &Isometry<Real>,
+        shape1: &dyn Shape,
+        shape2: &dyn Shape,
+    ) -> Result<bool, Unsupported> {
+        DefaultQueryDispatcher.intersection_test(pos12, shape1, shape2)
+    }
+
+    fn distance(
+        &self,
+        pos12: &Isometry<Real>,
+        shape1: &dyn Shape,
+        shape2: &dyn Shape,
+    ) -> Result<Real, Unsupported> {
+        DefaultQueryDispatcher.distance(pos12, shape1, shape2)
+    }
+
+    fn contact(
+        &self,
+        pos12: &Isometry<Real>,
+        shape1: &dyn Shape,
+        shape2: &dyn Shape,
+        prediction: Real,
+    ) -> Result<Option<Contact>, Unsupported> {
+        DefaultQueryDispatcher.contact(pos12, shape1, shape2, prediction)
+    }
+
+    fn closest_points(
+        &self,
+        pos12: &Isometry<Real>,
+        shape1: &dyn Shape,
+        shape2: &dyn Shape,
+        max_dist: Real,
+    ) -> Result<ClosestPoints, Unsupported> {
+        DefaultQueryDispatcher.closest_points(pos12, shape1, shape2, max_dist)
+    }
+
+    fn cast_shapes(
+        &self,
+        pos12: &Isometry<Real>,
+        local_vel12: &Vector<Real>,
+        shape1: &dyn Shape,
+        shape2: &dyn Shape,
+        options: ShapeCastOptions,
+    ) -> Result<Option<ShapeCastHit>, Unsupported> {
+        DefaultQueryDispatcher.cast_shapes(pos12, local_vel12, shape1, shape2, options)
+    }
+
+    fn cast_shapes_nonlinear(
+        &self,
+        motion1: &NonlinearRigidMotion,
+        shape1: &dyn Shape,
+        motion2: &NonlinearRigidMotion,
+        shape2: &dyn Shape,
+        start_time: Real,
+        end_time: Real,
+        stop_at_penetration: bool,
+    ) -> Result<Option<ShapeCastHit>, Unsupported> {
+        DefaultQueryDispatcher.cast_shapes_nonlinear(
+            motion1,
+            shape1,
+            motion2,
+            shape2,
+            start_time,
+            end_time,
+            stop_at_penetration,
+        )
+    }
+}
+
+impl<ManifoldData, ContactData> PersistentQueryDispatcher<ManifoldData, ContactData>
+    for SupportMapFallbackDispatcher
+where
+    ManifoldData: Default + Clone,
+    ContactData: Default + Copy,
+{
+    fn contact_manifolds(
+        &self,
+        pos12: &Isometry<Real>,
+        shape1: &dyn Shape,
+        shape2: &dyn Shape,
+        prediction: Real,
+        manifolds: &mut Vec<ContactManifold<ManifoldData, ContactData>>,
+        workspace: &mut Option<ContactManifoldsWorkspace>,
+    ) -> Result<(), Unsupported> {
+        if has_ball_fast_path(shape1.shape_type(), shape2.shape_type()) {
+            if manifolds.is_empty() {
+                manifolds.push(ContactManifold::new());
+            }
+
+            contact_manifold_with_ball(pos12, shape1, shape2, prediction, &mut manifolds[0]);
+            return Ok(());
+        }
+
+        if !is_supported_pair(shape1.shape_type(), shape2.shape_type()) {
+            report_unsupported_pair(shape1.shape_type(), shape2.shape_type());
+            return Err(Unsupported);
+        }
+
+        // NOTE: this mirrors the `DefaultQueryDispatcher`, but with `self` as the dispatcher
+        //       of the parts of composite shapes.
+        let composite1 = shape1.as_composite_shape();
+        let composite2 = shape2.as_composite_shape();
+
+        if let (Some(composite1), Some(composite2)) = (composite1, composite2) {
+            contact_manifolds_composite_shape_composite_shape(
+                self, pos12, composite1, composite2, prediction, manifolds, workspace,
+            );
+            return Ok(());
+        }
+
+        match (shape1.shape_type(), shape2.shape_type()) {
+            (ShapeType::HeightField, _) => {
+                if let Some(composite2) = composite2 {
+                    contact_manifolds_heightfield_composite_shape(
+                        self,
+                        pos12,
+                        &pos12.inverse(),
+                        shape1.as_heightfield().unwrap(),
+                        composite2,
+                        prediction,
+                        manifolds,
+                        workspace,
+                        false,
+                    )
+                } else {
+                    contact_manifolds_heightfield_shape_shapes(
+                        self, pos12, shape1, shape2, prediction, manifolds, workspace,
+                    );
+                }
+            }
+            (_, ShapeType::HeightField) => {
+                if let Some(composite1) = composite1 {
+                    contact_manifolds_heightfield_composite_shape(
+                        self,
+                        &pos12.inverse(),
+                        pos12,
+                        shape2.as_heightfield().unwrap(),
+                        composite1,
+                        prediction,
+                        manifolds,
+                        workspace,
+                        true,
+                    )
+                } else {
+                    contact_manifolds_heightfield_shape_shapes(
+                        self, pos12, shape1, shape2, prediction, manifolds, workspace,
+                    );
+                }
+            }
+            // NOTE: this must come after the heightfield cases, the triangles of a triangle mesh
+            //       can’t be matched against a heightfield.
+            (ShapeType::TriMesh, _) | (_, ShapeType::TriMesh) => {
+                contact_manifolds_trimesh_shape_shapes(
+                    self, pos12, shape1, shape2, prediction, manifolds, workspace,
+                );
+            }
+            _ => {
+                if let Some(composite1) = composite1 {
+                    contact_manifolds_composite_shape_shape(
+                        self, pos12, composite1, shape2, prediction, manifolds, workspace, false,
+                    );
+                } else if let Some(composite2) = composite2 {
+                    contact_manifolds_composite_shape_shape(
+                        self,
+                        &pos12.inverse(),
+                        composite2,
+                        shape1,
+                        prediction,
+                        manifolds,
+                        workspace,
+                        true,
+                    );
+                } else {
+                    if manifolds.is_empty() {
+                        manifolds.push(ContactManifold::new());
+                    }
+
+                    return self.contact_manifold_convex_convex(
+                        pos12,
+                        shape1,
+                        shape2,
+                        None,
+                        None,
+                        prediction,
+                        &mut manifolds[0],
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn contact_manifold_convex_convex(
+        &self,
+        pos12: &Isometry<Real>,
+        shape1: &dyn Shape,
+        shape2: &dyn Shape,
+        normal_constraints1: Option<&dyn NormalConstraints>,
+        normal_constraints2: Option<&dyn NormalConstraints>,
+        prediction: Real,
+        manifold: &mut ContactManifold<ManifoldData, ContactData>,
+    ) -> Result<(), Unsupported> {
+        if normal_constraints1.is_none()
+            && normal_constraints2.is_none()
+            && has_ball_fast_path(shape1.shape_type(), shape2.shape_type())
+        {
+            contact_manifold_with_ball(pos12, shape1, shape2, prediction, manifold);
+            return Ok(());
+        }
+
+        let result = DefaultQueryDispatcher.contact_manifold_convex_convex(
+            pos12,
+            shape1,
+            shape2,
+            normal_constraints1,
+            normal_constraints2,
+            prediction,
+            manifold,
+        );
+
+        if result.is_err() {
+            if let (Some(sm1), Some(sm2)) = (shape1.as_support_map(), shape2.as_support_map()) {
+                contact_manifold_support_map_support_map(
+                    pos12, shape1, sm1, shape2, sm2, prediction, manifold,
+                );
+                return Ok(());
+            }
+
+            report_unsupported_pair(shape1.shape_type(), shape2.shape_type());
+        }
+
+        result
+    }
+}
+
+/// Whether the [`SupportMapFallbackDispatcher`] can generate contact manifolds between built-in
+/// shapes of the given types.
+///
+/// Custom shapes are not listed here: they are supported if they are composite shapes or
+/// support-mapped shapes.
+pub(crate) fn is_supported_pair(type1: ShapeType, type2: ShapeType) -> bool {
+    !UNSUPPORTED_PAIRS
+        .iter()
+        .any(|pair| *pair == (type1, type2) || *pair == (type2, type1))
+}
+
+/// Logs a warning, in debug builds, the first time contacts between shapes of the given types
+/// can’t be generated.
+fn report_unsupported_pair(type1: ShapeType, type2: ShapeType) {
+    #[cfg(debug_assertions)]
+    {
+        let mut reported = REPORTED_PAIRS.lock().unwrap();
+        if !reported
+            .iter()
+            .any(|pair| *pair == (type1, type2) || *pair == (type2, type1))
+        {
+            reported.push((type1, type2));
+            log::warn!(
+                "Contacts between shapes of types {type1:?} and {type2:?} are not supported: \
+                 colliders with these shapes will go through each other."
+            );
+        }
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = (type1, type2);
+}
+
+/// Whether the contact manifold between shapes of the given types is computed by
+/// [`contact_manifold_with_ball`].
+fn has_ball_fast_path(type1: ShapeType, type2: ShapeType) -> bool {
+    matches!(
+        (type1, type2),
+        (
+            ShapeType::Ball,
+            ShapeType::Ball | ShapeType::HalfSpace | ShapeType::Cuboid
+        ) | (ShapeType::HalfSpace | ShapeType::Cuboid, ShapeType::Ball)
+    )
+}
+
+/// Computes the single-point contact manifold between a ball and a ball, half-space, or cuboid.
+///
+/// This generates the same contacts as the [`DefaultQueryDispatcher`], but skips its generic
+/// dispatch and the dynamic dispatch of the point projections, which dominate the contact
+/// generation of scenes made of many small balls.
+fn contact_manifold_with_ball<ManifoldData, ContactData>(
+    pos12: &Isometry<Real>,
+    shape1: &dyn Shape,
+    shape2: &dyn Shape,
+    prediction: Real,
+    manifold: &mut ContactManifold<ManifoldData, ContactData>,
+) where
+    ContactData: Default + Copy,
+{
+    match (shape1.as_ball(), shape2.as_ball()) {
+        (Some(ball1), Some(ball2)) => {
+            contact_manifold_ball_ball(pos12, ball1, ball2, prediction, manifold)
+        }
+        (None, Some(ball2)) => {
+            let center2 = Point::from(pos12.translation.vector);
+            let (proj1, fid1) = project_on_halfspace_or_cuboid(shape1, &center2);
+            contact_manifold_projection_ball(pos12, proj1, fid1, ball2, prediction, manifold, false)
+        }
+        (Some(ball1), None) => {
+            let pos21 = pos12.inverse();
+            let center1 = Point::from(pos21.translation.vector);
+            let (proj2, fid2) = project_on_halfspace_or_cuboid(shape2, &center1);
+            contact_manifold_projection_ball(&pos21, proj2, fid2, ball1, prediction, manifold, true)
+        }
+        (None, None) => unreachable!(),
+    }
+}
+
+/// Projects a point on a half-space or a cuboid, without dynamic dispatch.
+fn project_on_halfspace_or_cuboid(
+    shape: &dyn Shape,
+    point: &Point<Real>,
+) -> (PointProjection, FeatureId) {
+    match shape.as_typed_shape() {
+        TypedShape::HalfSpace(halfspace) => halfspace.project_local_point_and_get_feature(point),
+        TypedShape::Cuboid(cuboid) => cuboid.project_local_point_and_get_feature(point),
+        _ => unreachable!(),
+    }
+}
+
+/// Computes the contact manifold between a ball and a convex shape, given the projection of
+/// the ball center on that convex shape.
+///
+/// This is the part of `parry`’s `contact_manifold_convex_ball` following the point
+/// projection, without normal constraints.
+fn contact_manifold_projection_ball<ManifoldData, ContactData>(
+    pos12: &Isometry<Real>,
+    proj1: PointProjection,
+    fid1: FeatureId,
+    ball2: &Ball,
+    prediction: Real,
+    manifold: &mut ContactManifold<ManifoldData, ContactData>,
+    flipped: bool,
+) where
+    ContactData: Default + Copy,
+{
+    let dpos = pos12.translation.vector - proj1.point.coords;
+
+    // Same fallbacks as `parry` if the ball center lies exactly on the surface.
+    let (mut local_n1, mut dist) = Unit::try_new_and_get(dpos, 0.0).unwrap_or_else(|| {
+        (
+            Unit::try_new(pos12.translation.vector, 0.0).unwrap_or_else(Vector::x_axis),
+            0.0,
+        )
+    });
+
+    if proj1.is_inside {
+        local_n1 = -local_n1;
+        dist = -dist;
+    }
+
+    if dist > ball2.radius + prediction {
+        manifold.clear();
+        return;
+    }
+
+    let local_n2 = pos12.inverse_transform_vector(&-*local_n1);
+    let contact = TrackedContact::flipped(
+        proj1.point,
+        Point::from(local_n2 * ball2.radius),
+        fid1.into(),
+        PackedFeatureId::face(0),
+        dist - ball2.radius,
+        flipped,
+    );
+
+    if manifold.points.len() == 1 {
+        // Only copy the geometry to keep the warmstart impulses.
+        manifold.points[0].copy_geometry_from(contact);
+    } else {
+        manifold.clear();
+        manifold.points.push(contact);
+    }
+
+    if flipped {
+        manifold.local_n1 = local_n2;
+        manifold.local_n2 = *local_n1;
+    } else {
+        manifold.local_n1 = *local_n1;
+        manifold.local_n2 = local_n2;
+    }
+}
+
+/// Computes the contact manifold between two support-mapped shapes.
+///
+/// The deepest contact is computed with GJK/EPA. Then, the support points of each shape along
+/// slightly tilted contact normals are projected on the tangent plane of the other shape, and
+/// kept if they actually lie on that other shape (i.e. if it is flat there).
+fn contact_manifold_support_map_support_map<ManifoldData, ContactData>(
+    pos12: &Isometry<Real>,
+    shape1: &dyn Shape,
+    sm1: &dyn SupportMap,
+    shape2: &dyn Shape,
+    sm2: &dyn SupportMap,
+    prediction: Real,
+    manifold: &mut ContactManifold<ManifoldData, ContactData>,
+) where
+    ContactData: Default + Copy,
+{
+    let old_points = std::mem::take(&mut manifold.points);
+
+    let Some(contact) = contact_support_map_support_map(pos12, sm1, sm2, prediction) else {
+        return;
+    };
+
+    let normal1 = *contact.normal1;
+    manifold.local_n1 = normal1;
+    manifold.local_n2 = *contact.normal2;
+    let mut points = vec![TrackedContact::new(
+        contact.point1,
+        contact.point2,
+        PackedFeatureId::vertex(0),
+        PackedFeatureId::vertex(0),
+        contact.dist,
+    )];
+
+    #[cfg(feature = "dim2")]
+    let tilts = {
+        let [t] = normal1.orthonormal_basis();
+        [t, -t]
+    };
+    #[cfg(feature = "dim3")]
+    let tilts = {
+        let [t1, t2] = normal1.orthonormal_basis();
+        let diag1 = (t1 + t2) / Real::sqrt(2.0);
+        let diag2 = (t1 - t2) / Real::sqrt(2.0);
+        [t1, -t1, t2, -t2, diag1, -diag1, diag2, -diag2]
+    };
+
+    // Points closer than this to an already-selected contact are ignored.
+    let radius1 = shape1.compute_local_bounding_sphere().radius;
+    let radius2 = shape2.compute_local_bounding_sphere().radius;
+    let merge_distance = radius1.min(radius2) * 1.0e-2;
+    let point2_1 = pos12 * contact.point2;
+    let mut candidates = vec![];
+
+    for (k, tilt) in tilts.iter().enumerate() {
+        let dir = normal1 + tilt * NORMAL_PERTURBATION;
+
+        // A point of shape 2 projected on the tangent plane of shape 1 at the deepest contact.
+        let local_p2 = sm2.local_support_point(&pos12.inverse_transform_vector(&-dir));
+        let p2_1 = pos12 * local_p2;
+        let dist = (p2_1 - contact.point1).dot(&normal1);
+        let p1 = p2_1 - normal1 * dist;
+        let tolerance = flatness_tolerance(&p1, &contact.point1, prediction);
+        if dist <= tolerance && shape1.distance_to_local_point(&p1, true) <= tolerance {
+            let fid = PackedFeatureId::vertex(k as u32 + 1);
+            candidates.push(TrackedContact::new(p1, local_p2, fid, fid, dist));
+        }
+
+        // A point of shape 1 projected on the tangent plane of shape 2 at the deepest contact.
+        let p1 = sm1.local_support_point(&dir);
+        let dist = (point2_1 - p1).dot(&normal1);
+        let local_p2 = pos12.inverse_transform_point(&(p1 + normal1 * dist));
+        let tolerance = flatness_tolerance(&local_p2, &contact.point2, prediction);
+        if dist <= tolerance && shape2.distance_to_local_point(&local_p2, true) <= tolerance {
+            let fid = PackedFeatureId::vertex((k + tilts.len()) as u32 + 1);
+            candidates.push(TrackedContact::new(p1, local_p2, fid, fid, dist));
+        }
+    }
+
+    select_contact_area_extremities(&mut points, &mut candidates, merge_distance);
+    manifold.points.extend(points);
+    manifold.match_contacts(&old_points);
+}
+
+/// The distance below which `point`, on the tangent plane at `contact_point`, is considered
+/// in contact with a nearly-flat surface.
+fn flatness_tolerance(point: &Point<Real>, contact_point: &Point<Real>, prediction: Real) -> Real {
+    prediction + na::distance(point, contact_point) * FLATNESS_TOLERANCE
+}
+
+/// Moves to `points` (which already contains the deepest contact) the candidates spanning
+/// the largest contact area, up to [`MAX_MANIFOLD_POINTS`] points in total.
+fn select_contact_area_extremities<ContactData>(
+    points: &mut Vec<TrackedContact<ContactData>>,
+    candidates: &mut Vec<TrackedContact<ContactData>>,
+    merge_distance: Real,
+) {
+    while points.len() < MAX_MANIFOLD_POINTS && !candidates.is_empty() {
+        // Pick the candidate farthest from the points already selected.
+        let mut best = 0;
+        let mut best_score = -1.0;
+
+        for (i, candidate) in candidates.iter().enumerate() {
+            let score = points
+                .iter()
+                .map(|pt| na::distance_squared(&pt.local_p1, &candidate.local_p1))
+                .fold(Real::MAX, Real::min);
+
+            if score > best_score {
+                best = i;
+                best_score = score;
+            }
+        }
+
+        if best_score <= merge_distance * merge_distance {
+            break;
+        }
+
+        points.push(candidates.swap_remove(best));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry::{Aabb, Cuboid, Ray, RayIntersection};
+    use crate::math::{Point, Real, Vector};
+    use crate::prelude::*;
+    use na::RealField;
+    use parry::bounding_volume::BoundingSphere;
+    use parry::mass_properties::MassProperties;
+    use parry::query::details::{
+        local_point_projection_on_support_map, local_ray_intersection_with_support_map_with_params,
+    };
+    use parry::query::gjk::VoronoiSimplex;
+    use parry::query::{PointProjection, PointQuery, RayCast};
+    use parry::shape::{FeatureId, Shape, ShapeType, SupportMap, TypedShape};
+
+    /// A superellipse (2D) or superellipsoid (3D): the points `x` such that
+    /// `sum(|x_i / half_extents_i|^exponent) <= 1`.
+    #[derive(Copy, Clone, Debug)]
+    struct Superellipsoid {
+        half_extents: Vector<Real>,
+        exponent: Real,
+        /// Whether this shape exposes its support map to the query dispatcher.
+        support_mapped: bool,
+    }
+
+    impl SupportMap for Superellipsoid {
+        fn local_support_point(&self, dir: &Vector<Real>) -> Point<Real> {
+            // Maximizes `dir.dot(x)` with Hölder’s inequality.
+            let q = self.exponent / (self.exponent - 1.0);
+            let scaled_dir = dir.component_mul(&self.half_extents);
+            let norm = scaled_dir.map(|c| c.abs().powf(q)).sum().powf(1.0 / q);
+            if norm == 0.0 {
+                return Point::origin();
+            }
+
+            let coords = scaled_dir.map(|c| c.signum() * (c.abs() / norm).powf(q - 1.0));
+            Point::from(coords.component_mul(&self.half_extents))
+        }
+    }
+
+    impl RayCast for Superellipsoid {
+        fn cast_local_ray_and_get_normal(
+            &self,
+            ray: &Ray,
+            max_toi: Real,
+            solid: bool,
+        ) -> Option<RayIntersection> {
+            local_ray_intersection_with_support_map_with_params(
+                self,
+                &mut VoronoiSimplex::new(),
+                ray,
+                max_toi,
+                solid,
+            )
+        }
+    }
+
+    impl PointQuery for Superellipsoid {
+        fn project_local_point(&self, point: &Point<Real>, solid: bool) -> PointProjection {
+            local_point_projection_on_support_map(self, &mut VoronoiSimplex::new(), point, solid)
+        }
+
+        fn project_local_point_and_get_feature(
+            &self,
+            point: &Point<Real>,
+        ) -> (PointProjection, FeatureId) {
+            (self.project_local_point(point, false), FeatureId::Unknown)
+        }
+    }
+
+    impl Shape for Superellipsoid {
+        fn compute_local_aabb(&self) -> Aabb {
+            Aabb::from_half_extents(Point::origin(), self.half_extents)
+        }
+
+        fn compute_local_bounding_sphere(&self) -> BoundingSphere {
+            BoundingSphere::new(Point::origin(), self.half_extents.norm())
+        }
+
+        fn clone_box(&self) -> Box<dyn Shape> {
+            Box::new(*self)
+        }
+
+        fn mass_properties(&self, density: Real) -> MassProperties {
+            // Good enough for a large exponent.
+            Cuboid::new(self.half_extents).mass_properties(density)
+        }
+
+        fn shape_type(&self) -> ShapeType {
+            ShapeType::Custom
+        }
+
+        fn as_typed_shape(&self) -> TypedShape<'_> {
+            TypedShape::Custom(100)
+        }
+
+        fn ccd_thickness(&self) -> Real {
+            self.half_extents.min()
+        }
+
+        fn ccd_angular_thickness(&self) -> Real {
+            Real::frac_pi_2()
+        }
+
+        fn as_support_map(&self) -> Option<&dyn SupportMap> {
+            self.support_mapped.then_some(self as &dyn SupportMap)
+        }
+    }
+
+    #[test]
+    fn custom_convex_shape_stacked_on_cuboid() {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+        let cuboid = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        #[cfg(feature = "dim2")]
+        let cuboid_shape = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let cuboid_shape = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        world.insert_collider_with_parent(cuboid_shape, cuboid);
+
+        let shape = Superellipsoid {
+            half_extents: Vector::repeat(0.4),
+            exponent: 10.0,
+            support_mapped: true,
+        };
+        let custom = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 1.5)
+                .ccd_enabled(true),
+        );
+        world.insert_collider_with_parent(ColliderBuilder::new(SharedShape::new(shape)), custom);
+
+        for _ in 0..300 {
+            world.step();
+        }
+
+        // The custom shape rests on the cuboid without tilting nor sinking into it.
+        let rb = &world.bodies[custom];
+        assert!(
+            (rb.translation().y - 1.4).abs() < 1.0e-2,
+            "{}",
+            rb.translation()
+        );
+        assert!(rb.translation().x.abs() < 1.0e-2, "{}", rb.translation());
+        assert!(rb.rotation().angle().abs() < 1.0e-2);
+        assert!(rb.linvel().norm() < 1.0e-2);
+
+        // Scene queries go through the same shape implementation.
+        let ray = Ray::new(Point::from(Vector::y() * 5.0), -Vector::y());
+        let hit = world.cast_ray(&ray, Real::MAX, true, QueryFilter::default());
+        let (handle, toi) = hit.expect("the ray should hit the custom shape");
+        assert_eq!(world.colliders[handle].parent(), Some(custom));
+        assert!((toi - 5.0 + 1.8).abs() < 1.0e-2, "{toi}");
+    }
+
+    #[test]
+    fn ball_contacts_match_the_default_dispatcher() {
+        use super::SupportMapFallbackDispatcher;
+        use parry::query::{ContactManifold, DefaultQueryDispatcher, PersistentQueryDispatcher};
+        use parry::shape::{Ball, HalfSpace};
+
+        let ball = SharedShape::new(Ball::new(0.5));
+        let shapes = [
+            SharedShape::new(Ball::new(0.3)),
+            SharedShape::new(HalfSpace::new(Vector::y_axis())),
+            #[cfg(feature = "dim2")]
+            SharedShape::cuboid(1.0, 0.5),
+            #[cfg(feature = "dim3")]
+            SharedShape::cuboid(1.0, 0.5, 0.7),
+        ];
+
+        let mut num_contacts = 0;
+        for shape in &shapes {
+            for (shape1, shape2) in [(&ball, shape), (shape, &ball)] {
+                for i in 0..1000 {
+                    // Relative positions on a coarse grid, with centers inside the cuboid.
+                    let t = |k: usize| (((i / k) % 10) as Real - 4.5) * 0.3;
+                    #[cfg(feature = "dim2")]
+                    let pos12 = Isometry::new(Vector::new(t(1), t(10)), t(100));
+                    #[cfg(feature = "dim3")]
+                    let pos12 = Isometry::new(
+                        Vector::new(t(1), t(10), t(100)),
+                        Vector::new(t(100), t(1), t(10)),
+                    );
+
+                    let mut expected: Vec<ContactManifold<(), ()>> = vec![];
+                    let mut actual: Vec<ContactManifold<(), ()>> = vec![];
+                    DefaultQueryDispatcher
+                        .contact_manifolds(
+                            &pos12,
+                            &**shape1,
+                            &**shape2,
+                            0.1,
+                            &mut expected,
+                            &mut None,
+                        )
+                        .unwrap();
+                    SupportMapFallbackDispatcher
+                        .contact_manifolds(
+                            &pos12,
+                            &**shape1,
+                            &**shape2,
+                            0.1,
+                            &mut actual,
+                            &mut None,
+                        )
+                        .unwrap();
+
+                    assert_eq!(expected.len(), actual.len());
+                    for (expected, actual) in expected.iter().zip(actual.iter()) {
+                        assert_eq!(expected.points.len(), actual.points.len());
+                        if !expected.points.is_empty() {
+                            assert_eq!(expected.local_n1, actual.local_n1);
+                            assert_eq!(expected.local_n2, actual.local_n2);
+                        }
+
+                        for (pt1, pt2) in expected.points.iter().zip(actual.points.iter()) {
+                            assert_eq!(pt1.local_p1, pt2.local_p1);
+                            assert_eq!(pt1.local_p2, pt2.local_p2);
+                            assert_eq!(pt1.fid1, pt2.fid1);
+                            assert_eq!(pt1.fid2, pt2.fid2);
+                            assert_eq!(pt1.dist, pt2.dist);
+                            num_contacts += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        assert!(num_contacts > 500, "{num_contacts}");
+    }
+
+    /// A shape of the given built-in type, fitting roughly in a unit box around the origin, with
+    /// its upper side facing `+y`.
+    fn sample_shape(shape_type: ShapeType) -> SharedShape {
+        use parry::shape::{HalfSpace, HeightField};
+
+        // A segment and a triangle centered on the `y` axis, and a square mesh, with their
+        // upper sides at `y = 0`.
+        #[cfg(feature = "dim2")]
+        let (segment, triangle, vtx, idx) = (
+            [Point::new(-0.5, 0.0), Point::new(0.5, 0.0)],
+            [
+                Point::new(-0.5, -0.5),
+                Point::new(0.5, -0.5),
+                Point::origin(),
+            ],
+            vec![
+                Point::new(-0.5, -0.5),
+                Point::new(0.5, -0.5),
+                Point::new(0.5, 0.0),
+                Point::new(-0.5, 0.0),
+            ],
+            vec![[0, 1, 2], [0, 2, 3]],
+        );
+        #[cfg(feature = "dim3")]
+        let (segment, triangle, vtx, idx) = (
+            [Point::new(-0.5, 0.0, 0.0), Point::new(0.5, 0.0, 0.0)],
+            [
+                Point::new(-0.5, 0.0, -0.5),
+                Point::new(0.0, 0.0, 0.5),
+                Point::new(0.5, 0.0, -0.5),
+            ],
+            vec![
+                Point::new(-0.5, 0.0, -0.5),
+                Point::new(0.5, 0.0, -0.5),
+                Point::new(0.5, 0.0, 0.5),
+                Point::new(-0.5, 0.0, 0.5),
+            ],
+            vec![[0, 2, 1], [0, 3, 2]],
+        );
+        let cube: Vec<_> = (0..1 << DIM)
+            .map(|i| Point::from(Vector::from_fn(|k, _| ((i >> k) & 1) as Real - 0.5)))
+            .collect();
+        let half_extents = Vector::repeat(0.5);
+
+        match shape_type {
+            ShapeType::Ball => SharedShape::ball(0.5),
+            ShapeType::Cuboid => SharedShape::new(Cuboid::new(half_extents)),
+            ShapeType::Capsule => SharedShape::capsule_y(0.25, 0.25),
+            ShapeType::Segment => SharedShape::segment(segment[0], segment[1]),
+            ShapeType::Triangle => SharedShape::triangle(triangle[0], triangle[1], triangle[2]),
+            ShapeType::TriMesh => SharedShape::trimesh(vtx, idx),
+            ShapeType::Polyline => SharedShape::polyline(segment.to_vec(), None),
+            ShapeType::HalfSpace => SharedShape::new(HalfSpace::new(Vector::y_axis())),
+            #[cfg(feature = "dim2")]
+            ShapeType::HeightField => SharedShape::new(HeightField::new(
+                na::DVector::zeros(5),
+                Vector::new(1.0, 1.0),
+            )),
+            #[cfg(feature = "dim3")]
+            ShapeType::HeightField => SharedShape::new(HeightField::new(
+                na::DMatrix::zeros(5, 5),
+                Vector::new(1.0, 1.0, 1.0),
+            )),
+            ShapeType::Compound => {
+                let half = SharedShape::new(Cuboid::new(Vector::repeat(0.25)));
+                let shift = Vector::x() * 0.25;
+                SharedShape::compound(vec![
+                    (Isometry::from(-shift), half.clone()),
+                    (Isometry::from(shift), half),
+                ])
+            }
+            #[cfg(feature = "dim2")]
+            ShapeType::ConvexPolygon => SharedShape::convex_hull(&cube).unwrap(),
+            #[cfg(feature = "dim3")]
+            ShapeType::ConvexPolyhedron => SharedShape::convex_hull(&cube).unwrap(),
+            #[cfg(feature = "dim3")]
+            ShapeType::Cylinder => SharedShape::cylinder(0.5, 0.5),
+            #[cfg(feature = "dim3")]
+            ShapeType::Cone => SharedShape::cone(0.5, 0.5),
+            ShapeType::RoundCuboid => SharedShape::new(parry::shape::RoundCuboid {
+                inner_shape: Cuboid::new(half_extents * 0.8),
+                border_radius: 0.1,
+            }),
+            ShapeType::RoundTriangle => {
+                SharedShape::round_triangle(triangle[0], triangle[1], triangle[2], 0.1)
+            }
+            #[cfg(feature = "dim3")]
+            ShapeType::RoundCylinder => SharedShape::round_cylinder(0.4, 0.4, 0.1),
+            #[cfg(feature = "dim3")]
+            ShapeType::RoundCone => SharedShape::round_cone(0.4, 0.4, 0.1),
+            #[cfg(feature = "dim3")]
+            ShapeType::RoundConvexPolyhedron => SharedShape::round_convex_hull(&cube, 0.1).unwrap(),
+            #[cfg(feature = "dim2")]
+            ShapeType::RoundConvexPolygon => SharedShape::round_convex_hull(&cube, 0.1).unwrap(),
+            ShapeType::Custom => unreachable!(),
+        }
+    }
+
+    /// The contact manifolds between `shape1`, at the origin, and `shape2`, upside-down on top
+    /// of `shape1` at the distance `dist` from it (negative if they penetrate).
+    fn stacked_contact_manifolds(
+        shape1: &dyn Shape,
+        shape2: &dyn Shape,
+        dist: Real,
+    ) -> Result<Vec<parry::query::ContactManifold<(), ()>>, parry::query::Unsupported> {
+        use super::SupportMapFallbackDispatcher;
+        use parry::query::PersistentQueryDispatcher;
+
+        #[cfg(feature = "dim2")]
+        let flip = Isometry::rotation(Real::pi());
+        #[cfg(feature = "dim3")]
+        let flip = Isometry::rotation(Vector::x() * Real::pi());
+        // Half-spaces are unbounded, their boundary goes through their origin.
+        let top1 = match shape1.shape_type() {
+            ShapeType::HalfSpace => 0.0,
+            _ => shape1.compute_local_aabb().maxs.y,
+        };
+        let bottom2 = match shape2.shape_type() {
+            ShapeType::HalfSpace => 0.0,
+            _ => shape2.compute_aabb(&flip).mins.y,
+        };
+        let pos12 = Isometry::from(Vector::y() * (top1 - bottom2 + dist)) * flip;
+        let prediction = dist.max(0.0) * 2.0;
+
+        let mut manifolds = vec![];
+        SupportMapFallbackDispatcher
+            .contact_manifolds(
+                &pos12,
+                shape1,
+                shape2,
+                prediction,
+                &mut manifolds,
+                &mut None,
+            )
+            .map(|_| manifolds)
+    }
+
+    /// Checks that the contacts between the given shapes, stacked at the distance `dist`, are sane.
+    ///
+    /// If the shapes are separated, the contact normal must point upward and the closest contact
+    /// must be at the distance `dist`. If they penetrate, the features of the shapes may be
+    /// arbitrarily tangled, so only the penetration depth is checked to be in a sensible range.
+    fn check_stacked_contacts(type1: ShapeType, type2: ShapeType, dist: Real) {
+        let (shape1, shape2) = (sample_shape(type1), sample_shape(type2));
+        let manifolds = stacked_contact_manifolds(&*shape1, &*shape2, dist)
+            .unwrap_or_else(|_| panic!("{type1:?}, {type2:?}: unsupported"));
+
+        let mut min_dist = Real::MAX;
+        for manifold in &manifolds {
+            let n1 = manifold.local_n1;
+            assert!(
+                manifold.points.is_empty() || (n1.norm() - 1.0).abs() < 1.0e-3,
+                "{type1:?}, {type2:?}, {dist}: normal {n1}"
+            );
+            for contact in &manifold.points {
+                // The normal points from `shape1` toward `shape2`, i.e., upward.
+                assert!(
+                    dist < 0.0 || n1.y > 0.5,
+                    "{type1:?}, {type2:?}, {dist}: normal {n1}"
+                );
+                min_dist = min_dist.min(contact.dist);
+            }
+        }
+
+        if dist >= 0.0 {
+            assert!(
+                (min_dist - dist).abs() < 1.0e-3,
+                "{type1:?}, {type2:?}, {dist}: min dist {min_dist}"
+            );
+        } else {
+            assert!(
+                min_dist < 0.0 && min_dist > dist * 2.0,
+                "{type1:?}, {type2:?}, {dist}: min dist {min_dist}"
+            );
+        }
+    }
+
+    #[test]
+    fn all_supported_shape_pairs_generate_contacts() {
+        // Shapes without any thickness along `y` can’t penetrate each other.
+        #[cfg(feature = "dim2")]
+        let thin = [
+            ShapeType::Segment,
+            ShapeType::Polyline,
+            ShapeType::HeightField,
+        ];
+        #[cfg(feature = "dim3")]
+        let thin = [
+            ShapeType::Segment,
+            ShapeType::Polyline,
+            ShapeType::HeightField,
+            ShapeType::Triangle,
+            ShapeType::TriMesh,
+        ];
+
+        let pairs = NarrowPhase::supported_pairs();
+        assert!(pairs.len() > super::BUILTIN_SHAPE_TYPES.len());
+
+        for (type1, type2) in pairs {
+            for (type1, type2) in [(type1, type2), (type2, type1)] {
+                check_stacked_contacts(type1, type2, 0.05);
+                if !thin.contains(&type1) || !thin.contains(&type2) {
+                    check_stacked_contacts(type1, type2, -0.05);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn unsupported_pairs_are_reported() {
+        let reported =
+            |pair: &(ShapeType, ShapeType)| super::REPORTED_PAIRS.lock().unwrap().contains(pair);
+
+        // Built-in pairs missing from the registry.
+        for pair in super::UNSUPPORTED_PAIRS {
+            let (shape1, shape2) = (sample_shape(pair.0), sample_shape(pair.1));
+            assert!(stacked_contact_manifolds(&*shape1, &*shape2, 0.05).is_err());
+            assert!(reported(pair));
+        }
+
+        // A custom shape that isn’t support-mapped.
+        let shape = Superellipsoid {
+            half_extents: Vector::repeat(0.4),
+            exponent: 10.0,
+            support_mapped: false,
+        };
+        // NOTE: not a ball, their contacts only rely on point projections.
+        let cuboid = sample_shape(ShapeType::Cuboid);
+        let pair = (ShapeType::Custom, ShapeType::Cuboid);
+
+        assert!(!reported(&pair));
+        assert!(stacked_contact_manifolds(&shape, &*cuboid, 0.05).is_err());
+        assert!(reported(&pair));
+    }
+}
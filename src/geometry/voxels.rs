@@ -0,0 +1,898 @@
+use crate::geometry::{Aabb, Cuboid, Ray, RayIntersection};
+use crate::math::{Isometry, Point, Real, Vector, DIM};
+use na::{RealField, Unit};
+use parry::bounding_volume::BoundingSphere;
+use parry::mass_properties::MassProperties;
+use parry::partitioning::{Qbvh, QbvhUpdateWorkspace};
+use parry::query::details::{NormalConstraints, PointCompositeShapeProjBestFirstVisitor};
+use parry::query::{PointProjection, PointQuery, RayCast};
+use parry::shape::{
+    FeatureId, PolygonalFeature, PolygonalFeatureMap, Shape, ShapeType, SimdCompositeShape,
+    SupportMap, TypedShape, TypedSimdCompositeShape,
+};
+use parry::utils::hashmap::HashMap;
+
+/// Marker stored in [`Voxels::cells`] for occupied cells that are completely surrounded by other
+/// occupied cells, and are thus not part of the acceleration structure.
+const INTERIOR_CELL: u32 = u32::MAX;
+
+/// A shape made of a sparse set of axis-aligned square (2D) or cubic (3D) cells of identical size.
+///
+/// The cell with key `k` covers the local-space region `[k * cell_size, (k + 1) * cell_size]`.
+/// Only the cells on the surface of the shape (the ones with at least one empty face neighbor)
+/// are stored in its bounding volume hierarchy, so contacts are only generated against the surface
+/// cells close to the other shape. Each cell is reported to the contact generators as a cuboid,
+/// alongside normal constraints preventing contact normals from pointing toward its occupied
+/// neighbors. This avoids catches on the internal edges between coplanar faces of adjacent cells.
+///
+/// Cells can be added or removed at runtime with [`Voxels::insert`] and [`Voxels::remove`]. These
+/// only update the cells next to the modified one instead of rebuilding the whole shape. To modify
+/// the voxels of a collider, use `collider.shape_mut().as_shape_mut::<Voxels>()`: this will mark
+/// the collider shape as modified and wake up the rigid-bodies touching it.
+///
+/// This is a custom shape from the point of view of `parry`: colliders with this shape cannot be
+/// deserialized.
+#[derive(Clone, Debug)]
+pub struct Voxels {
+    cell_size: Real,
+    /// Maps each occupied cell to its part id if it is on the surface, or to `INTERIOR_CELL`.
+    cells: HashMap<Point<i32>, u32>,
+    /// The cell key associated to each part id.
+    parts: Vec<Point<i32>>,
+    free_parts: Vec<u32>,
+    /// The smallest and largest keys of the occupied cells (if there is any).
+    mins: Point<i32>,
+    maxs: Point<i32>,
+    qbvh: Qbvh<u32>,
+}
+
+impl Voxels {
+    /// Creates a new voxel shape from the keys of its occupied cells.
+    pub fn new(cell_size: Real, cells: &[Point<i32>]) -> Self {
+        let mut result = Self {
+            cell_size,
+            cells: cells.iter().map(|key| (*key, INTERIOR_CELL)).collect(),
+            parts: vec![],
+            free_parts: vec![],
+            mins: Point::origin(),
+            maxs: Point::origin(),
+            qbvh: Qbvh::new(),
+        };
+
+        let surface: Vec<_> = result
+            .cells
+            .keys()
+            .copied()
+            .filter(|key| result.is_exposed(*key))
+            .collect();
+
+        for key in surface {
+            let id = result.parts.len() as u32;
+            result.parts.push(key);
+            let _ = result.cells.insert(key, id);
+        }
+
+        let leaves = result
+            .parts
+            .iter()
+            .enumerate()
+            .map(|(id, key)| (id as u32, Self::aabb_of(*key, cell_size)));
+        result.qbvh.clear_and_rebuild(leaves, 0.0);
+        result.recompute_bounds();
+        result
+    }
+
+    /// The width of each cell.
+    pub fn cell_size(&self) -> Real {
+        self.cell_size
+    }
+
+    /// The number of occupied cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Is this shape free of any occupied cell?
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// An iterator through the keys of all the occupied cells, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = Point<i32>> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// An iterator through the keys of the occupied cells with at least one empty face neighbor.
+    pub fn surface_cells(&self) -> impl Iterator<Item = Point<i32>> + '_ {
+        self.cells
+            .iter()
+            .filter(|(_, part)| **part != INTERIOR_CELL)
+            .map(|(key, _)| *key)
+    }
+
+    /// Is the cell with the given key occupied?
+    pub fn contains(&self, key: Point<i32>) -> bool {
+        self.cells.contains_key(&key)
+    }
+
+    /// The key of the cell containing the given local-space point.
+    pub fn cell_at(&self, point: &Point<Real>) -> Point<i32> {
+        point.map(|x| (x / self.cell_size).floor() as i32)
+    }
+
+    /// The local-space center of the cell with the given key.
+    pub fn cell_center(&self, key: Point<i32>) -> Point<Real> {
+        key.map(|k| (k as Real + 0.5) * self.cell_size)
+    }
+
+    /// The local-space AABB of the cell with the given key.
+    pub fn cell_aabb(&self, key: Point<i32>) -> Aabb {
+        Self::aabb_of(key, self.cell_size)
+    }
+
+    /// Marks the cell with the given key as occupied.
+    ///
+    /// Returns `false` if it was already occupied.
+    pub fn insert(&mut self, key: Point<i32>) -> bool {
+        if self.contains(key) {
+            return false;
+        }
+
+        if self.cells.is_empty() {
+            self.mins = key;
+            self.maxs = key;
+        } else {
+            self.mins = self.mins.inf(&key);
+            self.maxs = self.maxs.sup(&key);
+        }
+
+        let _ = self.cells.insert(key, INTERIOR_CELL);
+        self.update_surface_around(key);
+        true
+    }
+
+    /// Marks the cell with the given key as empty.
+    ///
+    /// Returns `false` if it wasn’t occupied.
+    pub fn remove(&mut self, key: Point<i32>) -> bool {
+        let Some(part) = self.cells.remove(&key) else {
+            return false;
+        };
+
+        if part != INTERIOR_CELL {
+            let _ = self.qbvh.remove(part);
+            self.free_parts.push(part);
+        }
+
+        self.update_surface_around(key);
+
+        if (0..DIM).any(|i| key[i] == self.mins[i] || key[i] == self.maxs[i]) {
+            self.recompute_bounds();
+        }
+
+        true
+    }
+
+    fn aabb_of(key: Point<i32>, cell_size: Real) -> Aabb {
+        let mins = key.map(|k| k as Real * cell_size);
+        Aabb::new(mins, mins + Vector::repeat(cell_size))
+    }
+
+    fn neighbors(key: Point<i32>) -> impl Iterator<Item = Point<i32>> {
+        (0..DIM * 2).map(move |i| {
+            let mut neighbor = key;
+            neighbor[i / 2] += if i % 2 == 0 { -1 } else { 1 };
+            neighbor
+        })
+    }
+
+    /// Bitmask of the occupied face neighbors of a cell: the bit `2 * i` (resp. `2 * i + 1`) is
+    /// set if the neighbor along the negative (resp. positive) `i`-th axis is occupied.
+    fn occupied_neighbors(&self, key: Point<i32>) -> u8 {
+        Self::neighbors(key)
+            .enumerate()
+            .filter(|(_, neighbor)| self.contains(*neighbor))
+            .fold(0, |mask, (i, _)| mask | (1 << i))
+    }
+
+    fn is_exposed(&self, key: Point<i32>) -> bool {
+        Self::neighbors(key).any(|neighbor| !self.contains(neighbor))
+    }
+
+    /// Updates the surface status of the given cell and of its neighbors, then refits the QBVH.
+    fn update_surface_around(&mut self, key: Point<i32>) {
+        for cell in std::iter::once(key).chain(Self::neighbors(key)) {
+            let Some(part) = self.cells.get(&cell).copied() else {
+                continue;
+            };
+
+            match (self.is_exposed(cell), part == INTERIOR_CELL) {
+                (true, true) => {
+                    let part = self.free_parts.pop().unwrap_or_else(|| {
+                        self.parts.push(cell);
+                        self.parts.len() as u32 - 1
+                    });
+                    self.parts[part as usize] = cell;
+                    self.qbvh.pre_update_or_insert(part);
+                    let _ = self.cells.insert(cell, part);
+                }
+                (false, false) => {
+                    let _ = self.qbvh.remove(part);
+                    self.free_parts.push(part);
+                    let _ = self.cells.insert(cell, INTERIOR_CELL);
+                }
+                _ => {}
+            }
+        }
+
+        let parts = &self.parts;
+        let cell_size = self.cell_size;
+        let mut workspace = QbvhUpdateWorkspace::default();
+        let _ = self.qbvh.refit(0.0, &mut workspace, |part| {
+            Self::aabb_of(parts[*part as usize], cell_size)
+        });
+    }
+
+    fn recompute_bounds(&mut self) {
+        let mut keys = self.cells.keys();
+
+        if let Some(first) = keys.next() {
+            let (mins, maxs) = keys.fold((*first, *first), |(mins, maxs), key| {
+                (mins.inf(key), maxs.sup(key))
+            });
+            self.mins = mins;
+            self.maxs = maxs;
+        }
+    }
+
+    fn in_bounds(&self, key: Point<i32>) -> bool {
+        (0..DIM).all(|i| key[i] >= self.mins[i] && key[i] <= self.maxs[i])
+    }
+
+    /// Projects a point located inside of the given occupied cell on the closest face of that
+    /// cell that isn’t shared with another occupied cell.
+    fn project_on_exposed_face(&self, key: Point<i32>, point: &Point<Real>) -> Option<Point<Real>> {
+        let aabb = self.cell_aabb(key);
+        let mut best = None;
+        let mut best_dist = Real::MAX;
+
+        for (i, neighbor) in Self::neighbors(key).enumerate() {
+            if self.contains(neighbor) {
+                continue;
+            }
+
+            let axis = i / 2;
+            let face = if i % 2 == 0 {
+                aabb.mins[axis]
+            } else {
+                aabb.maxs[axis]
+            };
+            let dist = (point[axis] - face).abs();
+
+            if dist < best_dist {
+                let mut proj = *point;
+                proj[axis] = face;
+                best = Some(proj);
+                best_dist = dist;
+            }
+        }
+
+        best
+    }
+}
+
+/// Normal constraints of a single cell of a [`Voxels`] shape.
+///
+/// Contact normals pointing toward an occupied neighbor of the cell are projected on the plane of
+/// the shared face, so that contacts near the edges between coplanar cell faces behave as if the
+/// faces were merged.
+struct VoxelNormalConstraints {
+    occupied_neighbors: u8,
+}
+
+impl NormalConstraints for VoxelNormalConstraints {
+    fn project_local_normal_mut(&self, normal: &mut Vector<Real>) -> bool {
+        for i in 0..DIM {
+            let towards_neighbor = if normal[i] < 0.0 { 2 * i } else { 2 * i + 1 };
+
+            if self.occupied_neighbors & (1 << towards_neighbor) != 0 {
+                normal[i] = 0.0;
+            }
+        }
+
+        normal.try_normalize_mut(1.0e-5).is_some()
+    }
+}
+
+/// A single cell of a [`Voxels`] shape, as seen by the contact generators.
+///
+/// This behaves exactly like a cuboid, except that it isn’t identified as one: the contact
+/// generators specialized for pairs of cuboids ignore normal constraints, while the generic ones
+/// used for this shape don’t.
+#[derive(Copy, Clone, Debug)]
+struct VoxelCell(Cuboid);
+
+impl RayCast for VoxelCell {
+    fn cast_local_ray_and_get_normal(
+        &self,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        self.0.cast_local_ray_and_get_normal(ray, max_toi, solid)
+    }
+}
+
+impl PointQuery for VoxelCell {
+    fn project_local_point(&self, point: &Point<Real>, solid: bool) -> PointProjection {
+        self.0.project_local_point(point, solid)
+    }
+
+    fn project_local_point_and_get_feature(
+        &self,
+        point: &Point<Real>,
+    ) -> (PointProjection, FeatureId) {
+        self.0.project_local_point_and_get_feature(point)
+    }
+}
+
+impl SupportMap for VoxelCell {
+    fn local_support_point(&self, dir: &Vector<Real>) -> Point<Real> {
+        self.0.local_support_point(dir)
+    }
+}
+
+impl PolygonalFeatureMap for VoxelCell {
+    fn local_support_feature(&self, dir: &Unit<Vector<Real>>, out_feature: &mut PolygonalFeature) {
+        self.0.local_support_feature(dir, out_feature)
+    }
+}
+
+impl Shape for VoxelCell {
+    fn compute_local_aabb(&self) -> Aabb {
+        self.0.compute_local_aabb()
+    }
+
+    fn compute_local_bounding_sphere(&self) -> BoundingSphere {
+        self.0.compute_local_bounding_sphere()
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(*self)
+    }
+
+    fn mass_properties(&self, density: Real) -> MassProperties {
+        self.0.mass_properties(density)
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Custom
+    }
+
+    fn as_typed_shape(&self) -> TypedShape<'_> {
+        TypedShape::Custom(1)
+    }
+
+    fn ccd_thickness(&self) -> Real {
+        self.0.ccd_thickness()
+    }
+
+    fn ccd_angular_thickness(&self) -> Real {
+        self.0.ccd_angular_thickness()
+    }
+
+    fn as_support_map(&self) -> Option<&dyn SupportMap> {
+        Some(self)
+    }
+
+    fn as_polygonal_feature_map(&self) -> Option<(&dyn PolygonalFeatureMap, Real)> {
+        Some((self, 0.0))
+    }
+}
+
+impl TypedSimdCompositeShape for Voxels {
+    type PartShape = dyn Shape;
+    type PartNormalConstraints = dyn NormalConstraints;
+    type PartId = u32;
+
+    fn map_typed_part_at(
+        &self,
+        part: u32,
+        mut f: impl FnMut(Option<&Isometry<Real>>, &dyn Shape, Option<&dyn NormalConstraints>),
+    ) {
+        let Some(key) = self.parts.get(part as usize).copied() else {
+            return;
+        };
+
+        if self.cells.get(&key) != Some(&part) {
+            return;
+        }
+
+        let pos = Isometry::from(self.cell_center(key).coords);
+        let cell = VoxelCell(Cuboid::new(Vector::repeat(self.cell_size / 2.0)));
+        let constraints = VoxelNormalConstraints {
+            occupied_neighbors: self.occupied_neighbors(key),
+        };
+        f(Some(&pos), &cell, Some(&constraints))
+    }
+
+    fn map_untyped_part_at(
+        &self,
+        part: u32,
+        f: impl FnMut(Option<&Isometry<Real>>, &dyn Shape, Option<&dyn NormalConstraints>),
+    ) {
+        self.map_typed_part_at(part, f)
+    }
+
+    fn typed_qbvh(&self) -> &Qbvh<u32> {
+        &self.qbvh
+    }
+}
+
+impl SimdCompositeShape for Voxels {
+    fn map_part_at(
+        &self,
+        part: u32,
+        f: &mut dyn FnMut(Option<&Isometry<Real>>, &dyn Shape, Option<&dyn NormalConstraints>),
+    ) {
+        self.map_untyped_part_at(part, f)
+    }
+
+    fn qbvh(&self) -> &Qbvh<u32> {
+        &self.qbvh
+    }
+}
+
+impl RayCast for Voxels {
+    /// Casts a ray on this shape by walking through the cells it crosses (DDA traversal).
+    fn cast_local_ray_and_get_normal(
+        &self,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let aabb = self.compute_local_aabb();
+        let (tmin, _) = aabb.clip_ray_parameters(ray)?;
+
+        if tmin > max_toi {
+            return None;
+        }
+
+        let mut key;
+        let mut crossed_axis = None;
+        let mut toi = tmin.max(0.0);
+
+        if tmin > 0.0 {
+            // The ray enters the AABB from the outside: find the face it went through.
+            key = self
+                .cell_at(&ray.point_at(tmin))
+                .sup(&self.mins)
+                .inf(&self.maxs);
+            let mut best_t = -Real::MAX;
+            let mut axis = 0;
+
+            for i in 0..DIM {
+                if ray.dir[i] != 0.0 {
+                    let bound = if ray.dir[i] > 0.0 {
+                        aabb.mins[i]
+                    } else {
+                        aabb.maxs[i]
+                    };
+                    let t = (bound - ray.origin[i]) / ray.dir[i];
+
+                    if t > best_t {
+                        best_t = t;
+                        axis = i;
+                    }
+                }
+            }
+
+            key[axis] = if ray.dir[axis] > 0.0 {
+                self.mins[axis]
+            } else {
+                self.maxs[axis]
+            };
+            crossed_axis = Some(axis);
+        } else {
+            key = self.cell_at(&ray.origin);
+        }
+
+        let inside = tmin <= 0.0 && self.contains(key);
+
+        if inside && solid {
+            return Some(RayIntersection::new(
+                0.0,
+                Vector::zeros(),
+                FeatureId::Unknown,
+            ));
+        }
+
+        let mut step = [0i32; DIM];
+        let mut t_next = [Real::MAX; DIM];
+        let mut t_delta = [Real::MAX; DIM];
+
+        for i in 0..DIM {
+            if ray.dir[i] > 0.0 {
+                step[i] = 1;
+                t_next[i] = ((key[i] + 1) as Real * self.cell_size - ray.origin[i]) / ray.dir[i];
+                t_delta[i] = self.cell_size / ray.dir[i];
+            } else if ray.dir[i] < 0.0 {
+                step[i] = -1;
+                t_next[i] = (key[i] as Real * self.cell_size - ray.origin[i]) / ray.dir[i];
+                t_delta[i] = -self.cell_size / ray.dir[i];
+            }
+        }
+
+        loop {
+            if self.contains(key) != inside {
+                let mut normal = Vector::zeros();
+
+                if let Some(axis) = crossed_axis {
+                    normal[axis] = -step[axis] as Real;
+                }
+
+                return Some(RayIntersection::new(toi, normal, FeatureId::Unknown));
+            }
+
+            let axis = (0..DIM)
+                .min_by(|a, b| t_next[*a].total_cmp(&t_next[*b]))
+                .unwrap_or(0);
+
+            if step[axis] == 0 || t_next[axis] > max_toi {
+                return None;
+            }
+
+            toi = t_next[axis];
+            key[axis] += step[axis];
+            t_next[axis] += t_delta[axis];
+            crossed_axis = Some(axis);
+
+            if !inside && !self.in_bounds(key) {
+                // We are moving away from every occupied cell.
+                return None;
+            }
+        }
+    }
+}
+
+impl PointQuery for Voxels {
+    /// Projects a point on this shape.
+    ///
+    /// If the point is inside of the shape and `solid` is `false`, the projection is computed on
+    /// the closest exposed face of the cell containing the point (or on the closest surface cell if
+    /// that cell has no exposed face). This may not be the closest point of the shape boundary.
+    fn project_local_point(&self, point: &Point<Real>, solid: bool) -> PointProjection {
+        let key = self.cell_at(point);
+
+        if self.contains(key) {
+            if solid {
+                return PointProjection::new(true, *point);
+            }
+
+            if let Some(proj) = self.project_on_exposed_face(key, point) {
+                return PointProjection::new(true, proj);
+            }
+        }
+
+        let mut visitor = PointCompositeShapeProjBestFirstVisitor::new(self, point, false);
+        match self.qbvh.traverse_best_first(&mut visitor) {
+            Some((_, (proj, _))) => PointProjection::new(self.contains(key), proj.point),
+            // The shape is empty.
+            None => PointProjection::new(false, *point),
+        }
+    }
+
+    fn project_local_point_and_get_feature(
+        &self,
+        point: &Point<Real>,
+    ) -> (PointProjection, FeatureId) {
+        (self.project_local_point(point, false), FeatureId::Unknown)
+    }
+
+    fn contains_local_point(&self, point: &Point<Real>) -> bool {
+        self.contains(self.cell_at(point))
+    }
+}
+
+impl Shape for Voxels {
+    fn compute_local_aabb(&self) -> Aabb {
+        if self.is_empty() {
+            Aabb::new_invalid()
+        } else {
+            let maxs = self.maxs.map(|k| k + 1);
+            Aabb::new(
+                self.mins.map(|k| k as Real * self.cell_size),
+                maxs.map(|k| k as Real * self.cell_size),
+            )
+        }
+    }
+
+    fn compute_local_bounding_sphere(&self) -> BoundingSphere {
+        self.compute_local_aabb().bounding_sphere()
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn mass_properties(&self, density: Real) -> MassProperties {
+        if self.is_empty() {
+            return MassProperties::default();
+        }
+
+        let cell_mass = density * self.cell_size.powi(DIM as i32);
+        let mass = cell_mass * self.len() as Real;
+        let com = self.cells().fold(Point::origin(), |com, key| {
+            com + self.cell_center(key).coords
+        }) / self.len() as Real;
+        // The angular inertia of a single square/cube, around its center.
+        let cell_inertia = cell_mass * self.cell_size * self.cell_size / 6.0;
+
+        #[cfg(feature = "dim2")]
+        {
+            let inertia = self.cells().fold(0.0, |inertia, key| {
+                let shift = self.cell_center(key) - com;
+                inertia + cell_inertia + cell_mass * shift.norm_squared()
+            });
+            MassProperties::new(com, mass, inertia)
+        }
+
+        #[cfg(feature = "dim3")]
+        {
+            use crate::math::Matrix;
+
+            let inertia = self.cells().fold(Matrix::<Real>::zeros(), |inertia, key| {
+                let shift = self.cell_center(key) - com;
+                let shift_inertia =
+                    Matrix::identity() * shift.norm_squared() - shift * shift.transpose();
+                inertia + Matrix::identity() * cell_inertia + shift_inertia * cell_mass
+            });
+            MassProperties::with_inertia_matrix(com, mass, inertia)
+        }
+    }
+
+    fn is_convex(&self) -> bool {
+        false
+    }
+
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Custom
+    }
+
+    fn as_typed_shape(&self) -> TypedShape<'_> {
+        TypedShape::Custom(0)
+    }
+
+    fn ccd_thickness(&self) -> Real {
+        self.cell_size / 2.0
+    }
+
+    fn ccd_angular_thickness(&self) -> Real {
+        Real::frac_pi_2()
+    }
+
+    fn as_composite_shape(&self) -> Option<&dyn SimdCompositeShape> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Voxels;
+    use crate::dynamics::{RigidBodyBuilder, RigidBodyHandle};
+    use crate::geometry::{ColliderBuilder, ColliderHandle, Cuboid, Ray, Shape};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::PhysicsWorld;
+    use parry::query::{PointQuery, RayCast};
+
+    #[cfg(feature = "dim2")]
+    fn key(x: i32, y: i32, _z: i32) -> Point<i32> {
+        Point::new(x, y)
+    }
+
+    #[cfg(feature = "dim3")]
+    fn key(x: i32, y: i32, z: i32) -> Point<i32> {
+        Point::new(x, y, z)
+    }
+
+    #[cfg(feature = "dim2")]
+    fn block(n: i32) -> Vec<Point<i32>> {
+        (0..n)
+            .flat_map(|x| (0..n).map(move |y| key(x, y, 0)))
+            .collect()
+    }
+
+    #[cfg(feature = "dim3")]
+    fn block(n: i32) -> Vec<Point<i32>> {
+        (0..n)
+            .flat_map(|x| (0..n).flat_map(move |y| (0..n).map(move |z| key(x, y, z))))
+            .collect()
+    }
+
+    #[test]
+    fn local_edits_update_the_surface() {
+        let mut voxels = Voxels::new(1.0, &block(3));
+        let num_cells = voxels.len();
+        assert_eq!(voxels.surface_cells().count(), num_cells - 1);
+
+        // Digging a hole next to the center exposes it.
+        assert!(voxels.remove(key(1, 2, 1)));
+        assert!(!voxels.remove(key(1, 2, 1)));
+        assert_eq!(voxels.surface_cells().count(), num_cells - 1);
+        let in_hole = voxels.cell_center(key(1, 2, 1)) - Vector::y() * 0.4;
+        let proj = voxels.project_local_point(&in_hole, true);
+        assert!(!proj.is_inside);
+        assert!((proj.point.y - 2.0).abs() < 1.0e-5);
+
+        // Filling it back hides the center again.
+        assert!(voxels.insert(key(1, 2, 1)));
+        assert!(!voxels.insert(key(1, 2, 1)));
+        assert_eq!(voxels.surface_cells().count(), num_cells - 1);
+        assert!(voxels.contains_local_point(&voxels.cell_center(key(1, 2, 1))));
+
+        // Removing cells on the boundary shrinks the AABB.
+        for cell in block(3) {
+            if cell.x == 2 {
+                assert!(voxels.remove(cell));
+            }
+        }
+        assert_eq!(voxels.compute_local_aabb().maxs.x, 2.0);
+
+        // Voxels can be built incrementally from an empty shape.
+        let mut incremental = Voxels::new(0.5, &[]);
+        assert!(incremental.compute_local_aabb().mins.x > incremental.compute_local_aabb().maxs.x);
+        for cell in block(3) {
+            assert!(incremental.insert(cell));
+        }
+        assert_eq!(incremental.surface_cells().count(), num_cells - 1);
+        assert_eq!(
+            incremental.compute_local_aabb().maxs,
+            Point::from(Vector::repeat(1.5))
+        );
+        let proj = incremental.project_local_point(&Point::from(Vector::repeat(-1.0)), true);
+        assert!((proj.point - Point::origin()).norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn mass_properties_match_the_equivalent_cuboid() {
+        let voxels = Voxels::new(
+            0.5,
+            &[key(0, 0, 0), key(1, 0, 0), key(2, 0, 0), key(3, 0, 0)],
+        );
+        #[cfg(feature = "dim2")]
+        let cuboid = Cuboid::new(Vector::new(1.0, 0.25));
+        #[cfg(feature = "dim3")]
+        let cuboid = Cuboid::new(Vector::new(1.0, 0.25, 0.25));
+
+        let voxels_props = voxels.mass_properties(2.0);
+        let cuboid_props = cuboid.mass_properties(2.0);
+        assert!((voxels_props.mass() - cuboid_props.mass()).abs() < 1.0e-5);
+        let com = Point::from(Vector::repeat(0.25)) + Vector::x() * 0.75;
+        assert!((voxels_props.local_com - com).norm() < 1.0e-5);
+        #[cfg(feature = "dim2")]
+        assert!(
+            (voxels_props.principal_inertia() - cuboid_props.principal_inertia()).abs() < 1.0e-4
+        );
+        #[cfg(feature = "dim3")]
+        assert!(
+            (voxels_props.reconstruct_inertia_matrix() - cuboid_props.reconstruct_inertia_matrix())
+                .norm()
+                < 1.0e-4
+        );
+    }
+
+    #[test]
+    fn ray_casts_walk_through_the_cells() {
+        let voxels = Voxels::new(
+            1.0,
+            &[key(0, 0, 0), key(1, 0, 0), key(2, 0, 0), key(3, 0, 0)],
+        );
+        let center = voxels.cell_center(key(0, 0, 0));
+
+        // Entering from the outside.
+        let ray = Ray::new(center - Vector::x() * 2.0, Vector::x());
+        let hit = voxels
+            .cast_local_ray_and_get_normal(&ray, Real::MAX, true)
+            .unwrap();
+        assert!((hit.time_of_impact - 1.5).abs() < 1.0e-5);
+        assert_eq!(hit.normal, -Vector::x());
+        assert!(voxels.cast_local_ray(&ray, 1.0, true).is_none());
+
+        let ray = Ray::new(voxels.cell_center(key(2, 3, 0)), -Vector::y());
+        let hit = voxels
+            .cast_local_ray_and_get_normal(&ray, Real::MAX, true)
+            .unwrap();
+        assert!((hit.time_of_impact - 2.5).abs() < 1.0e-5);
+        assert_eq!(hit.normal, Vector::y());
+
+        // Starting inside.
+        let ray = Ray::new(center, Vector::x());
+        assert_eq!(voxels.cast_local_ray(&ray, Real::MAX, true), Some(0.0));
+        let hit = voxels
+            .cast_local_ray_and_get_normal(&ray, Real::MAX, false)
+            .unwrap();
+        assert!((hit.time_of_impact - 3.5).abs() < 1.0e-5);
+        assert_eq!(hit.normal, -Vector::x());
+
+        // Missing the cells, or going through a hole.
+        let ray = Ray::new(center + Vector::y() * 2.0, Vector::x());
+        assert!(voxels.cast_local_ray(&ray, Real::MAX, true).is_none());
+        let mut voxels = voxels;
+        assert!(voxels.remove(key(1, 0, 0)));
+        let ray = Ray::new(center + Vector::x() * 0.75, Vector::x());
+        assert!((voxels.cast_local_ray(&ray, Real::MAX, true).unwrap() - 0.75).abs() < 1.0e-5);
+    }
+
+    /// A voxel floor made of cells of width 1, with its top at `y = 0`.
+    fn box_on_voxel_floor(linvel: Vector<Real>) -> (PhysicsWorld, ColliderHandle, RigidBodyHandle) {
+        let mut world = PhysicsWorld::default();
+        let floor: Vec<_> = (-10..30)
+            .flat_map(|x| (-2..2).map(move |z| key(x, -1, z)))
+            .collect();
+        let floor = world.insert_collider(ColliderBuilder::voxels(1.0, &floor).friction(0.0));
+
+        let body = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .linvel(linvel),
+        );
+        #[cfg(feature = "dim2")]
+        let collider = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let collider = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        world.insert_collider_with_parent(collider.friction(0.0), body);
+        (world, floor, body)
+    }
+
+    #[test]
+    fn box_slides_on_voxel_floor_without_catching() {
+        let (mut world, _, body) = box_on_voxel_floor(Vector::x() * 5.0);
+
+        for _ in 0..120 {
+            world.step();
+        }
+
+        let body = &world.bodies()[body];
+        assert!(
+            (body.linvel().x - 5.0).abs() < 0.05,
+            "the box was slowed down: {}",
+            body.linvel()
+        );
+        assert!((body.translation().y - 0.5).abs() < 0.02);
+        assert!((body.translation().x - 10.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn removing_cells_wakes_up_touching_bodies() {
+        let (mut world, floor, body) = box_on_voxel_floor(Vector::zeros());
+
+        for _ in 0..200 {
+            world.step();
+        }
+        assert!(world.bodies()[body].is_sleeping());
+
+        let voxels = world.colliders[floor]
+            .shape_mut()
+            .as_shape_mut::<Voxels>()
+            .unwrap();
+        for x in -1..1 {
+            for z in -2..2 {
+                let _ = voxels.remove(key(x, -1, z));
+            }
+        }
+
+        for _ in 0..30 {
+            world.step();
+        }
+        assert!(!world.bodies()[body].is_sleeping());
+        assert!(world.bodies()[body].translation().y < 0.0);
+    }
+}
@@ -38,6 +38,11 @@ extern crate num_traits as num;
 #[cfg(feature = "parallel")]
 pub use rayon;
 
+#[cfg(feature = "glam")]
+pub extern crate glam;
+#[cfg(feature = "mint")]
+pub extern crate mint;
+
 #[cfg(all(
     feature = "simd-is-enabled",
     not(feature = "simd-stable"),
@@ -140,6 +145,8 @@ pub mod data;
 pub mod dynamics;
 pub mod geometry;
 pub mod pipeline;
+#[cfg(feature = "testbench")]
+pub mod testbench;
 pub mod utils;
 
 /// Elementary mathematical entities (vectors, matrices, isometries, etc).
@@ -211,6 +218,34 @@ pub mod math {
     /// The maximum number of rotational degrees of freedom of a rigid-body.
     #[cfg(feature = "dim3")]
     pub const ANG_DIM: usize = 3;
+
+    /*
+     * glam
+     */
+    /// The `glam` vector type convertible from and into [`Vector`] and [`Point`].
+    #[cfg(all(feature = "glam", feature = "dim2", feature = "f32"))]
+    pub type GlamVector = glam::Vec2;
+    /// The `glam` vector type convertible from and into [`Vector`] and [`Point`].
+    #[cfg(all(feature = "glam", feature = "dim2", feature = "f64"))]
+    pub type GlamVector = glam::DVec2;
+    /// The `glam` vector type convertible from and into [`Vector`] and [`Point`].
+    #[cfg(all(feature = "glam", feature = "dim3", feature = "f32"))]
+    pub type GlamVector = glam::Vec3;
+    /// The `glam` vector type convertible from and into [`Vector`] and [`Point`].
+    #[cfg(all(feature = "glam", feature = "dim3", feature = "f64"))]
+    pub type GlamVector = glam::DVec3;
+
+    /// The `glam` representation of a [`Rotation`]: its angle in radians.
+    ///
+    /// `glam` has no unit complex number type so 2D rotations are represented by their angle.
+    #[cfg(all(feature = "glam", feature = "dim2"))]
+    pub type GlamRotation = Real;
+    /// The `glam` quaternion type convertible from and into [`Rotation`].
+    #[cfg(all(feature = "glam", feature = "dim3", feature = "f32"))]
+    pub type GlamRotation = glam::Quat;
+    /// The `glam` quaternion type convertible from and into [`Rotation`].
+    #[cfg(all(feature = "glam", feature = "dim3", feature = "f64"))]
+    pub type GlamRotation = glam::DQuat;
 }
 
 /// Prelude containing the common types defined by Rapier.
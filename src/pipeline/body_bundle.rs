@@ -0,0 +1,126 @@
+use crate::dynamics::{GenericJoint, ImpulseJointHandle, RigidBody, RigidBodyHandle};
+use crate::geometry::{Collider, ColliderHandle};
+
+/// The unique identifier of a bundle inserted into a [`PhysicsWorld`](crate::pipeline::PhysicsWorld).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[repr(transparent)]
+pub struct BodyBundleHandle(pub crate::data::arena::Index);
+
+impl BodyBundleHandle {
+    /// Converts this handle into its (index, generation) components.
+    pub fn into_raw_parts(self) -> (u32, u32) {
+        self.0.into_raw_parts()
+    }
+
+    /// Reconstructs an handle from its (index, generation) components.
+    pub fn from_raw_parts(id: u32, generation: u32) -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(id, generation))
+    }
+
+    /// An always-invalid bundle handle.
+    pub fn invalid() -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(
+            crate::INVALID_U32,
+            crate::INVALID_U32,
+        ))
+    }
+}
+
+impl std::fmt::Display for BodyBundleHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BodyBundleHandle({})", self.0)
+    }
+}
+
+/// A structure made of rigid-bodies, colliders, and impulse joints, assembled before being
+/// inserted into a [`PhysicsWorld`](crate::pipeline::PhysicsWorld) all at once.
+///
+/// A bundle doesn’t need access to the world while it is being built, so it can be assembled on
+/// another thread than the one stepping the simulation. Its rigid-bodies are identified by the
+/// index returned by [`Self::add_body`] until the bundle is inserted with
+/// [`PhysicsWorld::insert_bundle`](crate::pipeline::PhysicsWorld::insert_bundle).
+#[derive(Clone, Default)]
+pub struct BodyBundle {
+    pub(crate) bodies: Vec<RigidBody>,
+    pub(crate) colliders: Vec<(Collider, Option<usize>)>,
+    pub(crate) impulse_joints: Vec<(usize, usize, GenericJoint)>,
+}
+
+impl BodyBundle {
+    /// Creates a new empty bundle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of rigid-bodies of this bundle.
+    pub fn num_bodies(&self) -> usize {
+        self.bodies.len()
+    }
+
+    /// The number of colliders of this bundle.
+    pub fn num_colliders(&self) -> usize {
+        self.colliders.len()
+    }
+
+    /// The number of impulse joints of this bundle.
+    pub fn num_impulse_joints(&self) -> usize {
+        self.impulse_joints.len()
+    }
+
+    /// Adds a rigid-body to this bundle and retrieves its index in the bundle.
+    pub fn add_body(&mut self, body: impl Into<RigidBody>) -> usize {
+        self.bodies.push(body.into());
+        self.bodies.len() - 1
+    }
+
+    /// Adds a collider not attached to any rigid-body to this bundle.
+    pub fn add_collider(&mut self, collider: impl Into<Collider>) {
+        self.colliders.push((collider.into(), None));
+    }
+
+    /// Adds a collider attached to the rigid-body with the index `parent` in this bundle.
+    ///
+    /// # Panics
+    /// Panics if `parent` doesn’t identify a rigid-body of this bundle.
+    pub fn add_collider_with_parent(&mut self, collider: impl Into<Collider>, parent: usize) {
+        assert!(
+            parent < self.bodies.len(),
+            "Parent rigid body not found in the bundle."
+        );
+        self.colliders.push((collider.into(), Some(parent)));
+    }
+
+    /// Adds an impulse joint between the rigid-bodies with the indices `body1` and `body2` in
+    /// this bundle.
+    ///
+    /// # Panics
+    /// Panics if `body1` or `body2` doesn’t identify a rigid-body of this bundle.
+    pub fn add_impulse_joint(
+        &mut self,
+        body1: usize,
+        body2: usize,
+        joint: impl Into<GenericJoint>,
+    ) {
+        assert!(
+            body1 < self.bodies.len() && body2 < self.bodies.len(),
+            "Joint rigid body not found in the bundle."
+        );
+        self.impulse_joints.push((body1, body2, joint.into()));
+    }
+}
+
+/// The handles of everything inserted into a [`PhysicsWorld`](crate::pipeline::PhysicsWorld)
+/// from a [`BodyBundle`].
+///
+/// The handles are in the same order as the elements were added to the bundle.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct BodyBundleHandles {
+    /// The handles of the rigid-bodies of the bundle.
+    pub bodies: Vec<RigidBodyHandle>,
+    /// The handles of the colliders of the bundle.
+    pub colliders: Vec<ColliderHandle>,
+    /// The handles of the impulse joints of the bundle.
+    pub impulse_joints: Vec<ImpulseJointHandle>,
+}
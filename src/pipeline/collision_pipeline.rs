@@ -17,6 +17,7 @@ use crate::{dynamics::RigidBodySet, geometry::ColliderSet};
 pub struct CollisionPipeline {
     broadphase_collider_pairs: Vec<ColliderPair>,
     broad_phase_events: Vec<BroadPhasePairEvent>,
+    step_id: u64,
 }
 
 #[allow(dead_code)]
@@ -37,9 +38,18 @@ impl CollisionPipeline {
         CollisionPipeline {
             broadphase_collider_pairs: Vec::new(),
             broad_phase_events: Vec::new(),
+            step_id: 0,
         }
     }
 
+    /// The number of collision detection steps executed by this pipeline.
+    ///
+    /// This is incremented at the beginning of each call to [`Self::step`], so it is also the
+    /// identifier of the step that generated the events passed to the [`EventHandler`].
+    pub fn step_id(&self) -> u64 {
+        self.step_id
+    }
+
     fn detect_collisions(
         &mut self,
         prediction_distance: Real,
@@ -51,6 +61,7 @@ impl CollisionPipeline {
         removed_colliders: &[ColliderHandle],
         hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
+        step_id: u64,
         handle_user_changes: bool,
     ) {
         // Update broad-phase.
@@ -76,13 +87,35 @@ impl CollisionPipeline {
                 colliders,
                 bodies,
                 events,
+                step_id,
             );
         }
 
-        narrow_phase.register_pairs(None, colliders, bodies, &self.broad_phase_events, events);
+        narrow_phase.register_pairs(
+            None,
+            colliders,
+            bodies,
+            &self.broad_phase_events,
+            events,
+            step_id,
+        );
+        narrow_phase.emit_broad_phase_events(
+            prediction_distance,
+            bodies,
+            colliders,
+            &self.broad_phase_events,
+            events,
+            step_id,
+        );
         narrow_phase.compute_contacts(
             prediction_distance,
             0.0,
+            0.0,
+            0,
+            0.0,
+            0.0,
+            usize::MAX,
+            true,
             bodies,
             colliders,
             &ImpulseJointSet::new(),
@@ -90,8 +123,16 @@ impl CollisionPipeline {
             modified_colliders,
             hooks,
             events,
+            step_id,
+        );
+        narrow_phase.compute_intersections(
+            bodies,
+            colliders,
+            modified_colliders,
+            hooks,
+            events,
+            step_id,
         );
-        narrow_phase.compute_intersections(bodies, colliders, modified_colliders, hooks, events);
     }
 
     fn clear_modified_colliders(
@@ -118,6 +159,10 @@ impl CollisionPipeline {
         hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
     ) {
+        self.step_id += 1;
+        let step_id = self.step_id;
+        narrow_phase.clear_step_transitions();
+
         let modified_bodies = bodies.take_modified();
         let mut modified_colliders = colliders.take_modified();
         let mut removed_colliders = colliders.take_removed();
@@ -155,6 +200,7 @@ impl CollisionPipeline {
             &removed_colliders,
             hooks,
             events,
+            step_id,
             true,
         );
 
@@ -2,14 +2,13 @@ use super::{outlines, DebugRenderBackend};
 use crate::dynamics::{
     GenericJoint, ImpulseJointSet, MultibodyJointSet, RigidBodySet, RigidBodyType,
 };
-use crate::geometry::{Ball, ColliderSet, Cuboid, NarrowPhase, Shape, TypedShape};
+use crate::geometry::{Ball, ColliderSet, Cuboid, NarrowPhase, Shape, ShapeExt, TypedShape};
 #[cfg(feature = "dim3")]
 use crate::geometry::{Cone, Cylinder};
 use crate::math::{Isometry, Point, Real, Vector, DIM};
 use crate::pipeline::debug_render_pipeline::debug_render_backend::DebugRenderObject;
 use crate::pipeline::debug_render_pipeline::DebugRenderStyle;
 use crate::utils::SimdBasis;
-use parry::utils::IsometryOpt;
 use std::any::TypeId;
 use std::collections::HashMap;
 
@@ -114,26 +113,20 @@ impl DebugRenderPipeline {
                     let object = DebugRenderObject::ContactPair(pair, co1, co2);
 
                     if backend.filter_object(object) {
-                        for manifold in &pair.manifolds {
-                            for contact in manifold.contacts() {
-                                let world_subshape_pos1 =
-                                    manifold.subshape_pos1.prepend_to(co1.position());
-                                backend.draw_line(
-                                    object,
-                                    world_subshape_pos1 * contact.local_p1,
-                                    manifold.subshape_pos2.prepend_to(co2.position())
-                                        * contact.local_p2,
-                                    self.style.contact_depth_color,
-                                );
-                                backend.draw_line(
-                                    object,
-                                    world_subshape_pos1 * contact.local_p1,
-                                    world_subshape_pos1
-                                        * (contact.local_p1
-                                            + manifold.local_n1 * self.style.contact_normal_length),
-                                    self.style.contact_normal_color,
-                                );
-                            }
+                        for contact in pair.world_contacts(colliders) {
+                            let point1 = contact.point1();
+                            backend.draw_line(
+                                object,
+                                point1,
+                                contact.point2(),
+                                self.style.contact_depth_color,
+                            );
+                            backend.draw_line(
+                                object,
+                                point1,
+                                point1 + contact.normal() * self.style.contact_normal_length,
+                                self.style.contact_normal_color,
+                            );
                         }
                     }
                 }
@@ -445,7 +438,21 @@ impl DebugRenderPipeline {
                 let vtx = s.to_polyline(self.style.border_subdivisions);
                 backend.draw_line_strip(object, &vtx, pos, &Vector::repeat(1.0), color, true)
             }
-            TypedShape::Custom(_) => {}
+            TypedShape::Custom(_) => {
+                if let Some(voxels) = shape.as_voxels() {
+                    let vtx = &self.instances[&TypeId::of::<Cuboid>()];
+                    let size = Vector::repeat(voxels.cell_size());
+
+                    for key in voxels.surface_cells() {
+                        let cell_pos = pos * Isometry::from(voxels.cell_center(key).coords);
+                        backend.draw_line_strip(object, vtx, &cell_pos, &size, color, true)
+                    }
+                } else if let Some(trimesh) = shape.as_deformable_trimesh() {
+                    for tri in trimesh.triangles() {
+                        self.render_shape(object, backend, &tri, pos, color)
+                    }
+                }
+            }
         }
     }
 
@@ -598,7 +605,101 @@ impl DebugRenderPipeline {
                 let (vtx, idx) = s.to_outline(self.style.border_subdivisions);
                 backend.draw_polyline(object, &vtx, &idx, pos, &Vector::repeat(1.0), color)
             }
-            TypedShape::Custom(_) => {}
+            TypedShape::Custom(_) => {
+                if let Some(voxels) = shape.as_voxels() {
+                    let (vtx, idx) = &self.instances[&TypeId::of::<Cuboid>()];
+                    let size = Vector::repeat(voxels.cell_size());
+
+                    for key in voxels.surface_cells() {
+                        let cell_pos = pos * Isometry::from(voxels.cell_center(key).coords);
+                        backend.draw_polyline(object, vtx, idx, &cell_pos, &size, color)
+                    }
+                } else if let Some(trimesh) = shape.as_deformable_trimesh() {
+                    for tri in trimesh.triangles() {
+                        self.render_shape(object, backend, &tri, pos, color)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DebugRenderMode, DebugRenderPipeline};
+    use crate::dynamics::{ImpulseJointSet, MultibodyJointSet, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderSet, Cuboid, NarrowPhase, SharedShape};
+    use crate::math::{Point, Real, Vector, DIM};
+    use crate::pipeline::{DebugRenderBackend, DebugRenderObject, DebugRenderStyle};
+
+    #[derive(Default)]
+    struct LineCollector {
+        lines: Vec<(Point<Real>, Point<Real>)>,
+    }
+
+    impl DebugRenderBackend for LineCollector {
+        fn draw_line(
+            &mut self,
+            _object: DebugRenderObject,
+            a: Point<Real>,
+            b: Point<Real>,
+            _color: [f32; 4],
+        ) {
+            self.lines.push((a, b));
+        }
+    }
+
+    #[test]
+    fn cuboid_renders_its_edges() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let half_extents = Vector::from_fn(|i, _| (i + 1) as Real);
+        colliders.insert(
+            ColliderBuilder::new(SharedShape::new(Cuboid::new(half_extents)))
+                .translation(Vector::repeat(10.0)),
+        );
+
+        let mut pipeline = DebugRenderPipeline::new(
+            DebugRenderStyle::default(),
+            DebugRenderMode::COLLIDER_SHAPES,
+        );
+        let mut backend = LineCollector::default();
+        pipeline.render(
+            &mut backend,
+            &bodies,
+            &colliders,
+            &ImpulseJointSet::new(),
+            &MultibodyJointSet::new(),
+            &NarrowPhase::new(),
+        );
+
+        #[cfg(feature = "dim2")]
+        let (num_edges, edges_per_axis) = (4, 2);
+        #[cfg(feature = "dim3")]
+        let (num_edges, edges_per_axis) = (12, 4);
+        assert_eq!(backend.lines.len(), num_edges);
+
+        // Every edge is aligned with one axis and has the length of the cuboid along that axis.
+        let mut edges_along_axis = [0; DIM];
+        for (a, b) in &backend.lines {
+            let dir = b - a;
+            let axis = dir.iamax();
+            approx::assert_relative_eq!(
+                dir[axis].abs(),
+                half_extents[axis] * 2.0,
+                epsilon = 1.0e-5
+            );
+            approx::assert_relative_eq!(dir.norm(), dir[axis].abs(), epsilon = 1.0e-5);
+            edges_along_axis[axis] += 1;
+
+            for pt in [a, b] {
+                approx::assert_relative_eq!(
+                    (pt.coords - Vector::repeat(10.0)).abs(),
+                    half_extents,
+                    epsilon = 1.0e-5
+                );
+            }
         }
+        assert_eq!(edges_along_axis, [edges_per_axis; DIM]);
     }
 }
@@ -1,5 +1,7 @@
 use crate::dynamics::RigidBodySet;
-use crate::geometry::{ColliderSet, CollisionEvent, ContactForceEvent, ContactPair};
+use crate::geometry::{
+    BroadPhaseEvent, ColliderSet, CollisionEvent, ContactForceEvent, ContactPair,
+};
 use crate::math::Real;
 use crossbeam::channel::Sender;
 
@@ -13,6 +15,9 @@ bitflags::bitflags! {
         /// If set, Rapier will call `EventHandler::handle_contact_force_event`
         /// whenever relevant for this collider.
         const CONTACT_FORCE_EVENTS = 0b0010;
+        /// If set, Rapier will call `EventHandler::handle_broad_phase_event`
+        /// whenever relevant for this collider.
+        const BROAD_PHASE_EVENTS = 0b0100;
     }
 }
 
@@ -32,7 +37,23 @@ pub trait EventHandler: Send + Sync {
     /// At least one of the involved colliders must have the `ActiveEvents::COLLISION_EVENTS` flag
     /// set.
     ///
+    /// Every `Stopped` event is preceded by exactly one `Started` event for the same pair of
+    /// colliders, including when the pair is destroyed because one of the colliders was removed or
+    /// disabled (in which case the `CollisionEventFlags::REMOVED` flag is set). Removing the
+    /// `COLLISION_EVENTS` flag doesn’t prevent the `Stopped` event of a pair that already started.
+    ///
+    /// The events are delivered in a deterministic order: the events caused by collider removals
+    /// come first, followed by the events of the pairs removed by the broad-phase, then the contact
+    /// events, and finally the intersection events. The last two groups are sorted by the indices
+    /// of the involved collider handles. With CCD substeps, this sequence repeats for each substep.
+    ///
+    /// When a `Stopped` event is caused by the removal of a collider, that collider no longer
+    /// exists in `colliders`. Use [`ColliderSet::removed_collider`] to retrieve its user-data,
+    /// parent rigid-body, and last position.
+    ///
     /// # Parameters
+    /// * `step_id` - The [`PhysicsPipeline::step_id`](crate::pipeline::PhysicsPipeline::step_id)
+    ///   of the step during which the event occurred.
     /// * `event` - The collision event.
     /// * `bodies` - The set of rigid-bodies.
     /// * `colliders` - The set of colliders.
@@ -41,6 +62,7 @@ pub trait EventHandler: Send + Sync {
     ///                    is ever computed).
     fn handle_collision_event(
         &self,
+        step_id: u64,
         bodies: &RigidBodySet,
         colliders: &ColliderSet,
         event: CollisionEvent,
@@ -58,19 +80,51 @@ pub trait EventHandler: Send + Sync {
     /// forces `{0.0, 1.0, 0.0}` and `{0.0, -1.0, 0.0}`, then the total force magnitude tested
     /// against the `contact_force_event_threshold` is `2.0` even if the sum of these forces is actually the
     /// zero vector.
+    ///
+    /// The `step_id` is the
+    /// [`PhysicsPipeline::step_id`](crate::pipeline::PhysicsPipeline::step_id) of the step
+    /// during which the forces were applied.
     fn handle_contact_force_event(
         &self,
+        step_id: u64,
         dt: Real,
         bodies: &RigidBodySet,
         colliders: &ColliderSet,
         contact_pair: &ContactPair,
         total_force_magnitude: Real,
     );
+
+    /// Handle a broad-phase event.
+    ///
+    /// A broad-phase event is emitted when the bounding volumes of two colliders start or stop
+    /// overlapping, before any contact is computed between them. At least one of the involved
+    /// colliders must have the `ActiveEvents::BROAD_PHASE_EVENTS` flag set.
+    ///
+    /// A `PairStarted` event is emitted as soon as the colliders are closer than the prediction
+    /// distance. To avoid emitting events repeatedly while they jitter around that distance, the
+    /// `PairStopped` event is only emitted once they are separated by more than twice the
+    /// prediction distance, or when one of the colliders is removed or disabled.
+    ///
+    /// The `step_id` is the
+    /// [`PhysicsPipeline::step_id`](crate::pipeline::PhysicsPipeline::step_id) of the step
+    /// during which the event occurred.
+    ///
+    /// The default implementation ignores the event.
+    fn handle_broad_phase_event(
+        &self,
+        step_id: u64,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        event: BroadPhaseEvent,
+    ) {
+        let _ = (step_id, bodies, colliders, event);
+    }
 }
 
 impl EventHandler for () {
     fn handle_collision_event(
         &self,
+        _step_id: u64,
         _bodies: &RigidBodySet,
         _colliders: &ColliderSet,
         _event: CollisionEvent,
@@ -80,6 +134,7 @@ impl EventHandler for () {
 
     fn handle_contact_force_event(
         &self,
+        _step_id: u64,
         _dt: Real,
         _bodies: &RigidBodySet,
         _colliders: &ColliderSet,
@@ -93,6 +148,7 @@ impl EventHandler for () {
 pub struct ChannelEventCollector {
     collision_event_sender: Sender<CollisionEvent>,
     contact_force_event_sender: Sender<ContactForceEvent>,
+    broad_phase_event_sender: Option<Sender<BroadPhaseEvent>>,
 }
 
 impl ChannelEventCollector {
@@ -104,13 +160,24 @@ impl ChannelEventCollector {
         Self {
             collision_event_sender,
             contact_force_event_sender,
+            broad_phase_event_sender: None,
         }
     }
+
+    /// Also collect the broad-phase events into the given crossbeam channel.
+    pub fn with_broad_phase_event_sender(
+        mut self,
+        broad_phase_event_sender: Sender<BroadPhaseEvent>,
+    ) -> Self {
+        self.broad_phase_event_sender = Some(broad_phase_event_sender);
+        self
+    }
 }
 
 impl EventHandler for ChannelEventCollector {
     fn handle_collision_event(
         &self,
+        _step_id: u64,
         _bodies: &RigidBodySet,
         _colliders: &ColliderSet,
         event: CollisionEvent,
@@ -121,13 +188,28 @@ impl EventHandler for ChannelEventCollector {
 
     fn handle_contact_force_event(
         &self,
+        step_id: u64,
         dt: Real,
         _bodies: &RigidBodySet,
         _colliders: &ColliderSet,
         contact_pair: &ContactPair,
         total_force_magnitude: Real,
     ) {
-        let result = ContactForceEvent::from_contact_pair(dt, contact_pair, total_force_magnitude);
+        let mut result =
+            ContactForceEvent::from_contact_pair(dt, contact_pair, total_force_magnitude);
+        result.step_id = step_id;
         let _ = self.contact_force_event_sender.send(result);
     }
+
+    fn handle_broad_phase_event(
+        &self,
+        _step_id: u64,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        event: BroadPhaseEvent,
+    ) {
+        if let Some(sender) = &self.broad_phase_event_sender {
+            let _ = sender.send(event);
+        }
+    }
 }
@@ -1,10 +1,15 @@
 //! Structure for combining the various physics components to perform an actual simulation.
 
+pub use body_bundle::{BodyBundle, BodyBundleHandle, BodyBundleHandles};
 pub use collision_pipeline::CollisionPipeline;
 pub use event_handler::{ActiveEvents, ChannelEventCollector, EventHandler};
 pub use physics_hooks::{ActiveHooks, ContactModificationContext, PairFilterContext, PhysicsHooks};
 pub use physics_pipeline::PhysicsPipeline;
+pub use physics_world::PhysicsWorld;
 pub use query_pipeline::{QueryFilter, QueryFilterFlags, QueryPipeline, QueryPipelineMode};
+pub use validation::{HealthIssue, InvalidDensity, InvalidHandle, ValidationError};
+
+pub(crate) use query_pipeline::cast_ray_on_shape;
 
 #[cfg(feature = "debug-render")]
 pub use self::debug_render_pipeline::{
@@ -12,12 +17,15 @@ pub use self::debug_render_pipeline::{
     DebugRenderStyle,
 };
 
+mod body_bundle;
 mod collision_pipeline;
 mod event_handler;
 mod physics_hooks;
 mod physics_pipeline;
+mod physics_world;
 mod query_pipeline;
 mod user_changes;
+mod validation;
 
 #[cfg(feature = "debug-render")]
 mod debug_render_pipeline;
@@ -6,15 +6,16 @@ use crate::dynamics::IslandSolver;
 #[cfg(feature = "parallel")]
 use crate::dynamics::JointGraphEdge;
 use crate::dynamics::{
-    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
-    RigidBodyChanges, RigidBodyHandle, RigidBodyPosition, RigidBodyType,
+    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointHandle,
+    MultibodyJointSet, RigidBodyChanges, RigidBodyHandle, RigidBodyPosition, RigidBodyType,
+    RigidBodyVelocity,
 };
 use crate::geometry::{
     BroadPhase, BroadPhasePairEvent, ColliderChanges, ColliderHandle, ColliderPair,
     ContactManifoldIndex, NarrowPhase, TemporaryInteractionIndex,
 };
 use crate::math::{Real, Vector};
-use crate::pipeline::{EventHandler, PhysicsHooks, QueryPipeline};
+use crate::pipeline::{EventHandler, HealthIssue, PhysicsHooks, QueryPipeline, ValidationError};
 use {crate::dynamics::RigidBodySet, crate::geometry::ColliderSet};
 
 /// The physics pipeline, responsible for stepping the whole physics simulation.
@@ -27,16 +28,35 @@ use {crate::dynamics::RigidBodySet, crate::geometry::ColliderSet};
 /// uses two solvers:
 /// - A velocity based solver based on PGS which computes forces for contact and joint constraints.
 /// - A position based solver based on non-linear PGS which performs constraint stabilization (i.e. correction of errors like penetrations).
-// NOTE: this contains only workspace data, so there is no point in making this serializable.
+// NOTE: apart from the step id and simulation time, this contains only workspace data, so
+//       only these two are serialized: restoring a snapshot keeps the timeline of the events.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-serialize", serde(default))]
 pub struct PhysicsPipeline {
     /// Counters used for benchmarking only.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
     pub counters: Counters,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
     contact_pair_indices: Vec<TemporaryInteractionIndex>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
     manifold_indices: Vec<Vec<ContactManifoldIndex>>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
     joint_constraint_indices: Vec<Vec<ContactManifoldIndex>>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
     broadphase_collider_pairs: Vec<ColliderPair>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
     broad_phase_events: Vec<BroadPhasePairEvent>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
     solvers: Vec<IslandSolver>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    force_field_bodies: Vec<(ColliderHandle, RigidBodyHandle)>,
+    // Bodies frozen for the current timestep, with their velocity and whether they were awake.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    frozen_bodies: Vec<(RigidBodyHandle, RigidBodyVelocity, bool)>,
+    step_id: u64,
+    // Accumulated in double precision so it stays the sum of the timestep lengths even after
+    // hours of simulation with `f32` reals.
+    sim_time: f64,
 }
 
 impl Default for PhysicsPipeline {
@@ -62,9 +82,45 @@ impl PhysicsPipeline {
             joint_constraint_indices: vec![],
             broadphase_collider_pairs: vec![],
             broad_phase_events: vec![],
+            force_field_bodies: vec![],
+            frozen_bodies: vec![],
+            step_id: 0,
+            sim_time: 0.0,
         }
     }
 
+    /// The number of timesteps executed by this pipeline.
+    ///
+    /// This is incremented at the beginning of each call to [`Self::step`], so it is also the
+    /// identifier of the timestep that generated the events passed to the [`EventHandler`], and
+    /// stays the same until the next step. It is zero before the first step.
+    pub fn step_id(&self) -> u64 {
+        self.step_id
+    }
+
+    /// The simulation time, in seconds, at the end of the last timestep executed by this pipeline.
+    ///
+    /// This is the sum of the [`IntegrationParameters::dt`] of every step, so it accounts for
+    /// timestep lengths changing between steps. It is accumulated in double precision, even
+    /// when [`Real`] is `f32`, to remain accurate over long simulations.
+    pub fn sim_time(&self) -> f64 {
+        self.sim_time
+    }
+
+    /// Clears the temporary buffers of this pipeline, but keeps their allocated memory.
+    ///
+    /// None of this data is carried from one step to the next, so this is only needed when
+    /// emptying a whole world in-place to make sure no stale handle is kept around.
+    pub fn clear_caches(&mut self) {
+        self.contact_pair_indices.clear();
+        self.manifold_indices.clear();
+        self.joint_constraint_indices.clear();
+        self.broadphase_collider_pairs.clear();
+        self.broad_phase_events.clear();
+        self.force_field_bodies.clear();
+        self.frozen_bodies.clear();
+    }
+
     fn clear_modified_colliders(
         &mut self,
         colliders: &mut ColliderSet,
@@ -89,6 +145,76 @@ impl PhysicsPipeline {
         }
     }
 
+    /// Temporarily turns the dynamic bodies outside of `step_groups` into kinematic bodies at rest.
+    fn freeze_unstepped_bodies(
+        &mut self,
+        step_groups: u32,
+        islands: &mut IslandManager,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+        multibody_joints: &MultibodyJointSet,
+    ) {
+        if step_groups == u32::MAX {
+            return;
+        }
+
+        for (handle, rb) in bodies.iter_mut_internal() {
+            if rb.body_type != RigidBodyType::Dynamic
+                || step_groups & (1 << rb.step_group) != 0
+                || multibody_joints.rigid_body_link(handle).is_some()
+            {
+                continue;
+            }
+
+            let awake = islands.active_dynamic_set.get(rb.ids.active_set_id) == Some(&handle);
+            self.frozen_bodies.push((handle, rb.vels, awake));
+            rb.body_type = RigidBodyType::KinematicVelocityBased;
+            rb.step_frozen = true;
+            rb.vels = RigidBodyVelocity::zero();
+        }
+
+        for (handle, _, awake) in &self.frozen_bodies {
+            // The contacts computed at the end of the last timestep treat this body as dynamic.
+            for co_handle in bodies[*handle].colliders() {
+                narrow_phase.update_relative_dominances(*co_handle, bodies, colliders);
+            }
+
+            // Frozen bodies must not be put to sleep while they don’t move.
+            if *awake {
+                let ids = bodies[*handle].ids;
+                islands.rigid_body_removed(*handle, &ids, bodies);
+            }
+        }
+    }
+
+    /// Restores the bodies frozen by [`Self::freeze_unstepped_bodies`].
+    fn unfreeze_bodies(
+        &mut self,
+        islands: &mut IslandManager,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+    ) {
+        for (handle, vels, awake) in &self.frozen_bodies {
+            let rb = bodies.index_mut_internal(*handle);
+            rb.body_type = RigidBodyType::Dynamic;
+            rb.step_frozen = false;
+            rb.vels = *vels;
+
+            if *awake {
+                rb.ids.active_set_id = islands.active_dynamic_set.len();
+                islands.active_dynamic_set.push(*handle);
+            }
+        }
+
+        for (handle, _, _) in self.frozen_bodies.drain(..) {
+            for co_handle in bodies[handle].colliders() {
+                narrow_phase.update_relative_dominances(*co_handle, bodies, colliders);
+            }
+        }
+    }
+
     fn detect_collisions(
         &mut self,
         integration_parameters: &IntegrationParameters,
@@ -103,6 +229,7 @@ impl PhysicsPipeline {
         removed_colliders: &[ColliderHandle],
         hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
+        step_id: u64,
         handle_user_changes: bool,
     ) {
         self.counters.stages.collision_detection_time.resume();
@@ -133,6 +260,7 @@ impl PhysicsPipeline {
                 colliders,
                 bodies,
                 events,
+                step_id,
             );
         }
         narrow_phase.register_pairs(
@@ -141,10 +269,25 @@ impl PhysicsPipeline {
             bodies,
             &self.broad_phase_events,
             events,
+            step_id,
         );
-        narrow_phase.compute_contacts(
+        narrow_phase.emit_broad_phase_events(
             integration_parameters.prediction_distance(),
+            bodies,
+            colliders,
+            &self.broad_phase_events,
+            events,
+            step_id,
+        );
+        let num_culled_pairs = narrow_phase.compute_contacts(
+            integration_parameters.prediction_distance(),
+            integration_parameters.restitution_velocity_threshold(),
             integration_parameters.dt,
+            integration_parameters.warmstart_min_contact_age,
+            integration_parameters.warmstart_max_teleport_displacement(),
+            integration_parameters.contact_matching_tolerance(),
+            integration_parameters.max_contact_points_per_manifold.get(),
+            integration_parameters.narrow_phase_culling_enabled,
             bodies,
             colliders,
             impulse_joints,
@@ -152,8 +295,19 @@ impl PhysicsPipeline {
             modified_colliders,
             hooks,
             events,
+            step_id,
+        );
+        narrow_phase.compute_intersections(
+            bodies,
+            colliders,
+            modified_colliders,
+            hooks,
+            events,
+            step_id,
         );
-        narrow_phase.compute_intersections(bodies, colliders, modified_colliders, hooks, events);
+        self.counters
+            .set_ncontact_pairs(narrow_phase.contact_graph().raw_graph().edges.len());
+        self.counters.set_nculled_pairs(num_culled_pairs);
 
         self.counters.cd.narrow_phase_time.pause();
         self.counters.stages.collision_detection_time.pause();
@@ -170,19 +324,23 @@ impl PhysicsPipeline {
         impulse_joints: &mut ImpulseJointSet,
         multibody_joints: &mut MultibodyJointSet,
         events: &dyn EventHandler,
+        step_id: u64,
     ) {
         self.counters.stages.island_construction_time.resume();
+        self.wake_up_force_field_bodies(islands, narrow_phase, bodies, colliders);
         islands.update_active_set_with_contacts(
-            integration_parameters.dt,
-            integration_parameters.length_unit,
+            integration_parameters,
             bodies,
             colliders,
             narrow_phase,
             impulse_joints,
             multibody_joints,
-            integration_parameters.min_island_size,
         );
 
+        if integration_parameters.order_by_user_data {
+            islands.sort_active_islands_by_user_data(bodies);
+        }
+
         if self.manifold_indices.len() < islands.num_islands() {
             self.manifold_indices
                 .resize(islands.num_islands(), Vec::new());
@@ -206,6 +364,32 @@ impl PhysicsPipeline {
             bodies,
             &mut self.joint_constraint_indices,
         );
+
+        if integration_parameters.order_by_user_data {
+            // NOTE: the sorts are stable so the manifolds of a pair, and the joints attached to
+            //       the same bodies with the same `user_data`, keep their relative order.
+            for manifold_indices in &mut self.manifold_indices[..islands.num_islands()] {
+                manifold_indices.sort_by_key(|i| {
+                    let data = &manifolds[*i].data;
+                    (
+                        colliders[data.collider1].user_data,
+                        colliders[data.collider2].user_data,
+                    )
+                });
+            }
+
+            let joints = &impulse_joints.joint_graph().raw_graph().edges;
+            for joint_indices in &mut self.joint_constraint_indices[..islands.num_islands()] {
+                joint_indices.sort_by_key(|i| {
+                    let joint = &joints[*i].weight;
+                    (
+                        bodies[joint.body1].user_data,
+                        bodies[joint.body2].user_data,
+                        joint.data.user_data,
+                    )
+                });
+            }
+        }
         self.counters.stages.island_construction_time.pause();
 
         self.counters.stages.update_time.resume();
@@ -217,6 +401,69 @@ impl PhysicsPipeline {
             let effective_mass = rb.mprops.effective_mass();
             rb.forces
                 .compute_effective_force_and_torque(gravity, &effective_mass);
+            // The gravity wells need to be updated again before the next step.
+            rb.forces.gravity_well_acceleration = Vector::zeros();
+
+            #[cfg(feature = "dim3")]
+            if integration_parameters.gyroscopic_forces_enabled {
+                rb.vels = rb.vels.apply_gyroscopic_forces(
+                    integration_parameters.dt,
+                    &rb.mprops,
+                    &rb.pos.position.rotation,
+                );
+            }
+        }
+
+        // Ramp the motor targets of the joints simulated at this step.
+        let joints = impulse_joints.joints_mut();
+        for joint_indices in &self.joint_constraint_indices[..islands.num_islands()] {
+            for i in joint_indices {
+                joints[*i]
+                    .weight
+                    .data
+                    .advance_motor_ramps(integration_parameters.dt);
+            }
+        }
+        for handle in islands.active_dynamic_bodies() {
+            if let Some(link) = multibody_joints.rigid_body_link(*handle).copied() {
+                if let Some(link) = multibody_joints
+                    .get_multibody_mut_internal(link.multibody)
+                    .and_then(|multibody| multibody.link_mut(link.id))
+                {
+                    link.joint
+                        .data
+                        .advance_motor_ramps(integration_parameters.dt);
+                }
+            }
+        }
+
+        for (field_handle, body_handle) in &self.force_field_bodies {
+            let field_co = &colliders[*field_handle];
+            if let Some(field) = field_co.force_field() {
+                let rb = bodies.index_mut_internal(*body_handle);
+                if rb.is_sleeping() {
+                    // The user explicitly put this body to sleep.
+                    continue;
+                }
+
+                let force = field.force_on_body(
+                    field_co.shape(),
+                    &field_co.pos,
+                    &rb.mprops.effective_mass(),
+                    &rb.mprops.world_com,
+                    &rb.vels.linvel,
+                );
+                rb.forces.force += force;
+                rb.forces.effective_gravity +=
+                    field.gravity_on_body(field_co.shape(), &field_co.pos, &rb.mprops.world_com);
+            }
+        }
+
+        for handle in islands.active_dynamic_bodies() {
+            let rb = bodies.index_mut_internal(*handle);
+            rb.vels_before_solve =
+                rb.forces
+                    .integrate(integration_parameters.dt, &rb.vels, &rb.mprops);
         }
         self.counters.stages.update_time.pause();
 
@@ -226,6 +473,23 @@ impl PhysicsPipeline {
                 .resize_with(islands.num_islands(), IslandSolver::new);
         }
 
+        self.counters.solver.islands.clear();
+        self.counters.solver.nconnected_islands = islands.num_connected_islands();
+        if self.counters.enabled() {
+            for island_id in 0..islands.num_islands() {
+                let island_counters = IslandSolver::island_counters(
+                    island_id,
+                    integration_parameters,
+                    islands,
+                    &manifolds[..],
+                    &self.manifold_indices[island_id],
+                    impulse_joints.joints_mut(),
+                    &self.joint_constraint_indices[island_id],
+                );
+                self.counters.solver.islands.push(island_counters);
+            }
+        }
+
         #[cfg(not(feature = "parallel"))]
         {
             enable_flush_to_zero!();
@@ -306,6 +570,7 @@ impl PhysicsPipeline {
             let pair = narrow_phase.contact_pair_at_index(pair_id);
             let co1 = &colliders[pair.collider1];
             let co2 = &colliders[pair.collider2];
+
             let threshold = co1
                 .effective_contact_force_event_threshold()
                 .min(co2.effective_contact_force_event_threshold());
@@ -317,6 +582,7 @@ impl PhysicsPipeline {
                 //       trigger an event if the force is 0.0 and the threshold is 0.0.
                 if total_magnitude > threshold {
                     events.handle_contact_force_event(
+                        step_id,
                         integration_parameters.dt,
                         bodies,
                         colliders,
@@ -330,6 +596,53 @@ impl PhysicsPipeline {
         self.counters.stages.solver_time.pause();
     }
 
+    /// Collects the dynamic rigid-bodies intersecting a sensor with a force field, and wakes
+    /// them up so they don’t fall asleep while the field is acting on them.
+    fn wake_up_force_field_bodies(
+        &mut self,
+        islands: &mut IslandManager,
+        narrow_phase: &NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+    ) {
+        self.force_field_bodies.clear();
+
+        for (handle1, handle2, intersecting) in narrow_phase.intersection_pairs() {
+            if !intersecting {
+                continue;
+            }
+
+            for (field_handle, other_handle) in [(handle1, handle2), (handle2, handle1)] {
+                let field_co = &colliders[field_handle];
+                let other_co = &colliders[other_handle];
+
+                if field_co.force_field().is_none() || !field_co.is_sensor() || other_co.is_sensor()
+                {
+                    continue;
+                }
+
+                let Some(parent) = other_co.parent() else {
+                    continue;
+                };
+
+                if field_co.parent() == Some(parent) || !bodies[parent].is_dynamic() {
+                    continue;
+                }
+
+                self.force_field_bodies.push((field_handle, parent));
+            }
+        }
+
+        // A body with several colliders inside the same field must only be affected once.
+        self.force_field_bodies
+            .sort_unstable_by_key(|(co, rb)| (co.into_raw_parts(), rb.into_raw_parts()));
+        self.force_field_bodies.dedup();
+
+        for (_, body_handle) in &self.force_field_bodies {
+            islands.wake_up(bodies, *body_handle, true);
+        }
+    }
+
     fn run_ccd_motion_clamping(
         &mut self,
         integration_parameters: &IntegrationParameters,
@@ -339,6 +652,7 @@ impl PhysicsPipeline {
         narrow_phase: &NarrowPhase,
         ccd_solver: &mut CCDSolver,
         events: &dyn EventHandler,
+        step_id: u64,
     ) {
         self.counters.ccd.toi_computation_time.start();
         // Handle CCD
@@ -349,8 +663,14 @@ impl PhysicsPipeline {
             colliders,
             narrow_phase,
             events,
+            step_id,
+        );
+        ccd_solver.clamp_motions_with_min_advancement(
+            integration_parameters.dt,
+            integration_parameters.ccd_min_advancement,
+            bodies,
+            &impacts,
         );
-        ccd_solver.clamp_motions(integration_parameters.dt, bodies, &impacts);
         self.counters.ccd.toi_computation_time.pause();
     }
 
@@ -404,7 +724,210 @@ impl PhysicsPipeline {
         }
     }
 
+    /// Checks that the given sets are consistent with each other.
+    ///
+    /// Returns the first collider or joint found attached to a rigid-body that doesn’t exist in
+    /// `bodies`, or the first half-space collider attached to a dynamic rigid-body. This is called
+    /// automatically at the beginning of [`Self::step`] in debug builds.
+    pub fn validate(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        impulse_joints: &ImpulseJointSet,
+        multibody_joints: &MultibodyJointSet,
+    ) -> Result<(), ValidationError> {
+        for (handle, co) in colliders.iter() {
+            if let Some(parent) = co.parent() {
+                let Some(body) = bodies.get(parent) else {
+                    return Err(ValidationError::ColliderWithRemovedParent {
+                        collider: handle,
+                        parent,
+                    });
+                };
+
+                if body.is_dynamic() && co.shape().as_halfspace().is_some() {
+                    return Err(ValidationError::HalfSpaceOnDynamicBody {
+                        collider: handle,
+                        body: parent,
+                    });
+                }
+            }
+        }
+
+        for (handle, joint) in impulse_joints.iter() {
+            for body in [joint.body1, joint.body2] {
+                if !bodies.contains(body) {
+                    return Err(ValidationError::ImpulseJointWithRemovedBody {
+                        joint: handle,
+                        body,
+                    });
+                }
+            }
+        }
+
+        for multibody in multibody_joints.multibodies() {
+            for link in multibody.links() {
+                if !bodies.contains(link.rigid_body) {
+                    return Err(ValidationError::MultibodyJointWithRemovedBody {
+                        joint: MultibodyJointHandle(link.rigid_body.0),
+                        body: link.rigid_body,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the positions and velocities of the rigid-bodies, and the AABBs of the
+    /// colliders, don’t contain any NaN or infinite value.
+    ///
+    /// This doesn’t panic and can be called at any time, e.g., to find the body responsible for
+    /// a simulation blowing up. An empty vector is returned if no issue was found.
+    pub fn health_check(&self, bodies: &RigidBodySet, colliders: &ColliderSet) -> Vec<HealthIssue> {
+        let mut issues = vec![];
+
+        for (handle, rb) in bodies.iter() {
+            if !rb.position().to_homogeneous().iter().all(|e| e.is_finite()) {
+                issues.push(HealthIssue::NonFiniteBodyPosition(handle));
+            }
+
+            #[cfg(feature = "dim2")]
+            let angvel_is_finite = rb.angvel().is_finite();
+            #[cfg(feature = "dim3")]
+            let angvel_is_finite = rb.angvel().iter().all(|e| e.is_finite());
+
+            if !angvel_is_finite || !rb.linvel().iter().all(|e| e.is_finite()) {
+                issues.push(HealthIssue::NonFiniteBodyVelocity(handle));
+            }
+        }
+
+        for (handle, co) in colliders.iter() {
+            let aabb = co.compute_aabb();
+            if !aabb
+                .mins
+                .iter()
+                .chain(aabb.maxs.iter())
+                .all(|e| e.is_finite())
+            {
+                issues.push(HealthIssue::NonFiniteColliderAabb(handle));
+            }
+        }
+
+        issues
+    }
+
+    /// Moves the origin of the world to `offset`, i.e., translates the whole simulation by `-offset`.
+    ///
+    /// This is meant for floating-origin setups, where the simulated area drifts far away from
+    /// the origin. Every world-space quantity is translated: the positions of all the rigid-bodies
+    /// (including sleeping ones and the next positions of kinematic ones), the positions of the
+    /// colliders, the poses of the multibody links, the broad-phase proxies, and the contact points
+    /// of the narrow-phase. Joint anchors are expressed in the local-space of the rigid-bodies so
+    /// they don’t need any change.
+    ///
+    /// No rigid-body is woken up and no change is flagged: the next step behaves exactly like
+    /// without rebasing, up to the rounding errors of the translation itself. If given, the query
+    /// pipeline is updated to take the new collider positions into account.
+    #[allow(clippy::too_many_arguments)]
+    pub fn shift_world_origin(
+        &self,
+        offset: Vector<Real>,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        multibody_joints: &mut MultibodyJointSet,
+        broad_phase: &mut dyn BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        query_pipeline: Option<&mut QueryPipeline>,
+    ) {
+        for (_, rb) in bodies.bodies.iter_mut() {
+            rb.pos.position.translation.vector -= offset;
+            rb.pos.next_position.translation.vector -= offset;
+            rb.mprops.world_com -= offset;
+        }
+
+        for (_, co) in colliders.colliders.iter_mut() {
+            co.pos.0.translation.vector -= offset;
+        }
+
+        for (_, multibody) in multibody_joints.multibodies.iter_mut() {
+            // The root joint pose is read back from its rigid-body at the next step.
+            for link in multibody.links_mut() {
+                link.local_to_world.translation.vector -= offset;
+            }
+        }
+
+        broad_phase.shift_origin(offset);
+        narrow_phase.shift_origin(offset);
+
+        if let Some(query_pipeline) = query_pipeline {
+            query_pipeline.update(bodies, colliders);
+        }
+    }
+
+    /// Runs [`Self::health_check`] and also checks the contact points computed by the
+    /// narrow-phase.
+    #[cfg(feature = "sanity-checks")]
+    fn health_check_with_contacts(
+        &self,
+        narrow_phase: &NarrowPhase,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+    ) -> Vec<HealthIssue> {
+        let mut issues = self.health_check(bodies, colliders);
+
+        for pair in narrow_phase.contact_pairs() {
+            let contacts_are_finite = pair.manifolds.iter().all(|manifold| {
+                manifold.local_n1.iter().all(|e| e.is_finite())
+                    && manifold.points.iter().all(|pt| {
+                        pt.dist.is_finite()
+                            && pt.local_p1.iter().all(|e| e.is_finite())
+                            && pt.local_p2.iter().all(|e| e.is_finite())
+                    })
+            });
+
+            if !contacts_are_finite {
+                issues.push(HealthIssue::NonFiniteContactPoint(
+                    pair.collider1,
+                    pair.collider2,
+                ));
+            }
+        }
+
+        issues
+    }
+
+    #[cfg(feature = "sanity-checks")]
+    fn run_sanity_checks(
+        &self,
+        stage: &str,
+        narrow_phase: &NarrowPhase,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+    ) {
+        if let Some(issue) = self
+            .health_check_with_contacts(narrow_phase, bodies, colliders)
+            .first()
+        {
+            panic!("Sanity check failed at the {stage} of the timestep: {issue}.");
+        }
+    }
+
     /// Executes one timestep of the physics simulation.
+    ///
+    /// The `gravity` can be different at each call. Changing it doesn’t wake up sleeping
+    /// rigid-bodies, so wake them up explicitly if they must react to the new gravity. See
+    /// [`RigidBody::effective_gravity`](crate::dynamics::RigidBody::effective_gravity) for the
+    /// gravity each rigid-body experienced during the last timestep.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, this panics if [`Self::validate`] detects an inconsistency between the
+    /// given sets, e.g., a collider attached to a rigid-body that was removed from `bodies`.
+    ///
+    /// With the `sanity-checks` feature enabled, this also panics if a NaN or infinite value is
+    /// found in the simulation state at the start or at the end of the timestep. The panic
+    /// message indicates the stage and the handle of the offending body, collider, or contact.
     pub fn step(
         &mut self,
         gravity: &Vector<Real>,
@@ -417,10 +940,75 @@ impl PhysicsPipeline {
         impulse_joints: &mut ImpulseJointSet,
         multibody_joints: &mut MultibodyJointSet,
         ccd_solver: &mut CCDSolver,
+        query_pipeline: Option<&mut QueryPipeline>,
+        hooks: &dyn PhysicsHooks,
+        events: &dyn EventHandler,
+    ) {
+        self.step_with_groups(
+            u32::MAX,
+            gravity,
+            integration_parameters,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+            query_pipeline,
+            hooks,
+            events,
+        )
+    }
+
+    /// Executes one timestep of the physics simulation, only for the dynamic bodies of the
+    /// given step groups.
+    ///
+    /// A dynamic rigid-body is stepped if the bit `1 << rb.step_group()` is set in `step_groups`
+    /// (see [`RigidBody::set_step_group`](crate::dynamics::RigidBody::set_step_group)). This
+    /// allows simulating different regions of a world at different rates, e.g., the surroundings
+    /// of the player at every frame and distant regions every few frames only.
+    ///
+    /// During this timestep, the dynamic bodies that are not stepped are treated as kinematic
+    /// bodies at rest: they are not integrated, don’t take part in islands, and can’t be put to
+    /// sleep or woken up. They still collide with the stepped bodies, acting as static obstacles.
+    /// Their type and velocity are restored at the end of the timestep, but hooks and event
+    /// handlers called during the timestep see them as kinematic bodies with a zero velocity.
+    ///
+    /// Multibody links and non-dynamic bodies are always stepped.
+    ///
+    /// See [`Self::step`] for the other parameters and the panics.
+    pub fn step_with_groups(
+        &mut self,
+        step_groups: u32,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        islands: &mut IslandManager,
+        broad_phase: &mut dyn BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
         mut query_pipeline: Option<&mut QueryPipeline>,
         hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
     ) {
+        #[cfg(debug_assertions)]
+        if let Err(err) = self.validate(bodies, colliders, impulse_joints, multibody_joints) {
+            panic!("Invalid physics state: {err}.");
+        }
+
+        self.step_id += 1;
+        self.sim_time += integration_parameters.dt as f64;
+        let step_id = self.step_id;
+        narrow_phase.clear_step_transitions();
+
+        #[cfg(feature = "sanity-checks")]
+        self.run_sanity_checks("start", narrow_phase, bodies, colliders);
+
         self.counters.reset();
         self.counters.step_started();
 
@@ -475,6 +1063,15 @@ impl PhysicsPipeline {
                 .update_rigid_bodies_internal(bodies, true, false, false);
         }
 
+        self.freeze_unstepped_bodies(
+            step_groups,
+            islands,
+            narrow_phase,
+            bodies,
+            colliders,
+            multibody_joints,
+        );
+
         self.detect_collisions(
             integration_parameters,
             islands,
@@ -488,6 +1085,7 @@ impl PhysicsPipeline {
             &removed_colliders,
             hooks,
             events,
+            step_id,
             true,
         );
 
@@ -584,6 +1182,7 @@ impl PhysicsPipeline {
                 impulse_joints,
                 multibody_joints,
                 events,
+                step_id,
             );
 
             // If CCD is enabled, execute the CCD motion clamping.
@@ -605,6 +1204,7 @@ impl PhysicsPipeline {
                         narrow_phase,
                         ccd_solver,
                         events,
+                        step_id,
                     );
                 }
             }
@@ -626,6 +1226,7 @@ impl PhysicsPipeline {
                 &[],
                 hooks,
                 events,
+                step_id,
                 false,
             );
 
@@ -643,6 +1244,8 @@ impl PhysicsPipeline {
             self.clear_modified_colliders(colliders, &mut modified_colliders);
         }
 
+        self.unfreeze_bodies(islands, narrow_phase, bodies, colliders);
+
         // Finally, make sure we update the world mass-properties of the rigid-bodies
         // that moved. Otherwise, users may end up applying forces with respect to an
         // outdated center of mass.
@@ -657,18 +1260,21 @@ impl PhysicsPipeline {
         self.counters.stages.update_time.pause();
 
         self.counters.step_completed();
+
+        #[cfg(feature = "sanity-checks")]
+        self.run_sanity_checks("end", narrow_phase, bodies, colliders);
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::dynamics::{
-        CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, RigidBodyBuilder,
-        RigidBodySet,
+        CCDSolver, FixedJointBuilder, ImpulseJointSet, IntegrationParameters, IslandManager,
+        RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
     };
     use crate::geometry::{BroadPhaseMultiSap, ColliderBuilder, ColliderSet, NarrowPhase};
-    use crate::math::Vector;
-    use crate::pipeline::PhysicsPipeline;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::{HealthIssue, PhysicsPipeline, PhysicsWorld};
     use crate::prelude::{MultibodyJointSet, RigidBodyType};
 
     #[test]
@@ -937,4 +1543,3452 @@ mod test {
         // Expect body to now be in active_dynamic_set
         assert!(islands.active_dynamic_set.contains(&h));
     }
+
+    #[test]
+    fn global_max_velocities_keep_explosion_bounded() {
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut pipeline = PhysicsPipeline::new();
+        let mut bf = BroadPhaseMultiSap::new();
+        let mut nf = NarrowPhase::new();
+        let mut islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+
+        // Two balls spawned almost entirely inside each other with an unbounded, very stiff
+        // penetration correction that isn’t removed by stabilization. Without any clamping,
+        // they are ejected at huge velocities.
+        let params = IntegrationParameters {
+            normalized_max_corrective_velocity: Real::MAX,
+            contact_natural_frequency: 1.0e4,
+            num_internal_stabilization_iterations: 0,
+            max_linear_velocity: 10.0,
+            max_angular_velocity: 5.0,
+            ..IntegrationParameters::default()
+        };
+
+        let mut handles = vec![];
+        for k in 0..2 {
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * (k as Real * 0.01))
+                .build();
+            let h = bodies.insert(rb);
+            let co = ColliderBuilder::ball(1.0).build();
+            colliders.insert_with_parent(co, h, &mut bodies);
+            handles.push(h);
+        }
+
+        for _ in 0..10 {
+            pipeline.step(
+                &Vector::zeros(),
+                &params,
+                &mut islands,
+                &mut bf,
+                &mut nf,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut CCDSolver::new(),
+                None,
+                &(),
+                &(),
+            );
+
+            for h in &handles {
+                let rb = &bodies[*h];
+                assert!(rb.linvel().norm() <= params.max_linear_velocity * 1.001);
+                #[cfg(feature = "dim3")]
+                assert!(rb.angvel().norm() <= params.max_angular_velocity * 1.001);
+                #[cfg(feature = "dim2")]
+                assert!(rb.angvel().abs() <= params.max_angular_velocity * 1.001);
+                assert!(rb.translation().norm() < 10.0);
+            }
+        }
+
+        // The balls did get pushed apart.
+        assert!(bodies[handles[0]].linvel().norm() > 0.0);
+    }
+
+    #[test]
+    fn global_damping_is_inherited_unless_overridden() {
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut pipeline = PhysicsPipeline::new();
+        let mut bf = BroadPhaseMultiSap::new();
+        let mut nf = NarrowPhase::new();
+        let mut islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+
+        let params = IntegrationParameters {
+            linear_damping: 1.0,
+            ..IntegrationParameters::default()
+        };
+
+        let rb = RigidBodyBuilder::dynamic()
+            .linvel(Vector::x() * 10.0)
+            .additional_mass(1.0);
+        let inherited = bodies.insert(rb.clone());
+        let overridden = bodies.insert(rb.linear_damping(0.0));
+
+        pipeline.step(
+            &Vector::zeros(),
+            &params,
+            &mut islands,
+            &mut bf,
+            &mut nf,
+            &mut bodies,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            &mut CCDSolver::new(),
+            None,
+            &(),
+            &(),
+        );
+
+        assert!(bodies[inherited].linvel().x < 10.0);
+        assert_eq!(bodies[overridden].linvel().x, 10.0);
+
+        assert!(bodies[inherited].inherits_linear_damping());
+        assert_eq!(bodies[inherited].linear_damping(), 0.0);
+        assert_eq!(bodies[inherited].effective_linear_damping(&params), 1.0);
+        assert!(!bodies[overridden].inherits_linear_damping());
+        assert_eq!(bodies[overridden].effective_linear_damping(&params), 0.0);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn gyroscopic_forces_flip_t_handle_without_energy_gain() {
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut pipeline = PhysicsPipeline::new();
+        let mut bf = BroadPhaseMultiSap::new();
+        let mut nf = NarrowPhase::new();
+        let mut islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+
+        let params = IntegrationParameters {
+            gyroscopic_forces_enabled: true,
+            ..IntegrationParameters::default()
+        };
+
+        // A T-handle made of a bar and a stem.
+        let handle = bodies.insert(RigidBodyBuilder::dynamic().can_sleep(false));
+        let bar = ColliderBuilder::cuboid(1.0, 0.1, 0.1);
+        let stem = ColliderBuilder::cuboid(0.1, 0.5, 0.1).translation(Vector::new(0.0, -0.6, 0.0));
+        colliders.insert_with_parent(bar, handle, &mut bodies);
+        colliders.insert_with_parent(stem, handle, &mut bodies);
+
+        // Spin mostly around the intermediate principal axis, with a small perturbation.
+        let mprops = bodies[handle].mass_properties().local_mprops;
+        let inertia = mprops.principal_inertia();
+        let mut axes = [0, 1, 2];
+        axes.sort_by(|a, b| inertia[*a].partial_cmp(&inertia[*b]).unwrap());
+        let mut local_angvel = Vector::zeros();
+        local_angvel[axes[1]] = 10.0;
+        local_angvel[axes[0]] = 0.01;
+        let frame = mprops.principal_inertia_local_frame;
+        bodies[handle].set_angvel(frame * local_angvel, true);
+
+        let initial_energy = bodies[handle].kinetic_energy();
+        let mut num_flips = 0;
+        let mut prev_sign = 1.0;
+
+        for _ in 0..10_000 {
+            pipeline.step(
+                &Vector::zeros(),
+                &params,
+                &mut islands,
+                &mut bf,
+                &mut nf,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut CCDSolver::new(),
+                None,
+                &(),
+                &(),
+            );
+
+            let rb = &bodies[handle];
+            assert!(rb.kinetic_energy() <= initial_energy * 1.0001);
+
+            let inertia_frame = rb.rotation() * frame;
+            let sign = inertia_frame.inverse_transform_vector(rb.angvel())[axes[1]].signum();
+            if sign != prev_sign {
+                num_flips += 1;
+                prev_sign = sign;
+            }
+        }
+
+        assert!(num_flips >= 2);
+    }
+
+    #[test]
+    fn removing_middle_of_sleeping_stack_wakes_up_top() {
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut pipeline = PhysicsPipeline::new();
+        let mut bf = BroadPhaseMultiSap::new();
+        let mut nf = NarrowPhase::new();
+        let mut islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+        let gravity = Vector::y() * -9.81;
+        let params = IntegrationParameters::default();
+
+        // A tower of three boxes resting on a flat floor.
+        let floor = bodies.insert(RigidBodyBuilder::fixed().translation(Vector::y() * -0.5));
+        #[cfg(feature = "dim2")]
+        let (floor_co, box_co) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (floor_co, box_co) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        colliders.insert_with_parent(floor_co, floor, &mut bodies);
+
+        let mut tower = vec![];
+        for k in 0..3 {
+            let rb = RigidBodyBuilder::dynamic().translation(Vector::y() * (0.5 + k as Real));
+            let h = bodies.insert(rb);
+            colliders.insert_with_parent(box_co.clone(), h, &mut bodies);
+            tower.push(h);
+        }
+
+        for _ in 0..1000 {
+            pipeline.step(
+                &gravity,
+                &params,
+                &mut islands,
+                &mut bf,
+                &mut nf,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut CCDSolver::new(),
+                None,
+                &(),
+                &(),
+            );
+            if tower.iter().all(|h| bodies[*h].is_sleeping()) {
+                break;
+            }
+        }
+
+        assert!(tower.iter().all(|h| bodies[*h].is_sleeping()));
+        let top_y = bodies[tower[2]].translation().y;
+
+        bodies.remove(
+            tower[1],
+            &mut islands,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            true,
+        );
+        pipeline.step(
+            &gravity,
+            &params,
+            &mut islands,
+            &mut bf,
+            &mut nf,
+            &mut bodies,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            &mut CCDSolver::new(),
+            None,
+            &(),
+            &(),
+        );
+
+        assert!(!bodies[tower[2]].is_sleeping());
+        assert!(bodies[tower[2]].translation().y < top_y);
+    }
+
+    fn max_penetration_against_fast_platform(platform_prediction: Option<Real>) -> Real {
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut pipeline = PhysicsPipeline::new();
+        let mut bf = BroadPhaseMultiSap::new();
+        let mut nf = NarrowPhase::new();
+        let mut islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+        let params = IntegrationParameters::default();
+
+        // A large platform moving up quickly toward a ball.
+        let platform_half_height = 0.5;
+        let platform =
+            bodies.insert(RigidBodyBuilder::kinematic_velocity_based().linvel(Vector::y() * 20.0));
+        #[cfg(feature = "dim2")]
+        let mut platform_co = ColliderBuilder::cuboid(10.0, platform_half_height);
+        #[cfg(feature = "dim3")]
+        let mut platform_co = ColliderBuilder::cuboid(10.0, platform_half_height, 10.0);
+        platform_co.contact_prediction_distance = platform_prediction;
+        colliders.insert_with_parent(platform_co, platform, &mut bodies);
+
+        let ball_radius = 0.5;
+        let ball = bodies.insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.23));
+        colliders.insert_with_parent(ColliderBuilder::ball(ball_radius), ball, &mut bodies);
+
+        let mut max_penetration: Real = 0.0;
+
+        for _ in 0..20 {
+            pipeline.step(
+                &Vector::zeros(),
+                &params,
+                &mut islands,
+                &mut bf,
+                &mut nf,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut CCDSolver::new(),
+                None,
+                &(),
+                &(),
+            );
+
+            let platform_top = bodies[platform].translation().y + platform_half_height;
+            let ball_bottom = bodies[ball].translation().y - ball_radius;
+            max_penetration = max_penetration.max(platform_top - ball_bottom);
+        }
+
+        // Make sure the ball was actually carried by the platform.
+        assert!(bodies[ball].linvel().y > 10.0);
+        max_penetration
+    }
+
+    #[test]
+    fn collider_contact_prediction_distance_override() {
+        let default_penetration = max_penetration_against_fast_platform(None);
+        let overridden_penetration = max_penetration_against_fast_platform(Some(1.0));
+        assert!(default_penetration > 0.1);
+        assert!(overridden_penetration < 0.02);
+    }
+
+    #[test]
+    fn kinematic_capsule_against_fixed_wall_emits_contact_events() {
+        use crate::geometry::{ActiveCollisionTypes, CollisionEvent};
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut pipeline = PhysicsPipeline::new();
+        let mut bf = BroadPhaseMultiSap::new();
+        let mut nf = NarrowPhase::new();
+        let mut islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+        let params = IntegrationParameters::default();
+
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, _) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        let wall = bodies.insert(RigidBodyBuilder::fixed().translation(Vector::x() * 3.0));
+        colliders.insert_with_parent(ColliderBuilder::ball(1.0), wall, &mut bodies);
+
+        let character = bodies.insert(RigidBodyBuilder::kinematic_position_based());
+        let character_co = ColliderBuilder::capsule_y(0.5, 0.3)
+            .active_collision_types(
+                ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_FIXED,
+            )
+            .active_events(ActiveEvents::COLLISION_EVENTS);
+        colliders.insert_with_parent(character_co, character, &mut bodies);
+
+        let mut started = false;
+
+        for i in 1..=60 {
+            let target = Vector::x() * (i as Real * 0.05);
+            bodies[character].set_next_kinematic_translation(target);
+
+            pipeline.step(
+                &Vector::zeros(),
+                &params,
+                &mut islands,
+                &mut bf,
+                &mut nf,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut CCDSolver::new(),
+                None,
+                &(),
+                &events,
+            );
+
+            while let Ok(event) = collision_recv.try_recv() {
+                started |= matches!(event, CollisionEvent::Started(..));
+            }
+
+            // The contact must not be resolved by the solver: the kinematic body
+            // keeps following its prescribed trajectory.
+            assert_eq!(*bodies[character].translation(), target);
+        }
+
+        assert!(started);
+        let wall_co = bodies[wall].colliders()[0];
+        let character_co = bodies[character].colliders()[0];
+        assert!(
+            nf.contact_pair(wall_co, character_co)
+                .unwrap()
+                .has_any_active_contact
+        );
+    }
+
+    #[test]
+    fn collision_exception_lets_bodies_overlap() {
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut pipeline = PhysicsPipeline::new();
+        let mut bf = BroadPhaseMultiSap::new();
+        let mut nf = NarrowPhase::new();
+        let mut islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+        let gravity = Vector::y() * -9.81;
+        let params = IntegrationParameters::default();
+
+        #[cfg(feature = "dim2")]
+        let floor_co = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let floor_co = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        let floor = bodies.insert(RigidBodyBuilder::fixed().translation(Vector::y() * -0.5));
+        colliders.insert_with_parent(floor_co, floor, &mut bodies);
+
+        // Two cuboids spawned half inside each other.
+        #[cfg(feature = "dim2")]
+        let cuboid = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let cuboid = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        let h1 = bodies.insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 1.0));
+        let h2 = bodies.insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 1.5));
+        colliders.insert_with_parent(cuboid.clone(), h1, &mut bodies);
+        colliders.insert_with_parent(cuboid, h2, &mut bodies);
+
+        assert!(nf.add_collision_exception(h2, h1));
+        assert!(!nf.add_collision_exception(h1, h2));
+        assert!(nf.has_collision_exception(h1, h2));
+
+        for _ in 0..120 {
+            pipeline.step(
+                &gravity,
+                &params,
+                &mut islands,
+                &mut bf,
+                &mut nf,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut CCDSolver::new(),
+                None,
+                &(),
+                &(),
+            );
+        }
+
+        // Both cuboids fall through each other and rest on the floor, at the same place.
+        assert!((bodies[h1].translation().y - 0.5).abs() < 0.05);
+        assert!((bodies[h2].translation().y - 0.5).abs() < 0.05);
+        assert!(bodies[h1].rotation().angle().abs() < 1.0e-3);
+        assert!(bodies[h2].rotation().angle().abs() < 1.0e-3);
+
+        // Removing one of the bodies removes the exception.
+        bodies.remove(
+            h2,
+            &mut islands,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            true,
+        );
+        pipeline.step(
+            &gravity,
+            &params,
+            &mut islands,
+            &mut bf,
+            &mut nf,
+            &mut bodies,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            &mut CCDSolver::new(),
+            None,
+            &(),
+            &(),
+        );
+        assert!(!nf.has_collision_exception(h1, h2));
+        assert_eq!(nf.collision_exceptions().len(), 0);
+    }
+
+    #[test]
+    fn handles_raw_parts_round_trip() {
+        use crate::prelude::*;
+
+        fn pack((index, generation): (u32, u32)) -> u64 {
+            index as u64 | ((generation as u64) << 32)
+        }
+
+        fn unpack(bits: u64) -> (u32, u32) {
+            (bits as u32, (bits >> 32) as u32)
+        }
+
+        let mut islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+
+        // Removing then re-inserting a body reuses its index with a different generation.
+        let removed = bodies.insert(RigidBodyBuilder::dynamic());
+        bodies.remove(
+            removed,
+            &mut islands,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            true,
+        );
+        let h1 = bodies.insert(RigidBodyBuilder::dynamic());
+        let h2 = bodies.insert(RigidBodyBuilder::dynamic());
+        assert_eq!(h1.into_raw_parts().0, removed.into_raw_parts().0);
+        assert_ne!(h1, removed);
+
+        for handle in [removed, h1, h2] {
+            let bits = pack(handle.into_raw_parts());
+            let (index, generation) = unpack(bits);
+            assert_eq!(RigidBodyHandle::from_raw_parts(index, generation), handle);
+            assert_eq!(
+                handle.to_string(),
+                format!("RigidBodyHandle({index}v{generation})")
+            );
+        }
+        let (index, generation) = unpack(pack(removed.into_raw_parts()));
+        let stale = RigidBodyHandle::from_raw_parts(index, generation);
+        assert!(bodies.get(stale).is_none());
+        assert!(bodies.get(h1).is_some());
+
+        let collider = colliders.insert_with_parent(ColliderBuilder::ball(0.5), h1, &mut bodies);
+        let (index, generation) = unpack(pack(collider.into_raw_parts()));
+        let collider_back = ColliderHandle::from_raw_parts(index, generation);
+        assert_eq!(collider_back, collider);
+        assert!(colliders.get(collider_back).is_some());
+        assert_eq!(
+            collider.to_string(),
+            format!("ColliderHandle({index}v{generation})")
+        );
+
+        let joint = impulse_joints.insert(h1, h2, FixedJointBuilder::new(), true);
+        let (index, generation) = unpack(pack(joint.into_raw_parts()));
+        let joint_back = ImpulseJointHandle::from_raw_parts(index, generation);
+        assert_eq!(joint_back, joint);
+        assert!(impulse_joints.get(joint_back).is_some());
+        assert_eq!(
+            joint.to_string(),
+            format!("ImpulseJointHandle({index}v{generation})")
+        );
+
+        let multibody_joint = multibody_joints
+            .insert(h1, h2, FixedJointBuilder::new(), true)
+            .unwrap();
+        let (index, generation) = unpack(pack(multibody_joint.into_raw_parts()));
+        let multibody_joint_back = MultibodyJointHandle::from_raw_parts(index, generation);
+        assert_eq!(multibody_joint_back, multibody_joint);
+        assert!(multibody_joints.get(multibody_joint_back).is_some());
+        assert_eq!(
+            multibody_joint.to_string(),
+            format!("MultibodyJointHandle({index}v{generation})")
+        );
+    }
+
+    #[test]
+    fn invalid_handles_are_reported() {
+        use crate::prelude::*;
+
+        let mut islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let pipeline = PhysicsPipeline::new();
+
+        let h1 = bodies.insert(RigidBodyBuilder::dynamic());
+        let removed = bodies.insert(RigidBodyBuilder::dynamic());
+        bodies.remove(
+            removed,
+            &mut islands,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            true,
+        );
+
+        // Checked insertions.
+        assert_eq!(
+            colliders.try_insert_with_parent(ColliderBuilder::ball(0.5), removed, &mut bodies),
+            Err(InvalidHandle::RigidBody(removed))
+        );
+        assert_eq!(
+            impulse_joints.try_insert(&bodies, h1, removed, FixedJointBuilder::new(), true),
+            Err(InvalidHandle::RigidBody(removed))
+        );
+        assert_eq!(
+            multibody_joints.try_insert(&bodies, removed, h1, FixedJointBuilder::new(), true),
+            Err(InvalidHandle::RigidBody(removed))
+        );
+        assert!(colliders.is_empty());
+        assert_eq!(impulse_joints.len(), 0);
+        assert_eq!(multibody_joints.multibodies().count(), 0);
+        assert_eq!(
+            pipeline.validate(&bodies, &colliders, &impulse_joints, &multibody_joints),
+            Ok(())
+        );
+
+        // Collider attached to a rigid-body missing from the set.
+        let collider = colliders
+            .try_insert_with_parent(ColliderBuilder::ball(0.5), h1, &mut bodies)
+            .unwrap();
+        assert_eq!(
+            pipeline.validate(
+                &RigidBodySet::new(),
+                &colliders,
+                &impulse_joints,
+                &multibody_joints
+            ),
+            Err(ValidationError::ColliderWithRemovedParent {
+                collider,
+                parent: h1
+            })
+        );
+
+        // Unchecked joint insertions with a removed rigid-body.
+        let joint = impulse_joints.insert(h1, removed, FixedJointBuilder::new(), true);
+        assert_eq!(
+            pipeline.validate(&bodies, &colliders, &impulse_joints, &multibody_joints),
+            Err(ValidationError::ImpulseJointWithRemovedBody {
+                joint,
+                body: removed
+            })
+        );
+        impulse_joints.remove(joint, true);
+
+        let joint = multibody_joints
+            .insert(h1, removed, FixedJointBuilder::new(), true)
+            .unwrap();
+        assert_eq!(
+            pipeline.validate(&bodies, &colliders, &impulse_joints, &multibody_joints),
+            Err(ValidationError::MultibodyJointWithRemovedBody {
+                joint,
+                body: removed
+            })
+        );
+        multibody_joints.remove(joint, true);
+
+        // Half-space attached to a dynamic rigid-body.
+        let halfspace = colliders.insert_with_parent(
+            ColliderBuilder::halfspace(Vector::y_axis()),
+            h1,
+            &mut bodies,
+        );
+        assert_eq!(
+            pipeline.validate(&bodies, &colliders, &impulse_joints, &multibody_joints),
+            Err(ValidationError::HalfSpaceOnDynamicBody {
+                collider: halfspace,
+                body: h1
+            })
+        );
+        bodies[h1].set_body_type(RigidBodyType::Fixed, true);
+        assert_eq!(
+            pipeline.validate(&bodies, &colliders, &impulse_joints, &multibody_joints),
+            Ok(())
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Invalid physics state: the impulse joint")]
+    fn step_with_invalid_joint_panics_in_debug() {
+        use crate::prelude::*;
+
+        let mut islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+
+        let h1 = bodies.insert(RigidBodyBuilder::dynamic());
+        let removed = bodies.insert(RigidBodyBuilder::dynamic());
+        bodies.remove(
+            removed,
+            &mut islands,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            true,
+        );
+        impulse_joints.insert(h1, removed, FixedJointBuilder::new(), true);
+
+        PhysicsPipeline::new().step(
+            &Vector::zeros(),
+            &IntegrationParameters::default(),
+            &mut islands,
+            &mut BroadPhaseMultiSap::new(),
+            &mut NarrowPhase::new(),
+            &mut bodies,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            &mut CCDSolver::new(),
+            None,
+            &(),
+            &(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid rigid-body handle")]
+    fn indexing_with_stale_handle_panics_with_handle() {
+        use crate::prelude::*;
+
+        let mut bodies = RigidBodySet::new();
+        let removed = bodies.insert(RigidBodyBuilder::dynamic());
+        bodies.remove(
+            removed,
+            &mut IslandManager::new(),
+            &mut ColliderSet::new(),
+            &mut ImpulseJointSet::new(),
+            &mut MultibodyJointSet::new(),
+            true,
+        );
+        let _ = &bodies[removed];
+    }
+
+    #[test]
+    fn global_sleep_thresholds() {
+        use crate::prelude::*;
+
+        // Returns the handles of a body creeping at 0.1m/s in zero-gravity, using the global
+        // sleep thresholds, and of a similar body overriding them.
+        fn creeping_bodies(
+            bodies: &mut RigidBodySet,
+            colliders: &mut ColliderSet,
+        ) -> (RigidBodyHandle, RigidBodyHandle) {
+            let inherit = bodies.insert(RigidBodyBuilder::dynamic().linvel(Vector::x() * 0.1));
+            let overridden = bodies.insert(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * 10.0)
+                    .linvel(Vector::x() * 0.1),
+            );
+            let activation = bodies[overridden].activation_mut();
+            activation.normalized_linear_threshold =
+                Some(RigidBodyActivation::default_normalized_linear_threshold());
+            activation.angular_threshold = Some(RigidBodyActivation::default_angular_threshold());
+            activation.time_until_sleep = Some(RigidBodyActivation::default_time_until_sleep());
+            colliders.insert_with_parent(ColliderBuilder::ball(0.5), inherit, bodies);
+            colliders.insert_with_parent(ColliderBuilder::ball(0.5), overridden, bodies);
+            (inherit, overridden)
+        }
+
+        for (params, num_steps, inherit_sleeps) in [
+            // With the default thresholds, both bodies fall asleep after 2 seconds.
+            (IntegrationParameters::default(), 180, true),
+            // With a lowered global threshold, the first body never sleeps.
+            (
+                IntegrationParameters {
+                    normalized_sleep_linear_threshold: 0.05,
+                    ..Default::default()
+                },
+                600,
+                false,
+            ),
+            // With a smaller timestep, bodies still sleep after 2 seconds, not 120 steps.
+            (
+                IntegrationParameters {
+                    dt: 1.0 / 240.0,
+                    ..Default::default()
+                },
+                400,
+                false,
+            ),
+            (
+                IntegrationParameters {
+                    dt: 1.0 / 240.0,
+                    ..Default::default()
+                },
+                540,
+                true,
+            ),
+        ] {
+            let mut world = PhysicsWorld::new(Vector::zeros());
+            world.integration_parameters = params;
+            let (inherit, overridden) = creeping_bodies(&mut world.bodies, &mut world.colliders);
+
+            for _ in 0..num_steps {
+                world.step();
+            }
+
+            let elapsed = num_steps as Real * params.dt;
+            assert_eq!(
+                world.bodies[inherit].is_sleeping(),
+                inherit_sleeps,
+                "after {elapsed}s"
+            );
+            assert_eq!(
+                world.bodies[overridden].is_sleeping(),
+                elapsed > 2.0,
+                "after {elapsed}s"
+            );
+
+            if !inherit_sleeps {
+                approx::assert_relative_eq!(
+                    world.bodies[inherit].translation().x,
+                    elapsed * 0.1,
+                    epsilon = 1.0e-3
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn body_sleep_thresholds_override_global_ones() {
+        use crate::prelude::*;
+
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        let creeping = |world: &mut PhysicsWorld, y: Real| {
+            let body = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * y)
+                    .linvel(Vector::x() * 0.1),
+            );
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.5), body);
+            body
+        };
+        let inherit = creeping(&mut world, 0.0);
+        let never_sleeps = creeping(&mut world, 10.0);
+        world.bodies[never_sleeps]
+            .activation_mut()
+            .normalized_linear_threshold = Some(0.05);
+        let sleeps_early = creeping(&mut world, 20.0);
+        world.bodies[sleeps_early].activation_mut().time_until_sleep = Some(0.5);
+
+        for _ in 0..60 {
+            world.step();
+        }
+        assert!(!world.bodies[inherit].is_sleeping());
+        assert!(!world.bodies[never_sleeps].is_sleeping());
+        assert!(world.bodies[sleeps_early].is_sleeping());
+
+        for _ in 0..120 {
+            world.step();
+        }
+        assert!(world.bodies[inherit].is_sleeping());
+        assert!(!world.bodies[never_sleeps].is_sleeping());
+    }
+
+    #[test]
+    fn creeping_box_falls_asleep_exactly_once() {
+        use crate::prelude::*;
+
+        let mut world = PhysicsWorld::default();
+        let ground = world.insert_body(RigidBodyBuilder::fixed());
+        #[cfg(feature = "dim2")]
+        let (ground_shape, cuboid) = (
+            ColliderBuilder::cuboid(20.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground_shape, cuboid) = (
+            ColliderBuilder::cuboid(20.0, 0.5, 20.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        world.insert_collider_with_parent(ground_shape.translation(Vector::y() * -0.5), ground);
+        let body = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .linvel(Vector::x() * 0.5),
+        );
+        world.insert_collider_with_parent(cuboid, body);
+
+        let mut sleeping = false;
+        let mut transitions = 0;
+
+        for _ in 0..2000 {
+            world.step();
+
+            if world.bodies[body].is_sleeping() != sleeping {
+                sleeping = !sleeping;
+                transitions += 1;
+            }
+        }
+
+        assert!(sleeping);
+        assert_eq!(transitions, 1);
+    }
+
+    #[test]
+    fn jittering_body_falls_asleep_with_sleep_hysteresis() {
+        use crate::prelude::*;
+
+        // A body whose velocity alternates between slightly below and slightly above the linear
+        // sleep threshold.
+        let jitter = |params: IntegrationParameters| {
+            let mut world = PhysicsWorld::new(Vector::zeros());
+            world.integration_parameters = params;
+            let body = world.insert_body(RigidBodyBuilder::dynamic());
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.5), body);
+
+            for i in 0..600 {
+                if world.bodies[body].is_sleeping() {
+                    return Some(i);
+                }
+
+                let speed = if i % 2 == 0 { 0.25 } else { 0.45 };
+                world.bodies[body].set_linvel(Vector::x() * speed, false);
+                world.step();
+            }
+
+            None
+        };
+
+        // The smoothed energy stays below the enter threshold: the body falls asleep after
+        // `time_until_sleep`.
+        let num_steps = jitter(IntegrationParameters::default()).unwrap();
+        assert!((120..=125).contains(&num_steps), "{num_steps}");
+
+        // Comparing the instantaneous energy, the countdown only progresses every other step
+        // since the energy alternates between below the enter threshold and below the exit one.
+        let params = IntegrationParameters {
+            sleep_energy_smoothing_time: 0.0,
+            ..Default::default()
+        };
+        let num_steps = jitter(params).unwrap();
+        assert!((240..=250).contains(&num_steps), "{num_steps}");
+
+        // Without hysteresis, the countdown keeps restarting.
+        let params = IntegrationParameters {
+            sleep_energy_smoothing_time: 0.0,
+            sleep_exit_energy_threshold: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(jitter(params), None);
+    }
+
+    fn world_with_nan_velocity() -> (PhysicsWorld, RigidBodyHandle) {
+        let mut world = PhysicsWorld::default();
+        let mut handles = vec![];
+
+        for i in 0..3 {
+            let body = world.insert_body(
+                RigidBodyBuilder::dynamic().translation(Vector::x() * (i as Real * 3.0)),
+            );
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.5), body);
+            handles.push(body);
+        }
+
+        world.step();
+        assert!(world
+            .physics_pipeline
+            .health_check(&world.bodies, &world.colliders)
+            .is_empty());
+
+        world.bodies[handles[1]].set_linvel(Vector::x() * Real::NAN, true);
+        (world, handles[1])
+    }
+
+    #[test]
+    fn health_check_reports_nan_velocity() {
+        let (world, faulty) = world_with_nan_velocity();
+        assert_eq!(
+            world
+                .physics_pipeline
+                .health_check(&world.bodies, &world.colliders),
+            vec![HealthIssue::NonFiniteBodyVelocity(faulty)]
+        );
+    }
+
+    #[cfg(feature = "sanity-checks")]
+    #[test]
+    #[should_panic(expected = "Sanity check failed at the start of the timestep")]
+    fn step_with_nan_velocity_panics_with_sanity_checks() {
+        let (mut world, _) = world_with_nan_velocity();
+        world.step();
+    }
+
+    #[test]
+    fn shift_world_origin_does_not_perturb_the_simulation() {
+        use crate::prelude::*;
+
+        let far = Vector::repeat(1024.0);
+        let build = || {
+            let mut world = PhysicsWorld::default();
+            #[cfg(feature = "dim2")]
+            let (ground, cuboid, joint) = (
+                ColliderBuilder::cuboid(20.0, 0.5),
+                ColliderBuilder::cuboid(0.5, 0.5),
+                RevoluteJointBuilder::new(),
+            );
+            #[cfg(feature = "dim3")]
+            let (ground, cuboid, joint) = (
+                ColliderBuilder::cuboid(20.0, 0.5, 20.0),
+                ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+                RevoluteJointBuilder::new(Vector::z_axis()),
+            );
+            world.insert_collider(ground.translation(far - Vector::y() * 0.5));
+
+            // A falling box, a sleeping box, and a kinematic box.
+            let falling =
+                world.insert_body(RigidBodyBuilder::dynamic().translation(far + Vector::y()));
+            world.insert_collider_with_parent(cuboid.clone(), falling);
+            let sleeping = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(far + Vector::x() * 3.0 + Vector::y() * 0.5)
+                    .sleeping(true),
+            );
+            world.insert_collider_with_parent(cuboid.clone(), sleeping);
+            let kinematic = world.insert_body(
+                RigidBodyBuilder::kinematic_velocity_based()
+                    .translation(far - Vector::x() * 3.0 + Vector::y() * 2.0)
+                    .linvel(-Vector::x()),
+            );
+            world.insert_collider_with_parent(cuboid, kinematic);
+
+            // A multibody pendulum.
+            let anchor = world.insert_body(
+                RigidBodyBuilder::fixed().translation(far + Vector::x() * 8.0 + Vector::y() * 5.0),
+            );
+            let bob = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(far + Vector::x() * 10.0 + Vector::y() * 5.0),
+            );
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.25), bob);
+            world
+                .insert_multibody_joint(
+                    anchor,
+                    bob,
+                    joint.local_anchor2(Point::from(-Vector::x() * 2.0)),
+                )
+                .unwrap();
+
+            (world, [falling, sleeping, kinematic, bob])
+        };
+
+        let (mut reference, handles) = build();
+        let (mut rebased, _) = build();
+
+        for _ in 0..30 {
+            reference.step();
+            rebased.step();
+        }
+
+        rebased.shift_world_origin(far);
+        assert!(rebased.bodies[handles[1]].is_sleeping());
+
+        for _ in 0..30 {
+            reference.step();
+            rebased.step();
+        }
+
+        for handle in handles {
+            let (expected, actual) = (&reference.bodies[handle], &rebased.bodies[handle]);
+            approx::assert_relative_eq!(
+                *actual.translation(),
+                expected.translation() - far,
+                epsilon = 1.0e-3
+            );
+            assert_eq!(actual.is_sleeping(), expected.is_sleeping());
+        }
+
+        for (handle, co) in rebased.colliders.iter() {
+            approx::assert_relative_eq!(
+                *co.translation(),
+                reference.colliders[handle].translation() - far,
+                epsilon = 1.0e-3
+            );
+        }
+
+        assert_eq!(
+            rebased.narrow_phase.contact_pairs().count(),
+            reference.narrow_phase.contact_pairs().count()
+        );
+    }
+
+    #[test]
+    fn restitution_velocity_threshold_override() {
+        use crate::prelude::*;
+
+        // Drops a bouncy marble and returns its number of bounces, and whether it fell asleep.
+        let count_bounces = |threshold: Option<Real>| {
+            let mut world = PhysicsWorld::default();
+            #[cfg(feature = "dim2")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+            world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+            let marble =
+                world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 1.0));
+            let mut collider = ColliderBuilder::ball(0.05)
+                .restitution(0.9)
+                .restitution_combine_rule(CoefficientCombineRule::Max);
+            if let Some(threshold) = threshold {
+                collider = collider.restitution_velocity_threshold(threshold);
+            }
+            world.insert_collider_with_parent(collider, marble);
+
+            let mut bounces = 0;
+            let mut was_falling = false;
+            for _ in 0..1200 {
+                world.step();
+                let vy = world.bodies[marble].linvel().y;
+                if was_falling && vy > 1.0e-2 {
+                    bounces += 1;
+                }
+                was_falling = vy < -1.0e-2;
+            }
+
+            (bounces, world.bodies[marble].is_sleeping())
+        };
+
+        let (default_bounces, default_sleeps) = count_bounces(None);
+        let (marble_bounces, marble_sleeps) = count_bounces(Some(0.05));
+        assert!(
+            marble_bounces >= default_bounces + 5,
+            "{marble_bounces} bounces with the override, {default_bounces} without"
+        );
+        assert!(default_sleeps);
+        assert!(marble_sleeps);
+    }
+
+    #[test]
+    fn perfectly_elastic_bounce_preserves_height() {
+        use crate::prelude::*;
+        use std::num::NonZeroUsize;
+
+        // Drops a ball with a restitution of 1 and returns the apex of its first bounce.
+        let bounce_height = |num_solver_iterations: usize| {
+            let mut world = PhysicsWorld::default();
+            world.integration_parameters.num_solver_iterations =
+                NonZeroUsize::new(num_solver_iterations).unwrap();
+            #[cfg(feature = "dim2")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+            world.insert_collider(ground.translation(Vector::y() * -0.5).restitution(1.0));
+
+            let ball =
+                world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.5));
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.5).restitution(1.0), ball);
+
+            let mut bounced = false;
+            let mut apex: Real = 0.0;
+            for _ in 0..300 {
+                world.step();
+                let rb = &world.bodies[ball];
+                if rb.linvel().y > 0.0 {
+                    bounced = true;
+                } else if bounced {
+                    break;
+                }
+                apex = apex.max(rb.translation().y);
+            }
+
+            assert!(bounced);
+            apex - 0.5
+        };
+
+        for num_solver_iterations in [1, 4, 16] {
+            let height = bounce_height(num_solver_iterations);
+            assert!(
+                (height - 2.0).abs() <= 2.0 * 0.02,
+                "bounced up to {height} instead of 2.0 with {num_solver_iterations} iterations"
+            );
+        }
+    }
+
+    #[test]
+    fn anisotropic_friction() {
+        use crate::prelude::*;
+
+        // Launches a cuboid along the `x` axis on a floor with a low friction coefficient along
+        // `low_friction_axis`, and returns the distance it traveled.
+        let sliding_distance = |low_friction_axis: UnitVector<Real>| {
+            let mut world = PhysicsWorld::default();
+            #[cfg(feature = "dim2")]
+            let floor = ColliderBuilder::cuboid(100.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let floor = ColliderBuilder::cuboid(100.0, 0.5, 100.0);
+            world.insert_collider(
+                floor
+                    .translation(Vector::y() * -0.5)
+                    .friction(1.0)
+                    .anisotropic_friction(low_friction_axis, 0.05),
+            );
+
+            let body = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * 0.5)
+                    .linvel(Vector::x() * 5.0),
+            );
+            #[cfg(feature = "dim2")]
+            let cuboid = ColliderBuilder::cuboid(0.5, 0.5);
+            #[cfg(feature = "dim3")]
+            let cuboid = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+            world.insert_collider_with_parent(
+                cuboid
+                    .friction(1.0)
+                    .friction_combine_rule(CoefficientCombineRule::Min),
+                body,
+            );
+
+            for _ in 0..300 {
+                world.step();
+            }
+
+            world.bodies[body].translation().x
+        };
+
+        #[cfg(feature = "dim2")]
+        let high_friction_axis = Vector::y_axis();
+        #[cfg(feature = "dim3")]
+        let high_friction_axis = Vector::z_axis();
+        let along_low_friction = sliding_distance(Vector::x_axis());
+        let along_high_friction = sliding_distance(high_friction_axis);
+
+        // With a 5m/s initial velocity, the cuboid travels about 19m in 5 seconds with a 0.05
+        // friction coefficient, but stops after 1.27m with a friction coefficient of 1.0.
+        assert!(
+            along_low_friction > 10.0 * along_high_friction,
+            "{along_low_friction} along the low-friction axis, {along_high_friction} otherwise"
+        );
+    }
+
+    #[test]
+    fn rolling_friction() {
+        use crate::prelude::*;
+
+        // Launches a ball on a flat plane and returns its velocity after `num_steps`.
+        let rolling_ball_velocity = |rolling_friction: Real, num_steps: usize| {
+            let mut world = PhysicsWorld::default();
+            #[cfg(feature = "dim2")]
+            let floor = ColliderBuilder::cuboid(100.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let floor = ColliderBuilder::cuboid(100.0, 0.5, 100.0);
+            world.insert_collider(floor.translation(Vector::y() * -0.5));
+
+            let ball = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * 0.5)
+                    .linvel(Vector::x() * 3.0),
+            );
+            world.insert_collider_with_parent(
+                ColliderBuilder::ball(0.5)
+                    .rolling_friction(rolling_friction)
+                    .friction_combine_rule(CoefficientCombineRule::Max),
+                ball,
+            );
+
+            for _ in 0..num_steps {
+                world.step();
+            }
+
+            *world.bodies[ball].linvel()
+        };
+
+        // Without rolling friction, the ball rolls forever (after losing some velocity to the
+        // sliding friction when it starts rolling).
+        assert!(rolling_ball_velocity(0.0, 600).x > 1.5);
+        // With rolling friction, it comes to rest.
+        let linvel = rolling_ball_velocity(0.05, 600);
+        assert!(linvel.norm() < 1.0e-2, "{linvel}");
+    }
+
+    #[test]
+    fn force_field_buoyancy() {
+        use crate::prelude::*;
+
+        // A water volume below y = 0, pushing bodies up with an acceleration stronger than gravity.
+        let mut world = PhysicsWorld::default();
+        let water = ForceField::new()
+            .acceleration(Vector::y() * 20.0)
+            .linear_drag(2.0)
+            .boundary_width(1.0);
+        #[cfg(feature = "dim2")]
+        let volume = ColliderBuilder::cuboid(10.0, 5.0);
+        #[cfg(feature = "dim3")]
+        let volume = ColliderBuilder::cuboid(10.0, 5.0, 10.0);
+        world.insert_collider(
+            volume
+                .translation(Vector::y() * -5.0)
+                .sensor(true)
+                .force_field(water),
+        );
+
+        let body = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * -4.0));
+        #[cfg(feature = "dim2")]
+        let shape = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let shape = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        world.insert_collider_with_parent(shape, body);
+
+        // The buoyancy increases with the depth of the box until it compensates gravity.
+        let floating_height = world.gravity.y / 20.0;
+
+        let mut max_height = Real::MIN;
+        let mut crossings = 0;
+        let mut was_above = false;
+        for _ in 0..1200 {
+            world.step();
+            let y = world.bodies[body].translation().y;
+            max_height = max_height.max(y);
+
+            let is_above = y > floating_height;
+            if is_above != was_above {
+                crossings += 1;
+                was_above = is_above;
+            }
+        }
+
+        // It overshoots the floating height, oscillates around it, and settles.
+        assert!(max_height > floating_height + 0.1, "{max_height}");
+        assert!(crossings > 2, "{crossings}");
+        let rb = &world.bodies[body];
+        assert!(
+            (rb.translation().y - floating_height).abs() < 1.0e-2,
+            "{}",
+            rb.translation()
+        );
+        assert!(rb.linvel().norm() < 0.1, "{}", rb.linvel());
+        assert!(!rb.is_sleeping());
+    }
+
+    #[test]
+    fn overlapping_force_fields_add_up() {
+        use crate::prelude::*;
+
+        let mut world = PhysicsWorld {
+            gravity: Vector::zeros(),
+            ..PhysicsWorld::default()
+        };
+        let wind = ForceField::new().force(Vector::x() * 1.0);
+        for _ in 0..2 {
+            world.insert_collider(ColliderBuilder::ball(100.0).sensor(true).force_field(wind));
+        }
+        let attractor = ForceField::new()
+            .radial_acceleration(2.0, ForceFieldFalloff::Linear { radius: 1000.0 });
+        world.insert_collider(
+            ColliderBuilder::ball(100.0)
+                .translation(Vector::y() * 10.0)
+                .sensor(true)
+                .force_field(attractor),
+        );
+
+        // The body has a mass of 2 and is also made of two colliders inside the same fields,
+        // which must not be counted twice.
+        let body = world.insert_body(RigidBodyBuilder::dynamic());
+        for _ in 0..2 {
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.5).mass(1.0), body);
+        }
+
+        world.step();
+        let dt = world.integration_parameters.dt;
+        let expected = Vector::x() * (2.0 / 2.0) * dt + Vector::y() * 2.0 * 0.99 * dt;
+        let linvel = *world.bodies[body].linvel();
+        assert!(
+            (linvel - expected).norm() < 1.0e-4,
+            "{linvel} != {expected}"
+        );
+    }
+
+    #[test]
+    fn effective_gravity_points_to_planet_center() {
+        use crate::prelude::*;
+
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        let planet_gravity = 10.0;
+        let planet = ForceField::new().radial_acceleration(
+            planet_gravity,
+            ForceFieldFalloff::InverseSquare { min_distance: 1.0 },
+        );
+        world.insert_collider(ColliderBuilder::ball(1.0));
+        world.insert_collider(
+            ColliderBuilder::ball(100.0)
+                .sensor(true)
+                .force_field(planet),
+        );
+
+        // Bodies on circular orbits around the planet.
+        let orbiting: Vec<_> = [3.0, 5.0, 8.0]
+            .into_iter()
+            .map(|radius: Real| {
+                let speed = (planet_gravity / radius).sqrt();
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic()
+                        .translation(Vector::x() * radius)
+                        .linvel(Vector::y() * speed),
+                );
+                world.insert_collider_with_parent(ColliderBuilder::ball(0.1), body);
+                body
+            })
+            .collect();
+
+        for _ in 0..300 {
+            world.step();
+
+            for body in &orbiting {
+                let rb = &world.bodies[*body];
+                let to_center = -rb.center_of_mass().coords;
+                let distance = to_center.norm();
+                let expected = to_center * planet_gravity / (distance * distance * distance);
+                // The gravity is evaluated at the beginning of the step.
+                assert!(
+                    (rb.effective_gravity() - expected).norm() < expected.norm() * 0.1,
+                    "{} != {expected}",
+                    rb.effective_gravity()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn changing_gravity_leaves_sleeping_bodies_asleep() {
+        use crate::prelude::*;
+
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        world.insert_collider(ground.translation(Vector::y() * -0.5));
+        let body = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .gravity_scale(2.0),
+        );
+        world.insert_collider_with_parent(cuboid, body);
+
+        while !world.bodies[body].is_sleeping() {
+            world.step();
+        }
+        let rest_pos = *world.bodies[body].position();
+        assert_eq!(world.bodies[body].effective_gravity(), world.gravity * 2.0);
+
+        // The gravity can be different at each step without waking up the sleeping body.
+        for i in 0..60 {
+            world.gravity = Vector::y() * -(9.81 + i as Real * 0.01);
+            world.step();
+            assert!(world.bodies[body].is_sleeping());
+            assert_eq!(*world.bodies[body].position(), rest_pos);
+        }
+
+        // Its effective gravity is updated as soon as it wakes up.
+        world.bodies[body].wake_up(true);
+        world.step();
+        assert_eq!(world.bodies[body].effective_gravity(), world.gravity * 2.0);
+    }
+
+    #[test]
+    fn per_joint_erp_overrides_drift_correction() {
+        use crate::dynamics::{GenericJointBuilder, JointAxesMask};
+        use crate::math::Point;
+
+        let mut world = PhysicsWorld::default();
+
+        // Two identical pendulums, one with a soft drift correction and one with a stiff one.
+        let pendulums: Vec<_> = [(-3.0, 0.1), (3.0, 0.8)]
+            .into_iter()
+            .map(|(x, erp)| {
+                let anchor =
+                    world.insert_body(RigidBodyBuilder::fixed().translation(Vector::x() * x));
+                let bob = world
+                    .insert_body(RigidBodyBuilder::dynamic().translation(Vector::x() * (x + 1.0)));
+                world.insert_collider_with_parent(ColliderBuilder::ball(0.25).density(10.0), bob);
+                let joint = GenericJointBuilder::new(JointAxesMask::LIN_AXES)
+                    .local_anchor2(Point::from(-Vector::x()))
+                    .erp(erp)
+                    .softness(0.0);
+                world.insert_impulse_joint(anchor, bob, joint);
+                (anchor, bob)
+            })
+            .collect();
+
+        // Pull both bobs away from their joint anchor.
+        for (_, bob) in &pendulums {
+            let pos = world.bodies[*bob].translation() - Vector::y() * 0.5;
+            world.bodies[*bob].set_translation(pos, true);
+        }
+
+        for _ in 0..3 {
+            world.step();
+        }
+
+        let errors: Vec<_> = pendulums
+            .iter()
+            .map(|(anchor, bob)| {
+                let anchor1 = world.bodies[*anchor].translation();
+                let anchor2 = world.bodies[*bob].position() * Point::from(-Vector::x());
+                (anchor2.coords - anchor1).norm()
+            })
+            .collect();
+
+        assert!(errors[0] < 0.5 && errors[1] < 0.5, "{errors:?}");
+        assert!(errors[1] < errors[0] * 0.25, "{errors:?}");
+    }
+
+    #[test]
+    fn joint_position_solver_removes_welded_beam_drift() {
+        use crate::dynamics::FixedJointBuilder;
+        use crate::math::Point;
+
+        const NUM_SEGMENTS: usize = 10;
+
+        // A beam made of heavy segments welded together, cantilevered from a fixed anchor.
+        // Returns the height of its free end, and the largest linear and angular separation
+        // of its joint frames after settling.
+        let simulate_beam = |position_solver_enabled: bool| {
+            let mut world = PhysicsWorld::default();
+            let mut parent = world.insert_body(RigidBodyBuilder::fixed());
+            let mut joints = vec![];
+
+            for k in 0..NUM_SEGMENTS {
+                let segment = world.insert_body(
+                    RigidBodyBuilder::dynamic().translation(Vector::x() * (k as Real + 0.5)),
+                );
+                world.insert_collider_with_parent(
+                    ColliderBuilder::cuboid(
+                        0.5,
+                        0.1,
+                        #[cfg(feature = "dim3")]
+                        0.1,
+                    )
+                    .density(100.0),
+                    segment,
+                );
+                let local_anchor1 = if k == 0 {
+                    Point::origin()
+                } else {
+                    Point::from(Vector::x() * 0.5)
+                };
+                let joint = FixedJointBuilder::new()
+                    .local_anchor1(local_anchor1)
+                    .local_anchor2(Point::from(-Vector::x() * 0.5))
+                    .contacts_enabled(false)
+                    .position_solver_enabled(position_solver_enabled);
+                joints.push(world.insert_impulse_joint(parent, segment, joint));
+                parent = segment;
+            }
+
+            for _ in 0..300 {
+                world.step();
+            }
+
+            let mut max_lin_error: Real = 0.0;
+            let mut max_ang_error: Real = 0.0;
+            for handle in &joints {
+                let joint = world.impulse_joints.get(*handle).unwrap();
+                let frame1 = world.bodies[joint.body1].position() * joint.data.local_frame1;
+                let frame2 = world.bodies[joint.body2].position() * joint.data.local_frame2;
+                max_lin_error = max_lin_error
+                    .max((frame2.translation.vector - frame1.translation.vector).norm());
+                max_ang_error = max_ang_error.max(frame1.rotation.angle_to(&frame2.rotation).abs());
+            }
+
+            let tip = world.bodies[parent].position() * Point::from(Vector::x() * 0.5);
+            (
+                tip.y,
+                max_lin_error,
+                max_ang_error,
+                world.integration_parameters.allowed_linear_error(),
+            )
+        };
+
+        let (tip_without, ..) = simulate_beam(false);
+        let (tip_with, lin_error, ang_error, allowed_error) = simulate_beam(true);
+
+        assert!(lin_error < allowed_error, "{lin_error} >= {allowed_error}");
+        assert!(ang_error < allowed_error, "{ang_error}");
+        // The sag left is only due to the joints’ softness, which the position solver doesn’t
+        // increase.
+        assert!(
+            tip_with > tip_without - 1.0e-3,
+            "{tip_with} < {tip_without}"
+        );
+        assert!(tip_with > -0.25, "{tip_with}");
+    }
+
+    #[test]
+    fn parameters_scaled_for_dt_preserve_the_trajectory() {
+        use crate::dynamics::{GenericJointBuilder, JointAxesMask};
+        use crate::math::Point;
+
+        let pendulum = |params: IntegrationParameters| {
+            let mut world = PhysicsWorld {
+                integration_parameters: params,
+                ..PhysicsWorld::default()
+            };
+            let anchor = world.insert_body(RigidBodyBuilder::fixed());
+            let bob = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::x()));
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.25), bob);
+            let joint = GenericJointBuilder::new(JointAxesMask::LIN_AXES)
+                .local_anchor2(Point::from(-Vector::x()));
+            world.insert_impulse_joint(anchor, bob, joint);
+            (world, bob)
+        };
+
+        let params = IntegrationParameters {
+            linear_damping: 0.5,
+            angular_damping: 0.5,
+            ..IntegrationParameters::default()
+        };
+        let scaled = params.scaled_for_dt(params.dt / 2.0);
+        assert_eq!(scaled.num_solver_iterations.get(), 2);
+        assert!(scaled.linear_damping < params.linear_damping);
+
+        let (mut world1, bob1) = pendulum(params);
+        let (mut world2, bob2) = pendulum(scaled);
+
+        for _ in 0..120 {
+            world1.step();
+            world2.step();
+            world2.step();
+
+            let pos1 = world1.bodies[bob1].translation();
+            let pos2 = world2.bodies[bob2].translation();
+            assert!((pos1 - pos2).norm() < 1.0e-3, "{pos1:?} vs. {pos2:?}");
+        }
+
+        // The pendulum actually swung.
+        assert!(world1.bodies[bob1].translation().y < -0.5);
+    }
+
+    #[test]
+    fn broad_phase_events_with_hysteresis() {
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut world = PhysicsWorld::default();
+        let (collision_send, _) = crossbeam::channel::unbounded();
+        let (contact_force_send, _) = crossbeam::channel::unbounded();
+        let (broad_phase_send, broad_phase_recv) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send)
+            .with_broad_phase_event_sender(broad_phase_send);
+
+        #[cfg(feature = "dim2")]
+        let cuboid = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let cuboid = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        let fixed = world.insert_collider(cuboid.clone());
+        let moving = world.insert_collider(
+            cuboid
+                .clone()
+                .translation(Vector::x() * 5.0)
+                .active_events(ActiveEvents::BROAD_PHASE_EVENTS),
+        );
+        // Overlaps the fixed box, but none of them generates broad-phase events.
+        world.insert_collider(cuboid.translation(-Vector::x() * 0.5));
+
+        let prediction = world.integration_parameters.prediction_distance();
+        let mut gaps = vec![4.0, 2.0, 1.0];
+        // Jitter around the distance where the AABBs start overlapping.
+        for _ in 0..10 {
+            gaps.push(prediction * 0.5);
+            gaps.push(prediction * 1.5);
+        }
+        gaps.extend([1.0, 4.0]);
+
+        let mut received = vec![];
+        for gap in gaps {
+            world.colliders[moving].set_translation(Vector::x() * (1.0 + gap));
+            world.step_with_events(&(), &events);
+
+            while let Ok(event) = broad_phase_recv.try_recv() {
+                received.push((gap, event));
+            }
+        }
+
+        let received: Vec<_> = received
+            .into_iter()
+            .map(|(gap, event)| {
+                let pair = (event.collider1(), event.collider2());
+                assert!(
+                    pair == (fixed, moving) || pair == (moving, fixed),
+                    "{event:?}"
+                );
+                (gap, event.started())
+            })
+            .collect();
+        assert_eq!(received, vec![(prediction * 0.5, true), (1.0, false)]);
+    }
+
+    #[test]
+    fn sensor_margin_prevents_flickering_intersections() {
+        use crate::geometry::ActiveCollisionTypes;
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let count_events = |sensor_margin: Real| {
+            let mut world = PhysicsWorld::default();
+            let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+            let (contact_force_send, _) = crossbeam::channel::unbounded();
+            let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+            // A pressure plate with its top surface at y = 0.
+            #[cfg(feature = "dim2")]
+            let plate = ColliderBuilder::cuboid(2.0, 1.0);
+            #[cfg(feature = "dim3")]
+            let plate = ColliderBuilder::cuboid(2.0, 1.0, 2.0);
+            world.insert_collider(
+                plate
+                    .translation(-Vector::y())
+                    .sensor(true)
+                    .sensor_margin(sensor_margin)
+                    .active_collision_types(ActiveCollisionTypes::all())
+                    .active_events(ActiveEvents::COLLISION_EVENTS),
+            );
+            let ball = world.insert_collider(ColliderBuilder::ball(0.5).translation(Vector::y()));
+
+            let mut heights = vec![1.0, 0.4];
+            for _ in 0..10 {
+                heights.push(0.501);
+                heights.push(0.499);
+            }
+            heights.push(1.0);
+
+            let mut num_events = 0;
+            for height in heights {
+                world.colliders[ball].set_translation(Vector::y() * height);
+                world.step_with_events(&(), &events);
+
+                while let Ok(event) = collision_recv.try_recv() {
+                    assert!(event.sensor());
+                    num_events += 1;
+                }
+
+                if sensor_margin > 0.0 {
+                    let within_margin = world
+                        .narrow_phase
+                        .intersection_graph()
+                        .interactions()
+                        .any(|pair| pair.within_margin);
+                    assert_eq!(within_margin, (height - 0.5).abs() < 0.01);
+                }
+            }
+
+            num_events
+        };
+
+        assert!(count_events(0.0) > 2);
+        assert_eq!(count_events(0.01), 2);
+    }
+
+    #[test]
+    fn sensors_of_sleeping_bodies_detect_intruders() {
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut world = PhysicsWorld::default();
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, _) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        #[cfg(feature = "dim2")]
+        let (ground, mine_shape) = (
+            ColliderBuilder::cuboid(50.0, 0.5),
+            ColliderBuilder::cuboid(0.25, 0.25),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, mine_shape) = (
+            ColliderBuilder::cuboid(50.0, 0.5, 50.0),
+            ColliderBuilder::cuboid(0.25, 0.25, 0.25),
+        );
+        world.insert_collider(ground.translation(-Vector::y() * 0.5));
+
+        // A landmine resting on the ground, with a sensor larger than itself.
+        let mine = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.25));
+        world.insert_collider_with_parent(mine_shape, mine);
+        let sensor = world.insert_collider_with_parent(
+            ColliderBuilder::ball(1.5)
+                .sensor(true)
+                .active_events(ActiveEvents::COLLISION_EVENTS),
+            mine,
+        );
+
+        for _ in 0..600 {
+            world.step_with_events(&(), &events);
+        }
+        assert!(world.bodies[mine].is_sleeping());
+        // Ignore the intersection between the sensor and the ground.
+        while collision_recv.try_recv().is_ok() {}
+
+        // A ball rolling into the sensor.
+        let ball = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * -5.0 + Vector::y() * 0.5)
+                .linvel(Vector::x() * 5.0),
+        );
+        let ball_collider = world.insert_collider_with_parent(ColliderBuilder::ball(0.5), ball);
+
+        let mut detected = false;
+        for _ in 0..60 {
+            world.step_with_events(&(), &events);
+
+            if let Ok(event) = collision_recv.try_recv() {
+                assert!(event.started() && event.sensor());
+                assert_eq!(
+                    [event.collider1(), event.collider2()],
+                    [sensor, ball_collider]
+                );
+                detected = true;
+                break;
+            }
+        }
+
+        assert!(detected);
+        // Intersections don’t wake up the sensor’s body.
+        assert!(world.bodies[mine].is_sleeping());
+    }
+
+    #[test]
+    fn collision_events_match_narrow_phase_state() {
+        use crate::geometry::{ColliderHandle, CollisionEvent};
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+        use std::collections::HashSet;
+
+        fn key(h1: ColliderHandle, h2: ColliderHandle) -> (ColliderHandle, ColliderHandle) {
+            if h1.into_raw_parts() <= h2.into_raw_parts() {
+                (h1, h2)
+            } else {
+                (h2, h1)
+            }
+        }
+
+        let run = || {
+            // Simple deterministic pseudo-random number generator.
+            let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+            let mut rand = move || {
+                seed = seed
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                (seed >> 40) as Real / (1u64 << 24) as Real
+            };
+
+            let mut world = PhysicsWorld::default();
+            world.integration_parameters.max_ccd_substeps = 4;
+            let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+            let (contact_force_send, _) = crossbeam::channel::unbounded();
+            let event_handler = ChannelEventCollector::new(collision_send, contact_force_send);
+
+            #[cfg(feature = "dim2")]
+            let ground = ColliderBuilder::cuboid(4.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let ground = ColliderBuilder::cuboid(4.0, 0.5, 4.0);
+            world.insert_collider(
+                ground
+                    .translation(-Vector::y() * 0.5)
+                    .active_events(ActiveEvents::COLLISION_EVENTS),
+            );
+
+            let mut handles = vec![];
+            let mut shadow = HashSet::new();
+            let mut log = vec![];
+
+            for _ in 0..600 {
+                // Randomly modify the scene.
+                if handles.len() < 25 && rand() < 0.3 {
+                    let position = Vector::repeat(rand() * 4.0 - 2.0) + Vector::y() * 4.0;
+                    let body = world.insert_body(
+                        RigidBodyBuilder::dynamic()
+                            .translation(position)
+                            .linvel(Vector::y() * (rand() * -40.0))
+                            .ccd_enabled(rand() < 0.3),
+                    );
+                    #[cfg(feature = "dim2")]
+                    let shape = ColliderBuilder::cuboid(0.3, 0.2);
+                    #[cfg(feature = "dim3")]
+                    let shape = ColliderBuilder::cuboid(0.3, 0.2, 0.25);
+                    let shape = if rand() < 0.5 {
+                        shape
+                    } else {
+                        ColliderBuilder::ball(0.1 + rand() * 0.3)
+                    };
+                    world.insert_collider_with_parent(
+                        shape
+                            .sensor(rand() < 0.2)
+                            .active_events(ActiveEvents::COLLISION_EVENTS),
+                        body,
+                    );
+                    handles.push(body);
+                }
+
+                if !handles.is_empty() {
+                    let i = (rand() * handles.len() as Real) as usize % handles.len();
+                    let body = handles[i];
+                    let collider = world.bodies[body].colliders().first().copied();
+                    let action = rand();
+
+                    if action < 0.03 {
+                        let _ = world.remove_body(handles.swap_remove(i));
+                    } else if action < 0.06 {
+                        if let Some(collider) = collider {
+                            let _ = world.remove_collider(collider);
+                        }
+                    } else if action < 0.1 {
+                        if let Some(collider) = collider {
+                            let collider = &mut world.colliders[collider];
+                            collider.set_enabled(!collider.is_enabled());
+                        }
+                    } else if action < 0.13 {
+                        if let Some(collider) = collider {
+                            let collider = &mut world.colliders[collider];
+                            collider.set_sensor(!collider.is_sensor());
+                        }
+                    } else if action < 0.18 {
+                        world.bodies[body].sleep();
+                    } else if action < 0.22 {
+                        world.bodies[body]
+                            .set_translation(Vector::repeat(rand() * 2.0 - 1.0), true);
+                    } else if action < 0.27 {
+                        if let Some(collider) = collider {
+                            let collider = &mut world.colliders[collider];
+                            collider.set_active_events(
+                                collider.active_events() ^ ActiveEvents::COLLISION_EVENTS,
+                            );
+                        }
+                    }
+                }
+
+                world.step_with_events(&(), &event_handler);
+
+                while let Ok(event) = collision_recv.try_recv() {
+                    let pair = key(event.collider1(), event.collider2());
+                    log.push((event.started(), pair, event.removed(), event.sensor()));
+
+                    match event {
+                        CollisionEvent::Started(..) => {
+                            assert!(shadow.insert(pair), "started twice: {event:?}")
+                        }
+                        CollisionEvent::Stopped(..) => {
+                            assert!(shadow.remove(&pair), "stopped before started: {event:?}")
+                        }
+                    }
+                }
+
+                let touching: HashSet<_> = world
+                    .narrow_phase
+                    .contact_pairs()
+                    .filter(|pair| pair.has_any_active_contact)
+                    .map(|pair| key(pair.collider1, pair.collider2))
+                    .chain(
+                        world
+                            .narrow_phase
+                            .intersection_pairs()
+                            .filter(|(_, _, intersecting)| *intersecting)
+                            .map(|(h1, h2, _)| key(h1, h2)),
+                    )
+                    .collect();
+                // Pairs that started before their colliders disabled collision events are
+                // only stopped once they stop touching.
+                assert!(shadow.is_subset(&touching));
+
+                for pair in touching {
+                    let events = world.colliders[pair.0].active_events()
+                        | world.colliders[pair.1].active_events();
+                    if events.contains(ActiveEvents::COLLISION_EVENTS) {
+                        assert!(shadow.contains(&pair), "missing started event: {pair:?}");
+                    }
+                }
+            }
+
+            log
+        };
+
+        let log = run();
+        assert!(log.iter().any(|(started, ..)| *started));
+        assert!(log.iter().any(|(_, _, removed, _)| *removed));
+        assert!(log.iter().any(|(_, _, _, sensor)| *sensor));
+        // Same scene, same events in the same order.
+        assert_eq!(log, run());
+    }
+
+    #[test]
+    fn polled_transitions_match_collision_events() {
+        use crate::geometry::CollisionEvent;
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut world = PhysicsWorld::default();
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, _) = crossbeam::channel::unbounded();
+        let event_handler = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        #[cfg(feature = "dim2")]
+        let (ground, sensor) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(10.0, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, sensor) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+        );
+        world.insert_collider(
+            ground
+                .translation(-Vector::y() * 0.5)
+                .active_events(ActiveEvents::COLLISION_EVENTS),
+        );
+        world.insert_collider(
+            sensor
+                .translation(Vector::y() * 2.0)
+                .sensor(true)
+                .active_events(ActiveEvents::COLLISION_EVENTS),
+        );
+
+        // Balls falling through the sensor onto the ground, at different times.
+        let balls: Vec<_> = (0..4)
+            .map(|i| {
+                let body = world.insert_body(RigidBodyBuilder::dynamic().translation(
+                    Vector::x() * (i as Real * 2.0 - 3.0) + Vector::y() * (4.0 + i as Real),
+                ));
+                let collider = world.insert_collider_with_parent(
+                    ColliderBuilder::ball(0.25).active_events(ActiveEvents::COLLISION_EVENTS),
+                    body,
+                );
+                (body, collider)
+            })
+            .collect();
+
+        let mut num_started = 0;
+        let mut num_stopped = 0;
+        let mut num_proximities = 0;
+
+        for step in 0..150 {
+            if step == 100 {
+                let _ = world.remove_collider(balls[0].1);
+            }
+            if step == 110 {
+                let _ = world.remove_body(balls[1].0);
+            }
+
+            world.step_with_events(&(), &event_handler);
+
+            let mut started = vec![];
+            let mut stopped = vec![];
+            let mut proximities = vec![];
+            while let Ok(event) = collision_recv.try_recv() {
+                let pair = (event.collider1(), event.collider2());
+                match event {
+                    CollisionEvent::Started(..) if event.sensor() => {
+                        proximities.push((pair, true, false))
+                    }
+                    CollisionEvent::Stopped(..) if event.sensor() => {
+                        proximities.push((pair, false, event.removed()))
+                    }
+                    CollisionEvent::Started(..) => started.push(pair),
+                    CollisionEvent::Stopped(..) => stopped.push((pair, event.removed())),
+                }
+            }
+
+            let narrow_phase = &world.narrow_phase;
+            assert!(narrow_phase
+                .contacts_started()
+                .all(|t| t.has_any_active_contact));
+            assert_eq!(
+                narrow_phase
+                    .contacts_started()
+                    .map(|t| (t.collider1, t.collider2))
+                    .collect::<Vec<_>>(),
+                started
+            );
+            assert_eq!(
+                narrow_phase
+                    .contacts_stopped()
+                    .map(|t| ((t.collider1, t.collider2), t.removed))
+                    .collect::<Vec<_>>(),
+                stopped
+            );
+            assert_eq!(
+                narrow_phase
+                    .proximities_changed()
+                    .map(|t| ((t.collider1, t.collider2), t.intersecting, t.removed))
+                    .collect::<Vec<_>>(),
+                proximities
+            );
+
+            num_started += started.len();
+            num_stopped += stopped.len();
+            num_proximities += proximities.len();
+        }
+
+        assert!(num_started >= 4, "{num_started}");
+        assert!(num_stopped >= 2, "{num_stopped}");
+        assert!(num_proximities >= 8, "{num_proximities}");
+    }
+
+    #[test]
+    fn box_stack_is_stable_from_the_first_step() {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let (ground, cuboid, manifold_len) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+            2,
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cuboid, manifold_len) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+            4,
+        );
+        world.insert_collider(ground.translation(-Vector::y() * 0.5));
+        let stack: Vec<_> = (0..10)
+            .map(|i| {
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic().translation(Vector::y() * (0.5 + i as Real)),
+                );
+                world.insert_collider_with_parent(cuboid.clone(), body);
+                body
+            })
+            .collect();
+
+        // The cuboid-cuboid manifolds are complete right away instead of being built
+        // incrementally over several steps.
+        world.step();
+        assert_eq!(world.narrow_phase.contact_pairs().count(), 10);
+        for pair in world.narrow_phase.contact_pairs() {
+            assert_eq!(pair.manifolds.len(), 1);
+            assert!(pair.manifolds[0].data.solver_contacts.len() >= manifold_len);
+        }
+
+        // The stack stays below the sleep thresholds, so it falls asleep as soon as
+        // `time_until_sleep` elapsed.
+        let dt = world.integration_parameters.dt;
+        let time_until_sleep = world.integration_parameters.time_until_sleep;
+        let max_steps = (time_until_sleep / dt).ceil() as usize + 1;
+        for _ in 1..max_steps {
+            world.step();
+        }
+
+        for (i, body) in stack.iter().enumerate() {
+            let body = &world.bodies[*body];
+            assert!(body.is_sleeping(), "box {i} is still awake");
+            let expected = Vector::y() * (0.5 + i as Real);
+            assert!(
+                (body.translation() - expected).norm() < 0.05,
+                "box {i} drifted"
+            );
+        }
+    }
+
+    #[test]
+    fn objects_rest_on_a_halfspace() {
+        use crate::geometry::{Ray, SharedShape};
+        use crate::math::{Isometry, Point};
+        use crate::pipeline::QueryFilter;
+
+        let ground_height = 1.0;
+        let mut world = PhysicsWorld::default();
+        world.insert_collider(
+            ColliderBuilder::halfspace(Vector::y_axis()).translation(Vector::y() * ground_height),
+        );
+
+        // Each shape, with the distance from its center to its lowest point.
+        let mut shapes = vec![
+            (ColliderBuilder::ball(0.5), 0.5),
+            (ColliderBuilder::capsule_y(0.3, 0.2), 0.5),
+            (
+                ColliderBuilder::compound(vec![
+                    (
+                        Isometry::translation(
+                            0.5,
+                            0.0,
+                            #[cfg(feature = "dim3")]
+                            0.0,
+                        ),
+                        SharedShape::ball(0.5),
+                    ),
+                    (
+                        Isometry::translation(
+                            -0.5,
+                            0.0,
+                            #[cfg(feature = "dim3")]
+                            0.0,
+                        ),
+                        SharedShape::ball(0.5),
+                    ),
+                ]),
+                0.5,
+            ),
+        ];
+        #[cfg(feature = "dim2")]
+        shapes.extend([
+            (ColliderBuilder::cuboid(0.5, 0.5), 0.5),
+            (ColliderBuilder::round_cuboid(0.4, 0.4, 0.1), 0.5),
+        ]);
+        #[cfg(feature = "dim3")]
+        shapes.extend([
+            (ColliderBuilder::cuboid(0.5, 0.5, 0.5), 0.5),
+            (ColliderBuilder::round_cuboid(0.4, 0.4, 0.4, 0.1), 0.5),
+            (ColliderBuilder::cylinder(0.5, 0.5), 0.5),
+            (ColliderBuilder::cone(0.5, 0.5), 0.5),
+        ]);
+
+        // Drop the shapes far from the origin too, where a huge cuboid would lack precision.
+        let mut dropped = vec![];
+        for (i, (shape, half_height)) in shapes.into_iter().enumerate() {
+            for x in [-5000.0, 0.0, 3000.0] {
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic()
+                        .translation(Vector::x() * (x + i as Real * 3.0) + Vector::y() * 3.0),
+                );
+                world.insert_collider_with_parent(shape.clone(), body);
+                dropped.push((body, half_height));
+            }
+        }
+
+        for _ in 0..300 {
+            world.step();
+        }
+
+        for (body, half_height) in dropped {
+            let body = &world.bodies[body];
+            let height = body.translation().y - half_height;
+            assert!(body.is_sleeping());
+            assert!((height - ground_height).abs() < 2.0e-3, "{height}");
+        }
+
+        // Scene queries hit the half-space too.
+        let origin = Point::from(Vector::x() * 1.0e4 + Vector::y() * 10.0);
+        let ray = Ray::new(origin, -Vector::y());
+        world.update_query_pipeline();
+        let (_, toi) = world
+            .cast_ray(&ray, Real::MAX, true, QueryFilter::only_fixed())
+            .unwrap();
+        approx::assert_relative_eq!(toi, 10.0 - ground_height);
+    }
+
+    #[test]
+    fn ccd_detects_impacts_of_fast_rotating_bodies() {
+        use crate::prelude::*;
+
+        // A long rod spinning by 2.4 radians per step around its center. Its tip passes through
+        // the thin wall without any of its initial and final positions being close to it.
+        let rod_direction_after_one_step = |ccd_enabled: bool| {
+            let mut world = PhysicsWorld::new(Vector::zeros());
+            #[cfg(feature = "dim2")]
+            let (rotation, angvel, wall) = (-1.2, 144.0, ColliderBuilder::cuboid(0.05, 0.3));
+            #[cfg(feature = "dim3")]
+            let (rotation, angvel, wall) = (
+                Vector::z() * -1.2,
+                Vector::z() * 144.0,
+                ColliderBuilder::cuboid(0.05, 0.3, 1.0),
+            );
+            world.insert_collider(wall.translation(Vector::x() * 1.5));
+            let rod = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .rotation(rotation)
+                    .angvel(angvel)
+                    .ccd_enabled(ccd_enabled),
+            );
+            #[cfg(feature = "dim2")]
+            let rod_shape = ColliderBuilder::cuboid(2.0, 0.1);
+            #[cfg(feature = "dim3")]
+            let rod_shape = ColliderBuilder::cuboid(2.0, 0.1, 0.1);
+            world.insert_collider_with_parent(rod_shape, rod);
+            world.step();
+            let dir = world.bodies[rod].rotation() * Vector::x();
+            dir.y.atan2(dir.x)
+        };
+
+        // Without CCD, the rod tunnels through the wall.
+        approx::assert_relative_eq!(rod_direction_after_one_step(false), 1.2, epsilon = 1.0e-3);
+
+        // With CCD, the rotation stops when the rod’s leading face hits the wall’s
+        // bottom corner (1.45, -0.3), i.e., at about -0.27 radians.
+        let corner: Vector<Real> = Vector::x() * 1.45 - Vector::y() * 0.3;
+        let impact_angle = corner.y.atan2(corner.x) - (0.1 / corner.norm()).asin();
+        let angle = rod_direction_after_one_step(true);
+        assert!(
+            angle <= impact_angle + 1.0e-3 && angle > impact_angle - 0.05,
+            "{angle} != {impact_angle}"
+        );
+    }
+
+    #[test]
+    fn additional_solver_iterations_are_reported_per_island() {
+        use crate::prelude::*;
+        use std::num::NonZeroUsize;
+
+        // 50 separate piles of boxes. The first one is flagged as high-priority
+        // through the additional solver iterations of its top box.
+        let build_world = |num_solver_iterations: usize, additional_iterations: usize| {
+            let mut world = PhysicsWorld::new(Vector::y() * -9.81);
+            world.integration_parameters.num_solver_iterations =
+                NonZeroUsize::new(num_solver_iterations).unwrap();
+            // Don’t merge the piles into bigger islands.
+            world.integration_parameters.min_island_size = 1;
+            world.physics_pipeline.counters.enable();
+
+            let mut high_priority = None;
+            for i in 0..50 {
+                let x = i as Real * 3.0;
+                #[cfg(feature = "dim2")]
+                let ground = ColliderBuilder::cuboid(1.0, 0.1);
+                #[cfg(feature = "dim3")]
+                let ground = ColliderBuilder::cuboid(1.0, 0.1, 1.0);
+                world.insert_collider(ground.translation(Vector::x() * x));
+
+                for k in 0..4 {
+                    let body = world.insert_body(
+                        RigidBodyBuilder::dynamic()
+                            .translation(Vector::x() * x + Vector::y() * (0.6 + k as Real)),
+                    );
+                    #[cfg(feature = "dim2")]
+                    let shape = ColliderBuilder::cuboid(0.5, 0.5);
+                    #[cfg(feature = "dim3")]
+                    let shape = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+                    world.insert_collider_with_parent(shape, body);
+
+                    if i == 0 && k == 3 {
+                        world.bodies[body].set_additional_solver_iterations(additional_iterations);
+                        high_priority = Some(body);
+                    }
+                }
+            }
+
+            (world, high_priority.unwrap())
+        };
+
+        let (mut mixed, high_priority) = build_world(2, 6);
+        let (mut all_high, _) = build_world(8, 0);
+
+        for _ in 0..30 {
+            mixed.step();
+            all_high.step();
+
+            let island_counters = &mixed.physics_pipeline.counters.solver.islands;
+            assert_eq!(island_counters.len(), 50);
+            let high_priority_island = mixed.bodies[high_priority].ids.active_island_id;
+
+            for (island_id, counters) in island_counters.iter().enumerate() {
+                let expected_iterations = if island_id == high_priority_island {
+                    8
+                } else {
+                    2
+                };
+                assert_eq!(counters.niterations, expected_iterations);
+                assert_eq!(counters.nbodies, 4);
+                assert_eq!(counters.njoint_constraints, 0);
+                // At least one contact constraint with the ground, and between each pair of boxes.
+                assert!(counters.ncontact_constraints >= 4);
+            }
+
+            assert!(all_high
+                .physics_pipeline
+                .counters
+                .solver
+                .islands
+                .iter()
+                .all(|counters| counters.niterations == 8));
+        }
+    }
+
+    #[test]
+    fn soft_ccd_stops_fast_ball_on_thin_floor() {
+        use crate::prelude::*;
+
+        // A ball falling at 20m/s moves by 0.33m per step, more than the thickness of
+        // the floor plus the ball’s diameter.
+        let final_height = |soft_ccd_prediction: Real| {
+            let mut world = PhysicsWorld::new(Vector::y() * -9.81);
+            #[cfg(feature = "dim2")]
+            let floor = ColliderBuilder::cuboid(2.0, 0.025);
+            #[cfg(feature = "dim3")]
+            let floor = ColliderBuilder::cuboid(2.0, 0.025, 2.0);
+            world.insert_collider(floor);
+            let ball = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * 1.0)
+                    .linvel(Vector::y() * -20.0)
+                    .soft_ccd_prediction(soft_ccd_prediction),
+            );
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.1), ball);
+
+            for _ in 0..60 {
+                world.step();
+            }
+
+            world.bodies[ball].translation().y
+        };
+
+        // Without CCD, the ball tunnels through the floor.
+        assert!(final_height(0.0) < -1.0);
+        // With soft-CCD only, it stops on the floor.
+        approx::assert_relative_eq!(final_height(1.0), 0.125, epsilon = 0.01);
+    }
+
+    #[test]
+    fn velocity_before_solve_gives_the_impact_velocity() {
+        let gravity = 9.81;
+        let height = 5.0;
+        let mut world = PhysicsWorld::new(Vector::y() * -gravity);
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world.insert_collider(ground.translation(Vector::y() * -0.5).restitution(0.8));
+        let ball = world
+            .insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * (height + 0.5)));
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5).restitution(0.8), ball);
+
+        let dt = world.integration_parameters.dt;
+        let impact_speed = (2.0 * gravity * height).sqrt();
+
+        let mut prev_linvel: Vector<Real> = Vector::zeros();
+
+        for _ in 0..200 {
+            world.step();
+            let rb = &world.bodies[ball];
+            let before_solve = rb.velocity_before_solve().linvel;
+
+            // The velocity before the solver is the previous velocity integrated with gravity.
+            assert!(
+                (before_solve.y - (prev_linvel.y - gravity * dt)).abs() < 1.0e-4,
+                "{} != {}",
+                before_solve.y,
+                prev_linvel.y - gravity * dt
+            );
+            prev_linvel = *rb.linvel();
+
+            if (rb.linvel() - before_solve).norm() < 1.0e-5 {
+                // Free fall: the solver didn’t change the velocity.
+                assert!(rb.linvel().y < 0.0);
+                continue;
+            }
+
+            // This is the impact step: the velocity before the solver is the free-fall velocity
+            // (up to the one or two steps it takes for the contact to be detected and to stop
+            // the ball), and the ball bounced.
+            assert!(
+                (before_solve.y + impact_speed).abs() < 2.0 * gravity * dt,
+                "{} != {}",
+                before_solve.y,
+                -impact_speed
+            );
+            assert!(rb.linvel().y > impact_speed * 0.5, "{}", rb.linvel());
+            return;
+        }
+
+        panic!("The ball never hit the ground.");
+    }
+
+    #[test]
+    fn limit_restitution_makes_prismatic_limit_bounce() {
+        use crate::dynamics::PrismaticJointBuilder;
+
+        let rebound_velocity = |restitution: Real| {
+            let mut world = PhysicsWorld::new(Vector::zeros());
+            let ground = world.insert_body(RigidBodyBuilder::fixed());
+            let weight = world.insert_body(RigidBodyBuilder::dynamic().linvel(Vector::x() * 2.0));
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.5), weight);
+            let joint = PrismaticJointBuilder::new(Vector::x_axis())
+                .limits([-1.0, 1.0])
+                .limit_restitution(restitution);
+            world.insert_impulse_joint(ground, weight, joint);
+
+            // The weight hits the limit after half a second.
+            for _ in 0..60 {
+                world.step();
+            }
+
+            world.bodies[weight].linvel().x
+        };
+
+        let bounce = rebound_velocity(0.5);
+        assert!((-1.3..=-0.7).contains(&bounce), "{bounce}");
+        let bounce = rebound_velocity(0.0);
+        assert!(bounce.abs() < 0.1, "{bounce}");
+    }
+
+    #[test]
+    fn soft_prismatic_limit_overshoots_and_settles() {
+        use crate::dynamics::PrismaticJointBuilder;
+
+        let fall_on_limit = |softness: Option<Real>| {
+            let mut world = PhysicsWorld::default();
+            let ground = world.insert_body(RigidBodyBuilder::fixed());
+            let weight = world.insert_body(RigidBodyBuilder::dynamic());
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.5), weight);
+            let mut joint = PrismaticJointBuilder::new(Vector::y_axis()).limits([-1.0, 1.0]);
+            if let Some(softness) = softness {
+                joint = joint.limit_softness(softness);
+            }
+            world.insert_impulse_joint(ground, weight, joint);
+
+            let mut lowest = 0.0 as Real;
+            for _ in 0..300 {
+                world.step();
+                lowest = lowest.min(world.bodies[weight].translation().y);
+            }
+
+            let body = &world.bodies[weight];
+            (lowest, body.translation().y, body.linvel().y)
+        };
+
+        let (lowest, _, _) = fall_on_limit(None);
+        assert!(lowest > -1.02, "{lowest}");
+
+        // The softest usable stop, see `JointLimits::softness`.
+        let (lowest, rest, vel) = fall_on_limit(Some(8.0));
+        assert!(lowest < -1.05, "{lowest}");
+        assert!((rest + 1.0).abs() < 0.05, "{rest}");
+        assert!(vel.abs() < 0.2, "{vel}");
+    }
+
+    /// Simulates a ball of the given density driven along `x` by a prismatic motor, and returns
+    /// its successive positions and its mass.
+    fn motorized_slider(
+        density: Real,
+        joint: crate::dynamics::PrismaticJointBuilder,
+    ) -> (Vec<Real>, Real) {
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        let ground = world.insert_body(RigidBodyBuilder::fixed());
+        let slider = world.insert_body(RigidBodyBuilder::dynamic());
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5).density(density), slider);
+        world.insert_impulse_joint(ground, slider, joint);
+
+        let positions = (0..60)
+            .map(|_| {
+                world.step();
+                world.bodies()[slider].translation().x
+            })
+            .collect();
+        (positions, world.bodies()[slider].mass())
+    }
+
+    #[test]
+    fn motor_models_diverge_with_mass() {
+        use crate::dynamics::{MotorModel, PrismaticJointBuilder};
+
+        let joint = |model| {
+            PrismaticJointBuilder::new(Vector::x_axis())
+                .motor_position(1.0, 50.0, 5.0)
+                .motor_model(model)
+        };
+
+        // Acceleration-based motors move light and heavy bodies identically.
+        let (light, _) = motorized_slider(1.0, joint(MotorModel::AccelerationBased));
+        let (heavy, _) = motorized_slider(10.0, joint(MotorModel::AccelerationBased));
+        assert!(light[59] > 0.9, "{}", light[59]);
+        for (light, heavy) in light.iter().zip(heavy.iter()) {
+            approx::assert_relative_eq!(light, heavy, max_relative = 1.0e-3);
+        }
+
+        // Force-based motors move heavy bodies slower.
+        let (light, _) = motorized_slider(1.0, joint(MotorModel::ForceBased));
+        let (heavy, _) = motorized_slider(10.0, joint(MotorModel::ForceBased));
+        assert!(light[10] > 0.1, "{}", light[10]);
+        assert!(heavy[10] < light[10] * 0.5, "{} {}", heavy[10], light[10]);
+    }
+
+    #[test]
+    fn motor_max_force_is_in_newtons() {
+        use crate::dynamics::{MotorModel, PrismaticJointBuilder};
+
+        let dt = crate::dynamics::IntegrationParameters::default().dt;
+        let max_force = 2.0;
+
+        for model in [MotorModel::AccelerationBased, MotorModel::ForceBased] {
+            for density in [1.0, 10.0] {
+                let joint = PrismaticJointBuilder::new(Vector::x_axis())
+                    .motor_velocity(100.0, 1000.0)
+                    .motor_model(model)
+                    .motor_max_force(max_force);
+                let (positions, mass) = motorized_slider(density, joint);
+
+                // The motor saturates, so the body is uniformly accelerated by `max_force`.
+                let accel = max_force / mass;
+                let time = positions.len() as Real * dt;
+                approx::assert_relative_eq!(
+                    positions[positions.len() - 1],
+                    accel * time * time / 2.0,
+                    max_relative = 0.05
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ramped_motor_impulse_is_bounded() {
+        use crate::dynamics::{JointAxis, PrismaticJointBuilder};
+
+        let max_acceleration = 10.0;
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        let ground = world.insert_body(RigidBodyBuilder::fixed());
+        let slider = world.insert_body(RigidBodyBuilder::dynamic());
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5), slider);
+        let joint = PrismaticJointBuilder::new(Vector::x_axis()).motor_velocity(0.0, 1000.0);
+        let joint = world.insert_impulse_joint(ground, slider, joint);
+        world
+            .impulse_joints
+            .get_mut(joint)
+            .unwrap()
+            .data
+            .as_prismatic_mut()
+            .unwrap()
+            .set_motor_target_with_ramp(50.0, max_acceleration);
+
+        let dt = world.integration_parameters.dt;
+        let max_impulse = world.bodies[slider].mass() * max_acceleration * dt;
+        for i in 1..=60 {
+            world.step();
+            let motor = world
+                .impulse_joints
+                .get(joint)
+                .unwrap()
+                .data
+                .motor(JointAxis::X);
+            let motor = motor.unwrap();
+            approx::assert_relative_eq!(
+                motor.effective_target_vel(),
+                max_acceleration * dt * i as Real,
+                max_relative = 1.0e-4
+            );
+            assert!(
+                motor.impulse.abs() <= max_impulse * 1.1,
+                "{} > {max_impulse}",
+                motor.impulse
+            );
+        }
+
+        // Without ramping, the motor stops the body almost instantly, instead of losing at most
+        // `max_acceleration * dt` of velocity per step.
+        world
+            .impulse_joints
+            .get_mut(joint)
+            .unwrap()
+            .data
+            .set_motor_velocity(JointAxis::X, 0.0, 1000.0);
+        world.step();
+        let motor = world
+            .impulse_joints
+            .get(joint)
+            .unwrap()
+            .data
+            .motor(JointAxis::X);
+        assert_eq!(motor.unwrap().effective_target_vel(), 0.0);
+        let linvel = world.bodies[slider].linvel().x;
+        assert!(linvel.abs() < max_acceleration * dt, "{linvel}");
+    }
+
+    #[test]
+    fn ramped_motor_position_moves_at_bounded_speed() {
+        use crate::dynamics::{JointAxis, PrismaticJointBuilder};
+
+        let max_velocity = 0.5;
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        let ground = world.insert_body(RigidBodyBuilder::fixed());
+        let slider = world.insert_body(RigidBodyBuilder::dynamic());
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5), slider);
+        let joint = PrismaticJointBuilder::new(Vector::x_axis()).motor_position(0.0, 100.0, 20.0);
+        let joint = world.insert_impulse_joint(ground, slider, joint);
+        world
+            .impulse_joints
+            .get_mut(joint)
+            .unwrap()
+            .data
+            .set_motor_position_with_ramp(JointAxis::X, 1.0, max_velocity);
+
+        let dt = world.integration_parameters.dt;
+        for i in 1..=240 {
+            world.step();
+            let motor = world
+                .impulse_joints
+                .get(joint)
+                .unwrap()
+                .data
+                .motor(JointAxis::X);
+            let expected = (max_velocity * dt * i as Real).min(1.0);
+            approx::assert_relative_eq!(
+                motor.unwrap().effective_target_pos(),
+                expected,
+                max_relative = 1.0e-4
+            );
+            assert!(world.bodies[slider].translation().x <= expected + 0.05);
+        }
+
+        approx::assert_relative_eq!(world.bodies[slider].translation().x, 1.0, epsilon = 0.01);
+    }
+
+    #[test]
+    fn orientation_lock_keeps_falling_boxes_aligned() {
+        use crate::dynamics::OrientationLockJointBuilder;
+
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let (cuboid, rotation1, rotation2, angvel) =
+            (ColliderBuilder::cuboid(0.5, 0.5), 0.0, 0.4, 2.0);
+        #[cfg(feature = "dim3")]
+        let (cuboid, rotation1, rotation2, angvel) = (
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+            Vector::zeros(),
+            Vector::new(0.4, -0.2, 0.1),
+            Vector::new(1.0, 2.0, -0.5),
+        );
+
+        let body1 = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .rotation(rotation1)
+                .linvel(Vector::x() * -3.0)
+                .angvel(angvel),
+        );
+        let body2 = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 3.0)
+                .rotation(rotation2)
+                .linvel(Vector::x() * 3.0 + Vector::y() * 5.0),
+        );
+        world.insert_collider_with_parent(cuboid.clone(), body1);
+        world.insert_collider_with_parent(cuboid, body2);
+
+        let (pos1, pos2) = (
+            *world.bodies()[body1].position(),
+            *world.bodies()[body2].position(),
+        );
+        let relative_rotation = pos1.rotation.inverse() * pos2.rotation;
+        world.insert_impulse_joint(
+            body1,
+            body2,
+            OrientationLockJointBuilder::from_poses(&pos1, &pos2).erp(0.5),
+        );
+
+        for _ in 0..100 {
+            world.step();
+        }
+
+        let (pos1, pos2) = (
+            world.bodies()[body1].position(),
+            world.bodies()[body2].position(),
+        );
+        // The bodies rotated together, keeping their initial relative orientation.
+        // NOTE: `angle_to` is signed in 2D.
+        assert!(
+            pos1.rotation
+                .angle_to(&crate::math::Rotation::identity())
+                .abs()
+                > 0.5
+        );
+        let error = (pos1.rotation.inverse() * pos2.rotation)
+            .angle_to(&relative_rotation)
+            .abs();
+        assert!(error < 1.0e-3, "{error}");
+
+        // Their relative translation is free.
+        let distance = (pos2.translation.vector - pos1.translation.vector).norm();
+        assert!(distance > 10.0, "{distance}");
+    }
+
+    #[test]
+    fn ball_joint_from_poses_keeps_its_anchor() {
+        #[cfg(feature = "dim2")]
+        use crate::dynamics::RevoluteJointBuilder as BallJointBuilder;
+        #[cfg(feature = "dim3")]
+        use crate::dynamics::SphericalJointBuilder as BallJointBuilder;
+
+        let mut world = PhysicsWorld::default();
+        let anchor = Point::from(Vector::y() * 2.0);
+        let body1 = world.insert_body(RigidBodyBuilder::fixed().translation(Vector::y() * 3.0));
+        #[cfg(feature = "dim2")]
+        let rotation = 0.7;
+        #[cfg(feature = "dim3")]
+        let rotation = Vector::new(0.7, 0.0, 0.2);
+        let body2 = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x())
+                .rotation(rotation),
+        );
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5), body2);
+
+        let pos1 = *world.bodies()[body1].position();
+        let pos2 = *world.bodies()[body2].position();
+        let local_anchor2 = pos2.inverse_transform_point(&anchor);
+        world.insert_impulse_joint(
+            body1,
+            body2,
+            BallJointBuilder::from_poses(&pos1, &pos2, anchor),
+        );
+
+        for _ in 0..100 {
+            world.step();
+        }
+
+        // The second body swings around the anchor, which stays attached to both bodies.
+        let new_pos2 = world.bodies()[body2].position();
+        assert!(new_pos2.rotation.angle_to(&pos2.rotation).abs() > 0.1);
+        let drift = (new_pos2 * local_anchor2 - anchor).norm();
+        assert!(drift < 1.0e-2, "{drift}");
+    }
+
+    #[test]
+    fn constraint_effective_mass_matches_contact_impulse() {
+        use crate::dynamics::{constraint_effective_mass, LockedAxes};
+
+        // A ball hitting the side of a box off-center, so the box also starts rotating.
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        #[cfg(feature = "dim2")]
+        let (cuboid, ball_center, contact_point) = (
+            ColliderBuilder::cuboid(0.5, 0.5),
+            Vector::new(-0.75, 0.4),
+            Point::new(-0.5, 0.4),
+        );
+        #[cfg(feature = "dim3")]
+        let (cuboid, ball_center, contact_point) = (
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+            Vector::new(-0.75, 0.4, 0.0),
+            Point::new(-0.5, 0.4, 0.0),
+        );
+        let target = world.insert_body(RigidBodyBuilder::dynamic());
+        let collider1 = world.insert_collider_with_parent(cuboid.friction(0.0), target);
+        let ball = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(ball_center)
+                .linvel(Vector::x()),
+        );
+        let collider2 =
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.25).friction(0.0), ball);
+
+        let effective_mass = |world: &PhysicsWorld| {
+            constraint_effective_mass(
+                &world.bodies[ball],
+                &contact_point,
+                &world.bodies[target],
+                &contact_point,
+                &Vector::x(),
+            )
+        };
+        let target_inv_mass = world.bodies[target].mass_properties().local_mprops.inv_mass;
+        let ball_inv_mass = world.bodies[ball].mass_properties().local_mprops.inv_mass;
+        // The lever arm of the contact point around the center of the box is `0.4`.
+        let target_inv_inertia = world.bodies[target].effective_world_inv_inertia();
+        #[cfg(feature = "dim2")]
+        let angular_part = target_inv_inertia * 0.16;
+        #[cfg(feature = "dim3")]
+        let angular_part = target_inv_inertia.m33 * 0.16;
+        approx::assert_relative_eq!(
+            effective_mass(&world),
+            1.0 / (target_inv_mass + ball_inv_mass + angular_part),
+            max_relative = 1.0e-5
+        );
+
+        // A dominant body acts as if its mass was infinite.
+        world.bodies[ball].set_dominance_group(1);
+        approx::assert_relative_eq!(
+            effective_mass(&world),
+            1.0 / (target_inv_mass + angular_part),
+            max_relative = 1.0e-5
+        );
+        world.bodies[ball].set_dominance_group(0);
+
+        // The solver stops the relative motion of the contact points along the normal.
+        let expected_impulse = effective_mass(&world);
+        world.step();
+        let pair = world
+            .narrow_phase
+            .contact_pair(collider1, collider2)
+            .unwrap();
+        approx::assert_relative_eq!(
+            pair.total_impulse_magnitude(),
+            expected_impulse,
+            max_relative = 0.05
+        );
+
+        // Locked axes are taken into account, like in the solver.
+        world.bodies[target].set_locked_axes(LockedAxes::TRANSLATION_LOCKED_X, false);
+        world.step();
+        assert_eq!(world.bodies[target].effective_inv_mass().x, 0.0);
+        assert_eq!(world.bodies[target].effective_inv_mass().y, target_inv_mass);
+    }
+
+    #[test]
+    fn split_body_keeps_touching_the_ground() {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let (ground, half) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, half) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        let ground = world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+        // A body made of two halves, resting on the ground.
+        let ship = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        let left =
+            world.insert_collider_with_parent(half.clone().translation(-Vector::x() * 0.5), ship);
+        let right = world.insert_collider_with_parent(half.translation(Vector::x() * 0.5), ship);
+
+        for _ in 0..50 {
+            world.step();
+        }
+
+        let mass = world.bodies()[ship].mass();
+        let right_pos = *world.colliders()[right].position();
+
+        // Split the body in two.
+        let ship_body = &world.bodies()[ship];
+        let new_ship = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .position(*ship_body.position())
+                .linvel(*ship_body.linvel()),
+        );
+        world
+            .colliders
+            .set_parent(right, Some(new_ship), &mut world.bodies);
+        approx::assert_relative_eq!(*world.colliders()[right].position(), right_pos);
+        assert_eq!(world.bodies()[ship].colliders(), &[left]);
+        assert_eq!(world.bodies()[new_ship].colliders(), &[right]);
+
+        for _ in 0..50 {
+            world.step();
+
+            for (body, collider) in [(ship, left), (new_ship, right)] {
+                let pair = world.narrow_phase.contact_pair(ground, collider).unwrap();
+                assert!(pair.has_any_active_contact);
+                let y = world.bodies()[body].translation().y;
+                assert!((y - 0.5).abs() < 0.01, "{y}");
+                approx::assert_relative_eq!(
+                    world.bodies()[body].mass(),
+                    mass / 2.0,
+                    max_relative = 1.0e-5
+                );
+            }
+
+            // The halves now interact with each other.
+            let pair = world.narrow_phase.contact_pair(left, right).unwrap();
+            assert!(pair.has_any_active_contact);
+        }
+
+        // Moving a collider back to the same body as its neighbor removes their contacts.
+        world
+            .colliders
+            .set_parent(right, Some(ship), &mut world.bodies);
+        world.step();
+        let pair = world.narrow_phase.contact_pair(left, right).unwrap();
+        assert!(!pair.has_any_active_contact);
+        approx::assert_relative_eq!(world.bodies()[ship].mass(), mass, max_relative = 1.0e-5);
+    }
+
+    /// Stacks 20 coins with the given contact skin and returns the number of steps until all of
+    /// them fall asleep (if they do within 10 seconds) along with their final heights.
+    fn settle_coin_stack(contact_skin: Real) -> (Option<usize>, Vec<Real>) {
+        let mut world = PhysicsWorld::default();
+        let half_thickness = 0.01;
+        #[cfg(feature = "dim2")]
+        let (ground, coin) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, half_thickness),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, coin) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cylinder(half_thickness, 0.5),
+        );
+        world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+        let coins: Vec<_> = (0..20)
+            .map(|i| {
+                let y = (2 * i + 1) as Real * (half_thickness + contact_skin + 0.001);
+                let body =
+                    world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * y));
+                world.insert_collider_with_parent(coin.clone().contact_skin(contact_skin), body);
+                body
+            })
+            .collect();
+
+        let asleep = (0..600).find(|_| {
+            world.step();
+            coins.iter().all(|coin| world.bodies()[*coin].is_sleeping())
+        });
+        let heights = coins
+            .iter()
+            .map(|coin| world.bodies()[*coin].translation().y)
+            .collect();
+
+        (asleep, heights)
+    }
+
+    #[test]
+    fn contact_skin_stabilizes_coin_stack() {
+        let is_ordered = |heights: &[Real]| heights.windows(2).all(|w| w[0] < w[1]);
+
+        // Without a skin, the bottom of the stack sinks so deep that some coins end up
+        // tunneling through their neighbors.
+        let (_, heights) = settle_coin_stack(0.0);
+        assert!(!is_ordered(&heights));
+
+        // A 1mm skin keeps the coins apart, and the stack falls asleep within a few seconds.
+        let (asleep, heights) = settle_coin_stack(0.001);
+        assert!(is_ordered(&heights));
+        assert!(asleep.is_some_and(|step| step < 180), "{:?}", asleep);
+    }
+
+    /// Spawns a box 90% inside a fixed one, and returns its height and speed after each step.
+    fn depenetration_trajectory(max_corrective_velocity: Real) -> Vec<(Real, Real)> {
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        world
+            .integration_parameters
+            .normalized_max_corrective_velocity = max_corrective_velocity;
+        #[cfg(feature = "dim2")]
+        let cuboid = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let cuboid = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        world.insert_collider(cuboid.clone());
+        let body = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.1));
+        world.insert_collider_with_parent(cuboid, body);
+
+        (0..300)
+            .map(|_| {
+                world.step();
+                let rb = &world.bodies()[body];
+                (rb.translation().y, rb.linvel().norm())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn deep_penetrations_are_recovered_smoothly() {
+        let dt = IntegrationParameters::default().dt;
+
+        for max_corrective_velocity in [10.0, 1.0] {
+            let trajectory = depenetration_trajectory(max_corrective_velocity);
+            let mut prev_height = 0.1;
+
+            for (height, speed) in &trajectory {
+                // The box is pushed out at most at the corrective velocity, and this
+                // correction doesn’t end up in its velocity.
+                assert!(*height - prev_height <= max_corrective_velocity * dt + 1.0e-4);
+                assert!(*speed < 0.5);
+                prev_height = *height;
+            }
+
+            // It takes several steps, but the box ends up separated.
+            assert!(trajectory[2].0 < 0.6);
+            assert!(trajectory.last().unwrap().0 > 0.99);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "dim2")]
+    fn capsule_rests_on_ledges_and_slides_off_smoothly() {
+        // Ledges with their top at y = 0 and their right end at x = 0.
+        let ledges = [
+            ColliderBuilder::cuboid(2.0, 0.5).translation(Vector::new(-2.0, -0.5)),
+            ColliderBuilder::segment(Point::new(-4.0, 0.0), Point::new(0.0, 0.0)),
+            ColliderBuilder::capsule_from_endpoints(
+                Point::new(-4.0, -0.2),
+                Point::new(-0.2, -0.2),
+                0.2,
+            ),
+        ];
+
+        for ledge in ledges {
+            // A lying capsule with its center of mass above the ledge, and its right end past it.
+            let mut world = PhysicsWorld::default();
+            let ledge = world.insert_collider(ledge.friction(0.0));
+            let capsule_body =
+                world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::new(-0.3, 0.2)));
+            let capsule = world.insert_collider_with_parent(
+                ColliderBuilder::capsule_x(0.5, 0.2).friction(0.0),
+                capsule_body,
+            );
+            let contact_features = |world: &PhysicsWorld| {
+                let pair = world.narrow_phase.contact_pair(ledge, capsule).unwrap();
+                pair.manifolds
+                    .iter()
+                    .flat_map(|m| m.points.iter().map(|pt| (pt.fid1, pt.fid2)))
+                    .collect::<Vec<_>>()
+            };
+
+            world.step();
+            let initial_features = contact_features(&world);
+            assert_eq!(initial_features.len(), 2);
+
+            // The capsule settles with the same two contact points, and falls asleep.
+            let asleep = (0..300).find(|_| {
+                world.step();
+                assert_eq!(contact_features(&world), initial_features);
+                world.bodies[capsule_body].is_sleeping()
+            });
+            assert!(asleep.is_some());
+            assert!(world.bodies[capsule_body].rotation().angle().abs() < 1.0e-4);
+
+            // Once pushed off the ledge, the capsule tips over monotonically, without bouncing.
+            world.bodies[capsule_body].set_linvel(Vector::x() * 0.5, true);
+            let mut prev_angvel = 0.0;
+            for _ in 0..300 {
+                world.step();
+                if world.narrow_phase.contact_pair(ledge, capsule).is_none() {
+                    break;
+                }
+
+                let rb = &world.bodies[capsule_body];
+                assert!(rb.linvel().y < 1.0e-3);
+                assert!(rb.angvel() <= prev_angvel + 1.0e-4);
+                prev_angvel = rb.angvel();
+            }
+            assert!(prev_angvel < -1.0);
+        }
+    }
+
+    #[test]
+    fn position_based_kinematic_platforms_carry_bodies() {
+        #[cfg(feature = "dim2")]
+        let (platform_shape, box_shape) = (
+            ColliderBuilder::cuboid(2.0, 0.1),
+            ColliderBuilder::cuboid(0.25, 0.25),
+        );
+        #[cfg(feature = "dim3")]
+        let (platform_shape, box_shape) = (
+            ColliderBuilder::cuboid(2.0, 0.1, 2.0),
+            ColliderBuilder::cuboid(0.25, 0.25, 0.25),
+        );
+
+        let mut world = PhysicsWorld::default();
+        let platform = world.insert_body(RigidBodyBuilder::kinematic_position_based());
+        let _ = world.insert_collider_with_parent(platform_shape, platform);
+        let passenger =
+            world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.35));
+        let _ = world.insert_collider_with_parent(box_shape.friction(1.0), passenger);
+
+        // A platform oscillating horizontally, then going up and down like an elevator.
+        let dt = world.integration_parameters.dt;
+        for i in 1..=1000 {
+            let t = i as Real * dt;
+            // NOTE: the platform starts at rest, and its acceleration remains below the one
+            //       friction can transmit, so the box shouldn’t slide at all.
+            let mut next = Vector::x() * (1.0 - (t * 2.0).cos()) * 1.5;
+            if i > 500 {
+                next.y = (1.0 - ((t - 500.0 * dt) * 1.5).cos()) * 0.5;
+            }
+            world.bodies[platform].set_next_kinematic_translation(next);
+            world.step();
+
+            let offset =
+                world.bodies[passenger].translation() - world.bodies[platform].translation();
+            assert!(
+                offset.x.abs() < 0.01,
+                "step {i}: horizontal drift {}",
+                offset.x
+            );
+            assert!(
+                (offset.y - 0.35).abs() < 0.01,
+                "step {i}: vertical offset {}",
+                offset.y
+            );
+        }
+    }
+
+    #[test]
+    fn far_pile_stepped_at_quarter_rate_stays_stable() {
+        use crate::dynamics::RigidBodyType;
+
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(50.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(50.0, 0.5, 50.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+        // A pyramid far from the player, in step group 1.
+        let mut pile = vec![];
+        for layer in 0..3 {
+            for i in 0..3 - layer {
+                let x = 10.0 + (i as Real + layer as Real * 0.5) * 1.01;
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic()
+                        .translation(Vector::x() * x + Vector::y() * (0.5 + layer as Real))
+                        .step_group(1),
+                );
+                world.insert_collider_with_parent(cuboid.clone(), body);
+                pile.push(body);
+            }
+        }
+
+        // The player’s ball, in the default step group 0, rolling toward the pile.
+        let ball = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .linvel(Vector::x() * 5.0),
+        );
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5), ball);
+
+        let mut max_pile_vel: Real = 0.0;
+        for frame in 0..300 {
+            // The ball reaches the pile around the frame 150 and pushes against it at quarter rate.
+            // Then the pile gets stepped at every frame too, as if the player had come closer.
+            if frame == 200 {
+                assert!(world.bodies[ball].translation().x < 9.1);
+                for body in &pile {
+                    world.bodies[*body].set_step_group(0);
+                }
+            }
+
+            let before: Vec<_> = pile.iter().map(|b| *world.bodies[*b].position()).collect();
+            let step_groups = if frame % 4 == 0 { 0b11 } else { 0b01 };
+            world.step_with_groups(step_groups);
+
+            for (body, pos) in pile.iter().zip(before.iter()) {
+                let rb = &world.bodies[*body];
+                assert_eq!(rb.body_type(), RigidBodyType::Dynamic);
+                if step_groups & (1 << rb.step_group()) == 0 {
+                    // Unstepped bodies don’t move.
+                    assert_eq!(rb.position(), pos);
+                }
+                max_pile_vel = max_pile_vel.max(rb.linvel().norm());
+                assert!(rb.translation().y > 0.4, "{}", rb.translation());
+            }
+        }
+
+        // The ball was stopped by the pile, which didn’t explode.
+        assert!(world.bodies[ball].translation().x < 9.1);
+        assert!(max_pile_vel < 2.0, "{max_pile_vel}");
+        for body in &pile {
+            assert!(world.bodies[*body].translation().y < 3.0);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "dim2")]
+    fn fixed_rotation_character_slides_along_floor() {
+        use crate::dynamics::CoefficientCombineRule;
+
+        let mut world = PhysicsWorld::default();
+        world.insert_collider(ColliderBuilder::cuboid(50.0, 0.5).translation(Vector::y() * -0.5));
+
+        // A platformer character: a capsule that can’t rotate, pushed sideways.
+        let character = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 0.75)
+            .linvel(Vector::x() * 3.0)
+            .lock_rotations();
+        let character = world.insert_body(character);
+        world.insert_collider_with_parent(
+            ColliderBuilder::capsule_y(0.5, 0.25)
+                .friction(0.0)
+                .friction_combine_rule(CoefficientCombineRule::Min),
+            character,
+        );
+
+        // This would tip the capsule over if its rotations weren’t locked.
+        world.bodies[character].apply_torque_impulse(1.0, true);
+
+        for _ in 0..60 {
+            world.step();
+        }
+
+        // It slid for one second without tipping over or bouncing.
+        let rb = &world.bodies[character];
+        assert_eq!(rb.rotation().angle(), 0.0);
+        assert!(
+            (rb.translation().x - 3.0).abs() < 0.05,
+            "{}",
+            rb.translation()
+        );
+        assert!(
+            (rb.translation().y - 0.75).abs() < 0.01,
+            "{}",
+            rb.translation()
+        );
+        assert!((rb.linvel().x - 3.0).abs() < 1.0e-3, "{}", rb.linvel());
+    }
+
+    fn pile_built_in_order(order: &[usize], order_by_user_data: bool) -> PhysicsWorld {
+        let mut world = PhysicsWorld::default();
+        world.integration_parameters.order_by_user_data = order_by_user_data;
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(20.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(20.0, 0.5, 20.0);
+        world.insert_collider(ground.translation(Vector::y() * -0.5).user_data(1000));
+
+        let mut handles = std::collections::HashMap::new();
+        for &i in order {
+            let x = (i % 10) as Real * 1.05 - 5.0;
+            let y = 1.0 + (i / 10) as Real * 1.2;
+            #[cfg(feature = "dim2")]
+            let angle = i as Real * 0.1;
+            #[cfg(feature = "dim3")]
+            let angle = Vector::z() * i as Real * 0.1;
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * x + Vector::y() * y)
+                .rotation(angle)
+                .user_data(i as u128);
+            #[cfg(feature = "dim2")]
+            let collider = if i % 2 == 0 {
+                ColliderBuilder::cuboid(0.5, 0.4)
+            } else {
+                ColliderBuilder::ball(0.45)
+            };
+            #[cfg(feature = "dim3")]
+            let collider = if i % 2 == 0 {
+                ColliderBuilder::cuboid(0.5, 0.4, 0.45)
+            } else {
+                ColliderBuilder::ball(0.45)
+            };
+            let handle = world.insert_body(body);
+            world.insert_collider_with_parent(collider.user_data(i as u128), handle);
+            handles.insert(i, handle);
+        }
+
+        // Chain the bodies of the first row with joints.
+        for &i in order.iter().filter(|i| **i < 4) {
+            let joint = FixedJointBuilder::new()
+                .local_anchor1(Point::from(Vector::x() * 0.525))
+                .local_anchor2(Point::from(Vector::x() * -0.525));
+            world.insert_impulse_joint(handles[&i], handles[&(i + 1)], joint);
+        }
+
+        world
+    }
+
+    fn positions_checksum(world: &PhysicsWorld) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut bodies: Vec<_> = world.bodies().iter().map(|(_, rb)| rb).collect();
+        bodies.sort_by_key(|rb| rb.user_data);
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for rb in bodies {
+            for coord in rb.position().to_homogeneous().iter() {
+                coord.to_bits().hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    #[test]
+    fn simulation_ordered_by_user_data_doesnt_depend_on_insertion_order() {
+        let forward: Vec<_> = (0..50).collect();
+        let reverse: Vec<_> = (0..50).rev().collect();
+        let mut world1 = pile_built_in_order(&forward, true);
+        let mut world2 = pile_built_in_order(&reverse, true);
+
+        for _ in 0..500 {
+            world1.step();
+            world2.step();
+        }
+
+        let num_active_pairs = |world: &PhysicsWorld| {
+            world
+                .narrow_phase
+                .contact_pairs()
+                .filter(|pair| pair.has_any_active_contact)
+                .count()
+        };
+        assert!(num_active_pairs(&world1) > 10);
+        assert_eq!(num_active_pairs(&world1), num_active_pairs(&world2));
+        assert_eq!(positions_checksum(&world1), positions_checksum(&world2));
+    }
+
+    #[test]
+    fn events_carry_the_id_of_their_step() {
+        use crate::dynamics::RigidBodySet;
+        use crate::geometry::{ColliderSet, CollisionEvent, ContactPair};
+        use crate::pipeline::{ActiveEvents, EventHandler};
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct StepRecorder {
+            collisions: Mutex<Vec<(u64, CollisionEvent)>>,
+            forces: Mutex<Vec<u64>>,
+        }
+
+        impl EventHandler for StepRecorder {
+            fn handle_collision_event(
+                &self,
+                step_id: u64,
+                _bodies: &RigidBodySet,
+                _colliders: &ColliderSet,
+                event: CollisionEvent,
+                _contact_pair: Option<&ContactPair>,
+            ) {
+                self.collisions.lock().unwrap().push((step_id, event));
+            }
+
+            fn handle_contact_force_event(
+                &self,
+                step_id: u64,
+                _dt: Real,
+                _bodies: &RigidBodySet,
+                _colliders: &ColliderSet,
+                _contact_pair: &ContactPair,
+                _total_force_magnitude: Real,
+            ) {
+                self.forces.lock().unwrap().push(step_id);
+            }
+        }
+
+        // A ball resting on the ground from the start.
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world.insert_collider(ground.translation(Vector::y() * -0.5));
+        let ball = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        let collider = world.insert_collider_with_parent(
+            ColliderBuilder::ball(0.5)
+                .active_events(ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS)
+                .contact_force_event_threshold(0.0),
+            ball,
+        );
+
+        let recorder = StepRecorder::default();
+        let mut sim_time = 0.0f64;
+        assert_eq!(world.physics_pipeline.step_id(), 0);
+
+        for i in 1..=10 {
+            world.integration_parameters.dt = if i <= 5 { 1.0 / 60.0 } else { 1.0 / 30.0 };
+            sim_time += world.integration_parameters.dt as f64;
+            world.step_with_events(&(), &recorder);
+
+            assert_eq!(world.physics_pipeline.step_id(), i);
+            assert_eq!(world.physics_pipeline.sim_time(), sim_time);
+            assert_eq!(recorder.forces.lock().unwrap().last(), Some(&i));
+        }
+
+        // The contact started during the first step.
+        world.remove_collider(collider);
+        world.step_with_events(&(), &recorder);
+        let collisions = recorder.collisions.lock().unwrap();
+        assert_eq!(collisions.len(), 2);
+        assert!(collisions[0].1.started());
+        assert_eq!(collisions[0].0, 1);
+        assert!(collisions[1].1.stopped());
+        assert_eq!(collisions[1].0, 11);
+        assert_eq!(
+            *recorder.forces.lock().unwrap(),
+            (1..=10).collect::<Vec<_>>()
+        );
+    }
 }
@@ -0,0 +1,1005 @@
+use crate::data::Arena;
+use crate::dynamics::{
+    CCDSolver, GenericJoint, GravityWellSet, ImpulseJointHandle, ImpulseJointSet,
+    IntegrationParameters, IslandManager, MultibodyJointHandle, MultibodyJointSet, RigidBody,
+    RigidBodyHandle, RigidBodySet,
+};
+use crate::geometry::{
+    BroadPhase, BroadPhasePairEvent, Collider, ColliderBroadPhaseData, ColliderChanges,
+    ColliderHandle, ColliderPair, ColliderSet, DefaultBroadPhase, NarrowPhase, Ray,
+    RayIntersection,
+};
+use crate::math::{Real, Vector};
+use crate::pipeline::{
+    BodyBundle, BodyBundleHandle, BodyBundleHandles, EventHandler, PhysicsHooks, PhysicsPipeline,
+    QueryFilter, QueryPipeline,
+};
+use std::collections::HashSet;
+
+/// A physics world owning every component needed to run a simulation.
+///
+/// This is a convenience wrapper around the low-level API: [`Self::step`] calls
+/// [`PhysicsPipeline::step`] with all the components in the right order. Every component is
+/// publicly accessible for advanced use-cases not covered by the methods of this structure.
+///
+/// With the `serde-serialize` feature enabled, the whole world can be serialized at once. Only
+/// the step id and simulation time of the physics pipeline are serialized, the rest of it being
+/// temporary buffers.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct PhysicsWorld {
+    /// The gravity applied to every dynamic rigid-body.
+    pub gravity: Vector<Real>,
+    /// The parameters controlling the simulation.
+    pub integration_parameters: IntegrationParameters,
+    /// The set of active and sleeping islands.
+    pub islands: IslandManager,
+    /// The broad-phase detecting pairs of colliders with overlapping AABBs.
+    pub broad_phase: DefaultBroadPhase,
+    /// The narrow-phase computing contacts and intersections between colliders.
+    pub narrow_phase: NarrowPhase,
+    /// The set of rigid-bodies of this world.
+    pub bodies: RigidBodySet,
+    /// The set of colliders of this world.
+    pub colliders: ColliderSet,
+    /// The set of impulse joints of this world.
+    pub impulse_joints: ImpulseJointSet,
+    /// The set of multibody joints of this world.
+    pub multibody_joints: MultibodyJointSet,
+    /// The solver responsible for continuous collision detection.
+    pub ccd_solver: CCDSolver,
+    /// The query pipeline used for scene queries, updated at each step.
+    pub query_pipeline: QueryPipeline,
+    /// The gravity wells attracting the dynamic rigid-bodies, updated before each step.
+    pub gravity_wells: GravityWellSet,
+    /// The physics pipeline used to step the simulation.
+    pub physics_pipeline: PhysicsPipeline,
+    /// The handles of the bundles inserted with [`Self::insert_bundle`].
+    pub bundles: Arena<BodyBundleHandles>,
+}
+
+impl Default for PhysicsWorld {
+    fn default() -> Self {
+        Self::new(Vector::y() * -9.81)
+    }
+}
+
+impl PhysicsWorld {
+    /// Creates a new empty physics world with the given gravity and default integration parameters.
+    pub fn new(gravity: Vector<Real>) -> Self {
+        Self {
+            gravity,
+            integration_parameters: IntegrationParameters::default(),
+            islands: IslandManager::new(),
+            broad_phase: DefaultBroadPhase::new(),
+            narrow_phase: NarrowPhase::new(),
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            ccd_solver: CCDSolver::new(),
+            query_pipeline: QueryPipeline::new(),
+            gravity_wells: GravityWellSet::new(),
+            physics_pipeline: PhysicsPipeline::new(),
+            bundles: Arena::new(),
+        }
+    }
+
+    /// The set of rigid-bodies of this world.
+    pub fn bodies(&self) -> &RigidBodySet {
+        &self.bodies
+    }
+
+    /// The set of colliders of this world.
+    pub fn colliders(&self) -> &ColliderSet {
+        &self.colliders
+    }
+
+    /// Executes one timestep of the physics simulation.
+    pub fn step(&mut self) {
+        self.step_with_events(&(), &())
+    }
+
+    /// Executes one timestep of the physics simulation with the given physics hooks and event
+    /// handler.
+    pub fn step_with_events(&mut self, hooks: &dyn PhysicsHooks, events: &dyn EventHandler) {
+        self.step_with_groups_and_events(u32::MAX, hooks, events)
+    }
+
+    /// Executes one timestep of the physics simulation, only for the dynamic bodies of the
+    /// given step groups.
+    ///
+    /// See [`PhysicsPipeline::step_with_groups`] for additional information.
+    pub fn step_with_groups(&mut self, step_groups: u32) {
+        self.step_with_groups_and_events(step_groups, &(), &())
+    }
+
+    /// Executes one timestep of the physics simulation, only for the dynamic bodies of the
+    /// given step groups, with the given physics hooks and event handler.
+    ///
+    /// See [`PhysicsPipeline::step_with_groups`] for additional information.
+    pub fn step_with_groups_and_events(
+        &mut self,
+        step_groups: u32,
+        hooks: &dyn PhysicsHooks,
+        events: &dyn EventHandler,
+    ) {
+        self.gravity_wells.update(
+            &self.integration_parameters,
+            &mut self.islands,
+            &mut self.bodies,
+            &self.narrow_phase,
+        );
+        self.physics_pipeline.step_with_groups(
+            step_groups,
+            &self.gravity,
+            &self.integration_parameters,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            Some(&mut self.query_pipeline),
+            hooks,
+            events,
+        );
+    }
+
+    /// Inserts a rigid-body into this world and retrieves its handle.
+    pub fn insert_body(&mut self, body: impl Into<RigidBody>) -> RigidBodyHandle {
+        self.bodies.insert(body)
+    }
+
+    /// Removes a rigid-body, as well as its attached colliders and joints, from this world.
+    pub fn remove_body(&mut self, handle: RigidBodyHandle) -> Option<RigidBody> {
+        self.bodies.remove(
+            handle,
+            &mut self.islands,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            true,
+        )
+    }
+
+    /// Removes several rigid-bodies, as well as their attached colliders and joints, from this
+    /// world.
+    ///
+    /// Returns the removed rigid-bodies with their handles. See [`RigidBodySet::remove_batch`].
+    pub fn remove_bodies(
+        &mut self,
+        handles: &[RigidBodyHandle],
+    ) -> Vec<(RigidBodyHandle, RigidBody)> {
+        self.bodies.remove_batch(
+            handles,
+            &mut self.islands,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            true,
+        )
+    }
+
+    /// Inserts a collider not attached to any rigid-body into this world.
+    pub fn insert_collider(&mut self, collider: impl Into<Collider>) -> ColliderHandle {
+        self.colliders.insert(collider)
+    }
+
+    /// Inserts a collider attached to the given rigid-body into this world.
+    pub fn insert_collider_with_parent(
+        &mut self,
+        collider: impl Into<Collider>,
+        parent: RigidBodyHandle,
+    ) -> ColliderHandle {
+        self.colliders
+            .insert_with_parent(collider, parent, &mut self.bodies)
+    }
+
+    /// Removes a collider from this world, waking up its parent rigid-body.
+    pub fn remove_collider(&mut self, handle: ColliderHandle) -> Option<Collider> {
+        self.colliders
+            .remove(handle, &mut self.islands, &mut self.bodies, true)
+    }
+
+    /// Removes several colliders from this world, waking up their parent rigid-bodies.
+    ///
+    /// This is much faster than removing the colliders one by one when many of them are attached
+    /// to the same rigid-body. Returns the removed colliders with their handles. See
+    /// [`ColliderSet::remove_batch`].
+    pub fn remove_colliders(
+        &mut self,
+        handles: &[ColliderHandle],
+    ) -> Vec<(ColliderHandle, Collider)> {
+        self.colliders
+            .remove_batch(handles, &mut self.islands, &mut self.bodies, true)
+    }
+
+    /// Inserts an impulse joint between two rigid-bodies of this world.
+    pub fn insert_impulse_joint(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        joint: impl Into<GenericJoint>,
+    ) -> ImpulseJointHandle {
+        self.impulse_joints.insert(body1, body2, joint, true)
+    }
+
+    /// Inserts a multibody joint between two rigid-bodies of this world.
+    ///
+    /// Returns `None` if this joint would create a loop in the multibody (see
+    /// [`MultibodyJointSet::insert`]).
+    pub fn insert_multibody_joint(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        joint: impl Into<GenericJoint>,
+    ) -> Option<MultibodyJointHandle> {
+        self.multibody_joints.insert(body1, body2, joint, true)
+    }
+
+    /// Removes an impulse joint from this world, waking up its attached rigid-bodies.
+    pub fn remove_impulse_joint(&mut self, handle: ImpulseJointHandle) -> Option<GenericJoint> {
+        self.impulse_joints
+            .remove(handle, true)
+            .map(|joint| joint.data)
+    }
+
+    /// Removes a multibody joint from this world, waking up its attached rigid-bodies.
+    pub fn remove_multibody_joint(&mut self, handle: MultibodyJointHandle) -> Option<GenericJoint> {
+        self.multibody_joints.remove(handle, true)
+    }
+
+    /// Inserts every rigid-body, collider, and impulse joint of a bundle into this world.
+    ///
+    /// Since this borrows the world mutably, the whole bundle is inserted between two steps: a
+    /// step never sees a partially-inserted bundle. Returns the handle identifying the bundle,
+    /// for [`Self::remove_bundle`], and the handles of its elements.
+    pub fn insert_bundle(&mut self, bundle: BodyBundle) -> (BodyBundleHandle, BodyBundleHandles) {
+        let bodies: Vec<_> = bundle
+            .bodies
+            .into_iter()
+            .map(|body| self.bodies.insert(body))
+            .collect();
+        let colliders = bundle
+            .colliders
+            .into_iter()
+            .map(|(collider, parent)| match parent {
+                Some(parent) => {
+                    self.colliders
+                        .insert_with_parent(collider, bodies[parent], &mut self.bodies)
+                }
+                None => self.colliders.insert(collider),
+            })
+            .collect();
+        let impulse_joints = bundle
+            .impulse_joints
+            .into_iter()
+            .map(|(body1, body2, joint)| {
+                self.impulse_joints
+                    .insert(bodies[body1], bodies[body2], joint, true)
+            })
+            .collect();
+
+        let handles = BodyBundleHandles {
+            bodies,
+            colliders,
+            impulse_joints,
+        };
+        let handle = BodyBundleHandle(self.bundles.insert(handles.clone()));
+        (handle, handles)
+    }
+
+    /// The handles of the elements of a bundle inserted with [`Self::insert_bundle`].
+    pub fn bundle(&self, handle: BodyBundleHandle) -> Option<&BodyBundleHandles> {
+        self.bundles.get(handle.0)
+    }
+
+    /// Removes every rigid-body, collider, and impulse joint of a bundle from this world, waking
+    /// up the rigid-bodies they were interacting with.
+    ///
+    /// Like its insertion, the removal of the whole bundle happens between two steps. Elements of
+    /// the bundle that were already removed individually are ignored. Returns the handles of the
+    /// elements of the bundle, or `None` if `handle` doesn’t identify a bundle of this world.
+    pub fn remove_bundle(&mut self, handle: BodyBundleHandle) -> Option<BodyBundleHandles> {
+        let handles = self.bundles.remove(handle.0)?;
+        // The joints of the bundle are removed along with the rigid-bodies they are attached to.
+        self.remove_colliders(&handles.colliders);
+        self.remove_bodies(&handles.bodies);
+        Some(handles)
+    }
+
+    /// Removes every rigid-body, collider, joint, and gravity well from this world, without
+    /// generating any event.
+    ///
+    /// The gravity and integration parameters are left unchanged, and every component keeps its
+    /// allocated memory so it can be filled again without reallocating, e.g., on level changes.
+    pub fn clear(&mut self) {
+        self.islands.clear();
+        self.broad_phase.clear();
+        self.narrow_phase.clear();
+        self.bodies.clear();
+        self.colliders.clear();
+        self.impulse_joints.clear();
+        self.multibody_joints.clear();
+        self.bundles.clear();
+        self.gravity_wells.clear();
+        self.physics_pipeline.clear_caches();
+        self.query_pipeline.update(&self.bodies, &self.colliders);
+    }
+
+    /// Checks that the broad-phase and the query pipeline are up-to-date with the colliders of
+    /// this world, and rebuilds the ones that aren’t.
+    ///
+    /// Call this after deserializing a world, e.g. from a level file: the serialized broad-phase
+    /// and query pipeline can then be used directly instead of being rebuilt, which is much
+    /// faster for large scenes, while still falling back to a rebuild if they don’t match the
+    /// colliders. For example, with about 200 000 static colliders, validating both structures
+    /// takes about 60ms while rebuilding them takes about 1s. See [`BroadPhaseMultiSap::validate`](crate::geometry::BroadPhaseMultiSap::validate)
+    /// and [`QueryPipeline::validate`].
+    ///
+    /// If the broad-phase is rebuilt, the contact and intersection pairs of the narrow-phase
+    /// are updated accordingly. Returns `true` if both structures were valid.
+    pub fn validate_or_rebuild_acceleration_structures(&mut self) -> bool {
+        let prediction_distance = self.integration_parameters.prediction_distance();
+        let broad_phase_is_valid = self
+            .broad_phase
+            .validate(&self.colliders, prediction_distance);
+
+        if !broad_phase_is_valid {
+            self.rebuild_broad_phase(prediction_distance);
+        }
+
+        let query_pipeline_is_valid = self
+            .query_pipeline
+            .validate_or_rebuild(&self.bodies, &self.colliders);
+        broad_phase_is_valid && query_pipeline_is_valid
+    }
+
+    fn rebuild_broad_phase(&mut self, prediction_distance: Real) {
+        self.broad_phase.clear();
+
+        // Reinsert every enabled collider, forgetting their stale proxy indices.
+        let mut reinserted = vec![];
+        for (handle, co) in self.colliders.colliders.iter_mut() {
+            co.bf_data = ColliderBroadPhaseData::default();
+
+            if co.is_enabled() {
+                reinserted.push((ColliderHandle(handle), co.changes));
+                co.changes |= ColliderChanges::POSITION;
+            }
+        }
+
+        let handles: Vec<_> = reinserted.iter().map(|(handle, _)| *handle).collect();
+        let mut events = vec![];
+        self.broad_phase.update(
+            self.integration_parameters.dt,
+            prediction_distance,
+            &mut self.colliders,
+            &self.bodies,
+            &handles,
+            &[],
+            &mut events,
+        );
+
+        for (handle, changes) in reinserted {
+            self.colliders.index_mut_internal(handle).changes = changes;
+        }
+
+        // The narrow-phase pairs no longer reported by the broad-phase must be removed.
+        let key = NarrowPhase::broad_phase_event_key;
+        let reported: HashSet<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                BroadPhasePairEvent::AddPair(pair) => Some(key(pair)),
+                BroadPhasePairEvent::DeletePair(_) => None,
+            })
+            .collect();
+        let stale: Vec<_> = self
+            .narrow_phase
+            .contact_pairs()
+            .map(|pair| ColliderPair::new(pair.collider1, pair.collider2))
+            .chain(
+                self.narrow_phase
+                    .intersection_pairs()
+                    .map(|(h1, h2, _)| ColliderPair::new(h1, h2)),
+            )
+            .filter(|pair| !reported.contains(&key(pair)))
+            .collect();
+        events.extend(stale.into_iter().map(BroadPhasePairEvent::DeletePair));
+
+        self.narrow_phase.register_pairs(
+            Some(&mut self.islands),
+            &self.colliders,
+            &mut self.bodies,
+            &events,
+            &(),
+            self.physics_pipeline.step_id(),
+        );
+    }
+
+    /// Moves the origin of the world to `offset`, translating every rigid-body, collider, and
+    /// contact point by `-offset` without waking anything up.
+    ///
+    /// See [`PhysicsPipeline::shift_world_origin`] for details.
+    pub fn shift_world_origin(&mut self, offset: Vector<Real>) {
+        self.physics_pipeline.shift_world_origin(
+            offset,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.multibody_joints,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            Some(&mut self.query_pipeline),
+        );
+    }
+
+    /// Forces the island containing the given dynamic rigid-body to sleep, after settling the
+    /// penetrations between its bodies.
+    ///
+    /// See [`IslandManager::force_sleep`] for details.
+    pub fn force_sleep(&mut self, handle: RigidBodyHandle) -> Vec<RigidBodyHandle> {
+        self.islands.force_sleep(
+            &self.integration_parameters,
+            &mut self.bodies,
+            &self.colliders,
+            &self.narrow_phase,
+            &self.impulse_joints,
+            &self.multibody_joints,
+            handle,
+        )
+    }
+
+    /// Updates the query pipeline so that scene queries take into account the changes made since
+    /// the last step.
+    pub fn update_query_pipeline(&mut self) {
+        self.query_pipeline.update(&self.bodies, &self.colliders);
+    }
+
+    /// Finds the closest collider hit by the given ray, and the time-of-impact of the ray on it.
+    ///
+    /// Scene queries rely on the state of the query pipeline at the last step. Call
+    /// [`Self::update_query_pipeline`] first to take into account more recent changes.
+    /// See [`QueryPipeline::cast_ray`] for details.
+    pub fn cast_ray(
+        &self,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, Real)> {
+        self.query_pipeline
+            .cast_ray(&self.bodies, &self.colliders, ray, max_toi, solid, filter)
+    }
+
+    /// Finds the closest collider hit by the given ray, and the corresponding intersection.
+    ///
+    /// See [`Self::cast_ray`] and [`QueryPipeline::cast_ray_and_get_normal`] for details.
+    pub fn cast_ray_and_get_normal(
+        &self,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, RayIntersection)> {
+        self.query_pipeline.cast_ray_and_get_normal(
+            &self.bodies,
+            &self.colliders,
+            ray,
+            max_toi,
+            solid,
+            filter,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PhysicsWorld;
+    use crate::dynamics::{FixedJointBuilder, RigidBodyBuilder};
+    use crate::geometry::{ColliderBuilder, Ray, SharedShape};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::QueryFilter;
+
+    fn ball_on_ground() -> (PhysicsWorld, crate::dynamics::RigidBodyHandle) {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+        world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+        let ball = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 3.0));
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5), ball);
+        (world, ball)
+    }
+
+    #[test]
+    fn ball_falls_on_ground() {
+        let (mut world, ball) = ball_on_ground();
+
+        for _ in 0..200 {
+            world.step();
+        }
+
+        let y = world.bodies()[ball].translation().y;
+        assert!(
+            (y - 0.5).abs() < 0.05,
+            "the ball should rest on the ground: {y}"
+        );
+
+        // The query pipeline was updated by the last step.
+        let ray = Ray::new(Point::from(Vector::y() * 10.0), -Vector::y());
+        let (hit, toi) = world
+            .cast_ray(&ray, Real::MAX, true, QueryFilter::default())
+            .unwrap();
+        assert_eq!(world.colliders()[hit].parent(), Some(ball));
+        approx::assert_relative_eq!(toi, 10.0 - y - 0.5, epsilon = 1.0e-3);
+
+        // Once the ball is removed, the ray hits the ground.
+        world.remove_body(ball);
+        world.update_query_pipeline();
+        let (hit, toi) = world
+            .cast_ray(&ray, Real::MAX, true, QueryFilter::default())
+            .unwrap();
+        assert_eq!(world.colliders()[hit].parent(), None);
+        approx::assert_relative_eq!(toi, 10.0, epsilon = 1.0e-3);
+    }
+
+    #[cfg(feature = "serde-serialize")]
+    #[test]
+    fn serialized_world_resumes_simulation() {
+        let (mut world, ball) = ball_on_ground();
+
+        for _ in 0..20 {
+            world.step();
+        }
+
+        let serialized = bincode::serialize(&world).unwrap();
+        let mut restored: PhysicsWorld = bincode::deserialize(&serialized).unwrap();
+
+        for _ in 0..20 {
+            world.step();
+            restored.step();
+        }
+
+        assert_eq!(
+            world.bodies()[ball].position(),
+            restored.bodies()[ball].position()
+        );
+        // The timeline of the events continues where it stopped.
+        assert_eq!(restored.physics_pipeline.step_id(), 40);
+        assert_eq!(
+            restored.physics_pipeline.sim_time(),
+            world.physics_pipeline.sim_time()
+        );
+    }
+
+    #[test]
+    fn removed_objects_can_be_reinserted() {
+        let (mut world, ball) = ball_on_ground();
+        let ball_collider = world.bodies()[ball].colliders()[0];
+
+        for _ in 0..100 {
+            world.step();
+        }
+        assert_eq!(
+            world.narrow_phase.contact_pairs_with(ball_collider).count(),
+            1
+        );
+
+        // Remove the collider, shrink it, and put it back.
+        let mut collider = world.remove_collider(ball_collider).unwrap();
+        world.step();
+        assert_eq!(world.narrow_phase.contact_pairs().count(), 0);
+
+        collider.set_shape(SharedShape::ball(0.25));
+        let ball_collider = world.insert_collider_with_parent(collider, ball);
+
+        for _ in 0..100 {
+            world.step();
+        }
+
+        let y = world.bodies()[ball].translation().y;
+        assert!((y - 0.25).abs() < 0.05, "{y}");
+        assert_eq!(world.narrow_phase.contact_pairs().count(), 1);
+        assert_eq!(
+            world.narrow_phase.contact_pairs_with(ball_collider).count(),
+            1
+        );
+
+        // Same with the rigid-body.
+        let mut body = world.remove_body(ball).unwrap();
+        body.set_translation(Vector::x() * 5.0 + Vector::y() * 3.0, true);
+        let ball = world.insert_body(body);
+        assert!(world.bodies()[ball].colliders().is_empty());
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5), ball);
+
+        for _ in 0..100 {
+            world.step();
+        }
+
+        let position = world.bodies()[ball].translation();
+        assert!((position.x - 5.0).abs() < 1.0e-3, "{position}");
+        assert!((position.y - 0.5).abs() < 0.05, "{position}");
+        assert_eq!(world.narrow_phase.contact_pairs().count(), 1);
+
+        // Same with a joint.
+        let other = world.insert_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 3.0));
+        let joint = world.insert_impulse_joint(ball, other, FixedJointBuilder::new());
+        let mut joint = world.remove_impulse_joint(joint).unwrap();
+        assert!(world.impulse_joints.is_empty());
+        joint.set_local_anchor2(Point::from(Vector::x()));
+        world.insert_impulse_joint(ball, other, joint);
+        assert_eq!(world.impulse_joints.len(), 1);
+
+        let joint = world
+            .insert_multibody_joint(ball, other, FixedJointBuilder::new())
+            .unwrap();
+        assert!(world.remove_multibody_joint(joint).is_some());
+        assert!(world.remove_multibody_joint(joint).is_none());
+    }
+
+    #[test]
+    fn colliders_and_bodies_can_be_removed_in_batches() {
+        let mut world = PhysicsWorld::default();
+        let chunk = world.insert_body(RigidBodyBuilder::fixed());
+        let tiles: Vec<_> = (0..20)
+            .map(|i| {
+                #[cfg(feature = "dim2")]
+                let tile = ColliderBuilder::cuboid(0.5, 0.5);
+                #[cfg(feature = "dim3")]
+                let tile = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+                let position = Vector::x() * (i as Real - 10.0) - Vector::y() * 0.5;
+                world.insert_collider_with_parent(tile.translation(position), chunk)
+            })
+            .collect();
+        let balls: Vec<_> = (0..4)
+            .map(|i| {
+                let position = Vector::x() * (i as Real * 4.0 - 8.0) + Vector::y() * 0.5;
+                let ball = world.insert_body(RigidBodyBuilder::dynamic().translation(position));
+                world.insert_collider_with_parent(ColliderBuilder::ball(0.5), ball);
+                ball
+            })
+            .collect();
+
+        for _ in 0..50 {
+            world.step();
+        }
+
+        // Remove the left half of the chunk, along with an invalid handle which is ignored.
+        let mut to_remove = tiles[..10].to_vec();
+        to_remove.push(crate::geometry::ColliderHandle::invalid());
+        let removed = world.remove_colliders(&to_remove);
+        assert_eq!(
+            removed
+                .iter()
+                .map(|(handle, _)| *handle)
+                .collect::<Vec<_>>(),
+            tiles[..10]
+        );
+        assert_eq!(world.bodies()[chunk].colliders(), &tiles[10..]);
+        assert!(world.remove_colliders(&tiles[..10]).is_empty());
+
+        for _ in 0..50 {
+            world.step();
+        }
+
+        // The balls above the removed tiles fell, the others are still resting on the chunk.
+        for (i, ball) in balls.iter().enumerate() {
+            let y = world.bodies()[*ball].translation().y;
+            if i < 2 {
+                assert!(y < -1.0, "{y}");
+            } else {
+                assert!((y - 0.5).abs() < 0.05, "{y}");
+            }
+        }
+
+        let removed = world.remove_bodies(&balls[2..]);
+        assert_eq!(
+            removed
+                .iter()
+                .map(|(handle, _)| *handle)
+                .collect::<Vec<_>>(),
+            balls[2..]
+        );
+        assert_eq!(world.bodies().len(), 3);
+        world.step();
+        assert!(world
+            .narrow_phase
+            .contact_pairs()
+            .all(|pair| !pair.has_any_active_contact));
+    }
+
+    #[test]
+    fn cleared_world_behaves_like_a_new_one() {
+        let build = |world: &mut PhysicsWorld| {
+            #[cfg(feature = "dim2")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5);
+            #[cfg(feature = "dim3")]
+            let ground = ColliderBuilder::cuboid(10.0, 0.5, 10.0);
+            world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+            let bodies: Vec<_> = (0..4)
+                .map(|i| {
+                    let body = world.insert_body(
+                        RigidBodyBuilder::dynamic().translation(Vector::y() * (1.0 + i as Real)),
+                    );
+                    world.insert_collider_with_parent(ColliderBuilder::ball(0.4), body);
+                    body
+                })
+                .collect();
+            world.insert_impulse_joint(bodies[0], bodies[1], FixedJointBuilder::new());
+            world.insert_multibody_joint(bodies[2], bodies[3], FixedJointBuilder::new());
+            bodies
+        };
+
+        let mut world = PhysicsWorld::default();
+        build(&mut world);
+        for _ in 0..50 {
+            world.step();
+        }
+
+        world.clear();
+        assert!(world.bodies.is_empty());
+        assert!(world.colliders.is_empty());
+        assert!(world.impulse_joints.is_empty());
+        assert_eq!(world.multibody_joints.multibodies().count(), 0);
+        assert_eq!(world.narrow_phase.contact_pairs().count(), 0);
+        assert!(world.islands.active_dynamic_bodies().is_empty());
+
+        let mut fresh = PhysicsWorld::default();
+        let bodies = build(&mut world);
+        let fresh_bodies = build(&mut fresh);
+
+        for _ in 0..50 {
+            world.step();
+            fresh.step();
+        }
+
+        for (body, fresh_body) in bodies.into_iter().zip(fresh_bodies) {
+            assert_eq!(
+                world.bodies()[body].position(),
+                fresh.bodies()[fresh_body].position()
+            );
+        }
+    }
+
+    /// A stack of cuboids resting on a fixed ground, simulated until all of them fall asleep.
+    fn sleeping_box_stack(len: usize) -> (PhysicsWorld, Vec<crate::dynamics::RigidBodyHandle>) {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        let ground_body =
+            world.insert_body(RigidBodyBuilder::fixed().translation(Vector::y() * -0.5));
+        world.insert_collider_with_parent(ground, ground_body);
+
+        let stack: Vec<_> = (0..len)
+            .map(|i| {
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic().translation(Vector::y() * (0.5 + i as Real)),
+                );
+                world.insert_collider_with_parent(cuboid.clone(), body);
+                body
+            })
+            .collect();
+
+        for _ in 0..500 {
+            world.step();
+        }
+        assert!(stack.iter().all(|body| world.bodies()[*body].is_sleeping()));
+
+        let mut bodies = vec![ground_body];
+        bodies.extend(stack);
+        (world, bodies)
+    }
+
+    /// Wakes up the stack and checks it doesn’t sag or jolt during the next steps, which
+    /// requires its contacts to be warmstarted with the impulses found before sleeping.
+    fn assert_stack_stays_at_rest(
+        world: &mut PhysicsWorld,
+        bodies: &[crate::dynamics::RigidBodyHandle],
+    ) {
+        for body in &bodies[1..] {
+            world.bodies[*body].wake_up(true);
+        }
+
+        for _ in 0..10 {
+            world.step();
+            for body in bodies {
+                let linvel = world.bodies()[*body].linvel().norm();
+                assert!(linvel < 0.02, "{linvel}");
+            }
+        }
+    }
+
+    #[cfg(feature = "serde-serialize")]
+    #[test]
+    fn restored_stack_is_warmstarted() {
+        let (world, bodies) = sleeping_box_stack(20);
+
+        let serialized = bincode::serialize(&world).unwrap();
+        let mut restored: PhysicsWorld = bincode::deserialize(&serialized).unwrap();
+        assert_stack_stays_at_rest(&mut restored, &bodies);
+    }
+
+    #[cfg(feature = "serde-serialize")]
+    #[test]
+    fn restored_acceleration_structures_are_validated() {
+        use crate::geometry::BroadPhaseMultiSap;
+
+        let (mut world, bodies) = sleeping_box_stack(5);
+        let serialized = bincode::serialize(&world).unwrap();
+
+        let mut restored: PhysicsWorld = bincode::deserialize(&serialized).unwrap();
+        assert!(restored.validate_or_rebuild_acceleration_structures());
+
+        // A broad-phase not matching the colliders is rebuilt, without altering the simulation.
+        restored.broad_phase = BroadPhaseMultiSap::new();
+        assert!(!restored.validate_or_rebuild_acceleration_structures());
+        assert!(restored.validate_or_rebuild_acceleration_structures());
+        assert_eq!(
+            restored.narrow_phase.contact_pairs().count(),
+            world.narrow_phase.contact_pairs().count()
+        );
+
+        for body in &bodies[1..] {
+            world.bodies[*body].wake_up(true);
+            restored.bodies[*body].wake_up(true);
+        }
+
+        for _ in 0..10 {
+            world.step();
+            restored.step();
+        }
+
+        for body in &bodies {
+            assert_eq!(
+                world.bodies()[*body].position(),
+                restored.bodies()[*body].position()
+            );
+        }
+    }
+
+    #[test]
+    fn teleported_stack_is_warmstarted() {
+        let (mut world, bodies) = sleeping_box_stack(20);
+
+        for body in &bodies {
+            let body = &mut world.bodies[*body];
+            body.set_translation(body.translation() + Vector::x() * 3.0, false);
+        }
+        assert_stack_stays_at_rest(&mut world, &bodies);
+    }
+
+    #[test]
+    fn bundles_are_never_observed_partially_inserted() {
+        use crate::geometry::CollisionEvent;
+        use crate::pipeline::{ActiveEvents, BodyBundle, ChannelEventCollector};
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::{Arc, Mutex};
+
+        #[cfg(feature = "dim2")]
+        let cuboid = |half_extent: Real| ColliderBuilder::cuboid(half_extent, half_extent);
+        #[cfg(feature = "dim3")]
+        let cuboid =
+            |half_extent: Real| ColliderBuilder::cuboid(half_extent, half_extent, half_extent);
+
+        // Every collider of the ship bundle will be inside of this sensor.
+        let mut world = PhysicsWorld::new(Vector::zeros());
+        world.insert_collider(
+            cuboid(50.0)
+                .sensor(true)
+                .active_events(ActiveEvents::COLLISION_EVENTS),
+        );
+        let world = Arc::new(Mutex::new(world));
+        let stop = Arc::new(AtomicBool::new(false));
+        let num_colliders = 502;
+
+        // Step the world on another thread, reporting the number of started and stopped
+        // intersections of each step.
+        let (counts_send, counts_recv) = std::sync::mpsc::channel();
+        let stepper = {
+            let world = world.clone();
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+                let (contact_force_send, _) = crossbeam::channel::unbounded();
+                let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+                loop {
+                    let stopping = stop.load(Ordering::SeqCst);
+                    let num_world_colliders = {
+                        let mut world = world.lock().unwrap();
+                        world.step_with_events(&(), &events);
+                        world.colliders.len()
+                    };
+                    assert!(
+                        num_world_colliders == 1 || num_world_colliders == 1 + num_colliders,
+                        "{num_world_colliders}"
+                    );
+
+                    let (mut started, mut stopped) = (0, 0);
+                    for event in collision_recv.try_iter() {
+                        match event {
+                            CollisionEvent::Started(..) => started += 1,
+                            CollisionEvent::Stopped(..) => stopped += 1,
+                        }
+                    }
+                    counts_send.send((started, stopped)).unwrap();
+
+                    if stopping {
+                        break;
+                    }
+                }
+            })
+        };
+
+        // A ship hull made of 500 tiles, with two turrets welded on top of it.
+        let mut bundle = BodyBundle::new();
+        let hull = bundle.add_body(RigidBodyBuilder::dynamic());
+        for i in 0..25 {
+            for j in 0..20 {
+                let tile =
+                    cuboid(0.5).translation(Vector::x() * i as Real + Vector::y() * j as Real);
+                bundle.add_collider_with_parent(tile, hull);
+            }
+        }
+        for x in [0.0, 24.0] {
+            let anchor = Vector::x() * x + Vector::y() * 21.0;
+            let turret = bundle.add_body(RigidBodyBuilder::dynamic().translation(anchor));
+            bundle.add_collider_with_parent(ColliderBuilder::ball(0.5), turret);
+            bundle.add_impulse_joint(
+                hull,
+                turret,
+                FixedJointBuilder::new().local_anchor1(Point::from(anchor)),
+            );
+        }
+        assert_eq!(bundle.num_colliders(), num_colliders);
+
+        let (handle, handles) = world.lock().unwrap().insert_bundle(bundle);
+        assert_eq!(handles.bodies.len(), 3);
+        assert_eq!(handles.colliders.len(), num_colliders);
+        assert_eq!(handles.impulse_joints.len(), 2);
+        assert_eq!(world.lock().unwrap().bundle(handle), Some(&handles));
+
+        // Every collider of the bundle starts intersecting the sensor during the same step.
+        loop {
+            let (started, stopped) = counts_recv.recv().unwrap();
+            assert_eq!(stopped, 0);
+            if started != 0 {
+                assert_eq!(started, num_colliders);
+                break;
+            }
+        }
+
+        let removed = world.lock().unwrap().remove_bundle(handle);
+        assert_eq!(removed, Some(handles));
+        stop.store(true, Ordering::SeqCst);
+
+        // And every one of them stops intersecting it during the same step.
+        loop {
+            let (started, stopped) = counts_recv.recv().unwrap();
+            assert_eq!(started, 0);
+            if stopped != 0 {
+                assert_eq!(stopped, num_colliders);
+                break;
+            }
+        }
+
+        stepper.join().unwrap();
+        let mut world = world.lock().unwrap();
+        assert!(world.bodies.is_empty());
+        assert!(world.impulse_joints.is_empty());
+        assert!(world.remove_bundle(handle).is_none());
+    }
+}
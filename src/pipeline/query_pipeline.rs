@@ -1,24 +1,43 @@
-use crate::dynamics::RigidBodyHandle;
+use crate::dynamics::{predicted_swept_aabb, RigidBodyHandle};
 use crate::geometry::{
     Aabb, Collider, ColliderHandle, InteractionGroups, PointProjection, Qbvh, Ray, RayIntersection,
 };
 use crate::math::{Isometry, Point, Real, Vector};
+use crate::math::{SimdBool, SimdReal, SIMD_WIDTH};
 use crate::{dynamics::RigidBodySet, geometry::ColliderSet};
-use parry::partitioning::{QbvhDataGenerator, QbvhUpdateWorkspace};
+use parry::bounding_volume::{BoundingVolume, SimdAabb};
+use parry::partitioning::{
+    QbvhDataGenerator, QbvhUpdateWorkspace, SimdBestFirstVisitStatus, SimdBestFirstVisitor,
+};
 use parry::query::details::{
+    cast_shapes_support_map_support_map, local_ray_intersection_with_support_map_with_params,
     NonlinearTOICompositeShapeShapeBestFirstVisitor, NormalConstraints,
     PointCompositeShapeProjBestFirstVisitor, PointCompositeShapeProjWithFeatureBestFirstVisitor,
-    RayCompositeShapeToiAndNormalBestFirstVisitor, RayCompositeShapeToiBestFirstVisitor,
     ShapeCastOptions, TOICompositeShapeShapeBestFirstVisitor,
 };
+use parry::query::gjk::VoronoiSimplex;
 use parry::query::visitors::{
     BoundingVolumeIntersectionsVisitor, PointIntersectionsVisitor, RayIntersectionsVisitor,
 };
-use parry::query::{DefaultQueryDispatcher, NonlinearRigidMotion, QueryDispatcher, ShapeCastHit};
-use parry::shape::{FeatureId, Shape, TypedSimdCompositeShape};
+use parry::query::{
+    DefaultQueryDispatcher, NonlinearRigidMotion, QueryDispatcher, ShapeCastHit, ShapeCastStatus,
+    SimdRay,
+};
+#[cfg(feature = "dim3")]
+use parry::query::{PointQuery, RayCast};
+#[cfg(feature = "dim3")]
+use parry::shape::TriMesh;
+use parry::shape::{FeatureId, Shape, SupportMap, TypedSimdCompositeShape};
+use simba::simd::{SimdBool as _, SimdPartialOrd, SimdValue};
 use std::sync::Arc;
 
 /// A pipeline for performing queries on all the colliders of a scene.
+///
+/// The query pipeline doesn’t depend on the physics simulation: it can be used as a standalone
+/// collision-detection library by inserting colliders without parent in a [`ColliderSet`], and
+/// passing an empty [`RigidBodySet`] (which doesn’t allocate) to [`Self::update`] and the scene
+/// queries. The rigid-bodies are only read to evaluate the [`QueryFilter`]s based on them, and
+/// by the sweep-test [`QueryPipelineMode`]s.
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
 pub struct QueryPipeline {
@@ -29,6 +48,7 @@ pub struct QueryPipeline {
     query_dispatcher: Arc<dyn QueryDispatcher>,
     qbvh: Qbvh<ColliderHandle>,
     dilation_factor: Real,
+    mode: QueryPipelineMode,
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
     workspace: QbvhUpdateWorkspace,
 }
@@ -54,6 +74,17 @@ bitflags::bitflags! {
         const EXCLUDE_SENSORS = 1 << 4;
         /// Exclude from the query any collider that is not a sensor.
         const EXCLUDE_SOLIDS = 1 << 5;
+        /// Ignore ray hits on the back faces of triangle meshes (3D only).
+        ///
+        /// This only affects ray-casts. A back face is a triangle hit from the side opposite
+        /// to its normal.
+        const EXCLUDE_BACK_FACES = 1 << 6;
+        /// Treat colliders as enlarged by their [`Collider::contact_skin`] in intersection tests.
+        ///
+        /// This only affects [`QueryPipeline::intersections_with_point`],
+        /// [`QueryPipeline::intersections_with_shape`] and [`QueryPipeline::intersection_with_shape`].
+        /// Ray-casts, shape-casts and point projections always ignore the contact skin.
+        const INCLUDE_CONTACT_SKIN = 1 << 7;
         /// Excludes all colliders not attached to a dynamic rigid-body.
         const ONLY_DYNAMIC = Self::EXCLUDE_FIXED.bits | Self::EXCLUDE_KINEMATIC.bits;
         /// Excludes all colliders not attached to a kinematic rigid-body.
@@ -201,6 +232,20 @@ impl<'a> QueryFilter<'a> {
         self
     }
 
+    /// Ignore ray hits on the back faces of triangle meshes (3D only).
+    pub fn exclude_back_faces(mut self) -> Self {
+        self.flags |= QueryFilterFlags::EXCLUDE_BACK_FACES;
+        self
+    }
+
+    /// Treat colliders as enlarged by their contact skin in intersection tests.
+    ///
+    /// See [`QueryFilterFlags::INCLUDE_CONTACT_SKIN`] for the affected queries.
+    pub fn include_contact_skin(mut self) -> Self {
+        self.flags |= QueryFilterFlags::INCLUDE_CONTACT_SKIN;
+        self
+    }
+
     /// Only colliders with collision groups compatible with this one will
     /// be included in the scene query.
     pub fn groups(mut self, groups: InteractionGroups) -> Self {
@@ -229,6 +274,13 @@ impl<'a> QueryFilter<'a> {
 
 /// Indicates how the colliders position should be taken into account when
 /// updating the query pipeline.
+///
+/// With the sweep-test modes, the query pipeline acceleration structure encloses the motion of
+/// each collider attached to a rigid-body. The regular scene queries still test the colliders at
+/// their current position, while [`QueryPipeline::swept_cast_ray`] and
+/// [`QueryPipeline::swept_cast_shape`] test the volumes swept by these motions.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum QueryPipelineMode {
     /// The `Collider::position` is taken into account.
     CurrentPosition,
@@ -243,6 +295,87 @@ pub enum QueryPipelineMode {
     },
 }
 
+impl QueryPipelineMode {
+    /// The position of the collider at the end of its motion, or `None` if it isn’t moving
+    /// according to this mode.
+    fn end_position(&self, bodies: &RigidBodySet, co: &Collider) -> Option<Isometry<Real>> {
+        let co_parent = co.parent?;
+        match *self {
+            QueryPipelineMode::CurrentPosition => None,
+            QueryPipelineMode::SweepTestWithNextPosition => {
+                let rb_next_pos = &bodies[co_parent.handle].pos.next_position;
+                Some(rb_next_pos * co_parent.pos_wrt_parent)
+            }
+            QueryPipelineMode::SweepTestWithPredictedPosition { dt } => {
+                let rb = &bodies[co_parent.handle];
+                let predicted_pos = rb
+                    .pos
+                    .integrate_forces_and_velocities(dt, &rb.forces, &rb.vels, &rb.mprops);
+                Some(predicted_pos * co_parent.pos_wrt_parent)
+            }
+        }
+    }
+
+    // NOTE: the AABBs include the contact skin so that queries with
+    //       `QueryFilterFlags::INCLUDE_CONTACT_SKIN` don’t miss any collider.
+    //       The swept AABBs also enclose the rotational part of the motion so that CCD
+    //       doesn’t miss the impacts of fast-spinning bodies.
+    fn collider_aabb(&self, bodies: &RigidBodySet, co: &Collider) -> Aabb {
+        let Some(co_parent) = co.parent else {
+            return co.compute_collision_aabb(0.0);
+        };
+
+        // NOTE: the rigid-body is only read by the sweep-test modes, so the current-position
+        //       mode works with colliders whose parent isn’t in `bodies`, e.g., when using the
+        //       query pipeline without any rigid-body.
+        let swept_aabb = match *self {
+            QueryPipelineMode::CurrentPosition => return co.compute_collision_aabb(0.0),
+            QueryPipelineMode::SweepTestWithNextPosition => {
+                let rb = &bodies[co_parent.handle];
+                // NOTE: the actual rotation angle isn’t known here. We assume the body
+                //       followed the shortest rotation to its next position.
+                let next_position = rb.pos.next_position * co_parent.pos_wrt_parent;
+                let angle = rb
+                    .pos
+                    .position
+                    .rotation
+                    .angle_to(&rb.pos.next_position.rotation);
+                let com = rb.pos.position * rb.mprops.local_mprops.local_com;
+                co.compute_rotating_swept_aabb(&next_position, &com, angle)
+            }
+            QueryPipelineMode::SweepTestWithPredictedPosition { dt } => {
+                let rb = &bodies[co_parent.handle];
+                predicted_swept_aabb(co, rb, &rb.vels, dt)
+            }
+        };
+
+        swept_aabb.loosened(co.contact_skin())
+    }
+}
+
+/// The convex hull of a support-mapped shape at two positions.
+///
+/// This is the volume swept by the shape if it translates from one position to the other,
+/// and a conservative approximation of it if it rotates too.
+struct SweptSupportMap<'a> {
+    shape: &'a dyn SupportMap,
+    start: &'a Isometry<Real>,
+    end: Isometry<Real>,
+}
+
+impl<'a> SupportMap for SweptSupportMap<'a> {
+    fn local_support_point(&self, dir: &Vector<Real>) -> Point<Real> {
+        let start = self.shape.support_point(self.start, dir);
+        let end = self.shape.support_point(&self.end, dir);
+
+        if start.coords.dot(dir) >= end.coords.dot(dir) {
+            start
+        } else {
+            end
+        }
+    }
+}
+
 impl<'a> TypedSimdCompositeShape for QueryPipelineAsCompositeShape<'a> {
     type PartShape = dyn Shape;
     type PartNormalConstraints = dyn NormalConstraints;
@@ -277,6 +410,163 @@ impl<'a> TypedSimdCompositeShape for QueryPipelineAsCompositeShape<'a> {
     }
 }
 
+/// Casts a ray on the shape of a collider.
+///
+/// Unlike `Shape::cast_ray_and_get_normal`, this takes `solid` into account for oriented
+/// triangle meshes, and can ignore hits on their back faces.
+pub(crate) fn cast_ray_on_shape(
+    shape: &dyn Shape,
+    pos: &Isometry<Real>,
+    ray: &Ray,
+    max_toi: Real,
+    solid: bool,
+    exclude_back_faces: bool,
+) -> Option<RayIntersection> {
+    #[cfg(feature = "dim3")]
+    if let Some(trimesh) = shape.as_trimesh() {
+        let local_ray = ray.inverse_transform_by(pos);
+        return cast_local_ray_on_trimesh(trimesh, &local_ray, max_toi, solid, exclude_back_faces)
+            .map(|hit| hit.transform_by(pos));
+    }
+
+    #[cfg(feature = "dim2")]
+    let _ = exclude_back_faces;
+
+    shape.cast_ray_and_get_normal(pos, ray, max_toi, solid)
+}
+
+#[cfg(feature = "dim3")]
+fn cast_local_ray_on_trimesh(
+    trimesh: &TriMesh,
+    ray: &Ray,
+    max_toi: Real,
+    solid: bool,
+    exclude_back_faces: bool,
+) -> Option<RayIntersection> {
+    // Only meshes with pseudo-normals are oriented, i.e., have a well-defined interior.
+    if solid
+        && trimesh.pseudo_normals().is_some()
+        && trimesh.project_local_point(&ray.origin, false).is_inside
+    {
+        return Some(RayIntersection::new(
+            0.0,
+            Vector::zeros(),
+            FeatureId::Unknown,
+        ));
+    }
+
+    if !exclude_back_faces {
+        return trimesh.cast_local_ray_and_get_normal(ray, max_toi, solid);
+    }
+
+    let mut best: Option<RayIntersection> = None;
+    let mut callback = |tri_id: &u32| {
+        let max_toi = best.map(|hit| hit.time_of_impact).unwrap_or(max_toi);
+        if let Some(mut hit) = trimesh
+            .triangle(*tri_id)
+            .cast_local_ray_and_get_normal(ray, max_toi, solid)
+        {
+            // The triangle ray-cast reports back faces with the feature `Face(1)`.
+            if hit.feature == FeatureId::Face(0) && hit.time_of_impact < max_toi {
+                hit.feature = FeatureId::Face(*tri_id);
+                best = Some(hit);
+            }
+        }
+        true
+    };
+    let mut visitor = RayIntersectionsVisitor::new(ray, max_toi, &mut callback);
+    trimesh.qbvh().traverse_depth_first(&mut visitor);
+    best
+}
+
+/// A visitor for casting a ray on the colliders of a query pipeline.
+struct RayCastBestFirstVisitor<'a> {
+    shape: &'a QueryPipelineAsCompositeShape<'a>,
+    ray: &'a Ray,
+    simd_ray: SimdRay,
+    max_toi: Real,
+    solid: bool,
+}
+
+impl<'a> RayCastBestFirstVisitor<'a> {
+    fn new(
+        shape: &'a QueryPipelineAsCompositeShape<'a>,
+        ray: &'a Ray,
+        max_toi: Real,
+        solid: bool,
+    ) -> Self {
+        Self {
+            shape,
+            ray,
+            simd_ray: SimdRay::splat(*ray),
+            max_toi,
+            solid,
+        }
+    }
+}
+
+impl<'a> SimdBestFirstVisitor<ColliderHandle, SimdAabb> for RayCastBestFirstVisitor<'a> {
+    type Result = (ColliderHandle, RayIntersection);
+
+    #[inline]
+    fn visit(
+        &mut self,
+        best: Real,
+        aabb: &SimdAabb,
+        data: Option<[Option<&ColliderHandle>; SIMD_WIDTH]>,
+    ) -> SimdBestFirstVisitStatus<Self::Result> {
+        let (hit, toi) = aabb.cast_local_ray(&self.simd_ray, SimdReal::splat(self.max_toi));
+
+        if let Some(data) = data {
+            let exclude_back_faces = self
+                .shape
+                .filter
+                .flags
+                .contains(QueryFilterFlags::EXCLUDE_BACK_FACES);
+            let mut weights = [0.0; SIMD_WIDTH];
+            let mut mask = [false; SIMD_WIDTH];
+            let mut results = [None; SIMD_WIDTH];
+
+            let better_toi = toi.simd_lt(SimdReal::splat(best));
+            let bitmask = (hit & better_toi).bitmask();
+
+            for ii in 0..SIMD_WIDTH {
+                if (bitmask & (1 << ii)) != 0 {
+                    if let Some(handle) = data[ii] {
+                        self.shape.map_typed_part_at(*handle, |pos, shape, _| {
+                            let pos = pos.copied().unwrap_or_else(Isometry::identity);
+                            if let Some(res) = cast_ray_on_shape(
+                                shape,
+                                &pos,
+                                self.ray,
+                                self.max_toi,
+                                self.solid,
+                                exclude_back_faces,
+                            ) {
+                                results[ii] = Some((*handle, res));
+                                mask[ii] = true;
+                                weights[ii] = res.time_of_impact;
+                            }
+                        });
+                    }
+                }
+            }
+
+            SimdBestFirstVisitStatus::MaybeContinue {
+                weights: SimdReal::from(weights),
+                mask: SimdBool::from(mask),
+                results,
+            }
+        } else {
+            SimdBestFirstVisitStatus::MaybeContinue {
+                weights: toi,
+                mask: hit,
+                results: [None; SIMD_WIDTH],
+            }
+        }
+    }
+}
+
 impl Default for QueryPipeline {
     fn default() -> Self {
         Self::new()
@@ -315,6 +605,7 @@ impl QueryPipeline {
             query_dispatcher: Arc::new(d),
             qbvh: Qbvh::new(),
             dilation_factor: 0.01,
+            mode: QueryPipelineMode::CurrentPosition,
             workspace: QbvhUpdateWorkspace::default(),
         }
     }
@@ -324,6 +615,13 @@ impl QueryPipeline {
         &*self.query_dispatcher
     }
 
+    /// The mode used by the last update of this query pipeline.
+    ///
+    /// Incremental updates always use [`QueryPipelineMode::CurrentPosition`].
+    pub fn mode(&self) -> QueryPipelineMode {
+        self.mode
+    }
+
     /// Update the query pipeline incrementally, avoiding a complete rebuild of its
     /// internal data-structure.
     pub fn update_incremental(
@@ -333,6 +631,8 @@ impl QueryPipeline {
         removed_colliders: &[ColliderHandle],
         refit_and_rebalance: bool,
     ) {
+        self.mode = QueryPipelineMode::CurrentPosition;
+
         // We remove first. This is needed to avoid the ABA problem: if a collider was removed
         // and another added right after with the same handle index, we can remove first, and
         // then update the new one (but only if its actually exists, to address the case where
@@ -350,7 +650,7 @@ impl QueryPipeline {
 
         if refit_and_rebalance {
             let _ = self.qbvh.refit(0.0, &mut self.workspace, |handle| {
-                colliders[*handle].compute_aabb()
+                colliders[*handle].compute_collision_aabb(0.0)
             });
             self.qbvh.rebalance(0.0, &mut self.workspace);
         }
@@ -381,38 +681,8 @@ impl QueryPipeline {
 
             #[inline(always)]
             fn for_each(&mut self, mut f: impl FnMut(ColliderHandle, Aabb)) {
-                match self.mode {
-                    QueryPipelineMode::CurrentPosition => {
-                        for (h, co) in self.colliders.iter_enabled() {
-                            f(h, co.shape.compute_aabb(&co.pos))
-                        }
-                    }
-                    QueryPipelineMode::SweepTestWithNextPosition => {
-                        for (h, co) in self.colliders.iter_enabled() {
-                            if let Some(co_parent) = co.parent {
-                                let rb_next_pos = &self.bodies[co_parent.handle].pos.next_position;
-                                let next_position = rb_next_pos * co_parent.pos_wrt_parent;
-                                f(h, co.shape.compute_swept_aabb(&co.pos, &next_position))
-                            } else {
-                                f(h, co.shape.compute_aabb(&co.pos))
-                            }
-                        }
-                    }
-                    QueryPipelineMode::SweepTestWithPredictedPosition { dt } => {
-                        for (h, co) in self.colliders.iter_enabled() {
-                            if let Some(co_parent) = co.parent {
-                                let rb = &self.bodies[co_parent.handle];
-                                let predicted_pos = rb.pos.integrate_forces_and_velocities(
-                                    dt, &rb.forces, &rb.vels, &rb.mprops,
-                                );
-
-                                let next_position = predicted_pos * co_parent.pos_wrt_parent;
-                                f(h, co.shape.compute_swept_aabb(&co.pos, &next_position))
-                            } else {
-                                f(h, co.shape.compute_aabb(&co.pos))
-                            }
-                        }
-                    }
+                for (h, co) in self.colliders.iter_enabled() {
+                    f(h, self.mode.collider_aabb(self.bodies, co))
                 }
             }
         }
@@ -423,9 +693,61 @@ impl QueryPipeline {
             mode,
         };
         self.qbvh.clear_and_rebuild(generator, self.dilation_factor);
+        self.mode = mode;
+    }
+
+    /// Checks that this query pipeline is up-to-date with the given colliders.
+    ///
+    /// This is meant to check a query pipeline deserialized together with its collider set
+    /// (e.g. from a level file) before using it, instead of rebuilding it with [`Self::update`].
+    /// It checks that every enabled collider, and only them, has a leaf in the acceleration
+    /// structure, and that the bounding volume of each leaf contains the collider’s AABB
+    /// for the [`Self::mode`] of the last update.
+    ///
+    /// This is linear in the number of colliders, but much faster than a rebuild.
+    pub fn validate(&self, bodies: &RigidBodySet, colliders: &ColliderSet) -> bool {
+        let mut num_leaves = 0;
+
+        for proxy in self.qbvh.raw_proxies() {
+            // This proxy isn’t attached to any leaf.
+            if proxy.node.index == u32::MAX {
+                continue;
+            }
+
+            let Some(co) = colliders.get(proxy.data).filter(|co| co.is_enabled()) else {
+                return false;
+            };
+
+            if self.qbvh.leaf_data(proxy.node) != Some(proxy.data) {
+                return false;
+            }
+
+            let leaf_aabb = self.qbvh.node_aabb(proxy.node);
+            if leaf_aabb.map(|aabb| aabb.contains(&self.mode.collider_aabb(bodies, co)))
+                != Some(true)
+            {
+                return false;
+            }
+
+            num_leaves += 1;
+        }
+
+        num_leaves == colliders.iter_enabled().count()
+    }
+
+    /// Rebuilds this query pipeline if [`Self::validate`] fails.
+    ///
+    /// The rebuild uses the same [`QueryPipelineMode`] as the last update. Returns `true` if the
+    /// query pipeline was valid, and `false` if it had to be rebuilt.
+    pub fn validate_or_rebuild(&mut self, bodies: &RigidBodySet, colliders: &ColliderSet) -> bool {
+        if self.validate(bodies, colliders) {
+            true
+        } else {
+            self.update_with_mode(bodies, colliders, self.mode);
+            false
+        }
     }
 
-    /// Find the closest intersection between a ray and a set of collider.
     ///
     /// # Parameters
     /// * `colliders` - The set of colliders taking part in this pipeline.
@@ -433,8 +755,9 @@ impl QueryPipeline {
     /// * `max_toi`: the maximum time-of-impact that can be reported by this cast. This effectively
     ///   limits the length of the ray to `ray.dir.norm() * max_toi`. Use `Real::MAX` for an unbounded ray.
     /// * `solid`: if this is `true` an impact at time 0.0 (i.e. at the ray origin) is returned if
-    ///            it starts inside of a shape. If this `false` then the ray will hit the shape's boundary
-    ///            even if its starts inside of it.
+    ///   it starts inside of a shape. If this `false` then the ray will hit the shape's boundary
+    ///   even if its starts inside of it. Heightfields and triangle meshes without the
+    ///   `TriMeshFlags::ORIENTED` flag have no interior so they are always hit on their boundary.
     /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
     pub fn cast_ray(
         &self,
@@ -445,11 +768,8 @@ impl QueryPipeline {
         solid: bool,
         filter: QueryFilter,
     ) -> Option<(ColliderHandle, Real)> {
-        let pipeline_shape = self.as_composite_shape(bodies, colliders, filter);
-        let mut visitor =
-            RayCompositeShapeToiBestFirstVisitor::new(&pipeline_shape, ray, max_toi, solid);
-
-        self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
+        self.cast_ray_and_get_normal(bodies, colliders, ray, max_toi, solid, filter)
+            .map(|(handle, hit)| (handle, hit.time_of_impact))
     }
 
     /// Find the closest intersection between a ray and a set of collider.
@@ -460,8 +780,9 @@ impl QueryPipeline {
     /// * `max_toi`: the maximum time-of-impact that can be reported by this cast. This effectively
     ///   limits the length of the ray to `ray.dir.norm() * max_toi`. Use `Real::MAX` for an unbounded ray.
     /// * `solid`: if this is `true` an impact at time 0.0 (i.e. at the ray origin) is returned if
-    ///            it starts inside of a shape. If this `false` then the ray will hit the shape's boundary
-    ///            even if its starts inside of it.
+    ///   it starts inside of a shape. If this `false` then the ray will hit the shape's boundary
+    ///   even if its starts inside of it. Heightfields and triangle meshes without the
+    ///   `TriMeshFlags::ORIENTED` flag have no interior so they are always hit on their boundary.
     /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
     pub fn cast_ray_and_get_normal(
         &self,
@@ -473,12 +794,7 @@ impl QueryPipeline {
         filter: QueryFilter,
     ) -> Option<(ColliderHandle, RayIntersection)> {
         let pipeline_shape = self.as_composite_shape(bodies, colliders, filter);
-        let mut visitor = RayCompositeShapeToiAndNormalBestFirstVisitor::new(
-            &pipeline_shape,
-            ray,
-            max_toi,
-            solid,
-        );
+        let mut visitor = RayCastBestFirstVisitor::new(&pipeline_shape, ray, max_toi, solid);
 
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
@@ -491,8 +807,9 @@ impl QueryPipeline {
     /// * `max_toi`: the maximum time-of-impact that can be reported by this cast. This effectively
     ///   limits the length of the ray to `ray.dir.norm() * max_toi`. Use `Real::MAX` for an unbounded ray.
     /// * `solid`: if this is `true` an impact at time 0.0 (i.e. at the ray origin) is returned if
-    ///            it starts inside of a shape. If this `false` then the ray will hit the shape's boundary
-    ///            even if its starts inside of it.
+    ///   it starts inside of a shape. If this `false` then the ray will hit the shape's boundary
+    ///   even if its starts inside of it. Heightfields and triangle meshes without the
+    ///   `TriMeshFlags::ORIENTED` flag have no interior so they are always hit on their boundary.
     /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
     /// * `callback`: function executed on each collider for which a ray intersection has been found.
     ///               There is no guarantees on the order the results will be yielded. If this callback returns `false`,
@@ -510,10 +827,16 @@ impl QueryPipeline {
         let mut leaf_callback = &mut |handle: &ColliderHandle| {
             if let Some(co) = colliders.get(*handle) {
                 if filter.test(bodies, *handle, co) {
-                    if let Some(hit) = co
-                        .shape
-                        .cast_ray_and_get_normal(&co.pos, ray, max_toi, solid)
-                    {
+                    let exclude_back_faces =
+                        filter.flags.contains(QueryFilterFlags::EXCLUDE_BACK_FACES);
+                    if let Some(hit) = cast_ray_on_shape(
+                        &*co.shape,
+                        &co.pos,
+                        ray,
+                        max_toi,
+                        solid,
+                        exclude_back_faces,
+                    ) {
                         return callback(*handle, hit);
                     }
                 }
@@ -528,6 +851,13 @@ impl QueryPipeline {
 
     /// Gets the handle of up to one collider intersecting the given shape.
     ///
+    /// This runs exact shape-versus-shape intersection tests and stops at the first intersecting
+    /// collider, which makes it suitable for checking if an object can be spawned at a given pose.
+    /// Use [`Self::intersections_with_shape`] to find all the intersecting colliders.
+    ///
+    /// If the filter has the [`QueryFilterFlags::INCLUDE_CONTACT_SKIN`] flag, a collider also
+    /// intersects the shape if it is closer than its contact skin.
+    ///
     /// # Parameters
     /// * `colliders` - The set of colliders taking part in this pipeline.
     /// * `shape_pos` - The position of the shape used for the intersection test.
@@ -541,6 +871,18 @@ impl QueryPipeline {
         shape: &dyn Shape,
         filter: QueryFilter,
     ) -> Option<ColliderHandle> {
+        if filter
+            .flags
+            .contains(QueryFilterFlags::INCLUDE_CONTACT_SKIN)
+        {
+            let mut result = None;
+            self.intersections_with_shape(bodies, colliders, shape_pos, shape, filter, |handle| {
+                result = Some(handle);
+                false
+            });
+            return result;
+        }
+
         let pipeline_shape = self.as_composite_shape(bodies, colliders, filter);
         #[allow(deprecated)]
         // TODO: replace this with IntersectionCompositeShapeShapeVisitor when it
@@ -588,6 +930,9 @@ impl QueryPipeline {
 
     /// Find all the colliders containing the given point.
     ///
+    /// If the filter has the [`QueryFilterFlags::INCLUDE_CONTACT_SKIN`] flag, a collider also
+    /// contains the point if it is closer than its contact skin.
+    ///
     /// # Parameters
     /// * `colliders` - The set of colliders taking part in this pipeline.
     /// * `point` - The point used for the containment test.
@@ -602,9 +947,20 @@ impl QueryPipeline {
         filter: QueryFilter,
         mut callback: impl FnMut(ColliderHandle) -> bool,
     ) {
+        let include_skin = filter
+            .flags
+            .contains(QueryFilterFlags::INCLUDE_CONTACT_SKIN);
+
         let mut leaf_callback = &mut |handle: &ColliderHandle| {
             if let Some(co) = colliders.get(*handle) {
-                if filter.test(bodies, *handle, co) && co.shape.contains_point(&co.pos, point) {
+                let skin = if include_skin { co.contact_skin() } else { 0.0 };
+                let contains_point = if skin > 0.0 {
+                    co.shape.distance_to_point(&co.pos, point, true) <= skin
+                } else {
+                    co.shape.contains_point(&co.pos, point)
+                };
+
+                if filter.test(bodies, *handle, co) && contains_point {
                     return callback(*handle);
                 }
             }
@@ -694,6 +1050,101 @@ impl QueryPipeline {
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
 
+    /// Casts a shape at a constant linear velocity and passes every collider it hits to a callback.
+    ///
+    /// Unlike [`Self::cast_shape`], this doesn’t stop at the first hit: each collider touched by
+    /// the shape along its whole path is reported with its own time-of-impact. See
+    /// [`Self::cast_shape`] for details on the other parameters and the results.
+    ///
+    /// # Parameters
+    /// * `include_initial_overlaps` - If `false`, the colliders already intersecting the shape at
+    ///   its starting position (i.e. hit at time 0.0) are ignored.
+    /// * `callback` - A function called with each collider hit by the shape, and its
+    ///   time-of-impact. There is no guarantees on the order the results will be yielded. If this
+    ///   callback returns `false`, this method will exit early, ignoring any further hit.
+    pub fn cast_shape_all(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        shape_pos: &Isometry<Real>,
+        shape_vel: &Vector<Real>,
+        shape: &dyn Shape,
+        options: ShapeCastOptions,
+        include_initial_overlaps: bool,
+        filter: QueryFilter,
+        mut callback: impl FnMut(ColliderHandle, ShapeCastHit) -> bool,
+    ) {
+        let end_pos = Isometry::from_parts(
+            (shape_pos.translation.vector + shape_vel * options.max_time_of_impact).into(),
+            shape_pos.rotation,
+        );
+        let aabb = shape.compute_swept_aabb(shape_pos, &end_pos);
+
+        let mut leaf_callback = |handle: &ColliderHandle| {
+            if let Some(co) = colliders.get(*handle) {
+                if filter.test(bodies, *handle, co) {
+                    let hit = self
+                        .query_dispatcher
+                        .cast_shapes(
+                            &co.pos.inv_mul(shape_pos),
+                            &co.pos.inverse_transform_vector(shape_vel),
+                            &*co.shape,
+                            shape,
+                            options,
+                        )
+                        .ok()
+                        .flatten();
+
+                    if let Some(hit) = hit {
+                        if include_initial_overlaps
+                            || hit.status != ShapeCastStatus::PenetratingOrWithinTargetDist
+                        {
+                            return callback(*handle, hit.transform1_by(&co.pos));
+                        }
+                    }
+                }
+            }
+
+            true
+        };
+
+        self.colliders_with_aabb_intersecting_aabb(&aabb, &mut leaf_callback);
+    }
+
+    /// Casts a shape at a constant linear velocity and retrieve all the colliders it hits, sorted
+    /// by increasing time-of-impact.
+    ///
+    /// See [`Self::cast_shape_all`] for details on the parameters.
+    pub fn cast_shape_all_sorted(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        shape_pos: &Isometry<Real>,
+        shape_vel: &Vector<Real>,
+        shape: &dyn Shape,
+        options: ShapeCastOptions,
+        include_initial_overlaps: bool,
+        filter: QueryFilter,
+    ) -> Vec<(ColliderHandle, ShapeCastHit)> {
+        let mut hits = vec![];
+        self.cast_shape_all(
+            bodies,
+            colliders,
+            shape_pos,
+            shape_vel,
+            shape,
+            options,
+            include_initial_overlaps,
+            filter,
+            |handle, hit| {
+                hits.push((handle, hit));
+                true
+            },
+        );
+        hits.sort_by(|a, b| a.1.time_of_impact.total_cmp(&b.1.time_of_impact));
+        hits
+    }
+
     /// Casts a shape with an arbitrary continuous motion and retrieve the first collider it hits.
     ///
     /// In the resulting `TOI`, witness and normal 1 refer to the world collider, and are in world
@@ -739,8 +1190,150 @@ impl QueryPipeline {
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
 
+    /// Find the closest intersection between a ray and the volumes swept by the colliders.
+    ///
+    /// The swept volumes are given by the [`QueryPipelineMode`] used by the last call to
+    /// [`Self::update_with_mode`]: each support-mapped collider attached to a rigid-body is
+    /// replaced by the convex hull of its current position and of its position at the end of the
+    /// motion. This lets fast-moving objects be hit where they will be during the next
+    /// simulation step. Other colliders are tested at their current position, as with
+    /// [`Self::cast_ray_and_get_normal`] which documents the other parameters.
+    pub fn swept_cast_ray(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, RayIntersection)> {
+        let exclude_back_faces = filter.flags.contains(QueryFilterFlags::EXCLUDE_BACK_FACES);
+        let mut best: Option<(ColliderHandle, RayIntersection)> = None;
+
+        let mut leaf_callback = &mut |handle: &ColliderHandle| {
+            if let Some(co) = colliders.get(*handle) {
+                if filter.test(bodies, *handle, co) {
+                    let max_toi = best.map(|(_, hit)| hit.time_of_impact).unwrap_or(max_toi);
+                    let swept_shape = co
+                        .shape
+                        .as_support_map()
+                        .zip(self.mode.end_position(bodies, co));
+                    let hit = if let Some((shape, end)) = swept_shape {
+                        let swept = SweptSupportMap {
+                            shape,
+                            start: &co.pos,
+                            end,
+                        };
+                        local_ray_intersection_with_support_map_with_params(
+                            &swept,
+                            &mut VoronoiSimplex::new(),
+                            ray,
+                            max_toi,
+                            solid,
+                        )
+                    } else {
+                        cast_ray_on_shape(
+                            &*co.shape,
+                            &co.pos,
+                            ray,
+                            max_toi,
+                            solid,
+                            exclude_back_faces,
+                        )
+                    };
+
+                    // The hit can’t be further than the best one thanks to `max_toi`.
+                    if let Some(hit) = hit {
+                        best = Some((*handle, hit));
+                    }
+                }
+            }
+
+            true
+        };
+
+        let mut visitor = RayIntersectionsVisitor::new(ray, max_toi, &mut leaf_callback);
+        self.qbvh.traverse_depth_first(&mut visitor);
+        best
+    }
+
+    /// Casts a shape at a constant linear velocity against the volumes swept by the colliders.
+    ///
+    /// The swept volumes are computed as in [`Self::swept_cast_ray`]. If `shape` isn’t
+    /// support-mapped, every collider is tested at its current position. See
+    /// [`Self::cast_shape`] for details on the other parameters and the result.
+    pub fn swept_cast_shape(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        shape_pos: &Isometry<Real>,
+        shape_vel: &Vector<Real>,
+        shape: &dyn Shape,
+        options: ShapeCastOptions,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, ShapeCastHit)> {
+        let end_pos = Isometry::from_parts(
+            (shape_pos.translation.vector + shape_vel * options.max_time_of_impact).into(),
+            shape_pos.rotation,
+        );
+        let aabb = shape.compute_swept_aabb(shape_pos, &end_pos);
+        let mut best: Option<(ColliderHandle, ShapeCastHit)> = None;
+
+        let mut leaf_callback = |handle: &ColliderHandle| {
+            if let Some(co) = colliders.get(*handle) {
+                if filter.test(bodies, *handle, co) {
+                    let mut options = options;
+                    if let Some((_, best)) = &best {
+                        options.max_time_of_impact = best.time_of_impact;
+                    }
+
+                    let swept_shape = co
+                        .shape
+                        .as_support_map()
+                        .zip(shape.as_support_map())
+                        .zip(self.mode.end_position(bodies, co));
+                    let hit = if let Some(((co_shape, shape), end)) = swept_shape {
+                        let swept = SweptSupportMap {
+                            shape: co_shape,
+                            start: &co.pos,
+                            end,
+                        };
+                        cast_shapes_support_map_support_map(
+                            shape_pos, shape_vel, &swept, shape, options,
+                        )
+                    } else {
+                        self.query_dispatcher
+                            .cast_shapes(
+                                &co.pos.inv_mul(shape_pos),
+                                &co.pos.inverse_transform_vector(shape_vel),
+                                &*co.shape,
+                                shape,
+                                options,
+                            )
+                            .ok()
+                            .flatten()
+                            .map(|hit| hit.transform1_by(&co.pos))
+                    };
+
+                    // The hit can’t be further than the best one thanks to `options.max_time_of_impact`.
+                    if let Some(hit) = hit {
+                        best = Some((*handle, hit));
+                    }
+                }
+            }
+
+            true
+        };
+
+        self.colliders_with_aabb_intersecting_aabb(&aabb, &mut leaf_callback);
+        best
+    }
+
     /// Retrieve all the colliders intersecting the given shape.
     ///
+    /// If the filter has the [`QueryFilterFlags::INCLUDE_CONTACT_SKIN`] flag, a collider also
+    /// intersects the shape if it is closer than its contact skin.
+    ///
     /// # Parameters
     /// * `colliders` - The set of colliders taking part in this pipeline.
     /// * `shapePos` - The position of the shape to test.
@@ -759,13 +1352,24 @@ impl QueryPipeline {
     ) {
         let dispatcher = &*self.query_dispatcher;
         let inv_shape_pos = shape_pos.inverse();
+        let include_skin = filter
+            .flags
+            .contains(QueryFilterFlags::INCLUDE_CONTACT_SKIN);
 
         let mut leaf_callback = &mut |handle: &ColliderHandle| {
             if let Some(co) = colliders.get(*handle) {
                 if filter.test(bodies, *handle, co) {
                     let pos12 = inv_shape_pos * co.pos.as_ref();
-
-                    if dispatcher.intersection_test(&pos12, shape, &*co.shape) == Ok(true) {
+                    let skin = if include_skin { co.contact_skin() } else { 0.0 };
+                    let intersects = if skin > 0.0 {
+                        dispatcher
+                            .distance(&pos12, shape, &*co.shape)
+                            .is_ok_and(|dist| dist <= skin)
+                    } else {
+                        dispatcher.intersection_test(&pos12, shape, &*co.shape) == Ok(true)
+                    };
+
+                    if intersects {
                         return callback(*handle);
                     }
                 }
@@ -780,3 +1384,478 @@ impl QueryPipeline {
         self.qbvh.traverse_depth_first(&mut visitor);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{QueryFilter, QueryPipeline, QueryPipelineMode};
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{Ball, ColliderBuilder, ColliderHandle, ColliderSet, Ray};
+    use crate::math::{Isometry, Point, Real, Vector};
+    use crate::pipeline::PhysicsWorld;
+    use parry::query::ShapeCastOptions;
+
+    #[test]
+    fn queries_work_without_rigid_bodies() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+
+        // A grid of 100x100 static balls, one unit apart along `x` and `y`.
+        let grid: Vec<_> = (0..100)
+            .flat_map(|i| (0..100).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let center = Vector::x() * i as Real + Vector::y() * j as Real;
+                colliders.insert(ColliderBuilder::ball(0.25).translation(center))
+            })
+            .collect();
+
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+        assert!(query_pipeline.validate(&bodies, &colliders));
+
+        // Rays going along each row of the grid hit its first ball.
+        for j in 0..100 {
+            let ray = Ray::new(
+                Point::from(Vector::y() * j as Real - Vector::x()),
+                Vector::x(),
+            );
+            let hit = query_pipeline.cast_ray(
+                &bodies,
+                &colliders,
+                &ray,
+                Real::MAX,
+                true,
+                QueryFilter::default(),
+            );
+            let (handle, toi) = hit.unwrap();
+            assert_eq!(handle, grid[j]);
+            approx::assert_relative_eq!(toi, 0.75, epsilon = 1.0e-5);
+        }
+
+        let point = Point::from(Vector::x() * 50.0 + Vector::y() * 50.4);
+        let (handle, projection) = query_pipeline
+            .project_point(&bodies, &colliders, &point, true, QueryFilter::default())
+            .unwrap();
+        assert_eq!(handle, grid[50 * 100 + 50]);
+        approx::assert_relative_eq!((projection.point - point).norm(), 0.15, epsilon = 1.0e-5);
+
+        // Incremental updates don’t need rigid-bodies either.
+        colliders[grid[0]].set_translation(Vector::x() * -10.0);
+        query_pipeline.update_incremental(&colliders, &[grid[0]], &[], true);
+        let ray = Ray::new(Point::from(Vector::x() * -20.0), Vector::x());
+        let hit = query_pipeline.cast_ray(
+            &bodies,
+            &colliders,
+            &ray,
+            Real::MAX,
+            true,
+            QueryFilter::default(),
+        );
+        assert_eq!(hit.map(|hit| hit.0), Some(grid[0]));
+    }
+
+    #[test]
+    fn swept_queries_hit_fast_moving_targets() {
+        let dt = 1.0 / 60.0;
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+
+        // A target crossing the bullet trajectory during the next step, and a wall behind it.
+        let target = bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 5.0 - Vector::y() * 2.0)
+                .linvel(Vector::y() * 120.0),
+        );
+        let target_collider =
+            colliders.insert_with_parent(ColliderBuilder::ball(0.25), target, &mut bodies);
+        #[cfg(feature = "dim2")]
+        let wall = ColliderBuilder::cuboid(0.5, 10.0);
+        #[cfg(feature = "dim3")]
+        let wall = ColliderBuilder::cuboid(0.5, 10.0, 10.0);
+        let wall = colliders.insert(wall.translation(Vector::x() * 10.0));
+
+        let ray = Ray::new(Point::origin(), Vector::x());
+        let bullet = Ball::new(0.1);
+        let options = ShapeCastOptions::with_max_time_of_impact(100.0);
+        let cast = |query_pipeline: &QueryPipeline, swept: bool| {
+            let filter = QueryFilter::default();
+            let ray_hit = if swept {
+                query_pipeline.swept_cast_ray(&bodies, &colliders, &ray, 100.0, true, filter)
+            } else {
+                query_pipeline
+                    .cast_ray_and_get_normal(&bodies, &colliders, &ray, 100.0, true, filter)
+            };
+            let shape_hit = if swept {
+                query_pipeline.swept_cast_shape(
+                    &bodies,
+                    &colliders,
+                    &Isometry::identity(),
+                    &Vector::x(),
+                    &bullet,
+                    options,
+                    filter,
+                )
+            } else {
+                query_pipeline.cast_shape(
+                    &bodies,
+                    &colliders,
+                    &Isometry::identity(),
+                    &Vector::x(),
+                    &bullet,
+                    options,
+                    filter,
+                )
+            };
+            (
+                ray_hit.map(|(handle, hit)| (handle, hit.time_of_impact)),
+                shape_hit.map(|(handle, hit)| (handle, hit.time_of_impact)),
+            )
+        };
+
+        type Hit = Option<(ColliderHandle, Real)>;
+        let assert_hits =
+            |(ray_hit, shape_hit): (Hit, Hit),
+             (handle, ray_toi, shape_toi): (ColliderHandle, Real, Real)| {
+                let (ray_handle, toi) = ray_hit.unwrap();
+                assert_eq!(ray_handle, handle);
+                approx::assert_relative_eq!(toi, ray_toi, epsilon = 1.0e-3);
+                let (shape_handle, toi) = shape_hit.unwrap();
+                assert_eq!(shape_handle, handle);
+                approx::assert_relative_eq!(toi, shape_toi, epsilon = 1.0e-3);
+            };
+
+        // The current positions miss the target and hit the wall.
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+        assert_hits(cast(&query_pipeline, false), (wall, 9.5, 9.4));
+        assert_hits(cast(&query_pipeline, true), (wall, 9.5, 9.4));
+
+        // Only the swept queries hit the target where it will be during the next step.
+        query_pipeline.update_with_mode(
+            &bodies,
+            &colliders,
+            QueryPipelineMode::SweepTestWithPredictedPosition { dt },
+        );
+        assert_eq!(
+            query_pipeline.mode(),
+            QueryPipelineMode::SweepTestWithPredictedPosition { dt }
+        );
+        assert_hits(cast(&query_pipeline, false), (wall, 9.5, 9.4));
+        assert_hits(cast(&query_pipeline, true), (target_collider, 4.75, 4.65));
+    }
+
+    #[test]
+    fn spawn_check_with_shape() {
+        use crate::geometry::Cuboid;
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        #[cfg(feature = "dim2")]
+        let (wall, sensor) = (
+            ColliderBuilder::cuboid(0.5, 10.0),
+            ColliderBuilder::cuboid(1.0, 1.0),
+        );
+        #[cfg(feature = "dim3")]
+        let (wall, sensor) = (
+            ColliderBuilder::cuboid(0.5, 10.0, 10.0),
+            ColliderBuilder::cuboid(1.0, 1.0, 1.0),
+        );
+        let wall = colliders.insert(wall.translation(Vector::x() * 10.0));
+        let sensor = colliders.insert(sensor.sensor(true).translation(Vector::y() * 5.0));
+
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+
+        let crate_ = Cuboid::new(Vector::repeat(0.4));
+        let check = |pos: Vector<Real>, filter| {
+            query_pipeline.intersection_with_shape(
+                &bodies,
+                &colliders,
+                &Isometry::from(pos),
+                &crate_,
+                filter,
+            )
+        };
+
+        // Inside of the wall, or partially overlapping it.
+        let in_wall = Vector::x() * 10.0;
+        assert_eq!(check(in_wall, QueryFilter::default()), Some(wall));
+        assert_eq!(check(Vector::x() * 9.2, QueryFilter::default()), Some(wall));
+        assert_eq!(
+            check(in_wall, QueryFilter::default().exclude_collider(wall)),
+            None
+        );
+
+        // In free space.
+        assert_eq!(check(Vector::x() * 8.0, QueryFilter::default()), None);
+
+        // Inside of the sensor.
+        let in_sensor = Vector::y() * 5.0;
+        assert_eq!(check(in_sensor, QueryFilter::default()), Some(sensor));
+        assert_eq!(
+            check(in_sensor, QueryFilter::default().exclude_sensors()),
+            None
+        );
+    }
+
+    #[cfg(feature = "serde-serialize")]
+    #[test]
+    fn deserialized_pipeline_matches_rebuilt_pipeline() {
+        use crate::geometry::Aabb;
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut handles = vec![];
+
+        for i in 0..20 {
+            for j in 0..20 {
+                let translation = Vector::x() * i as Real + Vector::y() * j as Real;
+                handles.push(colliders.insert(ColliderBuilder::ball(0.4).translation(translation)));
+            }
+        }
+
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+
+        // Casts one ray along each row of balls, and finds the balls around some points.
+        let queries = |query_pipeline: &QueryPipeline, colliders: &ColliderSet| {
+            let mut results = vec![];
+
+            for i in 0..20 {
+                let origin = Point::from(Vector::y() * (i as Real + 0.1) - Vector::x());
+                let ray = Ray::new(origin, Vector::x());
+                let hit = query_pipeline.cast_ray(
+                    &bodies,
+                    colliders,
+                    &ray,
+                    100.0,
+                    true,
+                    QueryFilter::default(),
+                );
+                results.push(hit.map(|(handle, toi)| (vec![handle], toi)));
+
+                let center = Point::from(Vector::repeat(i as Real * 0.7));
+                let aabb = Aabb::from_half_extents(center, Vector::repeat(0.5));
+                let mut found = vec![];
+                query_pipeline.colliders_with_aabb_intersecting_aabb(&aabb, |handle| {
+                    found.push(*handle);
+                    true
+                });
+                found.sort_by_key(|handle| handle.into_raw_parts());
+                results.push(Some((found, 0.0)));
+            }
+
+            results
+        };
+
+        let serialized = bincode::serialize(&(&query_pipeline, &colliders)).unwrap();
+        let (mut restored, restored_colliders): (QueryPipeline, ColliderSet) =
+            bincode::deserialize(&serialized).unwrap();
+        assert!(restored.validate(&bodies, &restored_colliders));
+        assert_eq!(
+            queries(&restored, &restored_colliders),
+            queries(&query_pipeline, &colliders)
+        );
+
+        // Colliders modified or removed since the pipeline was serialized are detected.
+        for modify in [true, false] {
+            let (mut restored, mut restored_colliders) =
+                (restored.clone(), restored_colliders.clone());
+
+            if modify {
+                restored_colliders[handles[25]].set_translation(Vector::repeat(3.5));
+            } else {
+                let _ = restored_colliders.remove(
+                    handles[25],
+                    &mut crate::dynamics::IslandManager::new(),
+                    &mut RigidBodySet::new(),
+                    false,
+                );
+            }
+
+            assert!(!restored.validate_or_rebuild(&bodies, &restored_colliders));
+            assert!(restored.validate(&bodies, &restored_colliders));
+
+            let mut rebuilt = QueryPipeline::new();
+            rebuilt.update(&bodies, &restored_colliders);
+            assert_eq!(
+                queries(&restored, &restored_colliders),
+                queries(&rebuilt, &restored_colliders)
+            );
+        }
+
+        // A valid pipeline is left untouched.
+        assert!(restored.validate_or_rebuild(&bodies, &restored_colliders));
+    }
+
+    #[test]
+    fn intersection_queries_can_include_contact_skin() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let handle = colliders.insert(ColliderBuilder::ball(1.0).contact_skin(0.1));
+        let mut pipeline = QueryPipeline::new();
+        pipeline.update(&bodies, &colliders);
+
+        let point = Point::from(Vector::x() * 1.05);
+        let shape_pos = Isometry::from(Vector::y() * 1.15);
+        let shape = Ball::new(0.1);
+
+        for (filter, expected) in [
+            (QueryFilter::default(), None),
+            (QueryFilter::default().include_contact_skin(), Some(handle)),
+        ] {
+            let mut hit = None;
+            pipeline.intersections_with_point(&bodies, &colliders, &point, filter, |h| {
+                hit = Some(h);
+                false
+            });
+            assert_eq!(hit, expected);
+
+            let mut hit = None;
+            pipeline.intersections_with_shape(
+                &bodies,
+                &colliders,
+                &shape_pos,
+                &shape,
+                filter,
+                |h| {
+                    hit = Some(h);
+                    false
+                },
+            );
+            assert_eq!(hit, expected);
+
+            let hit =
+                pipeline.intersection_with_shape(&bodies, &colliders, &shape_pos, &shape, filter);
+            assert_eq!(hit, expected);
+        }
+    }
+
+    #[test]
+    fn cast_shape_all_returns_every_hit() {
+        use crate::geometry::Cuboid;
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        #[cfg(feature = "dim2")]
+        let target = ColliderBuilder::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let target = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+        let targets: Vec<_> = [9.0, 3.0, 6.0]
+            .into_iter()
+            .map(|x| colliders.insert(target.clone().translation(Vector::x() * x)))
+            .collect();
+        // A collider overlapping the shape at its starting position.
+        let overlapping = colliders.insert(target.clone());
+
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+
+        let blade = Cuboid::new(Vector::repeat(0.25));
+        let options = ShapeCastOptions::with_max_time_of_impact(20.0);
+        let cast = |include_initial_overlaps| {
+            query_pipeline.cast_shape_all_sorted(
+                &bodies,
+                &colliders,
+                &Isometry::identity(),
+                &Vector::x(),
+                &blade,
+                options,
+                include_initial_overlaps,
+                QueryFilter::default(),
+            )
+        };
+
+        let hits = cast(false);
+        let handles: Vec<_> = hits.iter().map(|(handle, _)| *handle).collect();
+        assert_eq!(handles, [targets[1], targets[2], targets[0]]);
+        for ((_, hit), toi) in hits.iter().zip([2.25, 5.25, 8.25]) {
+            approx::assert_relative_eq!(hit.time_of_impact, toi, epsilon = 1.0e-3);
+        }
+
+        let hits = cast(true);
+        assert_eq!(hits.len(), 4);
+        assert_eq!(hits[0].0, overlapping);
+        assert_eq!(hits[0].1.time_of_impact, 0.0);
+
+        // The callback can stop the query early.
+        let mut num_hits = 0;
+        query_pipeline.cast_shape_all(
+            &bodies,
+            &colliders,
+            &Isometry::identity(),
+            &Vector::x(),
+            &blade,
+            options,
+            false,
+            QueryFilter::default(),
+            |_, _| {
+                num_hits += 1;
+                false
+            },
+        );
+        assert_eq!(num_hits, 1);
+    }
+
+    #[test]
+    fn ray_starting_inside_a_cuboid() {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let cuboid = ColliderBuilder::cuboid(1.0, 2.0);
+        #[cfg(feature = "dim3")]
+        let cuboid = ColliderBuilder::cuboid(1.0, 2.0, 3.0);
+        world.insert_collider(cuboid);
+        world.update_query_pipeline();
+
+        let ray = Ray::new(Point::origin(), Vector::x());
+        let filter = QueryFilter::default();
+
+        // A solid ray reports an impact at its origin.
+        let (_, hit) = world
+            .cast_ray_and_get_normal(&ray, Real::MAX, true, filter)
+            .unwrap();
+        assert_eq!(hit.time_of_impact, 0.0);
+
+        // A non-solid ray hits the boundary it exits through.
+        let (_, hit) = world
+            .cast_ray_and_get_normal(&ray, Real::MAX, false, filter)
+            .unwrap();
+        approx::assert_relative_eq!(hit.time_of_impact, 1.0, epsilon = 1.0e-5);
+        // The normal is oriented toward the inside, against the ray direction.
+        approx::assert_relative_eq!(hit.normal, -Vector::x(), epsilon = 1.0e-5);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn ray_starting_inside_a_trimesh() {
+        use parry::shape::{Cuboid, TriMeshFlags};
+
+        let mut world = PhysicsWorld::default();
+        let (vertices, indices) = Cuboid::new(Vector::new(1.0, 2.0, 3.0)).to_trimesh();
+        world.insert_collider(ColliderBuilder::trimesh_with_flags(
+            vertices,
+            indices,
+            TriMeshFlags::ORIENTED,
+        ));
+        world.update_query_pipeline();
+
+        let inside = Ray::new(Point::origin(), Vector::x());
+        let outside = Ray::new(Point::new(-5.0, 0.0, 0.0), Vector::x());
+        let filter = QueryFilter::default();
+        let front_only = QueryFilter::default().exclude_back_faces();
+
+        // The mesh is oriented so it behaves like a solid cuboid.
+        let (_, toi) = world.cast_ray(&inside, Real::MAX, true, filter).unwrap();
+        assert_eq!(toi, 0.0);
+        let (_, toi) = world.cast_ray(&inside, Real::MAX, false, filter).unwrap();
+        approx::assert_relative_eq!(toi, 1.0, epsilon = 1.0e-5);
+
+        // From the inside, the only triangle hit is a back face.
+        assert!(world
+            .cast_ray(&inside, Real::MAX, false, front_only)
+            .is_none());
+        let (_, toi) = world
+            .cast_ray(&outside, Real::MAX, false, front_only)
+            .unwrap();
+        approx::assert_relative_eq!(toi, 4.0, epsilon = 1.0e-5);
+    }
+}
@@ -26,6 +26,7 @@ pub(crate) fn handle_user_changes_to_colliders(
 
             if co.changes.intersects(
                 ColliderChanges::SHAPE
+                    | ColliderChanges::SHAPE_DEFORMED
                     | ColliderChanges::LOCAL_MASS_PROPERTIES
                     | ColliderChanges::ENABLED_OR_DISABLED
                     | ColliderChanges::PARENT,
@@ -35,6 +36,16 @@ pub(crate) fn handle_user_changes_to_colliders(
                     .and_then(|p| bodies.get_mut_internal_with_modification_tracking(p.handle))
                 {
                     rb.changes |= RigidBodyChanges::LOCAL_MASS_PROPERTIES;
+
+                    // The parent has to react to the new mass distribution. Colliders that
+                    // were just attached don’t wake up their parent, e.g., when building a
+                    // sleeping rigid-body.
+                    if co.changes.contains(ColliderChanges::LOCAL_MASS_PROPERTIES)
+                        && !co.changes.contains(ColliderChanges::PARENT)
+                        && rb.is_dynamic()
+                    {
+                        rb.wake_up(true);
+                    }
                 }
             }
         }
@@ -207,6 +218,15 @@ pub(crate) fn handle_user_changes_to_rigid_bodies(
                     &rb.colliders,
                     &rb.pos.position,
                 );
+
+                if rb.is_dynamic()
+                    && !rb.colliders.0.is_empty()
+                    && rb.mprops.local_mprops.mass() == 0.0
+                {
+                    log::warn!(
+                        "The dynamic rigid-body {handle} has a zero mass: it will behave as if its mass was infinite."
+                    );
+                }
             }
 
             rb.ids = ids;
@@ -0,0 +1,140 @@
+use crate::dynamics::{ImpulseJointHandle, MultibodyJointHandle, RigidBodyHandle};
+use crate::geometry::ColliderHandle;
+use crate::math::Real;
+use std::fmt;
+
+/// Error returned when a handle doesn’t identify any element of its set.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InvalidHandle {
+    /// The rigid-body handle doesn’t identify any rigid-body of the rigid-body set.
+    RigidBody(RigidBodyHandle),
+    /// The collider handle doesn’t identify any collider of the collider set.
+    Collider(ColliderHandle),
+}
+
+impl fmt::Display for InvalidHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RigidBody(handle) => write!(f, "invalid rigid-body handle {handle}"),
+            Self::Collider(handle) => write!(f, "invalid collider handle {handle}"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidHandle {}
+
+/// Error returned when setting a negative or non-finite collider density.
+///
+/// See [`Collider::set_density`](crate::geometry::Collider::set_density).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InvalidDensity(pub Real);
+
+impl fmt::Display for InvalidDensity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid density {}: it must be finite and non-negative",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidDensity {}
+
+/// An inconsistency between the rigid-body, collider, and joint sets given to the physics pipeline.
+///
+/// See [`PhysicsPipeline::validate`](crate::pipeline::PhysicsPipeline::validate).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// A collider is attached to a rigid-body that doesn’t exist.
+    ColliderWithRemovedParent {
+        /// The collider attached to a removed rigid-body.
+        collider: ColliderHandle,
+        /// The removed rigid-body.
+        parent: RigidBodyHandle,
+    },
+    /// An impulse joint is attached to a rigid-body that doesn’t exist.
+    ImpulseJointWithRemovedBody {
+        /// The impulse joint attached to a removed rigid-body.
+        joint: ImpulseJointHandle,
+        /// The removed rigid-body.
+        body: RigidBodyHandle,
+    },
+    /// A multibody joint is attached to a rigid-body that doesn’t exist.
+    MultibodyJointWithRemovedBody {
+        /// The multibody joint attached to a removed rigid-body.
+        joint: MultibodyJointHandle,
+        /// The removed rigid-body.
+        body: RigidBodyHandle,
+    },
+    /// A half-space collider is attached to a dynamic rigid-body.
+    ///
+    /// Half-spaces are infinite so they have no valid mass properties. They must be attached to
+    /// fixed or kinematic rigid-bodies, or to no rigid-body at all.
+    HalfSpaceOnDynamicBody {
+        /// The half-space collider.
+        collider: ColliderHandle,
+        /// The dynamic rigid-body it is attached to.
+        body: RigidBodyHandle,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ColliderWithRemovedParent { collider, parent } => write!(
+                f,
+                "the collider {collider} is attached to the removed rigid-body {parent}"
+            ),
+            Self::ImpulseJointWithRemovedBody { joint, body } => write!(
+                f,
+                "the impulse joint {joint} is attached to the removed rigid-body {body}"
+            ),
+            Self::MultibodyJointWithRemovedBody { joint, body } => write!(
+                f,
+                "the multibody joint {joint} is attached to the removed rigid-body {body}"
+            ),
+            Self::HalfSpaceOnDynamicBody { collider, body } => write!(
+                f,
+                "the half-space collider {collider} is attached to the dynamic rigid-body {body}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// A non-finite value detected in the simulation state.
+///
+/// See [`PhysicsPipeline::health_check`](crate::pipeline::PhysicsPipeline::health_check).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HealthIssue {
+    /// The position of this rigid-body contains a NaN or infinite value.
+    NonFiniteBodyPosition(RigidBodyHandle),
+    /// The linear or angular velocity of this rigid-body contains a NaN or infinite value.
+    NonFiniteBodyVelocity(RigidBodyHandle),
+    /// The AABB of this collider contains a NaN or infinite value.
+    NonFiniteColliderAabb(ColliderHandle),
+    /// A contact point between these two colliders contains a NaN or infinite value.
+    NonFiniteContactPoint(ColliderHandle, ColliderHandle),
+}
+
+impl fmt::Display for HealthIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonFiniteBodyPosition(handle) => {
+                write!(f, "the rigid-body {handle} has a non-finite position")
+            }
+            Self::NonFiniteBodyVelocity(handle) => {
+                write!(f, "the rigid-body {handle} has a non-finite velocity")
+            }
+            Self::NonFiniteColliderAabb(handle) => {
+                write!(f, "the collider {handle} has a non-finite AABB")
+            }
+            Self::NonFiniteContactPoint(handle1, handle2) => write!(
+                f,
+                "the contact between the colliders {handle1} and {handle2} has a non-finite point"
+            ),
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Deterministic pseudo-random disturbances for stress-testing the stability of a simulation.
+//!
+//! A [`Disturber`] applies small reproducible perturbations to a scene, at the timesteps given
+//! by its schedule. Combined with the checksum reported by [`Disturber::run_steps`], this turns
+//! regressions in the stability of stacks into checksum differences:
+//!
+//! ```ignore
+//! use rapier3d::testbench::{self, chaos::{Disturbance, Disturber}};
+//!
+//! let mut world = testbench::pyramid(10);
+//! let mut disturber = Disturber::new(42)
+//!     .every(10, Disturbance::Nudge { max_velocity_change: 0.05 })
+//!     .at_step(100, Disturbance::Projectile { radius: 0.2, speed: 20.0, distance: 10.0 });
+//! let report = disturber.run_steps(&mut world, 500);
+//! println!("checksum: {:x}", report.position_checksum);
+//! ```
+//!
+//! With the `serde-serialize` feature enabled, a disturber can be serialized together with the
+//! world it is applied to, so that a restored snapshot is disturbed exactly like the original.
+
+use super::{next_random, run_steps_with, TestbenchReport};
+use crate::dynamics::{RigidBodyBuilder, RigidBodyHandle};
+use crate::geometry::ColliderBuilder;
+use crate::math::{Real, Vector};
+use crate::pipeline::PhysicsWorld;
+
+/// A disturbance applied by a [`Disturber`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum Disturbance {
+    /// Applies an impulse changing the linear velocity of a randomly chosen dynamic rigid-body
+    /// by a random vector with a magnitude of at most `max_velocity_change`.
+    Nudge {
+        /// The maximum magnitude of the change of linear velocity.
+        max_velocity_change: Real,
+    },
+    /// Throws a ball at a randomly chosen dynamic rigid-body, from a random direction above it.
+    ///
+    /// The ball is removed from the world once it exceeds the lifetime given by
+    /// [`Disturber::projectile_lifetime`].
+    Projectile {
+        /// The radius of the ball.
+        radius: Real,
+        /// The initial speed of the ball.
+        speed: Real,
+        /// The initial distance between the ball and its target.
+        distance: Real,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+struct ScheduledDisturbance {
+    disturbance: Disturbance,
+    next_step: usize,
+    // Zero if the disturbance is only applied once.
+    interval: usize,
+}
+
+/// Applies reproducible pseudo-random disturbances to a physics world, following a schedule.
+///
+/// Every random choice is made from the seed given to [`Disturber::new`], so two runs of the
+/// same scene with the same seed and schedule lead to the same simulation.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Disturber {
+    rng_state: u64,
+    schedule: Vec<ScheduledDisturbance>,
+    num_steps: usize,
+    projectile_lifetime: usize,
+    // The projectiles currently in the world, with the timestep at which they are removed.
+    projectiles: Vec<(RigidBodyHandle, usize)>,
+}
+
+impl Disturber {
+    /// Creates a disturber with an empty schedule, using the given seed for its random choices.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng_state: seed,
+            schedule: vec![],
+            num_steps: 0,
+            projectile_lifetime: 120,
+            projectiles: vec![],
+        }
+    }
+
+    /// Schedules `disturbance` to be applied once, right before the timestep number `step`.
+    ///
+    /// Timesteps are numbered from zero, starting at the first call to [`Self::step`].
+    pub fn at_step(mut self, step: usize, disturbance: Disturbance) -> Self {
+        self.schedule.push(ScheduledDisturbance {
+            disturbance,
+            next_step: step,
+            interval: 0,
+        });
+        self
+    }
+
+    /// Schedules `disturbance` to be applied every `interval` timesteps, starting with the
+    /// timestep number `interval`.
+    pub fn every(mut self, interval: usize, disturbance: Disturbance) -> Self {
+        assert!(interval > 0, "the interval must be greater than zero");
+        self.schedule.push(ScheduledDisturbance {
+            disturbance,
+            next_step: interval,
+            interval,
+        });
+        self
+    }
+
+    /// Sets the number of timesteps after which projectiles are removed from the world
+    /// (defaults to 120).
+    pub fn projectile_lifetime(mut self, num_steps: usize) -> Self {
+        self.projectile_lifetime = num_steps;
+        self
+    }
+
+    /// The number of timesteps executed by this disturber so far.
+    pub fn num_steps(&self) -> usize {
+        self.num_steps
+    }
+
+    /// Applies the disturbances scheduled for the current timestep, then steps the world.
+    pub fn step(&mut self, world: &mut PhysicsWorld) {
+        let step = self.num_steps;
+
+        self.projectiles.retain(|(handle, removal_step)| {
+            if *removal_step <= step {
+                let _ = world.remove_body(*handle);
+                false
+            } else {
+                true
+            }
+        });
+
+        for i in 0..self.schedule.len() {
+            let scheduled = self.schedule[i];
+            if scheduled.next_step == step {
+                self.apply(world, scheduled.disturbance);
+                self.schedule[i].next_step = if scheduled.interval == 0 {
+                    usize::MAX
+                } else {
+                    step + scheduled.interval
+                };
+            }
+        }
+
+        world.step();
+        self.num_steps += 1;
+    }
+
+    /// Executes `num_steps` disturbed timesteps on the given world, and reports the resulting
+    /// counters and position checksum like [`super::run_steps`].
+    pub fn run_steps(&mut self, world: &mut PhysicsWorld, num_steps: usize) -> TestbenchReport {
+        run_steps_with(world, num_steps, |world| self.step(world))
+    }
+
+    fn apply(&mut self, world: &mut PhysicsWorld, disturbance: Disturbance) {
+        let Some(target) = self.random_target(world) else {
+            return;
+        };
+
+        match disturbance {
+            Disturbance::Nudge {
+                max_velocity_change,
+            } => {
+                let velocity_change =
+                    self.random_unit_vector() * max_velocity_change * self.random();
+                let rb = &mut world.bodies[target];
+                let impulse = velocity_change * rb.mass();
+                rb.apply_impulse(impulse, true);
+            }
+            Disturbance::Projectile {
+                radius,
+                speed,
+                distance,
+            } => {
+                let mut dir = self.random_unit_vector();
+                dir.y = dir.y.abs();
+                let position = world.bodies[target].center_of_mass().coords + dir * distance;
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic()
+                        .translation(position)
+                        .linvel(-dir * speed)
+                        .ccd_enabled(true),
+                );
+                let _ = world.insert_collider_with_parent(ColliderBuilder::ball(radius), body);
+                self.projectiles
+                    .push((body, self.num_steps + self.projectile_lifetime));
+            }
+        }
+    }
+
+    /// Picks a random dynamic rigid-body that isn’t a projectile.
+    fn random_target(&mut self, world: &PhysicsWorld) -> Option<RigidBodyHandle> {
+        let candidates: Vec<_> = world
+            .bodies
+            .iter()
+            .filter(|(handle, rb)| {
+                rb.is_dynamic() && !self.projectiles.iter().any(|(h, _)| h == handle)
+            })
+            .map(|(handle, _)| handle)
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let chosen = (self.random() * candidates.len() as Real) as usize;
+        Some(candidates[chosen.min(candidates.len() - 1)])
+    }
+
+    fn random(&mut self) -> Real {
+        next_random(&mut self.rng_state)
+    }
+
+    fn random_unit_vector(&mut self) -> Vector<Real> {
+        loop {
+            let v: Vector<Real> = Vector::from_fn(|_, _| self.random() * 2.0 - 1.0);
+            let norm = v.norm();
+
+            if norm > 1.0e-3 && norm <= 1.0 {
+                return v / norm;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Disturbance, Disturber};
+    use crate::dynamics::{RigidBodyBuilder, RigidBodyHandle};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::PhysicsWorld;
+
+    fn box_stack(n: usize) -> (PhysicsWorld, Vec<RigidBodyHandle>) {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        let _ = world.insert_collider(ground.translation(Vector::y() * -0.5));
+
+        let boxes = (0..n)
+            .map(|i| {
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic().translation(Vector::y() * (0.5 + i as Real)),
+                );
+                let _ = world.insert_collider_with_parent(cuboid.clone(), body);
+                body
+            })
+            .collect();
+
+        (world, boxes)
+    }
+
+    fn disturber(seed: u64) -> Disturber {
+        Disturber::new(seed)
+            .every(
+                5,
+                Disturbance::Nudge {
+                    max_velocity_change: 0.05,
+                },
+            )
+            .at_step(
+                100,
+                Disturbance::Projectile {
+                    radius: 0.1,
+                    speed: 5.0,
+                    distance: 5.0,
+                },
+            )
+            .projectile_lifetime(50)
+    }
+
+    #[test]
+    fn disturbances_are_reproducible() {
+        let checksum = |seed| {
+            let (mut world, _) = box_stack(10);
+            disturber(seed).run_steps(&mut world, 200).position_checksum
+        };
+
+        assert_eq!(checksum(1), checksum(1));
+        assert_ne!(checksum(1), checksum(2));
+
+        // The projectile was removed at the end of its lifetime.
+        let (mut world, boxes) = box_stack(10);
+        let _ = disturber(1).run_steps(&mut world, 200);
+        assert_eq!(world.bodies.len(), boxes.len());
+    }
+
+    #[test]
+    fn nudged_stack_of_ten_boxes_remains_standing() {
+        let (mut world, boxes) = box_stack(10);
+        let mut disturber = Disturber::new(0).every(
+            5,
+            Disturbance::Nudge {
+                max_velocity_change: 0.05,
+            },
+        );
+        let _ = disturber.run_steps(&mut world, 600);
+
+        for (i, handle) in boxes.iter().enumerate() {
+            let pos = world.bodies[*handle].translation();
+            let expected = Vector::y() * (0.5 + i as Real);
+            assert!(
+                (pos - expected).norm() < 0.2,
+                "box {i} moved from {expected:?} to {pos:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,504 @@
+//! Headless scene builders and stepping helpers for benchmarking and reproducing issues.
+//!
+//! This module is only available with the `testbench` feature enabled. Each scene builder
+//! returns a fully populated [`PhysicsWorld`] that can be stepped with [`run_steps`], which
+//! reports the performance counters of the simulation together with a checksum of the positions
+//! of every rigid-body. Because the scenes are built programmatically (any randomness comes from
+//! an explicit seed), they can be used to compare the effect of parameter changes reproducibly:
+//!
+//! ```ignore
+//! use rapier3d::testbench;
+//!
+//! let mut world = testbench::pyramid(10);
+//! world.integration_parameters.num_solver_iterations = std::num::NonZeroUsize::new(8).unwrap();
+//! let report = testbench::run_steps(&mut world, 200);
+//! println!(
+//!     "{:?} for 200 steps, checksum: {:x}",
+//!     report.elapsed, report.position_checksum
+//! );
+//! ```
+//!
+//! The same scenes are used by the `testbench` benchmarks of the `rapier2d` and `rapier3d`
+//! crates, that can be run with `cargo bench --features testbench`.
+
+pub mod chaos;
+
+use crate::counters::Counters;
+use crate::dynamics::RigidBodyBuilder;
+use crate::geometry::{ActiveCollisionTypes, BroadPhaseProxyShape, ColliderBuilder};
+use crate::math::{Real, Vector};
+use crate::pipeline::PhysicsWorld;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "dim2")]
+use crate::dynamics::RevoluteJointBuilder;
+#[cfg(feature = "dim3")]
+use crate::dynamics::SphericalJointBuilder;
+#[cfg(feature = "dim3")]
+use na::DMatrix;
+#[cfg(feature = "dim2")]
+use na::DVector;
+
+/// The result of [`run_steps`].
+#[derive(Clone)]
+pub struct TestbenchReport {
+    /// The number of timesteps that were executed.
+    pub num_steps: usize,
+    /// The total wall-clock time spent stepping the simulation.
+    pub elapsed: Duration,
+    /// The performance counters of the last timestep.
+    ///
+    /// The timers are only measured if the `profiler` feature is enabled.
+    pub counters: Counters,
+    /// A checksum of the positions of every rigid-body after the last timestep.
+    ///
+    /// Two runs of the same scene with the same parameters lead to the same checksum, as long
+    /// as the simulation is deterministic (see the `enhanced-determinism` feature for
+    /// cross-platform determinism).
+    pub position_checksum: u64,
+}
+
+impl TestbenchReport {
+    /// The average wall-clock time of one timestep.
+    pub fn average_step_time(&self) -> Duration {
+        self.elapsed / self.num_steps.max(1) as u32
+    }
+}
+
+/// Executes `num_steps` timesteps on the given world and reports the resulting counters and
+/// position checksum.
+pub fn run_steps(world: &mut PhysicsWorld, num_steps: usize) -> TestbenchReport {
+    run_steps_with(world, num_steps, PhysicsWorld::step)
+}
+
+fn run_steps_with(
+    world: &mut PhysicsWorld,
+    num_steps: usize,
+    mut step: impl FnMut(&mut PhysicsWorld),
+) -> TestbenchReport {
+    world.physics_pipeline.counters.enable();
+
+    let start = Instant::now();
+    for _ in 0..num_steps {
+        step(world);
+    }
+    let elapsed = start.elapsed();
+
+    TestbenchReport {
+        num_steps,
+        elapsed,
+        counters: world.physics_pipeline.counters.clone(),
+        position_checksum: position_checksum(world),
+    }
+}
+
+/// Computes a checksum of the positions of every rigid-body of the world.
+///
+/// This is the checksum reported by [`run_steps`].
+pub fn position_checksum(world: &PhysicsWorld) -> u64 {
+    // FNV-1a hash of the bit patterns of every coordinate.
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    let mut add = |x: Real| {
+        for byte in (x as f64).to_bits().to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+
+    for (_, body) in world.bodies.iter() {
+        let pos = body.position();
+        pos.translation.vector.iter().copied().for_each(&mut add);
+        #[cfg(feature = "dim2")]
+        [pos.rotation.re, pos.rotation.im]
+            .into_iter()
+            .for_each(&mut add);
+        #[cfg(feature = "dim3")]
+        pos.rotation.coords.iter().copied().for_each(&mut add);
+    }
+
+    hash
+}
+
+/// A pyramid of boxes with `n` levels resting on the ground.
+///
+/// In 3D, each level is a square layer of boxes.
+pub fn pyramid(n: usize) -> PhysicsWorld {
+    let mut world = PhysicsWorld::default();
+    insert_ground(&mut world, n as Real + 10.0);
+
+    let rad = 0.5;
+    let shift = rad * 2.0;
+
+    for level in 0..n {
+        let width = n - level;
+        let y = rad + level as Real * shift;
+        let offset = -(width as Real - 1.0) * shift / 2.0;
+
+        #[cfg(feature = "dim2")]
+        for i in 0..width {
+            let x = offset + i as Real * shift;
+            insert_cuboid(&mut world, Vector::new(x, y), rad);
+        }
+
+        #[cfg(feature = "dim3")]
+        for i in 0..width {
+            for k in 0..width {
+                let x = offset + i as Real * shift;
+                let z = offset + k as Real * shift;
+                insert_cuboid(&mut world, Vector::new(x, y, z), rad);
+            }
+        }
+    }
+
+    world
+}
+
+/// A pile of `n` boxes falling on the ground.
+///
+/// The boxes are arranged in columns, with slight offsets so that the pile collapses
+/// irregularly.
+pub fn box_pile(n: usize) -> PhysicsWorld {
+    let mut world = PhysicsWorld::default();
+    let rad = 0.5;
+    let shift = rad * 2.5;
+
+    #[cfg(feature = "dim2")]
+    let num_columns = ((n as Real).sqrt().ceil() as usize).max(1);
+    #[cfg(feature = "dim3")]
+    let num_columns = ((n as Real).cbrt().ceil() as usize).max(1);
+    let extent = num_columns as Real * shift;
+    insert_ground(&mut world, extent + 10.0);
+
+    for id in 0..n {
+        let i = id % num_columns;
+        let j = id / num_columns;
+        let jitter = (j % 3) as Real * 0.1 * rad;
+        let x = i as Real * shift - extent / 2.0 + jitter;
+
+        #[cfg(feature = "dim2")]
+        let position = Vector::new(x, rad * 2.0 + j as Real * shift);
+        #[cfg(feature = "dim3")]
+        let position = Vector::new(
+            x,
+            rad * 2.0 + (j / num_columns) as Real * shift,
+            (j % num_columns) as Real * shift - extent / 2.0 - jitter,
+        );
+        insert_cuboid(&mut world, position, rad);
+    }
+
+    world
+}
+
+/// A chain of `n` balls linked by joints, hanging from a fixed rigid-body and starting
+/// horizontally so that it swings under gravity.
+///
+/// The links are revolute joints in 2D and spherical joints in 3D.
+pub fn joint_chain(n: usize) -> PhysicsWorld {
+    let mut world = PhysicsWorld::default();
+    let rad = 0.2;
+    let shift = rad * 2.5;
+    let height = n as Real * shift + 1.0;
+
+    let mut parent = world.insert_body(RigidBodyBuilder::fixed().translation(Vector::y() * height));
+    let _ = world.insert_collider_with_parent(ColliderBuilder::ball(rad), parent);
+
+    for i in 1..=n {
+        let child = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * (i as Real * shift) + Vector::y() * height),
+        );
+        let _ = world.insert_collider_with_parent(ColliderBuilder::ball(rad), child);
+
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJointBuilder::new();
+        #[cfg(feature = "dim3")]
+        let joint = SphericalJointBuilder::new();
+        let joint = joint.local_anchor2((Vector::x() * -shift).into());
+        let _ = world.insert_impulse_joint(parent, child, joint);
+        parent = child;
+    }
+
+    world
+}
+
+/// `n` small balls poured into a container made of a half-space floor and cuboid walls.
+///
+/// Every contact pair of this scene involves a ball and another ball, the floor, or a wall.
+pub fn ball_container(n: usize) -> PhysicsWorld {
+    let mut world = PhysicsWorld::default();
+    let rad = 0.1;
+    let shift = rad * 2.1;
+
+    #[cfg(feature = "dim2")]
+    let num_columns = ((n as Real).sqrt().ceil() as usize).max(1);
+    #[cfg(feature = "dim3")]
+    let num_columns = ((n as Real).cbrt().ceil() as usize).max(1);
+    #[cfg(feature = "dim2")]
+    let num_levels = n.div_ceil(num_columns);
+    #[cfg(feature = "dim3")]
+    let num_levels = n.div_ceil(num_columns * num_columns);
+    let half_width = num_columns as Real * shift / 2.0;
+    let half_height = (num_levels as Real * shift + 1.0) / 2.0;
+
+    let _ = world.insert_collider(ColliderBuilder::halfspace(Vector::y_axis()));
+    for side in [-1.0, 1.0] {
+        let offset = side * (half_width + rad + 0.5);
+        #[cfg(feature = "dim2")]
+        let _ = world.insert_collider(
+            ColliderBuilder::cuboid(0.5, half_height).translation(Vector::new(offset, half_height)),
+        );
+        #[cfg(feature = "dim3")]
+        {
+            let wall_length = half_width + rad + 1.0;
+            let _ =
+                world.insert_collider(
+                    ColliderBuilder::cuboid(0.5, half_height, wall_length)
+                        .translation(Vector::new(offset, half_height, 0.0)),
+                );
+            let _ =
+                world.insert_collider(
+                    ColliderBuilder::cuboid(wall_length, half_height, 0.5)
+                        .translation(Vector::new(0.0, half_height, offset)),
+                );
+        }
+    }
+
+    for id in 0..n {
+        let i = id % num_columns;
+        let j = id / num_columns;
+        // Shift every other row so the balls don’t stay perfectly stacked.
+        let jitter = (j % 2) as Real * 0.2 * rad;
+        let x = i as Real * shift - half_width + rad + jitter;
+
+        #[cfg(feature = "dim2")]
+        let position = Vector::new(x, rad * 2.0 + j as Real * shift);
+        #[cfg(feature = "dim3")]
+        let position = Vector::new(
+            x,
+            rad * 2.0 + (j / num_columns) as Real * shift,
+            (j % num_columns) as Real * shift - half_width + rad,
+        );
+        let body = world.insert_body(RigidBodyBuilder::dynamic().translation(position));
+        let _ = world.insert_collider_with_parent(ColliderBuilder::ball(rad), body);
+    }
+
+    world
+}
+
+/// A randomly generated heightfield terrain with a mix of balls and boxes falling on it.
+///
+/// The same `seed` always generates the same scene.
+pub fn heightfield_terrain(seed: u64) -> PhysicsWorld {
+    let mut world = PhysicsWorld::default();
+
+    let mut seed = seed;
+    let mut rand = move || next_random(&mut seed);
+
+    let nsubdivs = 32;
+    let size = 40.0;
+
+    #[cfg(feature = "dim2")]
+    let terrain = ColliderBuilder::heightfield(
+        DVector::from_fn(nsubdivs + 1, |_, _| rand() * 2.0),
+        Vector::new(size, 1.0),
+    );
+    #[cfg(feature = "dim3")]
+    let terrain = ColliderBuilder::heightfield(
+        DMatrix::from_fn(nsubdivs + 1, nsubdivs + 1, |_, _| rand() * 2.0),
+        Vector::new(size, 1.0, size),
+    );
+    let _ = world.insert_collider(terrain);
+
+    let num = 8;
+    let rad = 0.5;
+    let shift = rad * 3.0;
+    let offset = -(num as Real - 1.0) * shift / 2.0;
+
+    for j in 0..4 {
+        for i in 0..num {
+            #[cfg(feature = "dim2")]
+            let positions = [Vector::new(
+                offset + i as Real * shift,
+                4.0 + j as Real * shift,
+            )];
+            #[cfg(feature = "dim3")]
+            let positions = (0..num).map(|k| {
+                Vector::new(
+                    offset + i as Real * shift,
+                    4.0 + j as Real * shift,
+                    offset + k as Real * shift,
+                )
+            });
+
+            for position in positions {
+                let body = world.insert_body(RigidBodyBuilder::dynamic().translation(position));
+                let collider = if rand() < 0.5 {
+                    ColliderBuilder::ball(rad)
+                } else {
+                    #[cfg(feature = "dim2")]
+                    let cuboid = ColliderBuilder::cuboid(rad, rad);
+                    #[cfg(feature = "dim3")]
+                    let cuboid = ColliderBuilder::cuboid(rad, rad, rad);
+                    cuboid
+                };
+                let _ = world.insert_collider_with_parent(collider, body);
+            }
+        }
+    }
+
+    world
+}
+
+/// `num_chunks` fixed rigid-bodies side by side, each made of `colliders_per_chunk` adjacent
+/// cuboids, like the chunks of a voxel terrain.
+///
+/// In 2D, the cuboids of a chunk form a square wall. In 3D, they form a square floor.
+pub fn static_chunks(num_chunks: usize, colliders_per_chunk: usize) -> PhysicsWorld {
+    let mut world = PhysicsWorld::default();
+    let rad = 0.5;
+    let shift = rad * 2.0;
+    let width = ((colliders_per_chunk as Real).sqrt().ceil() as usize).max(1);
+
+    for chunk_id in 0..num_chunks {
+        let origin = Vector::x() * (chunk_id * width) as Real * shift;
+        let chunk = world.insert_body(RigidBodyBuilder::fixed().translation(origin));
+
+        for id in 0..colliders_per_chunk {
+            let (i, j) = (id % width, id / width);
+            #[cfg(feature = "dim2")]
+            let cuboid = ColliderBuilder::cuboid(rad, rad)
+                .translation(Vector::new(i as Real * shift, -(j as Real) * shift));
+            #[cfg(feature = "dim3")]
+            let cuboid = ColliderBuilder::cuboid(rad, rad, rad).translation(Vector::new(
+                i as Real * shift,
+                0.0,
+                j as Real * shift,
+            ));
+            let _ = world.insert_collider_with_parent(cuboid, chunk);
+        }
+    }
+
+    world
+}
+
+/// A square grid of `n` thin rods spinning in place, each at its own angular velocity, with the
+/// given broad-phase proxy shape.
+///
+/// The rods are kinematic and collide with each other, so the contacts between neighbors only
+/// depend on the rotation of the rods, and not on the broad-phase proxies. In 3D, the rods
+/// spin around the vertical axis on a horizontal grid.
+pub fn spinning_rods(n: usize, proxy_shape: BroadPhaseProxyShape) -> PhysicsWorld {
+    let mut world = PhysicsWorld::default();
+    let half_length = 1.0;
+    let half_width = 0.05;
+    // Closer than the rods length, so that neighbors regularly touch.
+    let shift = half_length * 1.8;
+    let width = ((n as Real).sqrt().ceil() as usize).max(1);
+    let mut state = 0;
+
+    for id in 0..n {
+        let (i, j) = (id % width, id / width);
+        let speed = (next_random(&mut state) - 0.5) * 4.0;
+        #[cfg(feature = "dim2")]
+        let (position, angvel, rod) = (
+            Vector::new(i as Real * shift, j as Real * shift),
+            speed,
+            ColliderBuilder::cuboid(half_length, half_width),
+        );
+        #[cfg(feature = "dim3")]
+        let (position, angvel, rod) = (
+            Vector::new(i as Real * shift, 0.0, j as Real * shift),
+            Vector::y() * speed,
+            ColliderBuilder::cuboid(half_length, half_width, half_width),
+        );
+
+        let body = world.insert_body(
+            RigidBodyBuilder::kinematic_velocity_based()
+                .translation(position)
+                .angvel(angvel),
+        );
+        let rod = rod
+            .active_collision_types(ActiveCollisionTypes::all())
+            .broad_phase_proxy_shape(proxy_shape);
+        let _ = world.insert_collider_with_parent(rod, body);
+    }
+
+    world
+}
+
+/// Simple deterministic pseudo-random number generator in `[0, 1)`, updating `state`.
+fn next_random(state: &mut u64) -> Real {
+    *state = state
+        .wrapping_mul(6_364_136_223_846_793_005)
+        .wrapping_add(1_442_695_040_888_963_407);
+    (*state >> 40) as Real / (1u64 << 24) as Real
+}
+
+fn insert_ground(world: &mut PhysicsWorld, half_extent: Real) {
+    #[cfg(feature = "dim2")]
+    let ground = ColliderBuilder::cuboid(half_extent, 1.0);
+    #[cfg(feature = "dim3")]
+    let ground = ColliderBuilder::cuboid(half_extent, 1.0, half_extent);
+    let _ = world.insert_collider(ground.translation(Vector::y() * -1.0));
+}
+
+fn insert_cuboid(world: &mut PhysicsWorld, position: Vector<Real>, rad: Real) {
+    let body = world.insert_body(RigidBodyBuilder::dynamic().translation(position));
+    #[cfg(feature = "dim2")]
+    let cuboid = ColliderBuilder::cuboid(rad, rad);
+    #[cfg(feature = "dim3")]
+    let cuboid = ColliderBuilder::cuboid(rad, rad, rad);
+    let _ = world.insert_collider_with_parent(cuboid, body);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scenes_are_reproducible() {
+        let scenes: [fn() -> PhysicsWorld; 5] = [
+            || pyramid(5),
+            || box_pile(30),
+            || joint_chain(10),
+            || heightfield_terrain(42),
+            || ball_container(100),
+        ];
+
+        for scene in scenes {
+            let report1 = run_steps(&mut scene(), 20);
+            let report2 = run_steps(&mut scene(), 20);
+            assert_eq!(report1.num_steps, 20);
+            assert_eq!(report1.position_checksum, report2.position_checksum);
+            assert_eq!(
+                report1.counters.cd.ncontact_pairs,
+                report2.counters.cd.ncontact_pairs
+            );
+        }
+
+        assert_ne!(
+            run_steps(&mut heightfield_terrain(1), 100).position_checksum,
+            run_steps(&mut heightfield_terrain(2), 100).position_checksum
+        );
+    }
+
+    #[test]
+    fn pyramid_stays_standing() {
+        let levels = 6;
+        let mut world = pyramid(levels);
+        let top = world
+            .bodies
+            .iter()
+            .map(|(_, body)| body.translation().y)
+            .fold(Real::MIN, Real::max);
+        let report = run_steps(&mut world, 100);
+        assert!(report.counters.cd.ncontact_pairs > 0);
+
+        let new_top = world
+            .bodies
+            .iter()
+            .map(|(_, body)| body.translation().y)
+            .fold(Real::MIN, Real::max);
+        assert!((new_top - top).abs() < 0.1, "{top} -> {new_top}");
+    }
+}
@@ -8,6 +8,8 @@ use num::Zero;
 use simba::simd::SimdValue;
 use std::ops::IndexMut;
 
+use crate::dynamics::{RigidBodyHandle, RigidBodySet};
+use crate::geometry::{ColliderSet, NarrowPhase};
 use parry::utils::SdpMatrix3;
 use {
     crate::math::{Real, SimdReal},
@@ -667,3 +669,154 @@ pub fn smallest_abs_diff_between_angles<N: SimdRealCopy>(a: N, b: N) -> N {
     let s_err_is_smallest = s_err.simd_abs().simd_lt(s_err_complement.simd_abs());
     s_err.select(s_err_is_smallest, s_err_complement)
 }
+
+/// All the rigid-bodies transitively touching the given rigid-body.
+///
+/// This performs a breadth-first traversal of the contact graph starting at `body`. The
+/// returned list starts with `body` itself, followed by the other rigid-bodies in the order
+/// they were reached. Contacts are not followed through non-dynamic rigid-bodies (other
+/// than `body` itself): a fixed ground supporting two separate piles of objects is part of
+/// the connected component of both piles, but doesn’t connect them.
+///
+/// If `only_active_contacts` is `true`, only the contact pairs with at least one active
+/// contact point are followed. Otherwise, every contact pair from the narrow-phase (including
+/// colliders with overlapping bounding-volumes but no actual contact) is followed.
+///
+/// See [`connected_component_into`] for a variant that doesn’t allocate.
+pub fn connected_component(
+    body: RigidBodyHandle,
+    bodies: &RigidBodySet,
+    colliders: &ColliderSet,
+    narrow_phase: &NarrowPhase,
+    only_active_contacts: bool,
+) -> Vec<RigidBodyHandle> {
+    let mut out = vec![];
+    connected_component_into(
+        body,
+        bodies,
+        colliders,
+        narrow_phase,
+        only_active_contacts,
+        &mut out,
+    );
+    out
+}
+
+/// All the rigid-bodies transitively touching the given rigid-body, written into `out`.
+///
+/// This is the same as [`connected_component`], except that the result is written into the
+/// caller-provided buffer `out`. It is cleared first, and doubles as the traversal queue so
+/// no other allocation is performed.
+pub fn connected_component_into(
+    body: RigidBodyHandle,
+    bodies: &RigidBodySet,
+    colliders: &ColliderSet,
+    narrow_phase: &NarrowPhase,
+    only_active_contacts: bool,
+    out: &mut Vec<RigidBodyHandle>,
+) {
+    out.clear();
+
+    if !bodies.contains(body) {
+        return;
+    }
+
+    out.push(body);
+    let mut next = 0;
+
+    while let Some(curr) = out.get(next).copied() {
+        next += 1;
+
+        if curr != body && !bodies[curr].is_dynamic() {
+            continue;
+        }
+
+        for other in
+            narrow_phase.bodies_interacting_with(curr, bodies, colliders, only_active_contacts)
+        {
+            if !out.contains(&other) {
+                out.push(other);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{connected_component, connected_component_into};
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::PhysicsWorld;
+
+    #[test]
+    fn connected_component_of_a_box_stack() {
+        let mut world = PhysicsWorld::default();
+        #[cfg(feature = "dim2")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        let ground_body =
+            world.insert_body(RigidBodyBuilder::fixed().translation(Vector::y() * -0.5));
+        world.insert_collider_with_parent(ground, ground_body);
+
+        let stack: Vec<_> = (0..5)
+            .map(|i| {
+                let body = world.insert_body(
+                    RigidBodyBuilder::dynamic().translation(Vector::y() * (0.5 + i as Real)),
+                );
+                world.insert_collider_with_parent(cuboid.clone(), body);
+                body
+            })
+            .collect();
+        // A box resting on the ground on its own.
+        let lonely = world.insert_body(
+            RigidBodyBuilder::dynamic().translation(Vector::x() * 5.0 + Vector::y() * 0.5),
+        );
+        world.insert_collider_with_parent(cuboid, lonely);
+
+        for _ in 0..20 {
+            world.step();
+        }
+
+        let mut neighbors: Vec<_> = world
+            .narrow_phase
+            .bodies_in_contact_with(stack[2], &world.bodies, &world.colliders)
+            .collect();
+        neighbors.sort_by_key(|h| h.into_raw_parts());
+        assert_eq!(neighbors, vec![stack[1], stack[3]]);
+
+        let component = connected_component(
+            stack[0],
+            &world.bodies,
+            &world.colliders,
+            &world.narrow_phase,
+            true,
+        );
+        assert_eq!(component[0], stack[0]);
+        assert_eq!(component.len(), 6);
+        assert!(stack.iter().all(|body| component.contains(body)));
+        // The ground is reached, but not traversed to reach the other box.
+        assert!(component.contains(&ground_body));
+        assert!(!component.contains(&lonely));
+
+        // The buffer variant gives the same result when reused.
+        let mut buffer = vec![lonely];
+        connected_component_into(
+            stack[4],
+            &world.bodies,
+            &world.colliders,
+            &world.narrow_phase,
+            true,
+            &mut buffer,
+        );
+        assert_eq!(buffer.len(), 6);
+        assert!(stack.iter().all(|body| buffer.contains(body)));
+    }
+}
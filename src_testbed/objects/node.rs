@@ -9,9 +9,9 @@ use std::collections::HashMap;
 
 use bevy::render::render_resource::PrimitiveTopology;
 use bevy_pbr::wireframe::Wireframe;
-use rapier::geometry::{ColliderHandle, ColliderSet, Shape, ShapeType};
+use rapier::geometry::{ColliderHandle, ColliderSet, DeformableTriMesh, Shape, ShapeType, Voxels};
 #[cfg(feature = "dim3")]
-use rapier::geometry::{Cone, Cylinder};
+use rapier::geometry::{Cone, Cuboid, Cylinder};
 use rapier::math::{Isometry, Real, Vector};
 
 use crate::graphics::{BevyMaterial, InstancedMaterials, SELECTED_OBJECT_MATERIAL_KEY};
@@ -419,6 +419,51 @@ fn collider_mesh_scale(co_shape: &dyn Shape) -> Vec3 {
     }
 }
 
+/// The vertex and index buffers of the custom shapes provided by rapier.
+fn custom_shape_buffers(co_shape: &dyn Shape) -> Option<(Vec<Point3<Real>>, Vec<[u32; 3]>)> {
+    if let Some(trimesh) = co_shape.as_shape::<DeformableTriMesh>() {
+        #[cfg(feature = "dim2")]
+        let vertices = trimesh
+            .vertices()
+            .iter()
+            .map(|p| point![p.x, p.y, 0.0])
+            .collect();
+        #[cfg(feature = "dim3")]
+        let vertices = trimesh.vertices().to_vec();
+        Some((vertices, trimesh.indices().to_vec()))
+    } else if let Some(voxels) = co_shape.as_shape::<Voxels>() {
+        let mut vertices = vec![];
+        let mut indices = vec![];
+
+        for key in voxels.surface_cells() {
+            let aabb = voxels.cell_aabb(key);
+            #[cfg(feature = "dim2")]
+            let (cell_vertices, cell_indices) = (
+                aabb.vertices().map(|p| point![p.x, p.y, 0.0]).to_vec(),
+                vec![[0, 1, 2], [0, 2, 3]],
+            );
+            #[cfg(feature = "dim3")]
+            let (cell_vertices, cell_indices) = {
+                let (vtx, idx) = Cuboid::new(aabb.half_extents()).to_trimesh();
+                let center = aabb.center().coords;
+                (vtx.into_iter().map(|p| p + center).collect::<Vec<_>>(), idx)
+            };
+
+            let base = vertices.len() as u32;
+            vertices.extend(cell_vertices);
+            indices.extend(
+                cell_indices
+                    .into_iter()
+                    .map(|idx: [u32; 3]| idx.map(|i| i + base)),
+            );
+        }
+
+        Some((vertices, indices))
+    } else {
+        None
+    }
+}
+
 #[cfg(feature = "dim2")]
 fn generate_collider_mesh(co_shape: &dyn Shape) -> Option<Mesh> {
     let mesh = match co_shape.shape_type() {
@@ -462,6 +507,7 @@ fn generate_collider_mesh(co_shape: &dyn Shape) -> Option<Mesh> {
             let poly = co_shape.as_round_convex_polygon().unwrap();
             bevy_mesh_from_polyline(poly.inner_shape.points().to_vec())
         }
+        ShapeType::Custom => bevy_mesh(custom_shape_buffers(co_shape)?),
         _ => return None,
     };
 
@@ -495,6 +541,7 @@ fn generate_collider_mesh(co_shape: &dyn Shape) -> Option<Mesh> {
             let poly = co_shape.as_round_convex_polyhedron().unwrap();
             bevy_mesh(poly.inner_shape.to_trimesh())
         }
+        ShapeType::Custom => bevy_mesh(custom_shape_buffers(co_shape)?),
         _ => return None,
     };
 
@@ -17,8 +17,7 @@ use na::{self, Point2, Point3, Vector3};
 use rapier::control::DynamicRayCastVehicleController;
 use rapier::control::KinematicCharacterController;
 use rapier::dynamics::{
-    ImpulseJointSet, IntegrationParameters, MultibodyJointSet, RigidBodyActivation,
-    RigidBodyHandle, RigidBodySet,
+    ImpulseJointSet, IntegrationParameters, MultibodyJointSet, RigidBodyHandle, RigidBodySet,
 };
 #[cfg(feature = "dim3")]
 use rapier::geometry::Ray;
@@ -1373,15 +1372,13 @@ fn update_testbed(
         {
             if state.flags.contains(TestbedStateFlags::SLEEP) {
                 for (_, body) in harness.physics.bodies.iter_mut() {
-                    body.activation_mut().normalized_linear_threshold =
-                        RigidBodyActivation::default_normalized_linear_threshold();
-                    body.activation_mut().angular_threshold =
-                        RigidBodyActivation::default_angular_threshold();
+                    body.activation_mut().normalized_linear_threshold = None;
+                    body.activation_mut().angular_threshold = None;
                 }
             } else {
                 for (_, body) in harness.physics.bodies.iter_mut() {
                     body.wake_up(true);
-                    body.activation_mut().normalized_linear_threshold = -1.0;
+                    body.activation_mut().normalized_linear_threshold = Some(-1.0);
                 }
             }
         }
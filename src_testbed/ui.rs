@@ -216,6 +216,10 @@ pub fn update_ui(
         ui.add(
             Slider::new(&mut integration_parameters.max_ccd_substeps, 0..=10).text("CCD substeps"),
         );
+        ui.add(
+            Slider::new(&mut integration_parameters.ccd_min_advancement, 0.0..=1.0)
+                .text("CCD min. advancement"),
+        );
         ui.add(
             Slider::new(&mut integration_parameters.min_island_size, 1..=10_000)
                 .text("min island size"),